@@ -10,8 +10,13 @@ compile_error!("Cannot build the `reth` binary with the `optimism` feature flag
 
 #[cfg(not(feature = "optimism"))]
 fn main() {
-    use reth::cli::Cli;
-    use reth_node_ethereum::EthereumNode;
+    use clap::Parser;
+    use reth::{args::UserOperationPoolArgs, cli::Cli};
+    use reth_node_ethereum::{
+        install_bundle_pool, install_user_operation_pool, node::EthereumPayloadBuilder,
+        EthereumNode, RelaySubmission,
+    };
+    use reth_transaction_pool::BundlePool;
 
     reth_cli_util::sigsegv_handler::install();
 
@@ -20,8 +25,41 @@ fn main() {
         std::env::set_var("RUST_BACKTRACE", "1");
     }
 
-    if let Err(err) = Cli::parse_args().run(|builder, _| async {
-        let handle = builder.launch_node(EthereumNode::default()).await?;
+    if let Err(err) = Cli::<UserOperationPoolArgs>::parse().run(|builder, userop_args| async move {
+        let config = builder.config();
+        let bundle_pool = config.builder.bundles.then(BundlePool::default);
+        let mut payload_builder = EthereumPayloadBuilder::default();
+        if let Some(bundle_pool) = bundle_pool.clone() {
+            payload_builder = payload_builder.with_bundle_pool(bundle_pool);
+        }
+        if let (Some(account_key), Some(bls_key)) =
+            (&config.builder.relay_account_key, &config.builder.relay_bls_key)
+        {
+            if !config.builder.relays.is_empty() {
+                payload_builder =
+                    payload_builder.with_relay_submission(RelaySubmission::from_key_files(
+                        &config.builder.relays,
+                        account_key,
+                        bls_key,
+                        config.chain.chain.id(),
+                    )?);
+            }
+        }
+
+        let handle = builder
+            .with_types::<EthereumNode>()
+            .with_components(EthereumNode::components().payload(payload_builder))
+            .extend_rpc_modules(move |ctx| {
+                if let Some(bundle_pool) = bundle_pool {
+                    install_bundle_pool(ctx, bundle_pool)?;
+                }
+                if let Some(config) = userop_args.pool_config() {
+                    install_user_operation_pool(ctx, config)?;
+                }
+                Ok(())
+            })
+            .launch()
+            .await?;
         handle.node_exit_future.await
     }) {
         eprintln!("Error: {err:?}");
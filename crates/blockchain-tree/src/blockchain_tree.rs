@@ -1872,6 +1872,44 @@ mod tests {
         assert_eq!(state_root, block5.state_root);
     }
 
+    #[test]
+    fn sparse_state_root() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+        let genesis = data.genesis;
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(vec![exec2, exec1]).with_sparse_state_root(true);
+
+        // last finalized block would be number 9.
+        setup_genesis(&externals.provider_factory, genesis);
+
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let mut tree = BlockchainTree::new(externals, config, PruneModes::default())
+            .expect("failed to create tree");
+        tree.make_canonical(B256::ZERO).unwrap();
+        tree.finalize_block(10).unwrap();
+
+        // the state roots are validated without producing trie updates
+        for block in [&block1, &block2] {
+            assert_eq!(
+                tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap(),
+                InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+            );
+            let chain_id = tree.state.block_indices.get_block_chain_id(&block.hash()).unwrap();
+            assert!(tree.state.chains.get(&chain_id).unwrap().trie_updates().is_none());
+        }
+
+        // the trie updates are recomputed when the blocks are made canonical
+        assert_eq!(
+            tree.make_canonical(block2.hash()).unwrap(),
+            CanonicalOutcome::Committed { head: block2.header.clone() }
+        );
+        let provider = tree.externals.provider_factory.provider().unwrap();
+        assert_eq!(StateRoot::from_tx(provider.tx_ref()).root().unwrap(), block2.state_root);
+    }
+
     #[test]
     fn test_side_chain_fork() {
         let data = BlockchainTestData::default_from_number(11);
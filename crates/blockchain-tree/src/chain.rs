@@ -23,7 +23,7 @@ use reth_provider::{
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_trie::updates::TrieUpdates;
-use reth_trie_parallel::parallel_root::ParallelStateRoot;
+use reth_trie_parallel::{parallel_root::ParallelStateRoot, sparse_root::SparseStateRoot};
 use std::{
     collections::BTreeMap,
     ops::{Deref, DerefMut},
@@ -228,10 +228,17 @@ impl AppendableChain {
                     provider.block_execution_data_provider.execution_outcome().clone();
                 execution_outcome.extend(initial_execution_outcome.clone());
                 let hashed_state = execution_outcome.hash_state_slow();
-                ParallelStateRoot::new(consistent_view, hashed_state)
-                    .incremental_root_with_updates()
-                    .map(|(root, updates)| (root, Some(updates)))
-                    .map_err(ProviderError::from)?
+                if externals.sparse_state_root {
+                    // the trie updates are recomputed once the block is made canonical
+                    let mut sparse_state_root = SparseStateRoot::new(consistent_view);
+                    sparse_state_root.update(hashed_state).map_err(ProviderError::from)?;
+                    (sparse_state_root.root().map_err(ProviderError::from)?, None)
+                } else {
+                    ParallelStateRoot::new(consistent_view, hashed_state)
+                        .incremental_root_with_updates()
+                        .map(|(root, updates)| (root, Some(updates)))
+                        .map_err(ProviderError::from)?
+                }
             } else {
                 (provider.state_root(initial_execution_outcome.state())?, None)
             };
@@ -28,6 +28,9 @@ pub struct TreeExternals<DB, E> {
     pub(crate) consensus: Arc<dyn Consensus>,
     /// The executor factory to execute blocks with.
     pub(crate) executor_factory: E,
+    /// Whether the state roots of blocks extending the canonical chain are validated with a
+    /// [`SparseStateRoot`](reth_trie_parallel::sparse_root::SparseStateRoot).
+    pub(crate) sparse_state_root: bool,
 }
 
 impl<DB, E> TreeExternals<DB, E> {
@@ -37,7 +40,18 @@ impl<DB, E> TreeExternals<DB, E> {
         consensus: Arc<dyn Consensus>,
         executor_factory: E,
     ) -> Self {
-        Self { provider_factory, consensus, executor_factory }
+        Self { provider_factory, consensus, executor_factory, sparse_state_root: false }
+    }
+
+    /// Validates the state roots of blocks extending the canonical chain with a
+    /// [`SparseStateRoot`](reth_trie_parallel::sparse_root::SparseStateRoot) instead of a
+    /// [`ParallelStateRoot`](reth_trie_parallel::parallel_root::ParallelStateRoot).
+    ///
+    /// The sparse state root doesn't produce trie updates, so they're recomputed when the blocks
+    /// are made canonical.
+    pub const fn with_sparse_state_root(mut self, sparse_state_root: bool) -> Self {
+        self.sparse_state_root = sparse_state_root;
+        self
     }
 }
 
@@ -1,8 +1,16 @@
 //! Command that runs pruning without any limits.
 use crate::common::{AccessRights, Environment, EnvironmentArgs};
 use clap::Parser;
-use reth_prune::PrunerBuilder;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_config::PruneConfig;
+use reth_db::{tables, DatabaseEnv};
+use reth_db_api::table::Table;
+use reth_provider::{DatabaseProviderRO, ProviderFactory, StaticFileProviderFactory};
+use reth_prune::{PruneSegment, PruneSegmentEstimate, PrunerBuilder};
 use reth_static_file::StaticFileProducer;
+use std::sync::Arc;
 use tracing::info;
 
 /// Prunes according to the configuration without any limits
@@ -10,13 +18,24 @@ use tracing::info;
 pub struct PruneCommand {
     #[command(flatten)]
     env: EnvironmentArgs,
+
+    /// Estimate the number of entries and bytes each segment would delete, without copying data
+    /// to static files or modifying the database.
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
 }
 
 impl PruneCommand {
     /// Execute the `prune` command
     pub async fn execute(self) -> eyre::Result<()> {
-        let Environment { config, provider_factory, .. } = self.env.init(AccessRights::RW)?;
+        let access_rights = if self.dry_run { AccessRights::RO } else { AccessRights::RW };
+        let Environment { config, provider_factory, .. } = self.env.init(access_rights)?;
         let prune_config = config.prune.unwrap_or_default();
+        prune_config.segments.validate()?;
+
+        if self.dry_run {
+            return dry_run(provider_factory, prune_config)
+        }
 
         // Copy data from database to static files
         info!(target: "reth::cli", "Copying data from database to static files...");
@@ -40,3 +59,98 @@ impl PruneCommand {
         Ok(())
     }
 }
+
+/// Estimates the data the pruner would delete for the configuration, and prints it as a table.
+///
+/// Data that hasn't been copied to static files yet is not taken into account, as the pruner is
+/// run at the lowest height of the existing static files.
+fn dry_run(
+    provider_factory: ProviderFactory<Arc<DatabaseEnv>>,
+    prune_config: PruneConfig,
+) -> eyre::Result<()> {
+    let Some(prune_tip) = provider_factory.static_file_provider().get_highest_static_files().min()
+    else {
+        info!(target: "reth::cli", "No static files found, nothing to prune");
+        return Ok(())
+    };
+
+    info!(target: "reth::cli", ?prune_tip, ?prune_config, "Estimating pruned data...");
+    let pruner = PrunerBuilder::new(prune_config).build_with_provider_factory(provider_factory);
+    let estimates = pruner.dry_run(prune_tip, table_stats)?;
+
+    println!("{}", estimates_table(&estimates));
+
+    Ok(())
+}
+
+/// Returns the total size in bytes and the number of entries of the database tables of the
+/// segment.
+fn table_stats(
+    provider: &DatabaseProviderRO<Arc<DatabaseEnv>>,
+    segment: PruneSegment,
+) -> eyre::Result<(u64, usize)> {
+    let table_names: &[&str] = match segment {
+        PruneSegment::SenderRecovery => &[tables::TransactionSenders::NAME],
+        PruneSegment::TransactionLookup => &[tables::TransactionHashNumbers::NAME],
+        PruneSegment::Receipts | PruneSegment::ContractLogs => &[tables::Receipts::NAME],
+        PruneSegment::AccountHistory => {
+            &[tables::AccountChangeSets::NAME, tables::AccountsHistory::NAME]
+        }
+        PruneSegment::StorageHistory => {
+            &[tables::StorageChangeSets::NAME, tables::StoragesHistory::NAME]
+        }
+        PruneSegment::Headers => &[
+            tables::Headers::NAME,
+            tables::HeaderTerminalDifficulties::NAME,
+            tables::CanonicalHeaders::NAME,
+        ],
+        PruneSegment::Transactions => &[tables::Transactions::NAME],
+    };
+
+    let tx = provider.tx_ref();
+    let mut size = 0;
+    let mut entries = 0;
+    for table_name in table_names {
+        let table_db = tx.inner.open_db(Some(table_name)).wrap_err("Could not open db.")?;
+        let stats =
+            tx.inner.db_stat(&table_db).wrap_err(format!("Could not find table: {table_name}"))?;
+        let num_pages = stats.leaf_pages() + stats.branch_pages() + stats.overflow_pages();
+        size += stats.page_size() as u64 * num_pages as u64;
+        entries += stats.entries();
+    }
+
+    Ok((size, entries))
+}
+
+fn estimates_table(estimates: &[PruneSegmentEstimate]) -> ComfyTable {
+    let mut table = ComfyTable::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.set_header(["Segment", "Purpose", "Prune Mode", "To Block", "# Entries", "Freed Size"]);
+
+    let mut total_pruned = 0;
+    let mut total_freed_bytes = 0;
+    for estimate in estimates {
+        total_pruned += estimate.pruned;
+        total_freed_bytes += estimate.freed_bytes;
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new(estimate.segment))
+            .add_cell(Cell::new(format!("{:?}", estimate.purpose)))
+            .add_cell(Cell::new(format!("{:?}", estimate.prune_mode)))
+            .add_cell(Cell::new(estimate.to_block))
+            .add_cell(Cell::new(estimate.pruned))
+            .add_cell(Cell::new(human_bytes(estimate.freed_bytes as f64)));
+        table.add_row(row);
+    }
+
+    let mut row = Row::new();
+    row.add_cell(Cell::new("Total"))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(total_pruned))
+        .add_cell(Cell::new(human_bytes(total_freed_bytes as f64)));
+    table.add_row(row);
+
+    table
+}
@@ -136,6 +136,12 @@ impl Command {
             StageEnum::Merkle => {
                 tx.clear::<tables::AccountsTrie>()?;
                 tx.clear::<tables::StoragesTrie>()?;
+                tx.clear::<tables::AccountsTrieChangeSets>()?;
+                tx.clear::<tables::StoragesTrieChangeSets>()?;
+                tx.delete::<tables::ChainState>(
+                    tables::ChainStateKey::FirstArchivedTrieBlock,
+                    None,
+                )?;
                 tx.put::<tables::StageCheckpoints>(
                     StageId::MerkleExecute.to_string(),
                     Default::default(),
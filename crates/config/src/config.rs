@@ -1,5 +1,6 @@
 //! Configuration files.
 
+use alloy_primitives::Address;
 use reth_network_types::{PeersConfig, SessionsConfig};
 use reth_prune_types::PruneModes;
 use reth_stages_types::ExecutionStageThresholds;
@@ -26,6 +27,8 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for the transaction pool.
+    pub txpool: TxPoolConfig,
 }
 
 impl Config {
@@ -358,10 +361,37 @@ impl Default for PruneConfig {
 impl PruneConfig {
     /// Returns whether there is any kind of receipt pruning configuration.
     pub fn has_receipts_pruning(&self) -> bool {
-        self.segments.receipts.is_some() || !self.segments.receipts_log_filter.is_empty()
+        self.segments.receipts.is_some() || self.segments.has_receipts_log_filter()
     }
 }
 
+/// Transaction pool configuration.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct TxPoolConfig {
+    /// The strategy transactions in the pool are ordered by.
+    pub ordering: TransactionOrderingKind,
+    /// Senders whose transactions are ranked above all other transactions.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub priority_senders: Vec<Address>,
+    /// Recipients whose transactions are ranked above all other transactions.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub priority_recipients: Vec<Address>,
+}
+
+/// The strategy transactions in the pool are ordered by.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransactionOrderingKind {
+    /// Order by the tip paid to the block producer.
+    #[default]
+    CoinbaseTip,
+    /// Order by the time transactions were added to the pool.
+    Fifo,
+    /// Order by the effective gas price, including blob fees.
+    EffectiveGasPrice,
+}
+
 /// Helper type to support older versions of Duration deserialization.
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
@@ -382,7 +412,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, EXTENSION};
+    use super::{Config, TransactionOrderingKind, TxPoolConfig, EXTENSION};
     use std::time::Duration;
 
     fn with_tempdir(filename: &str, proc: fn(&std::path::Path)) {
@@ -751,4 +781,20 @@ connect_trusted_nodes_only = true
         let conf: Config = toml::from_str(trusted_nodes_only).unwrap();
         assert!(conf.peers.trusted_nodes_only);
     }
+
+    #[test]
+    fn test_txpool_config() {
+        let txpool = r#"
+[txpool]
+ordering = "effective-gas-price"
+priority_senders = ["0x1f9090aae28b8a3dceadf281b0f12828e676c326"]
+"#;
+        let conf: Config = toml::from_str(txpool).unwrap();
+        assert_eq!(conf.txpool.ordering, TransactionOrderingKind::EffectiveGasPrice);
+        assert_eq!(conf.txpool.priority_senders.len(), 1);
+        assert!(conf.txpool.priority_recipients.is_empty());
+
+        let conf: Config = toml::from_str("").unwrap();
+        assert_eq!(conf.txpool, TxPoolConfig::default());
+    }
 }
@@ -86,7 +86,7 @@ impl<DB: Database> DatabaseService<DB> {
                 let trie_updates = block.trie_updates().clone();
                 let hashed_state = block.hashed_state();
                 HashedStateChanges(hashed_state.clone()).write_to_db(&provider_rw)?;
-                trie_updates.write_to_database(provider_rw.tx_ref())?;
+                provider_rw.write_trie_updates(trie_updates, block.block().number)?;
             }
 
             // update history indices
@@ -1,11 +1,14 @@
 use super::ExecutedBlock;
 use reth_errors::ProviderResult;
-use reth_primitives::{Account, Address, BlockNumber, Bytecode, StorageKey, StorageValue, B256};
+use reth_primitives::{
+    Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, StorageValue, B256,
+};
 use reth_provider::{
     AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRootProvider,
 };
-use reth_trie::{updates::TrieUpdates, AccountProof};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
 use revm::db::BundleState;
+use std::collections::HashMap;
 
 /// A state provider that stores references to in-memory blocks along with their state as well as
 /// the historical state provider for fallback lookups.
@@ -103,6 +106,19 @@ where
     ) -> ProviderResult<AccountProof> {
         todo!()
     }
+
+    fn witness(
+        &self,
+        overlay: &BundleState,
+        target: &HashedPostState,
+    ) -> ProviderResult<HashMap<B256, Bytes>> {
+        let mut state = BundleState::default();
+        for block in &self.in_memory {
+            state.extend(block.execution_output.state().clone());
+        }
+        state.extend(overlay.clone());
+        self.historical.witness(&state, target)
+    }
 }
 
 impl<H> StateProvider for MemoryOverlayStateProvider<H>
@@ -0,0 +1,43 @@
+//! Support for accepting bundles via `eth_sendBundle` on an Ethereum node.
+
+use reth_node_builder::{rpc::RpcContext, FullNodeComponents};
+use reth_rpc_eth_api::EthBundleApiServer;
+use reth_tracing::tracing::info;
+use reth_transaction_pool::BundlePool;
+
+/// The methods that submit bundles to, or cancel bundles in, the [`BundlePool`].
+const BUNDLE_METHODS: [&str; 2] = ["eth_sendBundle", "eth_cancelBundle"];
+
+/// Serves `eth_sendBundle` and `eth_cancelBundle` on the configured transports, adding the
+/// submitted bundles to the given [`BundlePool`].
+///
+/// The same pool must be passed to [`EthereumPayloadBuilder::with_bundle_pool`], so that the
+/// payload builder includes the bundles.
+///
+/// [`EthereumPayloadBuilder::with_bundle_pool`]: crate::node::EthereumPayloadBuilder::with_bundle_pool
+///
+/// This is intended to be called from an
+/// [`extend_rpc_modules`](reth_node_builder::NodeBuilderWithComponents::extend_rpc_modules) hook.
+pub fn install_bundle_pool<Node>(
+    ctx: &mut RpcContext<'_, Node>,
+    bundle_pool: BundlePool,
+) -> eyre::Result<()>
+where
+    Node: FullNodeComponents,
+{
+    ctx.registry.set_bundle_pool(bundle_pool);
+
+    // the other methods of the bundle api are served by their own modules
+    let mut module = ctx.registry.bundle_api().into_rpc();
+    let other_methods =
+        module.method_names().filter(|name| !BUNDLE_METHODS.contains(name)).collect::<Vec<_>>();
+    for method in other_methods {
+        module.remove_method(method);
+    }
+
+    // replaces the methods of the bundle api without a bundle pool, if configured
+    ctx.modules.replace_configured(module)?;
+    info!(target: "reth::cli", "Bundle pool enabled");
+
+    Ok(())
+}
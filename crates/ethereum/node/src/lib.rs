@@ -15,3 +15,12 @@ pub use evm::{EthEvmConfig, EthExecutorProvider};
 
 pub mod node;
 pub use node::EthereumNode;
+
+pub mod bundle;
+pub use bundle::install_bundle_pool;
+
+pub mod relay;
+pub use relay::RelaySubmission;
+
+pub mod user_operation;
+pub use user_operation::{install_user_operation_pool, EthUserOperationPool};
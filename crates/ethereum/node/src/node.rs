@@ -1,8 +1,12 @@
 //! Ethereum Node types config.
 
-use crate::{EthEngineTypes, EthEvmConfig};
+use crate::{EthEngineTypes, EthEvmConfig, RelaySubmission};
+use futures_util::StreamExt;
 use reth_auto_seal_consensus::AutoSealConsensus;
-use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
+use reth_basic_payload_builder::{
+    BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig, MultiStrategyPayloadJobGenerator,
+    PayloadStrategy,
+};
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_ethereum_engine_primitives::{
     EthBuiltPayload, EthPayloadAttributes, EthPayloadBuilderAttributes,
@@ -17,11 +21,14 @@ use reth_node_builder::{
     node::{FullNodeTypes, NodeTypes},
     BuilderContext, Node, PayloadBuilderConfig, PayloadTypes,
 };
-use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
+use reth_payload_builder::{
+    PayloadBuilderHandle, PayloadBuilderService, PayloadJob, PayloadJobGenerator,
+};
+use reth_payload_relay::{RelayJobGenerator, RelayPayloadBuilder, RelayService};
 use reth_provider::CanonStateSubscriptions;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, EthTransactionPool, TransactionPool,
+    blobstore::DiskFileBlobStore, BundlePool, EthTransactionPool, TransactionPool,
     TransactionValidationTaskExecutor,
 };
 use std::sync::Arc;
@@ -140,26 +147,13 @@ where
         let transaction_pool =
             reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
         info!(target: "reth::cli", "Transaction pool initialized");
-        let transactions_path = data_dir.txpool_transactions();
 
         // spawn txpool maintenance task
         {
             let pool = transaction_pool.clone();
             let chain_events = ctx.provider().canonical_state_stream();
             let client = ctx.provider().clone();
-            let transactions_backup_config =
-                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
-
-            ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
-                "local transactions backup task",
-                |shutdown| {
-                    reth_transaction_pool::maintain::backup_local_transactions_task(
-                        shutdown,
-                        pool.clone(),
-                        transactions_backup_config,
-                    )
-                },
-            );
+            ctx.spawn_pool_persistence_task(pool.clone());
 
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
@@ -180,9 +174,68 @@ where
 }
 
 /// A basic ethereum payload service.
+///
+/// If configured with a [`BundlePool`], a builder that places the bundles of the pool at the top
+/// of the block, see [`EthereumBundlePayloadBuilder`], competes with the default builder in a
+/// [`MultiStrategyPayloadJobGenerator`].
+///
+/// If configured with a [`RelaySubmission`], payloads are built with a [`RelayPayloadBuilder`] and
+/// submitted to the relays by a [`RelayService`]. The relay builder doesn't include bundles, so
+/// configuring both is rejected when the service is spawned.
+///
+/// [`EthereumBundlePayloadBuilder`]: reth_ethereum_payload_builder::EthereumBundlePayloadBuilder
 #[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct EthereumPayloadBuilder;
+pub struct EthereumPayloadBuilder {
+    /// The pool the bundles are taken from, if any.
+    bundle_pool: Option<BundlePool>,
+    /// The relays the payloads are submitted to, if any.
+    relay: Option<RelaySubmission>,
+}
+
+impl EthereumPayloadBuilder {
+    /// Includes the bundles of the given pool at the top of the built blocks.
+    ///
+    /// The same pool should be passed to [`install_bundle_pool`](crate::install_bundle_pool), so
+    /// that bundles can be submitted via `eth_sendBundle`.
+    pub fn with_bundle_pool(mut self, bundle_pool: BundlePool) -> Self {
+        self.bundle_pool = Some(bundle_pool);
+        self
+    }
+
+    /// Builds payloads for the proposers registered with the given relays and submits them.
+    pub fn with_relay_submission(mut self, relay: RelaySubmission) -> Self {
+        self.relay = Some(relay);
+        self
+    }
+
+    /// Spawns the payload builder service with the given job generator.
+    fn spawn_service<Node, Gen>(
+        ctx: &BuilderContext<Node>,
+        payload_generator: Gen,
+    ) -> PayloadBuilderHandle<Node::Engine>
+    where
+        Node: FullNodeTypes,
+        Gen: PayloadJobGenerator + Unpin + 'static,
+        Gen::Job: PayloadJob<
+                PayloadAttributes = EthPayloadBuilderAttributes,
+                BuiltPayload = EthBuiltPayload,
+            > + Unpin
+            + 'static,
+        <Node as NodeTypes>::Engine: PayloadTypes<
+            BuiltPayload = EthBuiltPayload,
+            PayloadAttributes = EthPayloadAttributes,
+            PayloadBuilderAttributes = EthPayloadBuilderAttributes,
+        >,
+    {
+        let (payload_service, payload_builder) =
+            PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
+
+        ctx.task_executor().spawn_critical("payload builder service", Box::pin(payload_service));
+
+        payload_builder
+    }
+}
 
 impl<Node, Pool> PayloadServiceBuilder<Node, Pool> for EthereumPayloadBuilder
 where
@@ -208,20 +261,80 @@ where
             .max_payload_tasks(conf.max_payload_tasks())
             .extradata(conf.extradata_bytes());
 
-        let payload_generator = BasicPayloadJobGenerator::with_builder(
+        if self.relay.is_some() && self.bundle_pool.is_some() {
+            eyre::bail!("relay submission can't be combined with a bundle pool")
+        }
+
+        if let Some(relay) = self.relay {
+            let RelaySubmission { relays, secret_key, signer, config } = relay;
+            let relay_count = relays.len();
+            let (relay_service, submitter) = RelayService::new(relays, signer, config);
+            let relay_builder = RelayPayloadBuilder::new(
+                EthEvmConfig::default(),
+                secret_key,
+                config,
+                relay_service.duties(),
+                submitter.clone(),
+            );
+            let payload_generator = RelayJobGenerator::new(
+                BasicPayloadJobGenerator::with_builder(
+                    ctx.provider().clone(),
+                    pool,
+                    ctx.task_executor().clone(),
+                    payload_job_config,
+                    ctx.chain_spec(),
+                    relay_builder,
+                ),
+                submitter,
+            );
+            let handle = Self::spawn_service(ctx, payload_generator);
+
+            // the payload attributes refresh the duties of slots without a known proposer
+            let payload_events = handle
+                .subscribe()
+                .await?
+                .into_stream()
+                .filter_map(|event| futures_util::future::ready(event.ok()));
+            ctx.task_executor()
+                .spawn(Box::pin(relay_service.run::<Node::Engine, _>(Box::pin(payload_events))));
+            info!(target: "reth::cli", relays = relay_count, "Relay submission enabled");
+
+            return Ok(handle)
+        }
+
+        let Some(bundle_pool) = self.bundle_pool else {
+            let payload_generator = BasicPayloadJobGenerator::with_builder(
+                ctx.provider().clone(),
+                pool,
+                ctx.task_executor().clone(),
+                payload_job_config,
+                ctx.chain_spec(),
+                payload_builder,
+            );
+            return Ok(Self::spawn_service(ctx, payload_generator))
+        };
+
+        // placing bundles at the top of the block can make it less valuable, so the bundle-aware
+        // builder competes with the default one
+        let strategies = vec![
+            PayloadStrategy::new("default", payload_builder),
+            PayloadStrategy::new(
+                "bundles",
+                reth_ethereum_payload_builder::EthereumBundlePayloadBuilder::new(
+                    EthEvmConfig::default(),
+                    bundle_pool,
+                ),
+            ),
+        ];
+        let payload_generator = MultiStrategyPayloadJobGenerator::new(
             ctx.provider().clone(),
             pool,
             ctx.task_executor().clone(),
             payload_job_config,
             ctx.chain_spec(),
-            payload_builder,
+            strategies,
         );
-        let (payload_service, payload_builder) =
-            PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
-
-        ctx.task_executor().spawn_critical("payload builder service", Box::pin(payload_service));
-
-        Ok(payload_builder)
+        Ok(Self::spawn_service(ctx, payload_generator))
     }
 }
 
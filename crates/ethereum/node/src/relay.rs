@@ -0,0 +1,77 @@
+//! Support for submitting the payloads built by an Ethereum node to MEV-Boost relays.
+
+use eyre::{eyre, Context};
+use reth_payload_relay::{BuilderSigner, HttpRelay, Relay, RelayBuilderConfig};
+use reth_primitives::{hex, B256};
+use std::{fmt, path::Path, sync::Arc};
+
+/// The relays the payload builder of an Ethereum node submits to, see
+/// [`EthereumPayloadBuilder::with_relay_submission`].
+///
+/// [`EthereumPayloadBuilder::with_relay_submission`]: crate::node::EthereumPayloadBuilder::with_relay_submission
+#[derive(Clone)]
+pub struct RelaySubmission {
+    /// The relays the payloads are submitted to.
+    pub(crate) relays: Vec<Arc<dyn Relay>>,
+    /// The secret key of the builder account that pays the proposers.
+    pub(crate) secret_key: B256,
+    /// Signs the submissions.
+    pub(crate) signer: BuilderSigner,
+    /// The beacon chain parameters of the network.
+    pub(crate) config: RelayBuilderConfig,
+}
+
+impl RelaySubmission {
+    /// Creates a new submission to the given relays.
+    ///
+    /// The proposers are paid from the account of the given secret key, which must be the fee
+    /// recipient of the payload attributes.
+    pub fn new(
+        relays: Vec<Arc<dyn Relay>>,
+        secret_key: B256,
+        signer: BuilderSigner,
+        config: RelayBuilderConfig,
+    ) -> Self {
+        Self { relays, secret_key, signer, config }
+    }
+
+    /// Creates a submission to the relays with the given URLs, loading the hex encoded secret key
+    /// of the builder account and the BLS secret key from the given files.
+    ///
+    /// Returns an error if the chain is not a network with relays.
+    pub fn from_key_files(
+        relay_urls: &[String],
+        account_key: &Path,
+        bls_key: &Path,
+        chain_id: u64,
+    ) -> eyre::Result<Self> {
+        let config = RelayBuilderConfig::from_chain_id(chain_id)
+            .ok_or_else(|| eyre!("no relay configuration for chain {chain_id}"))?;
+        let secret_key = B256::try_from(read_hex_key(account_key)?.as_slice())
+            .wrap_err("builder account key must be 32 bytes")?;
+        let signer = BuilderSigner::new(&read_hex_key(bls_key)?, config.genesis_fork_version)?;
+        let relays = relay_urls
+            .iter()
+            .map(|url| Arc::new(HttpRelay::new(url.as_str())) as Arc<dyn Relay>)
+            .collect();
+        Ok(Self::new(relays, secret_key, signer, config))
+    }
+}
+
+impl fmt::Debug for RelaySubmission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RelaySubmission")
+            .field("relays", &self.relays)
+            .field("signer", &self.signer)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads a hex encoded key from the given file.
+fn read_hex_key(path: &Path) -> eyre::Result<Vec<u8>> {
+    let contents = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("failed to read key file {}", path.display()))?;
+    hex::decode(contents.trim())
+        .wrap_err_with(|| format!("key file {} is not hex encoded", path.display()))
+}
@@ -0,0 +1,51 @@
+//! Support for running the ERC-4337 user operation pool alongside an Ethereum node.
+
+use reth_node_builder::{rpc::RpcContext, FullNodeComponents};
+use reth_provider::{CanonStateSubscriptions, ChainSpecProvider};
+use reth_tracing::tracing::info;
+use reth_user_operation_pool::{
+    maintain::maintain_user_operation_pool_future, EthUserOperationValidator,
+    UserOperationApiServer, UserOperationPool, UserOperationPoolConfig, UserOperationRpc,
+    UserOperationValidationTaskExecutor,
+};
+
+/// Type alias for the user operation pool that validates operations with the
+/// [`EthUserOperationValidator`] on dedicated validation tasks.
+pub type EthUserOperationPool<Client, EvmConfig> = UserOperationPool<
+    UserOperationValidationTaskExecutor<EthUserOperationValidator<Client, EvmConfig>>,
+>;
+
+/// Creates the user operation pool for the given node, spawns its maintenance task and merges
+/// the ERC-4337 `eth` rpc methods into the configured transports.
+///
+/// This is intended to be called from an
+/// [`extend_rpc_modules`](reth_node_builder::NodeBuilderWithComponents::extend_rpc_modules) hook.
+pub fn install_user_operation_pool<Node>(
+    ctx: &mut RpcContext<'_, Node>,
+    config: UserOperationPoolConfig,
+) -> eyre::Result<EthUserOperationPool<Node::Provider, Node::Evm>>
+where
+    Node: FullNodeComponents,
+{
+    let provider = ctx.provider().clone();
+    let task_executor = ctx.node().task_executor().clone();
+
+    let validator = EthUserOperationValidator::new(
+        provider.clone(),
+        provider.chain_spec(),
+        ctx.node().evm_config().clone(),
+        config.clone(),
+    );
+    let validator = UserOperationValidationTaskExecutor::new(validator, task_executor.clone(), 0);
+    let pool = UserOperationPool::new(validator, config);
+
+    task_executor.spawn_critical(
+        "user operation pool maintenance task",
+        maintain_user_operation_pool_future(pool.clone(), provider.canonical_state_stream()),
+    );
+
+    ctx.modules.merge_configured(UserOperationRpc::new(pool.clone()).into_rpc())?;
+    info!(target: "reth::cli", entry_points = ?pool.config().entry_points, "User operation pool enabled");
+
+    Ok(pool)
+}
@@ -3,5 +3,6 @@ mod dev;
 mod eth;
 mod p2p;
 mod utils;
+mod witness;
 
 const fn main() {}
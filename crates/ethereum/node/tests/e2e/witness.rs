@@ -0,0 +1,111 @@
+use crate::utils::eth_payload_attributes;
+use alloy_primitives::{keccak256, Address, Bytes, B256};
+use alloy_rlp::{Decodable, Encodable};
+use reth::{
+    primitives::BlockNumberOrTag,
+    providers::{HeaderProvider, StateProofProvider, StateProviderFactory},
+    revm::db::BundleState,
+    rpc::api::DebugApiServer,
+};
+use reth_chainspec::{ChainSpecBuilder, MAINNET};
+use reth_e2e_test_utils::{setup, transaction::TransactionTestContext, wallet::Wallet};
+use reth_node_ethereum::EthereumNode;
+use reth_trie::{nodes::TrieNode, Nibbles, TrieAccount};
+use reth_trie_parallel::sparse::SparseTrie;
+use std::{collections::HashMap, sync::Arc};
+
+#[tokio::test]
+async fn execution_witness_recomputes_state_root() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let (mut nodes, _tasks, _wallet) = setup::<EthereumNode>(
+        1,
+        Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(serde_json::from_str(include_str!("../assets/genesis.json")).unwrap())
+                .cancun_activated()
+                .build(),
+        ),
+        false,
+    )
+    .await?;
+
+    let mut node = nodes.pop().unwrap();
+    let wallet = Wallet::default();
+    let raw_tx = TransactionTestContext::transfer_tx_bytes(1, wallet.inner).await;
+    node.rpc.inject_tx(raw_tx).await?;
+    let (payload, _) = node.advance_block(vec![], eth_payload_attributes).await?;
+    let block = payload.block();
+
+    let witness = DebugApiServer::debug_execution_witness(
+        &node.rpc.inner.debug_api(),
+        BlockNumberOrTag::Number(block.number),
+    )
+    .await?;
+
+    // reveal the state trie of the parent from the witness alone
+    let parent_root = node.inner.provider.header(&block.parent_hash)?.unwrap().state_root;
+    let mut trie = SparseTrie::blind(parent_root);
+    reveal(&mut trie, &witness.state, Nibbles::default(), parent_root)?;
+
+    // apply the post-state of all accessed accounts, the block doesn't change any storage
+    let state = node.inner.provider.latest()?;
+    let addresses = witness.keys.values().filter(|key| key.len() == Address::len_bytes());
+    for address in addresses.map(|key| Address::from_slice(key)) {
+        let proof = state.proof(&BundleState::default(), address, &[])?;
+        let path = Nibbles::unpack(keccak256(address));
+        match proof.info {
+            Some(account) => {
+                let mut value = Vec::new();
+                TrieAccount::from((account, proof.storage_root)).encode(&mut value);
+                trie.update_leaf(path, value)?;
+            }
+            None => trie.remove_leaf(&path)?,
+        }
+    }
+
+    assert_eq!(trie.root(), block.state_root);
+
+    Ok(())
+}
+
+/// Reveals the node with the given hash and its descendants that are part of the witness.
+fn reveal(
+    trie: &mut SparseTrie,
+    witness: &HashMap<B256, Bytes>,
+    path: Nibbles,
+    hash: B256,
+) -> eyre::Result<()> {
+    // subtries that are not needed for the block stay blinded
+    let Some(node) = witness.get(&hash) else { return Ok(()) };
+    trie.reveal_node(path.clone(), node)?;
+
+    let mut children = Vec::new();
+    match TrieNode::decode(&mut &node[..])? {
+        TrieNode::Branch(branch) => {
+            let mut stack = branch.stack.into_iter();
+            for nibble in 0..16u8 {
+                if branch.state_mask.is_bit_set(nibble) {
+                    children.push((join(&path, &[nibble]), stack.next().unwrap()));
+                }
+            }
+        }
+        TrieNode::Extension(extension) => {
+            children.push((join(&path, &extension.key), extension.child));
+        }
+        TrieNode::Leaf(_) => {}
+    }
+
+    // children shorter than a hash are embedded in their parent and already revealed
+    for (path, child) in children {
+        if child.len() == B256::len_bytes() + 1 {
+            reveal(trie, witness, path, B256::from_slice(&child[1..]))?;
+        }
+    }
+    Ok(())
+}
+
+fn join(path: &Nibbles, key: &[u8]) -> Nibbles {
+    Nibbles::from_nibbles_unchecked([&path[..], key].concat())
+}
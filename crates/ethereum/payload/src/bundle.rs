@@ -0,0 +1,533 @@
+//! Bundle-aware Ethereum payload builder.
+
+use crate::{build_payload, EthereumPayloadBuilder};
+use reth_basic_payload_builder::{BuildArguments, BuildOutcome, PayloadBuilder, PayloadConfig};
+use reth_errors::ProviderError;
+use reth_evm::ConfigureEvm;
+use reth_evm_ethereum::EthEvmConfig;
+use reth_payload_builder::{
+    error::PayloadBuilderError, EthBuiltPayload, EthPayloadBuilderAttributes,
+};
+use reth_primitives::{Receipt, TransactionSignedEcRecovered, U256};
+use reth_provider::StateProviderFactory;
+use reth_transaction_pool::{BundlePool, MevBundle, TransactionPool};
+use revm::{
+    db::states::{CacheState, TransitionState},
+    primitives::{BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, ResultAndState},
+    Database, DatabaseCommit, State,
+};
+use std::sync::Arc;
+use tracing::trace;
+
+/// Ethereum payload builder that places bundles from a [`BundlePool`] at the top of the block.
+///
+/// The bundles that target the block are simulated on their own and merged in the order of their
+/// effective gas price, skipping bundles that conflict with bundles merged before them, see
+/// [`merge_bundles`]. The rest of the block is filled with the best transactions from the pool.
+///
+/// Since the job of the
+/// [`BasicPayloadJobGenerator`](reth_basic_payload_builder::BasicPayloadJobGenerator) rebuilds the
+/// payload on every interval, bundles that arrive while the payload is being built are included in
+/// later attempts, if they make the payload more valuable.
+#[derive(Debug, Clone)]
+pub struct EthereumBundlePayloadBuilder<EvmConfig = EthEvmConfig> {
+    /// The type responsible for creating the evm.
+    evm_config: EvmConfig,
+    /// The bundles to include.
+    bundle_pool: BundlePool,
+}
+
+impl<EvmConfig> EthereumBundlePayloadBuilder<EvmConfig> {
+    /// `EthereumBundlePayloadBuilder` constructor.
+    pub const fn new(evm_config: EvmConfig, bundle_pool: BundlePool) -> Self {
+        Self { evm_config, bundle_pool }
+    }
+
+    /// Returns the pool the bundles are taken from.
+    pub const fn bundle_pool(&self) -> &BundlePool {
+        &self.bundle_pool
+    }
+}
+
+impl<EvmConfig, Pool, Client> PayloadBuilder<Pool, Client>
+    for EthereumBundlePayloadBuilder<EvmConfig>
+where
+    EvmConfig: ConfigureEvm,
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+{
+    type Attributes = EthPayloadBuilderAttributes;
+    type BuiltPayload = EthBuiltPayload;
+
+    fn try_build(
+        &self,
+        args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
+    ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
+        let parent_number = args.config.parent_block.number;
+        let removed = self.bundle_pool.remove_stale(parent_number);
+        if removed > 0 {
+            trace!(target: "payload_builder", removed, "removed stale bundles");
+        }
+
+        let bundles =
+            self.bundle_pool.bundles_for_block(parent_number + 1, args.config.attributes.timestamp);
+        build_payload(self.evm_config.clone(), args, bundles, None)
+    }
+
+    fn build_empty_payload(
+        &self,
+        client: &Client,
+        config: PayloadConfig<Self::Attributes>,
+    ) -> Result<EthBuiltPayload, PayloadBuilderError> {
+        <EthereumPayloadBuilder<EvmConfig> as PayloadBuilder<Pool, Client>>::build_empty_payload(
+            &EthereumPayloadBuilder::new(self.evm_config.clone()),
+            client,
+            config,
+        )
+    }
+}
+
+/// The bundles merged by [`merge_bundles`].
+#[derive(Debug, Default)]
+pub struct MergedBundles {
+    /// The number of merged bundles.
+    pub bundles: usize,
+    /// The transactions of all merged bundles, in order.
+    pub transactions: Vec<TransactionSignedEcRecovered>,
+    /// The receipts of the transactions.
+    pub receipts: Vec<Receipt>,
+    /// The gas used by all transactions.
+    pub gas_used: u64,
+    /// The increase of the coinbase balance, including direct payments to the coinbase.
+    pub fees: U256,
+}
+
+/// Executes the bundles at the top of the block and commits the non-conflicting ones to the
+/// state.
+///
+/// Every bundle is first simulated on its own, bundles that fail are dropped. The remaining
+/// bundles are merged in the order of their effective gas price, the coinbase profit per gas. A
+/// bundle is rolled back if it fails on top of the bundles merged before it or pays the coinbase
+/// less than in its own simulation, since it then conflicts with an earlier bundle.
+///
+/// A bundle fails if any of its transactions is invalid, or reverts without being listed in
+/// [`MevBundle::reverting_tx_hashes`], or if it doesn't fit into the block gas limit.
+pub fn merge_bundles<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: &mut State<DB>,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    bundles: Vec<Arc<MevBundle>>,
+    block_gas_limit: u64,
+) -> Result<MergedBundles, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database<Error = ProviderError>,
+{
+    let mut simulated = Vec::with_capacity(bundles.len());
+    for bundle in bundles {
+        let checkpoint = StateCheckpoint::new(db);
+        let execution = execute_bundle(
+            evm_config,
+            db,
+            initialized_cfg,
+            initialized_block_env,
+            &bundle,
+            0,
+            block_gas_limit,
+        )?;
+        checkpoint.restore(db);
+
+        match execution {
+            Some(execution) if execution.gas_used > 0 => {
+                simulated.push((bundle, execution.profit, execution.effective_gas_price()))
+            }
+            _ => trace!(target: "payload_builder", bundle=?bundle.hash(), "skipping failed bundle"),
+        }
+    }
+
+    // highest effective gas price first
+    simulated.sort_unstable_by(|(_, _, a), (_, _, b)| b.cmp(a));
+
+    let mut merged = MergedBundles::default();
+    for (bundle, simulated_profit, _) in simulated {
+        let checkpoint = StateCheckpoint::new(db);
+        let execution = execute_bundle(
+            evm_config,
+            db,
+            initialized_cfg,
+            initialized_block_env,
+            &bundle,
+            merged.gas_used,
+            block_gas_limit,
+        )?;
+
+        match execution {
+            Some(execution) if execution.profit >= simulated_profit => {
+                merged.bundles += 1;
+                merged.transactions.extend(execution.transactions);
+                merged.receipts.extend(execution.receipts);
+                merged.gas_used += execution.gas_used;
+                merged.fees += execution.profit;
+            }
+            _ => {
+                trace!(target: "payload_builder", bundle=?bundle.hash(), "skipping conflicting bundle");
+                checkpoint.restore(db);
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+/// The outcome of executing a single bundle.
+struct BundleExecution {
+    transactions: Vec<TransactionSignedEcRecovered>,
+    receipts: Vec<Receipt>,
+    gas_used: u64,
+    profit: U256,
+}
+
+impl BundleExecution {
+    fn effective_gas_price(&self) -> U256 {
+        self.profit / U256::from(self.gas_used)
+    }
+}
+
+/// Executes and commits all transactions of the bundle.
+///
+/// Returns `None` if the bundle failed, in which case the state contains the changes of the
+/// transactions executed before the failure and must be rolled back.
+fn execute_bundle<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: &mut State<DB>,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    bundle: &MevBundle,
+    cumulative_gas_used: u64,
+    block_gas_limit: u64,
+) -> Result<Option<BundleExecution>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database<Error = ProviderError>,
+{
+    let coinbase = initialized_block_env.coinbase;
+    let balance_before = db.basic(coinbase)?.map(|acc| acc.balance).unwrap_or_default();
+
+    let mut transactions = Vec::with_capacity(bundle.transactions.len());
+    let mut receipts = Vec::with_capacity(bundle.transactions.len());
+    let mut gas_used = 0;
+    for tx in &bundle.transactions {
+        if cumulative_gas_used + gas_used + tx.gas_limit() > block_gas_limit {
+            return Ok(None)
+        }
+
+        let env = EnvWithHandlerCfg::new_with_cfg_env(
+            initialized_cfg.clone(),
+            initialized_block_env.clone(),
+            evm_config.tx_env(tx),
+        );
+        let mut evm = evm_config.evm_with_env(&mut *db, env);
+        let ResultAndState { result, state } = match evm.transact() {
+            Ok(res) => res,
+            Err(EVMError::Transaction(err)) => {
+                trace!(target: "payload_builder", %err, tx=?tx.hash(), "invalid bundle transaction");
+                return Ok(None)
+            }
+            Err(err) => return Err(PayloadBuilderError::EvmExecutionError(err)),
+        };
+        drop(evm);
+
+        if !result.is_success() && !bundle.can_revert(&tx.hash()) {
+            trace!(target: "payload_builder", tx=?tx.hash(), "reverted bundle transaction");
+            return Ok(None)
+        }
+        db.commit(state);
+
+        gas_used += result.gas_used();
+
+        #[allow(clippy::needless_update)] // side-effect of optimism fields
+        receipts.push(Receipt {
+            tx_type: tx.tx_type(),
+            success: result.is_success(),
+            cumulative_gas_used: cumulative_gas_used + gas_used,
+            logs: result.into_logs().into_iter().map(Into::into).collect(),
+            ..Default::default()
+        });
+        transactions.push(tx.clone());
+    }
+
+    let balance_after = db.basic(coinbase)?.map(|acc| acc.balance).unwrap_or_default();
+
+    Ok(Some(BundleExecution {
+        transactions,
+        receipts,
+        gas_used,
+        profit: balance_after.saturating_sub(balance_before),
+    }))
+}
+
+/// A copy of the uncommitted changes of a [`State`], to roll back bundles.
+///
+/// Bundles are executed at the top of the block, so only the changes of the pre-block system calls
+/// and of earlier bundles are copied.
+struct StateCheckpoint {
+    cache: CacheState,
+    transition_state: Option<TransitionState>,
+}
+
+impl StateCheckpoint {
+    fn new<DB>(db: &State<DB>) -> Self {
+        Self { cache: db.cache.clone(), transition_state: db.transition_state.clone() }
+    }
+
+    fn restore<DB>(self, db: &mut State<DB>) {
+        db.cache = self.cache;
+        db.transition_state = self.transition_state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        Address, Bytes, Signature, Transaction, TransactionSigned, TxKind, TxLegacy,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_revm::database::StateProviderDatabase;
+    use revm::primitives::{CfgEnv, SpecId};
+    use std::collections::HashSet;
+
+    const COINBASE: Address = Address::repeat_byte(0xcb);
+    const RECIPIENT: Address = Address::repeat_byte(0xaa);
+    /// A contract that always reverts: `PUSH1 0 PUSH1 0 REVERT`.
+    const REVERTER: Address = Address::repeat_byte(0xee);
+    const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+    const TX_GAS_LIMIT: u64 = 50_000;
+
+    type TestState = State<StateProviderDatabase<MockEthProvider>>;
+
+    fn test_env(senders: &[Address]) -> (TestState, CfgEnvWithHandlerCfg, BlockEnv) {
+        let provider = MockEthProvider::default();
+        for sender in senders {
+            provider.add_account(*sender, ExtendedAccount::new(0, U256::from(u64::MAX)));
+        }
+        provider.add_account(
+            REVERTER,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd])),
+        );
+
+        let db = State::builder()
+            .with_database(StateProviderDatabase::new(provider))
+            .with_bundle_update()
+            .build();
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::CANCUN);
+        let block_env = BlockEnv {
+            number: U256::from(1),
+            coinbase: COINBASE,
+            gas_limit: U256::from(BLOCK_GAS_LIMIT),
+            ..Default::default()
+        };
+        (db, cfg, block_env)
+    }
+
+    fn transaction(
+        sender: Address,
+        nonce: u64,
+        to: Address,
+        gas_price: u128,
+    ) -> TransactionSignedEcRecovered {
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy {
+                nonce,
+                gas_price,
+                gas_limit: TX_GAS_LIMIT,
+                to: TxKind::Call(to),
+                ..Default::default()
+            }),
+            Signature::default(),
+        );
+        TransactionSignedEcRecovered::from_signed_transaction(tx, sender)
+    }
+
+    fn bundle(transactions: Vec<TransactionSignedEcRecovered>) -> MevBundle {
+        MevBundle {
+            transactions,
+            block_number: 1,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: HashSet::new(),
+            replacement_uuid: None,
+        }
+    }
+
+    fn balance(db: &mut TestState, address: Address) -> U256 {
+        db.basic(address).unwrap().map(|acc| acc.balance).unwrap_or_default()
+    }
+
+    fn execute(
+        db: &mut TestState,
+        cfg: &CfgEnvWithHandlerCfg,
+        block_env: &BlockEnv,
+        bundle: &MevBundle,
+    ) -> Option<BundleExecution> {
+        execute_bundle(&EthEvmConfig::default(), db, cfg, block_env, bundle, 0, BLOCK_GAS_LIMIT)
+            .unwrap()
+    }
+
+    #[test]
+    fn execute_bundle_commits_transactions() {
+        let sender = Address::repeat_byte(1);
+        let (mut db, cfg, block_env) = test_env(&[sender]);
+        let bundle = bundle(vec![
+            transaction(sender, 0, RECIPIENT, 2),
+            transaction(sender, 1, RECIPIENT, 2),
+        ]);
+
+        let execution = execute_bundle(
+            &EthEvmConfig::default(),
+            &mut db,
+            &cfg,
+            &block_env,
+            &bundle,
+            100,
+            BLOCK_GAS_LIMIT,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(execution.transactions, bundle.transactions);
+        assert_eq!(execution.gas_used, 42_000);
+        assert_eq!(
+            execution
+                .receipts
+                .iter()
+                .map(|receipt| receipt.cumulative_gas_used)
+                .collect::<Vec<_>>(),
+            vec![21_100, 42_100]
+        );
+        assert_eq!(execution.profit, U256::from(2 * 42_000));
+        assert_eq!(execution.effective_gas_price(), U256::from(2));
+        assert_eq!(balance(&mut db, COINBASE), execution.profit);
+    }
+
+    #[test]
+    fn execute_bundle_fails() {
+        let sender = Address::repeat_byte(1);
+
+        // reverts without being allowed to
+        let (mut db, cfg, block_env) = test_env(&[sender]);
+        let mut reverting = bundle(vec![transaction(sender, 0, REVERTER, 1)]);
+        assert!(execute(&mut db, &cfg, &block_env, &reverting).is_none());
+
+        let (mut db, cfg, block_env) = test_env(&[sender]);
+        reverting.reverting_tx_hashes.insert(reverting.transactions[0].hash());
+        let execution = execute(&mut db, &cfg, &block_env, &reverting).unwrap();
+        assert!(!execution.receipts[0].success);
+
+        // invalid nonce
+        let (mut db, cfg, block_env) = test_env(&[sender]);
+        let invalid = bundle(vec![transaction(sender, 5, RECIPIENT, 1)]);
+        assert!(execute(&mut db, &cfg, &block_env, &invalid).is_none());
+
+        // exceeds the block gas limit
+        let (mut db, cfg, block_env) = test_env(&[sender]);
+        let large = bundle(vec![
+            transaction(sender, 0, RECIPIENT, 1),
+            transaction(sender, 1, RECIPIENT, 1),
+        ]);
+        assert!(execute_bundle(
+            &EthEvmConfig::default(),
+            &mut db,
+            &cfg,
+            &block_env,
+            &large,
+            0,
+            2 * TX_GAS_LIMIT - 1,
+        )
+        .unwrap()
+        .is_none());
+    }
+
+    #[test]
+    fn state_checkpoint_restores_state() {
+        let sender = Address::repeat_byte(1);
+        let (mut db, cfg, block_env) = test_env(&[sender]);
+        let bundle = bundle(vec![transaction(sender, 0, RECIPIENT, 1)]);
+
+        let checkpoint = StateCheckpoint::new(&db);
+        execute(&mut db, &cfg, &block_env, &bundle).unwrap();
+        assert_eq!(db.basic(sender).unwrap().unwrap().nonce, 1);
+        assert_eq!(balance(&mut db, COINBASE), U256::from(21_000));
+
+        checkpoint.restore(&mut db);
+        assert_eq!(db.basic(sender).unwrap().unwrap().nonce, 0);
+        assert_eq!(balance(&mut db, COINBASE), U256::ZERO);
+
+        // the bundle is valid again
+        assert!(execute(&mut db, &cfg, &block_env, &bundle).is_some());
+    }
+
+    #[test]
+    fn merge_bundles_skips_failed_and_conflicting_bundles() {
+        let first = Address::repeat_byte(1);
+        let second = Address::repeat_byte(2);
+        let (mut db, cfg, block_env) = test_env(&[first, second]);
+
+        let low = bundle(vec![transaction(first, 0, RECIPIENT, 1)]);
+        let high = bundle(vec![transaction(first, 0, RECIPIENT, 3)]);
+        let other = bundle(vec![transaction(second, 0, RECIPIENT, 2)]);
+        let failed = bundle(vec![transaction(first, 7, RECIPIENT, 5)]);
+
+        let merged = merge_bundles(
+            &EthEvmConfig::default(),
+            &mut db,
+            &cfg,
+            &block_env,
+            vec![Arc::new(low), Arc::new(high.clone()), Arc::new(other.clone()), Arc::new(failed)],
+            BLOCK_GAS_LIMIT,
+        )
+        .unwrap();
+
+        // merged by effective gas price, `low` conflicts with `high`
+        assert_eq!(merged.bundles, 2);
+        assert_eq!(
+            merged.transactions,
+            vec![high.transactions[0].clone(), other.transactions[0].clone()]
+        );
+        assert_eq!(merged.gas_used, 42_000);
+        assert_eq!(
+            merged.receipts.iter().map(|receipt| receipt.cumulative_gas_used).collect::<Vec<_>>(),
+            vec![21_000, 42_000]
+        );
+        assert_eq!(merged.fees, U256::from(21_000 * 3 + 21_000 * 2));
+        assert_eq!(balance(&mut db, COINBASE), merged.fees);
+        assert_eq!(db.basic(first).unwrap().unwrap().nonce, 1);
+    }
+
+    #[test]
+    fn merge_bundles_respects_block_gas_limit() {
+        let first = Address::repeat_byte(1);
+        let second = Address::repeat_byte(2);
+        let (mut db, cfg, block_env) = test_env(&[first, second]);
+
+        let high = bundle(vec![transaction(first, 0, RECIPIENT, 2)]);
+        let low = bundle(vec![transaction(second, 0, RECIPIENT, 1)]);
+
+        // both bundles fit on their own, but not together
+        let merged = merge_bundles(
+            &EthEvmConfig::default(),
+            &mut db,
+            &cfg,
+            &block_env,
+            vec![Arc::new(low), Arc::new(high.clone())],
+            TX_GAS_LIMIT + 21_000 - 1,
+        )
+        .unwrap();
+
+        assert_eq!(merged.bundles, 1);
+        assert_eq!(merged.transactions, high.transactions);
+        assert_eq!(db.basic(second).unwrap().map(|acc| acc.nonce), Some(0));
+    }
+}
@@ -36,14 +36,24 @@ use reth_primitives::{
 };
 use reth_provider::StateProviderFactory;
 use reth_revm::{database::StateProviderDatabase, state_change::apply_blockhashes_update};
-use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
+use reth_transaction_pool::{BestTransactionsAttributes, MevBundle, TransactionPool};
 use revm::{
     db::states::bundle_state::BundleRetention,
     primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
     DatabaseCommit, State,
 };
+use std::sync::Arc;
 use tracing::{debug, trace, warn};
 
+mod bundle;
+pub use bundle::{merge_bundles, EthereumBundlePayloadBuilder, MergedBundles};
+
+mod payment;
+pub use payment::{
+    commit_proposer_payment, estimate_proposer_payment_gas, proposer_gas_limit,
+    proposer_payment_value, ProposerPayment, PROPOSER_PAYMENT_GAS_LIMIT,
+};
+
 /// Ethereum payload builder
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig> {
@@ -257,6 +267,49 @@ pub fn default_ethereum_payload_builder<EvmConfig, Pool, Client>(
     evm_config: EvmConfig,
     args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
 ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+{
+    build_payload(evm_config, args, Vec::new(), None)
+}
+
+/// Constructs an Ethereum payload for a relay using the best transactions from the pool.
+///
+/// The payload ends with the [`ProposerPayment`] of the collected fees, reduced by the cost of the
+/// payment transaction, whose gas is estimated with [`estimate_proposer_payment_gas`]. The fees of
+/// the returned payload are the value paid to the proposer. If the fees don't cover the payment,
+/// see [`proposer_payment_value`], building is aborted.
+///
+/// If the payment has the gas limit registered by the proposer, the block's gas limit moves
+/// towards it, see [`proposer_gas_limit`].
+///
+/// The fee recipient of the payload attributes must be the account of the payment's secret key.
+#[inline]
+pub fn ethereum_payload_with_proposer_payment<EvmConfig, Pool, Client>(
+    evm_config: EvmConfig,
+    args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
+    payment: ProposerPayment,
+) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+{
+    build_payload(evm_config, args, Vec::new(), Some(payment))
+}
+
+/// Constructs an Ethereum payload that starts with the non-conflicting subset of the given
+/// bundles, see [`merge_bundles`], and is filled with the best transactions from the pool.
+///
+/// If a [`ProposerPayment`] is given, the payload ends with the payment of the collected fees.
+fn build_payload<EvmConfig, Pool, Client>(
+    evm_config: EvmConfig,
+    args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
+    bundles: Vec<Arc<MevBundle>>,
+    payment: Option<ProposerPayment>,
+) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
 where
     EvmConfig: ConfigureEvm,
     Client: StateProviderFactory,
@@ -270,7 +323,7 @@ where
         State::builder().with_database_ref(cached_reads.as_db(state)).with_bundle_update().build();
     let extra_data = config.extra_data();
     let PayloadConfig {
-        initialized_block_env,
+        mut initialized_block_env,
         initialized_cfg,
         parent_block,
         attributes,
@@ -278,19 +331,37 @@ where
         ..
     } = config;
 
+    // relays expect the gas limit registered by the proposer
+    if let Some(gas_limit) = payment.as_ref().and_then(|payment| payment.gas_limit) {
+        initialized_block_env.gas_limit =
+            U256::from(proposer_gas_limit(parent_block.gas_limit, gas_limit));
+    }
+
     debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, "building new payload");
     let mut cumulative_gas_used = 0;
     let mut sum_blob_gas_used = 0;
     let block_gas_limit: u64 =
         initialized_block_env.gas_limit.try_into().unwrap_or(chain_spec.max_gas_limit);
+    // gas available to bundles and pool transactions
+    let available_gas_limit = if payment.is_some() {
+        block_gas_limit.saturating_sub(PROPOSER_PAYMENT_GAS_LIMIT)
+    } else {
+        block_gas_limit
+    };
     let base_fee = initialized_block_env.basefee.to::<u64>();
 
     let mut executed_txs = Vec::new();
 
-    let mut best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
-        base_fee,
-        initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
-    ));
+    let mut best_txs = pool.best_transactions_with_attributes(
+        BestTransactionsAttributes::new(
+            base_fee,
+            initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
+        )
+        .with_block(
+            initialized_block_env.number.to::<u64>(),
+            initialized_block_env.timestamp.to::<u64>(),
+        ),
+    );
 
     let mut total_fees = U256::ZERO;
 
@@ -327,9 +398,27 @@ where
     .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
 
     let mut receipts = Vec::new();
+
+    // bundles are placed at the top of the block
+    if !bundles.is_empty() {
+        let merged = merge_bundles(
+            &evm_config,
+            &mut db,
+            &initialized_cfg,
+            &initialized_block_env,
+            bundles,
+            available_gas_limit,
+        )?;
+        trace!(target: "payload_builder", bundles=merged.bundles, gas_used=merged.gas_used, fees=%merged.fees, "merged bundles");
+        cumulative_gas_used = merged.gas_used;
+        total_fees = merged.fees;
+        receipts.extend(merged.receipts.into_iter().map(Some));
+        executed_txs.extend(merged.transactions.into_iter().map(|tx| tx.into_signed()));
+    }
+
     while let Some(pool_tx) = best_txs.next() {
         // ensure we still have capacity for this transaction
-        if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
+        if cumulative_gas_used + pool_tx.gas_limit() > available_gas_limit {
             // we can't fit this transaction into the block, so we need to mark it as invalid
             // which also removes all dependent transaction from the iterator before we can
             // continue
@@ -434,6 +523,40 @@ where
         executed_txs.push(tx.into_signed());
     }
 
+    // pay the proposer, the value of the payload is the payment
+    if let Some(payment) = payment {
+        let gas_limit = estimate_proposer_payment_gas(
+            &evm_config,
+            &mut db,
+            &initialized_cfg,
+            &initialized_block_env,
+            chain_spec.chain().id(),
+            &payment,
+            total_fees,
+        )?;
+        let Some(value) = proposer_payment_value(total_fees, base_fee, gas_limit) else {
+            // the builder would pay the proposer from its own balance
+            trace!(target: "payload_builder", fees=%total_fees, "fees don't cover the proposer payment");
+            return Ok(BuildOutcome::Aborted { fees: U256::ZERO, cached_reads })
+        };
+        let (tx, receipt) = commit_proposer_payment(
+            &evm_config,
+            &mut db,
+            &initialized_cfg,
+            &initialized_block_env,
+            chain_spec.chain().id(),
+            &payment,
+            value,
+            gas_limit,
+            cumulative_gas_used,
+        )?;
+        trace!(target: "payload_builder", %value, recipient=%payment.proposer_fee_recipient, "committed proposer payment");
+        cumulative_gas_used = receipt.cumulative_gas_used;
+        receipts.push(Some(receipt));
+        executed_txs.push(tx);
+        total_fees = value;
+    }
+
     // check if we have a better block
     if !is_better_payload(best_payload.as_ref(), total_fees) {
         // can skip building the block
@@ -549,3 +672,117 @@ where
 
     Ok(BuildOutcome::Better { payload, cached_reads })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_basic_payload_builder::Cancelled;
+    use reth_chainspec::MAINNET;
+    use reth_payload_builder::{database::CachedReads, PayloadId};
+    use reth_primitives::{sign_message, Address, B256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction, TestPool},
+        TransactionOrigin,
+    };
+
+    const PARENT_GAS_LIMIT: u64 = 30_000_000;
+    const BASE_FEE: u64 = 7;
+    const BUILDER_KEY: B256 = B256::with_last_byte(1);
+    const PROPOSER: Address = Address::repeat_byte(0xaa);
+
+    fn builder_address() -> Address {
+        sign_message(BUILDER_KEY, B256::ZERO).unwrap().recover_signer(B256::ZERO).unwrap()
+    }
+
+    /// Returns a pool with a transfer that pays the given priority fee.
+    async fn pool(client: &MockEthProvider, priority_fee: u128) -> TestPool {
+        let sender = Address::random();
+        client.add_account(sender, ExtendedAccount::new(0, U256::from(u64::MAX)));
+        let pool = testing_pool();
+        let transaction = MockTransaction::eip1559()
+            .with_sender(sender)
+            .with_gas_limit(21_000)
+            .with_max_fee(1_000)
+            .with_priority_fee(priority_fee);
+        pool.add_transaction(TransactionOrigin::External, transaction).await.unwrap();
+        pool
+    }
+
+    fn build(
+        client: MockEthProvider,
+        pool: TestPool,
+        payment: ProposerPayment,
+    ) -> BuildOutcome<EthBuiltPayload> {
+        let parent = Arc::new(
+            Block {
+                header: Header {
+                    number: 1,
+                    gas_limit: PARENT_GAS_LIMIT,
+                    gas_used: PARENT_GAS_LIMIT / 2,
+                    base_fee_per_gas: Some(BASE_FEE),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .seal_slow(),
+        );
+        let attributes = EthPayloadBuilderAttributes {
+            id: PayloadId::new([0; 8]),
+            parent: parent.hash(),
+            timestamp: 12,
+            suggested_fee_recipient: builder_address(),
+            prev_randao: B256::ZERO,
+            withdrawals: Default::default(),
+            parent_beacon_block_root: None,
+        };
+        let config = PayloadConfig::new(parent, Default::default(), attributes, MAINNET.clone());
+        let args = BuildArguments::new(
+            client,
+            pool,
+            CachedReads::default(),
+            config,
+            Cancelled::default(),
+            None,
+        );
+        ethereum_payload_with_proposer_payment(EthEvmConfig::default(), args, payment).unwrap()
+    }
+
+    #[tokio::test]
+    async fn pays_proposer() {
+        let client = MockEthProvider::default();
+        let pool = pool(&client, 10).await;
+
+        let payment = ProposerPayment::new(BUILDER_KEY, PROPOSER).with_gas_limit(36_000_000);
+        let BuildOutcome::Better { payload, .. } = build(client, pool, payment) else {
+            panic!("expected a payload")
+        };
+
+        // the fees minus the cost of the payment, a plain transfer
+        let value = U256::from(21_000 * 10 - 21_000 * BASE_FEE);
+        assert_eq!(payload.fees(), value);
+
+        let block = payload.block();
+        assert_eq!(block.beneficiary, builder_address());
+        assert_eq!(block.gas_limit, PARENT_GAS_LIMIT + PARENT_GAS_LIMIT / 1024 - 1);
+        assert_eq!(block.gas_used, 21_000 + 21_000);
+        assert_eq!(block.body.len(), 2);
+        let payment = block.body.last().unwrap();
+        assert_eq!(payment.recover_signer(), Some(builder_address()));
+        assert_eq!(payment.to(), Some(PROPOSER));
+        assert_eq!(payment.value(), value);
+    }
+
+    #[tokio::test]
+    async fn skips_bid_below_payment_cost() {
+        let client = MockEthProvider::default();
+        // the fees of the transaction are below the cost of the payment
+        let pool = pool(&client, 1).await;
+
+        let payment = ProposerPayment::new(BUILDER_KEY, PROPOSER);
+        assert!(matches!(
+            build(client, pool, payment),
+            BuildOutcome::Aborted { fees, .. } if fees.is_zero()
+        ));
+    }
+}
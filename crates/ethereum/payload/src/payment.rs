@@ -0,0 +1,430 @@
+//! Payment of the proposer by a block builder.
+
+use reth_errors::ProviderError;
+use reth_evm::ConfigureEvm;
+use reth_payload_builder::error::PayloadBuilderError;
+use reth_primitives::{
+    constants::MINIMUM_GAS_LIMIT, sign_message, Address, Receipt, Transaction, TransactionSigned,
+    TransactionSignedEcRecovered, TxEip1559, TxKind, B256, U256,
+};
+use revm::{
+    primitives::{
+        BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, ResultAndState,
+    },
+    Database, DatabaseCommit, State,
+};
+
+/// The gas reserved in the block for the proposer payment transaction.
+///
+/// A plain value transfer uses 21_000 gas, but the fee recipient of the proposer may be a contract
+/// that uses more when it receives the payment, so the gas of the payment is estimated, see
+/// [`estimate_proposer_payment_gas`].
+pub const PROPOSER_PAYMENT_GAS_LIMIT: u64 = 100_000;
+
+/// Pays the proposer of a block built for a relay.
+///
+/// The fees of the block are collected by the builder, the fee recipient of the block. The
+/// builder pays the bid value to the fee recipient of the proposer with a transaction at the end
+/// of the block, see [`commit_proposer_payment`].
+#[derive(Clone)]
+pub struct ProposerPayment {
+    /// The secret key of the builder account, the fee recipient of the block.
+    secret_key: B256,
+    /// The fee recipient of the proposer.
+    pub proposer_fee_recipient: Address,
+    /// The gas limit the proposer registered with the relays, if any.
+    ///
+    /// The gas limit of the block moves from the parent's gas limit towards it, see
+    /// [`proposer_gas_limit`].
+    pub gas_limit: Option<u64>,
+}
+
+impl ProposerPayment {
+    /// Creates a new payment from the builder with the given secret key to the proposer's fee
+    /// recipient.
+    pub const fn new(secret_key: B256, proposer_fee_recipient: Address) -> Self {
+        Self { secret_key, proposer_fee_recipient, gas_limit: None }
+    }
+
+    /// Sets the gas limit the proposer registered with the relays.
+    pub const fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+}
+
+impl std::fmt::Debug for ProposerPayment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProposerPayment")
+            .field("proposer_fee_recipient", &self.proposer_fee_recipient)
+            .field("gas_limit", &self.gas_limit)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the value paid to the proposer of a block with the given fees.
+///
+/// The builder keeps the cost of the payment transaction, the given gas of the payment times the
+/// base fee. Returns `None` if the fees don't exceed that cost, the builder would pay the proposer
+/// from its own balance.
+pub fn proposer_payment_value(total_fees: U256, base_fee: u64, payment_gas: u64) -> Option<U256> {
+    let payment_cost = U256::from(payment_gas) * U256::from(base_fee);
+    total_fees.checked_sub(payment_cost).filter(|value| !value.is_zero())
+}
+
+/// Returns the gas limit of a block with the given parent gas limit, moved as far as allowed
+/// towards the gas limit the proposer registered with the relays.
+///
+/// Relays reject bids whose gas limit doesn't match this value.
+pub fn proposer_gas_limit(parent_gas_limit: u64, desired_gas_limit: u64) -> u64 {
+    // the gas limit may change by less than 1/1024 of the parent gas limit per block
+    let max_delta = (parent_gas_limit / 1024).saturating_sub(1);
+    let desired_gas_limit = desired_gas_limit.max(MINIMUM_GAS_LIMIT);
+    if desired_gas_limit > parent_gas_limit {
+        desired_gas_limit.min(parent_gas_limit.saturating_add(max_delta))
+    } else {
+        desired_gas_limit.max(parent_gas_limit - max_delta)
+    }
+}
+
+/// Returns the gas the transaction that pays the proposer out of the given fees needs, by executing
+/// it with [`PROPOSER_PAYMENT_GAS_LIMIT`] without committing it.
+///
+/// Returns an error if the payment fails with that gas, e.g. because the fee recipient of the
+/// proposer is a contract that doesn't accept it.
+pub fn estimate_proposer_payment_gas<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: &mut State<DB>,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    chain_id: u64,
+    payment: &ProposerPayment,
+    total_fees: U256,
+) -> Result<u64, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database<Error = ProviderError>,
+{
+    // the value can't exceed the fees minus the cost of the reserved gas, so the builder can
+    // afford the estimation
+    let value = total_fees
+        .saturating_sub(U256::from(PROPOSER_PAYMENT_GAS_LIMIT) * initialized_block_env.basefee);
+    let transaction = payment_transaction(
+        db,
+        initialized_block_env,
+        chain_id,
+        payment,
+        value,
+        PROPOSER_PAYMENT_GAS_LIMIT,
+    )?;
+    let ResultAndState { result, .. } =
+        transact_payment(evm_config, db, initialized_cfg, initialized_block_env, &transaction)?;
+
+    match result {
+        // the refund is only paid out at the end, so the transaction needs the gas before it
+        ExecutionResult::Success { gas_used, gas_refunded, .. } => Ok(gas_used + gas_refunded),
+        _ => Err(PayloadBuilderError::Other("proposer payment reverted".into())),
+    }
+}
+
+/// Executes and commits the transaction that pays the given value from the block's fee recipient
+/// to the proposer.
+///
+/// The transaction pays the base fee only, so the value plus the gas used times the base fee is
+/// deducted from the builder. The gas limit should be estimated with
+/// [`estimate_proposer_payment_gas`].
+///
+/// Returns the signed transaction and its receipt.
+#[allow(clippy::too_many_arguments)]
+pub fn commit_proposer_payment<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: &mut State<DB>,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    chain_id: u64,
+    payment: &ProposerPayment,
+    value: U256,
+    gas_limit: u64,
+    cumulative_gas_used: u64,
+) -> Result<(TransactionSigned, Receipt), PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database<Error = ProviderError>,
+{
+    let transaction =
+        payment_transaction(db, initialized_block_env, chain_id, payment, value, gas_limit)?;
+    let ResultAndState { result, state } =
+        transact_payment(evm_config, db, initialized_cfg, initialized_block_env, &transaction)?;
+
+    if !result.is_success() {
+        return Err(PayloadBuilderError::Other("proposer payment reverted".into()))
+    }
+    db.commit(state);
+
+    #[allow(clippy::needless_update)] // side-effect of optimism fields
+    let receipt = Receipt {
+        tx_type: transaction.tx_type(),
+        success: true,
+        cumulative_gas_used: cumulative_gas_used + result.gas_used(),
+        logs: result.into_logs().into_iter().map(Into::into).collect(),
+        ..Default::default()
+    };
+
+    Ok((transaction.into_signed(), receipt))
+}
+
+/// Returns the signed transaction that pays the given value from the block's fee recipient to the
+/// proposer.
+fn payment_transaction<DB>(
+    db: &mut State<DB>,
+    initialized_block_env: &BlockEnv,
+    chain_id: u64,
+    payment: &ProposerPayment,
+    value: U256,
+    gas_limit: u64,
+) -> Result<TransactionSignedEcRecovered, PayloadBuilderError>
+where
+    DB: Database<Error = ProviderError>,
+{
+    let builder = initialized_block_env.coinbase;
+    let nonce = db.basic(builder)?.map(|acc| acc.nonce).unwrap_or_default();
+
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        chain_id,
+        nonce,
+        gas_limit,
+        max_fee_per_gas: initialized_block_env.basefee.to::<u128>(),
+        max_priority_fee_per_gas: 0,
+        to: TxKind::Call(payment.proposer_fee_recipient),
+        value,
+        ..Default::default()
+    });
+    let signature = sign_message(payment.secret_key, transaction.signature_hash())
+        .map_err(PayloadBuilderError::other)?;
+    let transaction = TransactionSigned::from_transaction_and_signature(transaction, signature);
+
+    // the payment must be sent by the account that collected the fees
+    if transaction.recover_signer() != Some(builder) {
+        return Err(PayloadBuilderError::Other(
+            format!("proposer payment key does not belong to the fee recipient {builder}").into(),
+        ))
+    }
+    Ok(TransactionSignedEcRecovered::from_signed_transaction(transaction, builder))
+}
+
+/// Executes the payment transaction without committing it.
+fn transact_payment<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: &mut State<DB>,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    transaction: &TransactionSignedEcRecovered,
+) -> Result<ResultAndState, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database<Error = ProviderError>,
+{
+    let env = EnvWithHandlerCfg::new_with_cfg_env(
+        initialized_cfg.clone(),
+        initialized_block_env.clone(),
+        evm_config.tx_env(transaction),
+    );
+    let mut evm = evm_config.evm_with_env(&mut *db, env);
+    evm.transact().map_err(PayloadBuilderError::EvmExecutionError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::hex;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_revm::database::StateProviderDatabase;
+    use revm::primitives::{CfgEnv, SpecId};
+
+    const BASE_FEE: u64 = 7;
+
+    /// The gas of a plain value transfer.
+    const TRANSFER_GAS: u64 = 21_000;
+
+    /// Returns the address of the given secret key.
+    fn address(secret_key: B256) -> Address {
+        sign_message(secret_key, B256::ZERO).unwrap().recover_signer(B256::ZERO).unwrap()
+    }
+
+    fn test_env(
+        builder: Address,
+        balance: U256,
+    ) -> (State<StateProviderDatabase<MockEthProvider>>, CfgEnvWithHandlerCfg, BlockEnv) {
+        let provider = MockEthProvider::default();
+        provider.add_account(builder, ExtendedAccount::new(3, balance));
+        let db = State::builder()
+            .with_database(StateProviderDatabase::new(provider))
+            .with_bundle_update()
+            .build();
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::CANCUN);
+        let block_env = BlockEnv {
+            number: U256::from(1),
+            coinbase: builder,
+            gas_limit: U256::from(30_000_000),
+            basefee: U256::from(BASE_FEE),
+            ..Default::default()
+        };
+        (db, cfg, block_env)
+    }
+
+    #[test]
+    fn payment_value_deducts_payment_cost() {
+        let payment_cost = TRANSFER_GAS * BASE_FEE;
+        assert_eq!(
+            proposer_payment_value(U256::from(payment_cost + 100), BASE_FEE, TRANSFER_GAS),
+            Some(U256::from(100))
+        );
+        assert_eq!(proposer_payment_value(U256::from(payment_cost), BASE_FEE, TRANSFER_GAS), None);
+        assert_eq!(
+            proposer_payment_value(U256::from(payment_cost - 1), BASE_FEE, TRANSFER_GAS),
+            None
+        );
+        assert_eq!(proposer_payment_value(U256::ZERO, 0, TRANSFER_GAS), None);
+    }
+
+    #[test]
+    fn gas_limit_moves_towards_registered_gas_limit() {
+        let parent = 30_000_000;
+        let max_delta = parent / 1024 - 1;
+        assert_eq!(proposer_gas_limit(parent, parent), parent);
+        assert_eq!(proposer_gas_limit(parent, parent + 1_000), parent + 1_000);
+        assert_eq!(proposer_gas_limit(parent, 36_000_000), parent + max_delta);
+        assert_eq!(proposer_gas_limit(parent, parent - 1_000), parent - 1_000);
+        assert_eq!(proposer_gas_limit(parent, 0), parent - max_delta);
+        assert_eq!(proposer_gas_limit(MINIMUM_GAS_LIMIT, 0), MINIMUM_GAS_LIMIT);
+    }
+
+    #[test]
+    fn commits_payment() {
+        let secret_key = B256::with_last_byte(1);
+        let builder = address(secret_key);
+        let proposer = Address::repeat_byte(0xaa);
+        let (mut db, cfg, block_env) = test_env(builder, U256::from(1_000_000));
+
+        let payment = ProposerPayment::new(secret_key, proposer);
+        let evm_config = EthEvmConfig::default();
+        let gas = estimate_proposer_payment_gas(
+            &evm_config,
+            &mut db,
+            &cfg,
+            &block_env,
+            1,
+            &payment,
+            U256::from(1_000_000),
+        )
+        .unwrap();
+        assert_eq!(gas, TRANSFER_GAS);
+
+        let (tx, receipt) = commit_proposer_payment(
+            &evm_config,
+            &mut db,
+            &cfg,
+            &block_env,
+            1,
+            &payment,
+            U256::from(500_000),
+            gas,
+            100,
+        )
+        .unwrap();
+
+        assert_eq!(tx.recover_signer(), Some(builder));
+        assert_eq!(tx.nonce(), 3);
+        assert_eq!(tx.to(), Some(proposer));
+        assert_eq!(tx.value(), U256::from(500_000));
+        assert!(receipt.success);
+        assert_eq!(receipt.cumulative_gas_used, 100 + TRANSFER_GAS);
+
+        let payment_cost = TRANSFER_GAS * BASE_FEE;
+        assert_eq!(db.basic(proposer).unwrap().unwrap().balance, U256::from(500_000));
+        assert_eq!(
+            db.basic(builder).unwrap().unwrap().balance,
+            U256::from(1_000_000 - 500_000 - payment_cost)
+        );
+    }
+
+    #[test]
+    fn rejects_key_of_other_account() {
+        let builder = address(B256::with_last_byte(1));
+        let (mut db, cfg, block_env) = test_env(builder, U256::from(1_000_000));
+
+        let payment = ProposerPayment::new(B256::with_last_byte(2), Address::repeat_byte(0xaa));
+        assert!(commit_proposer_payment(
+            &EthEvmConfig::default(),
+            &mut db,
+            &cfg,
+            &block_env,
+            1,
+            &payment,
+            U256::from(1),
+            TRANSFER_GAS,
+            0,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn commits_payment_to_contract() {
+        let secret_key = B256::with_last_byte(1);
+        let builder = address(secret_key);
+        let (mut db, cfg, block_env) = test_env(builder, U256::from(10_000_000));
+
+        // a fee recipient that stores the received value: CALLVALUE PUSH1 0 SSTORE STOP
+        let proposer = Address::repeat_byte(0xbb);
+        db.database.0.add_account(
+            proposer,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("3460005500").into()),
+        );
+
+        let payment = ProposerPayment::new(secret_key, proposer);
+        let evm_config = EthEvmConfig::default();
+        let gas = estimate_proposer_payment_gas(
+            &evm_config,
+            &mut db,
+            &cfg,
+            &block_env,
+            1,
+            &payment,
+            U256::from(5_000_000),
+        )
+        .unwrap();
+        assert!(gas > TRANSFER_GAS);
+
+        // a plain transfer's gas isn't enough
+        let with_transfer_gas = commit_proposer_payment(
+            &evm_config,
+            &mut db,
+            &cfg,
+            &block_env,
+            1,
+            &payment,
+            U256::from(500_000),
+            TRANSFER_GAS,
+            0,
+        );
+        assert!(with_transfer_gas.is_err());
+
+        let (_, receipt) = commit_proposer_payment(
+            &evm_config,
+            &mut db,
+            &cfg,
+            &block_env,
+            1,
+            &payment,
+            U256::from(500_000),
+            gas,
+            0,
+        )
+        .unwrap();
+        assert!(receipt.success);
+        assert_eq!(receipt.cumulative_gas_used, gas);
+        assert_eq!(db.basic(proposer).unwrap().unwrap().balance, U256::from(500_000));
+        assert_eq!(db.storage(proposer, U256::ZERO).unwrap(), U256::from(500_000));
+    }
+}
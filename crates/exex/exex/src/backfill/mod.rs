@@ -1,3 +1,4 @@
+use futures::stream::BoxStream;
 use reth_evm::execute::{
     BatchExecutor, BlockExecutionError, BlockExecutionOutput, BlockExecutorProvider, Executor,
 };
@@ -5,17 +6,23 @@ use reth_node_api::FullNodeComponents;
 use reth_primitives::{Block, BlockNumber, BlockWithSenders, Receipt};
 use reth_primitives_traits::format_gas_throughput;
 use reth_provider::{
-    BlockReader, Chain, HeaderProvider, ProviderError, StateProviderFactory, TransactionVariant,
+    BlockNumReader, BlockReader, Chain, HeaderProvider, ProviderError, ProviderResult,
+    StateProviderFactory, TransactionVariant,
 };
 use reth_prune_types::PruneModes;
 use reth_revm::database::StateProviderDatabase;
 use reth_stages_api::ExecutionStageThresholds;
 use reth_tracing::tracing::{debug, trace};
 use std::{
+    fmt,
     ops::RangeInclusive,
+    sync::{Arc, OnceLock},
     time::{Duration, Instant},
 };
 
+mod stream;
+pub use stream::StreamBackfillJob;
+
 /// Factory for creating new backfill jobs.
 #[derive(Debug, Clone)]
 pub struct BackfillJobFactory<E, P> {
@@ -74,6 +81,76 @@ impl BackfillJobFactory<(), ()> {
     }
 }
 
+/// Backfills the blocks of `ExEx`'s that start below the node's tip, see
+/// [`ExExContext::set_head`](crate::ExExContext::set_head).
+///
+/// Implemented by [`BackfillJobFactory`].
+pub trait ExExBackfill: Send + Sync {
+    /// Returns the number of the highest block that can be backfilled.
+    fn best_block_number(&self) -> ProviderResult<BlockNumber>;
+
+    /// Returns a stream of the executed chains of the given range.
+    fn backfill_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> BoxStream<'static, Result<Chain, BlockExecutionError>>;
+}
+
+impl<E, P> ExExBackfill for BackfillJobFactory<E, P>
+where
+    E: BlockExecutorProvider,
+    P: BlockReader + HeaderProvider + StateProviderFactory + Clone + Unpin + 'static,
+{
+    fn best_block_number(&self) -> ProviderResult<BlockNumber> {
+        self.provider.best_block_number()
+    }
+
+    fn backfill_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> BoxStream<'static, Result<Chain, BlockExecutionError>> {
+        Box::pin(self.backfill(range).into_stream())
+    }
+}
+
+impl fmt::Debug for dyn ExExBackfill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExExBackfill").finish_non_exhaustive()
+    }
+}
+
+/// The backfill of an `ExEx`, shared between its [`ExExContext`](crate::ExExContext) and its
+/// [`ExExHandle`](crate::ExExHandle).
+#[derive(Debug, Clone, Default)]
+pub struct ExExBackfillState {
+    /// The last block the `ExEx` has processed before it started.
+    head: Arc<OnceLock<BlockNumber>>,
+    /// The error the backfill failed with.
+    error: Arc<OnceLock<BlockExecutionError>>,
+}
+
+impl ExExBackfillState {
+    /// Declares the head of the `ExEx`. Only the first declared head is used.
+    pub(crate) fn set_head(&self, head: BlockNumber) {
+        let _ = self.head.set(head);
+    }
+
+    /// Returns the head declared by the `ExEx`, if any.
+    pub(crate) fn head(&self) -> Option<BlockNumber> {
+        self.head.get().copied()
+    }
+
+    /// Records the error the backfill failed with.
+    pub(crate) fn set_error(&self, error: BlockExecutionError) {
+        let _ = self.error.set(error);
+    }
+
+    /// Returns the error the backfill failed with, if any.
+    pub(crate) fn error(&self) -> Option<&BlockExecutionError> {
+        self.error.get()
+    }
+}
+
 /// Backfill job started for a specific range.
 ///
 /// It implements [`Iterator`] that executes blocks in batches according to the provided thresholds
@@ -198,6 +275,11 @@ impl<E, P> BackfillJob<E, P> {
     pub fn into_single_blocks(self) -> SingleBlockBackfillJob<E, P> {
         self.into()
     }
+
+    /// Converts the backfill job into a stream that executes chunks of the range concurrently.
+    pub fn into_stream(self) -> StreamBackfillJob<E, P> {
+        self.into()
+    }
 }
 
 impl<E, P> From<BackfillJob<E, P>> for SingleBlockBackfillJob<E, P> {
@@ -266,6 +348,7 @@ where
 mod tests {
     use crate::BackfillJobFactory;
     use eyre::OptionExt;
+    use futures::TryStreamExt;
     use reth_blockchain_tree::noop::NoopBlockchainTree;
     use reth_chainspec::{ChainSpec, ChainSpecBuilder, EthereumHardfork, MAINNET};
     use reth_db_common::init::init_genesis;
@@ -519,4 +602,50 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_stream_backfill() -> eyre::Result<()> {
+        reth_tracing::init_test_tracing();
+
+        // Create a key pair for the sender
+        let key_pair = Keypair::new_global(&mut generators::rng());
+        let address = public_key_to_address(key_pair.public_key());
+
+        let chain_spec = chain_spec(address);
+
+        let executor = EthExecutorProvider::ethereum(chain_spec.clone());
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(provider_factory.clone())?;
+        let blockchain_db = BlockchainProvider::new(
+            provider_factory.clone(),
+            Arc::new(NoopBlockchainTree::default()),
+        )?;
+
+        let blocks_and_execution_outputs =
+            blocks_and_execution_outputs(provider_factory, chain_spec, key_pair)?;
+
+        // Backfill both blocks concurrently, one block per chunk
+        let factory = BackfillJobFactory::new(executor, blockchain_db);
+        let chains = factory
+            .backfill(1..=2)
+            .into_stream()
+            .with_batch_size(1)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        // Assert that the chunks are yielded in order, each with the state of its block
+        assert_eq!(chains.len(), 2);
+        for (mut chain, (block, block_execution_output)) in
+            chains.into_iter().zip(blocks_and_execution_outputs)
+        {
+            chain.execution_outcome_mut().bundle.reverts.sort();
+            assert_eq!(chain.blocks(), &[(block.number, block.clone())].into());
+            assert_eq!(
+                chain.execution_outcome(),
+                &to_execution_outcome(block.number, &block_execution_output)
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,140 @@
+use crate::BackfillJob;
+use futures::{
+    stream::{FuturesOrdered, Stream},
+    StreamExt,
+};
+use reth_evm::execute::{BlockExecutionError, BlockExecutorProvider};
+use reth_primitives::BlockNumber;
+use reth_provider::{BlockReader, Chain, HeaderProvider, StateProviderFactory};
+use reth_prune_types::PruneModes;
+use reth_stages_api::ExecutionStageThresholds;
+use reth_tracing::tracing::debug;
+use std::{
+    collections::VecDeque,
+    ops::RangeInclusive,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::task::JoinHandle;
+
+/// The default number of chunks executed concurrently by a [`StreamBackfillJob`].
+const DEFAULT_PARALLELISM: usize = 4;
+
+/// The default number of blocks in a chunk of a [`StreamBackfillJob`].
+const DEFAULT_BATCH_SIZE: u64 = 100;
+
+/// A task executing a chunk of the range on the blocking pool.
+type BackfillTask = JoinHandle<Result<Vec<Chain>, BlockExecutionError>>;
+
+/// Backfill job that executes chunks of the range concurrently.
+///
+/// The range is split into chunks of [`Self::with_batch_size`] blocks, and up to
+/// [`Self::with_parallelism`] chunks are executed at the same time on the blocking pool, each
+/// against the historical state before its first block. It implements [`Stream`] that yields the
+/// executed [`Chain`]s in order.
+///
+/// Executed chunks are held until all preceding chunks were yielded, and a new chunk is only
+/// spawned once the chains of the last finished chunk were yielded, so at most `parallelism`
+/// chunks are kept in memory.
+#[derive(Debug)]
+pub struct StreamBackfillJob<E, P> {
+    executor: E,
+    provider: P,
+    prune_modes: PruneModes,
+    thresholds: ExecutionStageThresholds,
+    /// The blocks that are not executing yet.
+    range: RangeInclusive<BlockNumber>,
+    /// The executing chunks, in order.
+    tasks: FuturesOrdered<BackfillTask>,
+    /// The chains of the last finished chunk that were not yielded yet.
+    ready: VecDeque<Chain>,
+    parallelism: usize,
+    batch_size: u64,
+}
+
+impl<E, P> StreamBackfillJob<E, P> {
+    /// Sets the number of chunks that are executed concurrently, 4 by default.
+    pub const fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Sets the number of blocks in a chunk, 100 by default.
+    pub const fn with_batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+impl<E, P> StreamBackfillJob<E, P>
+where
+    E: BlockExecutorProvider,
+    P: BlockReader + HeaderProvider + StateProviderFactory + Clone + 'static,
+{
+    /// Spawns chunks of the remaining range until the parallelism is reached.
+    fn spawn_tasks(&mut self) {
+        while self.tasks.len() < self.parallelism.max(1) && !self.range.is_empty() {
+            let start = *self.range.start();
+            let end = start.saturating_add(self.batch_size.max(1) - 1).min(*self.range.end());
+            self.range = end + 1..=*self.range.end();
+
+            debug!(target: "exex::backfill", range = ?start..=end, "Spawning backfill task");
+            let job = BackfillJob {
+                executor: self.executor.clone(),
+                provider: self.provider.clone(),
+                prune_modes: self.prune_modes.clone(),
+                thresholds: self.thresholds.clone(),
+                range: start..=end,
+            };
+            self.tasks.push_back(tokio::task::spawn_blocking(move || job.collect()));
+        }
+    }
+}
+
+impl<E, P> Stream for StreamBackfillJob<E, P>
+where
+    E: BlockExecutorProvider,
+    P: BlockReader + HeaderProvider + StateProviderFactory + Clone + Unpin + 'static,
+{
+    type Item = Result<Chain, BlockExecutionError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(chain) = this.ready.pop_front() {
+                // the next chunk is spawned once the finished chunk was yielded completely, so
+                // that it counts towards the parallelism until then
+                if this.ready.is_empty() {
+                    this.spawn_tasks();
+                }
+                return Poll::Ready(Some(Ok(chain)))
+            }
+
+            this.spawn_tasks();
+
+            match ready!(this.tasks.poll_next_unpin(cx)) {
+                Some(Ok(Ok(chains))) => this.ready.extend(chains),
+                Some(Ok(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Some(Err(err)) => return Poll::Ready(Some(Err(BlockExecutionError::other(err)))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl<E, P> From<BackfillJob<E, P>> for StreamBackfillJob<E, P> {
+    fn from(job: BackfillJob<E, P>) -> Self {
+        Self {
+            executor: job.executor,
+            provider: job.provider,
+            prune_modes: job.prune_modes,
+            thresholds: job.thresholds,
+            range: job.range,
+            tasks: FuturesOrdered::new(),
+            ready: VecDeque::new(),
+            parallelism: DEFAULT_PARALLELISM,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+}
@@ -1,7 +1,8 @@
-use crate::{ExExEvent, ExExNotification};
+use crate::{ExExBackfillState, ExExEvent, ExExNotification};
+use reth_evm::execute::BlockExecutionError;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::node_config::NodeConfig;
-use reth_primitives::Head;
+use reth_primitives::{BlockNumber, Head};
 use reth_tasks::TaskExecutor;
 use std::fmt::Debug;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
@@ -28,7 +29,12 @@ pub struct ExExContext<Node: FullNodeComponents> {
     ///
     /// Once a an [`ExExNotification`] is sent over the channel, it is considered delivered by the
     /// node.
+    ///
+    /// The channel is closed if the backfill of the exex failed, see
+    /// [`ExExContext::backfill_error`].
     pub notifications: Receiver<ExExNotification>,
+    /// The backfill of the exex, shared with the `ExEx` manager.
+    pub backfill: ExExBackfillState,
 
     /// node components
     pub components: Node,
@@ -42,12 +48,32 @@ impl<Node: FullNodeComponents> Debug for ExExContext<Node> {
             .field("reth_config", &self.reth_config)
             .field("events", &self.events)
             .field("notifications", &self.notifications)
+            .field("backfill", &self.backfill)
             .field("components", &"...")
             .finish()
     }
 }
 
 impl<Node: FullNodeComponents> ExExContext<Node> {
+    /// Declares the last block the exex has processed before it started, e.g. a persisted cursor.
+    ///
+    /// The exex will receive the blocks above it as `ChainCommitted` notifications, executed by a
+    /// backfill job up to the node's tip, followed by the live notifications without any gap or
+    /// duplicate.
+    ///
+    /// This must be called before the launch future of the exex resolves, the `ExEx` manager
+    /// reads the head once all exexs are launched. Later calls have no effect.
+    pub fn set_head(&self, head: BlockNumber) {
+        self.backfill.set_head(head);
+    }
+
+    /// Returns the error the backfill of the exex failed with, if any.
+    ///
+    /// The exex does not receive any notifications after its backfill failed.
+    pub fn backfill_error(&self) -> Option<&BlockExecutionError> {
+        self.backfill.error()
+    }
+
     /// Returns the transaction pool of the node.
     pub fn pool(&self) -> &Node::Pool {
         self.components.pool()
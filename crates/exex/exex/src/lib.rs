@@ -23,6 +23,13 @@
 //! event. To clarify: if the `ExEx` emits `ExExEvent::FinishedHeight(0)` it will receive
 //! notifications for any `block_number > 0`.
 //!
+//! # Backfill
+//!
+//! An `ExEx` that is added to a synced node, or that resumes from a persisted cursor, can declare
+//! the last block it has processed with `ExExContext::set_head` while it's launched. The blocks
+//! between it and the node's tip are then executed and sent as `ChainCommitted` notifications,
+//! followed by the live notifications.
+//!
 //! [`Future`]: std::future::Future
 //! [`ExExContext`]: crate::ExExContext
 //! [`CanonStateNotification`]: reth_provider::CanonStateNotification
@@ -49,6 +56,9 @@ pub use manager::*;
 mod notification;
 pub use notification::*;
 
+mod wal;
+pub use wal::*;
+
 // Re-export exex types
 #[doc(inline)]
 pub use reth_exex_types::*;
@@ -1,9 +1,16 @@
-use crate::{ExExEvent, ExExNotification, FinishedExExHeight};
+use crate::{
+    wal::notification_range, ExExBackfill, ExExBackfillState, ExExEvent, ExExNotification,
+    FinishedExExHeight, Wal,
+};
+use futures::{stream::BoxStream, FutureExt, StreamExt};
 use metrics::Gauge;
+use reth_evm::execute::BlockExecutionError;
 use reth_metrics::{metrics::Counter, Metrics};
 use reth_primitives::BlockNumber;
-use reth_tracing::tracing::debug;
+use reth_provider::Chain;
+use reth_tracing::tracing::{debug, error};
 use std::{
+    borrow::Cow,
     collections::VecDeque,
     future::{poll_fn, Future},
     pin::Pin,
@@ -13,9 +20,12 @@ use std::{
     },
     task::{ready, Context, Poll},
 };
-use tokio::sync::{
-    mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
-    watch,
+use tokio::{
+    sync::{
+        mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
+        watch,
+    },
+    task::JoinHandle,
 };
 use tokio_util::sync::{PollSendError, PollSender, ReusableBoxFuture};
 
@@ -34,7 +44,6 @@ struct ExExMetrics {
 /// A handle should be created for each `ExEx` with a unique ID. The channels returned by
 /// [`ExExHandle::new`] should be given to the `ExEx`, while the handle itself should be given to
 /// the manager in [`ExExManager::new`].
-#[derive(Debug)]
 pub struct ExExHandle {
     /// The execution extension's ID.
     id: String,
@@ -52,6 +61,44 @@ pub struct ExExHandle {
     ///
     /// If this is `None`, the `ExEx` has not emitted a `FinishedHeight` event.
     finished_height: Option<BlockNumber>,
+    /// The finished height of the `ExEx` recorded in the WAL and the ID of the first notification
+    /// that was not replayed from the WAL.
+    ///
+    /// Replayed notifications with all blocks at or below the height were processed before the
+    /// node shut down, and are not sent again.
+    replayed: Option<(BlockNumber, usize)>,
+
+    /// The backfill of the `ExEx`, shared with its context.
+    backfill_state: ExExBackfillState,
+    /// The highest block delivered to the `ExEx` by a backfill, or the head declared by the
+    /// `ExEx`. Committed blocks at or below it are not sent again, until the first live
+    /// notification is delivered.
+    backfilled_height: Option<BlockNumber>,
+    /// The highest block the running backfill must deliver before the `ExEx` receives live
+    /// notifications. It's extended by the chain commits received while the backfill is running.
+    backfill_target: Option<BlockNumber>,
+    /// The chains of a running backfill job.
+    ///
+    /// Notifications are not sent to the `ExEx` until the backfill is finished.
+    backfill: Option<BoxStream<'static, Result<Chain, BlockExecutionError>>>,
+}
+
+impl std::fmt::Debug for ExExHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExExHandle")
+            .field("id", &self.id)
+            .field("metrics", &self.metrics)
+            .field("sender", &self.sender)
+            .field("receiver", &self.receiver)
+            .field("next_notification_id", &self.next_notification_id)
+            .field("finished_height", &self.finished_height)
+            .field("replayed", &self.replayed)
+            .field("backfill_state", &self.backfill_state)
+            .field("backfilled_height", &self.backfilled_height)
+            .field("backfill_target", &self.backfill_target)
+            .field("backfill", &self.backfill.is_some())
+            .finish()
+    }
 }
 
 impl ExExHandle {
@@ -71,12 +118,107 @@ impl ExExHandle {
                 receiver: event_rx,
                 next_notification_id: 0,
                 finished_height: None,
+                replayed: None,
+                backfill_state: ExExBackfillState::default(),
+                backfilled_height: None,
+                backfill_target: None,
+                backfill: None,
             },
             event_tx,
             notification_rx,
         )
     }
 
+    /// Returns the backfill of the `ExEx`, which should be given to the `ExEx` in its context.
+    pub fn backfill_state(&self) -> ExExBackfillState {
+        self.backfill_state.clone()
+    }
+
+    /// Starts a backfill job for the blocks above the head declared by the `ExEx`, up to the
+    /// highest block of the node.
+    fn start_backfill(&mut self, backfill: &dyn ExExBackfill) {
+        let Some(head) = self.backfilled_height else { return };
+        let tip = match backfill.best_block_number() {
+            Ok(tip) => tip,
+            Err(err) => return self.fail_backfill(err.into()),
+        };
+        if tip <= head {
+            return
+        }
+
+        debug!(exex_id = %self.id, %head, %tip, "Starting backfill");
+        self.backfill_target = Some(tip);
+        self.backfill = Some(backfill.backfill_range(head + 1..=tip));
+    }
+
+    /// Extends the running backfill to the tip of a chain commit received while it's running,
+    /// instead of holding the notification in the manager's buffer until the backfill is finished.
+    fn extend_backfill(&mut self, tip: BlockNumber) {
+        self.backfill_target = self.backfill_target.max(Some(tip));
+    }
+
+    /// Records the error of the backfill in the state shared with the `ExEx` and closes its
+    /// notification channel, since the `ExEx` can't receive the blocks without a gap anymore.
+    fn fail_backfill(&mut self, err: BlockExecutionError) {
+        error!(exex_id = %self.id, %err, "Backfill failed");
+        self.backfill_state.set_error(err);
+        self.backfill_target = None;
+        self.backfill = None;
+        self.sender.close();
+    }
+
+    /// Returns `true` if the backfill of the `ExEx` failed. No notifications are sent to it
+    /// anymore.
+    fn is_failed(&self) -> bool {
+        self.backfill_state.error().is_some()
+    }
+
+    /// Sends the chains of the running backfill job to the `ExEx` as `ChainCommitted`
+    /// notifications. If the backfill was extended while it was running, a job for the remaining
+    /// blocks is started once the running one is done.
+    ///
+    /// Resolves once the backfill is finished or failed.
+    fn poll_backfill(
+        &mut self,
+        cx: &mut Context<'_>,
+        factory: Option<&dyn ExExBackfill>,
+    ) -> Poll<Result<(), PollSendError<ExExNotification>>> {
+        while let Some(backfill) = &mut self.backfill {
+            ready!(self.sender.poll_reserve(cx))?;
+            let chain = match ready!(backfill.poll_next_unpin(cx)) {
+                Some(Ok(chain)) => chain,
+                Some(Err(err)) => {
+                    self.fail_backfill(err);
+                    break
+                }
+                None => {
+                    self.sender.abort_send();
+                    self.backfill = None;
+
+                    if let (Some(factory), Some(head), Some(target)) =
+                        (factory, self.backfilled_height, self.backfill_target)
+                    {
+                        if target > head {
+                            debug!(exex_id = %self.id, %head, %target, "Extending backfill");
+                            self.backfill = Some(factory.backfill_range(head + 1..=target));
+                            continue
+                        }
+                    }
+
+                    debug!(exex_id = %self.id, backfilled_height = ?self.backfilled_height, "Finished backfill");
+                    self.backfill_target = None;
+                    break
+                }
+            };
+
+            debug!(exex_id = %self.id, range = ?chain.range(), "Sending backfilled chain");
+            self.backfilled_height = Some(chain.tip().number);
+            self.sender.send_item(ExExNotification::ChainCommitted { new: Arc::new(chain) })?;
+            self.metrics.notifications_sent_total.increment(1);
+        }
+        Poll::Ready(Ok(()))
+    }
+
     /// Reserves a slot in the `PollSender` channel and sends the notification if the slot was
     /// successfully reserved.
     ///
@@ -86,6 +228,25 @@ impl ExExHandle {
         cx: &mut Context<'_>,
         (notification_id, notification): &(usize, ExExNotification),
     ) -> Poll<Result<(), PollSendError<ExExNotification>>> {
+        if let Some((wal_finished_height, replayed_until)) = self.replayed {
+            // Skip the replayed notification if the ExEx processed all of its blocks before the
+            // node shut down, including reorgs and reverts.
+            if *notification_id < replayed_until &&
+                notification_range(notification)
+                    .map_or(true, |range| *range.end() <= wal_finished_height)
+            {
+                debug!(
+                    exex_id = %self.id,
+                    %notification_id,
+                    %wal_finished_height,
+                    "Skipping replayed notification"
+                );
+
+                self.next_notification_id = notification_id + 1;
+                return Poll::Ready(Ok(()))
+            }
+        }
+
         if let Some(finished_height) = self.finished_height {
             match notification {
                 ExExNotification::ChainCommitted { new } => {
@@ -113,6 +274,24 @@ impl ExExHandle {
             }
         }
 
+        let notification = match self.backfilled_height {
+            Some(backfilled_height) => {
+                notification.without_backfilled(backfilled_height).map(Cow::into_owned)
+            }
+            None => Some(notification.clone()),
+        };
+        let Some(notification) = notification else {
+            debug!(
+                exex_id = %self.id,
+                %notification_id,
+                backfilled_height = ?self.backfilled_height,
+                "Skipping backfilled notification"
+            );
+
+            self.next_notification_id = notification_id + 1;
+            return Poll::Ready(Ok(()))
+        };
+
         debug!(
             exex_id = %self.id,
             %notification_id,
@@ -128,8 +307,11 @@ impl ExExHandle {
             %notification_id,
             "Sending notification"
         );
-        match self.sender.send_item(notification.clone()) {
+        match self.sender.send_item(notification) {
             Ok(()) => {
+                // the `ExEx` is live, later commits of blocks at or below the backfilled height
+                // follow a reorg or revert and must be sent
+                self.backfilled_height = None;
                 self.next_notification_id = notification_id + 1;
                 self.metrics.notifications_sent_total.increment(1);
                 Poll::Ready(Ok(()))
@@ -139,6 +321,48 @@ impl ExExHandle {
     }
 }
 
+/// The write-ahead log of the [`ExExManager`].
+///
+/// The WAL is accessed on blocking tasks, one at a time, to keep the file system writes and syncs
+/// off the manager's task.
+#[derive(Debug)]
+struct ManagerWal {
+    /// The WAL, `None` while a blocking task accesses it.
+    wal: Option<Wal>,
+    /// The blocking task accessing the WAL, resolving to the notification to queue once it's
+    /// durable, if any.
+    task: Option<JoinHandle<(Wal, eyre::Result<Option<ExExNotification>>)>>,
+}
+
+impl ManagerWal {
+    /// Runs the operation on the WAL on a blocking task.
+    ///
+    /// # Panics
+    ///
+    /// If a task is already running.
+    fn spawn<F>(&mut self, op: F)
+    where
+        F: FnOnce(&mut Wal) -> eyre::Result<Option<ExExNotification>> + Send + 'static,
+    {
+        let mut wal = self.wal.take().expect("WAL task is already running");
+        self.task = Some(tokio::task::spawn_blocking(move || {
+            let result = op(&mut wal);
+            (wal, result)
+        }));
+    }
+
+    /// Polls the running task.
+    ///
+    /// Resolves once no task is running, with the notification of the finished task, if any.
+    fn poll_task(&mut self, cx: &mut Context<'_>) -> Poll<eyre::Result<Option<ExExNotification>>> {
+        let Some(task) = &mut self.task else { return Poll::Ready(Ok(None)) };
+        let (wal, result) = ready!(task.poll_unpin(cx))?;
+        self.task = None;
+        self.wal = Some(wal);
+        Poll::Ready(result)
+    }
+}
+
 /// Metrics for the `ExEx` manager.
 #[derive(Metrics)]
 #[metrics(scope = "exex_manager")]
@@ -194,6 +418,10 @@ pub struct ExExManager {
     /// The finished height of all `ExEx`'s.
     finished_height: watch::Sender<FinishedExExHeight>,
 
+    /// The write-ahead log of the notifications, if any.
+    wal: Option<ManagerWal>,
+    /// The factory of the backfill jobs, if any.
+    backfill: Option<Box<dyn ExExBackfill>>,
     /// A handle to the `ExEx` manager.
     handle: ExExManagerHandle,
     /// Metrics for the `ExEx` manager.
@@ -208,8 +436,14 @@ impl ExExManager {
     ///
     /// When the capacity is exceeded (which can happen if an `ExEx` is slow) no one can send
     /// notifications over [`ExExManagerHandle`]s until there is capacity again.
-    pub fn new(handles: Vec<ExExHandle>, max_capacity: usize) -> Self {
+    ///
+    /// The committed blocks at or below the heads the `ExEx`'s declared in their context are not
+    /// sent to them, so the manager must be created once all `ExEx`'s are launched.
+    pub fn new(mut handles: Vec<ExExHandle>, max_capacity: usize) -> Self {
         let num_exexs = handles.len();
+        for exex in &mut handles {
+            exex.backfilled_height = exex.backfill_state.head();
+        }
 
         let (handle_tx, handle_rx) = mpsc::unbounded_channel();
         let (is_ready_tx, is_ready_rx) = watch::channel(true);
@@ -239,6 +473,9 @@ impl ExExManager {
             is_ready: is_ready_tx,
             finished_height: finished_height_tx,
 
+            wal: None,
+            backfill: None,
+
             handle: ExExManagerHandle {
                 exex_tx: handle_tx,
                 num_exexs,
@@ -251,6 +488,65 @@ impl ExExManager {
         }
     }
 
+    /// Sets the write-ahead log of the manager.
+    ///
+    /// Every notification is written to the WAL before it's delivered. The finished heights of
+    /// the `ExEx`'s recorded in the WAL are restored, and the notifications in the WAL are queued
+    /// again, so that each `ExEx` receives the notifications with blocks above its own finished
+    /// height. Notifications below the finished height of all `ExEx`'s are removed from the WAL.
+    ///
+    /// The WAL is accessed on blocking tasks, so the manager must run on a tokio runtime.
+    pub fn with_wal(mut self, wal: Wal) -> eyre::Result<Self> {
+        for exex in &mut self.exex_handles {
+            if let Some(height) = wal.finished_height(&exex.id) {
+                exex.finished_height = Some(height);
+            }
+        }
+
+        // ExExes without a recorded finished height receive all notifications in the WAL
+        let replay_from = self
+            .exex_handles
+            .iter()
+            .map(|exex| exex.finished_height.unwrap_or_default())
+            .min()
+            .unwrap_or(BlockNumber::MAX);
+        for notification in wal.notifications_above(replay_from)? {
+            debug!(
+                committed_tip = ?notification.committed_chain().map(|chain| chain.tip().number),
+                reverted_tip = ?notification.reverted_chain().map(|chain| chain.tip().number),
+                "Replaying notification from WAL"
+            );
+            self.push_notification(notification);
+        }
+        for exex in &mut self.exex_handles {
+            exex.replayed = exex.finished_height.map(|height| (height, self.next_id));
+        }
+        self.update_capacity();
+
+        self.wal = Some(ManagerWal { wal: Some(wal), task: None });
+        Ok(self)
+    }
+
+    /// Starts the backfill jobs of the `ExEx`'s that declared a head in their context.
+    ///
+    /// The blocks between the head and the node's tip are executed and sent to the `ExEx` before
+    /// any other notification. Live notifications are buffered in the meantime, and the blocks
+    /// that were already backfilled are removed from them. To not miss any block, the
+    /// notifications sent to the manager must be subscribed to before this is called.
+    ///
+    /// The chain commits received while the backfill of an `ExEx` is running extend the backfill
+    /// to their tips, so a slow backfill doesn't fill up the buffer of the manager.
+    ///
+    /// If the backfill of an `ExEx` fails, the error is recorded in its
+    /// [`ExExBackfillState`], and the `ExEx` receives no more notifications.
+    pub fn with_backfill(mut self, backfill: impl ExExBackfill + 'static) -> Self {
+        for exex in &mut self.exex_handles {
+            exex.start_backfill(&backfill);
+        }
+        self.backfill = Some(Box::new(backfill));
+        self
+    }
+
     /// Returns the handle to the manager.
     pub fn handle(&self) -> ExExManagerHandle {
         self.handle.clone()
@@ -282,35 +578,90 @@ impl Future for ExExManager {
     type Output = eyre::Result<()>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        // drain handle notifications
-        while self.buffer.len() < self.max_capacity {
-            if let Poll::Ready(Some(notification)) = self.handle_rx.poll_recv(cx) {
-                debug!(
-                    committed_tip = ?notification.committed_chain().map(|chain| chain.tip().number),
-                    reverted_tip = ?notification.reverted_chain().map(|chain| chain.tip().number),
-                    "Received new notification"
-                );
-                self.push_notification(notification);
-                continue
+        // drain handle notifications, each is queued once it's durable in the WAL
+        loop {
+            match self.wal.as_mut().map(|wal| wal.poll_task(cx)) {
+                Some(Poll::Ready(Ok(Some(notification)))) => self.push_notification(notification),
+                Some(Poll::Ready(Ok(None))) | None => {}
+                Some(Poll::Ready(Err(err))) => return Poll::Ready(Err(err)),
+                Some(Poll::Pending) => break,
+            }
+            if self.buffer.len() >= self.max_capacity {
+                break
+            }
+
+            let Poll::Ready(Some(notification)) = self.handle_rx.poll_recv(cx) else { break };
+            debug!(
+                committed_tip = ?notification.committed_chain().map(|chain| chain.tip().number),
+                reverted_tip = ?notification.reverted_chain().map(|chain| chain.tip().number),
+                "Received new notification"
+            );
+            match &mut self.wal {
+                Some(wal) => wal.spawn(move |wal| {
+                    wal.commit(&notification)?;
+                    Ok(Some(notification))
+                }),
+                None => self.push_notification(notification),
             }
-            break
         }
 
         // update capacity
         self.update_capacity();
 
+        // handle incoming exex events
+        for exex in &mut self.exex_handles {
+            while let Poll::Ready(Some(event)) = exex.receiver.poll_recv(cx) {
+                debug!(exex_id = %exex.id, ?event, "Received event from exex");
+                exex.metrics.events_sent_total.increment(1);
+                match event {
+                    ExExEvent::FinishedHeight(height) => exex.finished_height = Some(height),
+                }
+            }
+        }
+
         // advance all poll senders
         let mut min_id = usize::MAX;
         for idx in (0..self.exex_handles.len()).rev() {
             let mut exex = self.exex_handles.swap_remove(idx);
 
+            // live notifications are sent once the backfill is finished
+            let backfill_finished = match exex.poll_backfill(cx, self.backfill.as_deref()) {
+                Poll::Ready(Ok(())) => true,
+                // the channel was closed, which is irrecoverable for the manager
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+                Poll::Pending => false,
+            };
+            // the notifications are not kept for an `ExEx` with a failed backfill
+            if exex.is_failed() {
+                self.exex_handles.push(exex);
+                continue
+            }
+
             // it is a logic error for this to ever underflow since the manager manages the
             // notification IDs
             let notification_index = exex
                 .next_notification_id
                 .checked_sub(self.min_id)
                 .expect("exex expected notification ID outside the manager's range");
-            if let Some(notification) = self.buffer.get(notification_index) {
+            if !backfill_finished {
+                // the blocks of the chain commits are delivered by the backfill, so they don't
+                // need to be kept for the `ExEx`. Reorgs and reverts are sent once the backfill
+                // is finished.
+                for (id, notification) in self.buffer.iter().skip(notification_index) {
+                    let ExExNotification::ChainCommitted { new } = notification else { break };
+                    debug!(
+                        exex_id = %exex.id,
+                        notification_id = %id,
+                        new_tip = %new.tip().number,
+                        "Extending backfill by notification"
+                    );
+                    exex.extend_backfill(new.tip().number);
+                    exex.next_notification_id = id + 1;
+                }
+            }
+            if let Some(notification) =
+                self.buffer.get(notification_index).filter(|_| backfill_finished)
+            {
                 if let Poll::Ready(Err(err)) = exex.send(cx, notification) {
                     // the channel was closed, which is irrecoverable for the manager
                     return Poll::Ready(Err(err.into()))
@@ -328,32 +679,58 @@ impl Future for ExExManager {
         // update capacity
         self.update_capacity();
 
-        // handle incoming exex events
-        for exex in &mut self.exex_handles {
-            while let Poll::Ready(Some(event)) = exex.receiver.poll_recv(cx) {
-                debug!(exex_id = %exex.id, ?event, "Received event from exex");
-                exex.metrics.events_sent_total.increment(1);
-                match event {
-                    ExExEvent::FinishedHeight(height) => exex.finished_height = Some(height),
+        // update watch channel block number
+        // the `ExEx`'s with a failed backfill don't receive any more blocks, so they don't hold
+        // back the finished height
+        let finished_height = self
+            .exex_handles
+            .iter_mut()
+            .filter(|exex| !exex.is_failed())
+            .try_fold(u64::MAX, |curr, exex| {
+                let height = match exex.finished_height {
+                    None => return Err(()),
+                    Some(height) => height,
+                };
+
+                if height < curr {
+                    Ok(height)
+                } else {
+                    Ok(curr)
                 }
-            }
+            });
+        if let Ok(finished_height) = finished_height {
+            let _ = self.finished_height.send(FinishedExExHeight::Height(finished_height));
         }
 
-        // update watch channel block number
-        let finished_height = self.exex_handles.iter_mut().try_fold(u64::MAX, |curr, exex| {
-            let height = match exex.finished_height {
-                None => return Err(()),
-                Some(height) => height,
-            };
+        // record the finished heights in the WAL, and remove the notifications all ExExes
+        // processed
+        let this = &mut *self;
+        if let Some(wal) = &mut this.wal {
+            if let Some(inner) = &wal.wal {
+                let finished_heights = this
+                    .exex_handles
+                    .iter()
+                    .filter_map(|exex| Some((exex.id.clone(), exex.finished_height?)))
+                    .filter(|(id, height)| inner.finished_height(id) != Some(*height))
+                    .collect::<Vec<_>>();
+                let finalize = finished_height.ok().filter(|height| inner.has_finalized(*height));
 
-            if height < curr {
-                Ok(height)
-            } else {
-                Ok(curr)
+                if !finished_heights.is_empty() || finalize.is_some() {
+                    wal.spawn(move |wal| {
+                        for (id, height) in finished_heights {
+                            wal.set_finished_height(&id, height)?;
+                        }
+                        if let Some(height) = finalize {
+                            wal.finalize(height)?;
+                        }
+                        Ok(None)
+                    });
+                    // register the waker of the task
+                    if let Poll::Ready(Err(err)) = wal.poll_task(cx) {
+                        return Poll::Ready(Err(err))
+                    }
+                }
             }
-        });
-        if let Ok(finished_height) = finished_height {
-            let _ = self.finished_height.send(FinishedExExHeight::Height(finished_height));
         }
 
         Poll::Pending
@@ -481,6 +858,188 @@ impl Clone for ExExManagerHandle {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use reth_provider::{ExecutionOutcome, ProviderResult};
+    use reth_testing_utils::generators::{self, random_block, Rng};
+    use std::ops::RangeInclusive;
+
+    fn chain(rng: &mut impl Rng, numbers: RangeInclusive<BlockNumber>) -> Arc<Chain> {
+        let blocks = numbers.map(|number| {
+            random_block(rng, number, None, Some(0), None).seal_with_senders().unwrap()
+        });
+        Arc::new(Chain::new(blocks, ExecutionOutcome::default(), None))
+    }
+
+    /// Backfills the given chains, up to a fixed tip.
+    struct TestBackfill {
+        tip: BlockNumber,
+        chains: Vec<Arc<Chain>>,
+        /// The block the backfill fails at, if any.
+        fail_at: Option<BlockNumber>,
+    }
+
+    impl ExExBackfill for TestBackfill {
+        fn best_block_number(&self) -> ProviderResult<BlockNumber> {
+            Ok(self.tip)
+        }
+
+        fn backfill_range(
+            &self,
+            range: RangeInclusive<BlockNumber>,
+        ) -> BoxStream<'static, Result<Chain, BlockExecutionError>> {
+            let chains = self
+                .chains
+                .iter()
+                .filter(|chain| range.contains(&chain.first().number))
+                .map(|chain| match self.fail_at {
+                    Some(block) if chain.range().contains(&block) => {
+                        Err(BlockExecutionError::msg("backfill failed"))
+                    }
+                    _ => Ok((**chain).clone()),
+                })
+                .collect::<Vec<_>>();
+            futures::stream::iter(chains).boxed()
+        }
+    }
+
+    /// Returns the numbers of the committed blocks of the notification.
+    fn committed_blocks(notification: Option<ExExNotification>) -> Vec<BlockNumber> {
+        notification.unwrap().committed_chain().unwrap().range().collect()
+    }
+
+    #[tokio::test]
+    async fn replays_wal_from_finished_height_of_each_exex() {
+        let mut rng = generators::rng();
+        let dir = tempfile::tempdir().unwrap();
+
+        let committed = ExExNotification::ChainCommitted { new: chain(&mut rng, 1..=2) };
+        let reorged = ExExNotification::ChainReorged {
+            old: chain(&mut rng, 2..=2),
+            new: chain(&mut rng, 2..=3),
+        };
+        let committed_after = ExExNotification::ChainCommitted { new: chain(&mut rng, 4..=4) };
+
+        let mut wal = Wal::open(dir.path()).unwrap();
+        for notification in [&committed, &reorged, &committed_after] {
+            wal.commit(notification).unwrap();
+        }
+        // `finished` processed the reorg before the shutdown, `behind` only the first commit
+        wal.set_finished_height("finished", 3).unwrap();
+        wal.set_finished_height("behind", 2).unwrap();
+
+        let (finished, _, mut finished_rx) = ExExHandle::new("finished".to_string());
+        let (behind, _, mut behind_rx) = ExExHandle::new("behind".to_string());
+        let (fresh, _, mut fresh_rx) = ExExHandle::new("fresh".to_string());
+        let manager = ExExManager::new(vec![finished, behind, fresh], 16)
+            .with_wal(Wal::open(dir.path()).unwrap())
+            .unwrap();
+        let manager_handle = manager.handle();
+        tokio::spawn(manager);
+
+        // the reorg is not sent again to the ExEx that processed it
+        assert_eq!(finished_rx.recv().await, Some(committed_after.clone()));
+        assert_eq!(behind_rx.recv().await, Some(reorged.clone()));
+        assert_eq!(behind_rx.recv().await, Some(committed_after.clone()));
+        // an ExEx without a finished height receives all notifications
+        assert_eq!(fresh_rx.recv().await, Some(committed));
+        assert_eq!(fresh_rx.recv().await, Some(reorged));
+        assert_eq!(fresh_rx.recv().await, Some(committed_after));
+
+        // live notifications are written to the WAL before they're delivered
+        let live = ExExNotification::ChainCommitted { new: chain(&mut rng, 5..=5) };
+        manager_handle.send(live.clone()).unwrap();
+        for rx in [&mut finished_rx, &mut behind_rx, &mut fresh_rx] {
+            assert_eq!(rx.recv().await, Some(live.clone()));
+        }
+        assert_eq!(Wal::open(dir.path()).unwrap().notifications_above(4).unwrap(), vec![live]);
+    }
+
+    #[tokio::test]
+    async fn hands_over_from_backfill_to_live_notifications() {
+        let mut rng = generators::rng();
+        let backfill = TestBackfill {
+            tip: 4,
+            chains: vec![
+                chain(&mut rng, 2..=3),
+                chain(&mut rng, 4..=4),
+                chain(&mut rng, 5..=5),
+                chain(&mut rng, 6..=6),
+            ],
+            fail_at: None,
+        };
+
+        let (exex, _, mut rx) = ExExHandle::new("test".to_string());
+        exex.backfill_state().set_head(1);
+        let manager = ExExManager::new(vec![exex], 16);
+
+        // block 4 was committed after the notifications were subscribed to, but before the
+        // backfill read the tip. The commits received while the backfill is running extend it
+        // to block 6.
+        let manager_handle = manager.handle();
+        for numbers in [4..=5, 6..=6] {
+            let notification = ExExNotification::ChainCommitted { new: chain(&mut rng, numbers) };
+            manager_handle.send(notification).unwrap();
+        }
+        tokio::spawn(manager.with_backfill(backfill));
+
+        let mut blocks = Vec::new();
+        while blocks.last() != Some(&6) {
+            blocks.extend(committed_blocks(rx.recv().await));
+        }
+        assert_eq!(blocks, vec![2, 3, 4, 5, 6]);
+
+        // blocks below the backfilled height are sent again once they're reverted
+        let reverted = ExExNotification::ChainReverted { old: chain(&mut rng, 3..=6) };
+        let recommitted = ExExNotification::ChainCommitted { new: chain(&mut rng, 3..=3) };
+        manager_handle.send(reverted.clone()).unwrap();
+        manager_handle.send(recommitted.clone()).unwrap();
+        assert_eq!(rx.recv().await, Some(reverted));
+        assert_eq!(rx.recv().await, Some(recommitted));
+    }
+
+    #[tokio::test]
+    async fn sends_backfill_error_to_exex() {
+        let mut rng = generators::rng();
+        let backfill = TestBackfill {
+            tip: 3,
+            chains: vec![chain(&mut rng, 2..=2), chain(&mut rng, 3..=3)],
+            fail_at: Some(3),
+        };
+
+        let (failing, _, mut failing_rx) = ExExHandle::new("failing".to_string());
+        let failing_state = failing.backfill_state();
+        failing_state.set_head(1);
+        let (live, live_events, mut live_rx) = ExExHandle::new("live".to_string());
+        let manager = ExExManager::new(vec![failing, live], 1).with_backfill(backfill);
+        let manager_handle = manager.handle();
+        let manager = tokio::spawn(manager);
+
+        assert_eq!(committed_blocks(failing_rx.recv().await), vec![2]);
+        assert_eq!(failing_rx.recv().await, None);
+        assert!(failing_state.error().is_some());
+
+        // the other ExEx keeps receiving notifications, and the ExEx with the failed backfill
+        // doesn't hold the buffer
+        for number in 4..=5 {
+            let notification =
+                ExExNotification::ChainCommitted { new: chain(&mut rng, number..=number) };
+            manager_handle.send(notification).unwrap();
+            assert_eq!(committed_blocks(live_rx.recv().await), vec![number]);
+        }
+        assert!(!manager.is_finished());
+
+        // the ExEx with the failed backfill doesn't hold back the finished height
+        let mut finished_height = manager_handle.finished_height();
+        live_events.send(ExExEvent::FinishedHeight(5)).unwrap();
+        manager_handle
+            .send(ExExNotification::ChainCommitted { new: chain(&mut rng, 6..=6) })
+            .unwrap();
+        finished_height
+            .wait_for(|height| matches!(height, FinishedExExHeight::Height(5)))
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn delivers_events() {}
 
@@ -1,10 +1,10 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
-use reth_provider::{CanonStateNotification, Chain};
+use reth_primitives::BlockNumber;
+use reth_provider::{CanonStateNotification, Chain, ChainSplit, ChainSplitTarget};
 
 /// Notifications sent to an `ExEx`.
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ExExNotification {
     /// Chain got committed without a reorg, and only the new chain is returned.
     ChainCommitted {
@@ -43,6 +43,27 @@ impl ExExNotification {
             Self::ChainCommitted { .. } => None,
         }
     }
+
+    /// Removes the committed blocks at or below the given height, that were already delivered by
+    /// a backfill, from a [`Self::ChainCommitted`] notification.
+    ///
+    /// Returns `None` if all blocks of the commit were delivered, and the notification itself if
+    /// none of its blocks were delivered or it isn't a commit.
+    pub fn without_backfilled(&self, backfilled_height: BlockNumber) -> Option<Cow<'_, Self>> {
+        let Self::ChainCommitted { new } = self else { return Some(Cow::Borrowed(self)) };
+
+        if backfilled_height >= new.tip().number {
+            return None
+        }
+        if backfilled_height < new.first().number {
+            return Some(Cow::Borrowed(self))
+        }
+        let new = match (**new).clone().split(ChainSplitTarget::Number(backfilled_height)) {
+            ChainSplit::Split { pending, .. } => pending,
+            ChainSplit::NoSplitPending(chain) | ChainSplit::NoSplitCanonical(chain) => chain,
+        };
+        Some(Cow::Owned(Self::ChainCommitted { new: Arc::new(new) }))
+    }
 }
 
 impl From<CanonStateNotification> for ExExNotification {
@@ -53,3 +74,34 @@ impl From<CanonStateNotification> for ExExNotification {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_provider::ExecutionOutcome;
+    use reth_testing_utils::generators::{self, random_block};
+
+    #[test]
+    fn skips_backfilled_blocks() {
+        let mut rng = generators::rng();
+        let blocks = (1..=4)
+            .map(|number| {
+                random_block(&mut rng, number, None, Some(0), None).seal_with_senders().unwrap()
+            })
+            .collect::<Vec<_>>();
+        let notification = ExExNotification::ChainCommitted {
+            new: Arc::new(Chain::new(blocks, ExecutionOutcome::default(), None)),
+        };
+
+        assert!(matches!(notification.without_backfilled(0), Some(Cow::Borrowed(_))));
+        assert_eq!(notification.without_backfilled(4), None);
+
+        let remaining = notification.without_backfilled(2).unwrap();
+        assert!(matches!(remaining, Cow::Owned(_)));
+        assert_eq!(remaining.committed_chain().unwrap().range(), 3..=4);
+
+        let reverted =
+            ExExNotification::ChainReverted { old: notification.committed_chain().unwrap() };
+        assert!(matches!(reverted.without_backfilled(4), Some(Cow::Borrowed(_))));
+    }
+}
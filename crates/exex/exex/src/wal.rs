@@ -0,0 +1,307 @@
+//! Write-ahead log of [`ExExNotification`]s.
+
+use crate::ExExNotification;
+use eyre::WrapErr;
+use reth_primitives::BlockNumber;
+use reth_provider::Chain;
+use reth_tracing::tracing::debug;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The extension of the files that store the notifications.
+const WAL_FILE_EXTENSION: &str = "wal";
+
+/// The version of the encoding of the notifications, the first byte of every WAL file.
+const WAL_FORMAT_VERSION: u8 = 1;
+
+/// The name of the file that stores the finished heights of the `ExEx`'s.
+const FINISHED_HEIGHTS_FILE_NAME: &str = "finished-heights.json";
+
+/// A durable log of the [`ExExNotification`]s sent to the `ExEx`'s.
+///
+/// Every notification is written to its own file in the WAL directory before it's delivered,
+/// named after a monotonically increasing ID and the block range of the notification:
+/// `<ID>_<FIRST BLOCK>_<LAST BLOCK>.wal`. The block range spans both the reverted and the
+/// committed chain of the notification.
+///
+/// The WAL additionally stores the last finished height of each `ExEx`, so that notifications
+/// that were not processed before a restart can be replayed. Notifications whose blocks are all
+/// below the minimum finished height of all `ExEx`'s are removed with [`Wal::finalize`].
+///
+/// The notifications are encoded with bincode, prefixed with the version of the encoding. Trie
+/// updates are not stored, since `ExEx`'s don't receive them for all blocks anyway.
+///
+/// All methods access the file system synchronously, the [`ExExManager`](crate::ExExManager) calls
+/// them on blocking tasks.
+#[derive(Debug)]
+pub struct Wal {
+    /// The directory of the WAL files.
+    directory: PathBuf,
+    /// The block ranges of the stored notifications, by ID.
+    entries: BTreeMap<u64, RangeInclusive<BlockNumber>>,
+    /// The ID of the next notification.
+    next_id: u64,
+    /// The last finished height of each `ExEx`, by `ExEx` ID.
+    finished_heights: BTreeMap<String, BlockNumber>,
+}
+
+impl Wal {
+    /// Opens the WAL in the given directory, creating the directory if it doesn't exist.
+    pub fn open(directory: impl Into<PathBuf>) -> eyre::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)
+            .wrap_err_with(|| format!("failed to create WAL directory {}", directory.display()))?;
+
+        let mut entries = BTreeMap::new();
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(WAL_FILE_EXTENSION) {
+                continue
+            }
+            let (id, range) = parse_file_name(&path)
+                .ok_or_else(|| eyre::eyre!("invalid WAL file name {}", path.display()))?;
+            entries.insert(id, range);
+        }
+        let next_id = entries.last_key_value().map_or(0, |(id, _)| id + 1);
+
+        let finished_heights_path = directory.join(FINISHED_HEIGHTS_FILE_NAME);
+        let finished_heights = if finished_heights_path.exists() {
+            serde_json::from_slice(&fs::read(&finished_heights_path)?)
+                .wrap_err("failed to decode ExEx finished heights")?
+        } else {
+            BTreeMap::new()
+        };
+
+        debug!(target: "exex::wal", directory = %directory.display(), entries = entries.len(), "Opened WAL");
+
+        Ok(Self { directory, entries, next_id, finished_heights })
+    }
+
+    /// Returns the directory of the WAL.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the number of stored notifications.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no notifications are stored.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the notification to the WAL.
+    ///
+    /// The notification is durable once this returns.
+    pub fn commit(&mut self, notification: &ExExNotification) -> eyre::Result<()> {
+        let Some(range) = notification_range(notification) else { return Ok(()) };
+
+        let id = self.next_id;
+        let path = self.file_path(id, &range);
+        let mut bytes = vec![WAL_FORMAT_VERSION];
+        bincode::serialize_into(&mut bytes, &WalNotification::from(notification))?;
+        write_atomic(&path, &bytes)
+            .wrap_err_with(|| format!("failed to write WAL file {}", path.display()))?;
+
+        debug!(target: "exex::wal", id, ?range, "Committed notification to WAL");
+        self.entries.insert(id, range);
+        self.next_id += 1;
+        Ok(())
+    }
+
+    /// Returns all stored notifications with blocks above the given height, in the order they were
+    /// committed.
+    pub fn notifications_above(&self, height: BlockNumber) -> eyre::Result<Vec<ExExNotification>> {
+        self.entries
+            .iter()
+            .filter(|(_, range)| *range.end() > height)
+            .map(|(id, range)| {
+                let path = self.file_path(*id, range);
+                let bytes = fs::read(&path)
+                    .wrap_err_with(|| format!("failed to read WAL file {}", path.display()))?;
+                let Some((&WAL_FORMAT_VERSION, bytes)) = bytes.split_first() else {
+                    eyre::bail!("unsupported encoding of WAL file {}", path.display())
+                };
+                let notification: WalNotification = bincode::deserialize(bytes)
+                    .wrap_err_with(|| format!("failed to decode WAL file {}", path.display()))?;
+                Ok(notification.into())
+            })
+            .collect()
+    }
+
+    /// Returns `true` if notifications with blocks at or below the given height are stored, see
+    /// [`Wal::finalize`].
+    pub fn has_finalized(&self, height: BlockNumber) -> bool {
+        self.entries.values().any(|range| *range.end() <= height)
+    }
+
+    /// Removes all notifications with blocks at or below the given height.
+    pub fn finalize(&mut self, height: BlockNumber) -> eyre::Result<()> {
+        let finalized = self
+            .entries
+            .iter()
+            .filter(|(_, range)| *range.end() <= height)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        for id in finalized {
+            let range = self.entries.remove(&id).expect("entry exists");
+            let path = self.file_path(id, &range);
+            fs::remove_file(&path)
+                .wrap_err_with(|| format!("failed to remove WAL file {}", path.display()))?;
+            debug!(target: "exex::wal", id, ?range, "Removed finalized notification from WAL");
+        }
+        Ok(())
+    }
+
+    /// Returns the last finished height of the `ExEx` with the given ID, if it was recorded.
+    pub fn finished_height(&self, exex_id: &str) -> Option<BlockNumber> {
+        self.finished_heights.get(exex_id).copied()
+    }
+
+    /// Records the finished height of the `ExEx` with the given ID.
+    pub fn set_finished_height(&mut self, exex_id: &str, height: BlockNumber) -> eyre::Result<()> {
+        if self.finished_heights.get(exex_id) == Some(&height) {
+            return Ok(())
+        }
+        self.finished_heights.insert(exex_id.to_string(), height);
+
+        let path = self.directory.join(FINISHED_HEIGHTS_FILE_NAME);
+        write_atomic(&path, &serde_json::to_vec(&self.finished_heights)?)
+            .wrap_err("failed to write ExEx finished heights")
+    }
+
+    fn file_path(&self, id: u64, range: &RangeInclusive<BlockNumber>) -> PathBuf {
+        self.directory.join(format!("{id}_{}_{}.{WAL_FILE_EXTENSION}", range.start(), range.end()))
+    }
+}
+
+/// Returns the range of all blocks in the notification.
+pub(crate) fn notification_range(
+    notification: &ExExNotification,
+) -> Option<RangeInclusive<BlockNumber>> {
+    let chains = [notification.reverted_chain(), notification.committed_chain()];
+    let first = chains.iter().flatten().map(|chain| chain.first().number).min()?;
+    let last = chains.iter().flatten().map(|chain| chain.tip().number).max()?;
+    Some(first..=last)
+}
+
+/// The encoding of an [`ExExNotification`] in the WAL.
+#[derive(Serialize, Deserialize)]
+enum WalNotification {
+    ChainCommitted { new: Chain },
+    ChainReorged { old: Chain, new: Chain },
+    ChainReverted { old: Chain },
+}
+
+impl From<&ExExNotification> for WalNotification {
+    /// Clears the trie updates of all chains.
+    fn from(notification: &ExExNotification) -> Self {
+        let clear = |chain: &Arc<Chain>| {
+            let mut chain = (**chain).clone();
+            chain.clear_trie_updates();
+            chain
+        };
+        match notification {
+            ExExNotification::ChainCommitted { new } => Self::ChainCommitted { new: clear(new) },
+            ExExNotification::ChainReorged { old, new } => {
+                Self::ChainReorged { old: clear(old), new: clear(new) }
+            }
+            ExExNotification::ChainReverted { old } => Self::ChainReverted { old: clear(old) },
+        }
+    }
+}
+
+impl From<WalNotification> for ExExNotification {
+    fn from(notification: WalNotification) -> Self {
+        match notification {
+            WalNotification::ChainCommitted { new } => Self::ChainCommitted { new: Arc::new(new) },
+            WalNotification::ChainReorged { old, new } => {
+                Self::ChainReorged { old: Arc::new(old), new: Arc::new(new) }
+            }
+            WalNotification::ChainReverted { old } => Self::ChainReverted { old: Arc::new(old) },
+        }
+    }
+}
+
+/// Parses the ID and block range from the name of a WAL file.
+fn parse_file_name(path: &Path) -> Option<(u64, RangeInclusive<BlockNumber>)> {
+    let stem = path.file_stem()?.to_str()?;
+    let mut parts = stem.split('_');
+    let id = parts.next()?.parse().ok()?;
+    let first = parts.next()?.parse().ok()?;
+    let last = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None
+    }
+    Some((id, first..=last))
+}
+
+/// Writes the file by writing to a temporary file first and renaming it, so that a crash never
+/// leaves a partially written file behind.
+///
+/// The directory is synced after the rename, so that the file is durable once this returns.
+fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+
+    if let Some(parent) = path.parent() {
+        fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_provider::ExecutionOutcome;
+    use reth_testing_utils::generators::{self, random_block, Rng};
+
+    fn chain(rng: &mut impl Rng, numbers: RangeInclusive<BlockNumber>) -> Arc<Chain> {
+        let blocks = numbers.map(|number| {
+            random_block(rng, number, None, Some(0), None).seal_with_senders().unwrap()
+        });
+        Arc::new(Chain::new(blocks, ExecutionOutcome::default(), None))
+    }
+
+    #[test]
+    fn commit_replay_and_finalize() {
+        let mut rng = generators::rng();
+        let dir = tempfile::tempdir().unwrap();
+
+        let committed = ExExNotification::ChainCommitted { new: chain(&mut rng, 1..=2) };
+        let reorged = ExExNotification::ChainReorged {
+            old: chain(&mut rng, 2..=2),
+            new: chain(&mut rng, 2..=4),
+        };
+
+        let mut wal = Wal::open(dir.path()).unwrap();
+        wal.commit(&committed).unwrap();
+        wal.commit(&reorged).unwrap();
+        wal.set_finished_height("exex", 2).unwrap();
+        assert_eq!(wal.len(), 2);
+
+        // reopen to replay from disk
+        let mut wal = Wal::open(dir.path()).unwrap();
+        assert_eq!(wal.finished_height("exex"), Some(2));
+        assert_eq!(wal.notifications_above(0).unwrap(), vec![committed, reorged.clone()]);
+        assert_eq!(wal.notifications_above(2).unwrap(), vec![reorged.clone()]);
+
+        wal.finalize(2).unwrap();
+        assert_eq!(wal.len(), 1);
+        let wal = Wal::open(dir.path()).unwrap();
+        assert_eq!(wal.notifications_above(0).unwrap(), vec![reorged]);
+    }
+}
@@ -0,0 +1,62 @@
+//! Client of a remote `ExEx` server.
+
+use crate::protocol::{
+    decode, encode, framed, ClientMessage, Notification, ProtocolError, ServerMessage,
+};
+use futures::{SinkExt, StreamExt};
+use reth_primitives::BlockNumber;
+use std::path::Path;
+use tokio::net::UnixStream;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// A connection to a [`RemoteExExServer`](crate::RemoteExExServer).
+///
+/// The client receives the notifications of the node, and should acknowledge the processed blocks
+/// with [`Self::send_finished_height`], so that the node doesn't prune them before.
+#[derive(Debug)]
+pub struct RemoteExExClient {
+    framed: Framed<UnixStream, LengthDelimitedCodec>,
+}
+
+impl RemoteExExClient {
+    /// Connects to the server listening on the given socket.
+    ///
+    /// The client with the given ID receives the blocks above the start height, the last block it
+    /// processed. The server keeps the blocks of a client when it disconnects, so that it can
+    /// resume with the same ID.
+    ///
+    /// Returns once the client is subscribed to notifications.
+    pub async fn connect(
+        path: impl AsRef<Path>,
+        client_id: impl Into<String>,
+        start_height: BlockNumber,
+    ) -> Result<Self, ProtocolError> {
+        let mut framed = framed(UnixStream::connect(path).await?);
+        let hello = ClientMessage::Hello { client_id: client_id.into(), start_height };
+        framed.send(encode(&hello)).await?;
+        match framed.next().await {
+            Some(frame) => match decode(&frame?)? {
+                ServerMessage::Hello => Ok(Self { framed }),
+                ServerMessage::Notification(_) => Err(ProtocolError::UnexpectedMessage),
+            },
+            None => Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+        }
+    }
+
+    /// Receives the next notification.
+    ///
+    /// Returns `None` if the server closed the connection.
+    pub async fn next_notification(&mut self) -> Result<Option<Notification>, ProtocolError> {
+        let Some(frame) = self.framed.next().await else { return Ok(None) };
+        match decode(&frame?)? {
+            ServerMessage::Notification(notification) => Ok(Some(notification)),
+            ServerMessage::Hello => Err(ProtocolError::UnexpectedMessage),
+        }
+    }
+
+    /// Acknowledges that all blocks up to and including the given height were processed.
+    pub async fn send_finished_height(&mut self, height: BlockNumber) -> Result<(), ProtocolError> {
+        self.framed.send(encode(&ClientMessage::FinishedHeight(height))).await?;
+        Ok(())
+    }
+}
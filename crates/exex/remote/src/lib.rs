@@ -0,0 +1,168 @@
+//! Execution extensions served to other processes over IPC.
+//!
+//! The [`RemoteExExServer`] is an `ExEx` that streams the notifications of the node to clients
+//! connected over a Unix socket, so that they can be consumed by processes written in any
+//! language. The [`RemoteExExClient`] is a client for Rust processes.
+//!
+//! # Protocol
+//!
+//! Messages are sent as length-delimited frames with an explicit RLP schema of the notifications,
+//! described in the [`protocol`] module. The client starts with [`ClientMessage::Hello`], with its
+//! ID and the last block it processed, and the server answers with [`ServerMessage::Hello`] once
+//! the client is subscribed. The server then sends the blocks above the start height, executed by
+//! a backfill job up to the node's tip, followed by the live notifications.
+//!
+//! Clients acknowledge the blocks they processed with [`ClientMessage::FinishedHeight`]. The
+//! finished height of the `ExEx` is the lowest height of all clients, including the disconnected
+//! ones, so the node doesn't prune blocks that they still need to resume.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use reth_exex_remote::RemoteExExServer;
+//! # use reth_exex::ExExContext;
+//! # use reth_node_api::FullNodeComponents;
+//! async fn remote_exex<Node: FullNodeComponents>(
+//!     ctx: ExExContext<Node>,
+//! ) -> eyre::Result<impl std::future::Future<Output = eyre::Result<()>>> {
+//!     let server = RemoteExExServer::bind(ctx.config.datadir().data_dir().join("exex.ipc"))?;
+//!     Ok(server.run(ctx))
+//! }
+//! ```
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg(unix)]
+
+mod client;
+pub use client::RemoteExExClient;
+
+pub mod protocol;
+pub use protocol::{ClientMessage, Notification, ProtocolError, ServerMessage, PROTOCOL_VERSION};
+
+mod server;
+pub use server::RemoteExExServer;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_exex::ExExEvent;
+    use reth_exex_test_utils::test_exex_context;
+    use reth_primitives::{Account, Address, Bytecode, Bytes, B256, U256};
+    use reth_provider::{BundleStateInit, Chain, ExecutionOutcome, RevertsInit};
+    use reth_testing_utils::generators::{self, random_block, random_receipt};
+
+    #[tokio::test]
+    async fn serve_notifications() -> eyre::Result<()> {
+        let mut rng = generators::rng();
+        let dir = tempfile::tempdir()?;
+        let (ctx, mut handle) = test_exex_context().await?;
+
+        let server = RemoteExExServer::bind(dir.path().join("exex.ipc"))?;
+        let path = server.path().to_path_buf();
+        tokio::spawn(server.run(ctx));
+        let mut client = RemoteExExClient::connect(path, "indexer", handle.genesis.number).await?;
+        // a new client holds the blocks above its start height
+        assert_eq!(
+            handle.events_rx.recv().await,
+            Some(ExExEvent::FinishedHeight(handle.genesis.number))
+        );
+
+        // a block with transactions, receipts and state changes
+        let block = random_block(&mut rng, 1, Some(handle.genesis.hash()), Some(2), Some(0))
+            .seal_with_senders()
+            .unwrap();
+        let receipts = block
+            .body
+            .iter()
+            .map(|transaction| Some(random_receipt(&mut rng, transaction, Some(1))))
+            .collect::<Vec<_>>();
+        let address = Address::with_last_byte(1);
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]));
+        let code_hash = code.hash_slow();
+        let state = BundleStateInit::from([(
+            address,
+            (
+                Some(Account { nonce: 0, balance: U256::from(10), bytecode_hash: None }),
+                Some(Account { nonce: 1, balance: U256::from(5), bytecode_hash: Some(code_hash) }),
+                [(B256::with_last_byte(1), (U256::ZERO, U256::from(7)))].into(),
+            ),
+        )]);
+        let outcome = ExecutionOutcome::new_init(
+            state,
+            RevertsInit::default(),
+            vec![(code_hash, code.clone())],
+            vec![receipts.clone()].into(),
+            1,
+            vec![],
+        );
+        let chain = Chain::new([block.clone()], outcome, None);
+        handle.send_notification_chain_committed(chain).await?;
+
+        let Some(Notification::ChainCommitted { new }) = client.next_notification().await? else {
+            panic!("expected a chain commit")
+        };
+        let [received] = new.blocks.as_slice() else { panic!("expected a single block") };
+        assert_eq!(received.block, block.block.clone().unseal());
+        assert_eq!(received.senders, block.senders);
+        assert_eq!(
+            received.receipts,
+            receipts.into_iter().map(|receipt| receipt.map(|r| r.with_bloom())).collect::<Vec<_>>()
+        );
+        let [account] = new.state.accounts.as_slice() else { panic!("expected a single account") };
+        assert_eq!(account.address, address);
+        assert_eq!(account.original_info.map(|info| info.balance), Some(U256::from(10)));
+        assert_eq!(account.info.map(|info| (info.nonce, info.code_hash)), Some((1, code_hash)));
+        assert_eq!(account.storage.len(), 1);
+        assert_eq!(account.storage[0].value, U256::from(7));
+        assert_eq!(new.state.contracts.len(), 1);
+        assert_eq!(new.state.contracts[0].code, code.original_bytes());
+
+        // the acknowledgement is reported as the finished height of the ExEx
+        client.send_finished_height(block.number).await?;
+        assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(block.number)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_finished_height_of_disconnected_clients() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (ctx, mut handle) = test_exex_context().await?;
+
+        let server = RemoteExExServer::bind(dir.path().join("exex.ipc"))?;
+        let path = server.path().to_path_buf();
+        tokio::spawn(server.run(ctx));
+
+        let mut first = RemoteExExClient::connect(&path, "first", 0).await?;
+        assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(0)));
+        first.send_finished_height(2).await?;
+        assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(2)));
+
+        // a new client without any acknowledgement holds the blocks above its start height
+        let mut second = RemoteExExClient::connect(&path, "second", 1).await?;
+        assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(1)));
+        second.send_finished_height(3).await?;
+        assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(2)));
+
+        // the disconnected client still holds its blocks until it resumes
+        drop(first);
+        second.send_finished_height(4).await?;
+        let first = RemoteExExClient::connect(&path, "first", 3).await?;
+        assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(3)));
+        drop(first);
+
+        // the finished heights are restored after a restart
+        let server = RemoteExExServer::bind(&path)?;
+        let (ctx, mut handle) = test_exex_context().await?;
+        tokio::spawn(server.run(ctx));
+        assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(3)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,580 @@
+//! The wire protocol between the remote `ExEx` server and its clients.
+//!
+//! # Frames
+//!
+//! Messages are sent as frames of a 4 byte big-endian length, followed by the 2 byte big-endian
+//! [`PROTOCOL_VERSION`], the 1 byte type of the message and its payload, an
+//! [RLP](https://ethereum.org/en/developers/docs/data-structures-and-encoding/rlp/) list.
+//!
+//! | Sender | Type   | Message                                 | Payload                          |
+//! |--------|--------|-----------------------------------------|----------------------------------|
+//! | client | `0x00` | [`ClientMessage::Hello`]                | `[client_id, start_height]`      |
+//! | client | `0x01` | [`ClientMessage::FinishedHeight`]       | `[height]`                       |
+//! | server | `0x00` | [`ServerMessage::Hello`]                | `[]`                             |
+//! | server | `0x01` | [`Notification::ChainCommitted`]        | `[new: Chain]`                   |
+//! | server | `0x02` | [`Notification::ChainReorged`]          | `[old: Chain, new: Chain]`       |
+//! | server | `0x03` | [`Notification::ChainReverted`]         | `[old: Chain]`                   |
+//!
+//! The client ID is a UTF-8 string, heights are block numbers.
+//!
+//! # Chains
+//!
+//! ```text
+//! Chain         = [blocks: [ChainBlock, ...], state: StateChanges]
+//! ChainBlock    = [block, senders: [address, ...], receipts: [receipt | [], ...]]
+//! StateChanges  = [accounts: [AccountChange, ...], contracts: [[code_hash, code], ...]]
+//! AccountChange = [address, original_info: AccountInfo | [], info: AccountInfo | [],
+//!                  storage_wiped, storage: [[slot, original_value, value], ...]]
+//! AccountInfo   = [nonce, balance, code_hash]
+//! ```
+//!
+//! - `block` is the consensus encoding of the block, `[header, transactions, ommers, withdrawals?,
+//!   requests?]`, with the transactions in their network encoding.
+//! - `receipts` are in the network encoding of the `eth/66` protocol, a list for legacy receipts
+//!   and a string of the type followed by the list for typed receipts. Pruned receipts are an empty
+//!   list.
+//! - `state` holds the state changes of all blocks of the chain: the values before the first block
+//!   and after the last block. An account that doesn't exist is an empty list. `storage_wiped` is
+//!   set if the account was destroyed, so that its storage slots before the chain are gone.
+//! - Accounts are sorted by address, storage slots by slot and contracts by code hash.
+
+use alloy_rlp::{
+    BufMut, Decodable, Encodable, Header, RlpDecodable, RlpEncodable, EMPTY_LIST_CODE,
+};
+use bytes::{Bytes, BytesMut};
+use reth_execution_types::ExecutionOutcome;
+use reth_exex::ExExNotification;
+use reth_primitives::{
+    revm_primitives, Address, Block, BlockNumber, Receipt, ReceiptWithBloom, B256, U256,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// The version of the protocol, sent with every message.
+///
+/// It's bumped whenever the encoding of a message changes.
+pub const PROTOCOL_VERSION: u16 = 2;
+
+/// The maximum length of a frame, 1 GiB.
+///
+/// A single notification can contain many blocks and their state changes.
+pub const MAX_FRAME_LENGTH: usize = 1024 * 1024 * 1024;
+
+/// Messages sent by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    /// The answer to [`ClientMessage::Hello`], sent once the client is subscribed to
+    /// notifications.
+    Hello,
+    /// A notification of the node.
+    Notification(Notification),
+}
+
+/// Messages sent by the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientMessage {
+    /// The first message on a connection.
+    Hello {
+        /// Identifies the client across connections.
+        client_id: String,
+        /// The last block the client processed. The client receives the blocks above it.
+        start_height: BlockNumber,
+    },
+    /// The highest block processed by the client, see
+    /// [`ExExEvent::FinishedHeight`](reth_exex::ExExEvent::FinishedHeight).
+    FinishedHeight(BlockNumber),
+}
+
+/// A notification of the node, see [`ExExNotification`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    /// Chain got committed without a reorg, and only the new chain is returned.
+    ChainCommitted {
+        /// The new chain after commit.
+        new: Chain,
+    },
+    /// Chain got reorged, and both the old and the new chains are returned.
+    ChainReorged {
+        /// The old chain before reorg.
+        old: Chain,
+        /// The new chain after reorg.
+        new: Chain,
+    },
+    /// Chain got reverted, and only the old chain is returned.
+    ChainReverted {
+        /// The old chain before reversion.
+        old: Chain,
+    },
+}
+
+impl From<&ExExNotification> for Notification {
+    fn from(notification: &ExExNotification) -> Self {
+        match notification {
+            ExExNotification::ChainCommitted { new } => {
+                Self::ChainCommitted { new: new.as_ref().into() }
+            }
+            ExExNotification::ChainReorged { old, new } => {
+                Self::ChainReorged { old: old.as_ref().into(), new: new.as_ref().into() }
+            }
+            ExExNotification::ChainReverted { old } => {
+                Self::ChainReverted { old: old.as_ref().into() }
+            }
+        }
+    }
+}
+
+/// A chain of blocks and their state changes.
+#[derive(Debug, Clone, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+pub struct Chain {
+    /// The blocks of the chain, in ascending order.
+    pub blocks: Vec<ChainBlock>,
+    /// The state changes of all blocks.
+    pub state: StateChanges,
+}
+
+impl From<&reth_execution_types::Chain> for Chain {
+    fn from(chain: &reth_execution_types::Chain) -> Self {
+        let outcome = chain.execution_outcome();
+        let blocks = chain
+            .blocks_iter()
+            .map(|block| ChainBlock {
+                block: block.block.clone().unseal(),
+                senders: block.senders.clone(),
+                receipts: outcome
+                    .receipts_by_block(block.number)
+                    .iter()
+                    .map(|receipt| receipt.clone().map(Receipt::with_bloom))
+                    .collect(),
+            })
+            .collect();
+        Self { blocks, state: outcome.into() }
+    }
+}
+
+/// A block of a [`Chain`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ChainBlock {
+    /// The block.
+    pub block: Block,
+    /// The senders of the transactions.
+    pub senders: Vec<Address>,
+    /// The receipts of the transactions, `None` if pruned.
+    pub receipts: Vec<Option<ReceiptWithBloom>>,
+}
+
+impl ChainBlock {
+    fn receipts_length(&self) -> usize {
+        self.receipts.iter().map(optional_length).sum()
+    }
+
+    fn payload_length(&self) -> usize {
+        let receipts_length = self.receipts_length();
+        self.block.length() +
+            self.senders.length() +
+            Header { list: true, payload_length: receipts_length }.length() +
+            receipts_length
+    }
+}
+
+impl Encodable for ChainBlock {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.block.encode(out);
+        self.senders.encode(out);
+        Header { list: true, payload_length: self.receipts_length() }.encode(out);
+        for receipt in &self.receipts {
+            encode_optional(receipt, out);
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        Header { list: true, payload_length }.length() + payload_length
+    }
+}
+
+impl Decodable for ChainBlock {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let mut payload = Header::decode_bytes(buf, true)?;
+        let block = Block::decode(&mut payload)?;
+        let senders = Vec::decode(&mut payload)?;
+        let mut receipts_payload = Header::decode_bytes(&mut payload, true)?;
+        let mut receipts = Vec::new();
+        while !receipts_payload.is_empty() {
+            receipts.push(decode_optional(&mut receipts_payload)?);
+        }
+        ensure_consumed(payload)?;
+        Ok(Self { block, senders, receipts })
+    }
+}
+
+/// The state changes of a [`Chain`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+pub struct StateChanges {
+    /// The changed accounts, sorted by address.
+    pub accounts: Vec<AccountChange>,
+    /// The deployed contracts, sorted by code hash.
+    pub contracts: Vec<Contract>,
+}
+
+impl From<&ExecutionOutcome> for StateChanges {
+    fn from(outcome: &ExecutionOutcome) -> Self {
+        let mut accounts = outcome
+            .bundle
+            .state
+            .iter()
+            .map(|(address, account)| {
+                let mut storage = account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.is_changed())
+                    .map(|(slot, value)| StorageChange {
+                        slot: *slot,
+                        original_value: value.previous_or_original_value,
+                        value: value.present_value,
+                    })
+                    .collect::<Vec<_>>();
+                storage.sort_unstable_by_key(|change| change.slot);
+
+                AccountChange {
+                    address: *address,
+                    original_info: account.original_info.as_ref().map(Into::into),
+                    info: account.info.as_ref().map(Into::into),
+                    storage_wiped: account.was_destroyed(),
+                    storage,
+                }
+            })
+            .collect::<Vec<_>>();
+        accounts.sort_unstable_by_key(|account| account.address);
+
+        let mut contracts = outcome
+            .bundle
+            .contracts
+            .iter()
+            .map(|(code_hash, code)| Contract {
+                code_hash: *code_hash,
+                code: code.original_bytes(),
+            })
+            .collect::<Vec<_>>();
+        contracts.sort_unstable_by_key(|contract| contract.code_hash);
+
+        Self { accounts, contracts }
+    }
+}
+
+/// The change of an account in a [`Chain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountChange {
+    /// The address of the account.
+    pub address: Address,
+    /// The account before the chain, `None` if it didn't exist.
+    pub original_info: Option<AccountInfo>,
+    /// The account after the chain, `None` if it was destroyed.
+    pub info: Option<AccountInfo>,
+    /// Whether the storage of the account before the chain was wiped.
+    pub storage_wiped: bool,
+    /// The changed storage slots, sorted by slot.
+    pub storage: Vec<StorageChange>,
+}
+
+impl AccountChange {
+    fn payload_length(&self) -> usize {
+        self.address.length() +
+            optional_length(&self.original_info) +
+            optional_length(&self.info) +
+            self.storage_wiped.length() +
+            self.storage.length()
+    }
+}
+
+impl Encodable for AccountChange {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.address.encode(out);
+        encode_optional(&self.original_info, out);
+        encode_optional(&self.info, out);
+        self.storage_wiped.encode(out);
+        self.storage.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        Header { list: true, payload_length }.length() + payload_length
+    }
+}
+
+impl Decodable for AccountChange {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let mut payload = Header::decode_bytes(buf, true)?;
+        let this = Self {
+            address: Address::decode(&mut payload)?,
+            original_info: decode_optional(&mut payload)?,
+            info: decode_optional(&mut payload)?,
+            storage_wiped: bool::decode(&mut payload)?,
+            storage: Vec::decode(&mut payload)?,
+        };
+        ensure_consumed(payload)?;
+        Ok(this)
+    }
+}
+
+/// The state of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+pub struct AccountInfo {
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The hash of the code of the account.
+    pub code_hash: B256,
+}
+
+impl From<&revm_primitives::AccountInfo> for AccountInfo {
+    fn from(info: &revm_primitives::AccountInfo) -> Self {
+        Self { nonce: info.nonce, balance: info.balance, code_hash: info.code_hash }
+    }
+}
+
+/// The change of a storage slot in a [`Chain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+pub struct StorageChange {
+    /// The storage slot.
+    pub slot: U256,
+    /// The value before the chain.
+    pub original_value: U256,
+    /// The value after the chain.
+    pub value: U256,
+}
+
+/// A contract deployed in a [`Chain`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+pub struct Contract {
+    /// The hash of the code.
+    pub code_hash: B256,
+    /// The code.
+    pub code: reth_primitives::Bytes,
+}
+
+/// Errors of the remote `ExEx` protocol.
+#[derive(Debug, thiserror::Error)]
+pub enum ProtocolError {
+    /// The connection failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A message could not be decoded.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The peer uses a different protocol version.
+    #[error("unsupported protocol version {0}, expected {PROTOCOL_VERSION}")]
+    UnsupportedVersion(u16),
+    /// The frame is too short to contain the protocol version and the message type.
+    #[error("frame without protocol version or message type")]
+    MissingHeader,
+    /// The message type is not known.
+    #[error("unknown message type {0}")]
+    UnknownMessage(u8),
+    /// The peer sent a message that is not valid at this point of the connection.
+    #[error("unexpected message")]
+    UnexpectedMessage,
+}
+
+/// A message of the protocol, see the [module documentation](self).
+pub trait Message: Sized {
+    /// Returns the type of the message.
+    fn message_type(&self) -> u8;
+
+    /// Encodes the payload of the message.
+    fn encode_payload(&self, out: &mut dyn BufMut);
+
+    /// Decodes the payload of a message of the given type.
+    fn decode_payload(message_type: u8, buf: &mut &[u8]) -> Result<Self, ProtocolError>;
+}
+
+impl Message for ServerMessage {
+    fn message_type(&self) -> u8 {
+        match self {
+            Self::Hello => 0x00,
+            Self::Notification(Notification::ChainCommitted { .. }) => 0x01,
+            Self::Notification(Notification::ChainReorged { .. }) => 0x02,
+            Self::Notification(Notification::ChainReverted { .. }) => 0x03,
+        }
+    }
+
+    fn encode_payload(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Hello => encode_fields(&[], out),
+            Self::Notification(Notification::ChainCommitted { new }) => encode_fields(&[new], out),
+            Self::Notification(Notification::ChainReorged { old, new }) => {
+                encode_fields(&[old, new], out)
+            }
+            Self::Notification(Notification::ChainReverted { old }) => encode_fields(&[old], out),
+        }
+    }
+
+    fn decode_payload(message_type: u8, buf: &mut &[u8]) -> Result<Self, ProtocolError> {
+        let mut payload = Header::decode_bytes(buf, true)?;
+        let message = match message_type {
+            0x00 => Self::Hello,
+            0x01 => Self::Notification(Notification::ChainCommitted {
+                new: Chain::decode(&mut payload)?,
+            }),
+            0x02 => Self::Notification(Notification::ChainReorged {
+                old: Chain::decode(&mut payload)?,
+                new: Chain::decode(&mut payload)?,
+            }),
+            0x03 => Self::Notification(Notification::ChainReverted {
+                old: Chain::decode(&mut payload)?,
+            }),
+            _ => return Err(ProtocolError::UnknownMessage(message_type)),
+        };
+        ensure_consumed(payload)?;
+        Ok(message)
+    }
+}
+
+impl Message for ClientMessage {
+    fn message_type(&self) -> u8 {
+        match self {
+            Self::Hello { .. } => 0x00,
+            Self::FinishedHeight(_) => 0x01,
+        }
+    }
+
+    fn encode_payload(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Hello { client_id, start_height } => {
+                encode_fields(&[client_id, start_height], out)
+            }
+            Self::FinishedHeight(height) => encode_fields(&[height], out),
+        }
+    }
+
+    fn decode_payload(message_type: u8, buf: &mut &[u8]) -> Result<Self, ProtocolError> {
+        let mut payload = Header::decode_bytes(buf, true)?;
+        let message = match message_type {
+            0x00 => Self::Hello {
+                client_id: String::decode(&mut payload)?,
+                start_height: BlockNumber::decode(&mut payload)?,
+            },
+            0x01 => Self::FinishedHeight(BlockNumber::decode(&mut payload)?),
+            _ => return Err(ProtocolError::UnknownMessage(message_type)),
+        };
+        ensure_consumed(payload)?;
+        Ok(message)
+    }
+}
+
+/// Encodes the message into a frame: the big-endian protocol version, the message type and the
+/// payload of the message.
+pub fn encode<T: Message>(message: &T) -> Bytes {
+    let mut frame = BytesMut::new();
+    frame.put_u16(PROTOCOL_VERSION);
+    frame.put_u8(message.message_type());
+    message.encode_payload(&mut frame);
+    frame.freeze()
+}
+
+/// Decodes a message from a frame, see [`encode`].
+pub fn decode<T: Message>(frame: &[u8]) -> Result<T, ProtocolError> {
+    let Some(([version @ .., message_type], mut payload)) = frame.split_first_chunk::<3>() else {
+        return Err(ProtocolError::MissingHeader)
+    };
+    let version = u16::from_be_bytes(*version);
+    if version != PROTOCOL_VERSION {
+        return Err(ProtocolError::UnsupportedVersion(version))
+    }
+    let message = T::decode_payload(*message_type, &mut payload)?;
+    ensure_consumed(payload)?;
+    Ok(message)
+}
+
+/// Wraps the connection into a transport of length-delimited frames.
+pub(crate) fn framed<T: AsyncRead + AsyncWrite>(io: T) -> Framed<T, LengthDelimitedCodec> {
+    LengthDelimitedCodec::builder().max_frame_length(MAX_FRAME_LENGTH).new_framed(io)
+}
+
+/// Encodes the fields as an RLP list.
+fn encode_fields(fields: &[&dyn Encodable], out: &mut dyn BufMut) {
+    let payload_length = fields.iter().map(|field| field.length()).sum();
+    Header { list: true, payload_length }.encode(out);
+    for field in fields {
+        field.encode(out);
+    }
+}
+
+/// Encodes the value, or an empty list if there is none.
+fn encode_optional<T: Encodable>(value: &Option<T>, out: &mut dyn BufMut) {
+    match value {
+        Some(value) => value.encode(out),
+        None => out.put_u8(EMPTY_LIST_CODE),
+    }
+}
+
+/// Returns the length of the encoding of [`encode_optional`].
+fn optional_length<T: Encodable>(value: &Option<T>) -> usize {
+    value.as_ref().map_or(1, Encodable::length)
+}
+
+/// Decodes a value encoded by [`encode_optional`].
+fn decode_optional<T: Decodable>(buf: &mut &[u8]) -> alloy_rlp::Result<Option<T>> {
+    if let Some((&EMPTY_LIST_CODE, rest)) = buf.split_first() {
+        *buf = rest;
+        return Ok(None)
+    }
+    T::decode(buf).map(Some)
+}
+
+/// Returns an error if the payload has trailing bytes.
+const fn ensure_consumed(payload: &[u8]) -> alloy_rlp::Result<()> {
+    if payload.is_empty() {
+        Ok(())
+    } else {
+        Err(alloy_rlp::Error::UnexpectedLength)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let message = ClientMessage::Hello { client_id: "indexer".to_string(), start_height: 42 };
+        let frame = encode(&message);
+        assert_eq!(&frame[..3], &[0x00, 0x02, 0x00]);
+        assert_eq!(decode::<ClientMessage>(&frame).unwrap(), message);
+    }
+
+    #[test]
+    fn roundtrip_optional_fields() {
+        let account = AccountChange {
+            address: Address::with_last_byte(1),
+            original_info: None,
+            info: Some(AccountInfo { nonce: 1, balance: U256::from(2), code_hash: B256::ZERO }),
+            storage_wiped: true,
+            storage: vec![StorageChange {
+                slot: U256::from(3),
+                original_value: U256::ZERO,
+                value: U256::from(4),
+            }],
+        };
+        let encoded = alloy_rlp::encode(&account);
+        assert_eq!(encoded.len(), account.length());
+        assert_eq!(AccountChange::decode(&mut encoded.as_slice()).unwrap(), account);
+
+        let block = ChainBlock { receipts: vec![None], ..Default::default() };
+        let encoded = alloy_rlp::encode(&block);
+        assert_eq!(encoded.len(), block.length());
+        assert_eq!(ChainBlock::decode(&mut encoded.as_slice()).unwrap(), block);
+    }
+
+    #[test]
+    fn reject_other_version() {
+        let mut frame = encode(&ClientMessage::FinishedHeight(42)).to_vec();
+        frame[..2].copy_from_slice(&(PROTOCOL_VERSION + 1).to_be_bytes());
+        assert!(matches!(
+            decode::<ClientMessage>(&frame),
+            Err(ProtocolError::UnsupportedVersion(version)) if version == PROTOCOL_VERSION + 1
+        ));
+    }
+}
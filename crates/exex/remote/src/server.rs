@@ -0,0 +1,246 @@
+//! The `ExEx` that serves notifications to other processes.
+
+use crate::protocol::{
+    decode, encode, framed, ClientMessage, Notification, ProtocolError, ServerMessage,
+};
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use reth_exex::{BackfillJobFactory, ExExBackfill, ExExContext, ExExEvent, ExExNotification};
+use reth_node_api::FullNodeComponents;
+use reth_primitives::BlockNumber;
+use reth_tracing::tracing::{debug, warn};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    net::{UnixListener, UnixStream},
+    sync::{broadcast, mpsc},
+};
+
+/// The number of notifications buffered for each client.
+///
+/// Clients that fall further behind are disconnected, and can resume from their last processed
+/// block.
+const NOTIFICATION_BUFFER: usize = 64;
+
+/// A notification of the node and its frame, sent to all connected clients.
+#[derive(Debug)]
+struct EncodedNotification {
+    notification: ExExNotification,
+    frame: Bytes,
+}
+
+/// The height processed by a client, reported by its connection.
+#[derive(Debug)]
+struct ClientHeight {
+    client_id: String,
+    height: BlockNumber,
+}
+
+/// An `ExEx` that streams the notifications of the node to clients connected over a Unix socket,
+/// see [`RemoteExExClient`](crate::RemoteExExClient).
+///
+/// Every notification is encoded once and sent to all connected clients. A client that connects
+/// with a start height below the node's tip first receives the blocks above it, executed by a
+/// backfill job.
+///
+/// The finished height of the `ExEx` is the lowest height of all clients that ever connected,
+/// including the disconnected ones, so the node doesn't prune blocks they didn't process yet. The
+/// heights are persisted next to the socket in a `.clients.json` file, which can be edited while
+/// the node is stopped to remove a client.
+#[derive(Debug)]
+pub struct RemoteExExServer {
+    listener: UnixListener,
+    path: PathBuf,
+    /// The finished height of each client.
+    finished_heights: BTreeMap<String, BlockNumber>,
+}
+
+impl RemoteExExServer {
+    /// Listens on the Unix socket at the given path, replacing a stale socket of a previous run.
+    ///
+    /// The finished heights of the clients of a previous run are restored.
+    pub fn bind(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        let clients_path = clients_path(&path);
+        let finished_heights = if clients_path.exists() {
+            serde_json::from_slice(&std::fs::read(&clients_path)?)?
+        } else {
+            BTreeMap::new()
+        };
+        let listener = UnixListener::bind(&path)?;
+        Ok(Self { listener, path, finished_heights })
+    }
+
+    /// Returns the path of the socket.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the lowest finished height of the clients.
+    fn finished_height(&self) -> Option<BlockNumber> {
+        self.finished_heights.values().min().copied()
+    }
+
+    /// Writes the finished heights of the clients next to the socket.
+    async fn persist_finished_heights(&self) -> io::Result<()> {
+        let path = clients_path(&self.path);
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_vec(&self.finished_heights)?).await?;
+        tokio::fs::rename(&tmp_path, &path).await
+    }
+
+    /// Serves the notifications of the `ExEx` context to the connected clients.
+    pub async fn run<Node: FullNodeComponents>(
+        mut self,
+        mut ctx: ExExContext<Node>,
+    ) -> eyre::Result<()> {
+        let backfill: Arc<dyn ExExBackfill> =
+            Arc::new(BackfillJobFactory::new_from_components(ctx.components.clone()));
+        let (notifications_tx, _) =
+            broadcast::channel::<Arc<EncodedNotification>>(NOTIFICATION_BUFFER);
+        let (heights_tx, mut heights_rx) = mpsc::unbounded_channel();
+
+        // the clients of a previous run still need their blocks
+        let mut reported_height = self.finished_height();
+        if let Some(height) = reported_height {
+            ctx.events.send(ExExEvent::FinishedHeight(height))?;
+        }
+        let mut next_connection = 0;
+
+        loop {
+            tokio::select! {
+                accepted = self.listener.accept() => {
+                    let (stream, _) = accepted?;
+                    let connection = next_connection;
+                    next_connection += 1;
+
+                    debug!(target: "exex::remote", connection, "Client connected");
+                    tokio::spawn(serve_client(
+                        connection,
+                        stream,
+                        notifications_tx.clone(),
+                        Arc::clone(&backfill),
+                        heights_tx.clone(),
+                    ));
+                }
+                notification = ctx.notifications.recv() => {
+                    let Some(notification) = notification else { return Ok(()) };
+                    let frame = encode(&ServerMessage::Notification((&notification).into()));
+                    // there may be no connected clients
+                    let _ = notifications_tx.send(Arc::new(EncodedNotification { notification, frame }));
+                }
+                Some(ClientHeight { client_id, height }) = heights_rx.recv() => {
+                    self.finished_heights.insert(client_id, height);
+                    self.persist_finished_heights().await?;
+
+                    let finished_height = self.finished_height();
+                    if let Some(height) = finished_height.filter(|_| finished_height != reported_height) {
+                        ctx.events.send(ExExEvent::FinishedHeight(height))?;
+                        reported_height = finished_height;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the path of the file with the finished heights of the clients of the socket.
+fn clients_path(path: &Path) -> PathBuf {
+    path.with_extension("clients.json")
+}
+
+/// Serves a client connection until it disconnects.
+async fn serve_client(
+    connection: u64,
+    stream: UnixStream,
+    notifications: broadcast::Sender<Arc<EncodedNotification>>,
+    backfill: Arc<dyn ExExBackfill>,
+    heights: mpsc::UnboundedSender<ClientHeight>,
+) {
+    if let Err(err) = try_serve_client(stream, notifications, backfill, heights).await {
+        warn!(target: "exex::remote", connection, %err, "Client connection failed");
+    }
+    debug!(target: "exex::remote", connection, "Client disconnected");
+}
+
+/// Subscribes the client to the notifications, backfills the blocks above its start height and
+/// forwards its acknowledgements.
+async fn try_serve_client(
+    stream: UnixStream,
+    notifications: broadcast::Sender<Arc<EncodedNotification>>,
+    backfill: Arc<dyn ExExBackfill>,
+    heights: mpsc::UnboundedSender<ClientHeight>,
+) -> eyre::Result<()> {
+    let mut framed = framed(stream);
+    let Some(frame) = framed.next().await else { return Ok(()) };
+    let ClientMessage::Hello { client_id, start_height } = decode(&frame?)? else {
+        return Err(ProtocolError::UnexpectedMessage.into())
+    };
+    debug!(target: "exex::remote", %client_id, %start_height, "Client subscribed");
+
+    // subscribe before the backfill reads the node's tip, so that no block is missed in between
+    let mut notifications = notifications.subscribe();
+    let _ = heights.send(ClientHeight { client_id: client_id.clone(), height: start_height });
+    framed.send(encode(&ServerMessage::Hello)).await?;
+
+    let mut backfilled_height = Some(start_height);
+    let tip = backfill.best_block_number()?;
+    if tip > start_height {
+        let mut chains = backfill.backfill_range(start_height + 1..=tip);
+        while let Some(chain) = chains.next().await {
+            let chain = chain?;
+            backfilled_height = Some(chain.tip().number);
+            let notification = Notification::ChainCommitted { new: (&chain).into() };
+            framed.send(encode(&ServerMessage::Notification(notification))).await?;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            notification = notifications.recv() => {
+                let notification = match notification {
+                    Ok(notification) => notification,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        eyre::bail!("client fell behind by {skipped} notifications")
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                };
+                let frame = match backfilled_height {
+                    Some(height) => match notification.notification.without_backfilled(height) {
+                        // the client is live, later commits of blocks at or below the backfilled
+                        // height follow a reorg or revert and must be sent
+                        Some(remaining) => {
+                            backfilled_height = None;
+                            match remaining {
+                                Cow::Borrowed(_) => notification.frame.clone(),
+                                Cow::Owned(remaining) => encode(&ServerMessage::Notification(
+                                    (&remaining).into(),
+                                )),
+                            }
+                        }
+                        None => continue,
+                    },
+                    None => notification.frame.clone(),
+                };
+                framed.send(frame).await?;
+            }
+            frame = framed.next() => {
+                let Some(frame) = frame else { return Ok(()) };
+                match decode(&frame?)? {
+                    ClientMessage::FinishedHeight(height) => {
+                        let _ = heights.send(ClientHeight { client_id: client_id.clone(), height });
+                    }
+                    ClientMessage::Hello { .. } => return Err(ProtocolError::UnexpectedMessage.into()),
+                }
+            }
+        }
+    }
+}
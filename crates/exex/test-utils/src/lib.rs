@@ -283,6 +283,7 @@ pub async fn test_exex_context_with_chain_spec(
         reth_config: reth_config::Config::default(),
         events: events_tx,
         notifications: notifications_rx,
+        backfill: Default::default(),
         components,
     };
 
@@ -374,6 +374,24 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Sets the advertised udp port
+    ///
+    /// This will update our [`NodeRecord`]'s udp port, for example after the port of the socket
+    /// was mapped to a different external port on the gateway.
+    pub fn set_udp_port(&self, port: u16) {
+        let cmd = Discv4Command::SetUdpPort(port);
+        self.send_to_service(cmd);
+    }
+
+    /// Sets the external ip address
+    ///
+    /// This will update our [`NodeRecord`]'s ip address, for example after a port mapping on the
+    /// gateway reported a new external address.
+    pub fn set_external_ip_addr(&self, ip: IpAddr) {
+        let cmd = Discv4Command::SetExternalIp(ip);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the pair in the EIP-868 [`Enr`] of the node.
     ///
     /// If the key already exists, this will update it.
@@ -1649,6 +1667,19 @@ impl Discv4Service {
                             let _ = self.local_eip_868_enr.set_tcp6(port, &self.secret_key);
                         }
                     }
+                    Discv4Command::SetUdpPort(port) => {
+                        debug!(target: "discv4", %port, "Update udp port");
+                        self.local_node_record.udp_port = port;
+                        if self.local_node_record.address.is_ipv4() {
+                            let _ = self.local_eip_868_enr.set_udp4(port, &self.secret_key);
+                        } else {
+                            let _ = self.local_eip_868_enr.set_udp6(port, &self.secret_key);
+                        }
+                        *self.shared_node_record.lock() = self.local_node_record;
+                    }
+                    Discv4Command::SetExternalIp(ip) => {
+                        self.set_external_ip_addr(ip);
+                    }
 
                     Discv4Command::Terminated => {
                         // terminate the service
@@ -1930,6 +1961,8 @@ impl Default for ReceiveCache {
 enum Discv4Command {
     Add(NodeRecord),
     SetTcpPort(u16),
+    SetUdpPort(u16),
+    SetExternalIp(IpAddr),
     SetEIP868RLPPair { key: Vec<u8>, rlp: Bytes },
     Ban(PeerId, IpAddr),
     BanPeer(PeerId),
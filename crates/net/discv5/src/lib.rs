@@ -122,6 +122,20 @@ impl Discv5 {
         self.set_eip868_in_local_enr(key, buf.into())
     }
 
+    /// Sets the external socket of the node in the local [`Enr`], i.e. the `ip` and either the
+    /// `tcp` or the `udp` port, depending on `is_tcp`.
+    ///
+    /// This is used to advertise an address that was mapped on the gateway of the node.
+    pub fn set_external_socket(&self, socket: SocketAddr, is_tcp: bool) {
+        if self.discv5.update_local_enr_socket(socket, is_tcp) {
+            debug!(target: "discv5",
+                %socket,
+                is_tcp,
+                "updated external socket in local enr"
+            );
+        }
+    }
+
     /// Adds the peer and id to the ban list.
     ///
     /// This will prevent any future inclusion in the table
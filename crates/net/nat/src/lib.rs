@@ -1,4 +1,7 @@
-//! Helpers for resolving the external IP.
+//! Helpers for resolving the external IP and mapping ports on the gateway.
+//!
+//! Ports can be mapped via `UPnP`, NAT-PMP or PCP with the [`PortMappingService`], which renews
+//! the mappings before their lease expires and removes them on shutdown.
 //!
 //! ## Feature Flags
 //!
@@ -22,6 +25,19 @@ use std::{
     time::Duration,
 };
 
+mod natpmp;
+mod pcp;
+mod port_mapping;
+mod upnp;
+
+pub use natpmp::{NatPmpGateway, NATPMP_PORT};
+pub use pcp::PcpGateway;
+pub use port_mapping::{
+    MappedPort, PortMapping, PortMappingConfig, PortMappingError, PortMappingEvent,
+    PortMappingHandle, PortMappingProtocol, PortMappingService, DEFAULT_PORT_MAPPING_LEASE,
+};
+pub use upnp::UpnpGateway;
+
 #[cfg(feature = "serde")]
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 
@@ -38,8 +54,12 @@ pub enum NatResolver {
     /// Resolve with any available resolver.
     #[default]
     Any,
-    /// Resolve external IP via `UPnP`.
+    /// Resolve external IP and map ports via `UPnP`.
     Upnp,
+    /// Resolve external IP and map ports via NAT-PMP.
+    NatPmp,
+    /// Map ports via PCP, the external IP is reported by the gateway with the mapping.
+    Pcp,
     /// Resolve external IP via a network request.
     PublicIp,
     /// Use the given [`IpAddr`]
@@ -53,6 +73,11 @@ impl NatResolver {
     pub async fn external_addr(self) -> Option<IpAddr> {
         external_addr_with(self).await
     }
+
+    /// Returns `true` if the resolver maps ports on the gateway, see [`PortMappingService`].
+    pub const fn supports_port_mapping(&self) -> bool {
+        matches!(self, Self::Upnp | Self::NatPmp | Self::Pcp)
+    }
 }
 
 impl fmt::Display for NatResolver {
@@ -60,6 +85,8 @@ impl fmt::Display for NatResolver {
         match self {
             Self::Any => f.write_str("any"),
             Self::Upnp => f.write_str("upnp"),
+            Self::NatPmp => f.write_str("natpmp"),
+            Self::Pcp => f.write_str("pcp"),
             Self::PublicIp => f.write_str("publicip"),
             Self::ExternalIp(ip) => write!(f, "extip:{ip}"),
             Self::None => f.write_str("none"),
@@ -85,6 +112,8 @@ impl FromStr for NatResolver {
         let r = match s {
             "any" => Self::Any,
             "upnp" => Self::Upnp,
+            "natpmp" | "nat-pmp" => Self::NatPmp,
+            "pcp" => Self::Pcp,
             "none" => Self::None,
             "publicip" | "public-ip" => Self::PublicIp,
             s => {
@@ -179,7 +208,21 @@ pub async fn external_ip() -> Option<IpAddr> {
 /// Given a [`NatResolver`] attempts to produce an IP address (best effort).
 pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     match resolver {
-        NatResolver::Any | NatResolver::Upnp | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Any | NatResolver::PublicIp | NatResolver::Pcp => resolve_external_ip().await,
+        NatResolver::Upnp => match UpnpGateway::discover().await {
+            Ok(gateway) => match gateway.external_ip().await {
+                Ok(ip) => Some(ip),
+                Err(_) => resolve_external_ip().await,
+            },
+            Err(_) => resolve_external_ip().await,
+        },
+        NatResolver::NatPmp => match port_mapping::default_gateway() {
+            Some(gateway) => match NatPmpGateway::new(gateway).external_ip().await {
+                Ok(ip) => Some(IpAddr::V4(ip)),
+                Err(_) => resolve_external_ip().await,
+            },
+            None => resolve_external_ip().await,
+        },
         NatResolver::ExternalIp(ip) => Some(ip),
         NatResolver::None => None,
     }
@@ -236,4 +279,20 @@ mod tests {
         assert_eq!(ip, s.parse().unwrap());
         assert_eq!(ip.to_string().as_str(), s);
     }
+
+    #[test]
+    fn test_port_mapping_resolvers() {
+        for (s, resolver) in [
+            ("upnp", NatResolver::Upnp),
+            ("natpmp", NatResolver::NatPmp),
+            ("pcp", NatResolver::Pcp),
+        ] {
+            assert_eq!(resolver, s.parse().unwrap());
+            assert_eq!(resolver.to_string(), s);
+            assert!(resolver.supports_port_mapping());
+        }
+        assert_eq!(NatResolver::NatPmp, "nat-pmp".parse().unwrap());
+        assert!(!NatResolver::Any.supports_port_mapping());
+        assert!(!NatResolver::PublicIp.supports_port_mapping());
+    }
 }
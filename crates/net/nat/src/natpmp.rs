@@ -0,0 +1,188 @@
+//! NAT-PMP client.
+//!
+//! See also <https://datatracker.ietf.org/doc/html/rfc6886>
+
+use crate::port_mapping::{udp_request, PortMapping, PortMappingError, PortMappingProtocol};
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+
+/// The port the gateway listens on for NAT-PMP requests.
+pub const NATPMP_PORT: u16 = 5351;
+
+/// NAT-PMP protocol version.
+const VERSION: u8 = 0;
+
+/// Opcode of the external address request.
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+
+/// Opcode of a UDP mapping request.
+const OP_MAP_UDP: u8 = 1;
+
+/// Opcode of a TCP mapping request.
+const OP_MAP_TCP: u8 = 2;
+
+/// Added to the opcode of the request in the response.
+const OP_RESPONSE: u8 = 128;
+
+/// Size of the response to an external address request.
+const EXTERNAL_ADDRESS_RESPONSE_LEN: usize = 12;
+
+/// Size of the response to a mapping request.
+const MAP_RESPONSE_LEN: usize = 16;
+
+/// A NAT-PMP capable gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NatPmpGateway {
+    /// Address of the NAT-PMP server of the gateway.
+    addr: SocketAddr,
+}
+
+impl NatPmpGateway {
+    /// Creates a new instance for the gateway with the given ip.
+    pub const fn new(gateway: Ipv4Addr) -> Self {
+        Self { addr: SocketAddr::V4(SocketAddrV4::new(gateway, NATPMP_PORT)) }
+    }
+
+    /// Requests the external ip address of the gateway.
+    pub async fn external_ip(&self) -> Result<Ipv4Addr, PortMappingError> {
+        let response = udp_request(self.addr, &[VERSION, OP_EXTERNAL_ADDRESS]).await?;
+        decode_external_address_response(&response)
+    }
+
+    /// Maps the `internal_port` to the `external_port` on the gateway for the given lifetime.
+    ///
+    /// The gateway may assign a different external port than the suggested one.
+    pub async fn add_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+    ) -> Result<PortMapping, PortMappingError> {
+        let lifetime = lifetime.as_secs().min(u32::MAX as u64) as u32;
+        let request = encode_map_request(protocol, internal_port, external_port, lifetime);
+        let response = udp_request(self.addr, &request).await?;
+        let (external_port, lifetime) = decode_map_response(protocol, internal_port, &response)?;
+        Ok(PortMapping {
+            protocol,
+            internal_port,
+            external_port,
+            external_ip: None,
+            lifetime: Duration::from_secs(lifetime as u64),
+        })
+    }
+
+    /// Removes the mapping of the `internal_port`.
+    pub async fn remove_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+    ) -> Result<(), PortMappingError> {
+        // a mapping is removed by requesting a lifetime of zero and an external port of zero
+        let request = encode_map_request(protocol, internal_port, 0, 0);
+        let response = udp_request(self.addr, &request).await?;
+        decode_map_response(protocol, internal_port, &response)?;
+        Ok(())
+    }
+}
+
+const fn map_opcode(protocol: PortMappingProtocol) -> u8 {
+    match protocol {
+        PortMappingProtocol::Udp => OP_MAP_UDP,
+        PortMappingProtocol::Tcp => OP_MAP_TCP,
+    }
+}
+
+/// Checks the header of a response and returns the result code.
+const fn decode_header(response: &[u8], opcode: u8, len: usize) -> Result<(), PortMappingError> {
+    if response.len() < len || response[0] != VERSION || response[1] != OP_RESPONSE + opcode {
+        return Err(PortMappingError::InvalidResponse)
+    }
+    let result_code = u16::from_be_bytes([response[2], response[3]]);
+    if result_code != 0 {
+        return Err(PortMappingError::ResultCode(result_code))
+    }
+    Ok(())
+}
+
+fn decode_external_address_response(response: &[u8]) -> Result<Ipv4Addr, PortMappingError> {
+    decode_header(response, OP_EXTERNAL_ADDRESS, EXTERNAL_ADDRESS_RESPONSE_LEN)?;
+    Ok(Ipv4Addr::new(response[8], response[9], response[10], response[11]))
+}
+
+fn encode_map_request(
+    protocol: PortMappingProtocol,
+    internal_port: u16,
+    external_port: u16,
+    lifetime: u32,
+) -> [u8; 12] {
+    let mut request = [0u8; 12];
+    request[0] = VERSION;
+    request[1] = map_opcode(protocol);
+    // bytes 2..4 are reserved
+    request[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    request[6..8].copy_from_slice(&external_port.to_be_bytes());
+    request[8..12].copy_from_slice(&lifetime.to_be_bytes());
+    request
+}
+
+/// Returns the mapped external port and the lifetime of the mapping in seconds.
+fn decode_map_response(
+    protocol: PortMappingProtocol,
+    internal_port: u16,
+    response: &[u8],
+) -> Result<(u16, u32), PortMappingError> {
+    decode_header(response, map_opcode(protocol), MAP_RESPONSE_LEN)?;
+    if u16::from_be_bytes([response[8], response[9]]) != internal_port {
+        return Err(PortMappingError::InvalidResponse)
+    }
+    let external_port = u16::from_be_bytes([response[10], response[11]]);
+    let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+    Ok((external_port, lifetime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_map() {
+        let request = encode_map_request(PortMappingProtocol::Tcp, 30303, 30303, 7200);
+        assert_eq!(request, [0, 2, 0, 0, 0x76, 0x5f, 0x76, 0x5f, 0, 0, 0x1c, 0x20]);
+
+        let mut response = [0u8; MAP_RESPONSE_LEN];
+        response[1] = OP_RESPONSE + OP_MAP_TCP;
+        response[8..10].copy_from_slice(&30303u16.to_be_bytes());
+        response[10..12].copy_from_slice(&40000u16.to_be_bytes());
+        response[12..16].copy_from_slice(&3600u32.to_be_bytes());
+        assert_eq!(
+            decode_map_response(PortMappingProtocol::Tcp, 30303, &response).unwrap(),
+            (40000, 3600)
+        );
+
+        // response for a different protocol
+        assert!(matches!(
+            decode_map_response(PortMappingProtocol::Udp, 30303, &response),
+            Err(PortMappingError::InvalidResponse)
+        ));
+
+        // not authorized
+        response[3] = 2;
+        assert!(matches!(
+            decode_map_response(PortMappingProtocol::Tcp, 30303, &response),
+            Err(PortMappingError::ResultCode(2))
+        ));
+    }
+
+    #[test]
+    fn decode_external_address() {
+        let response = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+        assert_eq!(
+            decode_external_address_response(&response).unwrap(),
+            Ipv4Addr::new(203, 0, 113, 7)
+        );
+        assert!(decode_external_address_response(&response[..8]).is_err());
+    }
+}
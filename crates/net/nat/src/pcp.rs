@@ -0,0 +1,203 @@
+//! Port Control Protocol (PCP) client.
+//!
+//! See also <https://datatracker.ietf.org/doc/html/rfc6887>
+
+use crate::{
+    natpmp::NATPMP_PORT,
+    port_mapping::{
+        local_ip_towards, udp_request, PortMapping, PortMappingError, PortMappingProtocol,
+    },
+};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+
+/// PCP protocol version.
+const VERSION: u8 = 2;
+
+/// Opcode of the `MAP` request.
+const OP_MAP: u8 = 1;
+
+/// Set in the opcode byte of responses.
+const OP_RESPONSE: u8 = 0x80;
+
+/// Size of a `MAP` request and response, including the common header.
+const MAP_PACKET_LEN: usize = 60;
+
+/// IANA protocol number of TCP.
+const PROTOCOL_TCP: u8 = 6;
+
+/// IANA protocol number of UDP.
+const PROTOCOL_UDP: u8 = 17;
+
+/// A PCP capable gateway.
+///
+/// PCP servers listen on the same port as NAT-PMP servers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcpGateway {
+    /// Address of the PCP server of the gateway.
+    addr: SocketAddr,
+}
+
+impl PcpGateway {
+    /// Creates a new instance for the gateway with the given ip.
+    pub const fn new(gateway: Ipv4Addr) -> Self {
+        Self { addr: SocketAddr::V4(SocketAddrV4::new(gateway, NATPMP_PORT)) }
+    }
+
+    /// Maps the `internal_port` to the `external_port` on the gateway for the given lifetime.
+    ///
+    /// The gateway may assign a different external port than the suggested one. The returned
+    /// [`PortMapping`] includes the assigned external ip.
+    pub async fn add_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+        nonce: [u8; 12],
+    ) -> Result<PortMapping, PortMappingError> {
+        let client_ip = local_ip_towards(self.addr).await?;
+        let lifetime = lifetime.as_secs().min(u32::MAX as u64) as u32;
+        let request =
+            encode_map_request(protocol, internal_port, external_port, lifetime, client_ip, nonce);
+        let response = udp_request(self.addr, &request).await?;
+        decode_map_response(protocol, internal_port, nonce, &response)
+    }
+
+    /// Removes the mapping of the `internal_port` that was created with the given nonce.
+    pub async fn remove_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        nonce: [u8; 12],
+    ) -> Result<(), PortMappingError> {
+        let client_ip = local_ip_towards(self.addr).await?;
+        // a mapping is removed by requesting a lifetime of zero
+        let request = encode_map_request(protocol, internal_port, 0, 0, client_ip, nonce);
+        let response = udp_request(self.addr, &request).await?;
+        decode_map_response(protocol, internal_port, nonce, &response)?;
+        Ok(())
+    }
+}
+
+const fn protocol_number(protocol: PortMappingProtocol) -> u8 {
+    match protocol {
+        PortMappingProtocol::Tcp => PROTOCOL_TCP,
+        PortMappingProtocol::Udp => PROTOCOL_UDP,
+    }
+}
+
+/// PCP always encodes addresses as IPv6 addresses, IPv4 addresses are IPv4-mapped.
+const fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+fn encode_map_request(
+    protocol: PortMappingProtocol,
+    internal_port: u16,
+    external_port: u16,
+    lifetime: u32,
+    client_ip: IpAddr,
+    nonce: [u8; 12],
+) -> [u8; MAP_PACKET_LEN] {
+    let mut request = [0u8; MAP_PACKET_LEN];
+    // common request header
+    request[0] = VERSION;
+    request[1] = OP_MAP;
+    // bytes 2..4 are reserved
+    request[4..8].copy_from_slice(&lifetime.to_be_bytes());
+    request[8..24].copy_from_slice(&to_ipv6(client_ip).octets());
+    // MAP opcode payload
+    request[24..36].copy_from_slice(&nonce);
+    request[36] = protocol_number(protocol);
+    // bytes 37..40 are reserved
+    request[40..42].copy_from_slice(&internal_port.to_be_bytes());
+    request[42..44].copy_from_slice(&external_port.to_be_bytes());
+    // no preference for the external ip
+    let suggested_ip = match client_ip {
+        IpAddr::V4(_) => Ipv4Addr::UNSPECIFIED.to_ipv6_mapped(),
+        IpAddr::V6(_) => Ipv6Addr::UNSPECIFIED,
+    };
+    request[44..60].copy_from_slice(&suggested_ip.octets());
+    request
+}
+
+fn decode_map_response(
+    protocol: PortMappingProtocol,
+    internal_port: u16,
+    nonce: [u8; 12],
+    response: &[u8],
+) -> Result<PortMapping, PortMappingError> {
+    if response.len() < MAP_PACKET_LEN ||
+        response[0] != VERSION ||
+        response[1] != OP_RESPONSE | OP_MAP
+    {
+        return Err(PortMappingError::InvalidResponse)
+    }
+    let result_code = response[3];
+    if result_code != 0 {
+        return Err(PortMappingError::ResultCode(result_code as u16))
+    }
+    if response[24..36] != nonce ||
+        response[36] != protocol_number(protocol) ||
+        u16::from_be_bytes([response[40], response[41]]) != internal_port
+    {
+        return Err(PortMappingError::InvalidResponse)
+    }
+
+    let lifetime = u32::from_be_bytes([response[4], response[5], response[6], response[7]]);
+    let external_port = u16::from_be_bytes([response[42], response[43]]);
+    let mut ip = [0u8; 16];
+    ip.copy_from_slice(&response[44..60]);
+    let ip = Ipv6Addr::from(ip);
+    let external_ip = ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(ip));
+
+    Ok(PortMapping {
+        protocol,
+        internal_port,
+        external_port,
+        external_ip: Some(external_ip),
+        lifetime: Duration::from_secs(lifetime as u64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_map() {
+        let nonce = [7u8; 12];
+        let client_ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10));
+        let request =
+            encode_map_request(PortMappingProtocol::Udp, 30303, 30303, 7200, client_ip, nonce);
+        assert_eq!(request[0], VERSION);
+        assert_eq!(request[1], OP_MAP);
+        assert_eq!(&request[8..24], &client_ip_octets());
+        assert_eq!(request[36], PROTOCOL_UDP);
+
+        // the server echoes the request with the assigned values
+        let mut response = request;
+        response[1] = OP_RESPONSE | OP_MAP;
+        response[42..44].copy_from_slice(&40000u16.to_be_bytes());
+        response[44..60].copy_from_slice(&Ipv4Addr::new(203, 0, 113, 7).to_ipv6_mapped().octets());
+
+        let mapping =
+            decode_map_response(PortMappingProtocol::Udp, 30303, nonce, &response).unwrap();
+        assert_eq!(mapping.external_port, 40000);
+        assert_eq!(mapping.external_ip, Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7))));
+        assert_eq!(mapping.lifetime, Duration::from_secs(7200));
+
+        // nonce mismatch
+        assert!(decode_map_response(PortMappingProtocol::Udp, 30303, [0; 12], &response).is_err());
+    }
+
+    const fn client_ip_octets() -> [u8; 16] {
+        Ipv4Addr::new(192, 168, 1, 10).to_ipv6_mapped().octets()
+    }
+}
@@ -0,0 +1,608 @@
+//! Port mappings on the gateway of the local network.
+//!
+//! The [`PortMappingService`] maps the configured ports via `UPnP`, NAT-PMP or PCP, renews the
+//! mappings before their lease expires and removes them again on shutdown.
+
+use crate::{natpmp::NatPmpGateway, pcp::PcpGateway, upnp::UpnpGateway, NatResolver};
+use std::{
+    fmt, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    net::UdpSocket,
+    sync::{mpsc, oneshot},
+};
+use tracing::{debug, trace, warn};
+
+/// Default lease duration of a port mapping.
+///
+/// Mappings are renewed after half of their lease duration.
+pub const DEFAULT_PORT_MAPPING_LEASE: Duration = Duration::from_secs(60 * 60);
+
+/// How long to wait before retrying after the mapping failed.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Lower bound of the renewal interval, in case the gateway grants very short leases.
+const MIN_RENEWAL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Timeout of the first attempt of a UDP request, doubled for every retransmission.
+const UDP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Number of attempts of a UDP request.
+const UDP_ATTEMPTS: u32 = 4;
+
+/// Transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PortMappingProtocol {
+    /// Map a TCP port, used by the `RLPx` listener.
+    Tcp,
+    /// Map a UDP port, used by discovery.
+    Udp,
+}
+
+impl fmt::Display for PortMappingProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp => f.write_str("TCP"),
+            Self::Udp => f.write_str("UDP"),
+        }
+    }
+}
+
+/// A port mapping granted by the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortMapping {
+    /// The mapped protocol.
+    pub protocol: PortMappingProtocol,
+    /// The local port.
+    pub internal_port: u16,
+    /// The port on the gateway that is forwarded to the local port.
+    pub external_port: u16,
+    /// The external ip of the gateway, if it was reported together with the mapping.
+    pub external_ip: Option<IpAddr>,
+    /// The lease duration of the mapping, zero if the mapping is permanent.
+    pub lifetime: Duration,
+}
+
+/// Errors that can occur when mapping ports on the gateway.
+#[derive(Debug, thiserror::Error)]
+pub enum PortMappingError {
+    /// Failed to send or receive a request.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// No gateway was found in the local network.
+    #[error("no gateway found")]
+    NoGateway,
+    /// The gateway did not respond in time.
+    #[error("gateway did not respond")]
+    Timeout,
+    /// The gateway sent a malformed response.
+    #[error("invalid response from gateway")]
+    InvalidResponse,
+    /// The gateway rejected the request with the given result code.
+    #[error("gateway rejected request with result code {0}")]
+    ResultCode(u16),
+    /// A `UPnP` specific failure.
+    #[error("upnp: {0}")]
+    Upnp(String),
+    /// An HTTP request to the `UPnP` gateway failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// Configures the [`PortMappingService`].
+#[derive(Debug, Clone)]
+pub struct PortMappingConfig {
+    /// Which protocol to use for the mappings.
+    ///
+    /// See also [`NatResolver::supports_port_mapping`].
+    pub resolver: NatResolver,
+    /// The local ports to map.
+    pub ports: Vec<(PortMappingProtocol, u16)>,
+    /// The requested lease duration of the mappings.
+    pub lease_duration: Duration,
+    /// The ip of the gateway for NAT-PMP and PCP.
+    ///
+    /// If not set, the default gateway of the system is used.
+    pub gateway: Option<Ipv4Addr>,
+}
+
+impl PortMappingConfig {
+    /// Creates a new config without any ports that maps with the given resolver.
+    pub const fn new(resolver: NatResolver) -> Self {
+        Self {
+            resolver,
+            ports: Vec::new(),
+            lease_duration: DEFAULT_PORT_MAPPING_LEASE,
+            gateway: None,
+        }
+    }
+
+    /// Adds a local port to map.
+    ///
+    /// Port `0` and ports that are already configured are ignored.
+    pub fn with_port(mut self, protocol: PortMappingProtocol, port: u16) -> Self {
+        if port != 0 && !self.ports.contains(&(protocol, port)) {
+            self.ports.push((protocol, port));
+        }
+        self
+    }
+
+    /// Sets the requested lease duration of the mappings.
+    pub const fn with_lease_duration(mut self, lease_duration: Duration) -> Self {
+        self.lease_duration = lease_duration;
+        self
+    }
+
+    /// Sets the ip of the gateway for NAT-PMP and PCP.
+    pub const fn with_gateway(mut self, gateway: Ipv4Addr) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+}
+
+/// Events emitted by the [`PortMappingService`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortMappingEvent {
+    /// The external address under which the mapped ports are reachable changed.
+    ExternalAddrUpdated {
+        /// The external ip of the gateway.
+        ip: IpAddr,
+        /// The external ports of the mapped local ports.
+        ports: Vec<MappedPort>,
+    },
+}
+
+impl PortMappingEvent {
+    /// Returns the external port that is mapped to the given local port, if any.
+    pub fn external_port(&self, protocol: PortMappingProtocol, internal_port: u16) -> Option<u16> {
+        let Self::ExternalAddrUpdated { ports, .. } = self;
+        ports
+            .iter()
+            .find(|port| port.protocol == protocol && port.internal_port == internal_port)
+            .map(|port| port.external_port)
+    }
+}
+
+/// A local port and the external port on the gateway that is forwarded to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MappedPort {
+    /// The mapped protocol.
+    pub protocol: PortMappingProtocol,
+    /// The local port.
+    pub internal_port: u16,
+    /// The port on the gateway that is forwarded to the local port.
+    pub external_port: u16,
+}
+
+impl From<&PortMapping> for MappedPort {
+    fn from(mapping: &PortMapping) -> Self {
+        Self {
+            protocol: mapping.protocol,
+            internal_port: mapping.internal_port,
+            external_port: mapping.external_port,
+        }
+    }
+}
+
+/// Handle to a spawned [`PortMappingService`].
+///
+/// Dropping the handle shuts the service down, which removes all mappings from the gateway.
+#[derive(Debug)]
+pub struct PortMappingHandle {
+    /// Receives the events of the service.
+    events: mpsc::UnboundedReceiver<PortMappingEvent>,
+    /// Dropped to shut the service down.
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl PortMappingHandle {
+    /// Polls for the next [`PortMappingEvent`].
+    ///
+    /// Returns `None` if the service terminated.
+    pub fn poll_event(&mut self, cx: &mut Context<'_>) -> Poll<Option<PortMappingEvent>> {
+        self.events.poll_recv(cx)
+    }
+
+    /// Returns the next [`PortMappingEvent`].
+    pub async fn next_event(&mut self) -> Option<PortMappingEvent> {
+        self.events.recv().await
+    }
+}
+
+/// Maps ports on the gateway and keeps the mappings alive.
+#[derive(Debug)]
+#[must_use = "Does nothing unless spawned or run"]
+pub struct PortMappingService {
+    config: PortMappingConfig,
+    /// The gateway that currently holds the mappings.
+    gateway: Option<Gateway>,
+    /// Mappings that are currently active on the gateway.
+    mappings: Vec<PortMapping>,
+    /// The last reported external address.
+    external_addr: Option<PortMappingEvent>,
+    /// Identifies our mappings on a PCP gateway.
+    pcp_nonce: [u8; 12],
+    events: mpsc::UnboundedSender<PortMappingEvent>,
+    shutdown: oneshot::Receiver<()>,
+}
+
+impl PortMappingService {
+    /// Creates a new service and the [`PortMappingHandle`] to it.
+    pub fn new(config: PortMappingConfig) -> (Self, PortMappingHandle) {
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let service = Self {
+            config,
+            gateway: None,
+            mappings: Vec::new(),
+            external_addr: None,
+            pcp_nonce: rand::random(),
+            events: events_tx,
+            shutdown: shutdown_rx,
+        };
+        (service, PortMappingHandle { events: events_rx, _shutdown: shutdown_tx })
+    }
+
+    /// Spawns the service on the current tokio runtime and returns its handle.
+    pub fn spawn(config: PortMappingConfig) -> PortMappingHandle {
+        let (service, handle) = Self::new(config);
+        tokio::spawn(service.run());
+        handle
+    }
+
+    /// Maps the ports and renews the mappings until the [`PortMappingHandle`] is dropped.
+    pub async fn run(mut self) {
+        loop {
+            let next_renewal = match self.map_ports().await {
+                Ok(next_renewal) => next_renewal,
+                Err(err) => {
+                    warn!(target: "net::nat", %err, resolver=%self.config.resolver, "Failed to map ports on gateway");
+                    // the mappings that can't be removed are renewed by the next attempt, which
+                    // rediscovers the gateway
+                    self.remove_mappings().await;
+                    RETRY_INTERVAL
+                }
+            };
+
+            tokio::select! {
+                _ = &mut self.shutdown => break,
+                _ = tokio::time::sleep(next_renewal) => {}
+            }
+        }
+
+        self.remove_mappings().await;
+    }
+
+    /// Creates or renews the mappings and returns when they need to be renewed.
+    async fn map_ports(&mut self) -> Result<Duration, PortMappingError> {
+        let gateway = match self.gateway.clone() {
+            Some(gateway) => gateway,
+            None => {
+                let gateway = Gateway::discover(&self.config).await?;
+                debug!(target: "net::nat", ?gateway, "Discovered gateway");
+                self.gateway = Some(gateway.clone());
+                gateway
+            }
+        };
+
+        for &(protocol, internal_port) in &self.config.ports {
+            // try to keep the external port of an existing mapping
+            let existing = self.mappings.iter().position(|mapping| {
+                mapping.protocol == protocol && mapping.internal_port == internal_port
+            });
+            let external_port =
+                existing.map_or(internal_port, |index| self.mappings[index].external_port);
+            let mapping = gateway
+                .add_mapping(
+                    protocol,
+                    internal_port,
+                    external_port,
+                    self.config.lease_duration,
+                    self.pcp_nonce,
+                )
+                .await?;
+            trace!(target: "net::nat", ?mapping, "Mapped port");
+            // record the mapping right away, so that it's removed if a later port fails
+            match existing {
+                Some(index) => self.mappings[index] = mapping,
+                None => self.mappings.push(mapping),
+            }
+        }
+
+        let ip = match self.mappings.iter().find_map(|mapping| mapping.external_ip) {
+            Some(ip) => ip,
+            None => gateway.external_ip().await?,
+        };
+        self.on_external_addr(ip);
+
+        // renew after half of the shortest lease, permanent mappings are refreshed at the same
+        // interval to detect a changed external ip
+        let lease = self
+            .mappings
+            .iter()
+            .map(|mapping| mapping.lifetime)
+            .filter(|lifetime| !lifetime.is_zero())
+            .min()
+            .unwrap_or(self.config.lease_duration);
+        Ok((lease / 2).max(MIN_RENEWAL_INTERVAL))
+    }
+
+    /// Reports the external address if it changed.
+    fn on_external_addr(&mut self, ip: IpAddr) {
+        if !is_routable(&ip) {
+            // the gateway is behind another NAT, its external ip is not reachable from the outside
+            warn!(target: "net::nat", %ip, "Gateway reported non-routable external ip");
+            return
+        }
+
+        let ports = self.mappings.iter().map(MappedPort::from).collect();
+        let event = PortMappingEvent::ExternalAddrUpdated { ip, ports };
+        if self.external_addr.as_ref() != Some(&event) {
+            debug!(target: "net::nat", ?event, "External address updated");
+            self.external_addr = Some(event.clone());
+            let _ = self.events.send(event);
+        }
+    }
+
+    /// Removes all active mappings from the gateway and forgets the gateway.
+    ///
+    /// The mappings that couldn't be removed are kept, so that they can be renewed or removed
+    /// later.
+    async fn remove_mappings(&mut self) {
+        let Some(gateway) = self.gateway.take() else { return };
+        let mut remaining = Vec::new();
+        for mapping in std::mem::take(&mut self.mappings) {
+            if let Err(err) = gateway.remove_mapping(&mapping, self.pcp_nonce).await {
+                debug!(target: "net::nat", %err, ?mapping, "Failed to remove port mapping");
+                remaining.push(mapping);
+            } else {
+                trace!(target: "net::nat", ?mapping, "Removed port mapping");
+            }
+        }
+        self.mappings = remaining;
+    }
+}
+
+/// The supported gateway protocols.
+#[derive(Debug, Clone)]
+enum Gateway {
+    Upnp(UpnpGateway),
+    NatPmp(NatPmpGateway),
+    Pcp(PcpGateway),
+}
+
+impl Gateway {
+    /// Finds the gateway for the configured resolver.
+    async fn discover(config: &PortMappingConfig) -> Result<Self, PortMappingError> {
+        let gateway_ip =
+            || config.gateway.or_else(default_gateway).ok_or(PortMappingError::NoGateway);
+        match config.resolver {
+            NatResolver::Upnp => Ok(Self::Upnp(UpnpGateway::discover().await?)),
+            NatResolver::NatPmp => Ok(Self::NatPmp(NatPmpGateway::new(gateway_ip()?))),
+            NatResolver::Pcp => Ok(Self::Pcp(PcpGateway::new(gateway_ip()?))),
+            _ => Err(PortMappingError::NoGateway),
+        }
+    }
+
+    async fn add_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+        nonce: [u8; 12],
+    ) -> Result<PortMapping, PortMappingError> {
+        match self {
+            Self::Upnp(gateway) => {
+                gateway.add_mapping(protocol, internal_port, external_port, lifetime).await
+            }
+            Self::NatPmp(gateway) => {
+                gateway.add_mapping(protocol, internal_port, external_port, lifetime).await
+            }
+            Self::Pcp(gateway) => {
+                gateway.add_mapping(protocol, internal_port, external_port, lifetime, nonce).await
+            }
+        }
+    }
+
+    async fn remove_mapping(
+        &self,
+        mapping: &PortMapping,
+        nonce: [u8; 12],
+    ) -> Result<(), PortMappingError> {
+        match self {
+            Self::Upnp(gateway) => {
+                gateway.remove_mapping(mapping.protocol, mapping.external_port).await
+            }
+            Self::NatPmp(gateway) => {
+                gateway.remove_mapping(mapping.protocol, mapping.internal_port).await
+            }
+            Self::Pcp(gateway) => {
+                gateway.remove_mapping(mapping.protocol, mapping.internal_port, nonce).await
+            }
+        }
+    }
+
+    async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        match self {
+            Self::Upnp(gateway) => gateway.external_ip().await,
+            Self::NatPmp(gateway) => gateway.external_ip().await.map(IpAddr::V4),
+            // PCP reports the external ip with every mapping
+            Self::Pcp(_) => Err(PortMappingError::InvalidResponse),
+        }
+    }
+}
+
+/// Sends the request to the given address and waits for the response, retransmitting the request
+/// with an exponential backoff.
+pub(crate) async fn udp_request(
+    addr: SocketAddr,
+    request: &[u8],
+) -> Result<Vec<u8>, PortMappingError> {
+    let socket = UdpSocket::bind(unspecified_addr(&addr)).await?;
+    socket.connect(addr).await?;
+
+    let mut buf = [0u8; 1100];
+    let mut timeout = UDP_INITIAL_TIMEOUT;
+    for _ in 0..UDP_ATTEMPTS {
+        socket.send(request).await?;
+        if let Ok(len) = tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+            return Ok(buf[..len?].to_vec())
+        }
+        timeout *= 2;
+    }
+    Err(PortMappingError::Timeout)
+}
+
+/// Returns the local ip that is used to reach the given address.
+pub(crate) async fn local_ip_towards(addr: SocketAddr) -> io::Result<IpAddr> {
+    // connecting a UDP socket doesn't send anything, but selects the route
+    let socket = UdpSocket::bind(unspecified_addr(&addr)).await?;
+    socket.connect(addr).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+fn unspecified_addr(addr: &SocketAddr) -> SocketAddr {
+    match addr {
+        SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+    }
+}
+
+/// Returns the ip of the default gateway of the system.
+#[cfg(target_os = "linux")]
+pub(crate) fn default_gateway() -> Option<Ipv4Addr> {
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    parse_default_gateway(&routes)
+}
+
+/// Returns the ip of the default gateway of the system.
+///
+/// Only supported on linux, otherwise the gateway needs to be configured.
+#[cfg(not(target_os = "linux"))]
+pub(crate) const fn default_gateway() -> Option<Ipv4Addr> {
+    None
+}
+
+/// Parses the default gateway from the contents of `/proc/net/route`.
+///
+/// Addresses are printed as hex in host byte order.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let destination = fields.nth(1)?;
+        let gateway = u32::from_str_radix(fields.next()?, 16).ok()?;
+        (destination == "00000000" && gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+/// Returns `true` if the ip can be reached from the internet.
+const fn is_routable(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            // shared address space for carrier-grade NAT, 100.64.0.0/10
+            let shared = octets[0] == 100 && (octets[1] & 0b1100_0000) == 64;
+            !(ip.is_private() ||
+                ip.is_loopback() ||
+                ip.is_link_local() ||
+                ip.is_unspecified() ||
+                shared)
+        }
+        IpAddr::V6(ip) => !(ip.is_loopback() || ip.is_unspecified()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_route_table() {
+        let gateway = u32::from_ne_bytes([192, 168, 1, 1]);
+        let routes = format!(
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+            eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+            eth0\t00000000\t{gateway:08X}\t0003\t0\t0\t0\t00000000\t0\t0\t0\n"
+        );
+        assert_eq!(parse_default_gateway(&routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(parse_default_gateway("Iface\tDestination\tGateway\n"), None);
+    }
+
+    #[test]
+    fn routable_ips() {
+        assert!(is_routable(&IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7))));
+        assert!(!is_routable(&IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))));
+        assert!(!is_routable(&IpAddr::V4(Ipv4Addr::new(100, 64, 0, 1))));
+        assert!(is_routable(&IpAddr::V4(Ipv4Addr::new(100, 128, 0, 1))));
+    }
+
+    #[tokio::test]
+    async fn udp_request_response() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = server.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            let (len, from) = server.recv_from(&mut buf).await.unwrap();
+            buf[..len].reverse();
+            server.send_to(&buf[..len], from).await.unwrap();
+        });
+
+        let response = udp_request(server_addr, &[1, 2, 3]).await.unwrap();
+        assert_eq!(response, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn config_ports() {
+        let config = PortMappingConfig::new(NatResolver::Upnp)
+            .with_port(PortMappingProtocol::Tcp, 30303)
+            .with_port(PortMappingProtocol::Udp, 30303)
+            .with_port(PortMappingProtocol::Udp, 0)
+            .with_port(PortMappingProtocol::Udp, 30303)
+            .with_port(PortMappingProtocol::Udp, 9200);
+        assert_eq!(
+            config.ports,
+            vec![
+                (PortMappingProtocol::Tcp, 30303),
+                (PortMappingProtocol::Udp, 30303),
+                (PortMappingProtocol::Udp, 9200)
+            ]
+        );
+    }
+
+    #[test]
+    fn event_external_port() {
+        let mapping = |protocol, internal_port, external_port| MappedPort {
+            protocol,
+            internal_port,
+            external_port,
+        };
+        let event = PortMappingEvent::ExternalAddrUpdated {
+            ip: IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)),
+            ports: vec![
+                mapping(PortMappingProtocol::Tcp, 30303, 30303),
+                mapping(PortMappingProtocol::Udp, 30303, 40404),
+                mapping(PortMappingProtocol::Udp, 9200, 9201),
+            ],
+        };
+        assert_eq!(event.external_port(PortMappingProtocol::Udp, 30303), Some(40404));
+        assert_eq!(event.external_port(PortMappingProtocol::Udp, 9200), Some(9201));
+        assert_eq!(event.external_port(PortMappingProtocol::Tcp, 9200), None);
+    }
+
+    #[tokio::test]
+    async fn shutdown_on_drop() {
+        let config = PortMappingConfig::new(NatResolver::NatPmp)
+            .with_port(PortMappingProtocol::Tcp, 30303)
+            .with_port(PortMappingProtocol::Udp, 30303)
+            .with_gateway(Ipv4Addr::LOCALHOST);
+        let (service, handle) = PortMappingService::new(config);
+        drop(handle);
+        // the service stops after the first (failed) attempt, since the handle is gone
+        tokio::time::timeout(Duration::from_secs(10), service.run()).await.unwrap();
+    }
+}
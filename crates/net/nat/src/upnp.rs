@@ -0,0 +1,430 @@
+//! Minimal `UPnP` Internet Gateway Device client.
+//!
+//! Supports discovering the gateway via SSDP and the `AddPortMapping`, `DeletePortMapping` and
+//! `GetExternalIPAddress` actions of the `WANIPConnection` and `WANPPPConnection` services.
+//!
+//! See also <https://openconnectivity.org/developer/specifications/upnp-resources/upnp/internet-gateway-device-igd-v-2-0/>
+
+use crate::port_mapping::{local_ip_towards, PortMapping, PortMappingError, PortMappingProtocol};
+use rand::Rng;
+use reqwest::Url;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+
+/// Multicast address for SSDP discovery.
+const SSDP_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+
+/// How long to wait for SSDP responses.
+const SSDP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Timeout for HTTP requests to the gateway.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The SSDP search request for internet gateway devices.
+const SSDP_SEARCH_REQUEST: &str = "M-SEARCH * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+    MAN: \"ssdp:discover\"\r\n\
+    MX: 2\r\n\r\n";
+
+/// Service types that support port mappings, in order of preference.
+const WAN_CONNECTION_SERVICES: &[&str] = &[
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// `UPnP` error code returned by gateways that only support permanent mappings.
+const ONLY_PERMANENT_LEASES_SUPPORTED: u16 = 725;
+
+/// `UPnP` error code returned if the external port is already mapped to another client.
+const CONFLICT_IN_MAPPING_ENTRY: u16 = 718;
+
+/// How often to retry with a random external port if the requested one is already taken.
+const CONFLICT_RETRIES: usize = 4;
+
+/// Description of the port mappings created by reth.
+const MAPPING_DESCRIPTION: &str = "reth";
+
+/// A `UPnP` internet gateway device.
+#[derive(Debug, Clone)]
+pub struct UpnpGateway {
+    /// URL of the control endpoint of the WAN connection service.
+    control_url: Url,
+    /// Type of the WAN connection service.
+    service_type: &'static str,
+    /// Our ip address in the local network of the gateway.
+    local_ip: IpAddr,
+    /// HTTP client to talk to the gateway.
+    client: reqwest::Client,
+}
+
+impl UpnpGateway {
+    /// Discovers the gateway in the local network via SSDP.
+    pub async fn discover() -> Result<Self, PortMappingError> {
+        let location = ssdp_search().await?;
+        let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?;
+        let description = client.get(location.clone()).send().await?.error_for_status()?;
+        let description = description.text().await?;
+
+        let (service_type, control_url) = find_wan_connection_service(&description)
+            .ok_or_else(|| PortMappingError::Upnp("no WAN connection service".to_string()))?;
+        let control_url = location
+            .join(&control_url)
+            .map_err(|err| PortMappingError::Upnp(format!("invalid control url: {err}")))?;
+
+        let gateway_addr = control_url
+            .socket_addrs(|| None)
+            .ok()
+            .and_then(|addrs| addrs.into_iter().next())
+            .ok_or_else(|| PortMappingError::Upnp("invalid control url".to_string()))?;
+        let local_ip = local_ip_towards(gateway_addr).await?;
+
+        Ok(Self { control_url, service_type, local_ip, client })
+    }
+
+    /// Requests the external ip address of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let response = self.soap_request("GetExternalIPAddress", "").await?;
+        xml_element(&response, "NewExternalIPAddress")
+            .and_then(|ip| ip.trim().parse().ok())
+            .ok_or(PortMappingError::InvalidResponse)
+    }
+
+    /// Maps the `internal_port` to the `external_port` on the gateway for the given lease
+    /// duration.
+    ///
+    /// If the gateway only supports permanent mappings, the mapping is created without a lease
+    /// duration. If the external port is already mapped to another client, a random external port
+    /// is requested instead.
+    pub async fn add_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        mut external_port: u16,
+        lease_duration: Duration,
+    ) -> Result<PortMapping, PortMappingError> {
+        let lease = lease_duration.as_secs().min(u32::MAX as u64) as u32;
+        let mut retries = 0;
+        let lease = loop {
+            match self.try_add_mapping(protocol, internal_port, external_port, lease).await {
+                Err(PortMappingError::ResultCode(CONFLICT_IN_MAPPING_ENTRY))
+                    if retries < CONFLICT_RETRIES =>
+                {
+                    retries += 1;
+                    external_port = rand::thread_rng().gen_range(1024..=u16::MAX);
+                }
+                res => break res?,
+            }
+        };
+
+        Ok(PortMapping {
+            protocol,
+            internal_port,
+            external_port,
+            external_ip: None,
+            lifetime: Duration::from_secs(lease as u64),
+        })
+    }
+
+    /// Maps the port, falling back to a permanent mapping if the gateway doesn't support leases.
+    ///
+    /// Returns the granted lease.
+    async fn try_add_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lease: u32,
+    ) -> Result<u32, PortMappingError> {
+        match self.add_mapping_with_lease(protocol, internal_port, external_port, lease).await {
+            Err(PortMappingError::ResultCode(ONLY_PERMANENT_LEASES_SUPPORTED)) if lease != 0 => {
+                self.add_mapping_with_lease(protocol, internal_port, external_port, 0).await?;
+                Ok(0)
+            }
+            res => res.map(|_| lease),
+        }
+    }
+
+    async fn add_mapping_with_lease(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lease: u32,
+    ) -> Result<(), PortMappingError> {
+        let args = format!(
+            "<NewRemoteHost></NewRemoteHost>\
+            <NewExternalPort>{external_port}</NewExternalPort>\
+            <NewProtocol>{protocol}</NewProtocol>\
+            <NewInternalPort>{internal_port}</NewInternalPort>\
+            <NewInternalClient>{}</NewInternalClient>\
+            <NewEnabled>1</NewEnabled>\
+            <NewPortMappingDescription>{MAPPING_DESCRIPTION}</NewPortMappingDescription>\
+            <NewLeaseDuration>{lease}</NewLeaseDuration>",
+            self.local_ip
+        );
+        self.soap_request("AddPortMapping", &args).await?;
+        Ok(())
+    }
+
+    /// Removes the mapping of the `external_port`.
+    pub async fn remove_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        external_port: u16,
+    ) -> Result<(), PortMappingError> {
+        let args = format!(
+            "<NewRemoteHost></NewRemoteHost>\
+            <NewExternalPort>{external_port}</NewExternalPort>\
+            <NewProtocol>{protocol}</NewProtocol>"
+        );
+        self.soap_request("DeletePortMapping", &args).await?;
+        Ok(())
+    }
+
+    /// Invokes the action on the WAN connection service and returns the response body.
+    async fn soap_request(&self, action: &str, args: &str) -> Result<String, PortMappingError> {
+        let service_type = self.service_type;
+        let body = format!(
+            "<?xml version=\"1.0\"?>\
+            <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+            s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+            <s:Body><u:{action} xmlns:u=\"{service_type}\">{args}</u:{action}></s:Body>\
+            </s:Envelope>"
+        );
+        let response = self
+            .client
+            .post(self.control_url.clone())
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPAction", format!("\"{service_type}#{action}\""))
+            .body(body)
+            .send()
+            .await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            // UPnP errors are reported as SOAP faults
+            return Err(xml_element(&text, "errorCode")
+                .and_then(|code| code.trim().parse().ok())
+                .map(PortMappingError::ResultCode)
+                .unwrap_or_else(|| PortMappingError::Upnp(format!("{action} failed: {status}"))))
+        }
+        Ok(text)
+    }
+}
+
+/// Searches for an internet gateway device and returns the location of its description.
+async fn ssdp_search() -> Result<Url, PortMappingError> {
+    let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0))).await?;
+    socket.send_to(SSDP_SEARCH_REQUEST.as_bytes(), SSDP_ADDR).await?;
+
+    let mut buf = [0u8; 2048];
+    let search = async {
+        loop {
+            let (len, _) = socket.recv_from(&mut buf).await?;
+            let response = String::from_utf8_lossy(&buf[..len]);
+            if let Some(location) = ssdp_location(&response) {
+                return Ok::<_, PortMappingError>(location)
+            }
+        }
+    };
+    tokio::time::timeout(SSDP_TIMEOUT, search).await.map_err(|_| PortMappingError::NoGateway)?
+}
+
+/// Returns the `LOCATION` header of an SSDP response.
+fn ssdp_location(response: &str) -> Option<Url> {
+    response.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("location").then(|| value.trim().parse().ok())?
+    })
+}
+
+/// Returns the service type and control url of the preferred WAN connection service in the
+/// device description.
+fn find_wan_connection_service(description: &str) -> Option<(&'static str, String)> {
+    let services = description
+        .split("<service>")
+        .skip(1)
+        .filter_map(|service| service.split("</service>").next())
+        .filter_map(|service| {
+            let service_type = xml_element(service, "serviceType")?;
+            let control_url = xml_element(service, "controlURL")?;
+            Some((service_type.trim().to_string(), control_url.trim().to_string()))
+        })
+        .collect::<Vec<_>>();
+
+    WAN_CONNECTION_SERVICES.iter().find_map(|wanted| {
+        services
+            .iter()
+            .find(|(service_type, _)| service_type == wanted)
+            .map(|(_, control_url)| (*wanted, control_url.clone()))
+    })
+}
+
+/// Returns the text of the first element with the given name, ignoring namespace prefixes.
+fn xml_element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = xml;
+    loop {
+        let start = rest.find('<')?;
+        rest = &rest[start + 1..];
+        let end = rest.find('>')?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        // strip the namespace prefix and attributes
+        let tag_name = tag.split_whitespace().next().unwrap_or_default();
+        let tag_name = tag_name.rsplit(':').next().unwrap_or_default();
+        if !tag.starts_with('/') && tag_name == name {
+            let close = rest.find("</")?;
+            return Some(&rest[..close])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>
+    <deviceList>
+      <device>
+        <deviceType>urn:schemas-upnp-org:device:WANConnectionDevice:1</deviceType>
+        <serviceList>
+          <service>
+            <serviceType>urn:schemas-upnp-org:service:WANPPPConnection:1</serviceType>
+            <controlURL>/ctl/PPPConn</controlURL>
+          </service>
+          <service>
+            <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+            <serviceId>urn:upnp-org:serviceId:WANIPConn1</serviceId>
+            <controlURL>/ctl/IPConn</controlURL>
+          </service>
+        </serviceList>
+      </device>
+    </deviceList>
+  </device>
+</root>"#;
+
+    #[test]
+    fn find_service() {
+        let (service_type, control_url) = find_wan_connection_service(DESCRIPTION).unwrap();
+        assert_eq!(service_type, "urn:schemas-upnp-org:service:WANIPConnection:1");
+        assert_eq!(control_url, "/ctl/IPConn");
+    }
+
+    #[test]
+    fn parse_ssdp_location() {
+        let response = "HTTP/1.1 200 OK\r\n\
+            CACHE-CONTROL: max-age=120\r\n\
+            Location: http://192.168.1.1:5000/rootDesc.xml\r\n\
+            ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\r\n";
+        assert_eq!(
+            ssdp_location(response).unwrap().as_str(),
+            "http://192.168.1.1:5000/rootDesc.xml"
+        );
+    }
+
+    #[test]
+    fn parse_soap_response() {
+        let response = r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+  <s:Body>
+    <u:GetExternalIPAddressResponse xmlns:u="urn:schemas-upnp-org:service:WANIPConnection:1">
+      <NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>
+    </u:GetExternalIPAddressResponse>
+  </s:Body>
+</s:Envelope>"#;
+        assert_eq!(xml_element(response, "NewExternalIPAddress"), Some("203.0.113.7"));
+
+        let fault = "<s:Fault><detail><UPnPError><errorCode>725</errorCode></UPnPError></detail>\
+            </s:Fault>";
+        assert_eq!(xml_element(fault, "errorCode"), Some("725"));
+    }
+
+    /// Serves SOAP requests on a local port, rejecting the first `conflicts` `AddPortMapping`
+    /// requests with a conflict. Returns the gateway and the requested external ports.
+    async fn mock_gateway(
+        conflicts: usize,
+    ) -> (UpnpGateway, tokio::sync::mpsc::UnboundedReceiver<u16>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (ports_tx, ports_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut requests = 0;
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !String::from_utf8_lossy(&request).contains("</s:Envelope>") {
+                    let len = stream.read(&mut buf).await.unwrap();
+                    assert_ne!(len, 0);
+                    request.extend_from_slice(&buf[..len]);
+                }
+                let request = String::from_utf8_lossy(&request);
+                let port = xml_element(&request, "NewExternalPort").unwrap().parse().unwrap();
+                ports_tx.send(port).unwrap();
+
+                requests += 1;
+                let (status, body) = if requests <= conflicts {
+                    (
+                        "500 Internal Server Error",
+                        format!(
+                            "<s:Envelope><s:Body><s:Fault><detail><UPnPError>\
+                            <errorCode>{CONFLICT_IN_MAPPING_ENTRY}</errorCode>\
+                            </UPnPError></detail></s:Fault></s:Body></s:Envelope>"
+                        ),
+                    )
+                } else {
+                    ("200 OK", "<s:Envelope><s:Body></s:Body></s:Envelope>".to_string())
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\
+                    Connection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let gateway = UpnpGateway {
+            control_url: format!("http://{addr}/ctl/IPConn").parse().unwrap(),
+            service_type: WAN_CONNECTION_SERVICES[1],
+            local_ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            client: reqwest::Client::new(),
+        };
+        (gateway, ports_rx)
+    }
+
+    #[tokio::test]
+    async fn retry_conflicting_mapping() {
+        let (gateway, mut ports) = mock_gateway(1).await;
+        let mapping = gateway
+            .add_mapping(PortMappingProtocol::Udp, 30303, 30303, Duration::from_secs(3600))
+            .await
+            .unwrap();
+
+        assert_eq!(ports.recv().await, Some(30303));
+        let retried = ports.recv().await.unwrap();
+        assert_eq!(mapping.external_port, retried);
+        assert_eq!(mapping.internal_port, 30303);
+        assert_eq!(mapping.lifetime, Duration::from_secs(3600));
+    }
+
+    #[tokio::test]
+    async fn give_up_on_conflicts() {
+        let (gateway, _ports) = mock_gateway(usize::MAX).await;
+        let err = gateway
+            .add_mapping(PortMappingProtocol::Tcp, 30303, 30303, Duration::from_secs(3600))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PortMappingError::ResultCode(CONFLICT_IN_MAPPING_ENTRY)));
+    }
+}
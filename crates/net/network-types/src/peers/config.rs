@@ -22,6 +22,18 @@ pub const DEFAULT_MAX_COUNT_PEERS_INBOUND: u32 = 30;
 /// This restricts how many outbound dials can be performed concurrently.
 pub const DEFAULT_MAX_COUNT_CONCURRENT_OUTBOUND_DIALS: usize = 15;
 
+/// Maximum number of inbound connections, pending and active, from a single IP address.
+pub const DEFAULT_MAX_COUNT_INBOUND_PER_IP: usize = 5;
+
+/// Maximum number of inbound connections, pending and active, from a single subnet.
+pub const DEFAULT_MAX_COUNT_INBOUND_PER_SUBNET: usize = 10;
+
+/// Default prefix length of the subnets IPv4 inbound connections are grouped by.
+pub const DEFAULT_INBOUND_SUBNET_PREFIX_V4: u8 = 24;
+
+/// Default prefix length of the subnets IPv6 inbound connections are grouped by.
+pub const DEFAULT_INBOUND_SUBNET_PREFIX_V6: u8 = 64;
+
 /// The durations to use when a backoff should be applied to a peer.
 ///
 /// See also [`BackoffKind`].
@@ -101,6 +113,9 @@ pub struct ConnectionsConfig {
     /// Maximum allowed concurrent outbound dials.
     #[cfg_attr(feature = "serde", serde(default))]
     pub max_concurrent_outbound_dials: usize,
+    /// Restrictions on inbound connections from individual IPs and subnets.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inbound_limits: InboundConnectionLimits,
 }
 
 impl Default for ConnectionsConfig {
@@ -109,10 +124,90 @@ impl Default for ConnectionsConfig {
             max_outbound: DEFAULT_MAX_COUNT_PEERS_OUTBOUND as usize,
             max_inbound: DEFAULT_MAX_COUNT_PEERS_INBOUND as usize,
             max_concurrent_outbound_dials: DEFAULT_MAX_COUNT_CONCURRENT_OUTBOUND_DIALS,
+            inbound_limits: Default::default(),
+        }
+    }
+}
+
+/// Restrictions on inbound connections that are enforced per remote IP address and subnet, before
+/// the `ECIES` handshake.
+///
+/// These limits only apply to globally routable addresses, see
+/// [`is_global`](reth_net_banlist::is_global).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct InboundConnectionLimits {
+    /// Maximum allowed inbound connections from a single IP address.
+    ///
+    /// `None` disables the limit.
+    pub max_per_ip: Option<usize>,
+    /// Maximum allowed inbound connections from a single subnet.
+    ///
+    /// `None` disables the limit.
+    pub max_per_subnet: Option<usize>,
+    /// Prefix length of the subnets IPv4 addresses are grouped by.
+    pub subnet_prefix_v4: u8,
+    /// Prefix length of the subnets IPv6 addresses are grouped by.
+    pub subnet_prefix_v6: u8,
+    /// Rate limit for incoming connection attempts from a single IP address.
+    ///
+    /// `None` disables the rate limit.
+    pub attempts_per_ip: Option<ConnectionRateLimit>,
+    /// How long to ban an IP address that exceeded the connection attempt rate limit.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub rate_limit_ban_duration: Duration,
+}
+
+impl InboundConnectionLimits {
+    /// Returns limits that don't restrict inbound connections at all.
+    pub const fn unlimited() -> Self {
+        Self {
+            max_per_ip: None,
+            max_per_subnet: None,
+            subnet_prefix_v4: DEFAULT_INBOUND_SUBNET_PREFIX_V4,
+            subnet_prefix_v6: DEFAULT_INBOUND_SUBNET_PREFIX_V6,
+            attempts_per_ip: None,
+            rate_limit_ban_duration: Duration::ZERO,
+        }
+    }
+}
+
+impl Default for InboundConnectionLimits {
+    fn default() -> Self {
+        Self {
+            max_per_ip: Some(DEFAULT_MAX_COUNT_INBOUND_PER_IP),
+            max_per_subnet: Some(DEFAULT_MAX_COUNT_INBOUND_PER_SUBNET),
+            subnet_prefix_v4: DEFAULT_INBOUND_SUBNET_PREFIX_V4,
+            subnet_prefix_v6: DEFAULT_INBOUND_SUBNET_PREFIX_V6,
+            attempts_per_ip: Some(ConnectionRateLimit::default()),
+            // 10min
+            rate_limit_ban_duration: Duration::from_secs(60 * 10),
         }
     }
 }
 
+/// A token-bucket rate limit.
+///
+/// The bucket holds up to `burst` tokens and is refilled with one token every
+/// `refill_interval`. Every attempt consumes a token, attempts are rejected if the bucket is
+/// empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConnectionRateLimit {
+    /// Maximum number of attempts that are allowed in a burst.
+    pub burst: u32,
+    /// How long it takes to refill a single token.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub refill_interval: Duration,
+}
+
+impl Default for ConnectionRateLimit {
+    fn default() -> Self {
+        // 10 attempts per minute, with bursts of up to 10 attempts
+        Self { burst: 10, refill_interval: Duration::from_secs(6) }
+    }
+}
+
 /// Config type for initiating a `PeersManager` instance.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -214,6 +309,28 @@ impl PeersConfig {
         self
     }
 
+    /// Maximum allowed inbound connections from a single IP address with optional update.
+    pub const fn with_max_inbound_per_ip_opt(mut self, max_per_ip: Option<usize>) -> Self {
+        if let Some(max_per_ip) = max_per_ip {
+            self.connection_info.inbound_limits.max_per_ip = Some(max_per_ip);
+        }
+        self
+    }
+
+    /// Maximum allowed inbound connections from a single subnet with optional update.
+    pub const fn with_max_inbound_per_subnet_opt(mut self, max_per_subnet: Option<usize>) -> Self {
+        if let Some(max_per_subnet) = max_per_subnet {
+            self.connection_info.inbound_limits.max_per_subnet = Some(max_per_subnet);
+        }
+        self
+    }
+
+    /// Configures the restrictions on inbound connections from individual IPs and subnets.
+    pub const fn with_inbound_limits(mut self, inbound_limits: InboundConnectionLimits) -> Self {
+        self.connection_info.inbound_limits = inbound_limits;
+        self
+    }
+
     /// Maximum allowed concurrent outbound dials.
     pub const fn with_max_concurrent_dials(mut self, max_concurrent_outbound_dials: usize) -> Self {
         self.connection_info.max_concurrent_outbound_dials = max_concurrent_outbound_dials;
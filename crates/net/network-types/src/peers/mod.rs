@@ -2,4 +2,4 @@ pub mod reputation;
 pub use reputation::ReputationChangeWeights;
 
 pub mod config;
-pub use config::{ConnectionsConfig, PeersConfig};
+pub use config::{ConnectionRateLimit, ConnectionsConfig, InboundConnectionLimits, PeersConfig};
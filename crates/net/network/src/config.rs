@@ -48,6 +48,11 @@ pub struct NetworkConfig<C> {
     pub discovery_v4_config: Option<Discv4Config>,
     /// How to set up discovery version 5.
     pub discovery_v5_config: Option<reth_discv5::Config>,
+    /// How to resolve the external address of the node.
+    ///
+    /// If the resolver supports port mapping, the listener and discovery ports are mapped on the
+    /// gateway, see [`NatResolver::supports_port_mapping`].
+    pub nat: Option<NatResolver>,
     /// Address to listen for incoming connections
     pub listener_addr: SocketAddr,
     /// How to instantiate peer manager.
@@ -159,6 +164,8 @@ pub struct NetworkConfigBuilder {
     discovery_v4_builder: Option<Discv4ConfigBuilder>,
     /// How to set up discovery version 5.
     discovery_v5_builder: Option<reth_discv5::ConfigBuilder>,
+    /// How to resolve the external address of the node.
+    nat: Option<NatResolver>,
     /// All boot nodes to start network discovery with.
     boot_nodes: HashSet<TrustedPeer>,
     /// Address to use for discovery
@@ -205,6 +212,7 @@ impl NetworkConfigBuilder {
             dns_discovery_config: Some(Default::default()),
             discovery_v4_builder: Some(Default::default()),
             discovery_v5_builder: None,
+            nat: None,
             boot_nodes: Default::default(),
             discovery_addr: None,
             listener_addr: None,
@@ -298,7 +306,7 @@ impl NetworkConfigBuilder {
     }
 
     /// Configures the transactions manager with the given config.
-    pub const fn transactions_manager_config(mut self, config: TransactionsManagerConfig) -> Self {
+    pub fn transactions_manager_config(mut self, config: TransactionsManagerConfig) -> Self {
         self.transactions_manager_config = config;
         self
     }
@@ -350,7 +358,11 @@ impl NetworkConfigBuilder {
     ///
     /// This is a convenience function for setting the external ip resolver on the default
     /// [`Discv4Config`] config.
+    ///
+    /// If the resolver supports port mapping, the listener and discovery ports are also mapped on
+    /// the gateway.
     pub fn external_ip_resolver(mut self, resolver: NatResolver) -> Self {
+        self.nat = Some(resolver);
         self.discovery_v4_builder
             .get_or_insert_with(Discv4Config::builder)
             .external_ip_resolver(Some(resolver));
@@ -474,6 +486,7 @@ impl NetworkConfigBuilder {
             mut dns_discovery_config,
             discovery_v4_builder,
             mut discovery_v5_builder,
+            nat,
             boot_nodes,
             discovery_addr,
             listener_addr,
@@ -537,6 +550,7 @@ impl NetworkConfigBuilder {
             dns_discovery_config,
             discovery_v4_config: discovery_v4_builder.map(|builder| builder.build()),
             discovery_v5_config: discovery_v5_builder.map(|builder| builder.build()),
+            nat,
             discovery_v4_addr: discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS),
             listener_addr,
             peers_config: peers_config.unwrap_or_default(),
@@ -0,0 +1,302 @@
+//! Per IP and per subnet restrictions on inbound connections.
+
+use crate::peers::InboundConnectionError;
+use reth_net_banlist::is_global;
+use reth_network_peers::PeerId;
+use reth_network_types::{ConnectionRateLimit, InboundConnectionLimits};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Instant,
+};
+
+/// Enforces the [`InboundConnectionLimits`] for incoming connections.
+///
+/// Every accepted incoming connection occupies a slot of its IP address and subnet until the
+/// pending session is closed, or, if the session was established, until the active session is
+/// closed.
+#[derive(Debug)]
+pub(crate) struct InboundConnectionLimiter {
+    /// The configured limits.
+    limits: InboundConnectionLimits,
+    /// Number of pending and active inbound connections per IP address.
+    per_ip: HashMap<IpAddr, usize>,
+    /// Number of pending and active inbound connections per subnet.
+    per_subnet: HashMap<IpAddr, usize>,
+    /// IP addresses of established inbound sessions.
+    active: HashMap<PeerId, IpAddr>,
+    /// Rate limits of incoming connection attempts per IP address.
+    attempts: HashMap<IpAddr, TokenBucket>,
+}
+
+// === impl InboundConnectionLimiter ===
+
+impl InboundConnectionLimiter {
+    /// Creates a new limiter that enforces the given limits.
+    pub(crate) fn new(limits: InboundConnectionLimits) -> Self {
+        Self {
+            limits,
+            per_ip: Default::default(),
+            per_subnet: Default::default(),
+            active: Default::default(),
+            attempts: Default::default(),
+        }
+    }
+
+    /// Checks whether a new incoming connection from the given IP address is acceptable, and if
+    /// so, occupies a slot for it.
+    ///
+    /// Every successful call must be followed by [`Self::on_pending_closed`] or
+    /// [`Self::on_session_established`].
+    pub(crate) fn try_acquire(&mut self, ip: IpAddr) -> Result<(), InboundConnectionError> {
+        if !is_global(&ip) {
+            return Ok(())
+        }
+
+        if let Some(rate_limit) = self.limits.attempts_per_ip {
+            let bucket = self.attempts.entry(ip).or_insert_with(|| TokenBucket::new(rate_limit));
+            if !bucket.try_take(rate_limit, Instant::now()) {
+                return Err(InboundConnectionError::RateLimited)
+            }
+        }
+
+        if self.limits.max_per_ip.is_some_and(|max| self.num_connections_from_ip(&ip) >= max) {
+            return Err(InboundConnectionError::ExceedsIpCapacity)
+        }
+
+        let subnet = self.subnet(ip);
+        if self
+            .limits
+            .max_per_subnet
+            .is_some_and(|max| self.per_subnet.get(&subnet).copied().unwrap_or_default() >= max)
+        {
+            return Err(InboundConnectionError::ExceedsSubnetCapacity)
+        }
+
+        *self.per_ip.entry(ip).or_default() += 1;
+        *self.per_subnet.entry(subnet).or_default() += 1;
+        Ok(())
+    }
+
+    /// Releases the slot of a pending incoming connection that was closed before a session was
+    /// established.
+    pub(crate) fn on_pending_closed(&mut self, ip: IpAddr) {
+        self.release(ip);
+    }
+
+    /// Keeps the slot of the incoming connection occupied by the now established session.
+    pub(crate) fn on_session_established(&mut self, peer_id: PeerId, ip: IpAddr) {
+        if !is_global(&ip) {
+            return
+        }
+        if let Some(prev) = self.active.insert(peer_id, ip) {
+            // this should not happen, since sessions are unique per peer, but we don't want to
+            // leak a slot
+            self.release(prev);
+        }
+    }
+
+    /// Releases the slot of the session to the given peer, if it was an inbound session.
+    pub(crate) fn on_session_closed(&mut self, peer_id: &PeerId) {
+        if let Some(ip) = self.active.remove(peer_id) {
+            self.release(ip);
+        }
+    }
+
+    /// Returns the number of pending and active inbound connections from the given IP address.
+    pub(crate) fn num_connections_from_ip(&self, ip: &IpAddr) -> usize {
+        self.per_ip.get(ip).copied().unwrap_or_default()
+    }
+
+    /// Removes rate limit entries of IP addresses that have a full bucket again, since these are
+    /// no different from IP addresses we haven't seen yet.
+    pub(crate) fn evict_rate_limits(&mut self, now: Instant) {
+        let Some(rate_limit) = self.limits.attempts_per_ip else { return };
+        self.attempts.retain(|_, bucket| !bucket.is_full(rate_limit, now));
+    }
+
+    /// Returns how long an IP address that exceeded the rate limit should be banned.
+    pub(crate) const fn rate_limit_ban_duration(&self) -> std::time::Duration {
+        self.limits.rate_limit_ban_duration
+    }
+
+    fn release(&mut self, ip: IpAddr) {
+        if !is_global(&ip) {
+            return
+        }
+        let subnet = self.subnet(ip);
+        decrement(&mut self.per_ip, ip);
+        decrement(&mut self.per_subnet, subnet);
+    }
+
+    /// Returns the network address of the subnet the IP address belongs to.
+    fn subnet(&self, ip: IpAddr) -> IpAddr {
+        match ip {
+            IpAddr::V4(ip) => {
+                let prefix = self.limits.subnet_prefix_v4.min(32) as u32;
+                let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or_default();
+                IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+            }
+            IpAddr::V6(ip) => {
+                let prefix = self.limits.subnet_prefix_v6.min(128) as u32;
+                let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or_default();
+                IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+            }
+        }
+    }
+}
+
+/// Decrements the counter of the key and removes it once it reaches zero.
+fn decrement(map: &mut HashMap<IpAddr, usize>, key: IpAddr) {
+    if let Entry::Occupied(mut entry) = map.entry(key) {
+        *entry.get_mut() = entry.get().saturating_sub(1);
+        if *entry.get() == 0 {
+            entry.remove();
+        }
+    }
+}
+
+/// A token bucket for a [`ConnectionRateLimit`].
+#[derive(Debug)]
+struct TokenBucket {
+    /// Number of available tokens.
+    tokens: u32,
+    /// When the bucket was last refilled.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate_limit: ConnectionRateLimit) -> Self {
+        Self { tokens: rate_limit.burst, last_refill: Instant::now() }
+    }
+
+    /// Refills the bucket with the tokens accumulated since the last refill.
+    fn refill(&mut self, rate_limit: ConnectionRateLimit, now: Instant) {
+        if self.tokens >= rate_limit.burst || rate_limit.refill_interval.is_zero() {
+            self.tokens = rate_limit.burst;
+            self.last_refill = now;
+            return
+        }
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refilled = elapsed.as_nanos() / rate_limit.refill_interval.as_nanos();
+        if self.tokens as u128 + refilled >= rate_limit.burst as u128 {
+            self.tokens = rate_limit.burst;
+            self.last_refill = now;
+        } else if refilled > 0 {
+            // can't overflow, since it's less than the burst size
+            let refilled = refilled as u32;
+            self.tokens += refilled;
+            self.last_refill += rate_limit.refill_interval * refilled;
+        }
+    }
+
+    /// Takes a token from the bucket, returns `false` if the bucket is empty.
+    fn try_take(&mut self, rate_limit: ConnectionRateLimit, now: Instant) -> bool {
+        self.refill(rate_limit, now);
+        if self.tokens == 0 {
+            return false
+        }
+        self.tokens -= 1;
+        true
+    }
+
+    /// Returns `true` if the bucket would be full at the given time.
+    fn is_full(&mut self, rate_limit: ConnectionRateLimit, now: Instant) -> bool {
+        self.refill(rate_limit, now);
+        self.tokens >= rate_limit.burst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const fn limits() -> InboundConnectionLimits {
+        InboundConnectionLimits {
+            max_per_ip: Some(2),
+            max_per_subnet: Some(3),
+            attempts_per_ip: None,
+            ..InboundConnectionLimits::unlimited()
+        }
+    }
+
+    #[test]
+    fn test_limit_per_ip() {
+        let mut limiter = InboundConnectionLimiter::new(limits());
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+
+        assert!(limiter.try_acquire(ip).is_ok());
+        assert!(limiter.try_acquire(ip).is_ok());
+        assert_eq!(limiter.try_acquire(ip), Err(InboundConnectionError::ExceedsIpCapacity));
+
+        limiter.on_pending_closed(ip);
+        assert!(limiter.try_acquire(ip).is_ok());
+        assert_eq!(limiter.num_connections_from_ip(&ip), 2);
+    }
+
+    #[test]
+    fn test_limit_per_subnet() {
+        let mut limiter = InboundConnectionLimiter::new(limits());
+
+        for i in 1..=3 {
+            assert!(limiter.try_acquire(IpAddr::V4(Ipv4Addr::new(1, 2, 3, i))).is_ok());
+        }
+        assert_eq!(
+            limiter.try_acquire(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 100))),
+            Err(InboundConnectionError::ExceedsSubnetCapacity)
+        );
+        // a different /24 is not affected
+        assert!(limiter.try_acquire(IpAddr::V4(Ipv4Addr::new(1, 2, 4, 1))).is_ok());
+    }
+
+    #[test]
+    fn test_active_session_keeps_slot() {
+        let mut limiter = InboundConnectionLimiter::new(limits());
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let peer_id = PeerId::random();
+
+        assert!(limiter.try_acquire(ip).is_ok());
+        limiter.on_session_established(peer_id, ip);
+        assert_eq!(limiter.num_connections_from_ip(&ip), 1);
+
+        limiter.on_session_closed(&peer_id);
+        assert_eq!(limiter.num_connections_from_ip(&ip), 0);
+        // closing twice doesn't release a slot again
+        limiter.on_session_closed(&peer_id);
+        assert_eq!(limiter.num_connections_from_ip(&ip), 0);
+    }
+
+    #[test]
+    fn test_local_ips_are_exempt() {
+        let mut limiter = InboundConnectionLimiter::new(limits());
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        for _ in 0..10 {
+            assert!(limiter.try_acquire(ip).is_ok());
+        }
+        assert_eq!(limiter.num_connections_from_ip(&ip), 0);
+    }
+
+    #[test]
+    fn test_attempt_rate_limit() {
+        let rate_limit = ConnectionRateLimit { burst: 2, refill_interval: Duration::from_secs(1) };
+        let mut limiter = InboundConnectionLimiter::new(InboundConnectionLimits {
+            attempts_per_ip: Some(rate_limit),
+            ..InboundConnectionLimits::unlimited()
+        });
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+
+        assert!(limiter.try_acquire(ip).is_ok());
+        assert!(limiter.try_acquire(ip).is_ok());
+        assert_eq!(limiter.try_acquire(ip), Err(InboundConnectionError::RateLimited));
+
+        let bucket = limiter.attempts.get_mut(&ip).unwrap();
+        let later = bucket.last_refill + Duration::from_secs(1);
+        assert!(bucket.try_take(rate_limit, later));
+        assert!(!bucket.try_take(rate_limit, later));
+
+        limiter.evict_rate_limits(later + Duration::from_secs(10));
+        assert!(limiter.attempts.is_empty());
+    }
+}
@@ -13,6 +13,10 @@ use reth_discv5::{DiscoveredPeer, Discv5};
 use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
+use reth_net_nat::{
+    NatResolver, PortMappingConfig, PortMappingEvent, PortMappingHandle, PortMappingProtocol,
+    PortMappingService,
+};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_primitives::{EnrForkIdEntry, ForkId};
 use secp256k1::SecretKey;
@@ -25,7 +29,7 @@ use std::{
 };
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
-use tracing::trace;
+use tracing::{debug, trace};
 
 /// Default max capacity for cache of discovered peers.
 ///
@@ -52,6 +56,8 @@ pub struct Discovery {
     _discv4_service: Option<JoinHandle<()>>,
     /// Handler to interact with the Discovery v5 service
     discv5: Option<Discv5>,
+    /// Local ENR of the discovery v5 service.
+    local_enr_discv5: Option<NodeRecord>,
     /// All KAD table updates from the discv5 service.
     discv5_updates: Option<ReceiverStream<discv5::Event>>,
     /// Handler to interact with the DNS discovery service
@@ -60,6 +66,10 @@ pub struct Discovery {
     dns_discovery_updates: Option<ReceiverStream<DnsNodeRecordUpdate>>,
     /// The handle to the spawned DNS discovery service
     _dns_disc_service: Option<JoinHandle<()>>,
+    /// Handle to the service that maps the listener and discovery ports on the gateway.
+    ///
+    /// Dropping the handle removes the mappings.
+    port_mapping: Option<PortMappingHandle>,
     /// Events buffered until polled.
     queued_events: VecDeque<DiscoveryEvent>,
     /// List of listeners subscribed to discovery events.
@@ -99,12 +109,14 @@ impl Discovery {
         };
 
         let discv5_future = async {
-            let Some(config) = discv5_config else { return Ok::<_, NetworkError>((None, None)) };
-            let (discv5, discv5_updates, _local_enr_discv5) = Discv5::start(&sk, config).await?;
-            Ok((Some(discv5), Some(discv5_updates.into())))
+            let Some(config) = discv5_config else {
+                return Ok::<_, NetworkError>((None, None, None))
+            };
+            let (discv5, discv5_updates, local_enr_discv5) = Discv5::start(&sk, config).await?;
+            Ok((Some(discv5), Some(discv5_updates.into()), Some(local_enr_discv5)))
         };
 
-        let ((discv4, discv4_updates, _discv4_service), (discv5, discv5_updates)) =
+        let ((discv4, discv4_updates, _discv4_service), (discv5, discv5_updates, local_enr_discv5)) =
             tokio::try_join!(discv4_future, discv5_future)?;
 
         // setup DNS discovery
@@ -128,15 +140,63 @@ impl Discovery {
             discv4_updates,
             _discv4_service,
             discv5,
+            local_enr_discv5,
             discv5_updates,
             discovered_nodes: LruMap::new(DEFAULT_MAX_CAPACITY_DISCOVERED_PEERS_CACHE),
             queued_events: Default::default(),
             _dns_disc_service,
             _dns_discovery,
             dns_discovery_updates,
+            port_mapping: None,
         })
     }
 
+    /// Spawns the port mapping service, which maps the `RLPx` listener port and the bound udp
+    /// ports of discv4 and discv5 on the gateway.
+    ///
+    /// Whenever the external address of the mapped ports changes, it is advertised via discv4
+    /// and discv5.
+    pub(crate) fn spawn_port_mapping(&mut self, resolver: NatResolver) {
+        let mut config = PortMappingConfig::new(resolver)
+            .with_port(PortMappingProtocol::Tcp, self.local_enr.tcp_port);
+        if let Some(discv4) = &self.discv4 {
+            // the configured port may be `0`, so map the port the socket is bound to
+            config = config.with_port(PortMappingProtocol::Udp, discv4.local_addr().port());
+        }
+        if let Some(local_enr_discv5) = &self.local_enr_discv5 {
+            config = config.with_port(PortMappingProtocol::Udp, local_enr_discv5.udp_port);
+        }
+        self.port_mapping = Some(PortMappingService::spawn(config));
+    }
+
+    /// Advertises the external address of the mapped ports.
+    fn on_port_mapping_event(&self, event: PortMappingEvent) {
+        let tcp_port = event
+            .external_port(PortMappingProtocol::Tcp, self.local_enr.tcp_port)
+            .unwrap_or(self.local_enr.tcp_port);
+        let PortMappingEvent::ExternalAddrUpdated { ip, ref ports } = event;
+        debug!(target: "net::discovery", %ip, ?ports, "Mapped ports on gateway");
+
+        if let Some(discv4) = &self.discv4 {
+            discv4.set_external_ip_addr(ip);
+            discv4.set_tcp_port(tcp_port);
+            let local_port = discv4.local_addr().port();
+            if let Some(udp_port) = event.external_port(PortMappingProtocol::Udp, local_port) {
+                discv4.set_udp_port(udp_port);
+            }
+        }
+        if let Some(discv5) = &self.discv5 {
+            discv5.set_external_socket(SocketAddr::new(ip, tcp_port), true);
+            let udp_port = self
+                .local_enr_discv5
+                .as_ref()
+                .and_then(|enr| event.external_port(PortMappingProtocol::Udp, enr.udp_port));
+            if let Some(udp_port) = udp_port {
+                discv5.set_external_socket(SocketAddr::new(ip, udp_port), false);
+            }
+        }
+    }
+
     /// Registers a listener for receiving [`DiscoveryEvent`] updates.
     pub(crate) fn add_listener(&mut self, tx: mpsc::UnboundedSender<DiscoveryEvent>) {
         self.discovery_listeners.push(tx);
@@ -266,6 +326,13 @@ impl Discovery {
                 }
             }
 
+            // drain the port mapping events
+            while let Some(Poll::Ready(Some(event))) =
+                self.port_mapping.as_mut().map(|port_mapping| port_mapping.poll_event(cx))
+            {
+                self.on_port_mapping_event(event);
+            }
+
             // drain the dns update stream
             while let Some(Poll::Ready(Some(update))) =
                 self.dns_discovery_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
@@ -315,12 +382,14 @@ impl Discovery {
             discv4: Default::default(),
             discv4_updates: Default::default(),
             discv5: None,
+            local_enr_discv5: None,
             discv5_updates: None,
             queued_events: Default::default(),
             _discv4_service: Default::default(),
             _dns_discovery: None,
             dns_discovery_updates: None,
             _dns_disc_service: None,
+            port_mapping: None,
             discovery_listeners: Default::default(),
         }
     }
@@ -1,6 +1,6 @@
 //! Fetch data from the network.
 
-use crate::{message::BlockRequest, peers::PeersHandle};
+use crate::{message::BlockRequest, metrics::StateFetcherMetrics, peers::PeersHandle};
 use futures::StreamExt;
 use reth_eth_wire::{GetBlockBodies, GetBlockHeaders};
 use reth_network_api::ReputationChangeKind;
@@ -18,12 +18,16 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 mod client;
+mod score;
+
 pub use client::FetchClient;
+pub use score::PeerResponseScore;
 
 /// Manages data fetching operations.
 ///
@@ -51,6 +55,8 @@ pub struct StateFetcher {
     download_requests_rx: UnboundedReceiverStream<DownloadRequest>,
     /// Sender for download requests, used to detach a [`FetchClient`]
     download_requests_tx: UnboundedSender<DownloadRequest>,
+    /// Metrics for the fetcher.
+    metrics: StateFetcherMetrics,
 }
 
 // === impl StateSyncer ===
@@ -67,6 +73,7 @@ impl StateFetcher {
             queued_requests: Default::default(),
             download_requests_rx: UnboundedReceiverStream::new(download_requests_rx),
             download_requests_tx,
+            metrics: Default::default(),
         }
     }
 
@@ -86,6 +93,7 @@ impl StateFetcher {
                 best_number,
                 timeout,
                 last_response_likely_bad: false,
+                score: PeerResponseScore::default(),
             },
         );
     }
@@ -104,6 +112,42 @@ impl StateFetcher {
         if let Some(req) = self.inflight_bodies_requests.remove(peer) {
             let _ = req.response.send(Err(RequestError::ConnectionDropped));
         }
+        self.update_scored_peers_metric();
+    }
+
+    /// Returns the response scores of all active peers.
+    pub(crate) fn peer_scores(&self) -> HashMap<PeerId, PeerResponseScore> {
+        self.peers.iter().map(|(peer_id, peer)| (*peer_id, peer.score)).collect()
+    }
+
+    /// Records a response of a peer to a request that was issued at `issued_at`.
+    fn record_response(
+        &mut self,
+        peer_id: &PeerId,
+        issued_at: Instant,
+        bytes: usize,
+        is_empty: bool,
+    ) -> Duration {
+        let elapsed = issued_at.elapsed();
+        if is_empty {
+            self.metrics.empty_responses.increment(1);
+        } else {
+            self.metrics.response_throughput.record(bytes as f64 / elapsed.as_secs_f64().max(1e-3));
+        }
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            let was_unscored = peer.score.is_empty();
+            peer.score.on_response(elapsed, bytes, is_empty);
+            if was_unscored {
+                self.update_scored_peers_metric();
+            }
+        }
+        elapsed
+    }
+
+    /// Updates the gauge that tracks the number of peers with a response score.
+    fn update_scored_peers_metric(&self) {
+        let scored = self.peers.values().filter(|peer| !peer.score.is_empty()).count();
+        self.metrics.scored_peers.set(scored as f64);
     }
 
     /// Updates the block information for the peer.
@@ -128,8 +172,10 @@ impl StateFetcher {
     }
 
     /// Returns the _next_ idle peer that's ready to accept a request,
-    /// prioritizing those with the lowest timeout/latency and those that recently responded with
-    /// adequate data.
+    /// prioritizing those with the best [`PeerResponseScore`] and those that recently responded
+    /// with adequate data.
+    ///
+    /// Peers without any recorded response are ranked by their current timeout.
     fn next_best_peer(&self) -> Option<PeerId> {
         let mut idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
 
@@ -142,8 +188,8 @@ impl StateFetcher {
                 continue
             }
 
-            // replace best peer if this peer has better rtt
-            if maybe_better.1.timeout() < best_peer.1.timeout() &&
+            // replace best peer if this peer is expected to respond faster
+            if maybe_better.1.is_better_than(best_peer.1) &&
                 !maybe_better.1.last_response_likely_bad
             {
                 best_peer = maybe_better;
@@ -220,7 +266,8 @@ impl StateFetcher {
 
         match req {
             DownloadRequest::GetBlockHeaders { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, issued_at: Instant::now() };
                 self.inflight_headers_requests.insert(peer_id, inflight);
                 let HeadersRequest { start, limit, direction } = request;
                 BlockRequest::GetBlockHeaders(GetBlockHeaders {
@@ -231,7 +278,8 @@ impl StateFetcher {
                 })
             }
             DownloadRequest::GetBlockBodies { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, issued_at: Instant::now() };
                 self.inflight_bodies_requests.insert(peer_id, inflight);
                 BlockRequest::GetBlockBodies(GetBlockBodies(request))
             }
@@ -268,6 +316,15 @@ impl StateFetcher {
             .unwrap_or_default();
 
         if let Some(resp) = resp {
+            let bytes = res.as_ref().map_or(0, |headers| headers.iter().map(Header::size).sum());
+            let elapsed = self.record_response(
+                &peer_id,
+                resp.issued_at,
+                bytes,
+                is_error || is_likely_bad_response,
+            );
+            self.metrics.headers_response_time.record(elapsed.as_secs_f64());
+
             // delegate the response
             let _ = resp.response.send(res.map(|h| (peer_id, h).into()));
         }
@@ -298,6 +355,11 @@ impl StateFetcher {
         let is_likely_bad_response = res.as_ref().map_or(true, |bodies| bodies.is_empty());
 
         if let Some(resp) = self.inflight_bodies_requests.remove(&peer_id) {
+            let bytes = res.as_ref().map_or(0, |bodies| bodies.iter().map(BlockBody::size).sum());
+            let elapsed =
+                self.record_response(&peer_id, resp.issued_at, bytes, is_likely_bad_response);
+            self.metrics.bodies_response_time.record(elapsed.as_secs_f64());
+
             let _ = resp.response.send(res.map(|b| (peer_id, b).into()));
         }
         if let Some(peer) = self.peers.get_mut(&peer_id) {
@@ -346,12 +408,19 @@ struct Peer {
     /// downloaded), but we still want to avoid requesting from the same peer again if it has the
     /// lowest timeout.
     last_response_likely_bad: bool,
+    /// Tracks the quality of the peer's responses, used to rank peers for new requests.
+    score: PeerResponseScore,
 }
 
 impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Returns `true` if this peer is expected to serve requests better than `other`.
+    fn is_better_than(&self, other: &Self) -> bool {
+        self.score.cmp_with(self.timeout(), &other.score, other.timeout()).is_lt()
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -398,6 +467,8 @@ struct Request<Req, Resp> {
     #[allow(dead_code)]
     request: Req,
     response: oneshot::Sender<Resp>,
+    /// When the request was sent to the peer.
+    issued_at: Instant,
 }
 
 /// Requests that can be sent to the Syncer from a [`FetchClient`]
@@ -540,6 +611,38 @@ mod tests {
         assert_eq!(fetcher.next_best_peer(), Some(peer2));
     }
 
+    #[tokio::test]
+    async fn test_peer_prioritization_by_score() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let peer1 = B512::random();
+        let peer2 = B512::random();
+
+        // peer1 has the lower timeout but hasn't responded yet
+        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(30)));
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(300)));
+        assert_eq!(fetcher.next_best_peer(), Some(peer1));
+
+        // peer1 turns out to be slow and mostly sends empty responses
+        for _ in 0..5 {
+            fetcher.peers.get_mut(&peer1).unwrap().score.on_response(
+                Duration::from_millis(100),
+                0,
+                true,
+            );
+        }
+        fetcher.peers.get_mut(&peer2).unwrap().score.on_response(
+            Duration::from_millis(80),
+            10_000,
+            false,
+        );
+        assert_eq!(fetcher.next_best_peer(), Some(peer2));
+
+        let scores = fetcher.peer_scores();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[&peer2].responses(), 1);
+    }
+
     #[tokio::test]
     async fn test_on_block_headers_response() {
         let manager = PeersManager::new(PeersConfig::default());
@@ -585,6 +688,7 @@ mod tests {
                     direction: Default::default(),
                 },
                 response: tx,
+                issued_at: Instant::now(),
             };
             let mut header = SealedHeader::default().unseal();
             header.number = 0u64;
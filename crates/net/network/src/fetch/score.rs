@@ -0,0 +1,149 @@
+//! Response quality tracking for peers that serve header and body requests.
+
+use std::{cmp::Ordering, time::Duration};
+
+/// Weight of a new sample in the exponentially weighted moving averages.
+const SAMPLE_IMPACT: f64 = 0.25;
+
+/// How much a peer's expected latency is inflated per unit of its empty response rate.
+///
+/// A peer that only ever sends empty responses is ranked as if it were `1 +
+/// EMPTY_RESPONSE_PENALTY` times slower than its measured latency.
+const EMPTY_RESPONSE_PENALTY: f64 = 4.0;
+
+/// Tracks the quality of the responses a peer sent to our `GetBlockHeaders` and
+/// `GetBlockBodies` requests.
+///
+/// All values are exponentially weighted moving averages, so that a peer that degrades (or
+/// improves) is re-ranked after a few responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PeerResponseScore {
+    /// Average time in milliseconds between sending a request and receiving its response.
+    latency_ms: f64,
+    /// Average throughput of responses in bytes per second.
+    bytes_per_sec: f64,
+    /// Average rate of empty or error responses, in `[0.0, 1.0]`.
+    empty_response_rate: f64,
+    /// Total number of responses that have been recorded.
+    responses: u64,
+}
+
+// === impl PeerResponseScore ===
+
+impl PeerResponseScore {
+    /// Returns the average response time in milliseconds.
+    pub const fn latency_ms(&self) -> f64 {
+        self.latency_ms
+    }
+
+    /// Returns the average throughput in bytes per second.
+    pub const fn bytes_per_sec(&self) -> f64 {
+        self.bytes_per_sec
+    }
+
+    /// Returns the average rate of empty or failed responses, in `[0.0, 1.0]`.
+    pub const fn empty_response_rate(&self) -> f64 {
+        self.empty_response_rate
+    }
+
+    /// Returns the number of responses recorded for the peer.
+    pub const fn responses(&self) -> u64 {
+        self.responses
+    }
+
+    /// Returns `true` if no response has been recorded yet.
+    pub const fn is_empty(&self) -> bool {
+        self.responses == 0
+    }
+
+    /// Records a response that took `elapsed` and carried `bytes` of payload.
+    ///
+    /// `is_empty` should be set for responses that were empty, likely bad or failed.
+    pub fn on_response(&mut self, elapsed: Duration, bytes: usize, is_empty: bool) {
+        let latency_ms = elapsed.as_secs_f64() * 1000.0;
+        // guard against a zero duration, which would yield an infinite throughput
+        let bytes_per_sec = bytes as f64 / elapsed.as_secs_f64().max(1e-3);
+        let empty = if is_empty { 1.0 } else { 0.0 };
+
+        if self.responses == 0 {
+            self.latency_ms = latency_ms;
+            self.bytes_per_sec = bytes_per_sec;
+            self.empty_response_rate = empty;
+        } else {
+            self.latency_ms = ewma(self.latency_ms, latency_ms);
+            self.bytes_per_sec = ewma(self.bytes_per_sec, bytes_per_sec);
+            self.empty_response_rate = ewma(self.empty_response_rate, empty);
+        }
+        self.responses += 1;
+    }
+
+    /// Returns the expected cost of sending a request to this peer, lower is better.
+    ///
+    /// This is the average latency, inflated by the empty response rate. If no response has been
+    /// recorded yet, the peer's current request `timeout` (in milliseconds) is used as the
+    /// latency estimate.
+    pub fn expected_cost(&self, timeout: u64) -> f64 {
+        if self.is_empty() {
+            return timeout as f64
+        }
+        self.latency_ms * EMPTY_RESPONSE_PENALTY.mul_add(self.empty_response_rate, 1.0)
+    }
+
+    /// Compares two peers by their expected cost, using throughput as tie-breaker.
+    ///
+    /// Returns [`Ordering::Less`] if `self` is the better peer.
+    pub(crate) fn cmp_with(&self, self_timeout: u64, other: &Self, other_timeout: u64) -> Ordering {
+        self.expected_cost(self_timeout)
+            .total_cmp(&other.expected_cost(other_timeout))
+            .then_with(|| other.bytes_per_sec.total_cmp(&self.bytes_per_sec))
+    }
+}
+
+/// Applies a new sample to an exponentially weighted moving average.
+#[inline]
+fn ewma(current: f64, sample: f64) -> f64 {
+    current.mul_add(1.0 - SAMPLE_IMPACT, sample * SAMPLE_IMPACT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_sample_initializes_score() {
+        let mut score = PeerResponseScore::default();
+        assert!(score.is_empty());
+        assert_eq!(score.expected_cost(100), 100.0);
+
+        score.on_response(Duration::from_millis(50), 5_000, false);
+        assert_eq!(score.responses(), 1);
+        assert_eq!(score.latency_ms(), 50.0);
+        assert_eq!(score.bytes_per_sec(), 100_000.0);
+        assert_eq!(score.empty_response_rate(), 0.0);
+        assert_eq!(score.expected_cost(100), 50.0);
+    }
+
+    #[test]
+    fn empty_responses_increase_cost() {
+        let mut fast_but_empty = PeerResponseScore::default();
+        let mut slow_but_full = PeerResponseScore::default();
+        for _ in 0..10 {
+            fast_but_empty.on_response(Duration::from_millis(20), 0, true);
+            slow_but_full.on_response(Duration::from_millis(60), 10_000, false);
+        }
+
+        assert!(fast_but_empty.empty_response_rate() > 0.9);
+        assert_eq!(slow_but_full.cmp_with(0, &fast_but_empty, 0), Ordering::Less);
+    }
+
+    #[test]
+    fn throughput_breaks_ties() {
+        let mut a = PeerResponseScore::default();
+        let mut b = PeerResponseScore::default();
+        a.on_response(Duration::from_millis(20), 10_000, false);
+        b.on_response(Duration::from_millis(20), 1_000, false);
+
+        assert_eq!(a.cmp_with(0, &b, 0), Ordering::Less);
+        assert_eq!(b.cmp_with(0, &a, 0), Ordering::Greater);
+    }
+}
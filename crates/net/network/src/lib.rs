@@ -118,6 +118,7 @@ mod budget;
 mod builder;
 pub mod cache;
 pub mod config;
+mod connection_limits;
 mod discovery;
 pub mod error;
 pub mod eth_requests;
@@ -139,7 +140,7 @@ pub mod transactions;
 pub use builder::NetworkBuilder;
 pub use config::{NetworkConfig, NetworkConfigBuilder};
 pub use discovery::{Discovery, DiscoveryEvent};
-pub use fetch::FetchClient;
+pub use fetch::{FetchClient, PeerResponseScore};
 pub use manager::{NetworkEvent, NetworkManager};
 pub use message::PeerRequest;
 pub use network::{NetworkEvents, NetworkHandle, NetworkProtocols};
@@ -43,7 +43,7 @@ use reth_eth_wire::{
 };
 use reth_fs_util::{self as fs, FsPathError};
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
-use reth_network_api::{EthProtocolInfo, NetworkStatus, PeerInfo, ReputationChangeKind};
+use reth_network_api::{EthProtocolInfo, NetworkStatus, PeerInfo, PeerKind, ReputationChangeKind};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_primitives::ForkId;
 use reth_storage_api::BlockNumReader;
@@ -174,6 +174,7 @@ impl NetworkManager {
             discovery_v4_addr,
             mut discovery_v4_config,
             discovery_v5_config,
+            nat,
             listener_addr,
             peers_config,
             sessions_config,
@@ -215,7 +216,7 @@ impl NetworkManager {
             disc_config
         });
 
-        let discovery = Discovery::new(
+        let mut discovery = Discovery::new(
             listener_addr,
             discovery_v4_addr,
             secret_key,
@@ -224,6 +225,10 @@ impl NetworkManager {
             dns_discovery_config,
         )
         .await?;
+        if let Some(nat) = nat.filter(|nat| nat.supports_port_mapping()) {
+            // map the listener and discovery ports on the gateway
+            discovery.spawn_port_mapping(nat);
+        }
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
         let discv4 = discovery.discv4();
@@ -614,6 +619,9 @@ impl NetworkManager {
             NetworkHandleMessage::FetchClient(tx) => {
                 let _ = tx.send(self.fetch_client());
             }
+            NetworkHandleMessage::GetFetchPeerScores(tx) => {
+                let _ = tx.send(self.swarm.state().fetch_peer_scores());
+            }
             NetworkHandleMessage::GetStatus(tx) => {
                 let _ = tx.send(self.status());
             }
@@ -667,6 +675,10 @@ impl NetworkManager {
                     .incoming_connections
                     .set(self.swarm.state().peers().num_inbound_connections() as f64);
             }
+            SwarmEvent::IncomingTcpConnectionRejected { remote_addr, error } => {
+                trace!(target: "net", ?remote_addr, %error, "Incoming connection rejected");
+                self.metrics.on_inbound_connection_rejected(error);
+            }
             SwarmEvent::OutgoingTcpConnection { remote_addr, peer_id } => {
                 trace!(target: "net", ?remote_addr, ?peer_id, "Starting outbound connection.");
                 self.metrics.total_outgoing_connections.increment(1);
@@ -708,6 +720,14 @@ impl NetworkManager {
 
                 self.update_active_connection_metrics();
 
+                let peer_kind = self
+                    .swarm
+                    .state()
+                    .peers()
+                    .peer_by_id(peer_id)
+                    .map(|(_, kind)| kind)
+                    .unwrap_or_default();
+
                 self.event_sender.notify(NetworkEvent::SessionEstablished {
                     peer_id,
                     remote_addr,
@@ -716,6 +736,7 @@ impl NetworkManager {
                     version,
                     status,
                     messages,
+                    peer_kind,
                 });
             }
             SwarmEvent::PeerAdded(peer_id) => {
@@ -728,6 +749,10 @@ impl NetworkManager {
                 self.event_sender.notify(NetworkEvent::PeerRemoved(peer_id));
                 self.metrics.tracked_peers.set(self.swarm.state().peers().num_known_peers() as f64);
             }
+            SwarmEvent::PeerKindChanged { peer_id, kind } => {
+                trace!(target: "net", ?peer_id, ?kind, "Peer kind changed");
+                self.event_sender.notify(NetworkEvent::PeerKindChanged { peer_id, kind });
+            }
             SwarmEvent::SessionClosed { peer_id, remote_addr, error } => {
                 let total_active = self.num_active_peers.fetch_sub(1, Ordering::Relaxed) - 1;
                 self.metrics.connected_peers.set(total_active as f64);
@@ -791,7 +816,7 @@ impl NetworkManager {
                     self.swarm
                         .state_mut()
                         .peers_mut()
-                        .on_incoming_pending_session_gracefully_closed();
+                        .on_incoming_pending_session_gracefully_closed(remote_addr.ip());
                 }
                 self.metrics.closed_sessions.increment(1);
                 self.metrics
@@ -1070,11 +1095,21 @@ pub enum NetworkEvent {
         status: Arc<Status>,
         /// negotiated eth version of the session
         version: EthVersion,
+        /// The kind of the peer, e.g. whether it is trusted.
+        peer_kind: PeerKind,
     },
     /// Event emitted when a new peer is added
     PeerAdded(PeerId),
     /// Event emitted when a new peer is removed
     PeerRemoved(PeerId),
+    /// Event emitted when the kind of a peer changed, e.g. when it was added to or removed from
+    /// the trusted set
+    PeerKindChanged {
+        /// The identifier of the peer.
+        peer_id: PeerId,
+        /// The new kind of the peer.
+        kind: PeerKind,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
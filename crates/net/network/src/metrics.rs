@@ -1,3 +1,4 @@
+use crate::peers::InboundConnectionError;
 use metrics::Histogram;
 use reth_eth_wire::DisconnectReason;
 use reth_metrics::{
@@ -49,6 +50,18 @@ pub struct NetworkMetrics {
     /// Number of invalid/malformed messages received from peers
     pub(crate) invalid_messages_received: Counter,
 
+    /* ================ INBOUND REJECTIONS ================ */
+    /// Total number of incoming connections rejected because the ip address is banned
+    pub(crate) rejected_inbound_banned: Counter,
+    /// Total number of incoming connections rejected because all inbound slots are occupied
+    pub(crate) rejected_inbound_capacity: Counter,
+    /// Total number of incoming connections rejected due to the per ip limit
+    pub(crate) rejected_inbound_ip_capacity: Counter,
+    /// Total number of incoming connections rejected due to the per subnet limit
+    pub(crate) rejected_inbound_subnet_capacity: Counter,
+    /// Total number of incoming connections rejected due to the connection attempt rate limit
+    pub(crate) rejected_inbound_rate_limited: Counter,
+
     /// Number of Eth Requests dropped due to channel being at full capacity
     pub(crate) total_dropped_eth_requests_at_full_capacity: Counter,
 
@@ -77,6 +90,23 @@ pub struct NetworkMetrics {
     pub(crate) acc_duration_poll_swarm: Gauge,
 }
 
+impl NetworkMetrics {
+    /// Increments the rejection counter for the given [`InboundConnectionError`].
+    pub(crate) fn on_inbound_connection_rejected(&self, error: InboundConnectionError) {
+        match error {
+            InboundConnectionError::IpBanned => self.rejected_inbound_banned.increment(1),
+            InboundConnectionError::ExceedsCapacity => self.rejected_inbound_capacity.increment(1),
+            InboundConnectionError::ExceedsIpCapacity => {
+                self.rejected_inbound_ip_capacity.increment(1)
+            }
+            InboundConnectionError::ExceedsSubnetCapacity => {
+                self.rejected_inbound_subnet_capacity.increment(1)
+            }
+            InboundConnectionError::RateLimited => self.rejected_inbound_rate_limited.increment(1),
+        }
+    }
+}
+
 /// Metrics for `SessionManager`
 #[derive(Metrics)]
 #[metrics(scope = "network")]
@@ -122,6 +152,11 @@ pub struct TransactionsManagerMetrics {
     /// (i.e. have no chance of passing validation, unlike imports that fail due to e.g. nonce
     /// gaps).
     pub(crate) bad_imports: Counter,
+    /// Total number of transactions dropped because the peer exceeded its admission rate limit.
+    pub(crate) rate_limited_transactions: Counter,
+    /// Total number of transactions dropped because the global admission rate limit was
+    /// exceeded.
+    pub(crate) globally_rate_limited_transactions: Counter,
     /// Number of inflight requests at which the
     /// [`TransactionPool`](reth_transaction_pool::TransactionPool) is considered to be at
     /// capacity. Note, this is not a limit to the number of inflight requests, but a health
@@ -306,6 +341,22 @@ impl DisconnectMetrics {
     }
 }
 
+/// Metrics for the `StateFetcher`
+#[derive(Metrics)]
+#[metrics(scope = "network.state_fetcher")]
+pub struct StateFetcherMetrics {
+    /// Time in seconds it took peers to respond to `GetBlockHeaders` requests
+    pub(crate) headers_response_time: Histogram,
+    /// Time in seconds it took peers to respond to `GetBlockBodies` requests
+    pub(crate) bodies_response_time: Histogram,
+    /// Throughput in bytes per second of responses to `GetBlockHeaders` and `GetBlockBodies`
+    pub(crate) response_throughput: Histogram,
+    /// Total number of empty, likely bad or failed responses
+    pub(crate) empty_responses: Counter,
+    /// Number of active peers with at least one recorded response
+    pub(crate) scored_peers: Gauge,
+}
+
 /// Metrics for the `EthRequestHandler`
 #[derive(Metrics)]
 #[metrics(scope = "network")]
@@ -7,7 +7,7 @@ use crate::{
     protocol::RlpxSubProtocol,
     swarm::NetworkConnectionState,
     transactions::TransactionsHandle,
-    FetchClient,
+    FetchClient, PeerResponseScore,
 };
 use enr::Enr;
 use parking_lot::Mutex;
@@ -23,6 +23,7 @@ use reth_primitives::{Head, TransactionSigned, B256};
 use reth_tokio_util::{EventSender, EventStream};
 use secp256k1::SecretKey;
 use std::{
+    collections::HashMap,
     net::SocketAddr,
     sync::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
@@ -105,6 +106,17 @@ impl NetworkHandle {
         rx.await
     }
 
+    /// Returns the response scores of all active peers that serve header and body requests.
+    ///
+    /// These scores are used to pick the peer for the next request of the [`FetchClient`].
+    pub async fn fetch_peer_scores(
+        &self,
+    ) -> Result<HashMap<PeerId, PeerResponseScore>, oneshot::error::RecvError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::GetFetchPeerScores(tx));
+        rx.await
+    }
+
     /// Returns the mode of the network, either pow, or pos
     pub fn mode(&self) -> &NetworkMode {
         &self.inner.network_mode
@@ -465,6 +477,8 @@ pub(crate) enum NetworkHandleMessage {
     ReputationChange(PeerId, ReputationChangeKind),
     /// Returns the client that can be used to interact with the network.
     FetchClient(oneshot::Sender<FetchClient>),
+    /// Retrieves the response scores of all peers tracked by the fetcher.
+    GetFetchPeerScores(oneshot::Sender<HashMap<PeerId, PeerResponseScore>>),
     /// Applies a status update.
     StatusUpdate {
         /// The head status to apply.
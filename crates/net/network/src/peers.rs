@@ -1,6 +1,7 @@
 //! Peer related implementations
 
 use crate::{
+    connection_limits::InboundConnectionLimiter,
     error::SessionError,
     session::{Direction, PendingSessionHandshakeError},
     swarm::NetworkConnectionState,
@@ -109,6 +110,8 @@ pub struct PeersManager {
     reputation_weights: ReputationChangeWeights,
     /// Tracks current slot stats.
     connection_info: ConnectionInfo,
+    /// Enforces per IP and per subnet restrictions on inbound connections.
+    inbound_limiter: InboundConnectionLimiter,
     /// Tracks unwanted ips/peer ids.
     ban_list: BanList,
     /// Tracks currently backed off peers.
@@ -177,6 +180,7 @@ impl PeersManager {
             reputation_weights,
             refill_slots_interval: tokio::time::interval(refill_slots_interval),
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
+            inbound_limiter: InboundConnectionLimiter::new(connection_info.inbound_limits),
             connection_info: ConnectionInfo::new(connection_info),
             ban_list,
             backed_off_peers: Default::default(),
@@ -258,7 +262,10 @@ impl PeersManager {
 
     /// Invoked when a new _incoming_ tcp connection is accepted.
     ///
-    /// returns an error if the inbound ip address is on the ban list
+    /// returns an error if the inbound ip address is on the ban list or exceeds the configured
+    /// [`InboundConnectionLimits`](reth_network_types::InboundConnectionLimits).
+    ///
+    /// If the ip address exceeds the rate limit for connection attempts, it is banned temporarily.
     pub(crate) fn on_incoming_pending_session(
         &mut self,
         addr: IpAddr,
@@ -273,18 +280,31 @@ impl PeersManager {
             return Err(InboundConnectionError::ExceedsCapacity)
         }
 
+        if let Err(err) = self.inbound_limiter.try_acquire(addr) {
+            if err == InboundConnectionError::RateLimited {
+                trace!(target: "net::peers", ?addr, "banning rate limited ip");
+                self.ban_list.ban_ip_until(
+                    addr,
+                    std::time::Instant::now() + self.inbound_limiter.rate_limit_ban_duration(),
+                );
+            }
+            return Err(err)
+        }
+
         self.connection_info.inc_pending_in();
         Ok(())
     }
 
     /// Invoked when a previous call to [`Self::on_incoming_pending_session`] succeeded but it was
     /// rejected.
-    pub(crate) fn on_incoming_pending_session_rejected_internally(&mut self) {
+    pub(crate) fn on_incoming_pending_session_rejected_internally(&mut self, addr: IpAddr) {
+        self.inbound_limiter.on_pending_closed(addr);
         self.connection_info.decr_pending_in();
     }
 
     /// Invoked when a pending session was closed.
-    pub(crate) fn on_incoming_pending_session_gracefully_closed(&mut self) {
+    pub(crate) fn on_incoming_pending_session_gracefully_closed(&mut self, addr: IpAddr) {
+        self.inbound_limiter.on_pending_closed(addr);
         self.connection_info.decr_pending_in()
     }
 
@@ -303,6 +323,7 @@ impl PeersManager {
             }
         }
 
+        self.inbound_limiter.on_pending_closed(remote_addr.ip());
         self.connection_info.decr_pending_in();
     }
 
@@ -314,6 +335,7 @@ impl PeersManager {
     /// be scheduled.
     pub(crate) fn on_incoming_session_established(&mut self, peer_id: PeerId, addr: SocketAddr) {
         self.connection_info.decr_pending_in();
+        self.inbound_limiter.on_session_established(peer_id, addr.ip());
 
         // we only need to check the peer id here as the ip address will have been checked at
         // on_incoming_pending_session. We also check if the peer is in the backoff list here.
@@ -508,6 +530,7 @@ impl PeersManager {
 
     /// Gracefully disconnected an active session
     pub(crate) fn on_active_session_gracefully_closed(&mut self, peer_id: PeerId) {
+        self.inbound_limiter.on_session_closed(&peer_id);
         match self.peers.entry(peer_id) {
             Entry::Occupied(mut entry) => {
                 self.connection_info.decr_state(entry.get().state);
@@ -550,6 +573,7 @@ impl PeersManager {
         peer_id: &PeerId,
         err: &EthStreamError,
     ) {
+        self.inbound_limiter.on_session_closed(peer_id);
         self.on_connection_failure(remote_addr, peer_id, err, ReputationChangeKind::Dropped)
     }
 
@@ -654,10 +678,11 @@ impl PeersManager {
     ///
     /// If the session was an outgoing connection, this means that the peer initiated a connection
     /// to us at the same time and this connection is already established.
-    pub(crate) fn on_already_connected(&mut self, direction: Direction) {
+    pub(crate) fn on_already_connected(&mut self, direction: Direction, remote_addr: IpAddr) {
         match direction {
             Direction::Incoming => {
                 // need to decrement the ingoing counter
+                self.inbound_limiter.on_pending_closed(remote_addr);
                 self.connection_info.decr_pending_in();
             }
             Direction::Outgoing(_) => {
@@ -715,6 +740,9 @@ impl PeersManager {
         match self.peers.entry(peer_id) {
             Entry::Occupied(mut entry) => {
                 let peer = entry.get_mut();
+                if peer.kind != kind {
+                    self.queued_actions.push_back(PeerAction::PeerKindChanged { peer_id, kind });
+                }
                 peer.kind = kind;
                 peer.fork_id = fork_id;
                 peer.addr = addr;
@@ -776,6 +804,8 @@ impl PeersManager {
 
         let peer = entry.get_mut();
         peer.kind = PeerKind::Basic;
+        self.queued_actions
+            .push_back(PeerAction::PeerKindChanged { peer_id, kind: PeerKind::Basic });
 
         self.trusted_peer_ids.remove(&peer_id);
     }
@@ -898,6 +928,7 @@ impl PeersManager {
             if self.release_interval.poll_tick(cx).is_ready() {
                 let now = std::time::Instant::now();
                 let (_, unbanned_peers) = self.ban_list.evict(now);
+                self.inbound_limiter.evict_rate_limits(now);
 
                 for peer_id in unbanned_peers {
                     if let Some(peer) = self.peers.get_mut(&peer_id) {
@@ -1300,6 +1331,13 @@ pub enum PeerAction {
     PeerAdded(PeerId),
     /// Emit peerRemoved event
     PeerRemoved(PeerId),
+    /// The kind of a tracked peer changed, e.g. it was added to or removed from the trusted set.
+    PeerKindChanged {
+        /// The peer ID.
+        peer_id: PeerId,
+        /// The new kind of the peer.
+        kind: PeerKind,
+    },
 }
 
 /// Error thrown when a incoming connection is rejected right away
@@ -1309,6 +1347,12 @@ pub enum InboundConnectionError {
     IpBanned,
     /// No capacity for new inbound connections
     ExceedsCapacity,
+    /// No capacity for new inbound connections from the remote's ip address
+    ExceedsIpCapacity,
+    /// No capacity for new inbound connections from the remote's subnet
+    ExceedsSubnetCapacity,
+    /// The remote's ip address exceeded the rate limit for connection attempts
+    RateLimited,
 }
 
 impl Display for InboundConnectionError {
@@ -1336,7 +1380,10 @@ mod tests {
     use reth_net_banlist::BanList;
     use reth_network_api::{Direction, ReputationChangeKind};
     use reth_network_peers::PeerId;
-    use reth_network_types::{peers::reputation::DEFAULT_REPUTATION, BackoffKind};
+    use reth_network_types::{
+        peers::reputation::DEFAULT_REPUTATION, BackoffKind, ConnectionRateLimit,
+        InboundConnectionLimits,
+    };
     use reth_primitives::B512;
     use std::{
         collections::HashSet,
@@ -1814,7 +1861,7 @@ mod tests {
 
         assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
         assert_eq!(peers.connection_info.num_pending_in, 1);
-        peers.on_incoming_pending_session_rejected_internally();
+        peers.on_incoming_pending_session_rejected_internally(socket_addr.ip());
         assert_eq!(peers.connection_info.num_pending_in, 0);
     }
 
@@ -1825,7 +1872,7 @@ mod tests {
 
         assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
         assert_eq!(peers.connection_info.num_pending_in, 1);
-        peers.on_incoming_pending_session_gracefully_closed();
+        peers.on_incoming_pending_session_gracefully_closed(socket_addr.ip());
         assert_eq!(peers.connection_info.num_pending_in, 0);
     }
 
@@ -1998,7 +2045,7 @@ mod tests {
         // Simulate a rejection due to an already established connection, expecting the
         // `num_pending_in` to decrease by 1. The peer should remain connected and the `num_inbound`
         // should not be changed.
-        peers.on_already_connected(Direction::Incoming);
+        peers.on_already_connected(Direction::Incoming, socket_addr.ip());
 
         let p = peers.peers.get_mut(&peer).expect("peer not found");
         assert_eq!(p.addr.tcp, socket_addr);
@@ -2532,7 +2579,7 @@ mod tests {
         }
 
         peers.on_incoming_session_established(peer_id, addr);
-        peers.on_already_connected(Direction::Outgoing(peer_id));
+        peers.on_already_connected(Direction::Outgoing(peer_id), addr.ip());
         assert_eq!(peers.peers.get(&peer_id).unwrap().state, PeerConnectionState::In);
         assert_eq!(peers.connection_info.num_inbound, 1);
         assert_eq!(peers.connection_info.num_pending_out, 0);
@@ -2573,6 +2620,53 @@ mod tests {
         assert_eq!(peers.connection_info.num_outbound, 0);
     }
 
+    #[tokio::test]
+    async fn test_ban_rate_limited_incoming_ip() {
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 8008);
+        let limits = InboundConnectionLimits {
+            attempts_per_ip: Some(ConnectionRateLimit {
+                burst: 1,
+                refill_interval: Duration::from_secs(60),
+            }),
+            rate_limit_ban_duration: Duration::from_secs(60),
+            ..InboundConnectionLimits::unlimited()
+        };
+        let mut peers = PeersManager::new(PeersConfig::test().with_inbound_limits(limits));
+
+        assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
+        peers.on_incoming_pending_session_gracefully_closed(socket_addr.ip());
+
+        assert_eq!(
+            peers.on_incoming_pending_session(socket_addr.ip()).unwrap_err(),
+            InboundConnectionError::RateLimited
+        );
+        assert!(peers.ban_list.is_banned_ip(&socket_addr.ip()));
+        assert_eq!(
+            peers.on_incoming_pending_session(socket_addr.ip()).unwrap_err(),
+            InboundConnectionError::IpBanned
+        );
+        assert_eq!(peers.connection_info.num_pending_in, 0);
+    }
+
+    #[tokio::test]
+    async fn test_incoming_ip_capacity_released_on_close() {
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 8008);
+        let limits =
+            InboundConnectionLimits { max_per_ip: Some(1), ..InboundConnectionLimits::unlimited() };
+        let mut peers = PeersManager::new(PeersConfig::test().with_inbound_limits(limits));
+        let peer = PeerId::random();
+
+        assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
+        peers.on_incoming_session_established(peer, socket_addr);
+        assert_eq!(
+            peers.on_incoming_pending_session(socket_addr.ip()).unwrap_err(),
+            InboundConnectionError::ExceedsIpCapacity
+        );
+
+        peers.on_active_session_gracefully_closed(peer);
+        assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
+    }
+
     #[tokio::test]
     async fn test_max_concurrent_dials() {
         let config = PeersConfig::default();
@@ -3,7 +3,7 @@
 use crate::{
     cache::LruCache,
     discovery::{Discovery, DiscoveryEvent},
-    fetch::{BlockResponseOutcome, FetchAction, StateFetcher},
+    fetch::{BlockResponseOutcome, FetchAction, PeerResponseScore, StateFetcher},
     manager::DiscoveredEvent,
     message::{
         BlockRequest, NewBlockMessage, PeerRequest, PeerRequestSender, PeerResponse,
@@ -133,6 +133,11 @@ impl NetworkState {
         self.state_fetcher.client()
     }
 
+    /// Returns the response scores of all peers tracked by the [`StateFetcher`].
+    pub(crate) fn fetch_peer_scores(&self) -> HashMap<PeerId, PeerResponseScore> {
+        self.state_fetcher.peer_scores()
+    }
+
     /// How many peers we're currently connected to.
     pub fn num_active_peers(&self) -> usize {
         self.active_peers.len()
@@ -350,6 +355,9 @@ impl NetworkState {
             PeerAction::PeerRemoved(peer_id) => {
                 self.queued_messages.push_back(StateAction::PeerRemoved(peer_id))
             }
+            PeerAction::PeerKindChanged { peer_id, kind } => {
+                self.queued_messages.push_back(StateAction::PeerKindChanged { peer_id, kind })
+            }
             PeerAction::BanPeer { .. } | PeerAction::UnBanPeer { .. } => {}
         }
     }
@@ -540,6 +548,8 @@ pub(crate) enum StateAction {
     PeerAdded(PeerId),
     /// A peer was dropped
     PeerRemoved(PeerId),
+    /// The kind of a peer changed
+    PeerKindChanged { peer_id: PeerId, kind: PeerKind },
 }
 
 #[cfg(test)]
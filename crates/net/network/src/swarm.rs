@@ -12,6 +12,7 @@ use reth_eth_wire::{
     errors::EthStreamError,
     EthVersion, Status,
 };
+use reth_network_api::PeerKind;
 use reth_network_peers::PeerId;
 use std::{
     io,
@@ -144,7 +145,7 @@ impl Swarm {
             }
             SessionEvent::AlreadyConnected { peer_id, remote_addr, direction } => {
                 trace!(target: "net", ?peer_id, ?remote_addr, ?direction, "already connected");
-                self.state.peers_mut().on_already_connected(direction);
+                self.state.peers_mut().on_already_connected(direction, remote_addr.ip());
                 None
             }
             SessionEvent::ValidMessage { peer_id, message } => {
@@ -202,8 +203,20 @@ impl Swarm {
                         InboundConnectionError::ExceedsCapacity => {
                             trace!(target: "net", ?remote_addr, "No capacity for incoming connection");
                         }
+                        InboundConnectionError::ExceedsIpCapacity => {
+                            trace!(target: "net", ?remote_addr, "No capacity for incoming connection from this ip address");
+                        }
+                        InboundConnectionError::ExceedsSubnetCapacity => {
+                            trace!(target: "net", ?remote_addr, "No capacity for incoming connection from this subnet");
+                        }
+                        InboundConnectionError::RateLimited => {
+                            trace!(target: "net", ?remote_addr, "The incoming ip address exceeded the connection rate limit");
+                        }
                     }
-                    return None
+                    return Some(SwarmEvent::IncomingTcpConnectionRejected {
+                        remote_addr,
+                        error: err,
+                    })
                 }
 
                 match self.sessions.on_incoming(stream, remote_addr) {
@@ -215,7 +228,7 @@ impl Swarm {
                         trace!(target: "net", %err, "Incoming connection rejected, capacity already reached.");
                         self.state_mut()
                             .peers_mut()
-                            .on_incoming_pending_session_rejected_internally();
+                            .on_incoming_pending_session_rejected_internally(remote_addr.ip());
                     }
                 }
             }
@@ -243,6 +256,9 @@ impl Swarm {
             }
             StateAction::PeerAdded(peer_id) => return Some(SwarmEvent::PeerAdded(peer_id)),
             StateAction::PeerRemoved(peer_id) => return Some(SwarmEvent::PeerRemoved(peer_id)),
+            StateAction::PeerKindChanged { peer_id, kind } => {
+                return Some(SwarmEvent::PeerKindChanged { peer_id, kind })
+            }
             StateAction::DiscoveredNode { peer_id, addr, fork_id } => {
                 // Don't try to connect to peer if node is shutting down
                 if self.is_shutting_down() {
@@ -376,6 +392,14 @@ pub(crate) enum SwarmEvent {
         /// Address of the remote peer.
         remote_addr: SocketAddr,
     },
+    /// An incoming tcp connection was rejected before the session authentication process
+    /// started.
+    IncomingTcpConnectionRejected {
+        /// Address of the remote peer.
+        remote_addr: SocketAddr,
+        /// Why the connection was rejected.
+        error: InboundConnectionError,
+    },
     /// An outbound connection is initiated.
     OutgoingTcpConnection {
         /// Address of the remote peer.
@@ -403,6 +427,8 @@ pub(crate) enum SwarmEvent {
     PeerAdded(PeerId),
     /// Admin rpc: peer removed
     PeerRemoved(PeerId),
+    /// The kind of a peer changed
+    PeerKindChanged { peer_id: PeerId, kind: PeerKind },
     /// Closed an incoming pending session during authentication.
     IncomingPendingSessionClosed {
         remote_addr: SocketAddr,
@@ -3,6 +3,10 @@ use super::{
     SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
 use derive_more::Constructor;
+use reth_network_api::PeerKind;
+use reth_network_peers::PeerId;
+use reth_transaction_pool::RateLimit;
+use std::{collections::HashSet, fmt, str::FromStr};
 
 /// Configuration for managing transactions within the network.
 #[derive(Debug, Default, Clone)]
@@ -10,6 +14,134 @@ use derive_more::Constructor;
 pub struct TransactionsManagerConfig {
     /// Configuration for fetching transactions.
     pub transaction_fetcher_config: TransactionFetcherConfig,
+    /// Decides to which peers transactions are propagated, unless a custom
+    /// [`TransactionPropagationPolicy`] is set on the
+    /// [`TransactionsManager`](super::TransactionsManager).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub propagation_mode: TransactionPropagationMode,
+    /// Peers whose transactions and announcements are dropped.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ignored_peers: HashSet<PeerId>,
+    /// Max number of new transactions a peer can broadcast to the pool within an interval.
+    ///
+    /// Transactions beyond the limit are dropped and the peer is penalized. Transactions we
+    /// requested from the peer are exempt. Unlimited if `None`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub peer_admission_limit: Option<RateLimit>,
+    /// Max number of new transactions of all peers that are imported into the pool within an
+    /// interval.
+    ///
+    /// Transactions beyond the limit are dropped without penalizing the peer. Unlimited if
+    /// `None`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub global_admission_limit: Option<RateLimit>,
+}
+
+impl TransactionsManagerConfig {
+    /// Sets the [`TransactionPropagationMode`].
+    pub const fn with_propagation_mode(mut self, mode: TransactionPropagationMode) -> Self {
+        self.propagation_mode = mode;
+        self
+    }
+
+    /// Sets the max number of new transactions a peer can broadcast to the pool within an
+    /// interval.
+    pub const fn with_peer_admission_limit(mut self, limit: RateLimit) -> Self {
+        self.peer_admission_limit = Some(limit);
+        self
+    }
+
+    /// Sets the max number of new transactions of all peers that are imported into the pool
+    /// within an interval.
+    pub const fn with_global_admission_limit(mut self, limit: RateLimit) -> Self {
+        self.global_admission_limit = Some(limit);
+        self
+    }
+
+    /// Drops all transactions and announcements received from the given peers.
+    pub fn with_ignored_peers(mut self, peers: impl IntoIterator<Item = PeerId>) -> Self {
+        self.ignored_peers.extend(peers);
+        self
+    }
+}
+
+/// Decides to which peers transactions are propagated.
+///
+/// The policy is asked with the current kind of the peer every time transactions are propagated,
+/// so it follows changes to the trusted and static peers. The built-in policies are the
+/// [`TransactionPropagationMode`]s, a custom policy can be set with
+/// [`TransactionsManager::with_propagation_policy`](super::TransactionsManager::with_propagation_policy).
+pub trait TransactionPropagationPolicy: fmt::Debug + Send + Sync + Unpin + 'static {
+    /// Returns `true` if transactions can be propagated to the peer at all.
+    ///
+    /// Peers for which this returns `false` don't count towards the peers that receive full
+    /// transactions.
+    fn can_propagate_to(&self, peer_id: &PeerId, peer_kind: PeerKind) -> bool {
+        let _ = (peer_id, peer_kind);
+        true
+    }
+
+    /// Returns `true` if a transaction can be propagated to the peer.
+    ///
+    /// `is_local` is `true` if the transaction originates from this node.
+    fn can_propagate(&self, peer_id: &PeerId, peer_kind: PeerKind, is_local: bool) -> bool;
+}
+
+/// The built-in [`TransactionPropagationPolicy`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum TransactionPropagationMode {
+    /// Propagate all transactions to all peers.
+    #[default]
+    All,
+    /// Propagate transactions to trusted peers only.
+    ///
+    /// Useful for a validator node that is only connected to the network through its sentry
+    /// nodes.
+    Trusted,
+    /// Propagate local transactions to trusted peers only, and all other transactions to all
+    /// peers.
+    ///
+    /// Transactions that originate from this node are never relayed to untrusted peers.
+    LocalToTrusted,
+}
+
+impl TransactionPropagationPolicy for TransactionPropagationMode {
+    fn can_propagate_to(&self, _peer_id: &PeerId, peer_kind: PeerKind) -> bool {
+        !matches!(self, Self::Trusted) || peer_kind.is_trusted()
+    }
+
+    fn can_propagate(&self, _peer_id: &PeerId, peer_kind: PeerKind, is_local: bool) -> bool {
+        match self {
+            Self::All => true,
+            Self::Trusted => peer_kind.is_trusted(),
+            Self::LocalToTrusted => !is_local || peer_kind.is_trusted(),
+        }
+    }
+}
+
+impl fmt::Display for TransactionPropagationMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Trusted => f.write_str("trusted"),
+            Self::LocalToTrusted => f.write_str("local-to-trusted"),
+        }
+    }
+}
+
+impl FromStr for TransactionPropagationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "trusted" => Ok(Self::Trusted),
+            "local-to-trusted" => Ok(Self::LocalToTrusted),
+            _ => Err(format!("unknown transaction propagation policy: {s}")),
+        }
+    }
 }
 
 /// Configuration for fetching transactions.
@@ -33,3 +165,41 @@ impl Default for TransactionFetcherConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn propagation_mode() {
+        let peer_id = PeerId::random();
+
+        let mode = TransactionPropagationMode::All;
+        assert!(mode.can_propagate(&peer_id, PeerKind::Basic, true));
+        assert!(mode.can_propagate(&peer_id, PeerKind::Static, false));
+
+        let mode = TransactionPropagationMode::Trusted;
+        assert!(mode.can_propagate(&peer_id, PeerKind::Trusted, true));
+        assert!(!mode.can_propagate(&peer_id, PeerKind::Basic, false));
+        assert!(!mode.can_propagate(&peer_id, PeerKind::Static, true));
+        assert!(!mode.can_propagate_to(&peer_id, PeerKind::Basic));
+
+        let mode = TransactionPropagationMode::LocalToTrusted;
+        assert!(mode.can_propagate(&peer_id, PeerKind::Trusted, true));
+        assert!(mode.can_propagate(&peer_id, PeerKind::Basic, false));
+        assert!(!mode.can_propagate(&peer_id, PeerKind::Basic, true));
+        assert!(mode.can_propagate_to(&peer_id, PeerKind::Basic));
+    }
+
+    #[test]
+    fn parse_propagation_mode() {
+        for mode in [
+            TransactionPropagationMode::All,
+            TransactionPropagationMode::Trusted,
+            TransactionPropagationMode::LocalToTrusted,
+        ] {
+            assert_eq!(mode.to_string().parse::<TransactionPropagationMode>(), Ok(mode));
+        }
+        assert!("none".parse::<TransactionPropagationMode>().is_err());
+    }
+}
@@ -21,7 +21,7 @@ use reth_eth_wire::{
     PooledTransactions, RequestTxHashes, Transactions,
 };
 use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
-use reth_network_api::{Peers, ReputationChangeKind};
+use reth_network_api::{PeerKind, Peers, ReputationChangeKind};
 use reth_network_p2p::{
     error::{RequestError, RequestResult},
     sync::SyncStateProvider,
@@ -33,7 +33,7 @@ use reth_primitives::{
 use reth_tokio_util::EventStream;
 use reth_transaction_pool::{
     error::{PoolError, PoolResult},
-    GetPooledTransactionLimit, PoolTransaction, PropagateKind, PropagatedTransactions,
+    GetPooledTransactionLimit, PoolTransaction, PropagateKind, PropagatedTransactions, RateLimiter,
     TransactionPool, ValidPoolTransaction,
 };
 use std::{
@@ -57,7 +57,10 @@ pub mod constants;
 /// Component responsible for fetching transactions from [`NewPooledTransactionHashes`].
 pub mod fetcher;
 pub mod validation;
-pub use config::{TransactionFetcherConfig, TransactionsManagerConfig};
+pub use config::{
+    TransactionFetcherConfig, TransactionPropagationMode, TransactionPropagationPolicy,
+    TransactionsManagerConfig,
+};
 
 use constants::SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE;
 pub(crate) use fetcher::{FetchEvent, TransactionFetcher};
@@ -224,6 +227,14 @@ pub struct TransactionsManager<Pool> {
     bad_imports: LruCache<TxHash>,
     /// All the connected peers.
     peers: HashMap<PeerId, PeerMetadata>,
+    /// Decides to which peers transactions are propagated.
+    propagation_policy: Box<dyn TransactionPropagationPolicy>,
+    /// Peers whose transactions and announcements are dropped.
+    ignored_peers: HashSet<PeerId>,
+    /// Limits the new transactions a peer can broadcast to the pool, if configured.
+    peer_admission_limiter: Option<RateLimiter<PeerId>>,
+    /// Limits the new transactions of all peers that are imported into the pool, if configured.
+    global_admission_limiter: Option<RateLimiter<()>>,
     /// Send half for the command channel.
     ///
     /// This is kept so that a new [`TransactionsHandle`] can be created at any time.
@@ -287,6 +298,14 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
             ),
             bad_imports: LruCache::new(DEFAULT_CAPACITY_CACHE_BAD_IMPORTS),
             peers: Default::default(),
+            propagation_policy: Box::new(transactions_manager_config.propagation_mode),
+            ignored_peers: transactions_manager_config.ignored_peers,
+            peer_admission_limiter: transactions_manager_config
+                .peer_admission_limit
+                .map(RateLimiter::new),
+            global_admission_limiter: transactions_manager_config
+                .global_admission_limit
+                .map(RateLimiter::new),
             command_tx,
             command_rx: UnboundedReceiverStream::new(command_rx),
             pending_transactions: ReceiverStream::new(pending),
@@ -297,6 +316,13 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
             metrics,
         }
     }
+
+    /// Sets a custom [`TransactionPropagationPolicy`], replacing the configured
+    /// [`TransactionPropagationMode`].
+    pub fn with_propagation_policy(mut self, policy: impl TransactionPropagationPolicy) -> Self {
+        self.propagation_policy = Box::new(policy);
+        self
+    }
 }
 
 // === impl TransactionsManager ===
@@ -418,10 +444,20 @@ where
 
         // send full transactions to a fraction of the connected peers (square root of the total
         // number of connected peers)
-        let max_num_full = (self.peers.len() as f64).sqrt() as usize + 1;
+        let policy = &self.propagation_policy;
+        let num_peers = self
+            .peers
+            .iter()
+            .filter(|(peer_id, peer)| policy.can_propagate_to(peer_id, peer.peer_kind))
+            .count();
+        let max_num_full = (num_peers as f64).sqrt() as usize + 1;
 
         // Note: Assuming ~random~ order due to random state of the peers map hasher
-        for (peer_idx, (peer_id, peer)) in self.peers.iter_mut().enumerate() {
+        let peers = self
+            .peers
+            .iter_mut()
+            .filter(|(peer_id, peer)| policy.can_propagate_to(peer_id, peer.peer_kind));
+        for (peer_idx, (peer_id, peer)) in peers.enumerate() {
             // filter all transactions unknown to the peer
             let mut hashes = PooledTransactionsHashesBuilder::new(peer.version);
             let mut full_transactions = FullTransactionsBuilder::default();
@@ -430,6 +466,9 @@ where
             // transaction lists, before deciding whether or not to send full transactions to the
             // peer.
             for tx in &to_propagate {
+                if !policy.can_propagate(peer_id, peer.peer_kind, tx.is_local) {
+                    continue
+                }
                 if peer.seen_transactions.insert(tx.hash()) {
                     hashes.push(tx);
 
@@ -502,6 +541,7 @@ where
         trace!(target: "net::tx", ?peer_id, "Propagating transactions to peer");
 
         let peer = self.peers.get_mut(&peer_id)?;
+        let (policy, peer_kind) = (&self.propagation_policy, peer.peer_kind);
         let mut propagated = PropagatedTransactions::default();
 
         // filter all transactions unknown to the peer
@@ -512,6 +552,7 @@ where
             .get_all(txs)
             .into_iter()
             .filter(|tx| !tx.transaction.is_eip4844())
+            .filter(|tx| policy.can_propagate(&peer_id, peer_kind, tx.is_local()))
             .map(PropagateTransaction::new);
 
         // Iterate through the transactions to propagate and fill the hashes and full transaction
@@ -552,9 +593,17 @@ where
                 // no such peer
                 return
             };
-
-            let to_propagate: Vec<PropagateTransaction> =
-                self.pool.get_all(hashes).into_iter().map(PropagateTransaction::new).collect();
+            let peer_kind = peer.peer_kind;
+
+            let to_propagate: Vec<PropagateTransaction> = self
+                .pool
+                .get_all(hashes)
+                .into_iter()
+                .filter(|tx| {
+                    self.propagation_policy.can_propagate(&peer_id, peer_kind, tx.is_local())
+                })
+                .map(PropagateTransaction::new)
+                .collect();
 
             let mut propagated = PropagatedTransactions::default();
 
@@ -812,6 +861,11 @@ where
     fn on_network_tx_event(&mut self, event: NetworkTransactionEvent) {
         match event {
             NetworkTransactionEvent::IncomingTransactions { peer_id, msg } => {
+                if self.ignored_peers.contains(&peer_id) {
+                    trace!(target: "net::tx", ?peer_id, "dropping transactions from ignored peer");
+                    return
+                }
+
                 // ensure we didn't receive any blob transactions as these are disallowed to be
                 // broadcasted in full
 
@@ -832,6 +886,10 @@ where
                 }
             }
             NetworkTransactionEvent::IncomingPooledTransactionHashes { peer_id, msg } => {
+                if self.ignored_peers.contains(&peer_id) {
+                    trace!(target: "net::tx", ?peer_id, "dropping announcement from ignored peer");
+                    return
+                }
                 self.on_new_pooled_transaction_hashes(peer_id, msg)
             }
             NetworkTransactionEvent::GetPooledTransactions { peer_id, request, response } => {
@@ -887,11 +945,22 @@ where
                 // remove the peer
                 self.peers.remove(&peer_id);
             }
+            NetworkEvent::PeerKindChanged { peer_id, kind } => {
+                // keep the kind up to date so propagation decisions use the current classification
+                if let Some(peer) = self.peers.get_mut(&peer_id) {
+                    peer.peer_kind = kind;
+                }
+            }
             NetworkEvent::SessionEstablished {
-                peer_id, client_version, messages, version, ..
+                peer_id,
+                client_version,
+                messages,
+                version,
+                peer_kind,
+                ..
             } => {
                 // Insert a new peer into the peerset.
-                let peer = PeerMetadata::new(messages, version, client_version);
+                let peer = PeerMetadata::new(messages, version, client_version, peer_kind);
                 let peer = match self.peers.entry(peer_id) {
                     Entry::Occupied(mut entry) => {
                         entry.insert(peer);
@@ -903,13 +972,19 @@ where
                 // Send a `NewPooledTransactionHashes` to the peer with up to
                 // `SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE`
                 // transactions in the pool.
-                if self.network.is_initially_syncing() || self.network.tx_gossip_disabled() {
+                if self.network.is_initially_syncing() ||
+                    self.network.tx_gossip_disabled() ||
+                    !self.propagation_policy.can_propagate_to(&peer_id, peer_kind)
+                {
                     return
                 }
 
-                let pooled_txs = self.pool.pooled_transactions_max(
+                let mut pooled_txs = self.pool.pooled_transactions_max(
                     SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE,
                 );
+                pooled_txs.retain(|tx| {
+                    self.propagation_policy.can_propagate(&peer_id, peer_kind, tx.is_local())
+                });
                 if pooled_txs.is_empty() {
                     // do not send a message if there are no transactions in the pool
                     return
@@ -973,6 +1048,11 @@ where
 
         // tracks the quality of the given transactions
         let mut has_bad_transactions = false;
+        // number of new transactions dropped because the peer exceeded its admission limit
+        let mut num_rate_limited = 0;
+        // number of new transactions dropped because the global admission limit was exceeded
+        let mut num_globally_rate_limited = 0;
+        let now = Instant::now();
 
         // 2. filter out transactions that are invalid or already pending import
         if let Some(peer) = self.peers.get_mut(&peer_id) {
@@ -1000,6 +1080,24 @@ where
                         entry.get_mut().insert(peer_id);
                     }
                     Entry::Vacant(entry) => {
+                        // only unsolicited broadcasts count against the peer's limit, responses
+                        // to our own requests are bounded by the transaction fetcher
+                        if source.is_broadcast() &&
+                            self.peer_admission_limiter
+                                .as_mut()
+                                .is_some_and(|limiter| !limiter.check(peer_id, now))
+                        {
+                            num_rate_limited += 1;
+                            continue
+                        }
+                        if self
+                            .global_admission_limiter
+                            .as_mut()
+                            .is_some_and(|limiter| !limiter.check((), now))
+                        {
+                            num_globally_rate_limited += 1;
+                            continue
+                        }
                         if !self.bad_imports.contains(tx.hash()) {
                             // this is a new transaction that should be imported into the pool
                             let pool_transaction = <Pool::Transaction as FromRecoveredPooledTransaction>::from_recovered_pooled_transaction(tx);
@@ -1059,6 +1157,19 @@ where
             }
         }
 
+        if num_rate_limited > 0 {
+            trace!(target: "net::tx", num_txs=%num_rate_limited, ?peer_id, "Peer exceeded transaction admission limit");
+            self.metrics.rate_limited_transactions.increment(num_rate_limited);
+            // peer is spamming the pool
+            has_bad_transactions = true;
+        }
+
+        if num_globally_rate_limited > 0 {
+            // the peer isn't penalized, the limit is exceeded by the traffic of all peers
+            trace!(target: "net::tx", num_txs=%num_globally_rate_limited, ?peer_id, "Global transaction admission limit exceeded");
+            self.metrics.globally_rate_limited_transactions.increment(num_globally_rate_limited);
+        }
+
         if has_bad_transactions {
             // peer sent us invalid transactions
             self.report_peer_bad_transactions(peer_id)
@@ -1361,6 +1472,8 @@ where
 struct PropagateTransaction {
     size: usize,
     transaction: Arc<TransactionSigned>,
+    /// Whether the transaction originates from this node.
+    is_local: bool,
 }
 
 // === impl PropagateTransaction ===
@@ -1374,7 +1487,7 @@ impl PropagateTransaction {
     fn new<T: PoolTransaction>(tx: Arc<ValidPoolTransaction<T>>) -> Self {
         let size = tx.encoded_length();
         let transaction = Arc::new(tx.transaction.to_recovered_transaction().into_signed());
-        Self { size, transaction }
+        Self { size, transaction, is_local: tx.is_local() }
     }
 }
 
@@ -1496,16 +1609,24 @@ pub struct PeerMetadata {
     version: EthVersion,
     /// The peer's client version.
     client_version: Arc<str>,
+    /// The kind of the peer, e.g. whether it is trusted.
+    peer_kind: PeerKind,
 }
 
 impl PeerMetadata {
     /// Returns a new instance of [`PeerMetadata`].
-    fn new(request_tx: PeerRequestSender, version: EthVersion, client_version: Arc<str>) -> Self {
+    fn new(
+        request_tx: PeerRequestSender,
+        version: EthVersion,
+        client_version: Arc<str>,
+        peer_kind: PeerKind,
+    ) -> Self {
         Self {
             seen_transactions: LruCache::new(DEFAULT_CAPACITY_CACHE_SEEN_BY_PEER),
             request_tx,
             version,
             client_version,
+            peer_kind,
         }
     }
 }
@@ -1617,7 +1738,10 @@ mod tests {
     };
     use reth_primitives::hex;
     use reth_provider::test_utils::NoopProvider;
-    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        RateLimit,
+    };
     use secp256k1::SecretKey;
     use std::{fmt, future::poll_fn, hash};
     use tests::fetcher::TxFetchMetadata;
@@ -1662,6 +1786,7 @@ mod tests {
                 PeerRequestSender::new(peer_id, to_mock_session_tx),
                 version,
                 Arc::from(""),
+                PeerKind::Basic,
             ),
             to_mock_session_rx,
         )
@@ -1716,6 +1841,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1726,6 +1852,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -1802,6 +1929,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1812,6 +1940,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -1886,6 +2015,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1896,6 +2026,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -1977,6 +2108,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => transactions.on_network_event(NetworkEvent::SessionEstablished {
                     peer_id,
                     remote_addr,
@@ -1985,6 +2117,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 }),
                 NetworkEvent::PeerAdded(_peer_id) => continue,
                 ev => {
@@ -2132,4 +2265,131 @@ mod tests {
         assert!(tx_fetcher.hashes_pending_fetch.is_empty());
         assert_eq!(tx_fetcher.active_peers.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_propagation_policy() {
+        reth_tracing::init_test_tracing();
+
+        let mut tx_manager = new_tx_manager().await;
+        tx_manager.propagation_policy = Box::new(TransactionPropagationMode::Trusted);
+
+        let basic_peer_id = PeerId::new([1; 64]);
+        let trusted_peer_id = PeerId::new([2; 64]);
+        let (basic_peer, _basic_rx) = new_mock_session(basic_peer_id, EthVersion::Eth68);
+        let (mut trusted_peer, _trusted_rx) = new_mock_session(trusted_peer_id, EthVersion::Eth68);
+        trusted_peer.peer_kind = PeerKind::Trusted;
+        tx_manager.peers.insert(basic_peer_id, basic_peer);
+        tx_manager.peers.insert(trusted_peer_id, trusted_peer);
+
+        // random tx: <https://etherscan.io/getRawTx?tx=0x9448608d36e721ef403c53b00546068a6474d6cbab6816c3926de449898e7bce>
+        let input = hex!("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76");
+        let signed_tx = TransactionSigned::decode(&mut &input[..]).unwrap();
+        let hash = signed_tx.hash();
+        let tx = PropagateTransaction {
+            size: input.len(),
+            transaction: Arc::new(signed_tx),
+            is_local: false,
+        };
+
+        let propagated = tx_manager.propagate_transactions(vec![tx]);
+        let peers = propagated.0[&hash].iter().map(PropagateKind::peer).collect::<Vec<_>>();
+        assert_eq!(peers, vec![&trusted_peer_id]);
+        assert!(!tx_manager.peers[&basic_peer_id].seen_transactions.contains(&hash));
+    }
+
+    #[tokio::test]
+    async fn test_propagation_policy_peer_kind_changed() {
+        reth_tracing::init_test_tracing();
+
+        let mut tx_manager = new_tx_manager().await;
+        tx_manager.propagation_policy = Box::new(TransactionPropagationMode::Trusted);
+
+        let peer_id = PeerId::new([1; 64]);
+        let (peer, _rx) = new_mock_session(peer_id, EthVersion::Eth68);
+        tx_manager.peers.insert(peer_id, peer);
+
+        // random tx: <https://etherscan.io/getRawTx?tx=0x9448608d36e721ef403c53b00546068a6474d6cbab6816c3926de449898e7bce>
+        let input = hex!("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76");
+        let signed_tx = Arc::new(TransactionSigned::decode(&mut &input[..]).unwrap());
+        let hash = signed_tx.hash();
+        let new_tx = || PropagateTransaction {
+            size: input.len(),
+            transaction: signed_tx.clone(),
+            is_local: false,
+        };
+
+        let propagated = tx_manager.propagate_transactions(vec![new_tx()]);
+        assert!(propagated.0.is_empty());
+
+        // the peer is promoted to trusted after the session was established
+        tx_manager
+            .on_network_event(NetworkEvent::PeerKindChanged { peer_id, kind: PeerKind::Trusted });
+
+        let propagated = tx_manager.propagate_transactions(vec![new_tx()]);
+        let peers = propagated.0[&hash].iter().map(PropagateKind::peer).collect::<Vec<_>>();
+        assert_eq!(peers, vec![&peer_id]);
+    }
+
+    #[tokio::test]
+    async fn test_ignored_peer_transactions_dropped() {
+        reth_tracing::init_test_tracing();
+
+        let mut tx_manager = new_tx_manager().await;
+        let peer_id = PeerId::new([1; 64]);
+        tx_manager.ignored_peers.insert(peer_id);
+        let (peer, _rx) = new_mock_session(peer_id, EthVersion::Eth66);
+        tx_manager.peers.insert(peer_id, peer);
+
+        let hash = B256::from_slice(&[1; 32]);
+        let msg = NewPooledTransactionHashes::Eth66(NewPooledTransactionHashes66(vec![hash]));
+        tx_manager.on_network_tx_event(NetworkTransactionEvent::IncomingPooledTransactionHashes {
+            peer_id,
+            msg,
+        });
+
+        assert!(!tx_manager.peers[&peer_id].seen_transactions.contains(&hash));
+        assert!(tx_manager.transaction_fetcher.hashes_fetch_inflight_and_pending_fetch.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_admission_limits() {
+        reth_tracing::init_test_tracing();
+
+        let mut tx_manager = new_tx_manager().await;
+        tx_manager.peer_admission_limiter =
+            Some(RateLimiter::new(RateLimit::new(0, Duration::from_secs(60))));
+        let peer_id = PeerId::new([1; 64]);
+        let (peer, _rx) = new_mock_session(peer_id, EthVersion::Eth68);
+        tx_manager.peers.insert(peer_id, peer);
+
+        // random tx: <https://etherscan.io/getRawTx?tx=0x9448608d36e721ef403c53b00546068a6474d6cbab6816c3926de449898e7bce>
+        let input = hex!("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76");
+        let signed_tx = TransactionSigned::decode(&mut &input[..]).unwrap();
+        let hash = signed_tx.hash();
+        let txs = || {
+            PooledTransactions(vec![PooledTransactionsElement::try_from_broadcast(
+                signed_tx.clone(),
+            )
+            .unwrap()])
+        };
+
+        // broadcasts count against the peer's limit
+        tx_manager.import_transactions(peer_id, txs(), TransactionSource::Broadcast);
+        assert!(!tx_manager.transactions_by_peers.contains_key(&hash));
+        assert!(tx_manager.pool_imports.is_empty());
+
+        // requested transactions are exempt
+        tx_manager.import_transactions(peer_id, txs(), TransactionSource::Response);
+        assert!(tx_manager.transactions_by_peers[&hash].contains(&peer_id));
+        assert_eq!(tx_manager.pool_imports.len(), 1);
+
+        // the global limit applies to all transactions
+        tx_manager.transactions_by_peers.clear();
+        tx_manager.pool_imports = Default::default();
+        tx_manager.global_admission_limiter =
+            Some(RateLimiter::new(RateLimit::new(0, Duration::from_secs(60))));
+        tx_manager.import_transactions(peer_id, txs(), TransactionSource::Response);
+        assert!(!tx_manager.transactions_by_peers.contains_key(&hash));
+        assert!(tx_manager.pool_imports.is_empty());
+    }
 }
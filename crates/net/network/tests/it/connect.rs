@@ -54,7 +54,9 @@ async fn test_establish_connections() {
         let mut established = listener0.take(4);
         while let Some(ev) = established.next().await {
             match ev {
-                NetworkEvent::SessionClosed { .. } | NetworkEvent::PeerRemoved(_) => {
+                NetworkEvent::SessionClosed { .. } |
+                NetworkEvent::PeerRemoved(_) |
+                NetworkEvent::PeerKindChanged { .. } => {
                     panic!("unexpected event")
                 }
                 NetworkEvent::SessionEstablished { peer_id, .. } => {
@@ -148,6 +148,9 @@ async fn test_sending_invalid_transactions() {
             NetworkEvent::PeerRemoved(_) => {
                 panic!("unexpected PeerRemoved event")
             }
+            NetworkEvent::PeerKindChanged { .. } => {
+                panic!("unexpected PeerKindChanged event")
+            }
         }
     }
 
@@ -0,0 +1,355 @@
+//! A single `wit` protocol connection.
+
+use crate::{
+    proto::{BlockWitness, GetBlockWitness, WitnessUnavailable},
+    ExecutionWitness, WitnessMessage, WitnessProvider,
+};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
+use reth_eth_wire::multiplex::ProtocolConnection;
+use reth_network_api::PeerId;
+use reth_primitives::{BytesMut, B256};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::{Interval, MissedTickBehavior},
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{debug, trace};
+
+/// Errors returned when requesting a witness from a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum WitnessRequestError {
+    /// The peer can't serve the witness.
+    #[error("witness unavailable")]
+    Unavailable,
+    /// The connection to the peer was closed.
+    #[error("connection closed")]
+    ConnectionClosed,
+    /// The peer did not respond in time.
+    #[error("request timed out")]
+    Timeout,
+}
+
+/// Our request that is waiting for a response of the peer.
+#[derive(Debug)]
+struct InflightWitnessRequest {
+    response: oneshot::Sender<Result<ExecutionWitness, WitnessRequestError>>,
+    /// When the request times out.
+    deadline: Instant,
+}
+
+/// Commands sent from a [`WitnessPeerHandle`] to the connection.
+#[derive(Debug)]
+pub(crate) enum WitnessCommand {
+    /// Requests the witness of a block.
+    GetBlockWitness {
+        block_hash: B256,
+        response: oneshot::Sender<Result<ExecutionWitness, WitnessRequestError>>,
+    },
+}
+
+/// Handle to request witnesses from a connected peer.
+#[derive(Debug, Clone)]
+pub struct WitnessPeerHandle {
+    peer_id: PeerId,
+    to_connection: mpsc::UnboundedSender<WitnessCommand>,
+}
+
+impl WitnessPeerHandle {
+    pub(crate) const fn new(
+        peer_id: PeerId,
+        to_connection: mpsc::UnboundedSender<WitnessCommand>,
+    ) -> Self {
+        Self { peer_id, to_connection }
+    }
+
+    /// Returns the id of the peer.
+    pub const fn peer_id(&self) -> PeerId {
+        self.peer_id
+    }
+
+    /// Requests the witness of the block with the given hash from the peer.
+    pub async fn block_witness(
+        &self,
+        block_hash: B256,
+    ) -> Result<ExecutionWitness, WitnessRequestError> {
+        let (tx, rx) = oneshot::channel();
+        self.to_connection
+            .send(WitnessCommand::GetBlockWitness { block_hash, response: tx })
+            .map_err(|_| WitnessRequestError::ConnectionClosed)?;
+        rx.await.map_err(|_| WitnessRequestError::ConnectionClosed)?
+    }
+}
+
+/// A `wit` protocol connection to a peer.
+///
+/// Serves the witness requests of the peer and forwards the requests of the local
+/// [`WitnessPeerHandle`]s to the peer.
+pub struct WitnessConnection<W> {
+    conn: ProtocolConnection,
+    provider: Arc<W>,
+    commands: UnboundedReceiverStream<WitnessCommand>,
+    /// Witness requests of the peer that are currently produced.
+    serving: FuturesUnordered<BoxFuture<'static, WitnessMessage>>,
+    /// How many requests of the peer are served concurrently.
+    max_concurrent_requests: usize,
+    /// Our requests that are waiting for a response.
+    inflight_requests: HashMap<u64, InflightWitnessRequest>,
+    next_request_id: u64,
+    /// How long to wait for the response to one of our requests.
+    request_timeout: Duration,
+    /// Interval at which timed out requests are resolved.
+    timeout_interval: Interval,
+}
+
+impl<W> std::fmt::Debug for WitnessConnection<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WitnessConnection")
+            .field("serving", &self.serving.len())
+            .field("max_concurrent_requests", &self.max_concurrent_requests)
+            .field("inflight_requests", &self.inflight_requests.len())
+            .field("next_request_id", &self.next_request_id)
+            .field("request_timeout", &self.request_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: WitnessProvider> WitnessConnection<W> {
+    pub(crate) fn new(
+        conn: ProtocolConnection,
+        provider: Arc<W>,
+        commands: mpsc::UnboundedReceiver<WitnessCommand>,
+        max_concurrent_requests: usize,
+        request_timeout: Duration,
+    ) -> Self {
+        // requests are resolved at most a quarter of the timeout after their deadline
+        let period = (request_timeout / 4).max(Duration::from_millis(10));
+        let mut timeout_interval = tokio::time::interval(period);
+        timeout_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            conn,
+            provider,
+            commands: UnboundedReceiverStream::new(commands),
+            serving: Default::default(),
+            max_concurrent_requests,
+            inflight_requests: Default::default(),
+            next_request_id: 0,
+            request_timeout,
+            timeout_interval,
+        }
+    }
+
+    /// Handles a request of the peer, returns the response if it can be sent right away.
+    fn on_witness_request(&self, request: GetBlockWitness) -> Option<WitnessMessage> {
+        let GetBlockWitness { request_id, block_hash } = request;
+        if self.serving.len() >= self.max_concurrent_requests {
+            trace!(target: "net::witness", %block_hash, "Too many concurrent witness requests");
+            return Some(WitnessMessage::WitnessUnavailable(WitnessUnavailable { request_id }))
+        }
+
+        // producing the witness requires re-executing the block
+        let provider = self.provider.clone();
+        let witness = tokio::task::spawn_blocking(move || provider.block_witness(block_hash));
+        self.serving.push(
+            witness
+                .map(move |res| match res {
+                    Ok(Ok(witness)) => {
+                        WitnessMessage::BlockWitness(BlockWitness { request_id, witness })
+                    }
+                    Ok(Err(err)) => {
+                        debug!(target: "net::witness", %err, %block_hash, "Failed to produce witness");
+                        WitnessMessage::WitnessUnavailable(WitnessUnavailable { request_id })
+                    }
+                    Err(_) => WitnessMessage::WitnessUnavailable(WitnessUnavailable { request_id }),
+                })
+                .boxed(),
+        );
+        None
+    }
+
+    /// Resolves our request that the response belongs to.
+    fn on_witness_response(
+        &mut self,
+        request_id: u64,
+        response: Result<ExecutionWitness, WitnessRequestError>,
+    ) {
+        if let Some(request) = self.inflight_requests.remove(&request_id) {
+            let _ = request.response.send(response);
+        } else {
+            // this includes late responses to requests that already timed out
+            trace!(target: "net::witness", request_id, "Received unsolicited witness response");
+        }
+    }
+
+    /// Resolves our requests that exceeded their deadline.
+    fn evict_timed_out_requests(&mut self, now: Instant) {
+        let timed_out = self
+            .inflight_requests
+            .iter()
+            .filter(|(_, request)| request.deadline <= now)
+            .map(|(request_id, _)| *request_id)
+            .collect::<Vec<_>>();
+        for request_id in timed_out {
+            trace!(target: "net::witness", request_id, "Witness request timed out");
+            self.on_witness_response(request_id, Err(WitnessRequestError::Timeout));
+        }
+    }
+}
+
+impl<W: WitnessProvider> Stream for WitnessConnection<W> {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while this.timeout_interval.poll_tick(cx).is_ready() {
+            this.evict_timed_out_requests(Instant::now());
+        }
+
+        loop {
+            // send the witnesses that are ready
+            if let Poll::Ready(Some(msg)) = this.serving.poll_next_unpin(cx) {
+                return Poll::Ready(Some(msg.encoded()))
+            }
+
+            if let Poll::Ready(Some(cmd)) = this.commands.poll_next_unpin(cx) {
+                return match cmd {
+                    WitnessCommand::GetBlockWitness { block_hash, response } => {
+                        let request_id = this.next_request_id;
+                        this.next_request_id += 1;
+                        let deadline = Instant::now() + this.request_timeout;
+                        this.inflight_requests
+                            .insert(request_id, InflightWitnessRequest { response, deadline });
+                        let msg = WitnessMessage::GetBlockWitness(GetBlockWitness {
+                            request_id,
+                            block_hash,
+                        });
+                        Poll::Ready(Some(msg.encoded()))
+                    }
+                }
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return Poll::Ready(None) };
+
+            let msg = match WitnessMessage::decode_message(&mut &msg[..]) {
+                Ok(msg) => msg,
+                Err(err) => {
+                    // disconnect peers that send invalid messages
+                    debug!(target: "net::witness", %err, "Failed to decode witness message");
+                    return Poll::Ready(None)
+                }
+            };
+
+            match msg {
+                WitnessMessage::GetBlockWitness(request) => {
+                    if let Some(response) = this.on_witness_request(request) {
+                        return Poll::Ready(Some(response.encoded()))
+                    }
+                }
+                WitnessMessage::BlockWitness(BlockWitness { request_id, witness }) => {
+                    this.on_witness_response(request_id, Ok(witness));
+                }
+                WitnessMessage::WitnessUnavailable(WitnessUnavailable { request_id }) => {
+                    this.on_witness_response(request_id, Err(WitnessRequestError::Unavailable));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{WitnessError, WitnessProtoHandler, WitnessProtocolEvent};
+    use reth_network::test_utils::{Testnet, TestnetHandle};
+    use reth_primitives::Bytes;
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_transaction_pool::test_utils::TestPool;
+
+    /// Serves the configured witnesses after a delay.
+    #[derive(Debug, Default)]
+    struct MockWitnessProvider {
+        witnesses: HashMap<B256, ExecutionWitness>,
+        delay: Duration,
+    }
+
+    impl WitnessProvider for MockWitnessProvider {
+        fn block_witness(&self, block_hash: B256) -> Result<ExecutionWitness, WitnessError> {
+            std::thread::sleep(self.delay);
+            self.witnesses.get(&block_hash).cloned().ok_or(WitnessError::UnknownBlock(block_hash))
+        }
+    }
+
+    /// Connects two peers that serve witnesses of the given providers and returns the handle of
+    /// the first peer to the second peer, together with the handle to the running network.
+    async fn connected_peers(
+        provider0: MockWitnessProvider,
+        provider1: MockWitnessProvider,
+        request_timeout: Duration,
+    ) -> (TestnetHandle<MockEthProvider, TestPool>, WitnessPeerHandle) {
+        let mut net = Testnet::create_with(2, MockEthProvider::default()).await;
+
+        let (tx, mut from_peer0) = mpsc::unbounded_channel();
+        net.peers_mut()[0].add_rlpx_sub_protocol(
+            WitnessProtoHandler::new(provider0, tx).with_request_timeout(request_timeout),
+        );
+        let (tx, _from_peer1) = mpsc::unbounded_channel();
+        net.peers_mut()[1].add_rlpx_sub_protocol(
+            WitnessProtoHandler::new(provider1, tx).with_request_timeout(request_timeout),
+        );
+
+        let handle = net.spawn();
+        handle.connect_peers().await;
+
+        let WitnessProtocolEvent::Established { peer_id, handle: peer_handle, .. } =
+            from_peer0.recv().await.unwrap();
+        assert_eq!(peer_id, *handle.peers()[1].peer_id());
+        (handle, peer_handle)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn request_witness() {
+        reth_tracing::init_test_tracing();
+        let block_hash = B256::random();
+        let witness = ExecutionWitness {
+            state: vec![Bytes::from_static(&[0xc0])],
+            codes: vec![Bytes::from_static(&[0x60, 0x00])],
+            keys: vec![Bytes::copy_from_slice(block_hash.as_slice())],
+        };
+        let provider1 = MockWitnessProvider {
+            witnesses: HashMap::from([(block_hash, witness.clone())]),
+            ..Default::default()
+        };
+
+        let (_net, peer1) =
+            connected_peers(Default::default(), provider1, Duration::from_secs(10)).await;
+        assert_eq!(peer1.block_witness(block_hash).await, Ok(witness));
+        assert_eq!(
+            peer1.block_witness(B256::random()).await,
+            Err(WitnessRequestError::Unavailable)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn request_witness_timeout() {
+        reth_tracing::init_test_tracing();
+        let block_hash = B256::random();
+        let provider1 = MockWitnessProvider {
+            witnesses: HashMap::from([(block_hash, ExecutionWitness::default())]),
+            delay: Duration::from_secs(2),
+        };
+
+        let (_net, peer1) =
+            connected_peers(Default::default(), provider1, Duration::from_millis(200)).await;
+        let res = tokio::time::timeout(Duration::from_secs(1), peer1.block_witness(block_hash))
+            .await
+            .expect("request resolves before the peer responds");
+        assert_eq!(res, Err(WitnessRequestError::Timeout));
+    }
+}
@@ -0,0 +1,295 @@
+//! Produces [`ExecutionWitness`]es by re-executing blocks.
+
+use crate::ExecutionWitness;
+use reth_evm::execute::{BlockExecutionError, BlockExecutorProvider, Executor};
+use reth_primitives::{Address, BlockNumber, Bytes, B256};
+use reth_revm::{database::StateProviderDatabase, db::BundleState, witness::RecordingDatabase};
+use reth_storage_api::{
+    BlockReader, HeaderProvider, StateProofProvider, StateProviderFactory, TransactionVariant,
+};
+use reth_storage_errors::provider::ProviderError;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Default number of blocks below the tip for which witnesses are served.
+pub const DEFAULT_WITNESS_WINDOW: u64 = 128;
+
+/// Errors that can occur when producing a witness.
+#[derive(Debug, thiserror::Error)]
+pub enum WitnessError {
+    /// The block is not known.
+    #[error("unknown block {0}")]
+    UnknownBlock(B256),
+    /// The block is too old, or the genesis block.
+    #[error("block {number} is outside the served window, best block is {best}")]
+    OutsideWindow {
+        /// The number of the requested block.
+        number: BlockNumber,
+        /// The current best block number.
+        best: BlockNumber,
+    },
+    /// Failed to read from the database.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Failed to re-execute the block.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+}
+
+/// A type that produces the [`ExecutionWitness`] of a block.
+pub trait WitnessProvider: Send + Sync + 'static {
+    /// Returns the witness of the block with the given hash.
+    ///
+    /// This is potentially expensive and blocking.
+    fn block_witness(&self, block_hash: B256) -> Result<ExecutionWitness, WitnessError>;
+}
+
+/// Produces witnesses by re-executing the block on top of the state of its parent.
+///
+/// All accounts, storage slots and bytecodes that are loaded during execution are recorded, and
+/// the witness contains the proofs of all these keys in the parent state.
+///
+/// Note: the witness does not include the trie nodes that are only required to compute the new
+/// state root after removals from the trie, or the headers of the block hashes accessed by the
+/// `BLOCKHASH` opcode.
+#[derive(Debug, Clone)]
+pub struct BlockWitnessGenerator<Provider, Exec> {
+    provider: Provider,
+    executor: Exec,
+    /// How many blocks below the tip witnesses are served for.
+    window: u64,
+}
+
+impl<Provider, Exec> BlockWitnessGenerator<Provider, Exec> {
+    /// Creates a new generator that serves witnesses for the [`DEFAULT_WITNESS_WINDOW`].
+    pub const fn new(provider: Provider, executor: Exec) -> Self {
+        Self { provider, executor, window: DEFAULT_WITNESS_WINDOW }
+    }
+
+    /// Sets how many blocks below the tip witnesses are served for.
+    pub const fn with_window(mut self, window: u64) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Returns how many blocks below the tip witnesses are served for.
+    pub const fn window(&self) -> u64 {
+        self.window
+    }
+}
+
+impl<Provider, Exec> BlockWitnessGenerator<Provider, Exec>
+where
+    Provider: BlockReader + HeaderProvider + StateProviderFactory,
+    Exec: BlockExecutorProvider,
+{
+    fn generate(&self, block_hash: B256) -> Result<ExecutionWitness, WitnessError> {
+        let number = self
+            .provider
+            .block_number(block_hash)?
+            .ok_or(WitnessError::UnknownBlock(block_hash))?;
+        let best = self.provider.best_block_number()?;
+        if number == 0 || best.saturating_sub(number) > self.window {
+            return Err(WitnessError::OutsideWindow { number, best })
+        }
+
+        let block = self
+            .provider
+            .block_with_senders(block_hash.into(), TransactionVariant::WithHash)?
+            .ok_or(WitnessError::UnknownBlock(block_hash))?;
+        let total_difficulty = self
+            .provider
+            .header_td_by_number(number)?
+            .ok_or(WitnessError::UnknownBlock(block_hash))?;
+
+        // re-execute the block on top of the parent state and record all accesses
+        let state = self.provider.history_by_block_number(number - 1)?;
+        let mut db = RecordingDatabase::new(StateProviderDatabase::new(state));
+        let output = self.executor.executor(&mut db).execute((&block, total_difficulty).into())?;
+
+        let RecordingDatabase { database, accounts: accessed_accounts, storage, codes } = db;
+        let state = database.into_inner();
+
+        // accessed accounts and their accessed storage slots
+        let mut accounts = BTreeMap::<Address, BTreeSet<B256>>::new();
+        for address in accessed_accounts.into_keys() {
+            accounts.entry(address).or_default();
+        }
+        for (address, slots) in storage {
+            accounts.entry(address).or_default().extend(slots.into_keys().map(B256::from));
+        }
+
+        // the bundle contains the changed storage slots, including slots that were only written
+        for (address, account) in &output.state.state {
+            accounts
+                .entry(*address)
+                .or_default()
+                .extend(account.storage.keys().map(|slot| B256::from(*slot)));
+        }
+
+        let mut nodes = BTreeSet::new();
+        let mut keys = Vec::new();
+        for (address, slots) in accounts {
+            let slots = slots.into_iter().collect::<Vec<_>>();
+            let proof = state.proof(&BundleState::default(), address, &slots)?;
+            nodes.extend(proof.proof);
+            for storage_proof in proof.storage_proofs {
+                nodes.extend(storage_proof.proof);
+            }
+
+            keys.push(Bytes::copy_from_slice(address.as_slice()));
+            keys.extend(slots.iter().map(|slot| Bytes::copy_from_slice(slot.as_slice())));
+        }
+
+        Ok(ExecutionWitness {
+            state: nodes.into_iter().collect(),
+            codes: codes
+                .into_iter()
+                .collect::<BTreeMap<_, _>>()
+                .into_values()
+                .map(|code| code.original_bytes())
+                .collect(),
+            keys,
+        })
+    }
+}
+
+impl<Provider, Exec> WitnessProvider for BlockWitnessGenerator<Provider, Exec>
+where
+    Provider: BlockReader + HeaderProvider + StateProviderFactory + 'static,
+    Exec: BlockExecutorProvider,
+{
+    fn block_witness(&self, block_hash: B256) -> Result<ExecutionWitness, WitnessError> {
+        self.generate(block_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_blockchain_tree::noop::NoopBlockchainTree;
+    use reth_chainspec::{ChainSpec, ChainSpecBuilder, EthereumHardfork, MAINNET};
+    use reth_db_common::init::init_genesis;
+    use reth_evm::execute::BlockExecutionInput;
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        b256, constants::ETH_TO_WEI, keccak256, public_key_to_address, Block, Genesis,
+        GenesisAccount, Header, Requests, Transaction, TxEip2930, TxKind, U256,
+    };
+    use reth_provider::{
+        providers::BlockchainProvider, test_utils::create_test_provider_factory_with_chain_spec,
+        BlockWriter, ExecutionOutcome, LatestStateProviderRef,
+    };
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use secp256k1::Keypair;
+    use std::sync::Arc;
+
+    fn chain_spec(address: Address) -> Arc<ChainSpec> {
+        Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(Genesis {
+                    alloc: [(
+                        address,
+                        GenesisAccount { balance: U256::from(ETH_TO_WEI), ..Default::default() },
+                    )]
+                    .into(),
+                    ..MAINNET.genesis.clone()
+                })
+                .paris_activated()
+                .build(),
+        )
+    }
+
+    #[test]
+    fn generate_witness() {
+        let key_pair = Keypair::new_global(&mut generators::rng());
+        let sender = public_key_to_address(key_pair.public_key());
+        let recipient = Address::with_last_byte(0x42);
+        let chain_spec = chain_spec(sender);
+
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(provider_factory.clone()).unwrap();
+
+        // transfer some ETH in the first block
+        let block = Block {
+            header: Header {
+                parent_hash: chain_spec.genesis_hash(),
+                receipts_root: b256!(
+                    "d3a6acf9a244d78b33831df95d472c4128ea85bf079a1d41e32ed0b7d2244c9e"
+                ),
+                difficulty: chain_spec.fork(EthereumHardfork::Paris).ttd().expect("Paris TTD"),
+                number: 1,
+                gas_limit: 21000,
+                gas_used: 21000,
+                ..Default::default()
+            },
+            body: vec![sign_tx_with_key_pair(
+                key_pair,
+                Transaction::Eip2930(TxEip2930 {
+                    chain_id: chain_spec.chain.id(),
+                    nonce: 0,
+                    gas_limit: 21000,
+                    gas_price: 1_500_000_000,
+                    to: TxKind::Call(recipient),
+                    value: U256::from(ETH_TO_WEI / 10),
+                    ..Default::default()
+                }),
+            )],
+            ..Default::default()
+        }
+        .with_recovered_senders()
+        .unwrap();
+
+        let executor = EthExecutorProvider::ethereum(chain_spec.clone());
+        let provider = provider_factory.provider().unwrap();
+        let mut output = executor
+            .executor(StateProviderDatabase::new(LatestStateProviderRef::new(
+                provider.tx_ref(),
+                provider.static_file_provider().clone(),
+            )))
+            .execute(BlockExecutionInput { block: &block, total_difficulty: U256::ZERO })
+            .unwrap();
+        drop(provider);
+        output.state.reverts.sort();
+        let outcome = ExecutionOutcome {
+            bundle: output.state,
+            receipts: output.receipts.into(),
+            first_block: 1,
+            requests: vec![Requests(output.requests)],
+        };
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        provider_rw
+            .append_blocks_with_state(
+                vec![block.clone().seal_slow()],
+                outcome,
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let blockchain_db =
+            BlockchainProvider::new(provider_factory, Arc::new(NoopBlockchainTree::default()))
+                .unwrap();
+        let generator = BlockWitnessGenerator::new(blockchain_db, executor);
+
+        let witness = generator.block_witness(block.hash_slow()).unwrap();
+        // the witness is rooted in the state root of the parent
+        let parent_state_root = chain_spec.genesis_header().state_root;
+        assert!(witness.state.iter().any(|node| keccak256(node) == parent_state_root));
+        for address in [sender, recipient] {
+            assert!(witness.keys.contains(&Bytes::copy_from_slice(address.as_slice())));
+        }
+        assert!(witness.codes.is_empty());
+
+        // the genesis block and unknown blocks are not served
+        assert!(matches!(
+            generator.block_witness(chain_spec.genesis_hash()),
+            Err(WitnessError::OutsideWindow { number: 0, best: 1 })
+        ));
+        assert!(matches!(
+            generator.block_witness(B256::random()),
+            Err(WitnessError::UnknownBlock(_))
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+//! Protocol and connection handlers of the `wit` protocol.
+
+use crate::{connection::WitnessConnection, WitnessMessage, WitnessPeerHandle, WitnessProvider};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol,
+};
+use reth_network::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use reth_network_api::{Direction, PeerId};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+
+/// Default number of witness requests of a peer that are served concurrently.
+pub const DEFAULT_MAX_CONCURRENT_WITNESS_REQUESTS: usize = 2;
+
+/// Default time to wait for a peer to respond to a witness request.
+///
+/// Peers produce the witness by re-executing the block, so this is more generous than the
+/// timeout of `eth` requests.
+pub const DEFAULT_WITNESS_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Events emitted by the `wit` protocol.
+#[derive(Debug)]
+pub enum WitnessProtocolEvent {
+    /// A connection that supports the `wit` protocol was established.
+    Established {
+        /// The direction of the connection.
+        direction: Direction,
+        /// The peer the connection was established with.
+        peer_id: PeerId,
+        /// Handle to request witnesses from the peer.
+        handle: WitnessPeerHandle,
+    },
+}
+
+/// The protocol handler of the `wit` protocol.
+///
+/// Serves the witnesses produced by the [`WitnessProvider`] and emits a
+/// [`WitnessProtocolEvent::Established`] for every connection, which can be used to request
+/// witnesses from the peer.
+#[derive(Debug)]
+pub struct WitnessProtoHandler<W> {
+    provider: Arc<W>,
+    events: mpsc::UnboundedSender<WitnessProtocolEvent>,
+    max_concurrent_requests: usize,
+    request_timeout: Duration,
+}
+
+impl<W> WitnessProtoHandler<W> {
+    /// Creates a new handler that serves the witnesses of the given provider.
+    pub fn new(provider: W, events: mpsc::UnboundedSender<WitnessProtocolEvent>) -> Self {
+        Self {
+            provider: Arc::new(provider),
+            events,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_WITNESS_REQUESTS,
+            request_timeout: DEFAULT_WITNESS_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Sets the number of witness requests of a peer that are served concurrently.
+    ///
+    /// Additional requests are answered as unavailable.
+    pub const fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Sets how long to wait for a peer to respond to a witness request.
+    ///
+    /// Requests that are not answered in time fail with [`WitnessRequestError::Timeout`].
+    ///
+    /// [`WitnessRequestError::Timeout`]: crate::WitnessRequestError::Timeout
+    pub const fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    fn connection_handler(&self) -> WitnessConnectionHandler<W> {
+        WitnessConnectionHandler {
+            provider: self.provider.clone(),
+            events: self.events.clone(),
+            max_concurrent_requests: self.max_concurrent_requests,
+            request_timeout: self.request_timeout,
+        }
+    }
+}
+
+impl<W> ProtocolHandler for WitnessProtoHandler<W>
+where
+    W: WitnessProvider + std::fmt::Debug,
+{
+    type ConnectionHandler = WitnessConnectionHandler<W>;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// The connection handler of the `wit` protocol.
+#[derive(Debug)]
+pub struct WitnessConnectionHandler<W> {
+    provider: Arc<W>,
+    events: mpsc::UnboundedSender<WitnessProtocolEvent>,
+    max_concurrent_requests: usize,
+    request_timeout: Duration,
+}
+
+impl<W: WitnessProvider> ConnectionHandler for WitnessConnectionHandler<W> {
+    type Connection = WitnessConnection<W>;
+
+    fn protocol(&self) -> Protocol {
+        WitnessMessage::protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handle = WitnessPeerHandle::new(peer_id, tx);
+        self.events.send(WitnessProtocolEvent::Established { direction, peer_id, handle }).ok();
+        WitnessConnection::new(
+            conn,
+            self.provider,
+            rx,
+            self.max_concurrent_requests,
+            self.request_timeout,
+        )
+    }
+}
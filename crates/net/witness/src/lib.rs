@@ -0,0 +1,35 @@
+//! `RLPx` subprotocol that serves execution witnesses of recent blocks.
+//!
+//! A witness contains everything a stateless client needs to re-execute a block on top of the
+//! parent's state root: the accessed trie nodes, the bytecodes of the called contracts and the
+//! preimages of the accessed keys.
+//!
+//! Witnesses are produced by re-executing the block on top of the parent state, see
+//! [`BlockWitnessGenerator`], and are served only for blocks within a configurable window of the
+//! chain tip.
+//!
+//! The protocol is installed on the network by adding a [`WitnessProtoHandler`] via
+//! [`NetworkProtocols::add_rlpx_sub_protocol`](reth_network::NetworkProtocols::add_rlpx_sub_protocol).
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod connection;
+pub use connection::{WitnessConnection, WitnessPeerHandle, WitnessRequestError};
+
+mod generator;
+pub use generator::{BlockWitnessGenerator, WitnessError, WitnessProvider, DEFAULT_WITNESS_WINDOW};
+
+mod handler;
+pub use handler::{
+    WitnessConnectionHandler, WitnessProtoHandler, WitnessProtocolEvent,
+    DEFAULT_MAX_CONCURRENT_WITNESS_REQUESTS, DEFAULT_WITNESS_REQUEST_TIMEOUT,
+};
+
+pub mod proto;
+pub use proto::{ExecutionWitness, WitnessMessage};
@@ -0,0 +1,191 @@
+//! Messages of the `wit` protocol.
+//!
+//! Every message is encoded as its message id followed by the RLP encoded message body,
+//! following the [RLPx specs](https://github.com/ethereum/devp2p/blob/master/rlpx.md).
+
+use alloy_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use reth_eth_wire::{protocol::Protocol, Capability};
+use reth_primitives::{Buf, BufMut, Bytes, BytesMut, B256};
+
+/// The execution witness of a block.
+///
+/// Contains everything that is required to execute the block on top of the state root of its
+/// parent.
+#[derive(Clone, Debug, Default, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct ExecutionWitness {
+    /// RLP encoded account and storage trie nodes of the parent state that were accessed.
+    pub state: Vec<Bytes>,
+    /// Bytecodes of the contracts that were loaded.
+    pub codes: Vec<Bytes>,
+    /// Preimages of the accessed trie keys, i.e. account addresses and storage slots.
+    pub keys: Vec<Bytes>,
+}
+
+impl ExecutionWitness {
+    /// Returns the approximate size of the witness in bytes.
+    pub fn size(&self) -> usize {
+        self.state.iter().chain(&self.codes).chain(&self.keys).map(|bytes| bytes.len()).sum()
+    }
+}
+
+/// Requests the [`ExecutionWitness`] of a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct GetBlockWitness {
+    /// Identifies the request, echoed in the response.
+    pub request_id: u64,
+    /// The hash of the block.
+    pub block_hash: B256,
+}
+
+/// Response to a [`GetBlockWitness`] request.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct BlockWitness {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The witness of the requested block.
+    pub witness: ExecutionWitness,
+}
+
+/// Response to a [`GetBlockWitness`] request if the witness can't be served, for example because
+/// the block is unknown or not within the served window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct WitnessUnavailable {
+    /// The id of the request.
+    pub request_id: u64,
+}
+
+/// Message ids of the `wit` protocol.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WitnessMessageId {
+    /// [`GetBlockWitness`]
+    GetBlockWitness = 0x00,
+    /// [`BlockWitness`]
+    BlockWitness = 0x01,
+    /// [`WitnessUnavailable`]
+    WitnessUnavailable = 0x02,
+}
+
+impl WitnessMessageId {
+    /// The number of message ids reserved by the protocol.
+    pub const COUNT: u8 = 3;
+}
+
+impl TryFrom<u8> for WitnessMessageId {
+    type Error = alloy_rlp::Error;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            0x00 => Ok(Self::GetBlockWitness),
+            0x01 => Ok(Self::BlockWitness),
+            0x02 => Ok(Self::WitnessUnavailable),
+            _ => Err(alloy_rlp::Error::Custom("unknown witness message id")),
+        }
+    }
+}
+
+/// All messages of the `wit` protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WitnessMessage {
+    /// Requests the witness of a block.
+    GetBlockWitness(GetBlockWitness),
+    /// The witness of a requested block.
+    BlockWitness(BlockWitness),
+    /// The requested witness is not available.
+    WitnessUnavailable(WitnessUnavailable),
+}
+
+impl WitnessMessage {
+    /// Returns the capability of the `wit` protocol.
+    pub const fn capability() -> Capability {
+        Capability::new_static("wit", 1)
+    }
+
+    /// Returns the `wit` protocol.
+    pub const fn protocol() -> Protocol {
+        Protocol::new(Self::capability(), WitnessMessageId::COUNT)
+    }
+
+    /// Returns the id of the message.
+    pub const fn message_id(&self) -> WitnessMessageId {
+        match self {
+            Self::GetBlockWitness(_) => WitnessMessageId::GetBlockWitness,
+            Self::BlockWitness(_) => WitnessMessageId::BlockWitness,
+            Self::WitnessUnavailable(_) => WitnessMessageId::WitnessUnavailable,
+        }
+    }
+
+    /// Returns the id of the request this message belongs to.
+    pub const fn request_id(&self) -> u64 {
+        match self {
+            Self::GetBlockWitness(msg) => msg.request_id,
+            Self::BlockWitness(msg) => msg.request_id,
+            Self::WitnessUnavailable(msg) => msg.request_id,
+        }
+    }
+
+    /// Encodes the message id followed by the RLP encoded message.
+    pub fn encoded(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+        buf.put_u8(self.message_id() as u8);
+        match self {
+            Self::GetBlockWitness(msg) => msg.encode(&mut buf),
+            Self::BlockWitness(msg) => msg.encode(&mut buf),
+            Self::WitnessUnavailable(msg) => msg.encode(&mut buf),
+        }
+        buf
+    }
+
+    /// Decodes a message from the given buffer.
+    pub fn decode_message(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        if buf.is_empty() {
+            return Err(alloy_rlp::Error::InputTooShort)
+        }
+        let id = WitnessMessageId::try_from(buf[0])?;
+        buf.advance(1);
+        let msg = match id {
+            WitnessMessageId::GetBlockWitness => {
+                Self::GetBlockWitness(GetBlockWitness::decode(buf)?)
+            }
+            WitnessMessageId::BlockWitness => Self::BlockWitness(BlockWitness::decode(buf)?),
+            WitnessMessageId::WitnessUnavailable => {
+                Self::WitnessUnavailable(WitnessUnavailable::decode(buf)?)
+            }
+        };
+        Ok(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(msg: WitnessMessage) {
+        let encoded = msg.encoded();
+        let decoded = WitnessMessage::decode_message(&mut &encoded[..]).unwrap();
+        assert_eq!(msg, decoded);
+    }
+
+    #[test]
+    fn encode_decode_messages() {
+        roundtrip(WitnessMessage::GetBlockWitness(GetBlockWitness {
+            request_id: 1,
+            block_hash: B256::random(),
+        }));
+        roundtrip(WitnessMessage::BlockWitness(BlockWitness {
+            request_id: 2,
+            witness: ExecutionWitness {
+                state: vec![Bytes::from_static(&[0xc0]), Bytes::from_static(&[1, 2, 3])],
+                codes: vec![Bytes::from_static(&[0x60, 0x00])],
+                keys: vec![Bytes::from_static(&[0xaa; 20])],
+            },
+        }));
+        roundtrip(WitnessMessage::WitnessUnavailable(WitnessUnavailable { request_id: 3 }));
+    }
+
+    #[test]
+    fn decode_unknown_message() {
+        assert!(WitnessMessage::decode_message(&mut &[0x05, 0xc0][..]).is_err());
+        assert!(WitnessMessage::decode_message(&mut &[][..]).is_err());
+    }
+}
@@ -30,7 +30,10 @@ use reth_node_core::{
 use reth_primitives::revm_primitives::EnvKzgSettings;
 use reth_provider::{providers::BlockchainProvider, ChainSpecProvider};
 use reth_tasks::TaskExecutor;
-use reth_transaction_pool::{PoolConfig, TransactionPool};
+use reth_transaction_pool::{
+    journal::TransactionJournalConfig, maintain::LocalTransactionBackupConfig, PoolConfig,
+    TransactionPool,
+};
 use secp256k1::SecretKey;
 pub use states::*;
 use std::sync::Arc;
@@ -463,8 +466,59 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     }
 
     /// Returns the transaction pool config of the node.
+    ///
+    /// The transaction ordering also takes the `[txpool]` section of the reth config into account.
     pub fn pool_config(&self) -> PoolConfig {
-        self.config().txpool.pool_config()
+        let txpool = &self.config().txpool;
+        PoolConfig {
+            ordering: txpool.ordering_config(&self.reth_config().txpool),
+            ..txpool.pool_config()
+        }
+    }
+
+    /// Returns the transaction pool journal config of the node, if the journal is enabled.
+    pub fn pool_journal_config(&self) -> Option<TransactionJournalConfig> {
+        let data_dir = self.config().datadir();
+        self.config()
+            .txpool
+            .journal_config(data_dir.txpool_journal(), data_dir.txpool_transactions())
+    }
+
+    /// Spawns the task that persists the transactions of the pool across restarts.
+    ///
+    /// This is the transaction journal if it is enabled, otherwise the backup of local
+    /// transactions.
+    pub fn spawn_pool_persistence_task<Pool>(&self, pool: Pool)
+    where
+        Pool: TransactionPool + Clone + 'static,
+    {
+        if let Some(journal_config) = self.pool_journal_config() {
+            // the journal includes the local transactions
+            self.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                "transaction journal task",
+                |shutdown| {
+                    reth_transaction_pool::journal::transaction_journal_task(
+                        shutdown,
+                        pool,
+                        journal_config,
+                    )
+                },
+            );
+        } else {
+            let transactions_backup_config = LocalTransactionBackupConfig::with_local_txs_backup(
+                self.config().datadir().txpool_transactions(),
+            );
+            self.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                "local transactions backup task",
+                |shutdown| {
+                    reth_transaction_pool::maintain::backup_local_transactions_task(
+                        shutdown,
+                        pool,
+                        transactions_backup_config,
+                    )
+                },
+            );
+        }
     }
 
     /// Loads `EnvKzgSettings::Default`.
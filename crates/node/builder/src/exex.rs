@@ -10,6 +10,9 @@ pub trait LaunchExEx<Node: FullNodeComponents>: Send {
     ///
     /// The `ExEx` should be able to run independently and emit events on the channels provided in
     /// the [`ExExContext`].
+    ///
+    /// The `ExEx` can declare the last block it processed with [`ExExContext::set_head`] before
+    /// the returned future resolves.
     fn launch(
         self,
         ctx: ExExContext<Node>,
@@ -29,8 +29,9 @@ use reth_node_core::{
 };
 use reth_primitives::{BlockNumber, Head, B256};
 use reth_provider::{
-    providers::{BlockchainProvider, StaticFileProvider},
-    CanonStateNotificationSender, ProviderFactory, StaticFileProviderFactory,
+    providers::{BlockchainProvider, RevertCheckpoints, StaticFileProvider},
+    BlockNumReader, CanonStateNotificationSender, CanonStateSubscriptions, ProviderFactory,
+    ProviderResult, StaticFileProviderFactory,
 };
 use reth_prune::{PruneModes, PrunerBuilder};
 use reth_rpc_builder::config::RethRpcServerConfig;
@@ -41,6 +42,7 @@ use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, error, info, warn};
 use std::{marker::PhantomData, sync::Arc, thread::available_parallelism};
 use tokio::sync::{
+    broadcast::error::RecvError,
     mpsc::{unbounded_channel, Receiver, UnboundedSender},
     oneshot, watch,
 };
@@ -360,7 +362,9 @@ where
     /// between the database and static files. **It may execute a pipeline unwind if it fails this
     /// check.**
     pub async fn create_provider_factory(&self) -> eyre::Result<ProviderFactory<DB>> {
-        let factory = ProviderFactory::new(
+        self.prune_modes().validate()?;
+
+        let mut factory = ProviderFactory::new(
             self.right().clone(),
             self.chain_spec(),
             StaticFileProvider::read_write(self.data_dir().static_files())?,
@@ -368,6 +372,16 @@ where
         .with_prune_modes(self.prune_modes())
         .with_static_files_metrics();
 
+        if let Some(interval) = self.node_config().rpc.rpc_proof_checkpoint_interval {
+            info!(target: "reth::cli", interval, "Enabling historical revert checkpoints");
+            factory = factory.with_revert_checkpoints(RevertCheckpoints::new(interval));
+        }
+
+        if self.node_config().pruning.archive_trie {
+            info!(target: "reth::cli", "Enabling archive-trie mode");
+            factory = factory.with_archive_trie(true);
+        }
+
         let has_receipt_pruning =
             self.toml_config().prune.as_ref().map_or(false, |a| a.has_receipts_pruning());
 
@@ -632,7 +646,8 @@ where
             self.provider_factory().clone(),
             consensus.clone(),
             components.block_executor().clone(),
-        );
+        )
+        .with_sparse_state_root(self.node_config().debug.sparse_state_root);
         let tree = BlockchainTree::new(tree_externals, *self.tree_config(), self.prune_modes())?
             .with_sync_metrics_tx(self.sync_metrics_tx())
             // Note: This is required because we need to ensure that both the components and the
@@ -741,6 +756,43 @@ where
     pub const fn components(&self) -> &CB::Components {
         &self.node_adapter().components
     }
+
+    /// Spawns a task that creates the revert checkpoints of the recent blocks whenever the
+    /// canonical chain changes, if they're enabled.
+    pub fn spawn_revert_checkpoints(&self) {
+        let Some(checkpoints) = self.provider_factory().revert_checkpoints().cloned() else {
+            return
+        };
+        let factory = self.provider_factory().clone();
+        let mut canon_state_notifications = self.blockchain_db().subscribe_to_canonical_state();
+
+        self.task_executor().spawn(async move {
+            loop {
+                let factory = factory.clone();
+                let checkpoints = checkpoints.clone();
+                let result = tokio::task::spawn_blocking(move || -> ProviderResult<()> {
+                    let provider = factory.provider()?;
+                    let tip = provider.best_block_number()?;
+                    Ok(checkpoints.build(provider.tx_ref(), tip)?)
+                })
+                .await;
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(err)) => {
+                        warn!(target: "reth::cli", %err, "Failed to create revert checkpoints")
+                    }
+                    Err(err) => {
+                        warn!(target: "reth::cli", %err, "Revert checkpoints task panicked")
+                    }
+                }
+
+                match canon_state_notifications.recv().await {
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
 }
 
 /// Joins two attachments together.
@@ -858,8 +910,10 @@ mod tests {
     fn test_save_prune_config() {
         with_tempdir("prune-store-test", |config_path| {
             let mut reth_config = Config::default();
-            let node_config =
-                NodeConfig { pruning: PruningArgs { full: true }, ..NodeConfig::test() };
+            let node_config = NodeConfig {
+                pruning: PruningArgs { full: true, ..Default::default() },
+                ..NodeConfig::test()
+            };
             LaunchContext::save_pruning_config_if_full_node(
                 &mut reth_config,
                 &node_config,
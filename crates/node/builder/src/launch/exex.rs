@@ -2,12 +2,13 @@
 
 use crate::{common::WithConfigs, exex::BoxedLaunchExEx};
 use futures::future;
-use reth_exex::{ExExContext, ExExHandle, ExExManager, ExExManagerHandle};
+use reth_exex::{BackfillJobFactory, ExExContext, ExExHandle, ExExManager, ExExManagerHandle, Wal};
 use reth_node_api::FullNodeComponents;
 use reth_primitives::Head;
 use reth_provider::CanonStateSubscriptions;
-use reth_tracing::tracing::{debug, info};
+use reth_tracing::tracing::{debug, info, warn};
 use std::{fmt, fmt::Debug};
+use tokio::sync::broadcast::error::RecvError;
 
 /// Can launch execution extensions.
 pub struct ExExLauncher<Node: FullNodeComponents> {
@@ -32,12 +33,16 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
     ///
     /// Spawns all extensions and returns the handle to the exex manager if any extensions are
     /// installed.
-    pub async fn launch(self) -> Option<ExExManagerHandle> {
+    ///
+    /// The manager replays the notifications in the write-ahead log that the extensions didn't
+    /// finish before the last shutdown, and backfills the blocks of extensions that declared a
+    /// head below the node's tip while they were launched.
+    pub async fn launch(self) -> eyre::Result<Option<ExExManagerHandle>> {
         let Self { head, extensions, components, config_container } = self;
 
         if extensions.is_empty() {
             // nothing to launch
-            return None
+            return Ok(None)
         }
 
         let mut exex_handles = Vec::with_capacity(extensions.len());
@@ -46,7 +51,6 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
         for (id, exex) in extensions {
             // create a new exex handle
             let (handle, events, notifications) = ExExHandle::new(id.clone());
-            exex_handles.push(handle);
 
             // create the launch context for the exex
             let context = ExExContext {
@@ -56,8 +60,11 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
                 components: components.clone(),
                 events,
                 notifications,
+                backfill: handle.backfill_state(),
             };
 
+            exex_handles.push(handle);
+
             let executor = components.task_executor().clone();
             exexs.push(async move {
                 debug!(target: "reth::cli", id, "spawning exex");
@@ -80,22 +87,41 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
 
         future::join_all(exexs).await;
 
+        // subscribe to the blockchain tree notifications before the backfills read the node's
+        // tip, so that no block is missed in between
+        let mut canon_state_notifications = components.provider().subscribe_to_canonical_state();
+
         // spawn exex manager
         debug!(target: "reth::cli", "spawning exex manager");
+        let wal = Wal::open(config_container.config.datadir().exex_wal())?;
+        let backfill = BackfillJobFactory::new_from_components(components.clone())
+            .with_thresholds(config_container.toml_config.stages.execution.into());
         // todo(onbjerg): rm magic number
-        let exex_manager = ExExManager::new(exex_handles, 1024);
+        let exex_manager =
+            ExExManager::new(exex_handles, 1024).with_wal(wal)?.with_backfill(backfill);
         let exex_manager_handle = exex_manager.handle();
         components.task_executor().spawn_critical("exex manager", async move {
             exex_manager.await.expect("exex manager crashed");
         });
 
         // send notifications from the blockchain tree to exex manager
-        let mut canon_state_notifications = components.provider().subscribe_to_canonical_state();
         let mut handle = exex_manager_handle.clone();
         components.task_executor().spawn_critical(
             "exex manager blockchain tree notifications",
             async move {
-                while let Ok(notification) = canon_state_notifications.recv().await {
+                loop {
+                    let notification = match canon_state_notifications.recv().await {
+                        Ok(notification) => notification,
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!(
+                                target: "reth::cli",
+                                %skipped,
+                                "ExEx manager lagged behind the blockchain tree notifications"
+                            );
+                            continue
+                        }
+                        Err(RecvError::Closed) => break,
+                    };
                     handle
                         .send_async(notification.into())
                         .await
@@ -106,7 +132,7 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
 
         info!(target: "reth::cli", "ExEx Manager started");
 
-        Some(exex_manager_handle)
+        Ok(Some(exex_manager_handle))
     }
 }
 
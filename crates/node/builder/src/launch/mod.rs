@@ -130,6 +130,9 @@ where
             .with_blockchain_db::<T>()?
             .with_components(components_builder, on_component_initialized).await?;
 
+        // create the revert checkpoints for historical proofs ahead of use
+        ctx.spawn_revert_checkpoints();
+
         // spawn exexs
         let exex_manager_handle = ExExLauncher::new(
             ctx.head(),
@@ -138,7 +141,7 @@ where
             ctx.configs().clone(),
         )
         .launch()
-        .await;
+        .await?;
 
         // create pipeline
         let network_client = ctx.components().network().fetch_client().await?;
@@ -239,6 +242,7 @@ where
         let pruner = pruner_builder.build_with_provider_factory(ctx.provider_factory().clone());
 
         let pruner_events = pruner.events();
+        let pruner_progress_events = pruner.events();
         info!(target: "reth::cli", prune_config=?ctx.prune_config().unwrap_or_default(), "Pruner initialized");
         hooks.add(PruneHook::new(pruner, Box::new(ctx.task_executor().clone())));
 
@@ -309,11 +313,19 @@ where
             ctx.node_adapter().clone(),
             engine_api,
             ctx.node_config(),
+            ctx.prune_modes(),
             jwt_secret,
             rpc,
         )
         .await?;
 
+        // report the progress of the pruner via `reth_pruneStatus`
+        let pruner_progress = rpc_registry.pruner_progress().clone();
+        ctx.task_executor().spawn(Box::pin(pruner_progress_events.for_each(move |event| {
+            pruner_progress.update(event.into());
+            futures::future::ready(())
+        })));
+
         // in dev mode we generate 20 random dev-signer accounts
         if ctx.is_dev() {
             rpc_registry.eth_api().with_dev_accounts();
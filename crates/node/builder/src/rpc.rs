@@ -10,6 +10,7 @@ use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::{node_config::NodeConfig, rpc::api::EngineApiServer};
 use reth_payload_builder::PayloadBuilderHandle;
+use reth_prune::PruneModes;
 use reth_rpc::eth::EthApi;
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
@@ -255,6 +256,7 @@ pub(crate) async fn launch_rpc_servers<Node, Engine>(
     node: Node,
     engine_api: Engine,
     config: &NodeConfig,
+    prune_modes: PruneModes,
     jwt_secret: JwtSecret,
     hooks: RpcHooks<Node>,
 ) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node>)>
@@ -265,7 +267,14 @@ where
     let RpcHooks { on_rpc_started, extend_rpc_modules } = hooks;
 
     let auth_config = config.rpc.auth_server_config(jwt_secret)?;
-    let module_config = config.rpc.transport_rpc_module_config();
+    let mut module_config = config.rpc.transport_rpc_module_config();
+    if let Some(module_config) = module_config.config_mut() {
+        module_config.eth_mut().prune_modes = prune_modes;
+        // proofs at old blocks read the archived trie, so the window isn't capped
+        if config.pruning.archive_trie {
+            module_config.eth_mut().eth_proof_window = config.rpc.rpc_eth_proof_window;
+        }
+    }
     debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
     let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
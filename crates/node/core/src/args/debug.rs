@@ -55,6 +55,11 @@ pub struct DebugArgs {
     /// will be written to specified location.
     #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
     pub engine_api_store: Option<PathBuf>,
+
+    /// Validate the state roots of new blocks with sparse tries revealed from proofs of the
+    /// changed state, instead of walking the database tries.
+    #[arg(long = "debug.sparse-state-root", help_heading = "Debug")]
+    pub sparse_state_root: bool,
 }
 
 #[cfg(test)]
@@ -40,6 +40,10 @@ pub use gas_price_oracle::GasPriceOracleArgs;
 mod txpool;
 pub use txpool::TxPoolArgs;
 
+/// UserOperationPoolArgs for configuring the ERC-4337 user operation pool
+mod user_operation_pool;
+pub use user_operation_pool::UserOperationPoolArgs;
+
 /// DevArgs for configuring the dev testnet
 mod dev;
 pub use dev::DevArgs;
@@ -12,19 +12,21 @@ use reth_discv5::{
 use reth_net_nat::NatResolver;
 use reth_network::{
     transactions::{
-        TransactionFetcherConfig, TransactionsManagerConfig,
+        TransactionFetcherConfig, TransactionPropagationMode, TransactionsManagerConfig,
         DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
         SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
     },
     HelloMessageWithProtocols, NetworkConfigBuilder, SessionsConfig,
 };
-use reth_network_peers::{mainnet_nodes, TrustedPeer};
+use reth_network_peers::{mainnet_nodes, PeerId, TrustedPeer};
+use reth_transaction_pool::RateLimit;
 use secp256k1::SecretKey;
 use std::{
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
     ops::Not,
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 
 /// Parameters for configuring the network more granularity via CLI
@@ -76,7 +78,7 @@ pub struct NetworkArgs {
     #[arg(long, verbatim_doc_comment)]
     pub no_persist_peers: bool,
 
-    /// NAT resolution method (any|none|upnp|publicip|extip:\<IP\>)
+    /// NAT resolution method (any|none|upnp|natpmp|pcp|publicip|extip:\<IP\>)
     #[arg(long, default_value = "any")]
     pub nat: NatResolver,
 
@@ -96,6 +98,15 @@ pub struct NetworkArgs {
     #[arg(long)]
     pub max_inbound_peers: Option<usize>,
 
+    /// Maximum number of inbound peers connected from a single IP address. default: 5
+    #[arg(long)]
+    pub max_inbound_peers_per_ip: Option<usize>,
+
+    /// Maximum number of inbound peers connected from a single /24 (IPv4) or /64 (IPv6) subnet.
+    /// default: 10
+    #[arg(long)]
+    pub max_inbound_peers_per_subnet: Option<usize>,
+
     /// Experimental, for usage in research. Sets the max accumulated byte size of transactions
     /// to pack in one response.
     /// Spec'd at 2MiB.
@@ -115,6 +126,29 @@ pub struct NetworkArgs {
     /// Default is 128 KiB.
     #[arg(long = "pooled-tx-pack-soft-limit", value_name = "BYTES", default_value_t = DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ, verbatim_doc_comment)]
     pub soft_limit_byte_size_pooled_transactions_response_on_pack_request: usize,
+
+    /// Decides to which peers transactions are propagated (all|trusted|local-to-trusted).
+    ///
+    /// `trusted` propagates transactions to trusted peers only, `local-to-trusted` never relays
+    /// local transactions to untrusted peers.
+    #[arg(long = "tx-propagation-policy", default_value_t = TransactionPropagationMode::All)]
+    pub tx_propagation_policy: TransactionPropagationMode,
+
+    /// Comma separated peer IDs whose transactions and transaction announcements are dropped.
+    #[arg(long = "tx-ignore-peers", value_name = "PEER_IDS", value_delimiter = ',')]
+    pub tx_ignored_peers: Vec<PeerId>,
+
+    /// Max number of new transactions a peer can broadcast to the pool per minute.
+    ///
+    /// Transactions beyond the limit are dropped and the peer is penalized.
+    #[arg(long = "tx-max-admissions-per-minute", value_name = "COUNT")]
+    pub tx_max_admissions_per_minute: Option<u32>,
+
+    /// Max number of new transactions of all peers that are imported into the pool per minute.
+    ///
+    /// Transactions beyond the limit are dropped.
+    #[arg(long = "tx-max-global-admissions-per-minute", value_name = "COUNT")]
+    pub tx_max_global_admissions_per_minute: Option<u32>,
 }
 
 impl NetworkArgs {
@@ -153,7 +187,9 @@ impl NetworkArgs {
             .peers
             .clone()
             .with_max_inbound_opt(self.max_inbound_peers)
-            .with_max_outbound_opt(self.max_outbound_peers);
+            .with_max_outbound_opt(self.max_outbound_peers)
+            .with_max_inbound_per_ip_opt(self.max_inbound_peers_per_ip)
+            .with_max_inbound_per_subnet_opt(self.max_inbound_peers_per_subnet);
 
         // Configure transactions manager
         let transactions_manager_config = TransactionsManagerConfig {
@@ -161,6 +197,14 @@ impl NetworkArgs {
                 self.soft_limit_byte_size_pooled_transactions_response,
                 self.soft_limit_byte_size_pooled_transactions_response_on_pack_request,
             ),
+            propagation_mode: self.tx_propagation_policy,
+            ignored_peers: self.tx_ignored_peers.iter().copied().collect(),
+            peer_admission_limit: self
+                .tx_max_admissions_per_minute
+                .map(|max| RateLimit::new(max, Duration::from_secs(60))),
+            global_admission_limit: self
+                .tx_max_global_admissions_per_minute
+                .map(|max| RateLimit::new(max, Duration::from_secs(60))),
         };
 
         // Configure basic network stack
@@ -258,9 +302,15 @@ impl Default for NetworkArgs {
             port: DEFAULT_DISCOVERY_PORT,
             max_outbound_peers: None,
             max_inbound_peers: None,
+            max_inbound_peers_per_ip: None,
+            max_inbound_peers_per_subnet: None,
             soft_limit_byte_size_pooled_transactions_response:
                 SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
             soft_limit_byte_size_pooled_transactions_response_on_pack_request: DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
+            tx_propagation_policy: TransactionPropagationMode::All,
+            tx_ignored_peers: vec![],
+            tx_max_admissions_per_minute: None,
+            tx_max_global_admissions_per_minute: None,
         }
     }
 }
@@ -495,6 +545,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_tx_propagation_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--tx-propagation-policy",
+            "local-to-trusted",
+            "--tx-ignore-peers",
+            "0xd860a01f9722d78051619d1e2351aba3f43f943f6f00718d1b9baa4101932a1f5011f16bb2b1bb35db20d6fe28fa0bf09636d26a87d31de9ec6203eeedb1f666",
+        ])
+        .args;
+
+        assert_eq!(args.tx_propagation_policy, TransactionPropagationMode::LocalToTrusted);
+        assert_eq!(
+            args.tx_ignored_peers,
+            vec!["0xd860a01f9722d78051619d1e2351aba3f43f943f6f00718d1b9baa4101932a1f5011f16bb2b1bb35db20d6fe28fa0bf09636d26a87d31de9ec6203eeedb1f666".parse::<PeerId>().unwrap()]
+        );
+    }
+
     #[test]
     fn parse_retry_strategy_args() {
         let tests = vec![0, 10];
@@ -7,7 +7,7 @@ use reth_cli_util::parse_duration_from_secs;
 use reth_primitives::constants::{
     ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION,
 };
-use std::{borrow::Cow, ffi::OsStr, time::Duration};
+use std::{borrow::Cow, ffi::OsStr, path::PathBuf, time::Duration};
 
 /// Parameters for configuring the Payload Builder
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
@@ -32,6 +32,32 @@ pub struct PayloadBuilderArgs {
     /// Maximum number of tasks to spawn for building a payload.
     #[arg(long = "builder.max-tasks", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
+
+    /// Accept bundles via `eth_sendBundle` and include them at the top of built payloads.
+    #[arg(long = "builder.bundles", default_value_t = false)]
+    pub bundles: bool,
+
+    /// Comma separated URLs of the MEV-Boost relays built payloads are submitted to.
+    ///
+    /// The fee recipient of the payload attributes must be the account of
+    /// `--builder.relay-account-key`, which pays the proposers.
+    #[arg(
+        long = "builder.relays",
+        value_delimiter = ',',
+        value_name = "URLS",
+        requires_all = ["relay_account_key", "relay_bls_key"],
+        conflicts_with = "bundles"
+    )]
+    pub relays: Vec<String>,
+
+    /// Path to the hex encoded secp256k1 secret key of the builder account that pays the
+    /// proposers.
+    #[arg(long = "builder.relay-account-key", value_name = "PATH")]
+    pub relay_account_key: Option<PathBuf>,
+
+    /// Path to the hex encoded BLS secret key that signs the submissions to the relays.
+    #[arg(long = "builder.relay-bls-key", value_name = "PATH")]
+    pub relay_bls_key: Option<PathBuf>,
 }
 
 impl Default for PayloadBuilderArgs {
@@ -42,6 +68,10 @@ impl Default for PayloadBuilderArgs {
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            bundles: false,
+            relays: Vec::new(),
+            relay_account_key: None,
+            relay_bls_key: None,
         }
     }
 }
@@ -148,6 +178,53 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_args_with_bundles() {
+        let args =
+            CommandParser::<PayloadBuilderArgs>::parse_from(["reth", "--builder.bundles"]).args;
+        assert!(args.bundles);
+    }
+
+    #[test]
+    fn test_args_with_relays() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.relays",
+            "https://relay1.example,https://relay2.example",
+            "--builder.relay-account-key",
+            "account.key",
+            "--builder.relay-bls-key",
+            "bls.key",
+        ])
+        .args;
+        assert_eq!(args.relays, vec!["https://relay1.example", "https://relay2.example"]);
+        assert_eq!(args.relay_account_key, Some(PathBuf::from("account.key")));
+        assert_eq!(args.relay_bls_key, Some(PathBuf::from("bls.key")));
+    }
+
+    #[test]
+    fn test_args_with_relays_require_keys() {
+        assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
+            "reth",
+            "--builder.relays",
+            "https://relay.example",
+            "--builder.relay-account-key",
+            "account.key",
+        ])
+        .is_err());
+        assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
+            "reth",
+            "--builder.relays",
+            "https://relay.example",
+            "--builder.relay-account-key",
+            "account.key",
+            "--builder.relay-bls-key",
+            "bls.key",
+            "--builder.bundles",
+        ])
+        .is_err());
+    }
+
     #[test]
     fn payload_builder_args_default_sanity_check() {
         let default_args = PayloadBuilderArgs::default();
@@ -13,6 +13,14 @@ pub struct PruningArgs {
     /// This flag takes priority over pruning configuration in reth.toml.
     #[arg(long, default_value_t = false)]
     pub full: bool,
+
+    /// Store the previous values of all changed trie nodes, so that historical state roots and
+    /// proofs read the trie as of a block instead of recomputing it from the reverted state.
+    ///
+    /// The trie history is recorded for every block persisted after the initial sync, and for the
+    /// last block of every pipeline run.
+    #[arg(long = "archive-trie", default_value_t = false)]
+    pub archive_trie: bool,
 }
 
 impl PruningArgs {
@@ -40,6 +48,7 @@ impl PruningArgs {
                         .into_iter()
                         .collect(),
                 ),
+                receipts_log_rules: Default::default(),
             },
         })
     }
@@ -159,12 +159,23 @@ pub struct RpcServerArgs {
     /// The maximum proof window for historical proof generation.
     /// This value allows for generating historical proofs up to
     /// configured number of blocks from current tip (up to `tip - window`).
+    ///
+    /// The window is capped at 216000 blocks, unless `--rpc.proof-checkpoint-interval` or
+    /// `--archive-trie` is set.
+    #[arg(long = "rpc.eth-proof-window", default_value_t = constants::DEFAULT_ETH_PROOF_WINDOW)]
+    pub rpc_eth_proof_window: u64,
+
+    /// Aggregate the reverts of historical blocks every N blocks.
+    ///
+    /// The reverts of the recent windows of N blocks are computed in the background and cached in
+    /// memory, so that historical proofs only walk the changesets up to the nearest checkpoint.
+    /// With checkpoints, `--rpc.eth-proof-window` isn't capped.
     #[arg(
-        long = "rpc.eth-proof-window",
-        default_value_t = constants::DEFAULT_ETH_PROOF_WINDOW,
-        value_parser = RangedU64ValueParser::<u64>::new().range(..=constants::MAX_ETH_PROOF_WINDOW)
+        long = "rpc.proof-checkpoint-interval",
+        value_name = "BLOCKS",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
     )]
-    pub rpc_eth_proof_window: u64,
+    pub rpc_proof_checkpoint_interval: Option<u64>,
 
     /// Maximum number of concurrent getproof requests.
     #[arg(long = "rpc.proof-permits", alias = "rpc-proof-permits", value_name = "COUNT", default_value_t = constants::DEFAULT_PROOF_PERMITS)]
@@ -301,6 +312,7 @@ impl Default for RpcServerArgs {
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
             rpc_gas_cap: constants::gas_oracle::RPC_DEFAULT_GAS_CAP,
             rpc_eth_proof_window: constants::DEFAULT_ETH_PROOF_WINDOW,
+            rpc_proof_checkpoint_interval: None,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
             rpc_proof_permits: constants::DEFAULT_PROOF_PERMITS,
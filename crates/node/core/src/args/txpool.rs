@@ -2,13 +2,19 @@
 
 use crate::cli::config::RethTransactionPoolConfig;
 use clap::Args;
+use reth_cli_util::parse_duration_from_secs;
+use reth_config::config::{TransactionOrderingKind, TxPoolConfig};
 use reth_primitives::Address;
 use reth_transaction_pool::{
-    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS, validate::DEFAULT_MAX_TX_INPUT_BYTES,
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS,
+    journal::{TransactionJournalConfig, DEFAULT_JOURNAL_SNAPSHOT_INTERVAL},
+    validate::DEFAULT_MAX_TX_INPUT_BYTES,
+    LocalTransactionConfig, PoolConfig, PriceBumpConfig, RateLimit, SubPoolLimit,
+    TransactionOrderingConfig, TransactionOrderingStrategy, DEFAULT_PRICE_BUMP,
+    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_MAX_REMOVED_TRANSACTIONS,
     TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
+use std::{path::PathBuf, time::Duration};
 /// Parameters for debugging purposes
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "TxPool")]
@@ -63,6 +69,90 @@ pub struct TxPoolArgs {
     /// Flag to toggle local transaction propagation.
     #[arg(long = "txpool.no-local-transactions-propagation")]
     pub no_local_transactions_propagation: bool,
+
+    /// Persist all transactions of the pool, including blob sidecars, to a journal and reinsert
+    /// them on startup.
+    ///
+    /// This replaces the backup of local transactions.
+    #[arg(long = "txpool.journal")]
+    pub journal: bool,
+    /// Interval at which a snapshot of the pool is written to the journal.
+    #[arg(long = "txpool.journal-interval", value_parser = parse_journal_interval, default_value = "60", value_name = "SECONDS")]
+    pub journal_interval: Duration,
+
+    /// The strategy transactions are ordered by: coinbase-tip, fifo or effective-gas-price.
+    ///
+    /// Overrides the ordering of the `[txpool]` section of the config file.
+    #[arg(long = "txpool.ordering", value_name = "STRATEGY")]
+    pub ordering: Option<TransactionOrderingStrategy>,
+    /// Comma separated list of senders whose transactions are ranked above all other transactions.
+    #[arg(long = "txpool.priority-senders", value_delimiter = ',', value_name = "ADDRESSES")]
+    pub priority_senders: Vec<Address>,
+    /// Comma separated list of recipients whose transactions are ranked above all other
+    /// transactions.
+    #[arg(long = "txpool.priority-recipients", value_delimiter = ',', value_name = "ADDRESSES")]
+    pub priority_recipients: Vec<Address>,
+
+    /// Max number of transactions a non-local sender can replace per minute.
+    #[arg(long = "txpool.max-replacements-per-minute", value_name = "COUNT")]
+    pub max_replacements_per_minute: Option<u32>,
+}
+
+impl TxPoolArgs {
+    /// Returns the journal configuration if the journal is enabled.
+    ///
+    /// The local transactions backup at `local_transactions_path` is reinserted as well, since
+    /// the journal replaces it.
+    pub fn journal_config(
+        &self,
+        path: PathBuf,
+        local_transactions_path: PathBuf,
+    ) -> Option<TransactionJournalConfig> {
+        self.journal.then(|| {
+            TransactionJournalConfig::new(path)
+                .with_snapshot_interval(self.journal_interval)
+                .with_local_transactions_backup(local_transactions_path)
+        })
+    }
+
+    /// Returns the transaction ordering configuration, merging the arguments with the `[txpool]`
+    /// section of the config file.
+    ///
+    /// The ordering strategy of the arguments takes precedence, the prioritized senders and
+    /// recipients of both are combined.
+    pub fn ordering_config(&self, config: &TxPoolConfig) -> TransactionOrderingConfig {
+        let strategy = self.ordering.unwrap_or(match config.ordering {
+            TransactionOrderingKind::CoinbaseTip => TransactionOrderingStrategy::CoinbaseTip,
+            TransactionOrderingKind::Fifo => TransactionOrderingStrategy::Fifo,
+            TransactionOrderingKind::EffectiveGasPrice => {
+                TransactionOrderingStrategy::EffectiveGasPrice
+            }
+        });
+        TransactionOrderingConfig {
+            strategy,
+            priority_senders: self
+                .priority_senders
+                .iter()
+                .chain(&config.priority_senders)
+                .copied()
+                .collect(),
+            priority_recipients: self
+                .priority_recipients
+                .iter()
+                .chain(&config.priority_recipients)
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+/// Parses the journal interval in seconds, which must not be zero.
+fn parse_journal_interval(arg: &str) -> eyre::Result<Duration> {
+    let interval = parse_duration_from_secs(arg)?;
+    if interval.is_zero() {
+        eyre::bail!("journal interval must be at least one second")
+    }
+    Ok(interval)
 }
 
 impl Default for TxPoolArgs {
@@ -82,6 +172,12 @@ impl Default for TxPoolArgs {
             no_locals: false,
             locals: Default::default(),
             no_local_transactions_propagation: false,
+            journal: false,
+            journal_interval: DEFAULT_JOURNAL_SNAPSHOT_INTERVAL,
+            ordering: None,
+            priority_senders: Default::default(),
+            priority_recipients: Default::default(),
+            max_replacements_per_minute: None,
         }
     }
 }
@@ -116,6 +212,11 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
             },
+            ordering: self.ordering_config(&Default::default()),
+            replacement_rate_limit: self
+                .max_replacements_per_minute
+                .map(|max| RateLimit::new(max, Duration::from_secs(60))),
+            max_removed_transactions: TXPOOL_MAX_REMOVED_TRANSACTIONS,
         }
     }
 }
@@ -138,4 +239,62 @@ mod tests {
         let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn txpool_parse_journal_interval() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.journal",
+            "--txpool.journal-interval",
+            "10",
+        ])
+        .args;
+        assert_eq!(args.journal_interval, Duration::from_secs(10));
+
+        let res =
+            CommandParser::<TxPoolArgs>::try_parse_from(["reth", "--txpool.journal-interval", "0"]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn txpool_parse_ordering() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.ordering",
+            "fifo",
+            "--txpool.priority-senders",
+            "0x1f9090aae28b8a3dceadf281b0f12828e676c326,0x388c818ca8b9251b393131c08a736a67ccb19297",
+        ])
+        .args;
+        assert_eq!(args.ordering, Some(TransactionOrderingStrategy::Fifo));
+        assert_eq!(args.priority_senders.len(), 2);
+
+        let config = TxPoolConfig {
+            ordering: TransactionOrderingKind::EffectiveGasPrice,
+            priority_senders: vec![args.priority_senders[0]],
+            priority_recipients: vec![Address::ZERO],
+        };
+        let ordering = args.ordering_config(&config);
+        assert_eq!(ordering.strategy, TransactionOrderingStrategy::Fifo);
+        assert_eq!(ordering.priority_senders.len(), 2);
+        assert!(ordering.priority_recipients.contains(&Address::ZERO));
+
+        let ordering = TxPoolArgs::default().ordering_config(&config);
+        assert_eq!(ordering.strategy, TransactionOrderingStrategy::EffectiveGasPrice);
+    }
+
+    #[test]
+    fn txpool_parse_max_replacements() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.max-replacements-per-minute",
+            "5",
+        ])
+        .args;
+        assert_eq!(
+            args.pool_config().replacement_rate_limit,
+            Some(RateLimit::new(5, Duration::from_secs(60)))
+        );
+        assert_eq!(TxPoolArgs::default().pool_config().replacement_rate_limit, None);
+    }
 }
@@ -0,0 +1,107 @@
+//! ERC-4337 user operation pool arguments
+
+use clap::Args;
+use reth_primitives::Address;
+use reth_user_operation_pool::{
+    UserOperationPoolConfig, DEFAULT_MAX_USER_OPERATIONS, DEFAULT_MAX_VERIFICATION_GAS,
+    DEFAULT_USER_OPERATION_PRICE_BUMP, ENTRY_POINT_V06,
+};
+
+/// Parameters for configuring the ERC-4337 user operation pool
+#[derive(Debug, Clone, Args, PartialEq, Eq)]
+#[command(next_help_heading = "UserOperationPool")]
+pub struct UserOperationPoolArgs {
+    /// Enables the user operation pool and the ERC-4337 `eth` rpc methods.
+    #[arg(long = "userop.enable", default_value_t = false)]
+    pub enabled: bool,
+
+    /// The entry points user operations are accepted for.
+    #[arg(
+        long = "userop.entry-points",
+        value_delimiter = ',',
+        value_name = "ADDRESSES",
+        default_value = "0x5ff137d4b0fdcd49dca30c7cf57e578a026d2789"
+    )]
+    pub entry_points: Vec<Address>,
+
+    /// Max number of user operations in the pool.
+    #[arg(long = "userop.max-count", default_value_t = DEFAULT_MAX_USER_OPERATIONS)]
+    pub max_operations: usize,
+
+    /// Price bump (in %) required to replace a user operation with the same sender and nonce.
+    #[arg(long = "userop.pricebump", default_value_t = DEFAULT_USER_OPERATION_PRICE_BUMP)]
+    pub price_bump: u128,
+
+    /// Max verification gas limit of a user operation.
+    #[arg(long = "userop.max-verification-gas", default_value_t = DEFAULT_MAX_VERIFICATION_GAS)]
+    pub max_verification_gas: u64,
+}
+
+impl UserOperationPoolArgs {
+    /// Returns the user operation pool configuration, if the pool is enabled.
+    pub fn pool_config(&self) -> Option<UserOperationPoolConfig> {
+        if !self.enabled {
+            return None
+        }
+
+        Some(UserOperationPoolConfig {
+            entry_points: self.entry_points.clone(),
+            max_operations: self.max_operations,
+            price_bump: self.price_bump,
+            max_verification_gas: self.max_verification_gas,
+            ..Default::default()
+        })
+    }
+}
+
+impl Default for UserOperationPoolArgs {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            entry_points: vec![ENTRY_POINT_V06],
+            max_operations: DEFAULT_MAX_USER_OPERATIONS,
+            price_bump: DEFAULT_USER_OPERATION_PRICE_BUMP,
+            max_verification_gas: DEFAULT_MAX_VERIFICATION_GAS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn user_operation_pool_args_default_sanity_test() {
+        let default_args = UserOperationPoolArgs::default();
+        let args = CommandParser::<UserOperationPoolArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+        assert_eq!(args.pool_config(), None);
+    }
+
+    #[test]
+    fn user_operation_pool_parse_args() {
+        let entry_point = Address::with_last_byte(1);
+        let args = CommandParser::<UserOperationPoolArgs>::parse_from([
+            "reth",
+            "--userop.enable",
+            "--userop.entry-points",
+            &entry_point.to_string(),
+            "--userop.pricebump",
+            "20",
+        ])
+        .args;
+
+        let config = args.pool_config().unwrap();
+        assert_eq!(config.entry_points, vec![entry_point]);
+        assert_eq!(config.price_bump, 20);
+        assert_eq!(config.max_operations, DEFAULT_MAX_USER_OPERATIONS);
+    }
+}
@@ -330,6 +330,20 @@ impl<D> ChainPath<D> {
         self.data_dir().join("txpool-transactions-backup.rlp")
     }
 
+    /// Returns the path to the transaction pool journal file
+    ///
+    /// `<DIR>/<CHAIN_ID>/txpool-journal.rlp`
+    pub fn txpool_journal(&self) -> PathBuf {
+        self.data_dir().join("txpool-journal.rlp")
+    }
+
+    /// Returns the path to the write-ahead log of the execution extension notifications.
+    ///
+    /// `<DIR>/<CHAIN_ID>/exex/wal`
+    pub fn exex_wal(&self) -> PathBuf {
+        self.data_dir().join("exex").join("wal")
+    }
+
     /// Returns the path to the config file for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/reth.toml`
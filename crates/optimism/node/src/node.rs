@@ -22,7 +22,7 @@ use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_provider::CanonStateSubscriptions;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, CoinbaseTipOrdering, TransactionPool,
+    blobstore::DiskFileBlobStore, ConfiguredOrdering, TransactionPool,
     TransactionValidationTaskExecutor,
 };
 use std::sync::Arc;
@@ -146,33 +146,21 @@ where
             )
             .map(OpTransactionValidator::new);
 
+        let pool_config = ctx.pool_config();
         let transaction_pool = reth_transaction_pool::Pool::new(
             validator,
-            CoinbaseTipOrdering::default(),
+            ConfiguredOrdering::new(pool_config.ordering.clone()),
             blob_store,
-            ctx.pool_config(),
+            pool_config,
         );
         info!(target: "reth::cli", "Transaction pool initialized");
-        let transactions_path = data_dir.txpool_transactions();
 
         // spawn txpool maintenance task
         {
             let pool = transaction_pool.clone();
             let chain_events = ctx.provider().canonical_state_stream();
             let client = ctx.provider().clone();
-            let transactions_backup_config =
-                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
-
-            ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
-                "local transactions backup task",
-                |shutdown| {
-                    reth_transaction_pool::maintain::backup_local_transactions_task(
-                        shutdown,
-                        pool.clone(),
-                        transactions_backup_config,
-                    )
-                },
-            );
+            ctx.spawn_pool_persistence_task(pool.clone());
 
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
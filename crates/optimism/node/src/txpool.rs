@@ -6,7 +6,7 @@ use reth_primitives::{Block, GotExpected, InvalidTransactionError, SealedBlock};
 use reth_provider::{BlockReaderIdExt, StateProviderFactory};
 use reth_revm::L1BlockInfo;
 use reth_transaction_pool::{
-    CoinbaseTipOrdering, EthPoolTransaction, EthPooledTransaction, EthTransactionValidator, Pool,
+    ConfiguredOrdering, EthPoolTransaction, EthPooledTransaction, EthTransactionValidator, Pool,
     TransactionOrigin, TransactionValidationOutcome, TransactionValidationTaskExecutor,
     TransactionValidator,
 };
@@ -18,7 +18,7 @@ use std::sync::{
 /// Type alias for default optimism transaction pool
 pub type OpTransactionPool<Client, S> = Pool<
     TransactionValidationTaskExecutor<OpTransactionValidator<Client, EthPooledTransaction>>,
-    CoinbaseTipOrdering<EthPooledTransaction>,
+    ConfiguredOrdering<EthPooledTransaction>,
     S,
 >;
 
@@ -47,6 +47,11 @@ use tokio::{
 use tracing::{debug, trace, warn};
 
 mod metrics;
+mod strategy;
+pub use strategy::{
+    MultiStrategyPayloadJob, MultiStrategyPayloadJobGenerator, PayloadStrategy,
+    ResolveStrategyPayload,
+};
 
 /// The [`PayloadJobGenerator`] that creates [`BasicPayloadJob`]s.
 #[derive(Debug)]
@@ -96,30 +101,6 @@ impl<Client, Pool, Tasks, Builder> BasicPayloadJobGenerator<Client, Pool, Tasks,
         }
     }
 
-    /// Returns the maximum duration a job should be allowed to run.
-    ///
-    /// This adheres to the following specification:
-    // > Client software SHOULD stop the updating process when either a call to engine_getPayload
-    // > with the build process's payloadId is made or SECONDS_PER_SLOT (12s in the Mainnet
-    // > configuration) have passed since the point in time identified by the timestamp parameter.
-    // See also <https://github.com/ethereum/execution-apis/blob/431cf72fd3403d946ca3e3afc36b973fc87e0e89/src/engine/paris.md?plain=1#L137>
-    #[inline]
-    fn max_job_duration(&self, unix_timestamp: u64) -> Duration {
-        let duration_until_timestamp = duration_until(unix_timestamp);
-
-        // safety in case clocks are bad
-        let duration_until_timestamp = duration_until_timestamp.min(self.config.deadline * 3);
-
-        self.config.deadline + duration_until_timestamp
-    }
-
-    /// Returns the [Instant](tokio::time::Instant) at which the job should be terminated because it
-    /// is considered timed out.
-    #[inline]
-    fn job_deadline(&self, unix_timestamp: u64) -> tokio::time::Instant {
-        tokio::time::Instant::now() + self.max_job_duration(unix_timestamp)
-    }
-
     /// Returns a reference to the tasks type
     pub const fn tasks(&self) -> &Tasks {
         &self.executor
@@ -150,21 +131,7 @@ where
         &self,
         attributes: <Self::Job as PayloadJob>::PayloadAttributes,
     ) -> Result<Self::Job, PayloadBuilderError> {
-        let parent_block = if attributes.parent().is_zero() {
-            // use latest block if parent is zero: genesis block
-            self.client
-                .block_by_number_or_tag(BlockNumberOrTag::Latest)?
-                .ok_or_else(|| PayloadBuilderError::MissingParentBlock(attributes.parent()))?
-                .seal_slow()
-        } else {
-            let block = self
-                .client
-                .find_block_by_hash(attributes.parent(), BlockSource::Any)?
-                .ok_or_else(|| PayloadBuilderError::MissingParentBlock(attributes.parent()))?;
-
-            // we already know the hash, so we can seal it
-            block.seal(attributes.parent())
-        };
+        let parent_block = load_parent_block(&self.client, attributes.parent())?;
 
         let config = PayloadConfig::new(
             Arc::new(parent_block),
@@ -173,7 +140,7 @@ where
             Arc::clone(&self.chain_spec),
         );
 
-        let until = self.job_deadline(config.attributes.timestamp());
+        let until = self.config.job_deadline(config.attributes.timestamp());
         let deadline = Box::pin(tokio::time::sleep_until(until));
 
         let cached_reads = self.maybe_pre_cached(config.parent_block.hash());
@@ -200,6 +167,49 @@ where
     }
 
     fn on_new_state(&mut self, new_state: CanonStateNotification) {
+        self.pre_cached = Some(PrecachedState::from_notification(&new_state));
+    }
+}
+
+/// Loads the parent block of a new payload, the latest block if the parent hash is zero.
+pub(crate) fn load_parent_block<Client>(
+    client: &Client,
+    parent: B256,
+) -> Result<SealedBlock, PayloadBuilderError>
+where
+    Client: BlockReaderIdExt,
+{
+    let parent_block = if parent.is_zero() {
+        // use latest block if parent is zero: genesis block
+        client
+            .block_by_number_or_tag(BlockNumberOrTag::Latest)?
+            .ok_or(PayloadBuilderError::MissingParentBlock(parent))?
+            .seal_slow()
+    } else {
+        let block = client
+            .find_block_by_hash(parent, BlockSource::Any)?
+            .ok_or(PayloadBuilderError::MissingParentBlock(parent))?;
+
+        // we already know the hash, so we can seal it
+        block.seal(parent)
+    };
+    Ok(parent_block)
+}
+
+/// Pre-filled [`CachedReads`] for a specific block.
+///
+/// This is extracted from the [`CanonStateNotification`] for the tip block.
+#[derive(Debug, Clone)]
+pub struct PrecachedState {
+    /// The block for which the state is pre-cached.
+    pub block: B256,
+    /// Cached state for the block.
+    pub cached: CachedReads,
+}
+
+impl PrecachedState {
+    /// Extracts the changed state of the tip block of the notification.
+    pub(crate) fn from_notification(new_state: &CanonStateNotification) -> Self {
         let mut cached = CachedReads::default();
 
         // extract the state from the notification and put it into the cache
@@ -215,21 +225,10 @@ where
             }
         }
 
-        self.pre_cached = Some(PrecachedState { block: committed.tip().hash(), cached });
+        Self { block: committed.tip().hash(), cached }
     }
 }
 
-/// Pre-filled [`CachedReads`] for a specific block.
-///
-/// This is extracted from the [`CanonStateNotification`] for the tip block.
-#[derive(Debug, Clone)]
-pub struct PrecachedState {
-    /// The block for which the state is pre-cached.
-    pub block: B256,
-    /// Cached state for the block.
-    pub cached: CachedReads,
-}
-
 /// Restricts how many generator tasks can be executed at once.
 #[derive(Debug, Clone)]
 pub struct PayloadTaskGuard(Arc<Semaphore>);
@@ -299,6 +298,30 @@ impl BasicPayloadJobGeneratorConfig {
         self.extradata = extradata;
         self
     }
+
+    /// Returns the maximum duration a job should be allowed to run.
+    ///
+    /// This adheres to the following specification:
+    // > Client software SHOULD stop the updating process when either a call to engine_getPayload
+    // > with the build process's payloadId is made or SECONDS_PER_SLOT (12s in the Mainnet
+    // > configuration) have passed since the point in time identified by the timestamp parameter.
+    // See also <https://github.com/ethereum/execution-apis/blob/431cf72fd3403d946ca3e3afc36b973fc87e0e89/src/engine/paris.md?plain=1#L137>
+    #[inline]
+    pub(crate) fn max_job_duration(&self, unix_timestamp: u64) -> Duration {
+        let duration_until_timestamp = duration_until(unix_timestamp);
+
+        // safety in case clocks are bad
+        let duration_until_timestamp = duration_until_timestamp.min(self.deadline * 3);
+
+        self.deadline + duration_until_timestamp
+    }
+
+    /// Returns the [Instant](tokio::time::Instant) at which the job should be terminated because it
+    /// is considered timed out.
+    #[inline]
+    pub(crate) fn job_deadline(&self, unix_timestamp: u64) -> tokio::time::Instant {
+        tokio::time::Instant::now() + self.max_job_duration(unix_timestamp)
+    }
 }
 
 impl Default for BasicPayloadJobGeneratorConfig {
@@ -575,7 +598,7 @@ impl<Payload> ResolveBestPayload<Payload> {
 
 impl<Payload> Future for ResolveBestPayload<Payload>
 where
-    Payload: Unpin,
+    Payload: BuiltPayload + Unpin,
 {
     type Output = Result<Payload, PayloadBuilderError>;
 
@@ -587,8 +610,12 @@ where
             if let Poll::Ready(res) = fut.poll(cx) {
                 this.maybe_better = None;
                 if let Ok(BuildOutcome::Better { payload, .. }) = res {
-                    debug!(target: "payload_builder", "resolving better payload");
-                    return Poll::Ready(Ok(payload))
+                    // the build may have started before the best payload was built
+                    if this.best_payload.as_ref().map_or(true, |best| payload.fees() > best.fees())
+                    {
+                        debug!(target: "payload_builder", "resolving better payload");
+                        return Poll::Ready(Ok(payload))
+                    }
                 }
             }
         }
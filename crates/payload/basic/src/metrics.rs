@@ -27,3 +27,39 @@ impl PayloadBuilderMetrics {
         self.failed_payload_builds.increment(1);
     }
 }
+
+/// Metrics of a single [`PayloadStrategy`](crate::PayloadStrategy).
+#[derive(Metrics, Clone)]
+#[metrics(scope = "payloads.strategy")]
+pub(crate) struct PayloadStrategyMetrics {
+    /// Total number of initiated payload build attempts
+    pub(crate) initiated_payload_builds: Counter,
+    /// Total number of failed payload build attempts
+    pub(crate) failed_payload_builds: Counter,
+    /// Number of built payloads that were better than the best payload of all strategies so far
+    pub(crate) better_payloads: Counter,
+    /// Number of resolved payloads that were built by this strategy
+    pub(crate) resolved_payloads: Counter,
+}
+
+impl PayloadStrategyMetrics {
+    pub(crate) fn new(strategy: &str) -> Self {
+        Self::new_with_labels(&[("strategy", strategy.to_string())])
+    }
+
+    pub(crate) fn inc_initiated_payload_builds(&self) {
+        self.initiated_payload_builds.increment(1);
+    }
+
+    pub(crate) fn inc_failed_payload_builds(&self) {
+        self.failed_payload_builds.increment(1);
+    }
+
+    pub(crate) fn inc_better_payloads(&self) {
+        self.better_payloads.increment(1);
+    }
+
+    pub(crate) fn inc_resolved_payloads(&self) {
+        self.resolved_payloads.increment(1);
+    }
+}
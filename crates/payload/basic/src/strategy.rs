@@ -0,0 +1,748 @@
+//! A payload job that builds payloads with multiple competing strategies.
+
+use crate::{
+    load_parent_block,
+    metrics::{PayloadBuilderMetrics, PayloadStrategyMetrics},
+    BasicPayloadJobGeneratorConfig, BuildArguments, BuildOutcome, Cancelled, PayloadBuilder,
+    PayloadConfig, PayloadTaskGuard, PendingPayload, PrecachedState,
+};
+use futures_util::FutureExt;
+use reth_chainspec::ChainSpec;
+use reth_payload_builder::{
+    database::CachedReads, error::PayloadBuilderError, KeepPayloadJobAlive, PayloadJob,
+    PayloadJobGenerator,
+};
+use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
+use reth_provider::{BlockReaderIdExt, CanonStateNotification, StateProviderFactory};
+use reth_tasks::TaskSpawner;
+use reth_transaction_pool::TransactionPool;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    sync::oneshot,
+    time::{Interval, Sleep},
+};
+use tracing::{debug, trace, warn};
+
+/// A named [`PayloadBuilder`] that competes with other strategies in a
+/// [`MultiStrategyPayloadJob`], e.g. a greedy fee ordering or a bundle-first ordering.
+pub struct PayloadStrategy<Pool, Client, Attributes, Payload> {
+    /// The name of the strategy, used as metrics label.
+    name: Arc<str>,
+    /// The type responsible for building payloads.
+    builder: Arc<dyn DynPayloadBuilder<Pool, Client, Attributes, Payload>>,
+    /// Metrics of this strategy.
+    metrics: PayloadStrategyMetrics,
+}
+
+impl<Pool, Client, Attributes, Payload> PayloadStrategy<Pool, Client, Attributes, Payload> {
+    /// Creates a new strategy with the given name that builds payloads with the given builder.
+    pub fn new<Builder>(name: impl Into<Arc<str>>, builder: Builder) -> Self
+    where
+        Builder:
+            PayloadBuilder<Pool, Client, Attributes = Attributes, BuiltPayload = Payload> + 'static,
+    {
+        let name = name.into();
+        let metrics = PayloadStrategyMetrics::new(&name);
+        Self { name, builder: Arc::new(builder), metrics }
+    }
+
+    /// Returns the name of the strategy.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<Pool, Client, Attributes, Payload> Clone
+    for PayloadStrategy<Pool, Client, Attributes, Payload>
+{
+    fn clone(&self) -> Self {
+        Self {
+            name: Arc::clone(&self.name),
+            builder: Arc::clone(&self.builder),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<Pool, Client, Attributes, Payload> fmt::Debug
+    for PayloadStrategy<Pool, Client, Attributes, Payload>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PayloadStrategy").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+/// An object safe [`PayloadBuilder`], so that strategies with different builders can be combined.
+trait DynPayloadBuilder<Pool, Client, Attributes, Payload>: Send + Sync {
+    fn try_build(
+        &self,
+        args: BuildArguments<Pool, Client, Attributes, Payload>,
+    ) -> Result<BuildOutcome<Payload>, PayloadBuilderError>;
+
+    fn build_empty_payload(
+        &self,
+        client: &Client,
+        config: PayloadConfig<Attributes>,
+    ) -> Result<Payload, PayloadBuilderError>;
+}
+
+impl<Pool, Client, Builder>
+    DynPayloadBuilder<Pool, Client, Builder::Attributes, Builder::BuiltPayload> for Builder
+where
+    Builder: PayloadBuilder<Pool, Client>,
+{
+    fn try_build(
+        &self,
+        args: BuildArguments<Pool, Client, Builder::Attributes, Builder::BuiltPayload>,
+    ) -> Result<BuildOutcome<Builder::BuiltPayload>, PayloadBuilderError> {
+        PayloadBuilder::try_build(self, args)
+    }
+
+    fn build_empty_payload(
+        &self,
+        client: &Client,
+        config: PayloadConfig<Builder::Attributes>,
+    ) -> Result<Builder::BuiltPayload, PayloadBuilderError> {
+        PayloadBuilder::build_empty_payload(self, client, config)
+    }
+}
+
+/// The [`PayloadJobGenerator`] that creates [`MultiStrategyPayloadJob`]s.
+#[derive(Debug)]
+pub struct MultiStrategyPayloadJobGenerator<Client, Pool, Tasks, Attributes, Payload> {
+    /// The client that can interact with the chain.
+    client: Client,
+    /// The transaction pool to pull transactions from.
+    pool: Pool,
+    /// The task executor to spawn payload building tasks on.
+    executor: Tasks,
+    /// The configuration for the job generator.
+    config: BasicPayloadJobGeneratorConfig,
+    /// Restricts how many generator tasks can be executed at once, shared by all strategies.
+    payload_task_guard: PayloadTaskGuard,
+    /// The chain spec.
+    chain_spec: Arc<ChainSpec>,
+    /// The competing strategies.
+    strategies: Vec<PayloadStrategy<Pool, Client, Attributes, Payload>>,
+    /// Stored `cached_reads` for new payload jobs.
+    pre_cached: Option<PrecachedState>,
+}
+
+// === impl MultiStrategyPayloadJobGenerator ===
+
+impl<Client, Pool, Tasks, Attributes, Payload>
+    MultiStrategyPayloadJobGenerator<Client, Pool, Tasks, Attributes, Payload>
+{
+    /// Creates a new [`MultiStrategyPayloadJobGenerator`] with the given config and strategies.
+    ///
+    /// The first strategy is used to build empty payloads.
+    ///
+    /// # Panics
+    ///
+    /// If `strategies` is empty.
+    pub fn new(
+        client: Client,
+        pool: Pool,
+        executor: Tasks,
+        config: BasicPayloadJobGeneratorConfig,
+        chain_spec: Arc<ChainSpec>,
+        strategies: Vec<PayloadStrategy<Pool, Client, Attributes, Payload>>,
+    ) -> Self {
+        assert!(!strategies.is_empty(), "at least one payload strategy is required");
+        Self {
+            client,
+            pool,
+            executor,
+            payload_task_guard: PayloadTaskGuard::new(config.max_payload_tasks),
+            config,
+            chain_spec,
+            strategies,
+            pre_cached: None,
+        }
+    }
+
+    /// Returns a reference to the tasks type
+    pub const fn tasks(&self) -> &Tasks {
+        &self.executor
+    }
+
+    /// Returns the competing strategies.
+    pub fn strategies(&self) -> &[PayloadStrategy<Pool, Client, Attributes, Payload>] {
+        &self.strategies
+    }
+}
+
+impl<Client, Pool, Tasks, Attributes, Payload> PayloadJobGenerator
+    for MultiStrategyPayloadJobGenerator<Client, Pool, Tasks, Attributes, Payload>
+where
+    Client: StateProviderFactory + BlockReaderIdExt + Clone + Unpin + 'static,
+    Pool: TransactionPool + Unpin + 'static,
+    Tasks: TaskSpawner + Clone + Unpin + 'static,
+    Attributes: PayloadBuilderAttributes + Unpin + Clone + 'static,
+    Payload: BuiltPayload + Unpin + Clone + 'static,
+{
+    type Job = MultiStrategyPayloadJob<Client, Pool, Tasks, Attributes, Payload>;
+
+    fn new_payload_job(&self, attributes: Attributes) -> Result<Self::Job, PayloadBuilderError> {
+        let parent_block = load_parent_block(&self.client, attributes.parent())?;
+
+        let config = PayloadConfig::new(
+            Arc::new(parent_block),
+            self.config.extradata.clone(),
+            attributes,
+            Arc::clone(&self.chain_spec),
+        );
+
+        let until = self.config.job_deadline(config.attributes.timestamp());
+        let deadline = Box::pin(tokio::time::sleep_until(until));
+
+        let cached_reads = self
+            .pre_cached
+            .as_ref()
+            .filter(|pc| pc.block == config.parent_block.hash())
+            .map(|pc| pc.cached.clone());
+
+        let mut job = MultiStrategyPayloadJob {
+            config,
+            client: self.client.clone(),
+            pool: self.pool.clone(),
+            executor: self.executor.clone(),
+            deadline,
+            interval: tokio::time::interval(self.config.interval),
+            best_payload: None,
+            best_strategy: None,
+            pending_builds: Vec::new(),
+            cached_reads,
+            payload_task_guard: self.payload_task_guard.clone(),
+            metrics: Default::default(),
+            strategies: self.strategies.clone(),
+        };
+
+        // start the first builds right away
+        job.spawn_build_jobs();
+
+        Ok(job)
+    }
+
+    fn on_new_state(&mut self, new_state: CanonStateNotification) {
+        self.pre_cached = Some(PrecachedState::from_notification(&new_state));
+    }
+}
+
+/// A payload job that continuously builds payloads with all of its [`PayloadStrategy`]s
+/// concurrently and keeps the payload with the highest fees.
+///
+/// Every interval, one build task per strategy is spawned on the blocking pool. The tasks start
+/// from the same cached reads, and acquire a permit of the [`PayloadTaskGuard`] each. The reads of
+/// all builds are merged into the cache for the next interval. A new round is only started once all
+/// builds of the previous round finished.
+#[derive(Debug)]
+pub struct MultiStrategyPayloadJob<Client, Pool, Tasks, Attributes, Payload> {
+    /// The configuration for how the payload will be created.
+    config: PayloadConfig<Attributes>,
+    /// The client that can interact with the chain.
+    client: Client,
+    /// The transaction pool.
+    pool: Pool,
+    /// How to spawn building tasks
+    executor: Tasks,
+    /// The deadline when this job should resolve.
+    deadline: Pin<Box<Sleep>>,
+    /// The interval at which the job should build new payloads after the last.
+    interval: Interval,
+    /// The best payload so far.
+    best_payload: Option<Payload>,
+    /// The index of the strategy that built the best payload.
+    best_strategy: Option<usize>,
+    /// The builds that are in progress, with the index of their strategy.
+    pending_builds: Vec<(usize, PendingPayload<Payload>)>,
+    /// Restricts how many generator tasks can be executed at once.
+    payload_task_guard: PayloadTaskGuard,
+    /// Caches all disk reads for the state the new payloads builds on
+    cached_reads: Option<CachedReads>,
+    /// metrics for this type
+    metrics: PayloadBuilderMetrics,
+    /// The competing strategies.
+    strategies: Vec<PayloadStrategy<Pool, Client, Attributes, Payload>>,
+}
+
+impl<Client, Pool, Tasks, Attributes, Payload>
+    MultiStrategyPayloadJob<Client, Pool, Tasks, Attributes, Payload>
+where
+    Client: StateProviderFactory + Clone + Unpin + 'static,
+    Pool: TransactionPool + Unpin + 'static,
+    Tasks: TaskSpawner + Clone + 'static,
+    Attributes: PayloadBuilderAttributes + Unpin + Clone + 'static,
+    Payload: BuiltPayload + Unpin + Clone + 'static,
+{
+    /// Spawns a new payload build task for every strategy.
+    fn spawn_build_jobs(&mut self) {
+        trace!(target: "payload_builder", strategies = self.strategies.len(), "spawn new payload build tasks");
+        let cached_reads = self.cached_reads.take().unwrap_or_default();
+        for (idx, strategy) in self.strategies.iter().enumerate() {
+            let (tx, rx) = oneshot::channel();
+            let cancel = Cancelled::default();
+            let args = BuildArguments::new(
+                self.client.clone(),
+                self.pool.clone(),
+                cached_reads.clone(),
+                self.config.clone(),
+                cancel.clone(),
+                self.best_payload.clone(),
+            );
+            let guard = self.payload_task_guard.clone();
+            let builder = Arc::clone(&strategy.builder);
+            self.metrics.inc_initiated_payload_builds();
+            strategy.metrics.inc_initiated_payload_builds();
+            self.executor.spawn_blocking(Box::pin(async move {
+                // acquire the permit for executing the task
+                let _permit = guard.acquire().await;
+                let result = builder.try_build(args);
+                let _ = tx.send(result);
+            }));
+
+            self.pending_builds.push((idx, PendingPayload::new(cancel, rx)));
+        }
+    }
+
+    /// Merges the reads of a finished build into the cache for the next builds.
+    fn merge_cached_reads(&mut self, cached_reads: CachedReads) {
+        match &mut self.cached_reads {
+            Some(cached) => cached.extend(cached_reads),
+            None => self.cached_reads = Some(cached_reads),
+        }
+    }
+
+    /// Handles the outcome of a finished build of the strategy with the given index.
+    fn on_build_finished(
+        &mut self,
+        idx: usize,
+        outcome: Result<BuildOutcome<Payload>, PayloadBuilderError>,
+    ) {
+        let strategy = self.strategies[idx].clone();
+        match outcome {
+            Ok(BuildOutcome::Better { payload, cached_reads }) => {
+                // the builds of a round compete with each other, so the payload may no longer be
+                // better than the best payload
+                if self.best_payload.as_ref().map_or(true, |best| payload.fees() > best.fees()) {
+                    debug!(target: "payload_builder", strategy = %strategy.name, value = %payload.fees(), "built better payload");
+                    strategy.metrics.inc_better_payloads();
+                    self.best_payload = Some(payload);
+                    self.best_strategy = Some(idx);
+                } else {
+                    trace!(target: "payload_builder", strategy = %strategy.name, worse_fees = %payload.fees(), "payload of competing strategy is better");
+                }
+                self.merge_cached_reads(cached_reads);
+            }
+            Ok(BuildOutcome::Aborted { fees, cached_reads }) => {
+                trace!(target: "payload_builder", strategy = %strategy.name, worse_fees = %fees, "skipped payload build of worse block");
+                self.merge_cached_reads(cached_reads);
+            }
+            Ok(BuildOutcome::Cancelled) => {
+                // the job never cancels its builds, but a strategy may give up on its own
+                trace!(target: "payload_builder", strategy = %strategy.name, "payload build cancelled");
+            }
+            Err(error) => {
+                // build failed, but we simply try again next interval
+                debug!(target: "payload_builder", strategy = %strategy.name, %error, "payload build attempt failed");
+                self.metrics.inc_failed_payload_builds();
+                strategy.metrics.inc_failed_payload_builds();
+            }
+        }
+    }
+}
+
+impl<Client, Pool, Tasks, Attributes, Payload> Future
+    for MultiStrategyPayloadJob<Client, Pool, Tasks, Attributes, Payload>
+where
+    Client: StateProviderFactory + Clone + Unpin + 'static,
+    Pool: TransactionPool + Unpin + 'static,
+    Tasks: TaskSpawner + Clone + 'static,
+    Attributes: PayloadBuilderAttributes + Unpin + Clone + 'static,
+    Payload: BuiltPayload + Unpin + Clone + 'static,
+{
+    type Output = Result<(), PayloadBuilderError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        // check if the deadline is reached
+        if this.deadline.as_mut().poll(cx).is_ready() {
+            trace!(target: "payload_builder", "payload building deadline reached");
+            return Poll::Ready(Ok(()))
+        }
+
+        // check if the interval is reached
+        while this.interval.poll_tick(cx).is_ready() {
+            // start new builds if the previous round finished
+            if this.pending_builds.is_empty() {
+                this.spawn_build_jobs();
+            }
+        }
+
+        // poll the pending builds
+        if !this.pending_builds.is_empty() {
+            let mut pending = std::mem::take(&mut this.pending_builds);
+            pending.retain_mut(|(idx, fut)| match fut.poll_unpin(cx) {
+                Poll::Ready(outcome) => {
+                    this.on_build_finished(*idx, outcome);
+                    false
+                }
+                Poll::Pending => true,
+            });
+            if pending.is_empty() {
+                this.interval.reset();
+            }
+            this.pending_builds = pending;
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<Client, Pool, Tasks, Attributes, Payload> PayloadJob
+    for MultiStrategyPayloadJob<Client, Pool, Tasks, Attributes, Payload>
+where
+    Client: StateProviderFactory + Clone + Unpin + 'static,
+    Pool: TransactionPool + Unpin + 'static,
+    Tasks: TaskSpawner + Clone + 'static,
+    Attributes: PayloadBuilderAttributes + Unpin + Clone + 'static,
+    Payload: BuiltPayload + Unpin + Clone + 'static,
+{
+    type PayloadAttributes = Attributes;
+    type ResolvePayloadFuture = ResolveStrategyPayload<Payload>;
+    type BuiltPayload = Payload;
+
+    fn best_payload(&self) -> Result<Payload, PayloadBuilderError> {
+        if let Some(ref payload) = self.best_payload {
+            return Ok(payload.clone())
+        }
+        // No payload has been built yet, but we need to return something that the CL then can
+        // deliver, so we need to return an empty payload.
+        self.metrics.inc_requested_empty_payload();
+        self.strategies[0].builder.build_empty_payload(&self.client, self.config.clone())
+    }
+
+    fn payload_attributes(&self) -> Result<Attributes, PayloadBuilderError> {
+        Ok(self.config.attributes.clone())
+    }
+
+    fn resolve(&mut self) -> (Self::ResolvePayloadFuture, KeepPayloadJobAlive) {
+        let best_payload = self.best_strategy.zip(self.best_payload.take());
+
+        if best_payload.is_none() && self.pending_builds.is_empty() {
+            // ensure we have builds scheduled if we don't have a best payload yet and none are
+            // active
+            self.spawn_build_jobs();
+        }
+
+        let pending_builds = std::mem::take(&mut self.pending_builds);
+        let mut empty_payload = None;
+
+        if best_payload.is_none() {
+            debug!(target: "payload_builder", id=%self.config.payload_id(), "no best payload yet to resolve, racing empty payload");
+
+            self.metrics.inc_requested_empty_payload();
+            let (tx, rx) = oneshot::channel();
+            let client = self.client.clone();
+            let config = self.config.clone();
+            let builder = Arc::clone(&self.strategies[0].builder);
+            self.executor.spawn_blocking(Box::pin(async move {
+                let res = builder.build_empty_payload(&client, config);
+                let _ = tx.send(res);
+            }));
+
+            empty_payload = Some(rx);
+        }
+
+        let fut = ResolveStrategyPayload {
+            best_payload,
+            pending_builds,
+            better_payload: None,
+            empty_payload,
+            strategies: self
+                .strategies
+                .iter()
+                .map(|strategy| (Arc::clone(&strategy.name), strategy.metrics.clone()))
+                .collect(),
+        };
+
+        (fut, KeepPayloadJobAlive::No)
+    }
+}
+
+/// The future that resolves the payload of a [`MultiStrategyPayloadJob`].
+///
+/// Like [`ResolveBestPayload`](crate::ResolveBestPayload), builds that are still in progress are
+/// not awaited if a payload was built already, and only replace it if they have higher fees. The
+/// builds may have started before the best payload was built, so they are compared with it here. If
+/// no payload was built yet, this returns the best payload of the builds in progress or the empty
+/// payload, whichever is ready first.
+///
+/// The strategy whose payload is returned is credited with the resolved payload.
+#[derive(Debug)]
+pub struct ResolveStrategyPayload<Payload> {
+    /// Best payload so far, with the index of its strategy.
+    best_payload: Option<(usize, Payload)>,
+    /// The builds that are in progress, with the index of their strategy.
+    pending_builds: Vec<(usize, PendingPayload<Payload>)>,
+    /// The best payload of the finished builds, with the index of its strategy.
+    better_payload: Option<(usize, Payload)>,
+    /// The empty payload building job in progress, if any.
+    empty_payload: Option<oneshot::Receiver<Result<Payload, PayloadBuilderError>>>,
+    /// The names and metrics of the strategies.
+    strategies: Vec<(Arc<str>, PayloadStrategyMetrics)>,
+}
+
+impl<Payload> ResolveStrategyPayload<Payload>
+where
+    Payload: BuiltPayload,
+{
+    /// Returns the payload with the highest fees of the best and the better payload.
+    fn take_best(&mut self) -> Option<(usize, Payload)> {
+        match (self.best_payload.take(), self.better_payload.take()) {
+            (Some(best), Some(better)) if better.1.fees() > best.1.fees() => Some(better),
+            (Some(best), _) => Some(best),
+            (None, better) => better,
+        }
+    }
+}
+
+impl<Payload> Future for ResolveStrategyPayload<Payload>
+where
+    Payload: BuiltPayload + Unpin,
+{
+    type Output = Result<Payload, PayloadBuilderError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        // check if any of the builds in progress built a better payload
+        let better_payload = &mut this.better_payload;
+        this.pending_builds.retain_mut(|(idx, build)| match build.poll_unpin(cx) {
+            Poll::Ready(outcome) => {
+                if let Ok(BuildOutcome::Better { payload, .. }) = outcome {
+                    if better_payload
+                        .as_ref()
+                        .map_or(true, |(_, better)| payload.fees() > better.fees())
+                    {
+                        *better_payload = Some((*idx, payload));
+                    }
+                }
+                false
+            }
+            Poll::Pending => true,
+        });
+
+        if this.best_payload.is_some() || this.pending_builds.is_empty() {
+            if let Some((idx, payload)) = this.take_best() {
+                let (name, metrics) = &this.strategies[idx];
+                debug!(target: "payload_builder", strategy = %name, value = %payload.fees(), "resolving best payload");
+                metrics.inc_resolved_payloads();
+                return Poll::Ready(Ok(payload))
+            }
+        }
+
+        if let Some(fut) = Pin::new(&mut this.empty_payload).as_pin_mut() {
+            if let Poll::Ready(res) = fut.poll(cx) {
+                this.empty_payload = None;
+                return match res {
+                    Ok(res) => {
+                        if let Err(err) = &res {
+                            warn!(target: "payload_builder", %err, "failed to resolve empty payload");
+                        } else {
+                            debug!(target: "payload_builder", "resolving empty payload");
+                        }
+                        Poll::Ready(res)
+                    }
+                    Err(err) => Poll::Ready(Err(err.into())),
+                }
+            }
+        }
+
+        if this.pending_builds.is_empty() && this.empty_payload.is_none() {
+            return Poll::Ready(Err(PayloadBuilderError::MissingPayload))
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::MAINNET;
+    use reth_payload_builder::{EthBuiltPayload, EthPayloadBuilderAttributes};
+    use reth_primitives::{Block, SealedBlock, B256, U256};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_rpc_types::engine::PayloadAttributes;
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::noop::NoopTransactionPool;
+    use std::time::Duration;
+
+    type TestGenerator = MultiStrategyPayloadJobGenerator<
+        MockEthProvider,
+        NoopTransactionPool,
+        TokioTaskExecutor,
+        EthPayloadBuilderAttributes,
+        EthBuiltPayload,
+    >;
+    type TestJob = MultiStrategyPayloadJob<
+        MockEthProvider,
+        NoopTransactionPool,
+        TokioTaskExecutor,
+        EthPayloadBuilderAttributes,
+        EthBuiltPayload,
+    >;
+
+    /// A builder that always builds a payload with the same fees, or fails.
+    #[derive(Debug, Clone)]
+    enum TestBuilder {
+        Fees(u64),
+        Cancelled,
+        Failing,
+    }
+
+    impl<Pool, Client> PayloadBuilder<Pool, Client> for TestBuilder
+    where
+        Pool: Send + Sync,
+        Client: Send + Sync,
+    {
+        type Attributes = EthPayloadBuilderAttributes;
+        type BuiltPayload = EthBuiltPayload;
+
+        fn try_build(
+            &self,
+            args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
+        ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
+            let fees = match self {
+                Self::Fees(fees) => U256::from(*fees),
+                Self::Cancelled => return Ok(BuildOutcome::Cancelled),
+                Self::Failing => return Err(PayloadBuilderError::MissingPayload),
+            };
+            let BuildArguments { cached_reads, config, best_payload, .. } = args;
+            if best_payload.is_some_and(|best| best.fees() >= fees) {
+                return Ok(BuildOutcome::Aborted { fees, cached_reads })
+            }
+            let payload = EthBuiltPayload::new(config.payload_id(), SealedBlock::default(), fees);
+            Ok(BuildOutcome::Better { payload, cached_reads })
+        }
+
+        fn build_empty_payload(
+            &self,
+            _client: &Client,
+            config: PayloadConfig<Self::Attributes>,
+        ) -> Result<Self::BuiltPayload, PayloadBuilderError> {
+            Ok(EthBuiltPayload::new(config.payload_id(), SealedBlock::default(), U256::ZERO))
+        }
+    }
+
+    fn generator(strategies: Vec<(&str, TestBuilder)>) -> (TestGenerator, B256) {
+        let client = MockEthProvider::default();
+        let parent = B256::with_last_byte(1);
+        client.add_block(parent, Block::default());
+
+        let generator = MultiStrategyPayloadJobGenerator::new(
+            client,
+            NoopTransactionPool::default(),
+            TokioTaskExecutor::default(),
+            BasicPayloadJobGeneratorConfig::default().interval(Duration::from_millis(10)),
+            MAINNET.clone(),
+            strategies
+                .into_iter()
+                .map(|(name, builder)| PayloadStrategy::new(name, builder))
+                .collect(),
+        );
+        (generator, parent)
+    }
+
+    fn attributes(parent: B256) -> EthPayloadBuilderAttributes {
+        EthPayloadBuilderAttributes::new(
+            parent,
+            PayloadAttributes {
+                timestamp: 1,
+                prev_randao: B256::ZERO,
+                suggested_fee_recipient: Default::default(),
+                withdrawals: None,
+                parent_beacon_block_root: None,
+            },
+        )
+    }
+
+    /// Drives the job for a few build rounds.
+    async fn poll_rounds(job: &mut TestJob) {
+        let _ = tokio::time::timeout(Duration::from_millis(100), job).await;
+    }
+
+    #[tokio::test]
+    async fn keeps_best_payload_of_all_strategies() {
+        let (generator, parent) = generator(vec![
+            ("low", TestBuilder::Fees(1)),
+            ("high", TestBuilder::Fees(5)),
+            ("cancelled", TestBuilder::Cancelled),
+            ("failing", TestBuilder::Failing),
+        ]);
+        assert_eq!(
+            generator.strategies().iter().map(PayloadStrategy::name).collect::<Vec<_>>(),
+            vec!["low", "high", "cancelled", "failing"]
+        );
+
+        let mut job = generator.new_payload_job(attributes(parent)).unwrap();
+        // the first round is spawned right away
+        assert_eq!(job.pending_builds.len(), 4);
+
+        poll_rounds(&mut job).await;
+        assert_eq!(job.best_payload().unwrap().fees(), U256::from(5));
+        assert_eq!(job.best_strategy, Some(1));
+
+        let (resolved, _) = job.resolve();
+        assert_eq!(resolved.await.unwrap().fees(), U256::from(5));
+    }
+
+    #[tokio::test]
+    async fn resolves_empty_payload_without_built_payload() {
+        let (generator, parent) = generator(vec![
+            ("cancelled", TestBuilder::Cancelled),
+            ("failing", TestBuilder::Failing),
+        ]);
+        let mut job = generator.new_payload_job(attributes(parent)).unwrap();
+
+        poll_rounds(&mut job).await;
+        assert!(job.best_payload.is_none());
+        assert_eq!(job.best_payload().unwrap().fees(), U256::ZERO);
+
+        let (resolved, _) = job.resolve();
+        assert_eq!(resolved.await.unwrap().fees(), U256::ZERO);
+    }
+
+    #[tokio::test]
+    async fn resolves_best_payload_over_worse_pending_build() {
+        let (generator, parent) =
+            generator(vec![("low", TestBuilder::Fees(1)), ("high", TestBuilder::Fees(5))]);
+        let mut job = generator.new_payload_job(attributes(parent)).unwrap();
+        poll_rounds(&mut job).await;
+        assert_eq!(job.best_strategy, Some(1));
+
+        // a build that started before the best payload was built finishes with lower fees
+        let (tx, rx) = oneshot::channel();
+        let payload =
+            EthBuiltPayload::new(job.config.payload_id(), SealedBlock::default(), U256::from(1));
+        tx.send(Ok(BuildOutcome::Better { payload, cached_reads: Default::default() })).unwrap();
+        job.pending_builds = vec![(0, PendingPayload::new(Cancelled::default(), rx))];
+
+        let (resolved, _) = job.resolve();
+        assert_eq!(resolved.await.unwrap().fees(), U256::from(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one payload strategy is required")]
+    fn requires_strategies() {
+        generator(Vec::new());
+    }
+}
@@ -59,6 +59,21 @@ impl CachedReads {
     ) {
         self.accounts.insert(address, CachedAccount { info: Some(info), storage });
     }
+
+    /// Extends the cache with the reads of another cache of the same state, e.g. of a concurrent
+    /// payload build attempt.
+    pub fn extend(&mut self, other: Self) {
+        for (address, account) in other.accounts {
+            match self.accounts.entry(address) {
+                Entry::Occupied(mut entry) => entry.get_mut().storage.extend(account.storage),
+                Entry::Vacant(entry) => {
+                    entry.insert(account);
+                }
+            }
+        }
+        self.contracts.extend(other.contracts);
+        self.block_hashes.extend(other.block_hashes);
+    }
 }
 
 /// A [Database] that caches reads inside [`CachedReads`].
@@ -0,0 +1,133 @@
+//! Payload building for relays.
+
+use crate::{ProposerDuties, RelayBuilderConfig, RelaySubmitter};
+use reth_basic_payload_builder::{BuildArguments, BuildOutcome, PayloadBuilder, PayloadConfig};
+use reth_ethereum_payload_builder::{
+    default_ethereum_payload_builder, ethereum_payload_with_proposer_payment,
+    EthereumPayloadBuilder, ProposerPayment,
+};
+use reth_evm::ConfigureEvm;
+use reth_evm_ethereum::EthEvmConfig;
+use reth_payload_builder::{
+    error::PayloadBuilderError, EthBuiltPayload, EthPayloadBuilderAttributes, PayloadJobGenerator,
+};
+use reth_primitives::B256;
+use reth_provider::{CanonStateNotification, StateProviderFactory};
+use reth_transaction_pool::TransactionPool;
+use tracing::trace;
+
+/// An Ethereum [`PayloadBuilder`] that builds payloads for the proposers registered with the
+/// relays and hands every better payload to the [`RelaySubmitter`].
+///
+/// The payloads pay the proposer with a [`ProposerPayment`] from the builder account, which must
+/// be the fee recipient of the payload attributes, and use the gas limit the proposer registered.
+/// Payloads for slots without a registered proposer are built like regular payloads and not
+/// submitted.
+#[derive(Clone)]
+pub struct RelayPayloadBuilder<EvmConfig = EthEvmConfig> {
+    /// The type responsible for creating the evm.
+    evm_config: EvmConfig,
+    /// The secret key of the builder account.
+    secret_key: B256,
+    config: RelayBuilderConfig,
+    duties: ProposerDuties,
+    submitter: RelaySubmitter,
+}
+
+impl<EvmConfig> RelayPayloadBuilder<EvmConfig> {
+    /// Creates a new builder that pays proposers from the account of the given secret key.
+    pub const fn new(
+        evm_config: EvmConfig,
+        secret_key: B256,
+        config: RelayBuilderConfig,
+        duties: ProposerDuties,
+        submitter: RelaySubmitter,
+    ) -> Self {
+        Self { evm_config, secret_key, config, duties, submitter }
+    }
+}
+
+impl<EvmConfig: std::fmt::Debug> std::fmt::Debug for RelayPayloadBuilder<EvmConfig> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RelayPayloadBuilder")
+            .field("evm_config", &self.evm_config)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<EvmConfig, Pool, Client> PayloadBuilder<Pool, Client> for RelayPayloadBuilder<EvmConfig>
+where
+    EvmConfig: ConfigureEvm,
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+{
+    type Attributes = EthPayloadBuilderAttributes;
+    type BuiltPayload = EthBuiltPayload;
+
+    fn try_build(
+        &self,
+        args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
+    ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
+        let slot = self.config.slot_at(args.config.attributes.timestamp);
+        let Some(proposer) = self.duties.get(slot) else {
+            trace!(target: "payload_builder::relay", slot, "no registered proposer, building regular payload");
+            return default_ethereum_payload_builder(self.evm_config.clone(), args)
+        };
+
+        let payment = ProposerPayment::new(self.secret_key, proposer.entry.message.fee_recipient)
+            .with_gas_limit(proposer.entry.message.gas_limit);
+        let outcome =
+            ethereum_payload_with_proposer_payment(self.evm_config.clone(), args, payment)?;
+        if let BuildOutcome::Better { payload, .. } = &outcome {
+            self.submitter.submit(slot, proposer, payload.clone());
+        }
+        Ok(outcome)
+    }
+
+    fn build_empty_payload(
+        &self,
+        client: &Client,
+        config: PayloadConfig<Self::Attributes>,
+    ) -> Result<EthBuiltPayload, PayloadBuilderError> {
+        <EthereumPayloadBuilder<EvmConfig> as PayloadBuilder<Pool, Client>>::build_empty_payload(
+            &EthereumPayloadBuilder::new(self.evm_config.clone()),
+            client,
+            config,
+        )
+    }
+}
+
+/// A [`PayloadJobGenerator`] that notifies the [`RelaySubmitter`] about new canonical heads, so
+/// that the proposer duties are kept up to date.
+#[derive(Debug)]
+pub struct RelayJobGenerator<Generator> {
+    inner: Generator,
+    submitter: RelaySubmitter,
+}
+
+impl<Generator> RelayJobGenerator<Generator> {
+    /// Wraps the given generator.
+    pub const fn new(inner: Generator, submitter: RelaySubmitter) -> Self {
+        Self { inner, submitter }
+    }
+}
+
+impl<Generator> PayloadJobGenerator for RelayJobGenerator<Generator>
+where
+    Generator: PayloadJobGenerator,
+{
+    type Job = Generator::Job;
+
+    fn new_payload_job(
+        &self,
+        attr: <Self::Job as reth_payload_builder::PayloadJob>::PayloadAttributes,
+    ) -> Result<Self::Job, PayloadBuilderError> {
+        self.inner.new_payload_job(attr)
+    }
+
+    fn on_new_state(&mut self, new_state: CanonStateNotification) {
+        self.submitter.on_new_head(new_state.tip().timestamp);
+        self.inner.on_new_state(new_state);
+    }
+}
@@ -0,0 +1,61 @@
+//! Configuration of the relay submission.
+
+/// The unix timestamp of the mainnet beacon chain genesis.
+pub const MAINNET_GENESIS_TIME: u64 = 1_606_824_023;
+
+/// The duration of a beacon chain slot in seconds.
+pub const SECONDS_PER_SLOT: u64 = 12;
+
+/// The beacon chain parameters needed to sign and time submissions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayBuilderConfig {
+    /// The unix timestamp of the beacon chain genesis.
+    pub genesis_time: u64,
+    /// The duration of a slot in seconds.
+    pub seconds_per_slot: u64,
+    /// The genesis fork version, which determines the builder signing domain.
+    pub genesis_fork_version: [u8; 4],
+}
+
+impl RelayBuilderConfig {
+    /// Returns the configuration of mainnet.
+    pub const fn mainnet() -> Self {
+        Self {
+            genesis_time: MAINNET_GENESIS_TIME,
+            seconds_per_slot: SECONDS_PER_SLOT,
+            genesis_fork_version: [0, 0, 0, 0],
+        }
+    }
+
+    /// Returns the configuration of the network with the given chain id, if it's a known network
+    /// with relays.
+    pub const fn from_chain_id(chain_id: u64) -> Option<Self> {
+        match chain_id {
+            1 => Some(Self::mainnet()),
+            // sepolia
+            11_155_111 => Some(Self {
+                genesis_time: 1_655_733_600,
+                seconds_per_slot: SECONDS_PER_SLOT,
+                genesis_fork_version: [0x90, 0x00, 0x00, 0x69],
+            }),
+            // holesky
+            17_000 => Some(Self {
+                genesis_time: 1_695_902_400,
+                seconds_per_slot: SECONDS_PER_SLOT,
+                genesis_fork_version: [0x01, 0x01, 0x70, 0x00],
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the slot of the block with the given timestamp.
+    pub const fn slot_at(&self, timestamp: u64) -> u64 {
+        timestamp.saturating_sub(self.genesis_time) / self.seconds_per_slot
+    }
+}
+
+impl Default for RelayBuilderConfig {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
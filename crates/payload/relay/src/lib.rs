@@ -0,0 +1,31 @@
+//! Block building for MEV-Boost relays.
+//!
+//! The [`RelayPayloadBuilder`] builds payloads for the proposers that registered with the relays
+//! and pays them with a transaction at the end of the block. The [`RelayService`] signs every
+//! better payload with the BLS key of the builder and submits it to all relays.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod builder;
+pub use builder::{RelayJobGenerator, RelayPayloadBuilder};
+
+mod config;
+pub use config::{RelayBuilderConfig, MAINNET_GENESIS_TIME, SECONDS_PER_SLOT};
+
+mod relay;
+pub use relay::{HttpRelay, Relay, RelayError, SignedBidSubmission};
+
+mod service;
+pub use service::{sign_submission, ProposerDuties, RelayService, RelaySubmitter};
+
+pub mod signer;
+pub use signer::{BuilderSigner, SignerError};
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
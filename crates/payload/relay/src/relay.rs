@@ -0,0 +1,116 @@
+//! Clients of the relay builder API.
+
+use futures_util::future::BoxFuture;
+use reth_rpc_types::beacon::relay::{
+    BidTrace, SignedBidSubmissionV2, SignedBidSubmissionV3, Validator,
+};
+use serde::Serialize;
+use std::fmt;
+
+/// A signed block submission to a relay.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum SignedBidSubmission {
+    /// A Capella block, submitted via `builder_submitBlockV2`.
+    V2(SignedBidSubmissionV2),
+    /// A Deneb block with its blobs, submitted via `builder_submitBlockV3`.
+    V3(SignedBidSubmissionV3),
+}
+
+impl SignedBidSubmission {
+    /// Returns the signed bid trace of the submission.
+    pub const fn bid_trace(&self) -> &BidTrace {
+        match self {
+            Self::V2(submission) => &submission.message,
+            Self::V3(submission) => &submission.message,
+        }
+    }
+}
+
+/// Errors returned by a [`Relay`].
+#[derive(Debug, thiserror::Error)]
+pub enum RelayError {
+    /// The request to the relay failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// The relay rejected the request.
+    #[error("relay responded with status {status}: {message}")]
+    Rejected {
+        /// The HTTP status code of the response.
+        status: u16,
+        /// The body of the response.
+        message: String,
+    },
+}
+
+/// The relay endpoints used by a block builder.
+pub trait Relay: Send + Sync + fmt::Debug {
+    /// Returns the proposers of the current and next epoch that registered with the relay.
+    fn validators(&self) -> BoxFuture<'_, Result<Vec<Validator>, RelayError>>;
+
+    /// Submits a signed block to the relay.
+    fn submit_block(
+        &self,
+        submission: SignedBidSubmission,
+    ) -> BoxFuture<'_, Result<(), RelayError>>;
+}
+
+/// A [`Relay`] that implements the relay builder API over HTTP.
+///
+/// Both submission versions are posted to `/relay/v1/builder/blocks`, the relay distinguishes them
+/// by their payload.
+#[derive(Debug, Clone)]
+pub struct HttpRelay {
+    /// The base URL of the relay.
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpRelay {
+    /// Creates a new client of the relay with the given base URL.
+    pub fn new(url: impl Into<String>) -> Self {
+        let url = url.into().trim_end_matches('/').to_string();
+        Self { url, client: reqwest::Client::new() }
+    }
+
+    /// Returns the base URL of the relay.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Relay for HttpRelay {
+    fn validators(&self) -> BoxFuture<'_, Result<Vec<Validator>, RelayError>> {
+        Box::pin(async move {
+            let response =
+                self.client.get(format!("{}/relay/v1/builder/validators", self.url)).send().await?;
+            Ok(ensure_success(response).await?.json().await?)
+        })
+    }
+
+    fn submit_block(
+        &self,
+        submission: SignedBidSubmission,
+    ) -> BoxFuture<'_, Result<(), RelayError>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(format!("{}/relay/v1/builder/blocks", self.url))
+                .json(&submission)
+                .send()
+                .await?;
+            ensure_success(response).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Returns an error with the body of the response if the request was not successful.
+async fn ensure_success(response: reqwest::Response) -> Result<reqwest::Response, RelayError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response)
+    }
+    let message = response.text().await.unwrap_or_default();
+    Err(RelayError::Rejected { status: status.as_u16(), message })
+}
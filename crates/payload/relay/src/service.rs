@@ -0,0 +1,291 @@
+//! The service that signs and submits payloads to relays.
+
+use crate::{BuilderSigner, Relay, RelayBuilderConfig, SignedBidSubmission};
+use futures_util::{Stream, StreamExt};
+use parking_lot::RwLock;
+use reth_payload_builder::{EthBuiltPayload, Events};
+use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes, PayloadTypes};
+use reth_rpc_types::{
+    beacon::relay::{BidTrace, SignedBidSubmissionV2, SignedBidSubmissionV3, Validator},
+    engine::ExecutionPayloadEnvelopeV3,
+};
+use reth_rpc_types_compat::engine::payload::block_to_payload_v2;
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::mpsc;
+use tracing::{debug, trace, warn};
+
+/// The proposers that registered with the relays, by slot.
+#[derive(Debug, Clone, Default)]
+pub struct ProposerDuties {
+    inner: Arc<RwLock<BTreeMap<u64, Validator>>>,
+}
+
+impl ProposerDuties {
+    /// Returns the registered proposer of the slot.
+    pub fn get(&self, slot: u64) -> Option<Validator> {
+        self.inner.read().get(&slot).cloned()
+    }
+
+    /// Adds the proposers returned by a relay.
+    pub fn extend(&self, validators: impl IntoIterator<Item = Validator>) {
+        self.inner.write().extend(validators.into_iter().map(|v| (v.slot, v)));
+    }
+
+    /// Removes the proposers of all slots up to and including the given slot.
+    pub fn remove_until(&self, slot: u64) {
+        let mut inner = self.inner.write();
+        *inner = inner.split_off(&(slot + 1));
+    }
+
+    /// Returns the number of slots with a registered proposer.
+    pub fn len(&self) -> usize {
+        self.inner.read().len()
+    }
+
+    /// Returns true if no proposer is registered.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().is_empty()
+    }
+}
+
+/// Commands sent to the [`RelayService`].
+#[derive(Debug)]
+enum RelayCommand {
+    /// A new block was added to the canonical chain.
+    NewHead { timestamp: u64 },
+    /// A better payload was built for the slot.
+    Submit { slot: u64, proposer: Validator, payload: EthBuiltPayload },
+}
+
+/// A handle to the [`RelayService`].
+#[derive(Debug, Clone)]
+pub struct RelaySubmitter {
+    to_service: mpsc::UnboundedSender<RelayCommand>,
+}
+
+impl RelaySubmitter {
+    /// Submits the payload built for the given proposer to all relays.
+    pub fn submit(&self, slot: u64, proposer: Validator, payload: EthBuiltPayload) {
+        let _ = self.to_service.send(RelayCommand::Submit { slot, proposer, payload });
+    }
+
+    /// Notifies the service about a new canonical head, to refresh the proposer duties.
+    pub fn on_new_head(&self, timestamp: u64) {
+        let _ = self.to_service.send(RelayCommand::NewHead { timestamp });
+    }
+}
+
+/// Signs the payloads built for registered proposers and submits them to all relays.
+///
+/// The service refreshes the [`ProposerDuties`] when the canonical head changes and when payload
+/// attributes arrive for a slot without a known proposer.
+#[derive(Debug)]
+pub struct RelayService {
+    relays: Vec<Arc<dyn Relay>>,
+    signer: BuilderSigner,
+    config: RelayBuilderConfig,
+    duties: ProposerDuties,
+    commands: mpsc::UnboundedReceiver<RelayCommand>,
+}
+
+impl RelayService {
+    /// Creates a new service that submits to the given relays and returns a handle to it.
+    pub fn new(
+        relays: Vec<Arc<dyn Relay>>,
+        signer: BuilderSigner,
+        config: RelayBuilderConfig,
+    ) -> (Self, RelaySubmitter) {
+        let (to_service, commands) = mpsc::unbounded_channel();
+        let service = Self { relays, signer, config, duties: ProposerDuties::default(), commands };
+        (service, RelaySubmitter { to_service })
+    }
+
+    /// Returns the proposer duties maintained by the service.
+    pub fn duties(&self) -> ProposerDuties {
+        self.duties.clone()
+    }
+
+    /// Runs the service until all [`RelaySubmitter`]s are dropped.
+    ///
+    /// The payload events are the events of the
+    /// [`PayloadBuilderHandle`](reth_payload_builder::PayloadBuilderHandle), an empty stream can be
+    /// passed if they're not available.
+    pub async fn run<Engine, S>(mut self, mut payload_events: S)
+    where
+        Engine: PayloadTypes,
+        S: Stream<Item = Events<Engine>> + Unpin,
+    {
+        self.refresh_duties();
+        loop {
+            tokio::select! {
+                command = self.commands.recv() => match command {
+                    Some(RelayCommand::NewHead { timestamp }) => {
+                        self.duties.remove_until(self.config.slot_at(timestamp));
+                        self.refresh_duties();
+                    }
+                    Some(RelayCommand::Submit { slot, proposer, payload }) => {
+                        self.submit(slot, &proposer, payload);
+                    }
+                    None => break,
+                },
+                Some(event) = payload_events.next() => {
+                    if let Events::Attributes(attributes) = event {
+                        let slot = self.config.slot_at(attributes.timestamp());
+                        if self.duties.get(slot).is_none() {
+                            trace!(target: "payload_builder::relay", slot, "no proposer for payload attributes");
+                            self.refresh_duties();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fetches the registered proposers from all relays.
+    fn refresh_duties(&self) {
+        for relay in &self.relays {
+            let relay = Arc::clone(relay);
+            let duties = self.duties.clone();
+            tokio::spawn(async move {
+                match relay.validators().await {
+                    Ok(validators) => duties.extend(validators),
+                    Err(err) => {
+                        warn!(target: "payload_builder::relay", ?relay, %err, "failed to fetch proposer duties")
+                    }
+                }
+            });
+        }
+    }
+
+    /// Signs the payload and submits it to all relays.
+    fn submit(&self, slot: u64, proposer: &Validator, payload: EthBuiltPayload) {
+        let submission = sign_submission(&self.signer, slot, proposer, payload);
+        debug!(target: "payload_builder::relay", slot, block_hash=%submission.bid_trace().block_hash, value=%submission.bid_trace().value, "submitting payload");
+        for relay in &self.relays {
+            let relay = Arc::clone(relay);
+            let submission = submission.clone();
+            tokio::spawn(async move {
+                if let Err(err) = relay.submit_block(submission).await {
+                    warn!(target: "payload_builder::relay", ?relay, %err, "failed to submit payload");
+                }
+            });
+        }
+    }
+}
+
+/// Creates the signed submission of the payload built for the given proposer.
+///
+/// Payloads with a parent beacon block root are Deneb payloads, submitted with their blobs.
+pub fn sign_submission(
+    signer: &BuilderSigner,
+    slot: u64,
+    proposer: &Validator,
+    payload: EthBuiltPayload,
+) -> SignedBidSubmission {
+    let block = payload.block();
+    let message = BidTrace {
+        slot,
+        parent_hash: block.parent_hash,
+        block_hash: block.hash(),
+        builder_pubkey: signer.public_key(),
+        proposer_pubkey: proposer.entry.message.pubkey,
+        proposer_fee_recipient: proposer.entry.message.fee_recipient,
+        gas_limit: block.gas_limit,
+        gas_used: block.gas_used,
+        value: payload.fees(),
+    };
+    let signature = signer.sign_bid_trace(&message);
+
+    if block.parent_beacon_block_root.is_some() {
+        let envelope = ExecutionPayloadEnvelopeV3::from(payload);
+        SignedBidSubmission::V3(SignedBidSubmissionV3 {
+            message,
+            execution_payload: envelope.execution_payload,
+            blobs_bundle: envelope.blobs_bundle,
+            signature,
+        })
+    } else {
+        let execution_payload = block_to_payload_v2(payload.block().clone());
+        SignedBidSubmission::V2(SignedBidSubmissionV2 { message, execution_payload, signature })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{signer::verify_bid_trace, test_utils::MockRelay};
+    use reth_ethereum_engine_primitives::EthEngineTypes;
+    use reth_payload_builder::PayloadId;
+    use reth_primitives::{Address, SealedBlock, U256};
+    use reth_rpc_types::beacon::{
+        relay::{ValidatorRegistration, ValidatorRegistrationMessage},
+        BlsPublicKey, BlsSignature,
+    };
+    use std::time::Duration;
+
+    fn validator(slot: u64) -> Validator {
+        Validator {
+            slot,
+            validator_index: 1,
+            entry: ValidatorRegistration {
+                message: ValidatorRegistrationMessage {
+                    fee_recipient: Address::random(),
+                    gas_limit: 30_000_000,
+                    timestamp: 0,
+                    pubkey: BlsPublicKey::random(),
+                },
+                signature: BlsSignature::ZERO,
+            },
+        }
+    }
+
+    #[test]
+    fn remove_past_duties() {
+        let duties = ProposerDuties::default();
+        duties.extend([validator(1), validator(2), validator(3)]);
+        duties.remove_until(2);
+        assert_eq!(duties.len(), 1);
+        assert!(duties.get(3).is_some());
+    }
+
+    #[tokio::test]
+    async fn submit_to_mock_relay() {
+        let relay = Arc::new(MockRelay::default());
+        relay.add_validator(validator(10));
+        let signer = BuilderSigner::new(&[1; 32], [0, 0, 0, 0]).unwrap();
+        let (service, submitter) =
+            RelayService::new(vec![relay.clone()], signer.clone(), RelayBuilderConfig::mainnet());
+        let duties = service.duties();
+        let service =
+            tokio::spawn(service.run::<EthEngineTypes, _>(futures_util::stream::pending()));
+
+        // wait for the duties to be fetched
+        while duties.get(10).is_none() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let proposer = duties.get(10).unwrap();
+        let payload =
+            EthBuiltPayload::new(PayloadId::default(), SealedBlock::default(), U256::from(7));
+        submitter.submit(10, proposer.clone(), payload);
+        drop(submitter);
+        service.await.unwrap();
+
+        // submissions are spawned
+        while relay.submissions().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let submissions = relay.submissions();
+        let SignedBidSubmission::V2(submission) = &submissions[0] else {
+            panic!("expected a capella submission")
+        };
+        assert_eq!(submission.message.slot, 10);
+        assert_eq!(submission.message.value, U256::from(7));
+        assert_eq!(submission.message.proposer_pubkey, proposer.entry.message.pubkey);
+        assert!(verify_bid_trace(
+            &submission.message,
+            &submission.signature,
+            &signer.public_key(),
+            signer.domain()
+        ));
+    }
+}
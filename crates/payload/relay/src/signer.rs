@@ -0,0 +1,182 @@
+//! BLS signing of bid submissions.
+
+use blst::min_pk::{PublicKey, SecretKey};
+use reth_primitives::B256;
+use reth_rpc_types::beacon::{relay::BidTrace, BlsPublicKey, BlsSignature};
+use sha2::{Digest, Sha256};
+
+/// The domain type of builder signatures, `DOMAIN_APPLICATION_BUILDER`.
+pub const DOMAIN_APPLICATION_BUILDER: [u8; 4] = [0, 0, 0, 1];
+
+/// The domain separation tag of BLS signatures on the beacon chain.
+const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Errors of the [`BuilderSigner`].
+#[derive(Debug, thiserror::Error)]
+pub enum SignerError {
+    /// The secret key is not a valid BLS secret key.
+    #[error("invalid BLS secret key: {0:?}")]
+    InvalidSecretKey(blst::BLST_ERROR),
+}
+
+/// Signs [`BidTrace`]s with the BLS key of the builder.
+#[derive(Clone)]
+pub struct BuilderSigner {
+    secret_key: SecretKey,
+    public_key: BlsPublicKey,
+    /// The builder signing domain of the network.
+    domain: B256,
+}
+
+impl BuilderSigner {
+    /// Creates a new signer from the 32 bytes BLS secret key and the genesis fork version of the
+    /// network.
+    pub fn new(secret_key: &[u8], genesis_fork_version: [u8; 4]) -> Result<Self, SignerError> {
+        let secret_key =
+            SecretKey::from_bytes(secret_key).map_err(SignerError::InvalidSecretKey)?;
+        let public_key = BlsPublicKey::from(secret_key.sk_to_pk().to_bytes());
+        Ok(Self { secret_key, public_key, domain: builder_domain(genesis_fork_version) })
+    }
+
+    /// Returns the BLS public key of the builder.
+    pub const fn public_key(&self) -> BlsPublicKey {
+        self.public_key
+    }
+
+    /// Returns the builder signing domain.
+    pub const fn domain(&self) -> B256 {
+        self.domain
+    }
+
+    /// Signs the bid trace in the builder domain.
+    pub fn sign_bid_trace(&self, bid_trace: &BidTrace) -> BlsSignature {
+        let signing_root = signing_root(bid_trace_root(bid_trace), self.domain);
+        let signature = self.secret_key.sign(signing_root.as_slice(), BLS_DST, &[]);
+        BlsSignature::from(signature.to_bytes())
+    }
+}
+
+impl std::fmt::Debug for BuilderSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BuilderSigner")
+            .field("public_key", &self.public_key)
+            .field("domain", &self.domain)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns true if the signature of the bid trace is valid for the given public key and domain.
+pub fn verify_bid_trace(
+    bid_trace: &BidTrace,
+    signature: &BlsSignature,
+    public_key: &BlsPublicKey,
+    domain: B256,
+) -> bool {
+    let (Ok(signature), Ok(public_key)) = (
+        blst::min_pk::Signature::from_bytes(signature.as_slice()),
+        PublicKey::from_bytes(public_key.as_slice()),
+    ) else {
+        return false
+    };
+    let signing_root = signing_root(bid_trace_root(bid_trace), domain);
+    signature.verify(true, signing_root.as_slice(), BLS_DST, &[], &public_key, true) ==
+        blst::BLST_ERROR::BLST_SUCCESS
+}
+
+/// Computes the builder signing domain, `compute_domain(DOMAIN_APPLICATION_BUILDER,
+/// genesis_fork_version, ZERO_HASH)`.
+///
+/// Builder signatures are not bound to a fork, so the genesis validators root is zero.
+pub fn builder_domain(genesis_fork_version: [u8; 4]) -> B256 {
+    // hash_tree_root(ForkData(current_version, genesis_validators_root))
+    let fork_data_root = hash_pair(pad(&genesis_fork_version), B256::ZERO);
+
+    let mut domain = B256::ZERO;
+    domain[..4].copy_from_slice(&DOMAIN_APPLICATION_BUILDER);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+    domain
+}
+
+/// Computes the SSZ `hash_tree_root` of the bid trace.
+pub fn bid_trace_root(bid_trace: &BidTrace) -> B256 {
+    merkleize(&[
+        pad(&bid_trace.slot.to_le_bytes()),
+        bid_trace.parent_hash,
+        bid_trace.block_hash,
+        bytes48_root(&bid_trace.builder_pubkey),
+        bytes48_root(&bid_trace.proposer_pubkey),
+        pad(bid_trace.proposer_fee_recipient.as_slice()),
+        pad(&bid_trace.gas_limit.to_le_bytes()),
+        pad(&bid_trace.gas_used.to_le_bytes()),
+        B256::from(bid_trace.value.to_le_bytes::<32>()),
+    ])
+}
+
+/// Computes the SSZ `hash_tree_root` of `SigningData(object_root, domain)`.
+fn signing_root(object_root: B256, domain: B256) -> B256 {
+    hash_pair(object_root, domain)
+}
+
+/// The root of a BLS public key, a 48 byte vector which spans two chunks.
+fn bytes48_root(bytes: &BlsPublicKey) -> B256 {
+    hash_pair(pad(&bytes[..32]), pad(&bytes[32..]))
+}
+
+/// Merkleizes the chunks, padded with zero chunks to the next power of two.
+fn merkleize(chunks: &[B256]) -> B256 {
+    let mut layer = chunks.to_vec();
+    layer.resize(chunks.len().next_power_of_two(), B256::ZERO);
+    while layer.len() > 1 {
+        layer = layer.chunks_exact(2).map(|pair| hash_pair(pair[0], pair[1])).collect();
+    }
+    layer[0]
+}
+
+fn hash_pair(left: B256, right: B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+/// Right-pads the bytes to a 32 byte chunk.
+fn pad(bytes: &[u8]) -> B256 {
+    let mut chunk = B256::ZERO;
+    chunk[..bytes.len()].copy_from_slice(bytes);
+    chunk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{b256, Address, U256};
+
+    #[test]
+    fn mainnet_builder_domain() {
+        assert_eq!(
+            builder_domain([0, 0, 0, 0]),
+            b256!("00000001f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9")
+        );
+    }
+
+    #[test]
+    fn sign_and_verify_bid_trace() {
+        let signer = BuilderSigner::new(&[1; 32], [0, 0, 0, 0]).unwrap();
+        let mut bid_trace = BidTrace {
+            slot: 1,
+            parent_hash: B256::random(),
+            block_hash: B256::random(),
+            builder_pubkey: signer.public_key(),
+            proposer_pubkey: BlsPublicKey::random(),
+            proposer_fee_recipient: Address::random(),
+            gas_limit: 30_000_000,
+            gas_used: 21_000,
+            value: U256::from(1),
+        };
+        let signature = signer.sign_bid_trace(&bid_trace);
+        assert!(verify_bid_trace(&bid_trace, &signature, &signer.public_key(), signer.domain()));
+
+        bid_trace.value = U256::from(2);
+        assert!(!verify_bid_trace(&bid_trace, &signature, &signer.public_key(), signer.domain()));
+    }
+}
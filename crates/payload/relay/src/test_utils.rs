@@ -0,0 +1,40 @@
+//! Utilities for testing the relay submission.
+
+use crate::{Relay, RelayError, SignedBidSubmission};
+use futures_util::future::BoxFuture;
+use parking_lot::Mutex;
+use reth_rpc_types::beacon::relay::Validator;
+
+/// A local [`Relay`] that serves the configured validators and records all submissions.
+#[derive(Debug, Default)]
+pub struct MockRelay {
+    validators: Mutex<Vec<Validator>>,
+    submissions: Mutex<Vec<SignedBidSubmission>>,
+}
+
+impl MockRelay {
+    /// Registers a proposer with the relay.
+    pub fn add_validator(&self, validator: Validator) {
+        self.validators.lock().push(validator);
+    }
+
+    /// Returns all submissions the relay received.
+    pub fn submissions(&self) -> Vec<SignedBidSubmission> {
+        self.submissions.lock().clone()
+    }
+}
+
+impl Relay for MockRelay {
+    fn validators(&self) -> BoxFuture<'_, Result<Vec<Validator>, RelayError>> {
+        let validators = self.validators.lock().clone();
+        Box::pin(async move { Ok(validators) })
+    }
+
+    fn submit_block(
+        &self,
+        submission: SignedBidSubmission,
+    ) -> BoxFuture<'_, Result<(), RelayError>> {
+        self.submissions.lock().push(submission);
+        Box::pin(async { Ok(()) })
+    }
+}
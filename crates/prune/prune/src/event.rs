@@ -1,5 +1,5 @@
 use alloy_primitives::BlockNumber;
-use reth_prune_types::{PruneProgress, PruneSegment};
+use reth_prune_types::{PruneProgress, PruneSegment, PrunedSegmentStats, PrunerProgress};
 use std::time::Duration;
 
 /// An event emitted by a [Pruner][crate::Pruner].
@@ -14,3 +14,23 @@ pub enum PrunerEvent {
         stats: Vec<(PruneSegment, usize, PruneProgress)>,
     },
 }
+
+impl From<PrunerEvent> for PrunerProgress {
+    fn from(event: PrunerEvent) -> Self {
+        match event {
+            PrunerEvent::Started { tip_block_number } => Self::Running { tip_block_number },
+            PrunerEvent::Finished { tip_block_number, elapsed, stats } => Self::Finished {
+                tip_block_number,
+                elapsed_ms: elapsed.as_millis().try_into().unwrap_or(u64::MAX),
+                segments: stats
+                    .into_iter()
+                    .map(|(segment, pruned, progress)| PrunedSegmentStats {
+                        segment,
+                        pruned,
+                        progress,
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
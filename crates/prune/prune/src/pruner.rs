@@ -7,12 +7,17 @@ use crate::{
 use alloy_primitives::BlockNumber;
 use reth_db_api::database::Database;
 use reth_exex_types::FinishedExExHeight;
-use reth_provider::{DatabaseProviderRW, ProviderFactory, PruneCheckpointReader};
-use reth_prune_types::{PruneLimiter, PruneProgress, PruneSegment};
+use reth_provider::{
+    DatabaseProviderRO, DatabaseProviderRW, ProviderFactory, PruneCheckpointReader,
+};
+use reth_prune_types::{PruneLimiter, PruneMode, PruneProgress, PrunePurpose, PruneSegment};
 use reth_tokio_util::{EventSender, EventStream};
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 use tokio::sync::watch;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Result of [`Pruner::run`] execution.
 pub type PrunerResult = Result<PruneProgress, PrunerError>;
@@ -22,6 +27,23 @@ pub type PrunerWithResult<S, DB> = (Pruner<S, DB>, PrunerResult);
 
 type PrunerStats = Vec<(PruneSegment, usize, PruneProgress)>;
 
+/// Estimated effect of pruning a segment, as reported by [`Pruner::dry_run`].
+#[derive(Debug, Clone)]
+pub struct PruneSegmentEstimate {
+    /// Segment that would be pruned.
+    pub segment: PruneSegment,
+    /// Purpose of pruning the segment.
+    pub purpose: PrunePurpose,
+    /// Prune mode the target block is derived from.
+    pub prune_mode: PruneMode,
+    /// Block number up to which the segment would be pruned, inclusive.
+    pub to_block: BlockNumber,
+    /// Estimated number of entries that would be deleted from the database.
+    pub pruned: usize,
+    /// Estimated number of bytes the tables of the segment would shrink by.
+    pub freed_bytes: u64,
+}
+
 /// Pruning routine. Main pruning logic happens in [`Pruner::run`].
 #[derive(Debug)]
 pub struct Pruner<DB, PF> {
@@ -321,13 +343,121 @@ impl<DB: Database> Pruner<DB, ProviderFactory<DB>> {
         provider.commit()?;
         result
     }
+
+    /// Estimates the data that [`Pruner::run`] would delete at the given tip, without enforcing
+    /// any limits and without modifying the database.
+    ///
+    /// The deleted entries are counted by each segment within a read-only transaction. The freed
+    /// bytes are estimated from `table_stats`, which returns the total size in bytes and the
+    /// number of entries of the tables of a segment, assuming that all entries are of equal size.
+    ///
+    /// Segments that don't support [estimating](crate::segments::Segment::estimate) are skipped.
+    pub fn dry_run<F, E>(
+        &self,
+        tip_block_number: BlockNumber,
+        mut table_stats: F,
+    ) -> Result<Vec<PruneSegmentEstimate>, E>
+    where
+        F: FnMut(&DatabaseProviderRO<DB>, PruneSegment) -> Result<(u64, usize), E>,
+        E: From<PrunerError>,
+    {
+        let provider = self.provider_factory.provider().map_err(PrunerError::from)?;
+        // Checkpoints the segments would save, as later segments may continue from them, e.g.
+        // static file and user receipts.
+        let mut checkpoints = HashMap::new();
+        let mut estimates = Vec::new();
+
+        for segment in &self.segments {
+            let Some((to_block, prune_mode)) = segment
+                .mode()
+                .map(|mode| {
+                    mode.prune_target_block(tip_block_number, segment.segment(), segment.purpose())
+                })
+                .transpose()
+                .map_err(PrunerError::from)?
+                .flatten()
+            else {
+                continue
+            };
+
+            let previous_checkpoint = match checkpoints.get(&segment.segment()) {
+                Some(checkpoint) => Some(*checkpoint),
+                None => {
+                    provider.get_prune_checkpoint(segment.segment()).map_err(PrunerError::from)?
+                }
+            };
+            let Some(output) = segment.estimate(
+                &provider,
+                PruneInput { previous_checkpoint, to_block, limiter: PruneLimiter::default() },
+            )?
+            else {
+                warn!(
+                    target: "pruner",
+                    segment = ?segment.segment(),
+                    purpose = ?segment.purpose(),
+                    "Segment doesn't support estimating, skipping"
+                );
+                continue
+            };
+            if let Some(checkpoint) = output.checkpoint {
+                checkpoints.insert(segment.segment(), checkpoint.as_prune_checkpoint(prune_mode));
+            }
+
+            let (table_bytes, table_entries) = table_stats(&provider, segment.segment())?;
+            let freed_bytes = if table_entries == 0 {
+                0
+            } else {
+                (table_bytes as u128 * output.pruned.min(table_entries) as u128 /
+                    table_entries as u128) as u64
+            };
+
+            debug!(
+                target: "pruner",
+                segment = ?segment.segment(),
+                purpose = ?segment.purpose(),
+                %to_block,
+                ?prune_mode,
+                %output.pruned,
+                %freed_bytes,
+                "Segment pruning estimated"
+            );
+
+            estimates.push(PruneSegmentEstimate {
+                segment: segment.segment(),
+                purpose: segment.purpose(),
+                prune_mode,
+                to_block,
+                pruned: output.pruned,
+                freed_bytes,
+            });
+        }
+
+        Ok(estimates)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Pruner;
+    use crate::{
+        segments::{ReceiptsByLogs, TransactionLookup},
+        Pruner, PrunerError,
+    };
+    use alloy_primitives::B256;
+    use reth_db::tables;
+    use reth_db_api::transaction::DbTx;
     use reth_exex_types::FinishedExExHeight;
-    use reth_provider::{test_utils::create_test_provider_factory, ProviderFactory};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, ProviderFactory, PruneCheckpointReader,
+    };
+    use reth_prune_types::{
+        PruneMode, PruneModes, PruneSegment, PruneSegmentStatus, ReceiptsLogRule, ReceiptsLogRules,
+        MINIMUM_PRUNING_DISTANCE,
+    };
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_testing_utils::{
+        generators,
+        generators::{random_block_range, random_eoa_account, random_log, random_receipt},
+    };
 
     #[test]
     fn is_pruning_needed() {
@@ -374,4 +504,140 @@ mod tests {
         finished_exex_height_tx.send(FinishedExExHeight::Height(third_block_number)).unwrap();
         assert!(pruner.is_pruning_needed(third_block_number));
     }
+
+    #[test]
+    fn prunes_receipts_by_logs() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let tip = MINIMUM_PRUNING_DISTANCE + 20;
+        let blocks = [
+            random_block_range(&mut rng, 1..=20, B256::ZERO, 1..3),
+            random_block_range(&mut rng, 21..=tip, B256::ZERO, 0..1),
+        ]
+        .concat();
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        // The last receipt of each block has a log of the contract
+        let (contract, _) = random_eoa_account(&mut rng);
+        let mut receipts = Vec::new();
+        for block in &blocks {
+            for (txi, transaction) in block.body.iter().enumerate() {
+                let mut receipt = random_receipt(&mut rng, transaction, Some(1));
+                if txi == block.body.len() - 1 {
+                    receipt.logs.push(random_log(&mut rng, Some(contract), Some(1)));
+                }
+                receipts.push((receipts.len() as u64, receipt));
+            }
+        }
+        db.insert_receipts(receipts.clone()).expect("insert receipts");
+
+        // All transactions are at or below `tip - MINIMUM_PRUNING_DISTANCE`, so only the receipts
+        // with logs of the contract are retained
+        let retained = receipts
+            .iter()
+            .filter(|(_, receipt)| receipt.logs.iter().any(|log| log.address == contract))
+            .count();
+
+        let rules =
+            ReceiptsLogRules(vec![ReceiptsLogRule::address(contract, PruneMode::Before(1))]);
+        let mut pruner = Pruner::<_, ProviderFactory<_>>::new(
+            db.factory.clone(),
+            vec![Box::new(ReceiptsByLogs::new(rules.clone()))],
+            5,
+            usize::MAX,
+            None,
+            tokio::sync::watch::channel(FinishedExExHeight::NoExExs).1,
+        );
+
+        // The dry run targets the same block as the prune status, and estimates the receipts that
+        // are pruned
+        let estimates = pruner
+            .dry_run(tip, |provider, segment| {
+                assert_eq!(segment, PruneSegment::ContractLogs);
+                let entries = provider.tx_ref().entries::<tables::Receipts>()?;
+                Ok::<_, PrunerError>((entries as u64, entries))
+            })
+            .unwrap();
+        let status = PruneSegmentStatus::new(
+            PruneSegment::ContractLogs,
+            &PruneModes { receipts_log_rules: rules, ..PruneModes::none() },
+            None,
+            tip,
+        )
+        .unwrap();
+        assert_eq!(estimates.len(), 1);
+        assert_eq!(estimates[0].segment, PruneSegment::ContractLogs);
+        assert_eq!(Some(estimates[0].to_block), status.target_block);
+        assert_eq!(estimates[0].pruned, receipts.len() - retained);
+
+        assert!(pruner.run(tip).unwrap().is_finished());
+
+        assert!(retained < receipts.len());
+        assert_eq!(db.table::<tables::Receipts>().unwrap().len(), retained);
+        assert!(db
+            .factory
+            .provider()
+            .unwrap()
+            .get_prune_checkpoint(PruneSegment::ContractLogs)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn dry_run() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=10, B256::ZERO, 2..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let mut tx_hash_numbers = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                tx_hash_numbers.push((transaction.hash, tx_hash_numbers.len() as u64));
+            }
+        }
+        db.insert_tx_hash_numbers(tx_hash_numbers.clone()).expect("insert tx hash numbers");
+
+        let to_block = 6;
+        let pruner = Pruner::<_, ProviderFactory<_>>::new(
+            db.factory.clone(),
+            vec![Box::new(TransactionLookup::new(PruneMode::Before(to_block)))],
+            5,
+            0,
+            None,
+            tokio::sync::watch::channel(FinishedExExHeight::NoExExs).1,
+        );
+
+        // Report two bytes per entry to make the estimate deterministic
+        let estimates = pruner
+            .dry_run(10, |provider, segment| {
+                assert_eq!(segment, PruneSegment::TransactionLookup);
+                let entries = provider.tx_ref().entries::<tables::TransactionHashNumbers>()?;
+                Ok::<_, PrunerError>((entries as u64 * 2, entries))
+            })
+            .unwrap();
+
+        let expected_pruned =
+            blocks.iter().take(to_block as usize - 1).map(|block| block.body.len()).sum::<usize>();
+        assert_eq!(estimates.len(), 1);
+        assert_eq!(estimates[0].segment, PruneSegment::TransactionLookup);
+        assert_eq!(estimates[0].to_block, to_block - 1);
+        assert_eq!(estimates[0].pruned, expected_pruned);
+        assert_eq!(estimates[0].freed_bytes, expected_pruned as u64 * 2);
+
+        // Nothing was deleted from the database
+        assert_eq!(
+            db.table::<tables::TransactionHashNumbers>().unwrap().len(),
+            tx_hash_numbers.len()
+        );
+        assert!(db
+            .factory
+            .provider()
+            .unwrap()
+            .get_prune_checkpoint(PruneSegment::TransactionLookup)
+            .unwrap()
+            .is_none());
+    }
 }
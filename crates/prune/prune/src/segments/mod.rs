@@ -7,7 +7,8 @@ use crate::PrunerError;
 use alloy_primitives::{BlockNumber, TxNumber};
 use reth_db_api::database::Database;
 use reth_provider::{
-    errors::provider::ProviderResult, BlockReader, DatabaseProviderRW, PruneCheckpointWriter,
+    errors::provider::ProviderResult, BlockReader, DatabaseProviderRO, DatabaseProviderRW,
+    PruneCheckpointWriter,
 };
 use reth_prune_types::{
     PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PrunePurpose,
@@ -49,6 +50,20 @@ pub trait Segment<DB: Database>: Debug + Send + Sync {
         input: PruneInput,
     ) -> Result<PruneOutput, PrunerError>;
 
+    /// Estimate the number of entries [`Self::prune`] would delete for the provided input, without
+    /// enforcing its limiter and without modifying the database.
+    ///
+    /// The returned checkpoint is the one [`Self::prune`] would return after deleting all of them.
+    ///
+    /// Returns [`None`] by default, for segments that don't support estimating.
+    fn estimate(
+        &self,
+        _provider: &DatabaseProviderRO<DB>,
+        _input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        Ok(None)
+    }
+
     /// Save checkpoint for [`Self::segment`] to the database.
     fn save_checkpoint(
         &self,
@@ -79,9 +94,9 @@ impl PruneInput {
     /// 2. If checkpoint doesn't exist, return 0.
     ///
     /// To get the range end: get last tx number for `to_block`.
-    pub(crate) fn get_next_tx_num_range<DB: Database>(
+    pub(crate) fn get_next_tx_num_range(
         &self,
-        provider: &DatabaseProviderRW<DB>,
+        provider: &impl BlockReader,
     ) -> ProviderResult<Option<RangeInclusive<TxNumber>>> {
         let from_tx_number = self.previous_checkpoint
             // Checkpoint exists, prune from the next transaction after the highest pruned one
@@ -117,6 +132,26 @@ impl PruneInput {
         Ok(Some(range))
     }
 
+    /// Estimate the output of pruning one entry per transaction of the next tx number range, see
+    /// [`Self::get_next_tx_num_range`].
+    pub(crate) fn estimate_next_tx_num_range(
+        &self,
+        provider: &impl BlockReader,
+    ) -> ProviderResult<PruneOutput> {
+        let Some(range) = self.get_next_tx_num_range(provider)? else {
+            return Ok(PruneOutput::done())
+        };
+
+        Ok(PruneOutput {
+            progress: PruneProgress::Finished,
+            pruned: range.clone().count(),
+            checkpoint: Some(PruneOutputCheckpoint {
+                block_number: Some(self.to_block),
+                tx_number: Some(*range.end()),
+            }),
+        })
+    }
+
     /// Get next inclusive block range to prune according to the checkpoint, `to_block` block
     /// number and `limit`.
     ///
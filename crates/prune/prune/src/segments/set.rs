@@ -45,13 +45,15 @@ impl<DB: Database> SegmentSet<DB> {
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
+        let receipts_log_rules = prune_modes.all_receipts_log_rules();
         let PruneModes {
             sender_recovery,
             transaction_lookup,
             receipts,
             account_history,
             storage_history,
-            receipts_log_filter,
+            receipts_log_filter: _,
+            receipts_log_rules: _,
         } = prune_modes;
 
         Self::default()
@@ -69,8 +71,7 @@ impl<DB: Database> SegmentSet<DB> {
             .segment_opt(receipts.map(UserReceipts::new))
             // Receipts by logs
             .segment_opt(
-                (!receipts_log_filter.is_empty())
-                    .then(|| ReceiptsByLogs::new(receipts_log_filter.clone())),
+                (!receipts_log_rules.is_empty()).then(|| ReceiptsByLogs::new(receipts_log_rules)),
             )
             // Transaction lookup
             .segment_opt(transaction_lookup.map(TransactionLookup::new))
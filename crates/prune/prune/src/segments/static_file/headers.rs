@@ -12,7 +12,7 @@ use reth_db::{
     tables,
     transaction::DbTxMut,
 };
-use reth_provider::{providers::StaticFileProvider, DatabaseProviderRW};
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO, DatabaseProviderRW};
 use reth_prune_types::{PruneLimiter, PruneMode, PruneProgress, PrunePurpose, PruneSegment};
 use reth_static_file_types::StaticFileSegment;
 use tracing::trace;
@@ -102,6 +102,25 @@ impl<DB: Database> Segment<DB> for Headers {
             }),
         })
     }
+
+    fn estimate(
+        &self,
+        _provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        let Some(range) = input.get_next_block_range() else {
+            return Ok(Some(PruneOutput::done()))
+        };
+
+        Ok(Some(PruneOutput {
+            progress: PruneProgress::Finished,
+            pruned: range.clone().count() * HEADER_TABLES_TO_PRUNE,
+            checkpoint: Some(PruneOutputCheckpoint {
+                block_number: Some(*range.end()),
+                tx_number: None,
+            }),
+        }))
+    }
 }
 type Walker<'a, DB, T> = RangeWalker<'a, T, <<DB as Database>::TXMut as DbTxMut>::CursorMut<T>>;
 
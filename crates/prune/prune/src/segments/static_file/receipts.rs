@@ -4,7 +4,8 @@ use crate::{
 };
 use reth_db_api::database::Database;
 use reth_provider::{
-    errors::provider::ProviderResult, providers::StaticFileProvider, DatabaseProviderRW,
+    errors::provider::ProviderResult, providers::StaticFileProvider, DatabaseProviderRO,
+    DatabaseProviderRW,
 };
 use reth_prune_types::{PruneCheckpoint, PruneMode, PrunePurpose, PruneSegment};
 use reth_static_file_types::StaticFileSegment;
@@ -43,6 +44,14 @@ impl<DB: Database> Segment<DB> for Receipts {
         crate::segments::receipts::prune(provider, input)
     }
 
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        Ok(Some(input.estimate_next_tx_num_range(provider)?))
+    }
+
     fn save_checkpoint(
         &self,
         provider: &DatabaseProviderRW<DB>,
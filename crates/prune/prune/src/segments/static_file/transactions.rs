@@ -4,7 +4,9 @@ use crate::{
 };
 use reth_db::tables;
 use reth_db_api::database::Database;
-use reth_provider::{providers::StaticFileProvider, DatabaseProviderRW, TransactionsProvider};
+use reth_provider::{
+    providers::StaticFileProvider, DatabaseProviderRO, DatabaseProviderRW, TransactionsProvider,
+};
 use reth_prune_types::{PruneMode, PruneProgress, PrunePurpose, PruneSegment};
 use reth_static_file_types::StaticFileSegment;
 use tracing::trace;
@@ -77,6 +79,14 @@ impl<DB: Database> Segment<DB> for Transactions {
             }),
         })
     }
+
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        Ok(Some(input.estimate_next_tx_num_range(provider)?))
+    }
 }
 
 #[cfg(test)]
@@ -1,14 +1,14 @@
 use crate::{
     segments::{
-        user::history::prune_history_indices, PruneInput, PruneOutput, PruneOutputCheckpoint,
-        Segment,
+        user::history::{estimate_changesets, prune_history_indices},
+        PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
     },
     PrunerError,
 };
 use itertools::Itertools;
 use reth_db::tables;
 use reth_db_api::{database::Database, models::ShardedKey};
-use reth_provider::DatabaseProviderRW;
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW};
 use reth_prune_types::{
     PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment,
 };
@@ -126,6 +126,19 @@ impl<DB: Database> Segment<DB> for AccountHistory {
             }),
         })
     }
+
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        let Some(range) = input.get_next_block_range() else {
+            return Ok(Some(PruneOutput::done()))
+        };
+        let range_end = *range.end();
+
+        Ok(Some(estimate_changesets::<DB, tables::AccountChangeSets>(provider, range, range_end)?))
+    }
 }
 
 #[cfg(test)]
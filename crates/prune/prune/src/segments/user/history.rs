@@ -1,3 +1,4 @@
+use crate::segments::{PruneOutput, PruneOutputCheckpoint};
 use alloy_primitives::BlockNumber;
 use reth_db::{BlockNumberList, RawKey, RawTable, RawValue};
 use reth_db_api::{
@@ -5,10 +6,12 @@ use reth_db_api::{
     database::Database,
     models::ShardedKey,
     table::Table,
-    transaction::DbTxMut,
+    transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
-use reth_provider::DatabaseProviderRW;
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW};
+use reth_prune_types::PruneProgress;
+use std::ops::RangeBounds;
 
 enum PruneShardOutcome {
     Deleted,
@@ -23,6 +26,33 @@ pub(crate) struct PrunedIndices {
     pub(crate) unchanged: usize,
 }
 
+/// Estimate the output of pruning the changesets in the provided key range, which ends with the
+/// changesets of block `range_end`.
+///
+/// Only the changesets are counted, because the history shards deleted along with them depend on
+/// the changesets of all later blocks.
+pub(crate) fn estimate_changesets<DB, T>(
+    provider: &DatabaseProviderRO<DB>,
+    range: impl RangeBounds<T::Key>,
+    range_end: BlockNumber,
+) -> Result<PruneOutput, DatabaseError>
+where
+    DB: Database,
+    T: Table,
+{
+    let pruned = provider
+        .tx_ref()
+        .cursor_read::<T>()?
+        .walk_range(range)?
+        .try_fold(0, |pruned, entry| entry.map(|_| pruned + 1))?;
+
+    Ok(PruneOutput {
+        progress: PruneProgress::Finished,
+        pruned,
+        checkpoint: Some(PruneOutputCheckpoint { block_number: Some(range_end), tx_number: None }),
+    })
+}
+
 /// Prune history indices according to the provided list of highest sharded keys.
 ///
 /// Returns total number of deleted, updated and unchanged entities.
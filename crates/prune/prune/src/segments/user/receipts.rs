@@ -3,7 +3,7 @@ use crate::{
     PrunerError,
 };
 use reth_db_api::database::Database;
-use reth_provider::{errors::provider::ProviderResult, DatabaseProviderRW};
+use reth_provider::{errors::provider::ProviderResult, DatabaseProviderRO, DatabaseProviderRW};
 use reth_prune_types::{PruneCheckpoint, PruneMode, PrunePurpose, PruneSegment};
 use tracing::instrument;
 
@@ -40,6 +40,14 @@ impl<DB: Database> Segment<DB> for Receipts {
         crate::segments::receipts::prune(provider, input)
     }
 
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        Ok(Some(input.estimate_next_tx_num_range(provider)?))
+    }
+
     fn save_checkpoint(
         &self,
         provider: &DatabaseProviderRW<DB>,
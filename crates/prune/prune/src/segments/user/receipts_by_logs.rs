@@ -2,99 +2,97 @@ use crate::{
     segments::{PruneInput, PruneOutput, Segment},
     PrunerError,
 };
+use alloy_primitives::{BlockNumber, TxNumber};
 use reth_db::tables;
-use reth_db_api::database::Database;
-use reth_provider::{BlockReader, DatabaseProviderRW, PruneCheckpointWriter, TransactionsProvider};
+use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
+use reth_provider::{
+    errors::provider::ProviderResult, BlockReader, DatabaseProviderRO, DatabaseProviderRW,
+    PruneCheckpointWriter, TransactionsProvider,
+};
 use reth_prune_types::{
-    PruneCheckpoint, PruneMode, PruneProgress, PrunePurpose, PruneSegment, ReceiptsLogPruneConfig,
-    MINIMUM_PRUNING_DISTANCE,
+    PruneCheckpoint, PruneMode, PruneProgress, PrunePurpose, PruneSegment, ReceiptsLogRule,
+    ReceiptsLogRules, MINIMUM_PRUNING_DISTANCE,
 };
 use tracing::{instrument, trace};
 
 #[derive(Debug)]
 pub struct ReceiptsByLogs {
-    config: ReceiptsLogPruneConfig,
+    config: ReceiptsLogRules,
 }
 
 impl ReceiptsByLogs {
-    pub const fn new(config: ReceiptsLogPruneConfig) -> Self {
+    pub const fn new(config: ReceiptsLogRules) -> Self {
         Self { config }
     }
-}
-
-impl<DB: Database> Segment<DB> for ReceiptsByLogs {
-    fn segment(&self) -> PruneSegment {
-        PruneSegment::ContractLogs
-    }
-
-    fn mode(&self) -> Option<PruneMode> {
-        None
-    }
 
-    fn purpose(&self) -> PrunePurpose {
-        PrunePurpose::User
+    /// Prune mode of the receipts without logs matching the rules.
+    ///
+    /// Contract log filtering removes every receipt possible except the ones matching the rules.
+    /// So, for the other receipts it's as if they had a `PruneMode::Distance()` of
+    /// `MINIMUM_PRUNING_DISTANCE`.
+    const MODE: PruneMode = PruneMode::Distance(MINIMUM_PRUNING_DISTANCE);
+
+    /// Returns the tip that the target block of the input was derived from with [`Self::MODE`].
+    /// The rules with a `PruneMode::Distance()` are relative to it.
+    const fn tip(input: &PruneInput) -> BlockNumber {
+        input.to_block + MINIMUM_PRUNING_DISTANCE
     }
 
-    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
-    fn prune(
-        &self,
-        provider: &DatabaseProviderRW<DB>,
-        input: PruneInput,
-    ) -> Result<PruneOutput, PrunerError> {
-        // Contract log filtering removes every receipt possible except the ones in the list. So,
-        // for the other receipts it's as if they had a `PruneMode::Distance()` of
-        // `MINIMUM_PRUNING_DISTANCE`.
-        let to_block = PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)
-            .prune_target_block(input.to_block, PruneSegment::ContractLogs, PrunePurpose::User)?
-            .map(|(bn, _)| bn)
-            .unwrap_or_default();
-
-        // Get status checkpoint from latest run
-        let mut last_pruned_block =
-            input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
-
-        let initial_last_pruned_block = last_pruned_block;
-
-        let mut from_tx_number = match initial_last_pruned_block {
+    /// Returns the first transaction number after the last pruned block.
+    fn next_tx_number(
+        provider: &impl BlockReader,
+        last_pruned_block: Option<BlockNumber>,
+    ) -> ProviderResult<TxNumber> {
+        Ok(match last_pruned_block {
             Some(block) => provider
                 .block_body_indices(block)?
                 .map(|block| block.last_tx_num() + 1)
                 .unwrap_or(0),
             None => 0,
-        };
+        })
+    }
 
+    /// Splits the blocks after the last pruned block up to `to_block` into ranges, each with the
+    /// number of leading rules of the returned list that apply to it.
+    #[allow(clippy::type_complexity)]
+    fn block_ranges(
+        &self,
+        tip: BlockNumber,
+        to_block: BlockNumber,
+        last_pruned_block: Option<BlockNumber>,
+    ) -> Result<(Vec<(BlockNumber, BlockNumber, usize)>, Vec<&ReceiptsLogRule>), PrunerError> {
         // Figure out what receipts have already been pruned, so we can have an accurate
-        // `address_filter`
-        let address_filter = self.config.group_by_block(input.to_block, last_pruned_block)?;
+        // `rules_filter`
+        let rules_filter = self.config.group_by_block(tip, last_pruned_block)?;
 
         // Splits all transactions in different block ranges. Each block range will have its own
-        // filter address list and will check it while going through the table
+        // list of log rules and will check it while going through the table
         //
         // Example:
-        // For an `address_filter` such as:
-        // { block9: [a1, a2], block20: [a3, a4, a5] }
+        // For a `rules_filter` such as:
+        // { block9: [r1, r2], block20: [r3, r4, r5] }
         //
         // The following structures will be created in the exact order as showed:
         // `block_ranges`: [
-        //    (block0, block8, 0 addresses),
-        //    (block9, block19, 2 addresses),
-        //    (block20, to_block, 5 addresses)
+        //    (block0, block8, 0 rules),
+        //    (block9, block19, 2 rules),
+        //    (block20, to_block, 5 rules)
         //  ]
-        // `filtered_addresses`: [a1, a2, a3, a4, a5]
+        // `filtered_rules`: [r1, r2, r3, r4, r5]
         //
         // The first range will delete all receipts between block0 - block8
         // The second range will delete all receipts between block9 - 19, except the ones with
-        //     emitter logs from these addresses: [a1, a2].
+        //     logs matching these rules: [r1, r2].
         // The third range will delete all receipts between block20 - to_block, except the ones with
-        //     emitter logs from these addresses: [a1, a2, a3, a4, a5]
+        //     logs matching these rules: [r1, r2, r3, r4, r5]
         let mut block_ranges = vec![];
-        let mut blocks_iter = address_filter.iter().peekable();
-        let mut filtered_addresses = vec![];
+        let mut blocks_iter = rules_filter.iter().peekable();
+        let mut filtered_rules = vec![];
 
-        while let Some((start_block, addresses)) = blocks_iter.next() {
-            filtered_addresses.extend_from_slice(addresses);
+        while let Some((start_block, rules)) = blocks_iter.next() {
+            filtered_rules.extend_from_slice(rules);
 
-            // This will clear all receipts before the first  appearance of a contract log or since
+            // This will clear all receipts before the first appearance of a matching log or since
             // the block after the last pruned one.
             if block_ranges.is_empty() {
                 let init = last_pruned_block.map(|b| b + 1).unwrap_or_default();
@@ -106,24 +104,60 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
             let end_block =
                 blocks_iter.peek().map(|(next_block, _)| *next_block - 1).unwrap_or(to_block);
 
-            // Addresses in lower block ranges, are still included in the inclusion list for future
+            // Rules in lower block ranges, are still included in the inclusion list for future
             // ranges.
-            block_ranges.push((*start_block, end_block, filtered_addresses.len()));
+            block_ranges.push((*start_block, end_block, filtered_rules.len()));
         }
 
         trace!(
             target: "pruner",
             ?block_ranges,
-            ?filtered_addresses,
-            "Calculated block ranges and filtered addresses",
+            ?filtered_rules,
+            "Calculated block ranges and filtered rules",
         );
 
+        Ok((block_ranges, filtered_rules))
+    }
+}
+
+impl<DB: Database> Segment<DB> for ReceiptsByLogs {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::ContractLogs
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(Self::MODE)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        let tip = Self::tip(&input);
+        let to_block = input.to_block;
+
+        // Get status checkpoint from latest run
+        let mut last_pruned_block =
+            input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
+
+        let initial_last_pruned_block = last_pruned_block;
+
+        let mut from_tx_number = Self::next_tx_number(provider, initial_last_pruned_block)?;
+
+        let (block_ranges, filtered_rules) = self.block_ranges(tip, to_block, last_pruned_block)?;
+
         let mut limiter = input.limiter;
 
         let mut done = true;
         let mut pruned = 0;
         let mut last_pruned_transaction = None;
-        for (start_block, end_block, num_addresses) in block_ranges {
+        for (start_block, end_block, num_rules) in block_ranges {
             let block_range = start_block..=end_block;
 
             // Calculate the transaction range from this block range
@@ -147,9 +181,9 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
                 tx_range,
                 &mut limiter,
                 |(tx_num, receipt)| {
-                    let skip = num_addresses > 0 &&
+                    let skip = num_rules > 0 &&
                         receipt.logs.iter().any(|log| {
-                            filtered_addresses[..num_addresses].contains(&&log.address)
+                            filtered_rules[..num_rules].iter().any(|rule| rule.matches(log))
                         });
 
                     if skip {
@@ -188,9 +222,9 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
             from_tx_number = last_pruned_transaction + 1;
         }
 
-        // If there are contracts using `PruneMode::Distance(_)` there will be receipts before
+        // If there are rules using `PruneMode::Distance(_)` there will be receipts before
         // `to_block` that become eligible to be pruned in future runs. Therefore, our checkpoint is
-        // not actually `to_block`, but the `lowest_block_with_distance` from any contract.
+        // not actually `to_block`, but the `lowest_block_with_distance` from any rule.
         // This ensures that in future pruner runs we can prune all these receipts between the
         // previous `lowest_block_with_distance` and the new one using
         // `get_next_tx_num_range_from_checkpoint`.
@@ -199,7 +233,7 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
         // checkpoint is the `last_pruned_block`.
         let prune_mode_block = self
             .config
-            .lowest_block_with_distance(input.to_block, initial_last_pruned_block)?
+            .lowest_block_with_distance(tip, initial_last_pruned_block)?
             .unwrap_or(to_block);
 
         provider.save_prune_checkpoint(
@@ -215,23 +249,59 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
 
         Ok(PruneOutput { progress, pruned, checkpoint: None })
     }
+
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        let last_pruned_block =
+            input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
+        let mut from_tx_number = Self::next_tx_number(provider, last_pruned_block)?;
+        let (block_ranges, filtered_rules) =
+            self.block_ranges(Self::tip(&input), input.to_block, last_pruned_block)?;
+
+        // Count the receipts that would be deleted, i.e. the ones without logs matching the rules
+        // of their block range
+        let mut receipts_cursor = provider.tx_ref().cursor_read::<tables::Receipts>()?;
+        let mut pruned = 0;
+        for (_, end_block, num_rules) in block_ranges {
+            let Some(body) = provider.block_body_indices(end_block)? else { continue };
+            for entry in receipts_cursor.walk_range(from_tx_number..=body.last_tx_num())? {
+                let (_, receipt) = entry?;
+                if !receipt
+                    .logs
+                    .iter()
+                    .any(|log| filtered_rules[..num_rules].iter().any(|rule| rule.matches(log)))
+                {
+                    pruned += 1;
+                }
+            }
+            from_tx_number = body.last_tx_num() + 1;
+        }
+
+        // The checkpoint is saved by `prune` itself
+        Ok(Some(PruneOutput { progress: PruneProgress::Finished, pruned, checkpoint: None }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::segments::{PruneInput, ReceiptsByLogs, Segment};
-    use alloy_primitives::B256;
+    use alloy_primitives::{Log, B256};
     use assert_matches::assert_matches;
     use reth_db::tables;
     use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
     use reth_provider::{PruneCheckpointReader, TransactionsProvider};
-    use reth_prune_types::{PruneLimiter, PruneMode, PruneSegment, ReceiptsLogPruneConfig};
+    use reth_prune_types::{
+        PruneLimiter, PruneMode, PruneSegment, ReceiptsLogRule, ReceiptsLogRules,
+        MINIMUM_PRUNING_DISTANCE,
+    };
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_testing_utils::{
         generators,
         generators::{random_block_range, random_eoa_account, random_log, random_receipt},
     };
-    use std::collections::BTreeMap;
 
     #[test]
     fn prune_receipts_by_logs() {
@@ -280,7 +350,7 @@ mod tests {
             let prune_before_block: usize = 20;
             let prune_mode = PruneMode::Before(prune_before_block as u64);
             let receipts_log_filter =
-                ReceiptsLogPruneConfig(BTreeMap::from([(deposit_contract_addr, prune_mode)]));
+                ReceiptsLogRules(vec![ReceiptsLogRule::address(deposit_contract_addr, prune_mode)]);
 
             let limiter = PruneLimiter::default().set_deleted_entries_limit(10);
 
@@ -293,7 +363,7 @@ mod tests {
                         .unwrap()
                         .get_prune_checkpoint(PruneSegment::ContractLogs)
                         .unwrap(),
-                    to_block: tip,
+                    to_block: tip - MINIMUM_PRUNING_DISTANCE,
                     limiter,
                 },
             );
@@ -339,4 +409,85 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn prune_receipts_by_log_topics() {
+        reth_tracing::init_test_tracing();
+
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let tip = 20000;
+        let blocks = [
+            random_block_range(&mut rng, 0..=100, B256::ZERO, 1..5),
+            random_block_range(&mut rng, (100 + 1)..=(tip - 100), B256::ZERO, 0..1),
+            random_block_range(&mut rng, (tip - 100 + 1)..=tip, B256::ZERO, 1..5),
+        ]
+        .concat();
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        // Transfers to the recipient from any contract are retained
+        let transfer = B256::random();
+        let recipient = B256::random();
+        let rule = ReceiptsLogRule {
+            address: None,
+            topic0: Some(transfer),
+            topic1: None,
+            topic2: Some(recipient),
+            topic3: None,
+            mode: PruneMode::Before(20),
+        };
+
+        let mut receipts = Vec::new();
+        for block in &blocks {
+            for (txi, transaction) in block.body.iter().enumerate() {
+                let mut receipt = random_receipt(&mut rng, transaction, Some(1));
+                if txi == block.body.len() - 1 {
+                    let (contract, _) = random_eoa_account(&mut rng);
+                    receipt.logs.push(Log::new_unchecked(
+                        contract,
+                        vec![transfer, B256::random(), recipient],
+                        Default::default(),
+                    ));
+                }
+                receipts.push((receipts.len() as u64, receipt));
+            }
+        }
+        db.insert_receipts(receipts).expect("insert receipts");
+
+        loop {
+            let provider = db.factory.provider_rw().unwrap();
+            let result = ReceiptsByLogs::new(ReceiptsLogRules(vec![rule.clone()])).prune(
+                &provider,
+                PruneInput {
+                    previous_checkpoint: db
+                        .factory
+                        .provider()
+                        .unwrap()
+                        .get_prune_checkpoint(PruneSegment::ContractLogs)
+                        .unwrap(),
+                    to_block: tip - MINIMUM_PRUNING_DISTANCE,
+                    limiter: PruneLimiter::default().set_deleted_entries_limit(10),
+                },
+            );
+            provider.commit().expect("commit");
+
+            if result.expect("prune receipts").progress.is_finished() {
+                break
+            }
+        }
+
+        let provider = db.factory.provider().unwrap();
+        let mut cursor = provider.tx_ref().cursor_read::<tables::Receipts>().unwrap();
+        let walker = cursor.walk(None).unwrap();
+        for receipt in walker {
+            let (tx_num, receipt) = receipt.unwrap();
+
+            // Either the receipt contains a matching log, or it's part of the unprunable receipts
+            assert!(
+                receipt.logs.iter().any(|log| rule.matches(log)) ||
+                    provider.transaction_block(tx_num).unwrap().unwrap() > tip - 128,
+            );
+        }
+    }
 }
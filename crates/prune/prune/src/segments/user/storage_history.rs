@@ -1,7 +1,7 @@
 use crate::{
     segments::{
-        user::history::prune_history_indices, PruneInput, PruneOutput, PruneOutputCheckpoint,
-        Segment,
+        user::history::{estimate_changesets, prune_history_indices},
+        PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
     },
     PrunerError,
 };
@@ -11,7 +11,7 @@ use reth_db_api::{
     database::Database,
     models::{storage_sharded_key::StorageShardedKey, BlockNumberAddress},
 };
-use reth_provider::DatabaseProviderRW;
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW};
 use reth_prune_types::{
     PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment,
 };
@@ -134,6 +134,23 @@ impl<DB: Database> Segment<DB> for StorageHistory {
             }),
         })
     }
+
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        let Some(range) = input.get_next_block_range() else {
+            return Ok(Some(PruneOutput::done()))
+        };
+        let range_end = *range.end();
+
+        Ok(Some(estimate_changesets::<DB, tables::StorageChangeSets>(
+            provider,
+            BlockNumberAddress::range(range),
+            range_end,
+        )?))
+    }
 }
 
 #[cfg(test)]
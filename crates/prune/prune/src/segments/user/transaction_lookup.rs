@@ -5,7 +5,7 @@ use crate::{
 use rayon::prelude::*;
 use reth_db::tables;
 use reth_db_api::database::Database;
-use reth_provider::{DatabaseProviderRW, TransactionsProvider};
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW, TransactionsProvider};
 use reth_prune_types::{PruneMode, PruneProgress, PrunePurpose, PruneSegment};
 use tracing::{instrument, trace};
 
@@ -103,6 +103,14 @@ impl<DB: Database> Segment<DB> for TransactionLookup {
             }),
         })
     }
+
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<Option<PruneOutput>, PrunerError> {
+        Ok(Some(input.estimate_next_tx_num_range(provider)?))
+    }
 }
 
 #[cfg(test)]
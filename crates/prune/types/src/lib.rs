@@ -11,16 +11,20 @@
 mod checkpoint;
 mod limiter;
 mod mode;
+mod receipts_log;
 mod segment;
+mod status;
 mod target;
 
 pub use checkpoint::PruneCheckpoint;
 pub use limiter::PruneLimiter;
 pub use mode::PruneMode;
+pub use receipts_log::{ReceiptsLogRule, ReceiptsLogRules};
 pub use segment::{PrunePurpose, PruneSegment, PruneSegmentError};
 use serde::{Deserialize, Serialize};
+pub use status::{PruneSegmentStatus, PruneStatus, PrunedSegmentStats, PrunerProgress};
 use std::collections::BTreeMap;
-pub use target::{PruneModes, MINIMUM_PRUNING_DISTANCE};
+pub use target::{PruneModes, PruneModesError, MINIMUM_PRUNING_DISTANCE};
 
 use alloy_primitives::{Address, BlockNumber};
 
@@ -101,7 +105,7 @@ impl ReceiptsLogPruneConfig {
 }
 
 /// Progress of pruning.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum PruneProgress {
     /// There is more data to prune.
     HasMoreData(PruneInterruptReason),
@@ -110,7 +114,7 @@ pub enum PruneProgress {
 }
 
 /// Reason for interrupting a prune run.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum PruneInterruptReason {
     /// Prune run timed out.
     Timeout,
@@ -0,0 +1,192 @@
+use crate::{PruneMode, PrunePurpose, PruneSegment, PruneSegmentError};
+use alloy_primitives::{Address, BlockNumber, Log, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Rule for retaining receipts that contain at least one log emitted by the `address` with the
+/// given topics. Fields that are not set match any value.
+///
+/// For example, ERC-20 `Transfer` events to an address are matched by the `Transfer` event
+/// signature in `topic0` and the left-padded recipient address in `topic2`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptsLogRule {
+    /// Address of the contract that emitted the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// First topic of the log, the event signature for non-anonymous events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic0: Option<B256>,
+    /// Second topic of the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic1: Option<B256>,
+    /// Third topic of the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic2: Option<B256>,
+    /// Fourth topic of the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic3: Option<B256>,
+    /// Pruning configuration of the receipts matched by the rule.
+    pub mode: PruneMode,
+}
+
+impl ReceiptsLogRule {
+    /// Creates a rule matching all logs emitted by the `address`.
+    pub const fn address(address: Address, mode: PruneMode) -> Self {
+        Self {
+            address: Some(address),
+            topic0: None,
+            topic1: None,
+            topic2: None,
+            topic3: None,
+            mode,
+        }
+    }
+
+    /// Returns the topics of the rule in the order they appear in a log.
+    pub const fn topics(&self) -> [Option<B256>; 4] {
+        [self.topic0, self.topic1, self.topic2, self.topic3]
+    }
+
+    /// Returns `true` if neither the address nor any topic is set, so the rule matches every log.
+    pub fn is_wildcard(&self) -> bool {
+        self.address.is_none() && self.topics().iter().all(Option::is_none)
+    }
+
+    /// Returns `true` if the log is matched by the rule.
+    pub fn matches(&self, log: &Log) -> bool {
+        self.address.map_or(true, |address| address == log.address) &&
+            self.topics().iter().enumerate().all(|(index, topic)| {
+                topic.map_or(true, |topic| log.topics().get(index) == Some(&topic))
+            })
+    }
+}
+
+/// Configuration for pruning receipts not associated with logs matching any of the rules.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ReceiptsLogRules(pub Vec<ReceiptsLogRule>);
+
+impl ReceiptsLogRules {
+    /// Checks if the configuration is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Given the `tip` block number, consolidates the rules so they can easily be queried for
+    /// filtering across a range of blocks.
+    ///
+    /// The [`BlockNumber`] key of the returned map should be viewed as `PruneMode::Before(block)`,
+    /// i.e. the rules are retaining receipts starting from the block. See
+    /// [`ReceiptsLogPruneConfig::group_by_block`](crate::ReceiptsLogPruneConfig::group_by_block).
+    pub fn group_by_block(
+        &self,
+        tip: BlockNumber,
+        pruned_block: Option<BlockNumber>,
+    ) -> Result<BTreeMap<BlockNumber, Vec<&ReceiptsLogRule>>, PruneSegmentError> {
+        let mut map = BTreeMap::new();
+        let pruned_block = pruned_block.unwrap_or_default();
+
+        for rule in &self.0 {
+            // Getting `None` means that there is nothing to prune yet, so the rule retains receipts
+            // from block 0.
+            let block = (pruned_block + 1).max(
+                rule.mode
+                    .prune_target_block(tip, PruneSegment::ContractLogs, PrunePurpose::User)?
+                    .map(|(block, _)| block)
+                    .unwrap_or_default() +
+                    1,
+            );
+
+            map.entry(block).or_insert_with(Vec::new).push(rule)
+        }
+        Ok(map)
+    }
+
+    /// Returns the lowest block where we start filtering logs which use `PruneMode::Distance(_)`.
+    pub fn lowest_block_with_distance(
+        &self,
+        tip: BlockNumber,
+        pruned_block: Option<BlockNumber>,
+    ) -> Result<Option<BlockNumber>, PruneSegmentError> {
+        let pruned_block = pruned_block.unwrap_or_default();
+        let mut lowest = None;
+
+        for rule in &self.0 {
+            if let PruneMode::Distance(_) = rule.mode {
+                if let Some((block, _)) = rule.mode.prune_target_block(
+                    tip,
+                    PruneSegment::ContractLogs,
+                    PrunePurpose::User,
+                )? {
+                    lowest = Some(lowest.unwrap_or(u64::MAX).min(block));
+                }
+            }
+        }
+
+        Ok(lowest.map(|lowest| lowest.max(pruned_block)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MINIMUM_PRUNING_DISTANCE;
+    use alloy_primitives::{address, b256, Bytes};
+
+    #[test]
+    fn receipts_log_rule_matches() {
+        let token = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        let transfer = b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+        let recipient = Address::with_last_byte(1).into_word();
+
+        let log = |address, topics: Vec<B256>| {
+            Log::new(address, topics, Bytes::default()).expect("valid log")
+        };
+
+        let rule = ReceiptsLogRule {
+            address: None,
+            topic0: Some(transfer),
+            topic1: None,
+            topic2: Some(recipient),
+            topic3: None,
+            mode: PruneMode::Before(1),
+        };
+        assert!(!rule.is_wildcard());
+        assert!(rule.matches(&log(token, vec![transfer, B256::ZERO, recipient])));
+        assert!(rule.matches(&log(Address::ZERO, vec![transfer, B256::ZERO, recipient])));
+        assert!(!rule.matches(&log(token, vec![transfer, recipient, B256::ZERO])));
+        assert!(!rule.matches(&log(token, vec![transfer])));
+
+        let rule = ReceiptsLogRule::address(token, PruneMode::Before(1));
+        assert!(rule.matches(&log(token, vec![])));
+        assert!(!rule.matches(&log(Address::ZERO, vec![transfer])));
+
+        assert!(ReceiptsLogRule { address: None, ..rule }.is_wildcard());
+    }
+
+    #[test]
+    fn receipts_log_rules_group_by_block() {
+        let rules = ReceiptsLogRules(vec![
+            ReceiptsLogRule::address(Address::with_last_byte(1), PruneMode::Before(872)),
+            ReceiptsLogRule::address(Address::with_last_byte(2), PruneMode::Before(500)),
+            ReceiptsLogRule::address(
+                Address::with_last_byte(3),
+                PruneMode::Distance(MINIMUM_PRUNING_DISTANCE),
+            ),
+        ]);
+
+        let tip = 20_000;
+        let grouped = rules.group_by_block(tip, None).unwrap();
+        assert_eq!(
+            grouped,
+            BTreeMap::from([
+                (500, vec![&rules.0[1]]),
+                (872, vec![&rules.0[0]]),
+                (tip - MINIMUM_PRUNING_DISTANCE + 1, vec![&rules.0[2]])
+            ])
+        );
+        assert_eq!(
+            rules.lowest_block_with_distance(tip, None).unwrap(),
+            Some(tip - MINIMUM_PRUNING_DISTANCE)
+        );
+    }
+}
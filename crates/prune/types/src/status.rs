@@ -0,0 +1,168 @@
+use crate::{
+    PruneCheckpoint, PruneMode, PruneModes, PruneProgress, PrunePurpose, PruneSegment,
+    PruneSegmentError,
+};
+use alloy_primitives::BlockNumber;
+use serde::{Deserialize, Serialize};
+
+/// Pruning status of the node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneStatus {
+    /// Block number the segment targets are calculated for.
+    pub tip_block_number: BlockNumber,
+    /// Status of the segments that are configured or have been pruned before.
+    pub segments: Vec<PruneSegmentStatus>,
+    /// Progress of the latest pruner run, if the pruner has run since the node started.
+    pub pruner: Option<PrunerProgress>,
+}
+
+/// Pruning status of a single [`PruneSegment`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneSegmentStatus {
+    /// The segment.
+    pub segment: PruneSegment,
+    /// Prune mode configured for the segment.
+    pub mode: Option<PruneMode>,
+    /// Block number up to which the segment should be pruned at the tip, inclusive.
+    pub target_block: Option<BlockNumber>,
+    /// Last saved checkpoint of the segment.
+    pub checkpoint: Option<PruneCheckpoint>,
+}
+
+impl PruneSegmentStatus {
+    /// Creates the status of the segment configured by the prune modes, with the target block
+    /// calculated for the `tip`.
+    pub fn new(
+        segment: PruneSegment,
+        prune_modes: &PruneModes,
+        checkpoint: Option<PruneCheckpoint>,
+        tip: BlockNumber,
+    ) -> Result<Self, PruneSegmentError> {
+        let mode = prune_modes.segment_mode(segment);
+        let target_block = mode
+            .map(|mode| mode.prune_target_block(tip, segment, PrunePurpose::User))
+            .transpose()?
+            .flatten()
+            .map(|(block, _)| block);
+
+        Ok(Self { segment, mode, target_block, checkpoint })
+    }
+
+    /// Returns `true` if the segment has a prune mode configured or has been pruned before.
+    pub const fn is_active(&self) -> bool {
+        self.mode.is_some() || self.checkpoint.is_some()
+    }
+}
+
+/// Progress of a pruner run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum PrunerProgress {
+    /// The pruner is running.
+    #[serde(rename_all = "camelCase")]
+    Running {
+        /// Tip block number the pruner runs for.
+        tip_block_number: BlockNumber,
+    },
+    /// The pruner finished running.
+    #[serde(rename_all = "camelCase")]
+    Finished {
+        /// Tip block number the pruner ran for.
+        tip_block_number: BlockNumber,
+        /// Duration of the run in milliseconds.
+        elapsed_ms: u64,
+        /// Segments that had entries pruned.
+        segments: Vec<PrunedSegmentStats>,
+    },
+}
+
+/// Number of entries pruned from a segment in a pruner run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrunedSegmentStats {
+    /// The segment.
+    pub segment: PruneSegment,
+    /// Number of entries deleted from the database.
+    pub pruned: usize,
+    /// Whether the segment has more data to prune.
+    pub progress: PruneProgress,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReceiptsLogRule, ReceiptsLogRules, MINIMUM_PRUNING_DISTANCE};
+    use alloy_primitives::Address;
+
+    #[test]
+    fn prune_segment_status() {
+        let prune_modes = PruneModes {
+            sender_recovery: Some(PruneMode::Full),
+            account_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+            receipts_log_rules: ReceiptsLogRules(vec![ReceiptsLogRule::address(
+                Address::ZERO,
+                PruneMode::Before(100),
+            )]),
+            ..PruneModes::none()
+        };
+        let tip = 20_000;
+
+        let status =
+            PruneSegmentStatus::new(PruneSegment::SenderRecovery, &prune_modes, None, tip).unwrap();
+        assert_eq!(status.target_block, Some(tip));
+        assert!(status.is_active());
+
+        let status =
+            PruneSegmentStatus::new(PruneSegment::AccountHistory, &prune_modes, None, tip).unwrap();
+        assert_eq!(status.target_block, Some(tip - MINIMUM_PRUNING_DISTANCE));
+
+        // receipts without logs matching the rules are pruned at the minimum distance
+        let status =
+            PruneSegmentStatus::new(PruneSegment::ContractLogs, &prune_modes, None, tip).unwrap();
+        assert_eq!(status.mode, Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)));
+        assert_eq!(status.target_block, Some(tip - MINIMUM_PRUNING_DISTANCE));
+
+        let checkpoint = PruneCheckpoint {
+            block_number: Some(100),
+            tx_number: None,
+            prune_mode: PruneMode::Before(101),
+        };
+        let status =
+            PruneSegmentStatus::new(PruneSegment::Receipts, &prune_modes, Some(checkpoint), tip)
+                .unwrap();
+        assert_eq!(status.mode, None);
+        assert_eq!(status.target_block, None);
+        assert!(status.is_active());
+
+        let status =
+            PruneSegmentStatus::new(PruneSegment::Headers, &prune_modes, None, tip).unwrap();
+        assert!(!status.is_active());
+    }
+
+    #[test]
+    fn serialize_pruner_progress() {
+        let progress = PrunerProgress::Finished {
+            tip_block_number: 100,
+            elapsed_ms: 5,
+            segments: vec![PrunedSegmentStats {
+                segment: PruneSegment::SenderRecovery,
+                pruned: 10,
+                progress: PruneProgress::Finished,
+            }],
+        };
+
+        let json = serde_json::to_value(&progress).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "status": "finished",
+                "tipBlockNumber": 100,
+                "elapsedMs": 5,
+                "segments": [{ "segment": "SenderRecovery", "pruned": 10, "progress": "Finished" }]
+            })
+        );
+        assert_eq!(serde_json::from_value::<PrunerProgress>(json).unwrap(), progress);
+    }
+}
@@ -1,5 +1,6 @@
-use crate::{PruneMode, ReceiptsLogPruneConfig};
+use crate::{PruneMode, PruneSegment, ReceiptsLogPruneConfig, ReceiptsLogRule, ReceiptsLogRules};
 use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
 
 /// Minimum distance from the tip necessary for the node to work correctly:
 /// 1. Minimum 2 epochs (32 blocks per epoch) required to handle any reorg according to the
@@ -18,8 +19,8 @@ pub struct PruneModes {
     /// Transaction Lookup pruning configuration.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transaction_lookup: Option<PruneMode>,
-    /// Receipts pruning configuration. This setting overrides `receipts_log_filter`
-    /// and offers improved performance.
+    /// Receipts pruning configuration. This setting overrides `receipts_log_filter` and
+    /// `receipts_log_rules`, and offers improved performance.
     #[serde(
         skip_serializing_if = "Option::is_none",
         deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
@@ -43,6 +44,12 @@ pub struct PruneModes {
     /// The [`BlockNumber`](`crate::BlockNumber`) represents the starting block from which point
     /// onwards the receipts are preserved.
     pub receipts_log_filter: ReceiptsLogPruneConfig,
+    /// Receipts pruning configuration by retaining only those receipts that contain logs matching
+    /// any of the rules by emitter address and topics, discarding others. Receipts retained by
+    /// either `receipts_log_filter` or `receipts_log_rules` are kept. This setting is overridden
+    /// by `receipts`.
+    #[serde(skip_serializing_if = "ReceiptsLogRules::is_empty")]
+    pub receipts_log_rules: ReceiptsLogRules,
 }
 
 impl PruneModes {
@@ -60,6 +67,7 @@ impl PruneModes {
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
             receipts_log_filter: Default::default(),
+            receipts_log_rules: Default::default(),
         }
     }
 
@@ -67,6 +75,112 @@ impl PruneModes {
     pub fn is_empty(&self) -> bool {
         self == &Self::none()
     }
+
+    /// Returns the prune mode configured for the segment.
+    ///
+    /// For [`PruneSegment::ContractLogs`], returns the mode of the receipts without logs matching
+    /// the receipts log rules, which are pruned at [`MINIMUM_PRUNING_DISTANCE`] if any rules are
+    /// set. Returns [`None`] for the segments that are only pruned after being copied to static
+    /// files.
+    pub fn segment_mode(&self, segment: PruneSegment) -> Option<PruneMode> {
+        match segment {
+            PruneSegment::SenderRecovery => self.sender_recovery,
+            PruneSegment::TransactionLookup => self.transaction_lookup,
+            PruneSegment::Receipts => self.receipts,
+            PruneSegment::ContractLogs => self
+                .has_receipts_log_filter()
+                .then_some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+            PruneSegment::AccountHistory => self.account_history,
+            PruneSegment::StorageHistory => self.storage_history,
+            PruneSegment::Headers | PruneSegment::Transactions => None,
+        }
+    }
+
+    /// Returns `true` if receipts are filtered by logs, either by contract addresses or by rules.
+    pub fn has_receipts_log_filter(&self) -> bool {
+        !self.receipts_log_filter.is_empty() || !self.receipts_log_rules.is_empty()
+    }
+
+    /// Returns all rules for retaining receipts by logs, with the contract addresses of
+    /// `receipts_log_filter` converted to rules on the emitter address.
+    pub fn all_receipts_log_rules(&self) -> ReceiptsLogRules {
+        ReceiptsLogRules(
+            self.receipts_log_filter
+                .0
+                .iter()
+                .map(|(address, mode)| ReceiptsLogRule::address(*address, *mode))
+                .chain(self.receipts_log_rules.0.iter().cloned())
+                .collect(),
+        )
+    }
+
+    /// Validates the combination of prune modes.
+    ///
+    /// Returns an error if:
+    /// - a receipts log rule matches every log
+    /// - a receipts log rule leaves less than [`MINIMUM_PRUNING_DISTANCE`] blocks in the database
+    /// - `receipts` prunes receipts that a receipts log rule is configured to retain
+    pub fn validate(&self) -> Result<(), PruneModesError> {
+        for rule in self.all_receipts_log_rules().0 {
+            if rule.is_wildcard() {
+                return Err(PruneModesError::WildcardReceiptsLogRule(rule))
+            }
+
+            let leaves_min_blocks = match rule.mode {
+                PruneMode::Full => false,
+                PruneMode::Distance(distance) => distance >= MINIMUM_PRUNING_DISTANCE,
+                PruneMode::Before(_) => true,
+            };
+            if !leaves_min_blocks {
+                return Err(PruneModesError::ReceiptsLogRuleDistance(rule))
+            }
+
+            if let Some(receipts) = self.receipts {
+                if receipts_prunes_retained(receipts, rule.mode) {
+                    return Err(PruneModesError::ReceiptsLogRuleOverridden { receipts, rule })
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` if the `receipts` prune mode deletes receipts of some block that the log rule
+/// prune mode retains, for any tip.
+const fn receipts_prunes_retained(receipts: PruneMode, rule: PruneMode) -> bool {
+    match (receipts, rule) {
+        (PruneMode::Full, _) | (PruneMode::Distance(_), PruneMode::Full | PruneMode::Before(_)) => {
+            true
+        }
+        (PruneMode::Distance(receipts), PruneMode::Distance(rule)) => receipts < rule,
+        (PruneMode::Before(receipts), PruneMode::Before(rule)) => receipts > rule,
+        (PruneMode::Before(receipts), PruneMode::Full | PruneMode::Distance(_)) => receipts > 0,
+    }
+}
+
+/// [`PruneModes`] validation error.
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum PruneModesError {
+    /// Receipts log rule matches every log.
+    #[error("receipts log rule {0:?} matches every log, use `receipts` pruning instead")]
+    WildcardReceiptsLogRule(ReceiptsLogRule),
+    /// Receipts log rule doesn't leave enough blocks in the database.
+    #[error(
+        "receipts log rule {0:?} must leave at least {min_blocks} blocks in the database",
+        min_blocks = MINIMUM_PRUNING_DISTANCE
+    )]
+    ReceiptsLogRuleDistance(ReceiptsLogRule),
+    /// `receipts` prune mode deletes receipts retained by a receipts log rule.
+    #[error(
+        "`receipts` prune mode {receipts:?} deletes receipts retained by receipts log rule {rule:?}"
+    )]
+    ReceiptsLogRuleOverridden {
+        /// The `receipts` prune mode.
+        receipts: PruneMode,
+        /// The overridden rule.
+        rule: ReceiptsLogRule,
+    },
 }
 
 /// Deserializes [`Option<PruneMode>`] and validates that the value is not less than the const
@@ -106,8 +220,101 @@ fn deserialize_opt_prune_mode_with_min_blocks<'de, const MIN_BLOCKS: u64, D: Des
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_primitives::{Address, B256};
     use assert_matches::assert_matches;
     use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn validate_receipts_log_rules() {
+        let deposit_contract = Address::with_last_byte(1);
+        let topic_rule = ReceiptsLogRule {
+            address: None,
+            topic0: Some(B256::with_last_byte(1)),
+            topic1: None,
+            topic2: Some(B256::with_last_byte(2)),
+            topic3: None,
+            mode: PruneMode::Distance(MINIMUM_PRUNING_DISTANCE),
+        };
+
+        let modes = PruneModes {
+            receipts: Some(PruneMode::Before(100)),
+            receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([(
+                deposit_contract,
+                PruneMode::Before(100),
+            )])),
+            ..Default::default()
+        };
+        assert_eq!(modes.validate(), Ok(()));
+
+        let modes = PruneModes {
+            receipts_log_rules: ReceiptsLogRules(vec![topic_rule.clone()]),
+            ..Default::default()
+        };
+        assert_eq!(modes.validate(), Ok(()));
+        assert!(modes.has_receipts_log_filter());
+
+        let wildcard = ReceiptsLogRule { topic0: None, topic2: None, ..topic_rule.clone() };
+        let modes = PruneModes {
+            receipts_log_rules: ReceiptsLogRules(vec![wildcard.clone()]),
+            ..Default::default()
+        };
+        assert_eq!(modes.validate(), Err(PruneModesError::WildcardReceiptsLogRule(wildcard)));
+
+        let short = ReceiptsLogRule { mode: PruneMode::Distance(128), ..topic_rule.clone() };
+        let modes = PruneModes {
+            receipts_log_rules: ReceiptsLogRules(vec![short.clone()]),
+            ..Default::default()
+        };
+        assert_eq!(modes.validate(), Err(PruneModesError::ReceiptsLogRuleDistance(short)));
+
+        let modes = PruneModes {
+            receipts: Some(PruneMode::Full),
+            receipts_log_rules: ReceiptsLogRules(vec![topic_rule.clone()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            modes.validate(),
+            Err(PruneModesError::ReceiptsLogRuleOverridden {
+                receipts: PruneMode::Full,
+                rule: topic_rule
+            })
+        );
+
+        let modes = PruneModes {
+            receipts: Some(PruneMode::Before(200)),
+            receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([(
+                deposit_contract,
+                PruneMode::Before(100),
+            )])),
+            ..Default::default()
+        };
+        assert_matches!(
+            modes.validate(),
+            Err(PruneModesError::ReceiptsLogRuleOverridden {
+                receipts: PruneMode::Before(200),
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_receipts_log_rules() {
+        let modes: PruneModes = toml::from_str(
+            r#"
+            [[receipts_log_rules]]
+            topic0 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+            topic2 = "0x0000000000000000000000000000000000000000000000000000000000000001"
+            mode = { before = 100 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(modes.receipts_log_rules.0.len(), 1);
+        let rule = &modes.receipts_log_rules.0[0];
+        assert_eq!(rule.address, None);
+        assert_eq!(rule.topic2, Some(B256::with_last_byte(1)));
+        assert_eq!(rule.mode, PruneMode::Before(100));
+    }
 
     #[test]
     fn test_deserialize_opt_prune_mode_with_min_blocks() {
@@ -1,12 +1,13 @@
 //! Helper for handling execution of multiple blocks.
 
-use crate::{precompile::Address, primitives::alloy_primitives::BlockNumber};
+use crate::primitives::{alloy_primitives::BlockNumber, HashSet};
 use core::time::Duration;
 use reth_execution_errors::BlockExecutionError;
-use reth_primitives::{Receipt, Receipts, Request, Requests};
-use reth_prune_types::{PruneMode, PruneModes, PruneSegmentError, MINIMUM_PRUNING_DISTANCE};
+use reth_primitives::{Address, Log, Receipt, Receipts, Request, Requests};
+use reth_prune_types::{
+    PruneMode, PruneModes, PruneSegmentError, ReceiptsLogRule, MINIMUM_PRUNING_DISTANCE,
+};
 use revm::db::states::bundle_state::BundleRetention;
-use std::collections::HashSet;
 use tracing::debug;
 
 #[cfg(not(feature = "std"))]
@@ -33,11 +34,11 @@ pub struct BlockBatchRecord {
     /// A transaction may have zero or more requests, so the length of the inner vector is not
     /// guaranteed to be the same as the number of transactions.
     requests: Vec<Requests>,
-    /// Memoized address pruning filter.
+    /// Memoized log pruning filter.
     ///
-    /// Empty implies that there is going to be addresses to include in the filter in a future
+    /// Empty implies that there is going to be rules to include in the filter in a future
     /// block. None means there isn't any kind of configuration.
-    pruning_address_filter: Option<(u64, HashSet<Address>)>,
+    pruning_log_filter: Option<ReceiptsLogFilter>,
     /// First block will be initialized to `None`
     /// and be set to the block number of first block executed.
     first_block: Option<BlockNumber>,
@@ -54,6 +55,7 @@ impl BlockBatchRecord {
     /// Set prune modes.
     pub fn set_prune_modes(&mut self, prune_modes: PruneModes) {
         self.prune_modes = prune_modes;
+        self.pruning_log_filter = None;
     }
 
     /// Set the first block number of the batch.
@@ -150,22 +152,24 @@ impl BlockBatchRecord {
             return Ok(())
         }
 
-        let contract_log_pruner = self.prune_modes.receipts_log_filter.group_by_block(tip, None)?;
-
-        if !contract_log_pruner.is_empty() {
-            let (prev_block, filter) =
-                self.pruning_address_filter.get_or_insert_with(|| (0, HashSet::new()));
-            for (_, addresses) in contract_log_pruner.range(*prev_block..=block_number) {
-                filter.extend(addresses.iter().copied());
-            }
+        // The rules are only grouped again if the tip of the batch has changed.
+        if self.pruning_log_filter.as_ref().map_or(true, |filter| filter.tip != tip) {
+            let log_rules = self.prune_modes.all_receipts_log_rules();
+            self.pruning_log_filter = if log_rules.is_empty() {
+                None
+            } else {
+                Some(ReceiptsLogFilter::new(tip, &log_rules.group_by_block(tip, None)?))
+            };
         }
 
-        if let Some((_, filter)) = &self.pruning_address_filter {
+        if let Some(filter) = &mut self.pruning_log_filter {
+            filter.advance(block_number);
+
             for receipt in receipts.iter_mut() {
-                // If there is an address_filter, it does not contain any of the
-                // contract addresses, then remove this receipt.
+                // If there is a log filter, and none of the logs match any of its rules, then
+                // remove this receipt.
                 let inner_receipt = receipt.as_ref().expect("receipts have not been pruned");
-                if !inner_receipt.logs.iter().any(|log| filter.contains(&log.address)) {
+                if !inner_receipt.logs.iter().any(|log| filter.matches(log)) {
                     receipt.take();
                 }
             }
@@ -180,6 +184,66 @@ impl BlockBatchRecord {
     }
 }
 
+/// Receipts log rules of a batch, grouped once for its tip.
+///
+/// Rules are included in the filter starting from the block they retain receipts from, so the
+/// blocks have to be advanced through in ascending order.
+#[derive(Debug)]
+struct ReceiptsLogFilter {
+    /// Tip the rules were grouped by.
+    tip: BlockNumber,
+    /// Rules with the first block they retain receipts from, in ascending block order.
+    pending: Vec<(BlockNumber, ReceiptsLogRule)>,
+    /// Index of the next pending rule to include in the filter.
+    next_rule: usize,
+    /// Emitter addresses of the included rules that don't filter by topics.
+    addresses: HashSet<Address>,
+    /// Included rules that filter by topics.
+    rules: Vec<ReceiptsLogRule>,
+}
+
+impl ReceiptsLogFilter {
+    /// Creates a filter from the rules grouped by
+    /// [`ReceiptsLogRules::group_by_block`](reth_prune_types::ReceiptsLogRules::group_by_block).
+    fn new<'a>(
+        tip: BlockNumber,
+        grouped: impl IntoIterator<Item = (&'a BlockNumber, &'a Vec<&'a ReceiptsLogRule>)>,
+    ) -> Self {
+        Self {
+            tip,
+            pending: grouped
+                .into_iter()
+                .flat_map(|(block, rules)| rules.iter().map(|rule| (*block, (*rule).clone())))
+                .collect(),
+            next_rule: 0,
+            addresses: HashSet::default(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Includes the rules retaining receipts from blocks up to and including `block_number`.
+    fn advance(&mut self, block_number: BlockNumber) {
+        while let Some((block, rule)) = self.pending.get(self.next_rule) {
+            if *block > block_number {
+                break
+            }
+
+            match rule.address {
+                Some(address) if rule.topics().iter().all(Option::is_none) => {
+                    self.addresses.insert(address);
+                }
+                _ => self.rules.push(rule.clone()),
+            }
+            self.next_rule += 1;
+        }
+    }
+
+    /// Returns `true` if the log is matched by any of the included rules.
+    fn matches(&self, log: &Log) -> bool {
+        self.addresses.contains(&log.address) || self.rules.iter().any(|rule| rule.matches(log))
+    }
+}
+
 /// Block execution statistics. Contains duration of each step of block execution.
 #[derive(Clone, Debug, Default)]
 pub struct BlockExecutorStats {
@@ -214,8 +278,8 @@ impl BlockExecutorStats {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reth_primitives::{Address, Log, Receipt};
-    use reth_prune_types::{PruneMode, ReceiptsLogPruneConfig};
+    use reth_primitives::{Address, Log, Receipt, B256};
+    use reth_prune_types::{PruneMode, ReceiptsLogPruneConfig, ReceiptsLogRules};
     use std::collections::BTreeMap;
 
     #[test]
@@ -395,4 +459,99 @@ mod tests {
         assert_eq!(recorder.receipts().len(), 4);
         assert_eq!(recorder.receipts()[3][0], Some(receipt3));
     }
+
+    // Test saving receipts with log topics filter pruning
+    #[test]
+    fn test_save_receipts_with_log_topics_filter_pruning() {
+        let transfer = B256::with_last_byte(1);
+        let recipient = B256::with_last_byte(2);
+        let prune_modes = PruneModes {
+            receipts_log_rules: ReceiptsLogRules(vec![ReceiptsLogRule {
+                address: None,
+                topic0: Some(transfer),
+                topic1: None,
+                topic2: Some(recipient),
+                topic3: None,
+                mode: PruneMode::Before(1300001),
+            }]),
+            ..Default::default()
+        };
+
+        let mut recorder = BlockBatchRecord::new(prune_modes);
+        recorder.set_first_block(1);
+        recorder.set_tip(1300000);
+
+        // With a receipt that should be pruned (recipient is the second topic)
+        let mut receipt = Receipt::default();
+        receipt.logs.push(Log::new_unchecked(
+            Address::with_last_byte(1),
+            vec![transfer, recipient, B256::ZERO],
+            Default::default(),
+        ));
+        assert!(recorder.save_receipts(vec![receipt]).is_ok());
+        assert_eq!(recorder.receipts()[0], vec![None]);
+
+        // With a receipt that should not be pruned (transfer to the recipient from any contract)
+        let mut receipt = Receipt::default();
+        receipt.logs.push(Log::new_unchecked(
+            Address::with_last_byte(2),
+            vec![transfer, B256::ZERO, recipient],
+            Default::default(),
+        ));
+        assert!(recorder.save_receipts(vec![receipt.clone()]).is_ok());
+        assert_eq!(recorder.receipts()[1][0], Some(receipt));
+    }
+
+    // Test that rules are only included in the log filter from the block they retain receipts
+    #[test]
+    fn test_save_receipts_log_filter_includes_rules_by_block() {
+        let contract = Address::with_last_byte(1);
+        let token = Address::with_last_byte(2);
+        let transfer = B256::with_last_byte(3);
+        let prune_modes = PruneModes {
+            receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([(
+                contract,
+                PruneMode::Before(3),
+            )])),
+            receipts_log_rules: ReceiptsLogRules(vec![ReceiptsLogRule {
+                topic0: Some(transfer),
+                ..ReceiptsLogRule::address(token, PruneMode::Before(1))
+            }]),
+            ..Default::default()
+        };
+
+        let mut recorder = BlockBatchRecord::new(prune_modes);
+        recorder.set_first_block(1);
+        recorder.set_tip(1300000);
+
+        let receipt_with_log = |log| {
+            let mut receipt = Receipt::default();
+            receipt.logs.push(log);
+            receipt
+        };
+        let contract_receipt = receipt_with_log(Log { address: contract, ..Default::default() });
+        let transfer_receipt =
+            receipt_with_log(Log::new_unchecked(token, vec![transfer], Default::default()));
+        let token_receipt = receipt_with_log(Log { address: token, ..Default::default() });
+
+        // Blocks 1 and 2 only retain the transfers of the token
+        for _ in 1..=2 {
+            let receipts =
+                vec![contract_receipt.clone(), transfer_receipt.clone(), token_receipt.clone()];
+            assert!(recorder.save_receipts(receipts).is_ok());
+        }
+        // Block 3 also retains the receipts with logs of the contract
+        let receipts = vec![contract_receipt.clone(), transfer_receipt.clone(), token_receipt];
+        assert!(recorder.save_receipts(receipts).is_ok());
+
+        assert_eq!(
+            *recorder.receipts(),
+            vec![
+                vec![None, Some(transfer_receipt.clone()), None],
+                vec![None, Some(transfer_receipt.clone()), None],
+                vec![Some(contract_receipt), Some(transfer_receipt), None],
+            ]
+            .into()
+        );
+    }
 }
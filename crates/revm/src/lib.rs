@@ -20,6 +20,9 @@ pub mod batch;
 /// State changes that are not related to transactions.
 pub mod state_change;
 
+/// Recording of the state accessed during execution.
+pub mod witness;
+
 /// Common test helpers
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
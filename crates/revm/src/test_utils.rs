@@ -5,7 +5,7 @@ use reth_storage_api::{
     AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRootProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
 use revm::db::BundleState;
 use std::collections::HashMap;
 
@@ -87,6 +87,14 @@ impl StateProofProvider for StateProviderTest {
     ) -> ProviderResult<AccountProof> {
         unimplemented!("proof generation is not supported")
     }
+
+    fn witness(
+        &self,
+        _overlay: &BundleState,
+        _target: &HashedPostState,
+    ) -> ProviderResult<HashMap<B256, Bytes>> {
+        unimplemented!("witness generation is not supported")
+    }
 }
 
 impl StateProvider for StateProviderTest {
@@ -0,0 +1,97 @@
+use reth_primitives::{Address, B256, U256};
+use revm::{
+    primitives::{AccountInfo, Bytecode, HashMap},
+    Database,
+};
+
+/// A [Database] wrapper that records all accounts, storage slots and bytecodes read from the
+/// underlying database.
+///
+/// The recorded values are the pre-state of the execution, which is used to generate execution
+/// witnesses.
+#[derive(Debug, Default)]
+pub struct RecordingDatabase<DB> {
+    /// The underlying database.
+    pub database: DB,
+    /// Accounts that were read, `None` if the account doesn't exist.
+    pub accounts: HashMap<Address, Option<AccountInfo>>,
+    /// Storage slots that were read, by account.
+    pub storage: HashMap<Address, HashMap<U256, U256>>,
+    /// Bytecodes that were read, by code hash.
+    pub codes: HashMap<B256, Bytecode>,
+}
+
+impl<DB> RecordingDatabase<DB> {
+    /// Creates a new recording database wrapping the given database.
+    pub fn new(database: DB) -> Self {
+        Self {
+            database,
+            accounts: HashMap::default(),
+            storage: HashMap::default(),
+            codes: HashMap::default(),
+        }
+    }
+}
+
+impl<DB: Database> Database for RecordingDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.database.basic(address)?;
+        if let Some(code) = info.as_ref().and_then(|info| info.code.clone()) {
+            self.codes.insert(code.hash_slow(), code);
+        }
+        self.accounts.entry(address).or_insert_with(|| info.clone());
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.database.code_by_hash(code_hash)?;
+        self.codes.entry(code_hash).or_insert_with(|| code.clone());
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.database.storage(address, index)?;
+        self.storage.entry(address).or_default().entry(index).or_insert(value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.database.block_hash(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{database::StateProviderDatabase, test_utils::StateProviderTest};
+    use reth_primitives::{keccak256, Account, Bytes};
+
+    #[test]
+    fn records_reads() {
+        let address = Address::with_last_byte(1);
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let code_hash = keccak256(&code);
+        let mut state = StateProviderTest::default();
+        state.insert_account(
+            address,
+            Account { nonce: 1, ..Default::default() },
+            Some(code),
+            std::collections::HashMap::from([(B256::with_last_byte(2), U256::from(3))]),
+        );
+
+        let mut db = RecordingDatabase::new(StateProviderDatabase::new(state));
+        assert!(db.basic(address).unwrap().is_some());
+        assert!(db.basic(Address::with_last_byte(2)).unwrap().is_none());
+        assert_eq!(db.storage(address, U256::from(2)).unwrap(), U256::from(3));
+        assert_eq!(db.storage(address, U256::from(4)).unwrap(), U256::ZERO);
+        db.code_by_hash(code_hash).unwrap();
+
+        assert_eq!(db.accounts.len(), 2);
+        assert_eq!(db.accounts[&Address::with_last_byte(2)], None);
+        assert_eq!(db.storage[&address].len(), 2);
+        assert_eq!(db.storage[&address][&U256::from(2)], U256::from(3));
+        assert!(db.codes.contains_key(&code_hash));
+    }
+}
@@ -5,7 +5,7 @@ use reth_rpc_types::{
         BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
         TraceResult,
     },
-    Bundle, RichBlock, StateContext, TransactionRequest,
+    Bundle, ExecutionWitness, RichBlock, StateContext, TransactionRequest,
 };
 
 /// Debug rpc interface.
@@ -132,6 +132,15 @@ pub trait DebugApi {
         opts: Option<GethDebugTracingCallOptions>,
     ) -> RpcResult<Vec<Vec<GethTrace>>>;
 
+    /// Re-executes the block and returns the execution witness: every trie node, contract bytecode
+    /// and key preimage of the state before the block that is accessed during execution and state
+    /// root computation.
+    ///
+    /// The witness is sufficient to execute the block and compute its state root statelessly.
+    #[method(name = "executionWitness")]
+    async fn debug_execution_witness(&self, block: BlockNumberOrTag)
+        -> RpcResult<ExecutionWitness>;
+
     /// Sets the logging backtrace location. When a backtrace location is set and a log message is
     /// emitted at that location, the stack of the goroutine executing the log statement will
     /// be printed to stderr.
@@ -73,6 +73,7 @@ pub mod clients {
         mev::MevApiClient,
         net::NetApiClient,
         otterscan::OtterscanClient,
+        reth::RethApiClient,
         rpc::RpcApiServer,
         trace::TraceApiClient,
         txpool::TxPoolApiClient,
@@ -1,5 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{Address, BlockId, U256};
+use reth_prune_types::PruneStatus;
 use std::collections::HashMap;
 
 /// Reth API namespace for reth-specific methods
@@ -12,4 +13,9 @@ pub trait RethApi {
         &self,
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
+
+    /// Returns the prune checkpoint and the current target of each prune segment, and the
+    /// progress of the latest pruner run
+    #[method(name = "pruneStatus")]
+    async fn reth_prune_status(&self) -> RpcResult<PruneStatus>;
 }
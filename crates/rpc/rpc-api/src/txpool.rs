@@ -1,6 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::Address;
-use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
+use reth_rpc_types::{
+    txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus},
+    TxpoolRemovedTransaction, TxpoolSubPoolContent,
+};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
@@ -33,4 +36,25 @@ pub trait TxPoolApi {
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     #[method(name = "content")]
     async fn txpool_content(&self) -> RpcResult<TxpoolContent>;
+
+    /// Returns the transactions of all four sub-pools (pending, basefee, blob and queued) together
+    /// with the state flags that determine the sub-pool of each transaction.
+    #[method(name = "subPoolContent")]
+    async fn txpool_sub_pool_content(&self) -> RpcResult<TxpoolSubPoolContent>;
+
+    /// Same as `txpool_subPoolContent` but only returns the transactions of the given sender.
+    #[method(name = "subPoolContentFrom")]
+    async fn txpool_sub_pool_content_from(&self, from: Address) -> RpcResult<TxpoolSubPoolContent>;
+
+    /// Returns the most recently removed transactions and why they were removed, oldest first.
+    #[method(name = "removed")]
+    async fn txpool_removed(&self) -> RpcResult<Vec<TxpoolRemovedTransaction>>;
+
+    /// Creates a subscription that streams the events of all transactions in the pool.
+    #[subscription(
+        name = "subscribeEvents",
+        unsubscribe = "unsubscribeEvents",
+        item = reth_rpc_types::TxpoolEvent
+    )]
+    async fn txpool_subscribe_events(&self) -> jsonrpsee::core::SubscriptionResult;
 }
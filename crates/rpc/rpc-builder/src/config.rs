@@ -6,7 +6,7 @@ use jsonrpsee::server::ServerBuilder;
 use reth_node_core::{args::RpcServerArgs, utils::get_or_create_jwt_secret_from_path};
 use reth_rpc_eth_types::{EthStateCacheConfig, GasPriceOracleConfig};
 use reth_rpc_layer::{JwtError, JwtSecret};
-use reth_rpc_server_types::RpcModuleSelection;
+use reth_rpc_server_types::{constants, RpcModuleSelection};
 use std::{net::SocketAddr, path::PathBuf};
 use tower::layer::util::Identity;
 use tracing::debug;
@@ -87,11 +87,19 @@ impl RethRpcServerConfig for RpcServerArgs {
     }
 
     fn eth_config(&self) -> EthConfig {
+        // proofs at old blocks are cheap with revert checkpoints, so the window is only capped
+        // without them
+        let eth_proof_window = if self.rpc_proof_checkpoint_interval.is_some() {
+            self.rpc_eth_proof_window
+        } else {
+            self.rpc_eth_proof_window.min(constants::MAX_ETH_PROOF_WINDOW)
+        };
+
         EthConfig::default()
             .max_tracing_requests(self.rpc_max_tracing_requests)
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
-            .eth_proof_window(self.rpc_eth_proof_window)
+            .eth_proof_window(eth_proof_window)
             .rpc_gas_cap(self.rpc_gas_cap)
             .state_cache(self.state_cache_config())
             .gpo_config(self.gas_price_oracle_config())
@@ -246,6 +254,33 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_eth_proof_window() {
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.eth-proof-window", "1000"])
+                .args;
+        assert_eq!(args.eth_config().eth_proof_window, 1000);
+
+        // the window is capped without revert checkpoints
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.eth-proof-window",
+            "1000000",
+        ])
+        .args;
+        assert_eq!(args.eth_config().eth_proof_window, constants::MAX_ETH_PROOF_WINDOW);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.eth-proof-window",
+            "1000000",
+            "--rpc.proof-checkpoint-interval",
+            "1000",
+        ])
+        .args;
+        assert_eq!(args.eth_config().eth_proof_window, 1000000);
+    }
+
     #[test]
     fn test_transport_rpc_module_config() {
         let args = CommandParser::<RpcServerArgs>::parse_from([
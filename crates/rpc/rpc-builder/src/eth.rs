@@ -6,6 +6,7 @@ use reth_provider::{
     BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, EvmEnvProvider,
     FullRpcProvider, StateProviderFactory,
 };
+use reth_prune_types::PruneModes;
 use reth_rpc::{eth::EthFilterConfig, EthApi, EthFilter, EthPubSub};
 use reth_rpc_eth_types::{
     cache::cache_new_blocks_task, fee_history::fee_history_cache_new_blocks_task, EthStateCache,
@@ -161,6 +162,9 @@ pub struct EthConfig {
     pub fee_history_cache: FeeHistoryCacheConfig,
     /// The maximum number of getproof calls that can be executed concurrently.
     pub proof_permits: usize,
+    /// Prune modes of the node, used to reject `eth_getLogs` requests over pruned receipts.
+    #[serde(default)]
+    pub prune_modes: PruneModes,
 }
 
 impl EthConfig {
@@ -170,6 +174,7 @@ impl EthConfig {
             .max_blocks_per_filter(self.max_blocks_per_filter)
             .max_logs_per_response(self.max_logs_per_response)
             .stale_filter_ttl(self.stale_filter_ttl)
+            .prune_modes(self.prune_modes.clone())
     }
 }
 
@@ -186,6 +191,7 @@ impl Default for EthConfig {
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
             proof_permits: DEFAULT_PROOF_PERMITS,
+            prune_modes: PruneModes::none(),
         }
     }
 }
@@ -238,6 +244,12 @@ impl EthConfig {
         self.proof_permits = permits;
         self
     }
+
+    /// Configures the prune modes of the node
+    pub fn prune_modes(mut self, prune_modes: PruneModes) -> Self {
+        self.prune_modes = prune_modes;
+        self
+    }
 }
 
 /// Context for building the `eth` namespace API.
@@ -18,7 +18,10 @@
 //! ```
 //! use reth_evm::ConfigureEvm;
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider};
+//! use reth_provider::{
+//!     AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider,
+//!     PruneCheckpointReader,
+//! };
 //! use reth_rpc_builder::{
 //!     EthApiBuild, RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder,
 //!     TransportRpcModuleConfig,
@@ -33,7 +36,7 @@
 //!     events: Events,
 //!     evm_config: EvmConfig,
 //! ) where
-//!     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+//!     Provider: FullRpcProvider + AccountReader + ChangeSetReader + PruneCheckpointReader,
 //!     Pool: TransactionPool + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions + Clone + 'static,
@@ -69,7 +72,10 @@
 //! use reth_engine_primitives::EngineTypes;
 //! use reth_evm::ConfigureEvm;
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider};
+//! use reth_provider::{
+//!     AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider,
+//!     PruneCheckpointReader,
+//! };
 //! use reth_rpc_api::EngineApiServer;
 //! use reth_rpc_builder::{
 //!     auth::AuthServerConfig, EthApiBuild, RethRpcModule, RpcModuleBuilder, RpcServerConfig,
@@ -87,7 +93,7 @@
 //!     engine_api: EngineApi,
 //!     evm_config: EvmConfig,
 //! ) where
-//!     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+//!     Provider: FullRpcProvider + AccountReader + ChangeSetReader + PruneCheckpointReader,
 //!     Pool: TransactionPool + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions + Clone + 'static,
@@ -151,11 +157,12 @@ use reth_evm::ConfigureEvm;
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_provider::{
     AccountReader, BlockReader, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, FullRpcProvider, StateProviderFactory,
+    EvmEnvProvider, FullRpcProvider, PruneCheckpointReader, StateProviderFactory,
 };
+use reth_prune_types::PruneModes;
 use reth_rpc::{
-    AdminApi, DebugApi, EngineEthApi, EthBundle, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi,
-    TxPoolApi, Web3Api,
+    AdminApi, DebugApi, EngineEthApi, EthBundle, NetApi, OtterscanApi, PrunerProgressTracker,
+    RPCApi, RethApi, TraceApi, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_eth_api::{
@@ -167,7 +174,7 @@ use reth_rpc_eth_api::{
 use reth_rpc_eth_types::{EthStateCache, EthSubscriptionIdProvider};
 use reth_rpc_layer::{AuthLayer, Claims, JwtAuthValidator, JwtSecret};
 use reth_tasks::{pool::BlockingTaskGuard, TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::{noop::NoopTransactionPool, TransactionPool};
+use reth_transaction_pool::{noop::NoopTransactionPool, BundlePool, TransactionPool};
 use serde::{Deserialize, Serialize};
 use tower_http::cors::CorsLayer;
 
@@ -220,7 +227,7 @@ pub async fn launch<Provider, Pool, Network, Tasks, Events, EvmConfig, EthApi, E
     eth: EthApiB,
 ) -> Result<RpcServerHandle, RpcError>
 where
-    Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+    Provider: FullRpcProvider + AccountReader + ChangeSetReader + PruneCheckpointReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -408,7 +415,7 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
 impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig>
 where
-    Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+    Provider: FullRpcProvider + AccountReader + ChangeSetReader + PruneCheckpointReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -623,6 +630,12 @@ pub struct RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi> {
     blocking_pool_guard: BlockingTaskGuard,
     /// Contains the [Methods] of a module
     modules: HashMap<RethRpcModule, Methods>,
+    /// The pool bundles sent via `eth_sendBundle` are added to
+    bundle_pool: Option<BundlePool>,
+    /// The prune modes reported by `reth_pruneStatus`
+    prune_modes: PruneModes,
+    /// The pruner progress reported by `reth_pruneStatus`
+    pruner_progress: PrunerProgressTracker,
 }
 
 // === impl RpcRegistryInner ===
@@ -654,6 +667,7 @@ where
             + 'static,
     {
         let blocking_pool_guard = BlockingTaskGuard::new(config.eth.max_tracing_requests);
+        let prune_modes = config.eth.prune_modes.clone();
 
         let eth = EthHandlers::builder(
             provider.clone(),
@@ -676,6 +690,9 @@ where
             modules: Default::default(),
             blocking_pool_guard,
             events,
+            bundle_pool: None,
+            prune_modes,
+            pruner_progress: Default::default(),
         }
     }
 }
@@ -706,6 +723,27 @@ impl<Provider, Pool, Network, Tasks, Events, EthApi>
         &self.pool
     }
 
+    /// Returns the pool bundles sent via `eth_sendBundle` are added to, if configured.
+    pub const fn bundle_pool(&self) -> Option<&BundlePool> {
+        self.bundle_pool.as_ref()
+    }
+
+    /// Configures the pool bundles sent via `eth_sendBundle` are added to.
+    ///
+    /// Without a bundle pool, `eth_sendBundle` and `eth_cancelBundle` are unsupported. This must be
+    /// called before the [`RethRpcModule::EthBundle`] module is created.
+    pub fn set_bundle_pool(&mut self, bundle_pool: BundlePool) -> &mut Self {
+        self.bundle_pool = Some(bundle_pool);
+        self
+    }
+
+    /// Returns the tracker of the pruner progress reported by `reth_pruneStatus`.
+    ///
+    /// It's empty until updated with the events of the node's pruner.
+    pub const fn pruner_progress(&self) -> &PrunerProgressTracker {
+        &self.pruner_progress
+    }
+
     /// Returns a reference to the events type
     pub const fn events(&self) -> &Events {
         &self.events
@@ -789,7 +827,7 @@ where
 impl<Provider, Pool, Network, Tasks, Events, EthApi>
     RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
 where
-    Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+    Provider: FullRpcProvider + AccountReader + ChangeSetReader + PruneCheckpointReader,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
     EthApi: Clone,
@@ -905,7 +943,12 @@ where
         EthApi: EthTransactions + LoadPendingBlock + Call,
     {
         let eth_api = self.eth_api().clone();
-        EthBundle::new(eth_api, self.blocking_pool_guard.clone())
+        match self.bundle_pool.clone() {
+            Some(bundle_pool) => {
+                EthBundle::with_bundle_pool(eth_api, self.blocking_pool_guard.clone(), bundle_pool)
+            }
+            None => EthBundle::new(eth_api, self.blocking_pool_guard.clone()),
+        }
     }
 
     /// Instantiates `OtterscanApi`
@@ -949,14 +992,19 @@ where
 
     /// Instantiates `RethApi`
     pub fn reth_api(&self) -> RethApi<Provider> {
-        RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
+        RethApi::with_pruning(
+            self.provider.clone(),
+            Box::new(self.executor.clone()),
+            self.prune_modes.clone(),
+            self.pruner_progress.clone(),
+        )
     }
 }
 
 impl<Provider, Pool, Network, Tasks, Events, EthApi>
     RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
 where
-    Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+    Provider: FullRpcProvider + AccountReader + ChangeSetReader + PruneCheckpointReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -1088,15 +1136,35 @@ where
                         .into_rpc()
                         .into(),
                         RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
-                        RethRpcModule::Reth => {
-                            RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
-                                .into_rpc()
-                                .into()
-                        }
-                        RethRpcModule::EthCallBundle => {
-                            EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
-                                .into_rpc()
-                                .into()
+                        RethRpcModule::Reth => RethApi::with_pruning(
+                            self.provider.clone(),
+                            Box::new(self.executor.clone()),
+                            self.prune_modes.clone(),
+                            self.pruner_progress.clone(),
+                        )
+                        .into_rpc()
+                        .into(),
+                        RethRpcModule::EthCallBundle => EthCallBundleApiServer::into_rpc(
+                            EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone()),
+                        )
+                        .into(),
+                        RethRpcModule::EthBundle => {
+                            let bundle = match self.bundle_pool.clone() {
+                                Some(bundle_pool) => EthBundle::with_bundle_pool(
+                                    eth_api.clone(),
+                                    self.blocking_pool_guard.clone(),
+                                    bundle_pool,
+                                ),
+                                None => EthBundle::new(
+                                    eth_api.clone(),
+                                    self.blocking_pool_guard.clone(),
+                                ),
+                            };
+                            let mut module = EthBundleApiServer::into_rpc(bundle);
+                            // served by `RethRpcModule::EthCallBundle`, which can be enabled
+                            // alongside this module
+                            module.remove_method("eth_callBundle");
+                            module.into()
                         }
                     })
                     .clone()
@@ -1655,6 +1723,29 @@ impl TransportRpcModules {
         self.merge_ipc(other)?;
         Ok(())
     }
+
+    /// Removes the method with the given name from all configured transports.
+    ///
+    /// Returns `true` if the method was removed from any transport.
+    pub fn remove_method_from_configured(&mut self, method_name: &'static str) -> bool {
+        [&mut self.http, &mut self.ws, &mut self.ipc]
+            .into_iter()
+            .flatten()
+            .fold(false, |removed, module| module.remove_method(method_name).is_some() || removed)
+    }
+
+    /// Merge the given [Methods] in all configured methods, replacing the methods that are
+    /// present already.
+    pub fn replace_configured(
+        &mut self,
+        other: impl Into<Methods>,
+    ) -> Result<(), RegisterMethodError> {
+        let other = other.into();
+        for method_name in other.method_names() {
+            self.remove_method_from_configured(method_name);
+        }
+        self.merge_configured(other)
+    }
 }
 
 /// A handle to the spawned servers.
@@ -1777,6 +1868,12 @@ mod tests {
         assert_eq!(selection, RethRpcModule::EthCallBundle);
     }
 
+    #[test]
+    fn parse_eth_bundle() {
+        let selection = "eth-bundle".parse::<RethRpcModule>().unwrap();
+        assert_eq!(selection, RethRpcModule::EthBundle);
+    }
+
     #[test]
     fn parse_eth_call_bundle_selection() {
         let selection = "eth,admin,debug,eth-call-bundle".parse::<RpcModuleSelection>().unwrap();
@@ -1917,4 +2014,31 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn test_replace_configured() {
+        let mut http = RpcModule::new(());
+        http.register_method("eth_sendBundle", |_, _, _| "old").unwrap();
+        http.register_method("eth_chainId", |_, _, _| "0x1").unwrap();
+        let mut modules = TransportRpcModules {
+            config: TransportRpcModuleConfig::default(),
+            http: Some(http),
+            ws: None,
+            ipc: None,
+        };
+
+        let mut other = RpcModule::new(());
+        other.register_method("eth_sendBundle", |_, _, _| "new").unwrap();
+        other.register_method("eth_cancelBundle", |_, _, _| ()).unwrap();
+        assert!(modules.merge_configured(other.clone()).is_err());
+        modules.replace_configured(other).unwrap();
+
+        let http = modules.http.as_ref().unwrap();
+        assert!(http.method("eth_sendBundle").is_some());
+        assert!(http.method("eth_cancelBundle").is_some());
+        assert!(http.method("eth_chainId").is_some());
+
+        assert!(modules.remove_method_from_configured("eth_chainId"));
+        assert!(!modules.remove_method_from_configured("eth_chainId"));
+    }
 }
@@ -17,8 +17,8 @@ use reth_primitives::{
 };
 use reth_rpc_api::{
     clients::{AdminApiClient, EthApiClient},
-    DebugApiClient, EthFilterApiClient, NetApiClient, OtterscanClient, TraceApiClient,
-    Web3ApiClient,
+    DebugApiClient, EthFilterApiClient, NetApiClient, OtterscanClient, RethApiClient,
+    TraceApiClient, Web3ApiClient,
 };
 use reth_rpc_server_types::RethRpcModule;
 use reth_rpc_types::{
@@ -342,6 +342,15 @@ where
     assert!(OtterscanClient::get_contract_creator(client, address).await.unwrap().is_none());
 }
 
+async fn test_basic_reth_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    let prune_status = RethApiClient::reth_prune_status(client).await.unwrap();
+    assert!(prune_status.segments.is_empty());
+    assert!(prune_status.pruner.is_none());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_filter_functions_http() {
     reth_tracing::init_test_tracing();
@@ -540,6 +549,24 @@ async fn test_call_otterscan_functions_http_and_ws() {
     test_basic_otterscan_calls(&client).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_reth_functions_http() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_http(vec![RethRpcModule::Reth]).await;
+    let client = handle.http_client().unwrap();
+    test_basic_reth_calls(&client).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_reth_functions_ws() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_ws(vec![RethRpcModule::Reth]).await;
+    let client = handle.ws_client().await.unwrap();
+    test_basic_reth_calls(&client).await;
+}
+
 // <https://github.com/paradigmxyz/reth/issues/5830>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_eth_logs_args() {
@@ -10,7 +10,7 @@ use reth_rpc_types::{
     state::{EvmOverrides, StateOverride},
     AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
     EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index, RichBlock,
-    StateContext, SyncStatus, Transaction, TransactionRequest, Work,
+    StateContext, SyncStatus, Transaction, TransactionConditional, TransactionRequest, Work,
 };
 use tracing::trace;
 
@@ -302,6 +302,15 @@ pub trait EthApi {
     #[method(name = "sendRawTransaction")]
     async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<B256>;
 
+    /// Sends signed transaction that may only be included in a block if the given conditions
+    /// hold, returning its hash.
+    #[method(name = "sendRawTransactionConditional")]
+    async fn send_raw_transaction_conditional(
+        &self,
+        bytes: Bytes,
+        conditional: TransactionConditional,
+    ) -> RpcResult<B256>;
+
     /// Returns an Ethereum specific signature with: sign(keccak256("\x19Ethereum Signed Message:\n"
     /// + len(message) + message))).
     #[method(name = "sign")]
@@ -696,6 +705,16 @@ where
         Ok(EthTransactions::send_raw_transaction(self, tx).await?)
     }
 
+    /// Handler for: `eth_sendRawTransactionConditional`
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> RpcResult<B256> {
+        trace!(target: "rpc::eth", ?tx, ?conditional, "Serving eth_sendRawTransactionConditional");
+        Ok(EthTransactions::send_raw_transaction_conditional(self, tx, conditional).await?)
+    }
+
     /// Handler for: `eth_sign`
     async fn sign(&self, address: Address, message: Bytes) -> RpcResult<Bytes> {
         trace!(target: "rpc::eth", ?address, ?message, "Serving eth_sign");
@@ -237,8 +237,12 @@ pub trait LoadState {
         self.spawn_blocking_io(move |this| {
             if block_id == Some(BlockId::pending()) {
                 let address_txs = this.pool().get_transactions_by_sender(address);
-                if let Some(highest_nonce) =
-                    address_txs.iter().map(|item| item.transaction.nonce()).max()
+                // private transactions must not be exposed
+                if let Some(highest_nonce) = address_txs
+                    .iter()
+                    .filter(|item| !item.origin.is_private())
+                    .map(|item| item.transaction.nonce())
+                    .max()
                 {
                     let tx_count = highest_nonce
                         .checked_add(1)
@@ -11,18 +11,23 @@ use reth_primitives::{
 };
 use reth_provider::{BlockReaderIdExt, ReceiptProvider, TransactionsProvider};
 use reth_rpc_eth_types::{
-    utils::recover_raw_transaction, EthApiError, EthResult, EthStateCache, SignError,
-    TransactionSource,
+    utils::{into_pool_conditional, recover_raw_transaction},
+    EthApiError, EthResult, EthStateCache, SignError, TransactionSource,
 };
+use reth_rpc_server_types::constants::DEFAULT_PRIVATE_TX_MAX_BLOCKS;
 use reth_rpc_types::{
     transaction::{
         EIP1559TransactionRequest, EIP2930TransactionRequest, EIP4844TransactionRequest,
         LegacyTransactionRequest,
     },
-    AnyTransactionReceipt, Transaction, TransactionRequest, TypedTransactionRequest,
+    AnyTransactionReceipt, Transaction, TransactionConditional, TransactionRequest,
+    TypedTransactionRequest,
 };
 use reth_rpc_types_compat::transaction::from_recovered_with_block_context;
-use reth_transaction_pool::{TransactionOrigin, TransactionPool};
+use reth_transaction_pool::{
+    PoolTransaction, TransactionConditional as PoolTransactionConditional, TransactionOrigin,
+    TransactionPool,
+};
 
 use super::EthSigner;
 
@@ -257,6 +262,93 @@ pub trait EthTransactions: LoadTransaction {
         }
     }
 
+    /// Decodes and recovers the transaction, attaches the given conditions and submits it to the
+    /// pool.
+    ///
+    /// Returns the hash of the transaction.
+    fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> impl Future<Output = EthResult<B256>> + Send {
+        async move {
+            // the conditions would be lost when forwarding the raw transaction
+            if self.raw_tx_forwarder().is_some() {
+                return Err(EthApiError::Unsupported("conditional transactions can't be forwarded"))
+            }
+
+            let recovered = recover_raw_transaction(tx)?;
+            let mut pool_transaction =
+                <Self::Pool as TransactionPool>::Transaction::from_recovered_pooled_transaction(
+                    recovered,
+                );
+            pool_transaction.set_conditional(into_pool_conditional(conditional));
+            if pool_transaction.conditional().is_none() {
+                return Err(EthApiError::Unsupported(
+                    "conditional transactions are not supported by the pool",
+                ))
+            }
+
+            // submit the transaction to the pool with a `Local` origin, the pool never propagates
+            // conditional transactions
+            let hash =
+                self.pool().add_transaction(TransactionOrigin::Local, pool_transaction).await?;
+
+            Ok(hash)
+        }
+    }
+
+    /// Decodes and recovers the transaction and submits it to the pool as a
+    /// [`TransactionOrigin::Private`] transaction.
+    ///
+    /// Private transactions are never propagated and are only included in locally built blocks
+    /// up to and including `max_block_number`, after which they are removed from the pool. If no
+    /// `max_block_number` is given, the transaction expires after
+    /// [`DEFAULT_PRIVATE_TX_MAX_BLOCKS`] blocks.
+    ///
+    /// Returns the hash of the transaction.
+    fn send_private_raw_transaction(
+        &self,
+        tx: Bytes,
+        max_block_number: Option<u64>,
+    ) -> impl Future<Output = EthResult<B256>> + Send {
+        async move {
+            // forwarding would make the transaction public
+            if self.raw_tx_forwarder().is_some() {
+                return Err(EthApiError::Unsupported("private transactions can't be forwarded"))
+            }
+
+            let tip = self.pool().block_info().last_seen_block_number;
+            let max_block_number = max_block_number.unwrap_or(tip + DEFAULT_PRIVATE_TX_MAX_BLOCKS);
+            if max_block_number <= tip {
+                return Err(EthApiError::InvalidParams(format!(
+                    "max block number {max_block_number} is not after the latest block {tip}"
+                )))
+            }
+
+            let recovered = recover_raw_transaction(tx)?;
+            let mut pool_transaction =
+                <Self::Pool as TransactionPool>::Transaction::from_recovered_pooled_transaction(
+                    recovered,
+                );
+            // the deadline is enforced by the pool like any other block number condition
+            pool_transaction.set_conditional(PoolTransactionConditional {
+                block_number_max: Some(max_block_number),
+                ..Default::default()
+            });
+            if pool_transaction.conditional().is_none() {
+                return Err(EthApiError::Unsupported(
+                    "private transactions are not supported by the pool",
+                ))
+            }
+
+            let hash =
+                self.pool().add_transaction(TransactionOrigin::Private, pool_transaction).await?;
+
+            Ok(hash)
+        }
+    }
+
     /// Signs transaction with a matching signer, if any and submits the transaction to the pool.
     /// Returns the hash of the signed transaction.
     fn send_transaction(
@@ -568,9 +660,12 @@ pub trait LoadTransaction: SpawnBlocking {
                 .await?;
 
             if resp.is_none() {
-                // tx not found on disk, check pool
-                if let Some(tx) =
-                    self.pool().get(&hash).map(|tx| tx.transaction.to_recovered_transaction())
+                // tx not found on disk, check pool, private transactions must not be exposed
+                if let Some(tx) = self
+                    .pool()
+                    .get(&hash)
+                    .filter(|tx| !tx.origin.is_private())
+                    .map(|tx| tx.transaction.to_recovered_transaction())
                 {
                     resp = Some(TransactionSource::Pool(tx));
                 }
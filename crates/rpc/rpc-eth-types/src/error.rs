@@ -588,6 +588,9 @@ pub enum RpcPoolError {
     /// When the replacement transaction is underpriced
     #[error("replacement transaction underpriced")]
     ReplaceUnderpriced,
+    /// When the sender replaced too many transactions
+    #[error("replacement rate limit exceeded")]
+    ReplacementRateLimited,
     /// When the transaction exceeds the block gas limit
     #[error("exceeds block gas limit")]
     ExceedsGasLimit,
@@ -615,6 +618,12 @@ pub enum RpcPoolError {
     /// constraint (blob vs normal tx)
     #[error("address already reserved")]
     AddressAlreadyReserved,
+    /// When the conditions of a conditional transaction are not met
+    #[error("transaction conditions not met")]
+    ConditionNotMet,
+    /// When the known accounts of a conditional transaction are too expensive to check
+    #[error("conditional cost exceeded maximum allowed")]
+    ConditionCostExceeded,
     /// Other unspecified error
     #[error(transparent)]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -624,6 +633,13 @@ impl From<RpcPoolError> for jsonrpsee_types::error::ErrorObject<'static> {
     fn from(error: RpcPoolError) -> Self {
         match error {
             RpcPoolError::Invalid(err) => err.into(),
+            error @ RpcPoolError::ConditionNotMet => {
+                rpc_error_with_code(EthRpcErrorCode::TransactionRejected.code(), error.to_string())
+            }
+            // same code as op-geth, see also EIP-1474 "limit exceeded"
+            error @ RpcPoolError::ConditionCostExceeded => {
+                rpc_error_with_code(-32005, error.to_string())
+            }
             error => internal_rpc_err(error.to_string()),
         }
     }
@@ -633,6 +649,7 @@ impl From<PoolError> for RpcPoolError {
     fn from(err: PoolError) -> Self {
         match err.kind {
             PoolErrorKind::ReplacementUnderpriced => Self::ReplaceUnderpriced,
+            PoolErrorKind::ReplacementRateLimitExceeded(_) => Self::ReplacementRateLimited,
             PoolErrorKind::FeeCapBelowMinimumProtocolFeeCap(_) => Self::Underpriced,
             PoolErrorKind::SpammerExceededCapacity(_) | PoolErrorKind::DiscardedOnInsert => {
                 Self::TxPoolOverflow
@@ -663,6 +680,8 @@ impl From<InvalidPoolTransactionError> for RpcPoolError {
             InvalidPoolTransactionError::Overdraft => {
                 Self::Invalid(RpcInvalidTransactionError::InsufficientFunds)
             }
+            InvalidPoolTransactionError::ConditionNotMet => Self::ConditionNotMet,
+            InvalidPoolTransactionError::ConditionCostExceeded(_) => Self::ConditionCostExceeded,
         }
     }
 }
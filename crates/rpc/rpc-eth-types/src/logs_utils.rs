@@ -3,10 +3,11 @@
 //! Log parsing for building filter.
 
 use reth_chainspec::ChainInfo;
-use reth_primitives::{BlockNumHash, Receipt, TxHash};
+use reth_primitives::{Address, BlockNumHash, BlockNumber, Receipt, TxHash};
 use reth_provider::{BlockReader, ProviderError};
+use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogRule, MINIMUM_PRUNING_DISTANCE};
 use reth_rpc_server_types::result::rpc_error_with_code;
-use reth_rpc_types::{FilterId, FilteredParams, Log};
+use reth_rpc_types::{Filter, FilterId, FilteredParams, Log};
 
 use crate::EthApiError;
 
@@ -25,6 +26,11 @@ pub enum EthFilterError {
     /// Query result is too large.
     #[error("query exceeds max results {0}")]
     QueryExceedsMaxResults(usize),
+    /// Receipts needed to serve the filter have been pruned.
+    #[error(
+        "receipts at block {0} have been pruned and don't retain all logs matching the filter"
+    )]
+    ReceiptsPruned(BlockNumber),
     /// Error serving request in `eth_` namespace.
     #[error(transparent)]
     EthAPIError(#[from] EthApiError),
@@ -46,7 +52,8 @@ impl From<EthFilterError> for jsonrpsee_types::error::ErrorObject<'static> {
             EthFilterError::EthAPIError(err) => err.into(),
             err @ EthFilterError::InvalidBlockRangeParams |
             err @ EthFilterError::QueryExceedsMaxBlocks(_) |
-            err @ EthFilterError::QueryExceedsMaxResults(_) => {
+            err @ EthFilterError::QueryExceedsMaxResults(_) |
+            err @ EthFilterError::ReceiptsPruned(_) => {
                 rpc_error_with_code(jsonrpsee_types::error::INVALID_PARAMS_CODE, err.to_string())
             }
         }
@@ -180,6 +187,64 @@ pub fn log_matches_filter(
     true
 }
 
+/// Returns an error if receipts with logs matching the filter may have been pruned in the range
+/// starting at `from_block`, according to the [`PruneModes`] and the current `tip`.
+///
+/// Receipts pruned by [`PruneModes::receipts`] can't serve any filter. Receipts that are only
+/// retained by the receipts log rules can serve a filter if every log matching the filter is also
+/// matched by a rule retaining the receipts from `from_block`.
+pub fn ensure_receipts_not_pruned(
+    prune_modes: &PruneModes,
+    filter: &Filter,
+    from_block: BlockNumber,
+    tip: BlockNumber,
+) -> Result<(), EthFilterError> {
+    // Pruning is monotonic in the block number, so it's enough to check the lowest block
+    if prune_modes.receipts.map_or(false, |mode| mode.should_prune(from_block, tip)) {
+        return Err(EthFilterError::ReceiptsPruned(from_block))
+    }
+
+    if prune_modes.has_receipts_log_filter() &&
+        PruneMode::Distance(MINIMUM_PRUNING_DISTANCE).should_prune(from_block, tip)
+    {
+        let rules = prune_modes.all_receipts_log_rules();
+        let retaining_rules = rules
+            .0
+            .iter()
+            .filter(|rule| !rule.mode.should_prune(from_block, tip))
+            .collect::<Vec<_>>();
+        if !filter_covered_by_rules(filter, &retaining_rules) {
+            return Err(EthFilterError::ReceiptsPruned(from_block))
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns true if every log matching the filter is also matched by one of the rules.
+///
+/// Every address of the filter needs to be covered by a rule, and the topics of that rule need to
+/// be a subset of the filter topics.
+fn filter_covered_by_rules(filter: &Filter, rules: &[&ReceiptsLogRule]) -> bool {
+    let covered_by = |rule: &ReceiptsLogRule, address: Option<&Address>| {
+        rule.address.map_or(true, |rule_address| address == Some(&rule_address)) &&
+            rule.topics().iter().zip(&filter.topics).all(|(rule_topic, topics)| {
+                rule_topic.map_or(true, |rule_topic| {
+                    !topics.is_empty() && topics.iter().all(|topic| *topic == rule_topic)
+                })
+            })
+    };
+
+    if filter.address.is_empty() {
+        rules.iter().any(|rule| covered_by(rule, None))
+    } else {
+        filter
+            .address
+            .iter()
+            .all(|address| rules.iter().any(|rule| covered_by(rule, Some(address))))
+    }
+}
+
 /// Computes the block range based on the filter range and current block numbers
 pub fn get_filter_block_range(
     from_block: Option<u64>,
@@ -208,10 +273,69 @@ pub fn get_filter_block_range(
 
 #[cfg(test)]
 mod tests {
-    use reth_rpc_types::Filter;
+    use reth_primitives::B256;
+    use reth_prune_types::{ReceiptsLogPruneConfig, ReceiptsLogRules};
+    use std::collections::BTreeMap;
 
     use super::*;
 
+    #[test]
+    fn test_receipts_pruned_for_filter() {
+        let tip = 100_000;
+        let old_block = tip - MINIMUM_PRUNING_DISTANCE - 1;
+        let deposit_contract = Address::with_last_byte(1);
+        let transfer = B256::with_last_byte(1);
+        let recipient = B256::with_last_byte(2);
+
+        // No pruning
+        let filter = Filter::new();
+        assert!(ensure_receipts_not_pruned(&PruneModes::none(), &filter, 0, tip).is_ok());
+
+        // Receipts pruning
+        let prune_modes = PruneModes {
+            receipts: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+            ..Default::default()
+        };
+        assert!(ensure_receipts_not_pruned(&prune_modes, &filter, tip - 10, tip).is_ok());
+        assert!(matches!(
+            ensure_receipts_not_pruned(&prune_modes, &filter, old_block, tip),
+            Err(EthFilterError::ReceiptsPruned(block)) if block == old_block
+        ));
+
+        // Receipts log filter and rules
+        let prune_modes = PruneModes {
+            receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([(
+                deposit_contract,
+                PruneMode::Before(1_000),
+            )])),
+            receipts_log_rules: ReceiptsLogRules(vec![ReceiptsLogRule {
+                address: None,
+                topic0: Some(transfer),
+                topic1: None,
+                topic2: Some(recipient),
+                topic3: None,
+                mode: PruneMode::Before(50_000),
+            }]),
+            ..Default::default()
+        };
+        assert!(ensure_receipts_not_pruned(&prune_modes, &filter, tip - 10, tip).is_ok());
+        assert!(ensure_receipts_not_pruned(&prune_modes, &filter, old_block, tip).is_err());
+
+        let deposits = Filter::new().address(deposit_contract);
+        assert!(ensure_receipts_not_pruned(&prune_modes, &deposits, 1_000, tip).is_ok());
+        assert!(ensure_receipts_not_pruned(&prune_modes, &deposits, 999, tip).is_err());
+        let deposits_and_more = Filter::new().address(vec![deposit_contract, Address::ZERO]);
+        assert!(ensure_receipts_not_pruned(&prune_modes, &deposits_and_more, 1_000, tip).is_err());
+
+        let transfers = Filter::new().event_signature(transfer).topic2(recipient);
+        assert!(ensure_receipts_not_pruned(&prune_modes, &transfers, 50_000, tip).is_ok());
+        assert!(ensure_receipts_not_pruned(&prune_modes, &transfers, 1_000, tip).is_err());
+        let token_transfers = transfers.clone().address(Address::ZERO);
+        assert!(ensure_receipts_not_pruned(&prune_modes, &token_transfers, 50_000, tip).is_ok());
+        let all_transfers = Filter::new().event_signature(transfer);
+        assert!(ensure_receipts_not_pruned(&prune_modes, &all_transfers, 50_000, tip).is_err());
+    }
+
     #[test]
     fn test_log_range_from_and_to() {
         let from = 14000000u64;
@@ -1,6 +1,8 @@
 //! Commonly used code snippets
 
 use reth_primitives::{Bytes, PooledTransactionsElement, PooledTransactionsElementEcRecovered};
+use reth_rpc_types::{KnownAccount as RpcKnownAccount, TransactionConditional as RpcConditional};
+use reth_transaction_pool::{KnownAccount, TransactionConditional};
 
 use super::{EthApiError, EthResult};
 
@@ -17,3 +19,27 @@ pub fn recover_raw_transaction(data: Bytes) -> EthResult<PooledTransactionsEleme
 
     transaction.try_into_ecrecovered().or(Err(EthApiError::InvalidTransactionSignature))
 }
+
+/// Converts the conditions of an `eth_sendRawTransactionConditional` request into the
+/// [`TransactionConditional`] of the pool.
+pub fn into_pool_conditional(conditional: RpcConditional) -> TransactionConditional {
+    TransactionConditional {
+        known_accounts: conditional
+            .known_accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let account = match account {
+                    RpcKnownAccount::StorageRoot(root) => KnownAccount::StorageRoot(root),
+                    RpcKnownAccount::Slots(slots) => {
+                        KnownAccount::Slots(slots.into_iter().collect())
+                    }
+                };
+                (address, account)
+            })
+            .collect(),
+        block_number_min: conditional.block_number_min.map(|n| n.to()),
+        block_number_max: conditional.block_number_max.map(|n| n.to()),
+        timestamp_min: conditional.timestamp_min.map(|ts| ts.to()),
+        timestamp_max: conditional.timestamp_max.map(|ts| ts.to()),
+    }
+}
@@ -51,6 +51,10 @@ pub const DEFAULT_ETH_PROOF_WINDOW: u64 = 0;
 /// Maximum eth historical proof window. Equivalent to roughly one month of data.
 pub const MAX_ETH_PROOF_WINDOW: u64 = 216_000;
 
+/// The default number of blocks a private transaction stays in the pool if it was submitted
+/// without a `maxBlockNumber`.
+pub const DEFAULT_PRIVATE_TX_MAX_BLOCKS: u64 = 25;
+
 /// GPO specific constants
 pub mod gas_oracle {
     use alloy_primitives::U256;
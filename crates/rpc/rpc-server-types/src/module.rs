@@ -260,6 +260,11 @@ pub enum RethRpcModule {
     /// This is separate from [`RethRpcModule::Eth`] because it is a non standardized call that
     /// should be opt-in.
     EthCallBundle,
+    /// Non-standard `eth_` namespace calls for bundles and private transactions, see
+    /// `EthBundleApi`.
+    ///
+    /// `eth_callBundle` is only served by [`RethRpcModule::EthCallBundle`].
+    EthBundle,
 }
 
 // === impl RethRpcModule ===
@@ -309,6 +314,7 @@ impl FromStr for RethRpcModule {
             "reth" => Self::Reth,
             "ots" => Self::Ots,
             "eth-call-bundle" | "eth_callBundle" => Self::EthCallBundle,
+            "eth-bundle" => Self::EthBundle,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
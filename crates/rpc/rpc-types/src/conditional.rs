@@ -0,0 +1,71 @@
+//! Types for `eth_sendRawTransactionConditional`.
+
+use alloy_primitives::{Address, B256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The expected storage of an account, either its storage root or the values of individual slots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KnownAccount {
+    /// The expected storage root of the account.
+    StorageRoot(B256),
+    /// The expected values of individual storage slots of the account.
+    Slots(BTreeMap<B256, B256>),
+}
+
+/// Conditions under which a transaction submitted via `eth_sendRawTransactionConditional` may be
+/// included in a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionConditional {
+    /// The expected storage of accounts.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub known_accounts: BTreeMap<Address, KnownAccount>,
+    /// Minimum block number of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_min: Option<U64>,
+    /// Maximum block number of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_max: Option<U64>,
+    /// Minimum timestamp of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_min: Option<U64>,
+    /// Maximum timestamp of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_max: Option<U64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_conditional() {
+        let s = r#"{
+            "knownAccounts": {
+                "0x0000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002",
+                "0x0000000000000000000000000000000000000003": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000004": "0x0000000000000000000000000000000000000000000000000000000000000005"
+                }
+            },
+            "blockNumberMax": "0x10",
+            "timestampMin": "0x20"
+        }"#;
+        let conditional: TransactionConditional = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            conditional.known_accounts[&Address::with_last_byte(1)],
+            KnownAccount::StorageRoot(B256::with_last_byte(2))
+        );
+        assert_eq!(
+            conditional.known_accounts[&Address::with_last_byte(3)],
+            KnownAccount::Slots(BTreeMap::from([(
+                B256::with_last_byte(4),
+                B256::with_last_byte(5)
+            )]))
+        );
+        assert_eq!(conditional.block_number_min, None);
+        assert_eq!(conditional.block_number_max, Some(U64::from(0x10)));
+        assert_eq!(conditional.timestamp_min, Some(U64::from(0x20)));
+    }
+}
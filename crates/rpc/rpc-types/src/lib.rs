@@ -9,10 +9,13 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
+mod conditional;
 #[allow(hidden_glob_reexports)]
 mod eth;
 mod peer;
+mod pool;
 mod rpc;
+mod witness;
 
 // re-export for convenience
 pub use alloy_rpc_types::serde_helpers;
@@ -53,5 +56,8 @@ pub use eth::{
     transaction::{self, TransactionRequest, TypedTransactionRequest},
 };
 
+pub use conditional::*;
 pub use peer::*;
+pub use pool::*;
 pub use rpc::*;
+pub use witness::*;
@@ -0,0 +1,170 @@
+//! Types for the reth specific extensions of the `txpool` namespace.
+
+use crate::{PeerId, Transaction};
+use alloy_primitives::{Address, B256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Transactions of a sub-pool, grouped by sender and nonce.
+pub type TxpoolSubPoolTransactions = BTreeMap<Address, BTreeMap<String, TxpoolSubPoolTransaction>>;
+
+/// The transactions of all sub-pools of the transaction pool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolSubPoolContent {
+    /// Transactions that are ready for inclusion in the next block.
+    pub pending: TxpoolSubPoolTransactions,
+    /// Transactions that don't meet the base fee of the next block.
+    pub base_fee: TxpoolSubPoolTransactions,
+    /// Blob transactions that are not pending.
+    pub blob: TxpoolSubPoolTransactions,
+    /// Transactions that are not executable, e.g. because of a nonce gap or insufficient funds.
+    pub queued: TxpoolSubPoolTransactions,
+}
+
+/// A transaction in a sub-pool together with its state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolSubPoolTransaction {
+    /// The transaction.
+    pub transaction: Transaction,
+    /// The state flags of the transaction that determine its sub-pool.
+    pub state: TxpoolTransactionState,
+}
+
+/// The state flags of a pooled transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolTransactionState {
+    /// Whether all prior transactions of the sender are pending.
+    pub no_parked_ancestors: bool,
+    /// Whether there is no nonce gap before the transaction.
+    pub no_nonce_gaps: bool,
+    /// Whether the sender's balance covers the cost of this and all prior transactions.
+    pub enough_balance: bool,
+    /// Whether the gas limit of the transaction is within the block gas limit.
+    pub not_too_much_gas: bool,
+    /// Whether the fee cap of the transaction covers the base fee of the next block.
+    pub enough_fee_cap_block: bool,
+    /// Whether the blob fee cap of the transaction covers the blob fee of the next block.
+    pub enough_blob_fee_cap_block: bool,
+    /// Whether this is a blob transaction.
+    pub blob_transaction: bool,
+}
+
+/// A transaction that was recently removed from the transaction pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolRemovedTransaction {
+    /// The hash of the transaction.
+    pub hash: B256,
+    /// The sender of the transaction.
+    pub sender: Address,
+    /// The nonce of the transaction.
+    pub nonce: U64,
+    /// Why the transaction was removed.
+    pub reason: TxpoolRemovalReason,
+    /// Unix timestamp in seconds of the removal.
+    pub timestamp: U64,
+}
+
+/// Why a transaction was removed from the transaction pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TxpoolRemovalReason {
+    /// Replaced by another transaction with the same sender and nonce.
+    Replaced {
+        /// The hash of the replacement.
+        #[serde(rename = "replacedBy")]
+        replaced_by: B256,
+    },
+    /// Evicted to enforce the size limits of the pool.
+    Evicted,
+    /// Included in a block.
+    Mined {
+        /// The hash of the block.
+        #[serde(rename = "blockHash")]
+        block_hash: B256,
+    },
+    /// The nonce fell below the on-chain nonce of the sender.
+    Outdated,
+    /// The conditions of a conditional transaction no longer hold.
+    ConditionNotMet,
+    /// Rejected as invalid.
+    Invalid {
+        /// The validation error.
+        error: String,
+    },
+    /// Removed on request.
+    Removed,
+}
+
+/// An event of a transaction in the transaction pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TxpoolEvent {
+    /// The transaction was added to the pending sub-pool.
+    Pending {
+        /// The hash of the transaction.
+        hash: B256,
+    },
+    /// The transaction was added to a parked sub-pool.
+    Queued {
+        /// The hash of the transaction.
+        hash: B256,
+    },
+    /// The transaction was included in a block.
+    Mined {
+        /// The hash of the transaction.
+        hash: B256,
+        /// The hash of the block.
+        #[serde(rename = "blockHash")]
+        block_hash: B256,
+    },
+    /// The transaction was replaced by another transaction with the same sender and nonce.
+    Replaced {
+        /// The hash of the transaction.
+        hash: B256,
+        /// The hash of the replacement.
+        #[serde(rename = "replacedBy")]
+        replaced_by: B256,
+    },
+    /// The transaction was discarded.
+    Discarded {
+        /// The hash of the transaction.
+        hash: B256,
+    },
+    /// The transaction became invalid.
+    Invalid {
+        /// The hash of the transaction.
+        hash: B256,
+    },
+    /// A transaction was propagated to peers.
+    Propagated {
+        /// The peers the transaction was sent to.
+        peers: Vec<PeerId>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_removed_transaction() {
+        let removed = TxpoolRemovedTransaction {
+            hash: B256::with_last_byte(1),
+            sender: Address::with_last_byte(2),
+            nonce: U64::from(3),
+            reason: TxpoolRemovalReason::Replaced { replaced_by: B256::with_last_byte(4) },
+            timestamp: U64::from(5),
+        };
+        let json = serde_json::to_value(&removed).unwrap();
+        assert_eq!(json["nonce"], "0x3");
+        assert_eq!(json["reason"]["type"], "replaced");
+        assert_eq!(
+            json["reason"]["replacedBy"],
+            serde_json::to_value(B256::with_last_byte(4)).unwrap()
+        );
+        assert_eq!(serde_json::from_value::<TxpoolRemovedTransaction>(json).unwrap(), removed);
+    }
+}
@@ -0,0 +1,19 @@
+//! Types for `debug_executionWitness`.
+
+use alloy_primitives::{Bytes, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Everything that is needed to re-execute a block and compute its state root without access to
+/// the state, keyed by the keccak256 hash of the values.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionWitness {
+    /// The trie nodes of the state before the block that are accessed during execution and state
+    /// root computation.
+    pub state: HashMap<B256, Bytes>,
+    /// The bytecodes of the contracts that are accessed during execution.
+    pub codes: HashMap<B256, Bytes>,
+    /// The preimages of the hashed addresses and storage slots that are accessed during
+    /// execution.
+    pub keys: HashMap<B256, Bytes>,
+}
@@ -1,21 +1,34 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_chainspec::EthereumHardforks;
-use reth_evm::ConfigureEvmEnv;
+use reth_evm::{
+    system_calls::{
+        post_block_consolidation_requests_contract_call,
+        post_block_withdrawal_requests_contract_call, pre_block_beacon_root_contract_call,
+    },
+    ConfigureEvmEnv,
+};
 use reth_primitives::{
-    Address, Block, BlockId, BlockNumberOrTag, Bytes, TransactionSignedEcRecovered, B256, U256,
+    keccak256, Address, Block, BlockId, BlockNumberOrTag, Bytes, TransactionSignedEcRecovered,
+    B256, U256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProviderFactory,
-    TransactionVariant,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProofProvider,
+    StateProviderFactory, TransactionVariant,
+};
+use reth_revm::{
+    database::StateProviderDatabase, state_change::post_block_balance_increments,
+    witness::RecordingDatabase,
 };
-use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_eth_api::helpers::{Call, EthApiSpec, EthTransactions, TraceExt};
-use reth_rpc_eth_types::{EthApiError, EthResult, StateCacheDb};
+use reth_rpc_eth_types::{
+    cache::db::StateProviderTraitObjWrapper, pending_block::pre_block_blockhashes_update,
+    EthApiError, EthResult, StateCacheDb,
+};
 use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
 use reth_rpc_types::{
     state::EvmOverrides,
@@ -23,11 +36,12 @@ use reth_rpc_types::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
         GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
     },
-    BlockError, Bundle, RichBlock, StateContext, TransactionRequest,
+    BlockError, Bundle, ExecutionWitness, RichBlock, StateContext, TransactionRequest,
 };
 use reth_tasks::pool::BlockingTaskGuard;
+use reth_trie::{HashedPostState, HashedStorage};
 use revm::{
-    db::CacheDB,
+    db::{states::bundle_state::BundleRetention, BundleState, CacheDB, State},
     primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
 };
 use revm_inspectors::tracing::{
@@ -206,6 +220,141 @@ where
         .await
     }
 
+    /// Re-executes a block and returns the execution witness: the trie nodes of the parent state,
+    /// the bytecodes and the preimages of the hashed keys that are required to execute the block
+    /// statelessly and compute its state root.
+    ///
+    /// Note, the parent of this block must be present, or it will fail.
+    pub async fn debug_execution_witness(
+        &self,
+        block_id: BlockNumberOrTag,
+    ) -> EthResult<ExecutionWitness> {
+        let block_id = BlockId::from(block_id);
+        let block_hash = self
+            .inner
+            .provider
+            .block_hash_for_id(block_id)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+
+        let ((cfg, block_env, _), block) = futures::try_join!(
+            self.inner.eth_api.evm_env_at(block_hash.into()),
+            self.inner.eth_api.block_with_senders(block_id),
+        )?;
+        let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let total_difficulty = self
+            .inner
+            .provider
+            .header_td_by_number(block.number)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let chain_spec = self.inner.provider.chain_spec();
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash.into(), move |state_provider| {
+                let (block, senders) = block.unseal().into_components();
+                let mut db = State::builder()
+                    .with_database(RecordingDatabase::new(StateProviderDatabase::new(
+                        StateProviderTraitObjWrapper(state_provider.0),
+                    )))
+                    .with_bundle_update()
+                    .build();
+
+                pre_block_beacon_root_contract_call(
+                    &mut db,
+                    Call::evm_config(this.eth_api()),
+                    &chain_spec,
+                    &cfg,
+                    &block_env,
+                    block.number,
+                    block.timestamp,
+                    block.parent_beacon_block_root,
+                )
+                .map_err(|err| EthApiError::Internal(err.into()))?;
+                pre_block_blockhashes_update(
+                    &mut db,
+                    &chain_spec,
+                    &block_env,
+                    block.number,
+                    block.parent_hash,
+                )?;
+
+                for (tx, sender) in block.body.iter().zip(senders) {
+                    let tx =
+                        TransactionSignedEcRecovered::from_signed_transaction(tx.clone(), sender);
+                    let env = EnvWithHandlerCfg {
+                        env: Env::boxed(
+                            cfg.cfg_env.clone(),
+                            block_env.clone(),
+                            Call::evm_config(this.eth_api()).tx_env(&tx),
+                        ),
+                        handler_cfg: cfg.handler_cfg,
+                    };
+                    let (res, _) = this.eth_api().transact(&mut db, env)?;
+                    db.commit(res.state);
+                }
+
+                if chain_spec.is_prague_active_at_timestamp(block.timestamp) {
+                    post_block_withdrawal_requests_contract_call(
+                        Call::evm_config(this.eth_api()),
+                        &mut db,
+                        &cfg,
+                        &block_env,
+                    )
+                    .map_err(|err| EthApiError::Internal(err.into()))?;
+                    post_block_consolidation_requests_contract_call(
+                        Call::evm_config(this.eth_api()),
+                        &mut db,
+                        &cfg,
+                        &block_env,
+                    )
+                    .map_err(|err| EthApiError::Internal(err.into()))?;
+                }
+
+                let balance_increments =
+                    post_block_balance_increments(&chain_spec, &block, total_difficulty);
+                db.increment_balances(balance_increments)?;
+                db.merge_transitions(BundleRetention::PlainState);
+
+                // The target state contains the changed accounts and slots with their new values,
+                // and all accessed accounts and slots with their unchanged values.
+                let mut hashed_state = HashedPostState::from_bundle_state(&db.bundle_state.state);
+                let mut keys = HashMap::default();
+                for (address, info) in &db.database.accounts {
+                    let hashed_address = keccak256(address);
+                    keys.insert(hashed_address, address.to_vec().into());
+                    hashed_state
+                        .accounts
+                        .entry(hashed_address)
+                        .or_insert_with(|| info.clone().map(Into::into));
+                }
+                for (address, slots) in &db.database.storage {
+                    let hashed_address = keccak256(address);
+                    keys.insert(hashed_address, address.to_vec().into());
+                    let storage = hashed_state
+                        .storages
+                        .entry(hashed_address)
+                        .or_insert_with(|| HashedStorage::new(false));
+                    for (slot, value) in slots {
+                        let slot = B256::from(*slot);
+                        let hashed_slot = keccak256(slot);
+                        keys.insert(hashed_slot, slot.to_vec().into());
+                        storage.storage.entry(hashed_slot).or_insert(*value);
+                    }
+                }
+
+                let state = state_provider.witness(&BundleState::default(), &hashed_state)?;
+                let codes = db
+                    .database
+                    .codes
+                    .iter()
+                    .map(|(hash, code)| (*hash, code.original_bytes()))
+                    .collect();
+
+                Ok(ExecutionWitness { state, codes, keys })
+            })
+            .await
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -793,6 +942,14 @@ where
         Ok(Self::debug_trace_call_many(self, bundles, state_context, opts).await?)
     }
 
+    async fn debug_execution_witness(
+        &self,
+        block: BlockNumberOrTag,
+    ) -> RpcResult<ExecutionWitness> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_execution_witness(self, block).await?)
+    }
+
     async fn debug_backtrace_at(&self, _location: &str) -> RpcResult<()> {
         Ok(())
     }
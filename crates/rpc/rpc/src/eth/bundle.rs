@@ -7,21 +7,27 @@ use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
 use reth_primitives::{
     keccak256,
     revm_primitives::db::{DatabaseCommit, DatabaseRef},
-    PooledTransactionsElement, U256,
+    Bytes, PooledTransactionsElement, B256, U256,
 };
 use reth_revm::database::StateProviderDatabase;
-use reth_rpc_types::mev::{EthCallBundle, EthCallBundleResponse, EthCallBundleTransactionResult};
+use reth_rpc_types::mev::{
+    CancelBundleRequest, CancelPrivateTransactionRequest, EthBundleHash, EthCallBundle,
+    EthCallBundleResponse, EthCallBundleTransactionResult, EthSendBundle,
+    PrivateTransactionRequest,
+};
 use reth_tasks::pool::BlockingTaskGuard;
+use reth_transaction_pool::{BundlePool, MevBundle, TransactionPool};
 use revm::{
     db::CacheDB,
     primitives::{ResultAndState, TxEnv},
 };
 use revm_primitives::{EnvKzgSettings, EnvWithHandlerCfg, SpecId, MAX_BLOB_GAS_PER_BLOCK};
+use tracing::trace;
 
 use reth_provider::{ChainSpecProvider, HeaderProvider};
 use reth_rpc_eth_api::{
     helpers::{Call, EthTransactions, LoadPendingBlock},
-    EthCallBundleApiServer,
+    EthBundleApiServer, EthCallBundleApiServer,
 };
 use reth_rpc_eth_types::{
     utils::recover_raw_transaction, EthApiError, EthResult, RpcInvalidTransactionError,
@@ -36,7 +42,28 @@ pub struct EthBundle<Eth> {
 impl<Eth> EthBundle<Eth> {
     /// Create a new `EthBundle` instance.
     pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        Self { inner: Arc::new(EthBundleInner { eth_api, blocking_task_guard }) }
+        Self { inner: Arc::new(EthBundleInner { eth_api, blocking_task_guard, bundle_pool: None }) }
+    }
+
+    /// Create a new `EthBundle` instance that accepts bundles via `eth_sendBundle` into the given
+    /// [`BundlePool`].
+    pub fn with_bundle_pool(
+        eth_api: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        bundle_pool: BundlePool,
+    ) -> Self {
+        Self {
+            inner: Arc::new(EthBundleInner {
+                eth_api,
+                blocking_task_guard,
+                bundle_pool: Some(bundle_pool),
+            }),
+        }
+    }
+
+    /// Returns the [`BundlePool`] bundles are submitted to, if any.
+    pub fn bundle_pool(&self) -> Option<&BundlePool> {
+        self.inner.bundle_pool.as_ref()
     }
 }
 
@@ -248,6 +275,116 @@ where
     }
 }
 
+impl<Eth> EthBundle<Eth>
+where
+    Eth: EthTransactions + 'static,
+{
+    /// Adds the bundle to the [`BundlePool`], from which it is included by a bundle-aware payload
+    /// builder when it builds the targeted block.
+    ///
+    /// Bundles are not simulated on submission, invalid bundles are dropped by the payload builder.
+    pub fn send_bundle(&self, bundle: EthSendBundle) -> EthResult<EthBundleHash> {
+        let Some(bundle_pool) = self.bundle_pool() else {
+            return Err(EthApiError::Unsupported("eth_sendBundle is not supported"))
+        };
+
+        let EthSendBundle {
+            txs,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+            replacement_uuid,
+        } = bundle;
+        if txs.is_empty() {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::EmptyBundleTransactions.to_string(),
+            ))
+        }
+        if block_number == 0 {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleMissingBlockNumber.to_string(),
+            ))
+        }
+        let tip = self.inner.eth_api.pool().block_info().last_seen_block_number;
+        if block_number <= tip {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleBlockNumberTooLow(block_number, tip).to_string(),
+            ))
+        }
+        let max_block_number = tip.saturating_add(bundle_pool.config().max_blocks_ahead);
+        if block_number > max_block_number {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleBlockNumberTooHigh(block_number, max_block_number)
+                    .to_string(),
+            ))
+        }
+
+        let transactions = txs
+            .into_iter()
+            .map(recover_raw_transaction)
+            .map(|tx| tx.map(|tx| tx.into_ecrecovered_transaction()))
+            .collect::<EthResult<Vec<_>>>()?;
+
+        let bundle_hash = bundle_pool
+            .add_bundle(MevBundle {
+                transactions,
+                block_number,
+                min_timestamp,
+                max_timestamp,
+                reverting_tx_hashes: reverting_tx_hashes.into_iter().collect(),
+                replacement_uuid,
+            })
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        Ok(EthBundleHash { bundle_hash })
+    }
+
+    /// Removes the bundle with the given hash or replacement UUID from the [`BundlePool`].
+    ///
+    /// Returns an error if the pool has no such bundle, e.g. because it was already included or
+    /// dropped.
+    pub fn cancel_bundle(&self, bundle_id: &str) -> EthResult<()> {
+        let Some(bundle_pool) = self.bundle_pool() else {
+            return Err(EthApiError::Unsupported("eth_cancelBundle is not supported"))
+        };
+        let cancelled = match bundle_id.parse::<B256>() {
+            Ok(bundle_hash) => bundle_pool.remove_bundle(&bundle_hash),
+            Err(_) => bundle_pool.cancel_bundle(bundle_id),
+        };
+        if !cancelled {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::UnknownBundle(bundle_id.to_string()).to_string(),
+            ))
+        }
+        Ok(())
+    }
+
+    /// Removes the private transaction with the given hash from the pool, together with all
+    /// private transactions of the same sender that depend on it, i.e. have a higher nonce.
+    ///
+    /// Returns `false` if the pool has no
+    /// [`TransactionOrigin::Private`](reth_transaction_pool::TransactionOrigin::Private)
+    /// transaction with that hash.
+    ///
+    /// Note: unlike Flashbots, the request is not authenticated, so this should only be exposed to
+    /// the submitters of private transactions.
+    pub fn cancel_private_transaction(&self, tx_hash: B256) -> bool {
+        let pool = self.inner.eth_api.pool();
+        let Some(tx) = pool.get(&tx_hash).filter(|tx| tx.origin.is_private()) else { return false };
+
+        // without the cancelled transaction, its descendants could never be mined
+        let mut hashes = vec![tx_hash];
+        hashes.extend(
+            pool.get_transactions_by_sender(tx.sender())
+                .into_iter()
+                .filter(|dependent| dependent.origin.is_private() && dependent.nonce() > tx.nonce())
+                .map(|dependent| *dependent.hash()),
+        );
+        !pool.remove_transactions(hashes).is_empty()
+    }
+}
+
 #[async_trait::async_trait]
 impl<Eth> EthCallBundleApiServer for EthBundle<Eth>
 where
@@ -258,6 +395,57 @@ where
     }
 }
 
+#[async_trait::async_trait]
+impl<Eth> EthBundleApiServer for EthBundle<Eth>
+where
+    Eth: EthTransactions + LoadPendingBlock + Call + 'static,
+{
+    /// Handler for: `eth_sendBundle`
+    async fn send_bundle(&self, bundle: EthSendBundle) -> RpcResult<EthBundleHash> {
+        trace!(target: "rpc::eth", ?bundle, "Serving eth_sendBundle");
+        Ok(Self::send_bundle(self, bundle)?)
+    }
+
+    async fn call_bundle(&self, request: EthCallBundle) -> RpcResult<EthCallBundleResponse> {
+        Ok(Self::call_bundle(self, request).await?)
+    }
+
+    /// Handler for: `eth_cancelBundle`
+    async fn cancel_bundle(&self, request: CancelBundleRequest) -> RpcResult<()> {
+        trace!(target: "rpc::eth", ?request, "Serving eth_cancelBundle");
+        Ok(Self::cancel_bundle(self, &request.bundle_hash)?)
+    }
+
+    /// Handler for: `eth_sendPrivateTransaction`
+    async fn send_private_transaction(
+        &self,
+        request: PrivateTransactionRequest,
+    ) -> RpcResult<B256> {
+        trace!(target: "rpc::eth", ?request, "Serving eth_sendPrivateTransaction");
+        Ok(EthTransactions::send_private_raw_transaction(
+            &self.inner.eth_api,
+            request.tx,
+            request.max_block_number,
+        )
+        .await?)
+    }
+
+    /// Handler for: `eth_sendPrivateRawTransaction`
+    async fn send_private_raw_transaction(&self, bytes: Bytes) -> RpcResult<B256> {
+        trace!(target: "rpc::eth", ?bytes, "Serving eth_sendPrivateRawTransaction");
+        Ok(EthTransactions::send_private_raw_transaction(&self.inner.eth_api, bytes, None).await?)
+    }
+
+    /// Handler for: `eth_cancelPrivateTransaction`
+    async fn cancel_private_transaction(
+        &self,
+        request: CancelPrivateTransactionRequest,
+    ) -> RpcResult<bool> {
+        trace!(target: "rpc::eth", ?request, "Serving eth_cancelPrivateTransaction");
+        Ok(Self::cancel_private_transaction(self, request.tx_hash))
+    }
+}
+
 /// Container type for  `EthBundle` internals
 #[derive(Debug)]
 struct EthBundleInner<Eth> {
@@ -266,6 +454,8 @@ struct EthBundleInner<Eth> {
     // restrict the number of concurrent tracing calls.
     #[allow(dead_code)]
     blocking_task_guard: BlockingTaskGuard,
+    /// The pool bundles sent via `eth_sendBundle` are added to.
+    bundle_pool: Option<BundlePool>,
 }
 
 impl<Eth> std::fmt::Debug for EthBundle<Eth> {
@@ -289,6 +479,15 @@ pub enum EthBundleError {
     /// Thrown if the bundle does not contain a block number, or block number is 0.
     #[error("bundle missing blockNumber")]
     BundleMissingBlockNumber,
+    /// Thrown if the bundle targets a block that was already mined.
+    #[error("bundle blockNumber {0} is not after the current block {1}")]
+    BundleBlockNumberTooLow(u64, u64),
+    /// Thrown if the bundle targets a block too far ahead of the current block.
+    #[error("bundle blockNumber {0} exceeds the maximum of {1}")]
+    BundleBlockNumberTooHigh(u64, u64),
+    /// Thrown if the bundle to cancel is not in the bundle pool.
+    #[error("unknown bundle {0}")]
+    UnknownBundle(String),
     /// Thrown when the blob gas usage of the blob transactions in a bundle exceed
     /// [`MAX_BLOB_GAS_PER_BLOCK`].
     #[error("blob gas usage exceeds the limit of {MAX_BLOB_GAS_PER_BLOCK} gas per block.")]
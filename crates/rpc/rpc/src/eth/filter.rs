@@ -14,6 +14,7 @@ use jsonrpsee::{core::RpcResult, server::IdProvider};
 use reth_chainspec::ChainInfo;
 use reth_primitives::{IntoRecoveredTransaction, TxHash};
 use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, ProviderError};
+use reth_prune_types::PruneModes;
 use reth_rpc_eth_api::EthFilterApiServer;
 use reth_rpc_eth_types::{
     logs_utils::{self, append_matching_block_logs},
@@ -61,8 +62,12 @@ where
         config: EthFilterConfig,
         task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
-        let EthFilterConfig { max_blocks_per_filter, max_logs_per_response, stale_filter_ttl } =
-            config;
+        let EthFilterConfig {
+            max_blocks_per_filter,
+            max_logs_per_response,
+            stale_filter_ttl,
+            prune_modes,
+        } = config;
         let inner = EthFilterInner {
             provider,
             active_filters: Default::default(),
@@ -75,6 +80,7 @@ where
             // if not set, use the max value, which is effectively no limit
             max_blocks_per_filter: max_blocks_per_filter.unwrap_or(u64::MAX),
             max_logs_per_response: max_logs_per_response.unwrap_or(usize::MAX),
+            prune_modes,
         };
 
         let eth_filter = Self { inner: Arc::new(inner) };
@@ -336,6 +342,8 @@ struct EthFilterInner<Provider, Pool> {
     task_spawner: Box<dyn TaskSpawner>,
     /// Duration since the last filter poll, after which the filter is considered stale
     stale_filter_ttl: Duration,
+    /// Prune modes of the node, used to detect filters over pruned receipts
+    prune_modes: PruneModes,
 }
 
 impl<Provider, Pool> EthFilterInner<Provider, Pool>
@@ -354,6 +362,13 @@ where
                     .header_by_hash_or_number(block_hash.into())?
                     .ok_or(ProviderError::HeaderNotFound(block_hash.into()))?;
 
+                logs_utils::ensure_receipts_not_pruned(
+                    &self.prune_modes,
+                    &filter,
+                    block.number,
+                    self.provider.best_block_number()?,
+                )?;
+
                 // we also need to ensure that the receipts are available and return an error if
                 // not, in case the block hash been reorged
                 let receipts = self
@@ -437,6 +452,8 @@ where
             return Err(EthFilterError::QueryExceedsMaxBlocks(self.max_blocks_per_filter))
         }
 
+        logs_utils::ensure_receipts_not_pruned(&self.prune_modes, filter, from_block, best_number)?;
+
         let mut all_logs = Vec::new();
         let filter_params = FilteredParams::new(Some(filter.clone()));
 
@@ -531,6 +548,11 @@ pub struct EthFilterConfig {
     /// A filter is considered stale if it has not been polled for longer than this duration and
     /// will be removed.
     pub stale_filter_ttl: Duration,
+    /// Prune modes of the node.
+    ///
+    /// Used to return an error for filters over ranges whose matching receipts have been pruned,
+    /// instead of silently returning incomplete logs.
+    pub prune_modes: PruneModes,
 }
 
 impl EthFilterConfig {
@@ -552,6 +574,12 @@ impl EthFilterConfig {
         self.stale_filter_ttl = duration;
         self
     }
+
+    /// Sets the prune modes of the node, to detect filters over pruned receipts.
+    pub fn prune_modes(mut self, prune_modes: PruneModes) -> Self {
+        self.prune_modes = prune_modes;
+        self
+    }
 }
 
 impl Default for EthFilterConfig {
@@ -561,6 +589,7 @@ impl Default for EthFilterConfig {
             max_logs_per_response: None,
             // 5min
             stale_filter_ttl: Duration::from_secs(5 * 60),
+            prune_modes: PruneModes::none(),
         }
     }
 }
@@ -49,7 +49,7 @@ pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthBundle, EthFilter, EthPubSub};
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
-pub use reth::RethApi;
+pub use reth::{PrunerProgressTracker, RethApi};
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
 pub use txpool::TxPoolApi;
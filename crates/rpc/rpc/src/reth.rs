@@ -2,9 +2,13 @@ use std::{collections::HashMap, future::Future, sync::Arc};
 
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use reth_errors::RethResult;
+use parking_lot::RwLock;
+use reth_errors::{RethError, RethResult};
 use reth_primitives::{Address, BlockId, U256};
-use reth_provider::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_provider::{
+    BlockReaderIdExt, ChangeSetReader, PruneCheckpointReader, StateProviderFactory,
+};
+use reth_prune_types::{PruneModes, PruneSegment, PruneSegmentStatus, PruneStatus, PrunerProgress};
 use reth_rpc_api::RethApiServer;
 use reth_rpc_eth_types::{EthApiError, EthResult};
 use reth_tasks::TaskSpawner;
@@ -25,16 +29,41 @@ impl<Provider> RethApi<Provider> {
         &self.inner.provider
     }
 
+    /// The prune modes the node is configured with.
+    pub fn prune_modes(&self) -> &PruneModes {
+        &self.inner.prune_modes
+    }
+
     /// Create a new instance of the [`RethApi`]
+    ///
+    /// The node is reported to have no prune modes configured and the pruner to have never run,
+    /// see [`RethApi::with_pruning`].
     pub fn new(provider: Provider, task_spawner: Box<dyn TaskSpawner>) -> Self {
-        let inner = Arc::new(RethApiInner { provider, task_spawner });
+        Self::with_pruning(
+            provider,
+            task_spawner,
+            PruneModes::none(),
+            PrunerProgressTracker::default(),
+        )
+    }
+
+    /// Create a new instance of the [`RethApi`] that reports the pruning status of the node with
+    /// the configured prune modes and the pruner progress.
+    pub fn with_pruning(
+        provider: Provider,
+        task_spawner: Box<dyn TaskSpawner>,
+        prune_modes: PruneModes,
+        pruner_progress: PrunerProgressTracker,
+    ) -> Self {
+        let inner = Arc::new(RethApiInner { provider, task_spawner, prune_modes, pruner_progress });
         Self { inner }
     }
 }
 
 impl<Provider> RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + PruneCheckpointReader + StateProviderFactory + 'static,
 {
     /// Executes the future on a new blocking task.
     async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
@@ -82,12 +111,36 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the prune checkpoints and targets of the segments, and the progress of the latest
+    /// pruner run.
+    pub async fn prune_status(&self) -> EthResult<PruneStatus> {
+        self.on_blocking_task(|this| async move { this.try_prune_status() }).await
+    }
+
+    fn try_prune_status(&self) -> EthResult<PruneStatus> {
+        let tip_block_number = self.provider().best_block_number()?;
+
+        let mut segments = Vec::new();
+        for segment in PruneSegment::ALL {
+            let checkpoint = self.provider().get_prune_checkpoint(segment)?;
+            let status =
+                PruneSegmentStatus::new(segment, self.prune_modes(), checkpoint, tip_block_number)
+                    .map_err(RethError::other)?;
+            if status.is_active() {
+                segments.push(status);
+            }
+        }
+
+        Ok(PruneStatus { tip_block_number, segments, pruner: self.inner.pruner_progress.latest() })
+    }
 }
 
 #[async_trait]
 impl<Provider> RethApiServer for RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + PruneCheckpointReader + StateProviderFactory + 'static,
 {
     /// Handler for `reth_getBalanceChangesInBlock`
     async fn reth_get_balance_changes_in_block(
@@ -96,6 +149,11 @@ where
     ) -> RpcResult<HashMap<Address, U256>> {
         Ok(Self::balance_changes_in_block(self, block_id).await?)
     }
+
+    /// Handler for `reth_pruneStatus`
+    async fn reth_prune_status(&self) -> RpcResult<PruneStatus> {
+        Ok(Self::prune_status(self).await?)
+    }
 }
 
 impl<Provider> std::fmt::Debug for RethApi<Provider> {
@@ -115,4 +173,24 @@ struct RethApiInner<Provider> {
     provider: Provider,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
+    /// The prune modes the node is configured with.
+    prune_modes: PruneModes,
+    /// Progress of the latest pruner run.
+    pruner_progress: PrunerProgressTracker,
+}
+
+/// Tracks the latest [`PrunerProgress`] reported by the pruner for [`RethApi`].
+#[derive(Debug, Clone, Default)]
+pub struct PrunerProgressTracker(Arc<RwLock<Option<PrunerProgress>>>);
+
+impl PrunerProgressTracker {
+    /// Records the progress of the pruner.
+    pub fn update(&self, progress: PrunerProgress) {
+        *self.0.write() = Some(progress);
+    }
+
+    /// Returns the latest progress of the pruner, if it has run.
+    pub fn latest(&self) -> Option<PrunerProgress> {
+        self.0.read().clone()
+    }
 }
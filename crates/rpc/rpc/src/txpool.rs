@@ -1,12 +1,21 @@
+use crate::eth::pubsub::pipe_from_stream;
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult as Result;
-use reth_primitives::Address;
+use futures::StreamExt;
+use jsonrpsee::{
+    core::{RpcResult as Result, SubscriptionResult},
+    PendingSubscriptionSink,
+};
+use reth_primitives::{Address, U64};
 use reth_rpc_api::TxPoolApiServer;
 use reth_rpc_types::{
     txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
-    Transaction,
+    Transaction, TxpoolEvent, TxpoolRemovalReason, TxpoolRemovedTransaction, TxpoolSubPoolContent,
+    TxpoolSubPoolTransaction, TxpoolSubPoolTransactions, TxpoolTransactionState,
+};
+use reth_transaction_pool::{
+    AllPoolTransactions, AllSubPoolTransactions, FullTransactionEvent, PoolTransaction,
+    RemovalReason, RemovedTransaction, SubPoolTransaction, TransactionPool, TxState,
 };
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
 use std::collections::BTreeMap;
 use tracing::trace;
 
@@ -30,6 +39,15 @@ impl<Pool> TxPoolApi<Pool>
 where
     Pool: TransactionPool + 'static,
 {
+    /// Returns all transactions of the pool, except
+    /// [`TransactionOrigin::Private`](reth_transaction_pool::TransactionOrigin::Private) ones.
+    fn all_transactions(&self) -> AllPoolTransactions<Pool::Transaction> {
+        let AllPoolTransactions { mut pending, mut queued } = self.pool.all_transactions();
+        pending.retain(|tx| !tx.origin.is_private());
+        queued.retain(|tx| !tx.origin.is_private());
+        AllPoolTransactions { pending, queued }
+    }
+
     fn content(&self) -> TxpoolContent {
         #[inline]
         fn insert<T: PoolTransaction>(
@@ -42,7 +60,7 @@ where
             );
         }
 
-        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        let AllPoolTransactions { pending, queued } = self.all_transactions();
 
         let mut content = TxpoolContent::default();
         for pending in pending {
@@ -54,6 +72,96 @@ where
 
         content
     }
+
+    /// Returns the transactions of all sub-pools, optionally only those of the given sender.
+    fn sub_pool_content(&self, from: Option<Address>) -> TxpoolSubPoolContent {
+        #[inline]
+        fn insert<T: PoolTransaction>(
+            transactions: Vec<SubPoolTransaction<T>>,
+            from: Option<Address>,
+            content: &mut TxpoolSubPoolTransactions,
+        ) {
+            for SubPoolTransaction { transaction, state } in transactions {
+                let tx = &transaction.transaction;
+                if transaction.origin.is_private() || from.is_some_and(|from| from != tx.sender()) {
+                    continue
+                }
+                content.entry(tx.sender()).or_default().insert(
+                    tx.nonce().to_string(),
+                    TxpoolSubPoolTransaction {
+                        transaction: reth_rpc_types_compat::transaction::from_recovered(
+                            tx.to_recovered_transaction(),
+                        ),
+                        state: transaction_state(state),
+                    },
+                );
+            }
+        }
+
+        let AllSubPoolTransactions { pending, basefee, blob, queued } =
+            self.pool.all_sub_pool_transactions();
+
+        let mut content = TxpoolSubPoolContent::default();
+        insert(pending, from, &mut content.pending);
+        insert(basefee, from, &mut content.base_fee);
+        insert(blob, from, &mut content.blob);
+        insert(queued, from, &mut content.queued);
+
+        content
+    }
+}
+
+/// Converts the [`TxState`] of a pooled transaction into its rpc representation.
+const fn transaction_state(state: TxState) -> TxpoolTransactionState {
+    TxpoolTransactionState {
+        no_parked_ancestors: state.contains(TxState::NO_PARKED_ANCESTORS),
+        no_nonce_gaps: state.contains(TxState::NO_NONCE_GAPS),
+        enough_balance: state.contains(TxState::ENOUGH_BALANCE),
+        not_too_much_gas: state.contains(TxState::NOT_TOO_MUCH_GAS),
+        enough_fee_cap_block: state.contains(TxState::ENOUGH_FEE_CAP_BLOCK),
+        enough_blob_fee_cap_block: state.contains(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK),
+        blob_transaction: state.contains(TxState::BLOB_TRANSACTION),
+    }
+}
+
+/// Converts a [`RemovedTransaction`] into its rpc representation.
+fn removed_transaction(removed: RemovedTransaction) -> TxpoolRemovedTransaction {
+    let RemovedTransaction { hash, sender, nonce, reason, timestamp } = removed;
+    let reason = match reason {
+        RemovalReason::Replaced { replaced_by } => TxpoolRemovalReason::Replaced { replaced_by },
+        RemovalReason::Evicted => TxpoolRemovalReason::Evicted,
+        RemovalReason::Mined { block_hash } => TxpoolRemovalReason::Mined { block_hash },
+        RemovalReason::Outdated => TxpoolRemovalReason::Outdated,
+        RemovalReason::ConditionNotMet => TxpoolRemovalReason::ConditionNotMet,
+        RemovalReason::Invalid(error) => TxpoolRemovalReason::Invalid { error },
+        RemovalReason::Removed => TxpoolRemovalReason::Removed,
+    };
+    TxpoolRemovedTransaction {
+        hash,
+        sender,
+        nonce: U64::from(nonce),
+        reason,
+        timestamp: U64::from(timestamp),
+    }
+}
+
+/// Converts a [`FullTransactionEvent`] into its rpc representation.
+fn transaction_event<T: PoolTransaction>(event: FullTransactionEvent<T>) -> TxpoolEvent {
+    match event {
+        FullTransactionEvent::Pending(hash) => TxpoolEvent::Pending { hash },
+        FullTransactionEvent::Queued(hash) => TxpoolEvent::Queued { hash },
+        FullTransactionEvent::Mined { tx_hash, block_hash } => {
+            TxpoolEvent::Mined { hash: tx_hash, block_hash }
+        }
+        FullTransactionEvent::Replaced { transaction, replaced_by } => {
+            TxpoolEvent::Replaced { hash: *transaction.hash(), replaced_by }
+        }
+        FullTransactionEvent::Discarded(hash) => TxpoolEvent::Discarded { hash },
+        FullTransactionEvent::Invalid(hash) => TxpoolEvent::Invalid { hash },
+        FullTransactionEvent::Propagated(kinds) => {
+            TxpoolEvent::Propagated { peers: kinds.iter().map(|kind| *kind.peer()).collect() }
+        }
+    }
 }
 
 #[async_trait]
@@ -68,7 +176,7 @@ where
     /// Handler for `txpool_status`
     async fn txpool_status(&self) -> Result<TxpoolStatus> {
         trace!(target: "rpc::eth", "Serving txpool_status");
-        let all = self.pool.all_transactions();
+        let all = self.all_transactions();
         Ok(TxpoolStatus { pending: all.pending.len() as u64, queued: all.queued.len() as u64 })
     }
 
@@ -99,7 +207,7 @@ where
             );
         }
 
-        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        let AllPoolTransactions { pending, queued } = self.all_transactions();
 
         Ok(TxpoolInspect {
             pending: pending.iter().fold(Default::default(), |mut acc, tx| {
@@ -132,6 +240,35 @@ where
         trace!(target: "rpc::eth", "Serving txpool_content");
         Ok(self.content())
     }
+
+    /// Handler for `txpool_subPoolContent`
+    async fn txpool_sub_pool_content(&self) -> Result<TxpoolSubPoolContent> {
+        trace!(target: "rpc::eth", "Serving txpool_subPoolContent");
+        Ok(self.sub_pool_content(None))
+    }
+
+    /// Handler for `txpool_subPoolContentFrom`
+    async fn txpool_sub_pool_content_from(&self, from: Address) -> Result<TxpoolSubPoolContent> {
+        trace!(target: "rpc::eth", ?from, "Serving txpool_subPoolContentFrom");
+        Ok(self.sub_pool_content(Some(from)))
+    }
+
+    /// Handler for `txpool_removed`
+    async fn txpool_removed(&self) -> Result<Vec<TxpoolRemovedTransaction>> {
+        trace!(target: "rpc::eth", "Serving txpool_removed");
+        Ok(self.pool.removed_transactions().into_iter().map(removed_transaction).collect())
+    }
+
+    /// Handler for `txpool_subscribeEvents`
+    async fn txpool_subscribe_events(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        let sink = pending.accept().await?;
+        let stream = self.pool.all_transactions_event_listener().map(transaction_event);
+        pipe_from_stream(sink, stream).await?;
+        Ok(())
+    }
 }
 
 impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
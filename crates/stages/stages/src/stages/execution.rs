@@ -206,17 +206,16 @@ where
         let static_file_provider = provider.static_file_provider();
 
         // We only use static files for Receipts, if there is no receipt pruning of any kind.
-        let static_file_producer = if self.prune_modes.receipts.is_none() &&
-            self.prune_modes.receipts_log_filter.is_empty()
-        {
-            let mut producer = prepare_static_file_producer(provider, start_block)?;
-            // Since there might be a database <-> static file inconsistency (read
-            // `prepare_static_file_producer` for context), we commit the change straight away.
-            producer.commit()?;
-            Some(producer)
-        } else {
-            None
-        };
+        let static_file_producer =
+            if self.prune_modes.receipts.is_none() && !self.prune_modes.has_receipts_log_filter() {
+                let mut producer = prepare_static_file_producer(provider, start_block)?;
+                // Since there might be a database <-> static file inconsistency (read
+                // `prepare_static_file_producer` for context), we commit the change straight away.
+                producer.commit()?;
+                Some(producer)
+            } else {
+                None
+            };
 
         let db = StateProviderDatabase(LatestStateProviderRef::new(
             provider.tx_ref(),
@@ -428,7 +427,7 @@ where
         }
 
         // Unwind all receipts for transactions in the block range
-        if self.prune_modes.receipts.is_none() && self.prune_modes.receipts_log_filter.is_empty() {
+        if self.prune_modes.receipts.is_none() && !self.prune_modes.has_receipts_log_filter() {
             // We only use static files for Receipts, if there is no receipt pruning of any kind.
 
             // prepare_static_file_producer does a consistency check that will unwind static files
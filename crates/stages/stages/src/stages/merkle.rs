@@ -55,6 +55,12 @@ pub const MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD: u64 = 5_000;
 /// stages. The order of these two variants is important. The unwind variant should be added to the
 /// pipeline before the execution variant.
 ///
+/// In archive-trie mode, see
+/// [`DatabaseProvider::archive_trie`](reth_provider::DatabaseProvider::archive_trie), the previous
+/// values of the trie nodes changed by an incremental update are written to the trie changesets of
+/// the last block of the range, so the trie of the blocks in between is not recorded. A rebuild
+/// drops the trie changesets, and the trie is archived as of its target block.
+///
 /// An example pipeline to only hash state would be:
 ///
 /// - [`MerkleStage::Unwind`]
@@ -197,6 +203,9 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 self.save_execution_checkpoint(provider, None)?;
                 provider.tx_ref().clear::<tables::AccountsTrie>()?;
                 provider.tx_ref().clear::<tables::StoragesTrie>()?;
+                provider.tx_ref().clear::<tables::AccountsTrieChangeSets>()?;
+                provider.tx_ref().clear::<tables::StoragesTrieChangeSets>()?;
+                provider.save_first_archived_trie_block(None)?;
 
                 None
             }
@@ -238,6 +247,9 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 }
                 StateRootProgress::Complete(root, hashed_entries_walked, updates) => {
                     updates.write_to_database(tx)?;
+                    if provider.archive_trie() {
+                        provider.save_first_archived_trie_block(Some(to_block))?;
+                    }
 
                     entities_checkpoint.processed += hashed_entries_walked as u64;
 
@@ -252,7 +264,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                         error!(target: "sync::stages::merkle", %e, ?current_block_number, ?to_block, "Incremental state root failed! {INVALID_STATE_ROOT_ERROR_MESSAGE}");
                         StageError::Fatal(Box::new(e))
                     })?;
-            updates.write_to_database(provider.tx_ref())?;
+            provider.write_trie_updates(updates, to_block)?;
 
             let total_hashed_entries = (provider.count_entries::<tables::HashedAccounts>()? +
                 provider.count_entries::<tables::HashedStorages>()?)
@@ -304,6 +316,9 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         if input.unwind_to == 0 {
             tx.clear::<tables::AccountsTrie>()?;
             tx.clear::<tables::StoragesTrie>()?;
+            tx.clear::<tables::AccountsTrieChangeSets>()?;
+            tx.clear::<tables::StoragesTrieChangeSets>()?;
+            provider.save_first_archived_trie_block(None)?;
 
             entities_checkpoint.processed = 0;
 
@@ -326,6 +341,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
 
             // Validation passed, apply unwind changes to the database.
             updates.write_to_database(provider.tx_ref())?;
+            provider.unwind_trie_changesets(input.unwind_to)?;
 
             // TODO(alexey): update entities checkpoint
         } else {
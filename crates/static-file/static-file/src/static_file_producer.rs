@@ -209,7 +209,7 @@ impl<DB: Database> StaticFileProducerInner<DB> {
             }),
             // StaticFile receipts only if they're not pruned according to the user configuration
             receipts: if self.prune_modes.receipts.is_none() &&
-                self.prune_modes.receipts_log_filter.is_empty()
+                !self.prune_modes.has_receipts_log_filter()
             {
                 finalized_block_numbers.receipts.and_then(|finalized_block_number| {
                     self.get_static_file_target(
@@ -211,6 +211,7 @@ impl_compression_for_compact!(
     StoredNibbles,
     StoredNibblesSubKey,
     StorageTrieEntry,
+    TrieChangeSetsEntry,
     StoredBlockBodyIndices,
     StoredBlockOmmers,
     StoredBlockWithdrawals,
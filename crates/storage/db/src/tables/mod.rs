@@ -20,7 +20,7 @@ pub(crate) mod utils;
 
 use reth_db_api::{
     models::{
-        accounts::{AccountBeforeTx, BlockNumberAddress},
+        accounts::{AccountBeforeTx, BlockNumberAddress, BlockNumberHashedAddress},
         blocks::{HeaderHash, StoredBlockOmmers},
         client_version::ClientVersion,
         storage_sharded_key::StorageShardedKey,
@@ -35,7 +35,9 @@ use reth_primitives::{
 use reth_primitives_traits::IntegerList;
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::StageCheckpoint;
-use reth_trie_common::{StorageTrieEntry, StoredBranchNode, StoredNibbles, StoredNibblesSubKey};
+use reth_trie_common::{
+    StorageTrieEntry, StoredBranchNode, StoredNibbles, StoredNibblesSubKey, TrieChangeSetsEntry,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -386,6 +388,18 @@ tables! {
     /// From HashedAddress => NibblesSubKey => Intermediate value
     table StoragesTrie<Key = B256, Value = StorageTrieEntry, SubKey = StoredNibblesSubKey>;
 
+    /// Stores the value of an account trie node before a certain block changed it.
+    /// If [`TrieChangeSetsEntry::node`] is `None`, the node didn't exist before the block.
+    ///
+    /// Only written in archive-trie mode.
+    table AccountsTrieChangeSets<Key = BlockNumber, Value = TrieChangeSetsEntry, SubKey = StoredNibblesSubKey>;
+
+    /// Stores the value of a storage trie node before a certain block changed it.
+    /// If [`TrieChangeSetsEntry::node`] is `None`, the node didn't exist before the block.
+    ///
+    /// Only written in archive-trie mode.
+    table StoragesTrieChangeSets<Key = BlockNumberHashedAddress, Value = TrieChangeSetsEntry, SubKey = StoredNibblesSubKey>;
+
     /// Stores the transaction sender for each canonical transaction.
     /// It is needed to speed up execution stage and allows fetching signer without doing
     /// transaction signed recovery
@@ -415,6 +429,9 @@ tables! {
 pub enum ChainStateKey {
     /// Last finalized block key
     LastFinalizedBlock,
+    /// First block as of which the trie is archived: the trie at the end of the block was
+    /// persisted, and the trie changesets of all later blocks are written.
+    FirstArchivedTrieBlock,
 }
 
 impl Encode for ChainStateKey {
@@ -423,16 +440,17 @@ impl Encode for ChainStateKey {
     fn encode(self) -> Self::Encoded {
         match self {
             Self::LastFinalizedBlock => [0],
+            Self::FirstArchivedTrieBlock => [1],
         }
     }
 }
 
 impl Decode for ChainStateKey {
     fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, reth_db_api::DatabaseError> {
-        if value.as_ref() == [0] {
-            Ok(Self::LastFinalizedBlock)
-        } else {
-            Err(reth_db_api::DatabaseError::Decode)
+        match value.as_ref() {
+            [0] => Ok(Self::LastFinalizedBlock),
+            [1] => Ok(Self::FirstArchivedTrieBlock),
+            _ => Err(reth_db_api::DatabaseError::Decode),
         }
     }
 }
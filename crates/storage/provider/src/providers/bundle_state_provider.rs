@@ -1,11 +1,12 @@
 use crate::{
     AccountReader, BlockHashReader, ExecutionDataProvider, StateProvider, StateRootProvider,
 };
-use reth_primitives::{Account, Address, BlockNumber, Bytecode, B256};
+use reth_primitives::{Account, Address, BlockNumber, Bytecode, Bytes, B256};
 use reth_storage_api::StateProofProvider;
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
 use revm::db::BundleState;
+use std::collections::HashMap;
 
 /// A state provider that resolves to data from either a wrapped [`crate::ExecutionOutcome`]
 /// or an underlying state provider.
@@ -94,6 +95,16 @@ impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProofProvider
         state.extend(bundle_state.clone());
         self.state_provider.proof(&state, address, slots)
     }
+
+    fn witness(
+        &self,
+        overlay: &BundleState,
+        target: &HashedPostState,
+    ) -> ProviderResult<HashMap<B256, Bytes>> {
+        let mut state = self.block_execution_data_provider.execution_outcome().state().clone();
+        state.extend(overlay.clone());
+        self.state_provider.witness(&state, target)
+    }
 }
 
 impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProvider for BundleStateProvider<SP, EDP> {
@@ -1,5 +1,5 @@
 use crate::{
-    providers::{state::latest::LatestStateProvider, StaticFileProvider},
+    providers::{state::latest::LatestStateProvider, RevertCheckpoints, StaticFileProvider},
     to_range,
     traits::{BlockSource, ReceiptProvider},
     BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
@@ -48,6 +48,10 @@ pub struct ProviderFactory<DB> {
     static_file_provider: StaticFileProvider,
     /// Optional pruning configuration
     prune_modes: PruneModes,
+    /// Optional checkpoints of the reverts of historical blocks
+    revert_checkpoints: Option<RevertCheckpoints>,
+    /// Whether the trie changesets are written and used for historical state roots and proofs
+    archive_trie: bool,
 }
 
 impl<DB> ProviderFactory<DB> {
@@ -57,7 +61,14 @@ impl<DB> ProviderFactory<DB> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { db: Arc::new(db), chain_spec, static_file_provider, prune_modes: PruneModes::none() }
+        Self {
+            db: Arc::new(db),
+            chain_spec,
+            static_file_provider,
+            prune_modes: PruneModes::none(),
+            revert_checkpoints: None,
+            archive_trie: false,
+        }
     }
 
     /// Enables metrics on the static file provider.
@@ -72,6 +83,27 @@ impl<DB> ProviderFactory<DB> {
         self
     }
 
+    /// Enables checkpoints of the reverts of historical blocks for the historical state
+    /// providers, so that state roots and proofs at old blocks don't walk all changesets up to the
+    /// tip, see [`RevertCheckpoints`].
+    pub fn with_revert_checkpoints(mut self, revert_checkpoints: RevertCheckpoints) -> Self {
+        self.revert_checkpoints = Some(revert_checkpoints);
+        self
+    }
+
+    /// Enables the archive-trie mode: the previous values of all changed trie nodes are written to
+    /// the trie changesets, which the historical state providers use to read the trie as of a
+    /// block for state roots and proofs.
+    pub const fn with_archive_trie(mut self, archive_trie: bool) -> Self {
+        self.archive_trie = archive_trie;
+        self
+    }
+
+    /// Returns the checkpoints of the reverts of historical blocks, if enabled.
+    pub const fn revert_checkpoints(&self) -> Option<&RevertCheckpoints> {
+        self.revert_checkpoints.as_ref()
+    }
+
     /// Returns reference to the underlying database.
     pub fn db_ref(&self) -> &DB {
         &self.db
@@ -98,6 +130,8 @@ impl ProviderFactory<DatabaseEnv> {
             chain_spec,
             static_file_provider,
             prune_modes: PruneModes::none(),
+            revert_checkpoints: None,
+            archive_trie: false,
         })
     }
 }
@@ -116,7 +150,9 @@ impl<DB: Database> ProviderFactory<DB> {
             self.chain_spec.clone(),
             self.static_file_provider.clone(),
             self.prune_modes.clone(),
-        ))
+        )
+        .with_revert_checkpoints(self.revert_checkpoints.clone())
+        .with_archive_trie(self.archive_trie))
     }
 
     /// Returns a provider with a created `DbTxMut` inside, which allows fetching and updating
@@ -125,12 +161,15 @@ impl<DB: Database> ProviderFactory<DB> {
     /// open.
     #[track_caller]
     pub fn provider_rw(&self) -> ProviderResult<DatabaseProviderRW<DB>> {
-        Ok(DatabaseProviderRW(DatabaseProvider::new_rw(
-            self.db.tx_mut()?,
-            self.chain_spec.clone(),
-            self.static_file_provider.clone(),
-            self.prune_modes.clone(),
-        )))
+        Ok(DatabaseProviderRW(
+            DatabaseProvider::new_rw(
+                self.db.tx_mut()?,
+                self.chain_spec.clone(),
+                self.static_file_provider.clone(),
+                self.prune_modes.clone(),
+            )
+            .with_archive_trie(self.archive_trie),
+        ))
     }
 
     /// State provider for latest block
@@ -591,6 +630,8 @@ impl<DB> Clone for ProviderFactory<DB> {
             chain_spec: self.chain_spec.clone(),
             static_file_provider: self.static_file_provider.clone(),
             prune_modes: self.prune_modes.clone(),
+            revert_checkpoints: self.revert_checkpoints.clone(),
+            archive_trie: self.archive_trie,
         }
     }
 }
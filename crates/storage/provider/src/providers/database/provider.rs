@@ -1,6 +1,8 @@
 use crate::{
     bundle_state::{BundleStateInit, HashedStateChanges, RevertsInit},
-    providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
+    providers::{
+        database::metrics, static_file::StaticFileWriter, RevertCheckpoints, StaticFileProvider,
+    },
     to_range,
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
@@ -22,7 +24,8 @@ use reth_db_api::{
     database::Database,
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        BlockNumberHashedAddress, ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers,
+        StoredBlockWithdrawals,
     },
     table::{Table, TableRow},
     transaction::{DbTx, DbTxMut},
@@ -106,6 +109,10 @@ pub struct DatabaseProvider<TX> {
     static_file_provider: StaticFileProvider,
     /// Pruning configuration
     prune_modes: PruneModes,
+    /// Optional checkpoints of the reverts of historical blocks
+    revert_checkpoints: Option<RevertCheckpoints>,
+    /// Whether the trie changesets are written and used for historical state roots and proofs
+    archive_trie: bool,
 }
 
 impl<TX> DatabaseProvider<TX> {
@@ -113,6 +120,26 @@ impl<TX> DatabaseProvider<TX> {
     pub const fn static_file_provider(&self) -> &StaticFileProvider {
         &self.static_file_provider
     }
+
+    /// Sets the checkpoints used by the historical state providers, see [`RevertCheckpoints`].
+    pub fn with_revert_checkpoints(
+        mut self,
+        revert_checkpoints: Option<RevertCheckpoints>,
+    ) -> Self {
+        self.revert_checkpoints = revert_checkpoints;
+        self
+    }
+
+    /// Sets whether the archive-trie mode is enabled, see [`Self::write_trie_updates`].
+    pub const fn with_archive_trie(mut self, archive_trie: bool) -> Self {
+        self.archive_trie = archive_trie;
+        self
+    }
+
+    /// Returns `true` if the archive-trie mode is enabled.
+    pub const fn archive_trie(&self) -> bool {
+        self.archive_trie
+    }
 }
 
 impl<TX: DbTxMut> DatabaseProvider<TX> {
@@ -123,7 +150,14 @@ impl<TX: DbTxMut> DatabaseProvider<TX> {
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes }
+        Self {
+            tx,
+            chain_spec,
+            static_file_provider,
+            prune_modes,
+            revert_checkpoints: None,
+            archive_trie: false,
+        }
     }
 }
 
@@ -148,7 +182,9 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
             self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
 
         let mut state_provider =
-            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider);
+            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider)
+                .with_revert_checkpoints(self.revert_checkpoints)
+                .with_archive_trie(self.archive_trie);
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
@@ -261,7 +297,14 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes }
+        Self {
+            tx,
+            chain_spec,
+            static_file_provider,
+            prune_modes,
+            revert_checkpoints: None,
+            archive_trie: false,
+        }
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -284,6 +327,12 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         &self.chain_spec
     }
 
+    /// Returns the first block as of which the trie is archived, see
+    /// [`tables::ChainStateKey::FirstArchivedTrieBlock`].
+    pub fn first_archived_trie_block(&self) -> Result<Option<BlockNumber>, DatabaseError> {
+        self.tx.get::<tables::ChainState>(tables::ChainStateKey::FirstArchivedTrieBlock)
+    }
+
     /// Disables long-lived read transaction safety guarantees for leaks prevention and
     /// observability improvements.
     ///
@@ -1125,6 +1174,63 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         ))
     }
 
+    /// Writes the trie updates of the given block, or block range ending with the given block, to
+    /// the database.
+    ///
+    /// In archive-trie mode, the previous values of the changed trie nodes are written to the trie
+    /// changesets of the block first, see [`tables::AccountsTrieChangeSets`] and
+    /// [`tables::StoragesTrieChangeSets`]. The first block written in archive-trie mode is saved
+    /// as [`tables::ChainStateKey::FirstArchivedTrieBlock`], and it's removed when the trie is
+    /// written without changesets, so the trie is only read from the changesets of the blocks
+    /// that were all written in archive-trie mode.
+    ///
+    /// # Returns
+    ///
+    /// The number of trie entries updated in the database.
+    pub fn write_trie_updates(
+        &self,
+        trie_updates: TrieUpdates,
+        block_number: BlockNumber,
+    ) -> Result<usize, DatabaseError> {
+        if self.archive_trie {
+            // the trie before the first archived range is not recorded, only the trie after it
+            if self.first_archived_trie_block()?.is_none() {
+                self.save_first_archived_trie_block(Some(block_number))?;
+            }
+            trie_updates.write_changesets(&self.tx, block_number)?;
+        } else {
+            self.save_first_archived_trie_block(None)?;
+        }
+        trie_updates.write_to_database(&self.tx)
+    }
+
+    /// Removes the trie changesets of all blocks above the given block number.
+    ///
+    /// The trie of the given block is the latest one, so it's archived if any later block was.
+    pub fn unwind_trie_changesets(&self, block_number: BlockNumber) -> Result<(), DatabaseError> {
+        self.remove::<tables::AccountsTrieChangeSets>(block_number + 1..)?;
+        self.remove::<tables::StoragesTrieChangeSets>(
+            BlockNumberHashedAddress((block_number + 1, B256::ZERO))..,
+        )?;
+        if self.first_archived_trie_block()?.is_some_and(|first| first > block_number) {
+            self.save_first_archived_trie_block(Some(block_number))?;
+        }
+        Ok(())
+    }
+
+    /// Saves the first block as of which the trie is archived, or removes it if the trie is not
+    /// archived, see [`Self::write_trie_updates`].
+    pub fn save_first_archived_trie_block(
+        &self,
+        block_number: Option<BlockNumber>,
+    ) -> Result<(), DatabaseError> {
+        let key = tables::ChainStateKey::FirstArchivedTrieBlock;
+        match block_number {
+            Some(block_number) => self.tx.put::<tables::ChainState>(key, block_number),
+            None => self.tx.delete::<tables::ChainState>(key, None).map(drop),
+        }
+    }
+
     /// Remove list of entries from the table. Returns the number of entries removed.
     #[inline]
     pub fn remove<T: Table>(
@@ -2807,7 +2913,7 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
                     block_hash: end_block_hash,
                 })))
             }
-            trie_updates.write_to_database(&self.tx)?;
+            self.write_trie_updates(trie_updates, *range.end())?;
         }
         durations_recorder.record_relative(metrics::Action::InsertMerkleTree);
 
@@ -3017,6 +3123,7 @@ impl<DB: Database> BlockExecutionWriter for DatabaseProviderRW<DB> {
             })))
         }
         trie_updates.write_to_database(&self.tx)?;
+        self.unwind_trie_changesets(parent_number)?;
 
         // get blocks
         let blocks = self.take_block_range(range.clone())?;
@@ -3105,6 +3212,7 @@ impl<DB: Database> BlockExecutionWriter for DatabaseProviderRW<DB> {
             })))
         }
         trie_updates.write_to_database(&self.tx)?;
+        self.unwind_trie_changesets(parent_number)?;
 
         // get blocks
         let blocks = self.take_block_range(range.clone())?;
@@ -3303,7 +3411,7 @@ impl<DB: Database> BlockWriter for DatabaseProviderRW<DB> {
         // insert hashes and intermediate merkle nodes
         {
             HashedStateChanges(hashed_state).write_to_db(self)?;
-            trie_updates.write_to_database(&self.tx)?;
+            self.write_trie_updates(trie_updates, last_block_number)?;
         }
         durations_recorder.record_relative(metrics::Action::InsertHashes);
 
@@ -3354,17 +3462,10 @@ impl<TX: DbTx> StatsReader for DatabaseProvider<TX> {
 
 impl<TX: DbTx> FinalizedBlockReader for DatabaseProvider<TX> {
     fn last_finalized_block_number(&self) -> ProviderResult<BlockNumber> {
-        let mut finalized_blocks = self
+        Ok(self
             .tx
-            .cursor_read::<tables::ChainState>()?
-            .walk(Some(tables::ChainStateKey::LastFinalizedBlock))?
-            .take(1)
-            .collect::<Result<BTreeMap<tables::ChainStateKey, BlockNumber>, _>>()?;
-
-        let last_finalized_block_number = finalized_blocks
-            .pop_first()
-            .unwrap_or((tables::ChainStateKey::LastFinalizedBlock, 0_u64));
-        Ok(last_finalized_block_number.1)
+            .get::<tables::ChainState>(tables::ChainStateKey::LastFinalizedBlock)?
+            .unwrap_or_default())
     }
 }
 
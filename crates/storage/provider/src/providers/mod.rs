@@ -47,6 +47,7 @@ pub use static_file::{
 
 mod state;
 pub use state::{
+    checkpoints::{RevertCheckpoints, DEFAULT_MAX_REVERT_CHECKPOINTS},
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
     latest::{LatestStateProvider, LatestStateProviderRef},
 };
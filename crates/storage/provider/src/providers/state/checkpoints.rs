@@ -0,0 +1,219 @@
+use parking_lot::Mutex;
+use reth_db::tables;
+use reth_db_api::transaction::DbTx;
+use reth_primitives::{BlockNumber, B256};
+use reth_storage_errors::db::DatabaseError;
+use reth_trie::HashedPostState;
+use schnellru::{ByLength, LruMap};
+use std::{fmt, ops::RangeInclusive, sync::Arc};
+
+/// The default number of revert checkpoints kept in memory.
+pub const DEFAULT_MAX_REVERT_CHECKPOINTS: u32 = 64;
+
+/// A checkpoint of the aggregated reverts of a window of blocks.
+#[derive(Debug)]
+struct RevertCheckpoint {
+    /// Hash of the last block of the window when the checkpoint was created.
+    ///
+    /// The checkpoint is only valid while this block is canonical.
+    last_block_hash: B256,
+    /// Reverts of the window, i.e. the state before its first block for every key it changed.
+    reverts: Arc<HashedPostState>,
+}
+
+/// Periodic checkpoints of the reverts of historical blocks, shared by the historical state
+/// providers.
+///
+/// Reverting the state to an old block requires walking the changesets of every block up to the
+/// tip, see [`HashedPostState::from_revert_range`]. With checkpoints, the changesets are aggregated
+/// per window of `interval` blocks starting at multiples of the interval, so that reverting to any
+/// block only walks the changesets up to the next checkpoint and after the last full window. The
+/// reverts of the windows in between are taken from the checkpoints, which are kept for the least
+/// recently used `max_checkpoints` windows. The checkpoints of the recent windows are created in
+/// the background with [`Self::build`], and the others on first use.
+///
+/// Checkpoints are invalidated if the last block of their window is no longer canonical.
+#[derive(Clone)]
+pub struct RevertCheckpoints {
+    interval: u64,
+    max_checkpoints: u32,
+    checkpoints: Arc<Mutex<LruMap<u64, RevertCheckpoint, ByLength>>>,
+}
+
+impl RevertCheckpoints {
+    /// Creates checkpoints every `interval` blocks, keeping at most
+    /// [`DEFAULT_MAX_REVERT_CHECKPOINTS`] in memory.
+    pub fn new(interval: u64) -> Self {
+        Self::with_max_checkpoints(interval, DEFAULT_MAX_REVERT_CHECKPOINTS)
+    }
+
+    /// Creates checkpoints every `interval` blocks, keeping at most `max_checkpoints` in memory.
+    pub fn with_max_checkpoints(interval: u64, max_checkpoints: u32) -> Self {
+        Self {
+            interval: interval.max(1),
+            max_checkpoints,
+            checkpoints: Arc::new(Mutex::new(LruMap::new(ByLength::new(max_checkpoints)))),
+        }
+    }
+
+    /// Returns the number of blocks between checkpoints.
+    pub const fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Returns the number of checkpoints in memory.
+    pub fn len(&self) -> usize {
+        self.checkpoints.lock().len()
+    }
+
+    /// Returns `true` if there are no checkpoints in memory.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates the checkpoints of the most recent full windows up to the given block that don't
+    /// exist or are no longer canonical, as many as are kept in memory.
+    ///
+    /// This is meant to run in the background whenever the canonical chain changes, so that
+    /// historical proofs don't create the checkpoints of the recent windows themselves.
+    pub fn build<TX: DbTx>(&self, tx: &TX, tip: BlockNumber) -> Result<(), DatabaseError> {
+        let full_windows = tip.saturating_add(1) / self.interval;
+        // the newest windows are created last, so that they're evicted last
+        for window in full_windows.saturating_sub(self.max_checkpoints as u64)..full_windows {
+            self.window_reverts(tx, window * self.interval)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the reverts of the range, equivalent to [`HashedPostState::from_revert_range`].
+    pub fn revert_range<TX: DbTx>(
+        &self,
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<HashedPostState, DatabaseError> {
+        let (start, end) = range.into_inner();
+        let first_window = start.div_ceil(self.interval) * self.interval;
+        let full_windows = (end.saturating_add(1).saturating_sub(first_window)) / self.interval;
+        if start > end || full_windows == 0 {
+            return HashedPostState::from_revert_range(tx, start..=end)
+        }
+        let tail_start = first_window + full_windows * self.interval;
+
+        // The first value of a key in the range takes precedence, so the reverts are extended from
+        // the newest to the oldest blocks.
+        let mut state = if tail_start <= end {
+            HashedPostState::from_revert_range(tx, tail_start..=end)?
+        } else {
+            HashedPostState::default()
+        };
+        for window in (0..full_windows).rev() {
+            let window_start = first_window + window * self.interval;
+            state.extend_ref(&self.window_reverts(tx, window_start)?);
+        }
+        if start < first_window {
+            state.extend(HashedPostState::from_revert_range(tx, start..=first_window - 1)?);
+        }
+
+        Ok(state)
+    }
+
+    /// Returns the reverts of the window starting at the given block, creating the checkpoint if
+    /// it doesn't exist or is no longer canonical.
+    fn window_reverts<TX: DbTx>(
+        &self,
+        tx: &TX,
+        window_start: BlockNumber,
+    ) -> Result<Arc<HashedPostState>, DatabaseError> {
+        let window_end = window_start + self.interval - 1;
+        let Some(last_block_hash) = tx.get::<tables::CanonicalHeaders>(window_end)? else {
+            return HashedPostState::from_revert_range(tx, window_start..=window_end).map(Arc::new)
+        };
+
+        if let Some(checkpoint) = self.checkpoints.lock().get(&window_start) {
+            if checkpoint.last_block_hash == last_block_hash {
+                return Ok(checkpoint.reverts.clone())
+            }
+        }
+
+        tracing::debug!(
+            target: "provider::historical_sp",
+            window = ?window_start..=window_end,
+            "Creating revert checkpoint"
+        );
+        let reverts = Arc::new(HashedPostState::from_revert_range(tx, window_start..=window_end)?);
+        self.checkpoints
+            .lock()
+            .insert(window_start, RevertCheckpoint { last_block_hash, reverts: reverts.clone() });
+        Ok(reverts)
+    }
+}
+
+impl fmt::Debug for RevertCheckpoints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RevertCheckpoints")
+            .field("interval", &self.interval)
+            .field("max_checkpoints", &self.max_checkpoints)
+            .field("checkpoints", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_db_api::{database::Database, models::AccountBeforeTx, transaction::DbTxMut};
+    use reth_primitives::{Account, Address, StorageEntry, U256};
+
+    #[test]
+    fn revert_range_matches_changesets() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        let address = Address::with_last_byte(1);
+        for block in 0..20u64 {
+            tx.put::<tables::CanonicalHeaders>(block, B256::with_last_byte(block as u8)).unwrap();
+            let info = Some(Account { nonce: block, ..Default::default() });
+            tx.put::<tables::AccountChangeSets>(block, AccountBeforeTx { address, info }).unwrap();
+            tx.put::<tables::StorageChangeSets>(
+                (block, address).into(),
+                StorageEntry {
+                    key: B256::with_last_byte(block as u8 % 3),
+                    value: U256::from(block),
+                },
+            )
+            .unwrap();
+        }
+
+        let checkpoints = RevertCheckpoints::new(4);
+        for start in 0..20 {
+            for end in start..20 {
+                assert_eq!(
+                    checkpoints.revert_range(&tx, start..=end).unwrap(),
+                    HashedPostState::from_revert_range(&tx, start..=end).unwrap(),
+                    "range {start}..={end}"
+                );
+            }
+        }
+        assert_eq!(checkpoints.len(), 5);
+
+        // checkpoints of reorged windows are recreated
+        tx.put::<tables::AccountChangeSets>(
+            7,
+            AccountBeforeTx { address: Address::with_last_byte(2), info: None },
+        )
+        .unwrap();
+        tx.put::<tables::CanonicalHeaders>(7, B256::with_last_byte(0xff)).unwrap();
+        assert_eq!(
+            checkpoints.revert_range(&tx, 0..=19).unwrap(),
+            HashedPostState::from_revert_range(&tx, 0..=19).unwrap()
+        );
+
+        // only the most recent full windows are built ahead of use
+        let checkpoints = RevertCheckpoints::with_max_checkpoints(4, 2);
+        checkpoints.build(&tx, 18).unwrap();
+        assert_eq!(checkpoints.len(), 2);
+        for window_start in [8, 12] {
+            assert!(checkpoints.checkpoints.lock().peek(&window_start).is_some());
+        }
+    }
+}
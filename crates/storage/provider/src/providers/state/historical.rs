@@ -1,23 +1,31 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{state::macros::delegate_provider_impls, RevertCheckpoints, StaticFileProvider},
     AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
 };
 use reth_db::{tables, BlockNumberList};
 use reth_db_api::{
     cursor::{DbCursorRO, DbDupCursorRO},
-    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    models::{storage_sharded_key::StorageShardedKey, BlockNumberHashedAddress, ShardedKey},
     table::Table,
     transaction::DbTx,
 };
 use reth_primitives::{
-    constants::EPOCH_SLOTS, Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey,
-    StorageValue, B256,
+    constants::EPOCH_SLOTS, Account, Address, BlockNumber, Bytecode, Bytes, StaticFileSegment,
+    StorageKey, StorageValue, B256,
 };
 use reth_storage_api::StateProofProvider;
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
+use reth_trie::{
+    hashed_cursor::HashedPostStateCursorFactory,
+    prefix_set::TriePrefixSetsMut,
+    proof::Proof,
+    trie_cursor::InMemoryTrieCursorFactory,
+    updates::{TrieUpdates, TrieUpdatesSorted},
+    witness::TrieWitness,
+    AccountProof, HashedPostState, StateRoot,
+};
 use revm::db::BundleState;
-use std::fmt::Debug;
+use std::{collections::HashMap, fmt::Debug};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -30,6 +38,7 @@ use std::fmt::Debug;
 /// - [`tables::StoragesHistory`]
 /// - [`tables::AccountChangeSets`]
 /// - [`tables::StorageChangeSets`]
+/// - [`tables::AccountsTrieChangeSets`] and [`tables::StoragesTrieChangeSets`] in archive-trie mode
 #[derive(Debug)]
 pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     /// Transaction
@@ -40,6 +49,10 @@ pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Optional checkpoints of the reverts of historical blocks.
+    revert_checkpoints: Option<RevertCheckpoints>,
+    /// Whether the trie as of the block number is read from the trie changesets.
+    archive_trie: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -57,7 +70,14 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            revert_checkpoints: None,
+            archive_trie: false,
+        }
     }
 
     /// Create new `StateProvider` for historical block number and lowest block numbers at which
@@ -68,7 +88,30 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         lowest_available_blocks: LowestAvailableBlocks,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks, static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks,
+            static_file_provider,
+            revert_checkpoints: None,
+            archive_trie: false,
+        }
+    }
+
+    /// Sets the checkpoints used to revert the state to the block number.
+    pub fn with_revert_checkpoints(
+        mut self,
+        revert_checkpoints: Option<RevertCheckpoints>,
+    ) -> Self {
+        self.revert_checkpoints = revert_checkpoints;
+        self
+    }
+
+    /// Sets whether the trie as of the block number is read from the trie changesets when
+    /// available, instead of recomputing the nodes of all reverted state.
+    pub const fn with_archive_trie(mut self, archive_trie: bool) -> Self {
+        self.archive_trie = archive_trie;
+        self
     }
 
     /// Lookup an account in the `AccountsHistory` table
@@ -113,15 +156,11 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             return Err(ProviderError::StateAtBlockPruned(self.block_number))
         }
 
-        let tip = self
-            .tx
-            .cursor_read::<tables::CanonicalHeaders>()?
-            .last()?
-            .map(|(tip, _)| tip)
-            .or_else(|| {
-                self.static_file_provider.get_highest_static_file_block(StaticFileSegment::Headers)
-            })
-            .ok_or(ProviderError::BestBlockNotFound)?;
+        let tip = self.tip()?;
+
+        if let Some(revert_checkpoints) = &self.revert_checkpoints {
+            return Ok(revert_checkpoints.revert_range(self.tx, self.block_number..=tip)?)
+        }
 
         if tip.saturating_sub(self.block_number) > EPOCH_SLOTS {
             tracing::warn!(
@@ -134,6 +173,80 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         Ok(HashedPostState::from_revert_range(self.tx, self.block_number..=tip)?)
     }
 
+    /// Retrieve the trie reverts from the trie changesets if the trie as of the block number is
+    /// available, see [`Self::with_archive_trie`].
+    ///
+    /// The changesets of a block hold the previous values of the trie nodes changed since the trie
+    /// was last persisted, so the trie is available as of each block at which it was persisted in
+    /// archive-trie mode and as of any block after which it didn't change. The trie is not
+    /// available before [`tables::ChainStateKey::FirstArchivedTrieBlock`], since the changesets of
+    /// the blocks written without archive-trie mode are missing.
+    fn trie_reverts(&self) -> ProviderResult<Option<TrieUpdatesSorted>> {
+        if !self.archive_trie || self.block_number == 0 {
+            return Ok(None)
+        }
+
+        // The state at the start of the block number is the state after the previous block.
+        let parent = self.block_number - 1;
+        let Some(first_archived_block) =
+            self.tx.get::<tables::ChainState>(tables::ChainStateKey::FirstArchivedTrieBlock)?
+        else {
+            return Ok(None)
+        };
+        if parent < first_archived_block {
+            return Ok(None)
+        }
+
+        let mut account_changesets = self.tx.cursor_read::<tables::AccountsTrieChangeSets>()?;
+        let mut storage_changesets = self.tx.cursor_read::<tables::StoragesTrieChangeSets>()?;
+        let persisted_at_parent = parent == first_archived_block ||
+            account_changesets.seek_exact(parent)?.is_some() ||
+            storage_changesets
+                .seek(BlockNumberHashedAddress((parent, B256::ZERO)))?
+                .map_or(false, |(key, _)| key.block_number() == parent);
+        if !persisted_at_parent {
+            let changed_after = account_changesets.seek(self.block_number)?.is_some() ||
+                storage_changesets
+                    .seek(BlockNumberHashedAddress((self.block_number, B256::ZERO)))?
+                    .is_some();
+            if changed_after {
+                return Ok(None)
+            }
+        }
+
+        let tip = self.tip()?;
+        Ok(Some(TrieUpdates::from_revert_range(self.tx, self.block_number..=tip)?.into_sorted()))
+    }
+
+    /// Retrieve the hashed state as of the block number with the given state on top. If the trie
+    /// as of the block number is available, the trie reverts and the prefix sets of the given state
+    /// are returned as well, so that only the nodes changed by the given state are recomputed.
+    #[allow(clippy::type_complexity)]
+    fn hashed_state_with_trie_reverts(
+        &self,
+        state: &BundleState,
+    ) -> ProviderResult<(HashedPostState, Option<(TrieUpdatesSorted, TriePrefixSetsMut)>)> {
+        let mut revert_state = self.revert_state()?;
+        let hashed_state = HashedPostState::from_bundle_state(&state.state);
+        let trie_reverts = self
+            .trie_reverts()?
+            .map(|trie_reverts| (trie_reverts, hashed_state.construct_prefix_sets()));
+        revert_state.extend(hashed_state);
+        Ok((revert_state, trie_reverts))
+    }
+
+    /// Retrieve the best block number.
+    fn tip(&self) -> ProviderResult<BlockNumber> {
+        self.tx
+            .cursor_read::<tables::CanonicalHeaders>()?
+            .last()?
+            .map(|(tip, _)| tip)
+            .or_else(|| {
+                self.static_file_provider.get_highest_static_file_block(StaticFileSegment::Headers)
+            })
+            .ok_or(ProviderError::BestBlockNotFound)
+    }
+
     fn history_info<T, K>(
         &self,
         key: K,
@@ -258,17 +371,47 @@ impl<'b, TX: DbTx> BlockHashReader for HistoricalStateProviderRef<'b, TX> {
 
 impl<'b, TX: DbTx> StateRootProvider for HistoricalStateProviderRef<'b, TX> {
     fn state_root(&self, state: &BundleState) -> ProviderResult<B256> {
-        let mut revert_state = self.revert_state()?;
-        revert_state.extend(HashedPostState::from_bundle_state(&state.state));
-        revert_state.state_root(self.tx).map_err(|err| ProviderError::Database(err.into()))
+        let (hashed_state, trie_reverts) = self.hashed_state_with_trie_reverts(state)?;
+        match trie_reverts {
+            Some((trie_reverts, prefix_sets)) => {
+                let hashed_state = hashed_state.into_sorted();
+                StateRoot::from_tx(self.tx)
+                    .with_trie_cursor_factory(InMemoryTrieCursorFactory::new(
+                        self.tx,
+                        &trie_reverts,
+                    ))
+                    .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                        self.tx,
+                        &hashed_state,
+                    ))
+                    .with_prefix_sets(prefix_sets.freeze())
+                    .root()
+            }
+            None => hashed_state.state_root(self.tx),
+        }
+        .map_err(|err| ProviderError::Database(err.into()))
     }
 
     fn state_root_with_updates(&self, state: &BundleState) -> ProviderResult<(B256, TrieUpdates)> {
-        let mut revert_state = self.revert_state()?;
-        revert_state.extend(HashedPostState::from_bundle_state(&state.state));
-        revert_state
-            .state_root_with_updates(self.tx)
-            .map_err(|err| ProviderError::Database(err.into()))
+        let (hashed_state, trie_reverts) = self.hashed_state_with_trie_reverts(state)?;
+        match trie_reverts {
+            Some((trie_reverts, prefix_sets)) => {
+                let hashed_state = hashed_state.into_sorted();
+                StateRoot::from_tx(self.tx)
+                    .with_trie_cursor_factory(InMemoryTrieCursorFactory::new(
+                        self.tx,
+                        &trie_reverts,
+                    ))
+                    .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                        self.tx,
+                        &hashed_state,
+                    ))
+                    .with_prefix_sets(prefix_sets.freeze())
+                    .root_with_updates()
+            }
+            None => hashed_state.state_root_with_updates(self.tx),
+        }
+        .map_err(|err| ProviderError::Database(err.into()))
     }
 }
 
@@ -280,11 +423,51 @@ impl<'b, TX: DbTx> StateProofProvider for HistoricalStateProviderRef<'b, TX> {
         address: Address,
         slots: &[B256],
     ) -> ProviderResult<AccountProof> {
-        let mut revert_state = self.revert_state()?;
-        revert_state.extend(HashedPostState::from_bundle_state(&state.state));
-        revert_state
-            .account_proof(self.tx, address, slots)
-            .map_err(|err| ProviderError::Database(err.into()))
+        let (hashed_state, trie_reverts) = self.hashed_state_with_trie_reverts(state)?;
+        match trie_reverts {
+            Some((trie_reverts, prefix_sets)) => {
+                let hashed_state = hashed_state.into_sorted();
+                Proof::from_tx(self.tx)
+                    .with_trie_cursor_factory(InMemoryTrieCursorFactory::new(
+                        self.tx,
+                        &trie_reverts,
+                    ))
+                    .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                        self.tx,
+                        &hashed_state,
+                    ))
+                    .with_prefix_sets_mut(prefix_sets)
+                    .account_proof(address, slots)
+            }
+            None => hashed_state.account_proof(self.tx, address, slots),
+        }
+        .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    fn witness(
+        &self,
+        overlay: &BundleState,
+        target: &HashedPostState,
+    ) -> ProviderResult<HashMap<B256, Bytes>> {
+        let (hashed_state, trie_reverts) = self.hashed_state_with_trie_reverts(overlay)?;
+        match trie_reverts {
+            Some((trie_reverts, prefix_sets)) => {
+                let hashed_state = hashed_state.into_sorted();
+                TrieWitness::from_tx(self.tx)
+                    .with_trie_cursor_factory(InMemoryTrieCursorFactory::new(
+                        self.tx,
+                        &trie_reverts,
+                    ))
+                    .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                        self.tx,
+                        &hashed_state,
+                    ))
+                    .with_prefix_sets_mut(prefix_sets)
+                    .compute(target)
+            }
+            None => hashed_state.witness(self.tx, target),
+        }
+        .map_err(|err| ProviderError::Database(err.into()))
     }
 }
 
@@ -337,6 +520,10 @@ pub struct HistoricalStateProvider<TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Optional checkpoints of the reverts of historical blocks.
+    revert_checkpoints: Option<RevertCheckpoints>,
+    /// Whether the trie as of the block number is read from the trie changesets.
+    archive_trie: bool,
 }
 
 impl<TX: DbTx> HistoricalStateProvider<TX> {
@@ -346,7 +533,31 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            revert_checkpoints: None,
+            archive_trie: false,
+        }
+    }
+
+    /// Sets the checkpoints used to revert the state to the block number, see
+    /// [`RevertCheckpoints`].
+    pub fn with_revert_checkpoints(
+        mut self,
+        revert_checkpoints: Option<RevertCheckpoints>,
+    ) -> Self {
+        self.revert_checkpoints = revert_checkpoints;
+        self
+    }
+
+    /// Sets whether the trie as of the block number is read from the trie changesets when
+    /// available, see [`HistoricalStateProviderRef::with_archive_trie`].
+    pub const fn with_archive_trie(mut self, archive_trie: bool) -> Self {
+        self.archive_trie = archive_trie;
+        self
     }
 
     /// Set the lowest block number at which the account history is available.
@@ -376,6 +587,8 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
             self.lowest_available_blocks,
             self.static_file_provider.clone(),
         )
+        .with_revert_checkpoints(self.revert_checkpoints.clone())
+        .with_archive_trie(self.archive_trie)
     }
 }
 
@@ -415,16 +628,18 @@ mod tests {
     use crate::{
         providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
         test_utils::create_test_provider_factory,
-        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
-        StaticFileProviderFactory,
+        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProofProvider,
+        StateProvider, StateRootProvider, StaticFileProviderFactory,
     };
     use reth_db::{tables, BlockNumberList};
     use reth_db_api::{
         models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
         transaction::{DbTx, DbTxMut},
     };
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{address, b256, keccak256, Account, Address, StorageEntry, B256, U256};
     use reth_storage_errors::provider::ProviderError;
+    use reth_trie::StateRoot;
+    use revm::db::BundleState;
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -727,4 +942,82 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn archive_trie_matches_rebuilt_trie() {
+        let factory = create_test_provider_factory();
+        let addresses = (0..64).map(Address::with_last_byte).collect::<Vec<_>>();
+        let account = |balance: u64| Account { balance: U256::from(balance), ..Default::default() };
+
+        // the genesis trie is written without changesets
+        let mut roots = Vec::new();
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            let tx = provider_rw.tx_ref();
+            tx.put::<tables::CanonicalHeaders>(0, B256::ZERO).unwrap();
+            for address in &addresses {
+                tx.put::<tables::PlainAccountState>(*address, account(1)).unwrap();
+                tx.put::<tables::HashedAccounts>(keccak256(address), account(1)).unwrap();
+            }
+            let (root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+            updates.write_to_database(tx).unwrap();
+            roots.push(root);
+            provider_rw.commit().unwrap();
+        }
+
+        // block 3 is written without archive-trie mode, so the trie is only archived from block 4
+        for block_number in 1..=7u64 {
+            let archive_trie = block_number != 3;
+            let provider_rw =
+                factory.clone().with_archive_trie(archive_trie).provider_rw().unwrap();
+            let tx = provider_rw.tx_ref();
+            tx.put::<tables::CanonicalHeaders>(
+                block_number,
+                B256::with_last_byte(block_number as u8),
+            )
+            .unwrap();
+            for address in addresses.iter().skip(block_number as usize).step_by(7) {
+                let previous = tx.get::<tables::PlainAccountState>(*address).unwrap();
+                tx.put::<tables::AccountChangeSets>(
+                    block_number,
+                    AccountBeforeTx { address: *address, info: previous },
+                )
+                .unwrap();
+                if block_number % 2 == 0 {
+                    tx.delete::<tables::PlainAccountState>(*address, None).unwrap();
+                    tx.delete::<tables::HashedAccounts>(keccak256(address), None).unwrap();
+                } else {
+                    tx.put::<tables::PlainAccountState>(*address, account(block_number)).unwrap();
+                    tx.put::<tables::HashedAccounts>(keccak256(address), account(block_number))
+                        .unwrap();
+                }
+            }
+            let (root, updates) =
+                StateRoot::incremental_root_with_updates(tx, block_number..=block_number).unwrap();
+            provider_rw.write_trie_updates(updates, block_number).unwrap();
+            roots.push(root);
+            provider_rw.commit().unwrap();
+        }
+
+        let tx = factory.provider().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+        for block_number in 1..=7u64 {
+            let archive =
+                HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone())
+                    .with_archive_trie(true);
+            let rebuilt =
+                HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone());
+            assert_eq!(archive.trie_reverts().unwrap().is_some(), block_number >= 5);
+
+            let root = archive.state_root(&BundleState::default()).unwrap();
+            assert_eq!(root, roots[block_number as usize - 1]);
+            assert_eq!(root, rebuilt.state_root(&BundleState::default()).unwrap());
+            for address in &addresses {
+                assert_eq!(
+                    archive.proof(&BundleState::default(), *address, &[]).unwrap(),
+                    rebuilt.proof(&BundleState::default(), *address, &[]).unwrap()
+                );
+            }
+        }
+    }
 }
@@ -8,12 +8,14 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_primitives::{
-    Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey, StorageValue, B256,
+    Account, Address, BlockNumber, Bytecode, Bytes, StaticFileSegment, StorageKey, StorageValue,
+    B256,
 };
 use reth_storage_api::StateProofProvider;
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
 use revm::db::BundleState;
+use std::collections::HashMap;
 
 /// State provider over latest state that takes tx reference.
 #[derive(Debug)]
@@ -102,6 +104,16 @@ impl<'b, TX: DbTx> StateProofProvider for LatestStateProviderRef<'b, TX> {
             .account_proof(self.tx, address, slots)
             .map_err(Into::<reth_db::DatabaseError>::into)?)
     }
+
+    fn witness(
+        &self,
+        overlay: &BundleState,
+        target: &HashedPostState,
+    ) -> ProviderResult<HashMap<B256, Bytes>> {
+        Ok(HashedPostState::from_bundle_state(&overlay.state)
+            .witness(self.tx, target)
+            .map_err(Into::<reth_db::DatabaseError>::into)?)
+    }
 }
 
 impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
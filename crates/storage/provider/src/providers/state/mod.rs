@@ -1,4 +1,5 @@
 //! [`StateProvider`](crate::StateProvider) implementations
+pub(crate) mod checkpoints;
 pub(crate) mod historical;
 pub(crate) mod latest;
 pub(crate) mod macros;
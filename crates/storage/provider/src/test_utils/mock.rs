@@ -17,7 +17,7 @@ use reth_primitives::{
 };
 use reth_storage_api::StateProofProvider;
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
-use reth_trie::{updates::TrieUpdates, AccountProof};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
 use revm::{
     db::BundleState,
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
@@ -563,6 +563,14 @@ impl StateProofProvider for MockEthProvider {
     ) -> ProviderResult<AccountProof> {
         Ok(AccountProof::new(address))
     }
+
+    fn witness(
+        &self,
+        _overlay: &BundleState,
+        _target: &HashedPostState,
+    ) -> ProviderResult<HashMap<B256, Bytes>> {
+        Ok(HashMap::default())
+    }
 }
 
 impl StateProvider for MockEthProvider {
@@ -1,7 +1,8 @@
-use reth_primitives::{Address, B256};
+use reth_primitives::{Address, Bytes, B256};
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
 use revm::db::BundleState;
+use std::collections::HashMap;
 
 /// A type that can compute the state root of a given post state.
 #[auto_impl::auto_impl(&, Box, Arc)]
@@ -34,4 +35,12 @@ pub trait StateProofProvider: Send + Sync {
         address: Address,
         slots: &[B256],
     ) -> ProviderResult<AccountProof>;
+
+    /// Get the trie nodes required to apply the target state on top of the `BundleState` and the
+    /// current state, keyed by their hash.
+    fn witness(
+        &self,
+        overlay: &BundleState,
+        target: &HashedPostState,
+    ) -> ProviderResult<HashMap<B256, Bytes>>;
 }
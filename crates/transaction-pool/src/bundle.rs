@@ -0,0 +1,352 @@
+//! A pool of transaction bundles.
+//!
+//! Bundles are submitted via `eth_sendBundle` and target a specific block. Unlike transactions in
+//! the [`Pool`](crate::Pool), bundles are not validated on admission: they are only executed by a
+//! bundle-aware payload builder when it builds the block a bundle targets.
+
+use parking_lot::RwLock;
+use reth_primitives::{keccak256, TransactionSignedEcRecovered, TxHash, B256};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tracing::trace;
+
+/// The default maximum number of bundles in the [`BundlePool`].
+pub const DEFAULT_MAX_BUNDLES: usize = 1024;
+
+/// The default maximum number of transactions of a single bundle.
+pub const DEFAULT_MAX_BUNDLE_TRANSACTIONS: usize = 100;
+
+/// The default maximum number of blocks a bundle can target ahead of the current block.
+pub const DEFAULT_MAX_BUNDLE_BLOCKS_AHEAD: u64 = 25;
+
+/// A bundle of transactions that must be included in a block in the given order, without any
+/// other transactions in between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MevBundle {
+    /// The transactions of the bundle.
+    pub transactions: Vec<TransactionSignedEcRecovered>,
+    /// The number of the block the bundle is valid for.
+    pub block_number: u64,
+    /// The minimum timestamp of the including block.
+    pub min_timestamp: Option<u64>,
+    /// The maximum timestamp of the including block.
+    pub max_timestamp: Option<u64>,
+    /// Hashes of the transactions that are allowed to revert.
+    pub reverting_tx_hashes: HashSet<TxHash>,
+    /// The UUID that can be used to replace or cancel the bundle.
+    pub replacement_uuid: Option<String>,
+}
+
+impl MevBundle {
+    /// Returns the hash of the bundle, the keccak256 hash of the concatenated transaction hashes.
+    pub fn hash(&self) -> B256 {
+        let mut hash_bytes = Vec::with_capacity(self.transactions.len() * 32);
+        for tx in &self.transactions {
+            hash_bytes.extend_from_slice(tx.hash().as_slice());
+        }
+        keccak256(hash_bytes)
+    }
+
+    /// Returns true if the transaction with the given hash is allowed to revert.
+    pub fn can_revert(&self, tx_hash: &TxHash) -> bool {
+        self.reverting_tx_hashes.contains(tx_hash)
+    }
+
+    /// Returns true if the bundle can be included in the block with the given number and
+    /// timestamp.
+    pub fn matches_block(&self, number: u64, timestamp: u64) -> bool {
+        self.block_number == number &&
+            self.min_timestamp.map_or(true, |min| timestamp >= min) &&
+            self.max_timestamp.map_or(true, |max| timestamp <= max)
+    }
+}
+
+/// Configuration of the [`BundlePool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundlePoolConfig {
+    /// Maximum number of bundles in the pool.
+    pub max_bundles: usize,
+    /// Maximum number of transactions of a single bundle.
+    pub max_bundle_transactions: usize,
+    /// Maximum number of blocks a bundle can target ahead of the current block.
+    pub max_blocks_ahead: u64,
+}
+
+impl Default for BundlePoolConfig {
+    fn default() -> Self {
+        Self {
+            max_bundles: DEFAULT_MAX_BUNDLES,
+            max_bundle_transactions: DEFAULT_MAX_BUNDLE_TRANSACTIONS,
+            max_blocks_ahead: DEFAULT_MAX_BUNDLE_BLOCKS_AHEAD,
+        }
+    }
+}
+
+/// Errors that can occur when adding a bundle to the [`BundlePool`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BundlePoolError {
+    /// The bundle has no transactions.
+    #[error("bundle has no transactions")]
+    EmptyBundle,
+    /// The bundle has more transactions than allowed.
+    #[error("bundle has {0} transactions, exceeding the limit of {1}")]
+    TooManyTransactions(usize, usize),
+    /// The bundle contains a blob transaction, these can't be bundled because their sidecars are
+    /// not part of the bundle.
+    #[error("bundle contains blob transaction {0}")]
+    BlobTransaction(TxHash),
+    /// The pool reached its maximum number of bundles.
+    #[error("bundle pool is full")]
+    PoolFull,
+}
+
+/// A pool of [`MevBundle`]s, shared between the RPC that accepts bundles and the payload builder
+/// that includes them.
+///
+/// Bundles are kept until they are cancelled or [removed as stale](Self::remove_stale). Adding a
+/// bundle with the [replacement UUID](MevBundle::replacement_uuid) of a bundle in the pool
+/// replaces it.
+#[derive(Debug, Clone, Default)]
+pub struct BundlePool {
+    inner: Arc<RwLock<BundlePoolInner>>,
+}
+
+#[derive(Debug, Default)]
+struct BundlePoolInner {
+    config: BundlePoolConfig,
+    /// All bundles by their hash.
+    bundles: HashMap<B256, Arc<MevBundle>>,
+    /// Hashes of the bundles with a replacement UUID.
+    by_uuid: HashMap<String, B256>,
+}
+
+impl BundlePoolInner {
+    fn remove(&mut self, hash: &B256) -> Option<Arc<MevBundle>> {
+        let bundle = self.bundles.remove(hash)?;
+        if let Some(uuid) = &bundle.replacement_uuid {
+            if self.by_uuid.get(uuid) == Some(hash) {
+                self.by_uuid.remove(uuid);
+            }
+        }
+        Some(bundle)
+    }
+}
+
+impl BundlePool {
+    /// Creates a new, empty pool with the given configuration.
+    pub fn new(config: BundlePoolConfig) -> Self {
+        Self { inner: Arc::new(RwLock::new(BundlePoolInner { config, ..Default::default() })) }
+    }
+
+    /// Returns the configuration of the pool.
+    pub fn config(&self) -> BundlePoolConfig {
+        self.inner.read().config
+    }
+
+    /// Adds the bundle to the pool, replacing the bundle with the same replacement UUID, if any.
+    ///
+    /// Returns the hash of the bundle.
+    pub fn add_bundle(&self, bundle: MevBundle) -> Result<B256, BundlePoolError> {
+        if bundle.transactions.is_empty() {
+            return Err(BundlePoolError::EmptyBundle)
+        }
+        if let Some(tx) = bundle.transactions.iter().find(|tx| tx.is_eip4844()) {
+            return Err(BundlePoolError::BlobTransaction(tx.hash()))
+        }
+
+        let hash = bundle.hash();
+        let mut inner = self.inner.write();
+        let max_txs = inner.config.max_bundle_transactions;
+        if bundle.transactions.len() > max_txs {
+            return Err(BundlePoolError::TooManyTransactions(bundle.transactions.len(), max_txs))
+        }
+
+        if let Some(replaced) =
+            bundle.replacement_uuid.as_ref().and_then(|uuid| inner.by_uuid.get(uuid).copied())
+        {
+            trace!(target: "txpool::bundle", ?replaced, ?hash, "replacing bundle");
+            inner.remove(&replaced);
+        }
+        inner.remove(&hash);
+
+        if inner.bundles.len() >= inner.config.max_bundles {
+            return Err(BundlePoolError::PoolFull)
+        }
+
+        if let Some(uuid) = &bundle.replacement_uuid {
+            inner.by_uuid.insert(uuid.clone(), hash);
+        }
+        inner.bundles.insert(hash, Arc::new(bundle));
+
+        Ok(hash)
+    }
+
+    /// Removes the bundle with the given replacement UUID.
+    ///
+    /// Returns `false` if there's no such bundle.
+    pub fn cancel_bundle(&self, replacement_uuid: &str) -> bool {
+        let mut inner = self.inner.write();
+        let Some(hash) = inner.by_uuid.get(replacement_uuid).copied() else { return false };
+        inner.remove(&hash).is_some()
+    }
+
+    /// Removes the bundle with the given hash.
+    ///
+    /// Returns `false` if there's no such bundle.
+    pub fn remove_bundle(&self, hash: &B256) -> bool {
+        self.inner.write().remove(hash).is_some()
+    }
+
+    /// Returns the bundle with the given hash.
+    pub fn get(&self, hash: &B256) -> Option<Arc<MevBundle>> {
+        self.inner.read().bundles.get(hash).cloned()
+    }
+
+    /// Returns all bundles that can be included in the block with the given number and timestamp.
+    pub fn bundles_for_block(&self, number: u64, timestamp: u64) -> Vec<Arc<MevBundle>> {
+        self.inner
+            .read()
+            .bundles
+            .values()
+            .filter(|bundle| bundle.matches_block(number, timestamp))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes all bundles that target the block with the given number or an earlier one.
+    ///
+    /// Returns the number of removed bundles.
+    pub fn remove_stale(&self, block_number: u64) -> usize {
+        let mut inner = self.inner.write();
+        let stale = inner
+            .bundles
+            .iter()
+            .filter(|(_, bundle)| bundle.block_number <= block_number)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in &stale {
+            inner.remove(hash);
+        }
+        stale.len()
+    }
+
+    /// Returns the number of bundles in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.read().bundles.len()
+    }
+
+    /// Returns true if the pool has no bundles.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().bundles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        Address, Signature, Transaction, TransactionSigned, TxEip4844, TxLegacy,
+    };
+
+    fn transaction(nonce: u64) -> TransactionSignedEcRecovered {
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy { nonce, ..Default::default() }),
+            Signature::default(),
+        );
+        TransactionSignedEcRecovered::from_signed_transaction(tx, Address::random())
+    }
+
+    fn bundle(block_number: u64, nonces: &[u64]) -> MevBundle {
+        MevBundle {
+            transactions: nonces.iter().copied().map(transaction).collect(),
+            block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: HashSet::new(),
+            replacement_uuid: None,
+        }
+    }
+
+    #[test]
+    fn add_and_select_bundles() {
+        let pool = BundlePool::default();
+        let first = pool.add_bundle(bundle(10, &[0, 1])).unwrap();
+        let mut timed = bundle(10, &[2]);
+        timed.max_timestamp = Some(100);
+        pool.add_bundle(timed).unwrap();
+        pool.add_bundle(bundle(11, &[3])).unwrap();
+
+        assert_eq!(pool.len(), 3);
+        assert_eq!(pool.bundles_for_block(10, 100).len(), 2);
+        let late = pool.bundles_for_block(10, 101);
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].hash(), first);
+
+        assert_eq!(pool.remove_stale(10), 2);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.get(&first).is_none());
+    }
+
+    #[test]
+    fn replace_and_cancel_by_uuid() {
+        let pool = BundlePool::default();
+        let mut original = bundle(10, &[0]);
+        original.replacement_uuid = Some("uuid".to_string());
+        let original = pool.add_bundle(original).unwrap();
+
+        let mut replacement = bundle(10, &[1]);
+        replacement.replacement_uuid = Some("uuid".to_string());
+        let replacement = pool.add_bundle(replacement).unwrap();
+
+        assert_eq!(pool.len(), 1);
+        assert!(pool.get(&original).is_none());
+        assert!(pool.get(&replacement).is_some());
+
+        assert!(!pool.cancel_bundle("other"));
+        assert!(pool.cancel_bundle("uuid"));
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn remove_by_hash() {
+        let pool = BundlePool::default();
+        let mut bundle = bundle(10, &[0]);
+        bundle.replacement_uuid = Some("uuid".to_string());
+        let hash = pool.add_bundle(bundle).unwrap();
+
+        assert!(!pool.remove_bundle(&B256::ZERO));
+        assert!(pool.remove_bundle(&hash));
+        assert!(pool.is_empty());
+        // the uuid no longer refers to the removed bundle
+        assert!(!pool.cancel_bundle("uuid"));
+    }
+
+    #[test]
+    fn reject_invalid_bundles() {
+        let pool = BundlePool::new(BundlePoolConfig {
+            max_bundles: 1,
+            max_bundle_transactions: 2,
+            ..Default::default()
+        });
+        assert_eq!(pool.add_bundle(bundle(10, &[])), Err(BundlePoolError::EmptyBundle));
+        assert_eq!(
+            pool.add_bundle(bundle(10, &[0, 1, 2])),
+            Err(BundlePoolError::TooManyTransactions(3, 2))
+        );
+
+        let mut blob = bundle(10, &[]);
+        let blob_tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip4844(TxEip4844::default()),
+            Signature::default(),
+        );
+        let blob_hash = blob_tx.hash();
+        blob.transactions
+            .push(TransactionSignedEcRecovered::from_signed_transaction(blob_tx, Address::ZERO));
+        assert_eq!(pool.add_bundle(blob), Err(BundlePoolError::BlobTransaction(blob_hash)));
+
+        pool.add_bundle(bundle(10, &[0])).unwrap();
+        assert_eq!(pool.add_bundle(bundle(10, &[1])), Err(BundlePoolError::PoolFull));
+    }
+}
@@ -0,0 +1,174 @@
+//! Conditions of transactions submitted via `eth_sendRawTransactionConditional`.
+
+use reth_primitives::{Address, B256};
+use reth_provider::{ProviderResult, StateProvider};
+use revm::db::BundleState;
+use std::collections::HashMap;
+
+/// Maximum [cost](TransactionConditional::known_accounts_cost) of the known accounts of a
+/// conditional transaction, same as op-geth's `TransactionConditionalMaxCost`.
+///
+/// Every known account is checked against the state whenever its storage changes, so the number
+/// of storage roots and slots a transaction can expect is bounded.
+pub const MAX_TRANSACTION_CONDITIONAL_COST: usize = 1_000;
+
+/// The expected storage of an account in a [`TransactionConditional`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnownAccount {
+    /// The expected storage root of the account.
+    StorageRoot(B256),
+    /// The expected values of individual storage slots of the account.
+    Slots(HashMap<B256, B256>),
+}
+
+/// Conditions that must hold for a transaction to be included in a block.
+///
+/// The transaction is only valid if all [`KnownAccount`]s match the state it is executed on and
+/// the block number and timestamp of the including block are within the configured bounds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionConditional {
+    /// The expected storage of accounts.
+    pub known_accounts: HashMap<Address, KnownAccount>,
+    /// Minimum block number of the including block.
+    pub block_number_min: Option<u64>,
+    /// Maximum block number of the including block.
+    pub block_number_max: Option<u64>,
+    /// Minimum timestamp of the including block.
+    pub timestamp_min: Option<u64>,
+    /// Maximum timestamp of the including block.
+    pub timestamp_max: Option<u64>,
+}
+
+impl TransactionConditional {
+    /// Returns true if a block with the given number satisfies the block number bounds.
+    pub fn matches_block_number(&self, number: u64) -> bool {
+        self.block_number_min.map_or(true, |min| number >= min) &&
+            self.block_number_max.map_or(true, |max| number <= max)
+    }
+
+    /// Returns true if a block with the given timestamp satisfies the timestamp bounds.
+    pub fn matches_timestamp(&self, timestamp: u64) -> bool {
+        self.timestamp_min.map_or(true, |min| timestamp >= min) &&
+            self.timestamp_max.map_or(true, |max| timestamp <= max)
+    }
+
+    /// Returns true if no block building on the block with the given number and timestamp can
+    /// satisfy the bounds anymore.
+    pub fn is_expired(&self, number: u64, timestamp: u64) -> bool {
+        self.block_number_max.is_some_and(|max| max <= number) ||
+            self.timestamp_max.is_some_and(|max| max <= timestamp)
+    }
+
+    /// Returns the number of storage roots and slots that need to be checked against the state.
+    pub fn known_accounts_cost(&self) -> usize {
+        self.known_accounts
+            .values()
+            .map(|account| match account {
+                KnownAccount::StorageRoot(_) => 1,
+                KnownAccount::Slots(slots) => slots.len(),
+            })
+            .sum()
+    }
+
+    /// Returns true if the cost of the known accounts exceeds
+    /// [`MAX_TRANSACTION_CONDITIONAL_COST`].
+    pub fn exceeds_max_cost(&self) -> bool {
+        self.known_accounts_cost() > MAX_TRANSACTION_CONDITIONAL_COST
+    }
+
+    /// Returns true if the storage of all known accounts matches the given state.
+    pub fn matches_state<P: StateProvider + ?Sized>(&self, state: &P) -> ProviderResult<bool> {
+        for (address, account) in &self.known_accounts {
+            match account {
+                KnownAccount::StorageRoot(root) => {
+                    let proof = state.proof(&BundleState::default(), *address, &[])?;
+                    if proof.storage_root != *root {
+                        return Ok(false)
+                    }
+                }
+                KnownAccount::Slots(slots) => {
+                    for (slot, value) in slots {
+                        let current = state.storage(*address, *slot)?.unwrap_or_default();
+                        if B256::from(current) != *value {
+                            return Ok(false)
+                        }
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{StorageKey, U256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    #[test]
+    fn block_bounds() {
+        let conditional = TransactionConditional {
+            block_number_min: Some(10),
+            block_number_max: Some(20),
+            timestamp_max: Some(1000),
+            ..Default::default()
+        };
+
+        assert!(!conditional.matches_block_number(9));
+        assert!(conditional.matches_block_number(10));
+        assert!(conditional.matches_block_number(20));
+        assert!(!conditional.matches_block_number(21));
+        assert!(conditional.matches_timestamp(0));
+        assert!(!conditional.matches_timestamp(1001));
+
+        assert!(!conditional.is_expired(19, 999));
+        assert!(conditional.is_expired(20, 999));
+        assert!(conditional.is_expired(19, 1000));
+    }
+
+    #[test]
+    fn max_cost() {
+        let mut conditional = TransactionConditional::default();
+        conditional.known_accounts.insert(
+            Address::with_last_byte(1),
+            KnownAccount::Slots(
+                (0..MAX_TRANSACTION_CONDITIONAL_COST as u64)
+                    .map(|slot| (B256::from(U256::from(slot)), B256::ZERO))
+                    .collect(),
+            ),
+        );
+        assert!(!conditional.exceeds_max_cost());
+
+        conditional
+            .known_accounts
+            .insert(Address::with_last_byte(2), KnownAccount::StorageRoot(B256::ZERO));
+        assert_eq!(conditional.known_accounts_cost(), MAX_TRANSACTION_CONDITIONAL_COST + 1);
+        assert!(conditional.exceeds_max_cost());
+    }
+
+    #[test]
+    fn known_slots() {
+        let address = Address::random();
+        let slot = StorageKey::with_last_byte(1);
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage([(slot, U256::from(7))]),
+        );
+
+        let mut conditional = TransactionConditional::default();
+        conditional.known_accounts.insert(
+            address,
+            KnownAccount::Slots(HashMap::from([(slot, B256::from(U256::from(7)))])),
+        );
+        assert!(conditional.matches_state(&provider).unwrap());
+        assert_eq!(conditional.known_accounts_cost(), 1);
+
+        conditional.known_accounts.insert(
+            address,
+            KnownAccount::Slots(HashMap::from([(slot, B256::from(U256::from(8)))])),
+        );
+        assert!(!conditional.matches_state(&provider).unwrap());
+    }
+}
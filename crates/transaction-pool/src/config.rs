@@ -1,4 +1,4 @@
-use crate::{PoolSize, TransactionOrigin};
+use crate::{PoolSize, RateLimit, TransactionOrderingConfig, TransactionOrigin};
 use reth_primitives::{Address, EIP4844_TX_TYPE_ID};
 use std::collections::HashSet;
 /// Guarantees max transactions for one sender, compatible with geth/erigon
@@ -10,6 +10,9 @@ pub const TXPOOL_SUBPOOL_MAX_TXS_DEFAULT: usize = 10_000;
 /// The default maximum allowed size of the given subpool.
 pub const TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT: usize = 20;
 
+/// The default number of recently removed transactions that are tracked.
+pub const TXPOOL_MAX_REMOVED_TRANSACTIONS: usize = 1024;
+
 /// Default price bump (in %) for the transaction pool underpriced check.
 pub const DEFAULT_PRICE_BUMP: u128 = 10;
 
@@ -36,6 +39,16 @@ pub struct PoolConfig {
     /// How to handle locally received transactions:
     /// [`TransactionOrigin::Local`](crate::TransactionOrigin).
     pub local_transactions_config: LocalTransactionConfig,
+    /// How transactions are ordered in the pool.
+    pub ordering: TransactionOrderingConfig,
+    /// Max number of replacements per sender within an interval.
+    ///
+    /// Replacements of [local](crate::TransactionOrigin::Local) senders are exempt. Unlimited if
+    /// `None`.
+    pub replacement_rate_limit: Option<RateLimit>,
+    /// Max number of recently removed transactions that are tracked, see
+    /// [`TransactionPool::removed_transactions`](crate::TransactionPool::removed_transactions).
+    pub max_removed_transactions: usize,
 }
 
 impl PoolConfig {
@@ -59,6 +72,9 @@ impl Default for PoolConfig {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bumps: Default::default(),
             local_transactions_config: Default::default(),
+            ordering: Default::default(),
+            replacement_rate_limit: None,
+            max_removed_transactions: TXPOOL_MAX_REMOVED_TRANSACTIONS,
         }
     }
 }
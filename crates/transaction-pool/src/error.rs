@@ -49,6 +49,10 @@ pub enum PoolErrorKind {
     /// Thrown when the number of unique transactions of a sender exceeded the slot capacity.
     #[error("rejected due to {0} being identified as a spammer")]
     SpammerExceededCapacity(Address),
+    /// Thrown when a sender replaced more transactions than the configured
+    /// [`PoolConfig::replacement_rate_limit`](crate::PoolConfig::replacement_rate_limit) allows.
+    #[error("rejected due to {0} exceeding the replacement rate limit")]
+    ReplacementRateLimitExceeded(Address),
     /// Thrown when a new transaction is added to the pool, but then immediately discarded to
     /// respect the size limits of the pool.
     #[error("transaction discarded outright due to pool size constraints")]
@@ -116,6 +120,11 @@ impl PoolError {
                 // (pool lags behind) and old transaction still occupy a slot in the pool
                 false
             }
+            PoolErrorKind::ReplacementRateLimitExceeded(_) => {
+                // the sender replaced too many transactions, but the peer that relayed the
+                // replacement can't know about the replacements the sender sent to other peers
+                false
+            }
             PoolErrorKind::DiscardedOnInsert => {
                 // valid tx but dropped due to size constraints
                 false
@@ -205,6 +214,15 @@ pub enum InvalidPoolTransactionError {
     /// invocation.
     #[error("intrinsic gas too low")]
     IntrinsicGasTooLow,
+    /// Thrown if the [`TransactionConditional`](crate::TransactionConditional) of a transaction
+    /// doesn't hold anymore.
+    #[error("transaction conditions not met")]
+    ConditionNotMet,
+    /// Thrown if the known accounts of a
+    /// [`TransactionConditional`](crate::TransactionConditional) exceed the
+    /// [`MAX_TRANSACTION_CONDITIONAL_COST`](crate::MAX_TRANSACTION_CONDITIONAL_COST).
+    #[error("conditional cost {0} exceeds maximum allowed")]
+    ConditionCostExceeded(usize),
 }
 
 // === impl InvalidPoolTransactionError ===
@@ -262,6 +280,14 @@ impl InvalidPoolTransactionError {
             }
             Self::IntrinsicGasTooLow => true,
             Self::Overdraft => false,
+            Self::ConditionNotMet => {
+                // the conditions depend on the current state
+                false
+            }
+            Self::ConditionCostExceeded(_) => {
+                // conditions are only submitted via rpc
+                false
+            }
             Self::Other(err) => err.is_bad_transaction(),
             Self::Eip4844(eip4844_err) => {
                 match eip4844_err {
@@ -0,0 +1,439 @@
+//! Persistent journal of all transactions in the pool.
+//!
+//! Unlike the local transactions backup of the [maintain](crate::maintain) module, the journal
+//! contains all transactions of the pool, including the sidecars of blob transactions, and is
+//! written periodically so that the pool survives crashes as well as restarts.
+
+use crate::{
+    maintain::{load_and_reinsert_transactions, TransactionsBackupError},
+    TransactionOrigin, TransactionPool,
+};
+use alloy_rlp::{BufMut, Decodable, Encodable, Header};
+use reth_primitives::{FromRecoveredPooledTransaction, PooledTransactionsElement};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tracing::{debug, error, info, trace, warn};
+
+/// Default interval at which the journal is written.
+pub const DEFAULT_JOURNAL_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Settings for the transaction journal task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionJournalConfig {
+    /// Path to the journal file.
+    pub path: PathBuf,
+    /// Interval at which a snapshot of the pool is written to the journal.
+    pub snapshot_interval: Duration,
+    /// Path to the backup of local transactions, which is reinserted on startup and then
+    /// superseded by the journal.
+    pub local_transactions_path: Option<PathBuf>,
+}
+
+impl TransactionJournalConfig {
+    /// Creates a new config that writes the journal to the given path every
+    /// [`DEFAULT_JOURNAL_SNAPSHOT_INTERVAL`].
+    pub const fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            snapshot_interval: DEFAULT_JOURNAL_SNAPSHOT_INTERVAL,
+            local_transactions_path: None,
+        }
+    }
+
+    /// Sets the interval at which a snapshot of the pool is written to the journal.
+    ///
+    /// The interval must not be zero.
+    pub const fn with_snapshot_interval(mut self, snapshot_interval: Duration) -> Self {
+        self.snapshot_interval = snapshot_interval;
+        self
+    }
+
+    /// Sets the path to the backup of local transactions of the
+    /// [`backup_local_transactions_task`](crate::maintain::backup_local_transactions_task).
+    ///
+    /// The backup is reinserted on startup and removed afterwards, since its transactions are
+    /// included in the journal from then on.
+    pub fn with_local_transactions_backup(mut self, path: PathBuf) -> Self {
+        self.local_transactions_path = Some(path);
+        self
+    }
+}
+
+/// A transaction in the journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// Where the transaction originates from.
+    pub origin: TransactionOrigin,
+    /// The transaction, including the sidecar if it is a blob transaction.
+    pub transaction: PooledTransactionsElement,
+}
+
+impl JournalEntry {
+    const fn origin_id(&self) -> u8 {
+        match self.origin {
+            TransactionOrigin::Local => 0,
+            TransactionOrigin::External => 1,
+            TransactionOrigin::Private => 2,
+        }
+    }
+
+    fn payload_length(&self) -> usize {
+        self.origin_id().length() + self.transaction.length()
+    }
+}
+
+impl Encodable for JournalEntry {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.origin_id().encode(out);
+        self.transaction.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for JournalEntry {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let origin = match u8::decode(buf)? {
+            0 => TransactionOrigin::Local,
+            1 => TransactionOrigin::External,
+            2 => TransactionOrigin::Private,
+            _ => return Err(alloy_rlp::Error::Custom("unknown transaction origin")),
+        };
+        let transaction = PooledTransactionsElement::decode(buf)?;
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(Self { origin, transaction })
+    }
+}
+
+/// Returns all transactions of the pool with their sidecars and origins.
+fn snapshot<P>(pool: &P) -> Vec<JournalEntry>
+where
+    P: TransactionPool,
+{
+    let all = pool.all_sub_pool_transactions();
+    all.pending
+        .into_iter()
+        .chain(all.basefee)
+        .chain(all.blob)
+        .chain(all.queued)
+        // the conditions of conditional transactions aren't persisted, they'd be restored without
+        // them. This includes private transactions, which would lose their deadline.
+        .filter(|tx| !tx.transaction.origin.is_private() && tx.transaction.conditional().is_none())
+        .filter_map(|tx| {
+            // this also fetches the sidecar of blob transactions from the blob store
+            let transaction = pool.get_pooled_transaction_element(*tx.transaction.hash())?;
+            Some(JournalEntry { origin: tx.transaction.origin, transaction })
+        })
+        .collect()
+}
+
+/// Writes a snapshot of all transactions in the pool to the journal.
+///
+/// The snapshot is written to a temporary file first, which then replaces the journal, so that a
+/// crash while writing never leaves a corrupted journal behind.
+fn write_journal<P>(pool: &P, path: &Path) -> Result<usize, TransactionsBackupError>
+where
+    P: TransactionPool,
+{
+    let entries = snapshot(pool);
+
+    let mut buf = Vec::new();
+    alloy_rlp::encode_list(&entries, &mut buf);
+
+    if let Some(parent) = path.parent() {
+        reth_fs_util::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    reth_fs_util::write(&tmp_path, buf)?;
+    reth_fs_util::rename(&tmp_path, path)?;
+
+    Ok(entries.len())
+}
+
+/// Reads the journal and reinserts all transactions into the pool with their original origin.
+///
+/// The transactions are validated again by the pool's validator before they are inserted.
+async fn load_journal<P>(pool: &P, path: &Path) -> Result<(), TransactionsBackupError>
+where
+    P: TransactionPool,
+{
+    if !path.exists() {
+        return Ok(())
+    }
+
+    debug!(target: "txpool", journal=?path, "Loading transactions from journal");
+    let data = reth_fs_util::read(path)?;
+    if data.is_empty() {
+        return Ok(())
+    }
+
+    let entries: Vec<JournalEntry> = Decodable::decode(&mut data.as_slice())?;
+    let num_entries = entries.len();
+
+    let mut local = Vec::new();
+    let mut external = Vec::new();
+    let mut private = Vec::new();
+    for JournalEntry { origin, transaction } in entries {
+        let Ok(transaction) = transaction.try_into_ecrecovered() else { continue };
+        let transaction =
+            <P::Transaction as FromRecoveredPooledTransaction>::from_recovered_pooled_transaction(
+                transaction,
+            );
+        match origin {
+            TransactionOrigin::Local => local.push(transaction),
+            TransactionOrigin::External => external.push(transaction),
+            TransactionOrigin::Private => private.push(transaction),
+        }
+    }
+
+    let mut num_inserted = 0;
+    for (origin, transactions) in [
+        (TransactionOrigin::Local, local),
+        (TransactionOrigin::Private, private),
+        (TransactionOrigin::External, external),
+    ] {
+        if transactions.is_empty() {
+            continue
+        }
+        num_inserted += pool
+            .add_transactions(origin, transactions)
+            .await
+            .into_iter()
+            .filter(Result::is_ok)
+            .count();
+    }
+
+    info!(target: "txpool", journal=?path, num_entries, num_inserted, "Reinserted transactions from journal");
+    Ok(())
+}
+
+/// Task which manages the transaction journal.
+///
+/// Reinserts the transactions of the journal into the pool on startup, then periodically writes a
+/// snapshot of the pool to the journal, and a final one on shutdown.
+pub async fn transaction_journal_task<P>(
+    shutdown: reth_tasks::shutdown::GracefulShutdown,
+    pool: P,
+    config: TransactionJournalConfig,
+) where
+    P: TransactionPool + 'static,
+{
+    let TransactionJournalConfig { path, snapshot_interval, local_transactions_path } = config;
+
+    if let Some(local_transactions_path) = local_transactions_path {
+        if let Err(err) =
+            load_and_reinsert_transactions(pool.clone(), &local_transactions_path).await
+        {
+            error!(target: "txpool", %err, "Failed to load local transactions backup");
+        }
+    }
+    if let Err(err) = load_journal(&pool, &path).await {
+        error!(target: "txpool", %err, journal=?path, "Failed to load transaction journal");
+    }
+
+    let mut interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + snapshot_interval,
+        snapshot_interval,
+    );
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let mut shutdown = std::pin::pin!(shutdown);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let (pool, path) = (pool.clone(), path.clone());
+                let res = tokio::task::spawn_blocking(move || write_journal(&pool, &path)).await;
+                match res {
+                    Ok(Ok(num_txs)) => {
+                        trace!(target: "txpool", num_txs, "Wrote transaction journal");
+                    }
+                    Ok(Err(err)) => {
+                        warn!(target: "txpool", %err, "Failed to write transaction journal");
+                    }
+                    Err(err) => {
+                        warn!(target: "txpool", %err, "Transaction journal task failed");
+                    }
+                }
+            }
+            guard = &mut shutdown => {
+                match write_journal(&pool, &path) {
+                    Ok(num_txs) => {
+                        info!(target: "txpool", num_txs, journal=?path, "Wrote transaction journal");
+                    }
+                    Err(err) => {
+                        warn!(target: "txpool", %err, journal=?path, "Failed to write transaction journal");
+                    }
+                }
+                drop(guard);
+                break
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blobstore::InMemoryBlobStore,
+        validate::{EthTransactionValidator, EthTransactionValidatorBuilder},
+        BlockInfo, CoinbaseTipOrdering, EthPooledTransaction, Pool, PoolTransaction,
+        TransactionConditional, TransactionPoolExt,
+    };
+    use reth_chainspec::MAINNET;
+    use reth_primitives::{hex, U256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_tasks::TaskManager;
+
+    fn transaction() -> PooledTransactionsElement {
+        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+        PooledTransactionsElement::decode_enveloped(&mut &tx_bytes[..]).unwrap()
+    }
+
+    fn pooled_transaction() -> EthPooledTransaction {
+        EthPooledTransaction::from_recovered_pooled_transaction(
+            transaction().try_into_ecrecovered().unwrap(),
+        )
+    }
+
+    /// Returns a new pool in which the sender of [`transaction`] is funded.
+    fn funded_pool() -> Pool<
+        EthTransactionValidator<MockEthProvider, EthPooledTransaction>,
+        CoinbaseTipOrdering<EthPooledTransaction>,
+        InMemoryBlobStore,
+    > {
+        let provider = MockEthProvider::default();
+        let sender = hex!("1f9090aaE28b8a3dCeaDf281B0F12828e676c326").into();
+        provider.add_account(sender, ExtendedAccount::new(42, U256::MAX));
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, blob_store.clone());
+        Pool::new(validator, CoinbaseTipOrdering::default(), blob_store, Default::default())
+    }
+
+    #[test]
+    fn encode_decode_entry() {
+        for origin in
+            [TransactionOrigin::Local, TransactionOrigin::External, TransactionOrigin::Private]
+        {
+            let entry = JournalEntry { origin, transaction: transaction() };
+            let encoded = alloy_rlp::encode(&entry);
+            assert_eq!(encoded.len(), entry.length());
+            assert_eq!(JournalEntry::decode(&mut encoded.as_slice()).unwrap(), entry);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn write_and_load_journal() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("txpool-journal.rlp");
+
+        let pool = funded_pool();
+        let transaction = pooled_transaction();
+        pool.add_transaction(TransactionOrigin::External, transaction.clone()).await.unwrap();
+
+        assert_eq!(write_journal(&pool, &path).unwrap(), 1);
+
+        let restored = funded_pool();
+        load_journal(&restored, &path).await.unwrap();
+        let restored_txs = restored.get_transactions_by_origin(TransactionOrigin::External);
+        assert_eq!(restored_txs.len(), 1);
+        assert_eq!(restored_txs[0].hash(), transaction.hash());
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn journal_includes_all_sub_pools() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("txpool-journal.rlp");
+
+        let pool = funded_pool();
+
+        // the fee cap of the transaction is below the base fee, so it is parked in the basefee
+        // sub-pool
+        pool.set_block_info(BlockInfo { pending_basefee: u64::MAX, ..pool.block_info() });
+        let transaction = pooled_transaction();
+        pool.add_transaction(TransactionOrigin::External, transaction).await.unwrap();
+        assert_eq!(pool.all_sub_pool_transactions().basefee.len(), 1);
+
+        assert_eq!(write_journal(&pool, &path).unwrap(), 1);
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn journal_excludes_conditional_transactions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("txpool-journal.rlp");
+
+        let pool = funded_pool();
+
+        let mut transaction = pooled_transaction();
+        transaction.set_conditional(TransactionConditional {
+            block_number_max: Some(100),
+            ..Default::default()
+        });
+        pool.add_transaction(TransactionOrigin::Local, transaction).await.unwrap();
+        assert_eq!(pool.len(), 1);
+
+        // the conditions can't be restored, so the transaction isn't journaled
+        assert_eq!(write_journal(&pool, &path).unwrap(), 0);
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn journal_task_reinserts_local_transactions_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("txpool-journal.rlp");
+        let backup_path = temp_dir.path().join("txpool-transactions-backup.rlp");
+
+        let pool = funded_pool();
+
+        // a backup written by the local transactions backup task
+        let transaction = transaction().into_transaction();
+        reth_fs_util::write(&backup_path, alloy_rlp::encode(vec![transaction.clone()])).unwrap();
+
+        let manager = TaskManager::new(tokio::runtime::Handle::current());
+        let config = TransactionJournalConfig::new(path.clone())
+            .with_local_transactions_backup(backup_path.clone());
+        manager.executor().spawn_critical_with_graceful_shutdown_signal("test task", |shutdown| {
+            transaction_journal_task(shutdown, pool.clone(), config)
+        });
+
+        // the backup is removed once it is reinserted
+        while backup_path.exists() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let local = pool.get_local_transactions();
+        assert_eq!(local.len(), 1);
+        assert_eq!(local[0].hash(), &transaction.hash());
+
+        // the journal is written on shutdown
+        manager.graceful_shutdown();
+        assert!(path.exists());
+
+        temp_dir.close().unwrap();
+    }
+}
@@ -153,24 +153,34 @@ use crate::{identifier::TransactionId, pool::PoolInner};
 use aquamarine as _;
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash, U256};
-use reth_provider::StateProviderFactory;
+use reth_provider::{StateProvider, StateProviderFactory};
 use std::{collections::HashSet, sync::Arc};
 use tokio::sync::mpsc::Receiver;
 use tracing::{instrument, trace};
 
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
+    bundle::{BundlePool, BundlePoolConfig, BundlePoolError, MevBundle},
+    conditional::{KnownAccount, TransactionConditional, MAX_TRANSACTION_CONDITIONAL_COST},
     config::{
         LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
         REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-        TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+        TXPOOL_MAX_REMOVED_TRANSACTIONS, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+        TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
-    ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
+    ordering::{
+        CoinbaseTipOrdering, ConfiguredOrdering, EffectiveGasPriceOrdering, FifoOrdering, Priority,
+        PriorityListOrdering, TransactionOrdering, TransactionOrderingConfig,
+        TransactionOrderingStrategy,
+    },
     pool::{
-        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
+        blob_tx_priority, fee_delta,
+        state::{SubPool, TxState},
+        AllTransactionsEvents, FullTransactionEvent, RemovalReason, RemovedTransaction,
         TransactionEvent, TransactionEvents,
     },
+    rate_limit::{RateLimit, RateLimiter},
     traits::*,
     validate::{
         EthTransactionValidator, TransactionValidationOutcome, TransactionValidationTaskExecutor,
@@ -179,6 +189,7 @@ pub use crate::{
 };
 
 pub mod error;
+pub mod journal;
 pub mod maintain;
 pub mod metrics;
 pub mod noop;
@@ -186,9 +197,12 @@ pub mod pool;
 pub mod validate;
 
 pub mod blobstore;
+pub mod bundle;
+mod conditional;
 mod config;
 pub mod identifier;
 mod ordering;
+mod rate_limit;
 mod traits;
 
 #[cfg(any(test, feature = "test-utils"))]
@@ -198,7 +212,7 @@ pub mod test_utils;
 /// Type alias for default ethereum transaction pool
 pub type EthTransactionPool<Client, S> = Pool<
     TransactionValidationTaskExecutor<EthTransactionValidator<Client, EthPooledTransaction>>,
-    CoinbaseTipOrdering<EthPooledTransaction>,
+    ConfiguredOrdering<EthPooledTransaction>,
     S,
 >;
 
@@ -279,7 +293,8 @@ where
     S: BlobStore,
 {
     /// Returns a new [Pool] that uses the default [`TransactionValidationTaskExecutor`] when
-    /// validating [`EthPooledTransaction`]s and ords via [`CoinbaseTipOrdering`]
+    /// validating [`EthPooledTransaction`]s and orders them via the [`ConfiguredOrdering`] of the
+    /// [`PoolConfig`], which is [`CoinbaseTipOrdering`] by default.
     ///
     /// # Example
     ///
@@ -311,7 +326,8 @@ where
         blob_store: S,
         config: PoolConfig,
     ) -> Self {
-        Self::new(validator, CoinbaseTipOrdering::default(), blob_store, config)
+        let ordering = ConfiguredOrdering::new(config.ordering.clone());
+        Self::new(validator, ordering, blob_store, config)
     }
 }
 
@@ -450,6 +466,14 @@ where
         self.pool.all_transactions()
     }
 
+    fn all_sub_pool_transactions(&self) -> AllSubPoolTransactions<Self::Transaction> {
+        self.pool.all_sub_pool_transactions()
+    }
+
+    fn removed_transactions(&self) -> Vec<RemovedTransaction> {
+        self.pool.removed_transactions()
+    }
+
     fn remove_transactions(
         &self,
         hashes: Vec<TxHash>,
@@ -543,6 +567,18 @@ where
         self.pool.update_accounts(accounts);
     }
 
+    fn remove_unmet_conditional_transactions(
+        &self,
+        state: &dyn StateProvider,
+        accounts: &HashSet<Address>,
+    ) -> Vec<TxHash> {
+        self.pool
+            .remove_unmet_conditional_transactions(state, accounts)
+            .into_iter()
+            .map(|tx| *tx.hash())
+            .collect()
+    }
+
     fn delete_blob(&self, tx: TxHash) {
         self.pool.delete_blob(tx)
     }
@@ -355,6 +355,12 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 };
                 pool.on_canonical_state_change(update);
 
+                // storage changed in either the old or the new chain may differ at the new tip
+                let changed_storage: HashSet<_> = changed_storage_iter(old_state)
+                    .chain(changed_storage_iter(new_state))
+                    .collect();
+                remove_unmet_conditional_transactions(&client, &pool, &changed_storage);
+
                 // all transactions that were mined in the old chain but not in the new chain need
                 // to be re-injected
                 //
@@ -436,6 +442,9 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 };
                 pool.on_canonical_state_change(update);
 
+                let changed_storage: HashSet<_> = changed_storage_iter(state).collect();
+                remove_unmet_conditional_transactions(&client, &pool, &changed_storage);
+
                 // keep track of mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&blocks);
             }
@@ -565,10 +574,46 @@ fn changed_accounts_iter(
         .map(|(address, acc)| ChangedAccount { address, nonce: acc.nonce, balance: acc.balance })
 }
 
+/// Extracts all accounts with changed storage from the `BundleState`
+fn changed_storage_iter(
+    execution_outcome: &ExecutionOutcome,
+) -> impl Iterator<Item = Address> + '_ {
+    execution_outcome
+        .bundle_accounts_iter()
+        .filter(|(_, acc)| !acc.storage.is_empty() || acc.status.was_destroyed())
+        .map(|(address, _)| address)
+}
+
+/// Removes all conditional transactions that expect the storage of any of the given accounts and
+/// no longer match the latest state.
+fn remove_unmet_conditional_transactions<Client, P>(
+    client: &Client,
+    pool: &P,
+    changed_storage: &HashSet<Address>,
+) where
+    Client: StateProviderFactory,
+    P: TransactionPoolExt,
+{
+    if changed_storage.is_empty() {
+        return
+    }
+    match client.latest() {
+        Ok(state) => {
+            let removed = pool.remove_unmet_conditional_transactions(&*state, changed_storage);
+            if !removed.is_empty() {
+                trace!(target: "txpool", count=removed.len(), "removed conditional transactions");
+            }
+        }
+        Err(err) => {
+            debug!(target: "txpool", %err, "failed to load state to check transaction conditions")
+        }
+    }
+}
+
 /// Loads transactions from a file, decodes them from the RLP format, and inserts them
 /// into the transaction pool on node boot up.
 /// The file is removed after the transactions have been successfully processed.
-async fn load_and_reinsert_transactions<P>(
+pub(crate) async fn load_and_reinsert_transactions<P>(
     pool: P,
     file_path: &Path,
 ) -> Result<(), TransactionsBackupError>
@@ -608,7 +653,12 @@ fn save_local_txs_backup<P>(pool: P, file_path: &Path)
 where
     P: TransactionPool,
 {
-    let local_transactions = pool.get_local_transactions();
+    // conditional transactions are skipped, they'd be restored without their conditions
+    let local_transactions = pool
+        .get_local_transactions()
+        .into_iter()
+        .filter(|tx| tx.conditional().is_none())
+        .collect::<Vec<_>>();
     if local_transactions.is_empty() {
         trace!(target: "txpool", "no local transactions to save");
         return
@@ -110,4 +110,6 @@ pub struct AllTransactionsMetrics {
     pub(crate) blob_base_fee: Gauge,
     /// The current base fee
     pub(crate) base_fee: Gauge,
+    /// Number of replacements rejected by the replacement rate limit
+    pub(crate) rate_limited_replacements: Counter,
 }
@@ -11,10 +11,11 @@ use crate::{
         TransactionListenerKind,
     },
     validate::ValidTransaction,
-    AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPoolTransaction,
-    EthPooledTransaction, NewTransactionEvent, PoolResult, PoolSize, PoolTransaction,
-    PooledTransactionsElement, PropagatedTransactions, TransactionEvents, TransactionOrigin,
-    TransactionPool, TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
+    AllPoolTransactions, AllSubPoolTransactions, AllTransactionsEvents, BestTransactions,
+    BlockInfo, EthPoolTransaction, EthPooledTransaction, NewTransactionEvent, PoolResult, PoolSize,
+    PoolTransaction, PooledTransactionsElement, PropagatedTransactions, RemovedTransaction,
+    TransactionEvents, TransactionOrigin, TransactionPool, TransactionValidationOutcome,
+    TransactionValidator, ValidPoolTransaction,
 };
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, TxHash, U256};
@@ -173,6 +174,14 @@ impl TransactionPool for NoopTransactionPool {
         AllPoolTransactions::default()
     }
 
+    fn all_sub_pool_transactions(&self) -> AllSubPoolTransactions<Self::Transaction> {
+        AllSubPoolTransactions::default()
+    }
+
+    fn removed_transactions(&self) -> Vec<RemovedTransaction> {
+        Vec::new()
+    }
+
     fn remove_transactions(
         &self,
         _hashes: Vec<TxHash>,
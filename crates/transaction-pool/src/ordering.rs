@@ -1,6 +1,6 @@
-use crate::traits::PoolTransaction;
-use reth_primitives::U256;
-use std::{fmt, marker::PhantomData};
+use crate::{traits::PoolTransaction, ValidPoolTransaction};
+use reth_primitives::{Address, U256};
+use std::{collections::HashSet, fmt, marker::PhantomData, str::FromStr, time::Instant};
 
 /// Priority of the transaction that can be missing.
 ///
@@ -39,6 +39,20 @@ pub trait TransactionOrdering: Send + Sync + 'static {
         transaction: &Self::Transaction,
         base_fee: u64,
     ) -> Priority<Self::PriorityValue>;
+
+    /// Returns the priority score for the given transaction of the pool.
+    ///
+    /// Unlike [`TransactionOrdering::priority`], this also has access to the metadata the pool
+    /// tracks for the transaction, like the time it was added to the pool.
+    ///
+    /// By default this is the same as [`TransactionOrdering::priority`].
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        self.priority(&transaction.transaction, base_fee)
+    }
 }
 
 /// Default ordering for the pool.
@@ -79,3 +93,443 @@ impl<T> Clone for CoinbaseTipOrdering<T> {
         Self::default()
     }
 }
+
+/// Orders transactions by the time they were added to the pool.
+///
+/// The earlier a transaction was added to the pool, the higher its priority, regardless of the fees
+/// it pays. Transactions that can't pay the base fee are still ranked lower.
+#[derive(Debug)]
+pub struct FifoOrdering<T> {
+    /// Reference point for the arrival times of the transactions.
+    epoch: Instant,
+    _marker: PhantomData<T>,
+}
+
+impl<T> TransactionOrdering for FifoOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    /// Without the arrival time all transactions have the same priority, so they're ordered by the
+    /// order they were submitted to the pool.
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        transaction.effective_tip_per_gas(base_fee).map(|_| U256::ZERO).into()
+    }
+
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        transaction
+            .transaction
+            .effective_tip_per_gas(base_fee)
+            .map(|_| {
+                let age = transaction.timestamp.saturating_duration_since(self.epoch);
+                U256::MAX - U256::from(age.as_nanos())
+            })
+            .into()
+    }
+}
+
+impl<T> Default for FifoOrdering<T> {
+    fn default() -> Self {
+        Self { epoch: Instant::now(), _marker: PhantomData }
+    }
+}
+
+impl<T> Clone for FifoOrdering<T> {
+    fn clone(&self) -> Self {
+        Self { epoch: self.epoch, _marker: PhantomData }
+    }
+}
+
+/// Orders transactions by the effective gas price they pay, including blob fees.
+///
+/// The effective gas price is `base_fee + effective_tip`. For EIP-4844 transactions the max blob
+/// fee is added, spread over the gas limit of the transaction.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct EffectiveGasPriceOrdering<T>(PhantomData<T>);
+
+impl<T> TransactionOrdering for EffectiveGasPriceOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        let Some(tip) = transaction.effective_tip_per_gas(base_fee) else { return Priority::None };
+        let mut price = U256::from(base_fee) + U256::from(tip);
+
+        if let (Some(blob_fee), Some(blob_gas)) =
+            (transaction.max_fee_per_blob_gas(), transaction.blob_gas_used())
+        {
+            let gas_limit = transaction.gas_limit().max(1);
+            price += U256::from(blob_fee) * U256::from(blob_gas) / U256::from(gas_limit);
+        }
+
+        Priority::Value(price)
+    }
+}
+
+impl<T> Default for EffectiveGasPriceOrdering<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T> Clone for EffectiveGasPriceOrdering<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// Wraps another ordering and ranks transactions of whitelisted senders, or to whitelisted
+/// contracts, above all other transactions.
+///
+/// Transactions within the same group are ordered by the wrapped ordering.
+#[derive(Debug, Clone)]
+pub struct PriorityListOrdering<O> {
+    /// The ordering of transactions within the same group.
+    inner: O,
+    /// Senders whose transactions are prioritized.
+    senders: HashSet<Address>,
+    /// Recipients whose transactions are prioritized.
+    recipients: HashSet<Address>,
+}
+
+impl<O> PriorityListOrdering<O> {
+    /// Creates a new ordering that prioritizes the given senders and recipients.
+    pub const fn new(inner: O, senders: HashSet<Address>, recipients: HashSet<Address>) -> Self {
+        Self { inner, senders, recipients }
+    }
+
+    /// Returns the wrapped ordering.
+    pub const fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    /// Returns true if the transaction is sent by a prioritized sender or to a prioritized
+    /// recipient.
+    pub fn is_prioritized<T: PoolTransaction>(&self, transaction: &T) -> bool {
+        self.senders.contains(&transaction.sender()) ||
+            transaction.to().map_or(false, |to| self.recipients.contains(&to))
+    }
+
+    fn boost<V: Ord + Clone>(prioritized: bool, priority: Priority<V>) -> Priority<(bool, V)> {
+        match priority {
+            Priority::Value(value) => Priority::Value((prioritized, value)),
+            Priority::None => Priority::None,
+        }
+    }
+}
+
+impl<O> TransactionOrdering for PriorityListOrdering<O>
+where
+    O: TransactionOrdering,
+{
+    type PriorityValue = (bool, O::PriorityValue);
+    type Transaction = O::Transaction;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        Self::boost(self.is_prioritized(transaction), self.inner.priority(transaction, base_fee))
+    }
+
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        Self::boost(
+            self.is_prioritized(&transaction.transaction),
+            self.inner.pool_priority(transaction, base_fee),
+        )
+    }
+}
+
+/// The built-in strategies to order transactions by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionOrderingStrategy {
+    /// Order by coinbase tip, see [`CoinbaseTipOrdering`].
+    #[default]
+    CoinbaseTip,
+    /// Order by arrival time, see [`FifoOrdering`].
+    Fifo,
+    /// Order by effective gas price including blob fees, see [`EffectiveGasPriceOrdering`].
+    EffectiveGasPrice,
+}
+
+impl TransactionOrderingStrategy {
+    /// Returns the name of the strategy.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::CoinbaseTip => "coinbase-tip",
+            Self::Fifo => "fifo",
+            Self::EffectiveGasPrice => "effective-gas-price",
+        }
+    }
+}
+
+impl fmt::Display for TransactionOrderingStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TransactionOrderingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "coinbase-tip" => Ok(Self::CoinbaseTip),
+            "fifo" => Ok(Self::Fifo),
+            "effective-gas-price" => Ok(Self::EffectiveGasPrice),
+            _ => Err(format!("unknown transaction ordering strategy: {s}")),
+        }
+    }
+}
+
+/// Configures how the pool orders transactions, see [`ConfiguredOrdering`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionOrderingConfig {
+    /// The strategy to order transactions by.
+    pub strategy: TransactionOrderingStrategy,
+    /// Senders whose transactions are ranked above all other transactions.
+    pub priority_senders: HashSet<Address>,
+    /// Recipients whose transactions are ranked above all other transactions.
+    pub priority_recipients: HashSet<Address>,
+}
+
+/// The ordering for the pool selected by a [`TransactionOrderingConfig`].
+#[derive(Debug)]
+pub struct ConfiguredOrdering<T> {
+    inner: PriorityListOrdering<StrategyOrdering<T>>,
+}
+
+impl<T> Clone for ConfiguredOrdering<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T> ConfiguredOrdering<T> {
+    /// Creates the ordering selected by the given config.
+    pub fn new(config: TransactionOrderingConfig) -> Self {
+        let TransactionOrderingConfig { strategy, priority_senders, priority_recipients } = config;
+        let strategy = match strategy {
+            TransactionOrderingStrategy::CoinbaseTip => {
+                StrategyOrdering::CoinbaseTip(Default::default())
+            }
+            TransactionOrderingStrategy::Fifo => StrategyOrdering::Fifo(Default::default()),
+            TransactionOrderingStrategy::EffectiveGasPrice => {
+                StrategyOrdering::EffectiveGasPrice(Default::default())
+            }
+        };
+        Self { inner: PriorityListOrdering::new(strategy, priority_senders, priority_recipients) }
+    }
+
+    /// Returns the strategy transactions are ordered by.
+    pub const fn strategy(&self) -> TransactionOrderingStrategy {
+        match self.inner.inner() {
+            StrategyOrdering::CoinbaseTip(_) => TransactionOrderingStrategy::CoinbaseTip,
+            StrategyOrdering::Fifo(_) => TransactionOrderingStrategy::Fifo,
+            StrategyOrdering::EffectiveGasPrice(_) => {
+                TransactionOrderingStrategy::EffectiveGasPrice
+            }
+        }
+    }
+}
+
+impl<T> Default for ConfiguredOrdering<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T> TransactionOrdering for ConfiguredOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = (bool, U256);
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        self.inner.priority(transaction, base_fee)
+    }
+
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        self.inner.pool_priority(transaction, base_fee)
+    }
+}
+
+/// Dispatches to the ordering of a [`TransactionOrderingStrategy`].
+#[derive(Debug)]
+enum StrategyOrdering<T> {
+    CoinbaseTip(CoinbaseTipOrdering<T>),
+    Fifo(FifoOrdering<T>),
+    EffectiveGasPrice(EffectiveGasPriceOrdering<T>),
+}
+
+impl<T> Clone for StrategyOrdering<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::CoinbaseTip(ordering) => Self::CoinbaseTip(ordering.clone()),
+            Self::Fifo(ordering) => Self::Fifo(ordering.clone()),
+            Self::EffectiveGasPrice(ordering) => Self::EffectiveGasPrice(ordering.clone()),
+        }
+    }
+}
+
+impl<T> TransactionOrdering for StrategyOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        match self {
+            Self::CoinbaseTip(ordering) => ordering.priority(transaction, base_fee),
+            Self::Fifo(ordering) => ordering.priority(transaction, base_fee),
+            Self::EffectiveGasPrice(ordering) => ordering.priority(transaction, base_fee),
+        }
+    }
+
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        match self {
+            Self::CoinbaseTip(ordering) => ordering.pool_priority(transaction, base_fee),
+            Self::Fifo(ordering) => ordering.pool_priority(transaction, base_fee),
+            Self::EffectiveGasPrice(ordering) => ordering.pool_priority(transaction, base_fee),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{MockTransaction, MockTransactionFactory},
+        PoolTransaction,
+    };
+    use reth_primitives::{constants::eip4844::DATA_GAS_PER_BLOB, BlobTransactionSidecar};
+
+    #[test]
+    fn parse_strategy() {
+        for strategy in [
+            TransactionOrderingStrategy::CoinbaseTip,
+            TransactionOrderingStrategy::Fifo,
+            TransactionOrderingStrategy::EffectiveGasPrice,
+        ] {
+            assert_eq!(strategy.to_string().parse::<TransactionOrderingStrategy>(), Ok(strategy));
+        }
+        assert!("unknown".parse::<TransactionOrderingStrategy>().is_err());
+    }
+
+    #[test]
+    fn fifo_prefers_earlier_transactions() {
+        let mut f = MockTransactionFactory::default();
+        let ordering = FifoOrdering::<MockTransaction>::default();
+
+        let early = f.validated(MockTransaction::eip1559());
+        let mut late = f.validated(MockTransaction::eip1559().inc_price_by(1_000));
+        late.timestamp = early.timestamp + std::time::Duration::from_secs(1);
+
+        assert!(ordering.pool_priority(&early, 0) > ordering.pool_priority(&late, 0));
+
+        // transactions that can't pay the base fee are ranked lowest
+        let base_fee = early.transaction.max_fee_per_gas() as u64 + 1;
+        assert_eq!(ordering.pool_priority(&early, base_fee), Priority::None);
+    }
+
+    #[test]
+    fn effective_gas_price_includes_blob_fee() {
+        let ordering = EffectiveGasPriceOrdering::<MockTransaction>::default();
+
+        let tx = MockTransaction::eip1559().with_gas_limit(DATA_GAS_PER_BLOB);
+        let base_fee = 1;
+        let tip = tx.effective_tip_per_gas(base_fee).unwrap();
+        assert_eq!(
+            ordering.priority(&tx, base_fee),
+            Priority::Value(U256::from(base_fee as u128 + tip))
+        );
+
+        let sidecar = BlobTransactionSidecar {
+            blobs: vec![Default::default()],
+            commitments: vec![Default::default()],
+            proofs: vec![Default::default()],
+        };
+        let blob_tx = MockTransaction::eip4844_with_sidecar(sidecar)
+            .with_gas_limit(DATA_GAS_PER_BLOB)
+            .with_blob_fee(10);
+        let tip = blob_tx.effective_tip_per_gas(base_fee).unwrap();
+        assert_eq!(
+            ordering.priority(&blob_tx, base_fee),
+            Priority::Value(U256::from(base_fee as u128 + tip + 10))
+        );
+    }
+
+    #[test]
+    fn priority_list_boosts_senders_and_recipients() {
+        let sender_tx = MockTransaction::eip1559();
+        let recipient_tx = MockTransaction::eip1559();
+        let other_tx = MockTransaction::eip1559().inc_price_by(1_000);
+
+        let ordering = PriorityListOrdering::new(
+            CoinbaseTipOrdering::<MockTransaction>::default(),
+            HashSet::from([sender_tx.sender()]),
+            HashSet::from([recipient_tx.to().unwrap()]),
+        );
+
+        assert!(ordering.is_prioritized(&sender_tx));
+        assert!(ordering.is_prioritized(&recipient_tx));
+        assert!(!ordering.is_prioritized(&other_tx));
+        assert!(ordering.priority(&sender_tx, 0) > ordering.priority(&other_tx, 0));
+        assert!(ordering.priority(&recipient_tx, 0) > ordering.priority(&other_tx, 0));
+    }
+
+    #[test]
+    fn configured_ordering() {
+        let config = TransactionOrderingConfig {
+            strategy: TransactionOrderingStrategy::Fifo,
+            ..Default::default()
+        };
+        let ordering = ConfiguredOrdering::<MockTransaction>::new(config);
+        assert_eq!(ordering.strategy(), TransactionOrderingStrategy::Fifo);
+        assert_eq!(
+            ConfiguredOrdering::<MockTransaction>::default().strategy(),
+            TransactionOrderingStrategy::CoinbaseTip
+        );
+    }
+}
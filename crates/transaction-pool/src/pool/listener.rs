@@ -8,7 +8,7 @@ use crate::{
 use futures_util::Stream;
 use reth_primitives::{TxHash, B256};
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -78,6 +78,9 @@ pub(crate) struct PoolEventBroadcast<T: PoolTransaction> {
     all_events_broadcaster: AllPoolEventsBroadcaster<T>,
     /// All listeners for events for a certain transaction hash.
     broadcasters_by_hash: HashMap<TxHash, PoolEventBroadcaster>,
+    /// Hashes of private transactions, whose events are not broadcast to the listeners for all
+    /// transactions.
+    private_transactions: HashSet<TxHash>,
 }
 
 impl<T: PoolTransaction> Default for PoolEventBroadcast<T> {
@@ -85,6 +88,7 @@ impl<T: PoolTransaction> Default for PoolEventBroadcast<T> {
         Self {
             all_events_broadcaster: AllPoolEventsBroadcaster::default(),
             broadcasters_by_hash: HashMap::default(),
+            private_transactions: HashSet::default(),
         }
     }
 }
@@ -106,8 +110,23 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
             }
         }
 
-        // Broadcast to all listeners for all transactions.
-        self.all_events_broadcaster.broadcast(pool_event);
+        // Broadcast to all listeners for all transactions, unless the transaction is private.
+        let is_private = if event.is_final() {
+            self.private_transactions.remove(hash)
+        } else {
+            self.private_transactions.contains(hash)
+        };
+        if !is_private {
+            self.all_events_broadcaster.broadcast(pool_event);
+        }
+    }
+
+    /// Marks the transaction as private.
+    ///
+    /// Until its final event, the events of the transaction are only sent to the listeners for
+    /// its hash.
+    pub(crate) fn private(&mut self, tx: &TxHash) {
+        self.private_transactions.insert(*tx);
     }
 
     /// Create a new subscription for the given transaction hash.
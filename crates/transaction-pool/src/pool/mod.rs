@@ -74,8 +74,8 @@ use crate::{
         txpool::{SenderInfo, TxPool},
     },
     traits::{
-        AllPoolTransactions, BestTransactionsAttributes, BlockInfo, NewTransactionEvent, PoolSize,
-        PoolTransaction, PropagatedTransactions, TransactionOrigin,
+        AllPoolTransactions, AllSubPoolTransactions, BestTransactionsAttributes, BlockInfo,
+        NewTransactionEvent, PoolSize, PoolTransaction, PropagatedTransactions, TransactionOrigin,
     },
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
     CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering, TransactionValidator,
@@ -87,6 +87,7 @@ use reth_primitives::{
     Address, BlobTransaction, BlobTransactionSidecar, IntoRecoveredTransaction,
     PooledTransactionsElement, TransactionSigned, TxHash, B256,
 };
+use reth_provider::StateProvider;
 use std::{
     collections::{HashMap, HashSet},
     fmt,
@@ -99,7 +100,7 @@ mod events;
 use crate::{
     blobstore::BlobStore,
     metrics::BlobStoreMetrics,
-    pool::txpool::UpdateOutcome,
+    pool::{removed::RemovedTransactions, txpool::UpdateOutcome},
     traits::{GetPooledTransactionLimit, NewBlobSidecar, TransactionListenerKind},
     validate::ValidTransaction,
 };
@@ -109,12 +110,14 @@ pub use events::{FullTransactionEvent, TransactionEvent};
 pub use listener::{AllTransactionsEvents, TransactionEvents};
 pub use parked::{BasefeeOrd, ParkedOrd, ParkedPool, QueuedOrd};
 pub use pending::PendingPool;
+pub use removed::{RemovalReason, RemovedTransaction};
 
 mod best;
 mod blob;
 mod listener;
 mod parked;
 pub(crate) mod pending;
+mod removed;
 pub(crate) mod size;
 pub(crate) mod state;
 pub mod txpool;
@@ -149,6 +152,8 @@ where
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// The most recently removed transactions.
+    removed_transactions: Mutex<RemovedTransactions>,
 }
 
 // === impl PoolInner ===
@@ -169,6 +174,9 @@ where
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
             blob_transaction_sidecar_listener: Default::default(),
+            removed_transactions: Mutex::new(RemovedTransactions::new(
+                config.max_removed_transactions,
+            )),
             config,
             blob_store,
             blob_store_metrics: Default::default(),
@@ -309,6 +317,8 @@ where
     }
 
     /// Returns converted [`PooledTransactionsElement`] for the given transaction hashes.
+    ///
+    /// Private transactions are skipped, they must not leave the node.
     pub(crate) fn get_pooled_transaction_elements(
         &self,
         tx_hashes: Vec<TxHash>,
@@ -318,6 +328,10 @@ where
         let mut elements = Vec::with_capacity(transactions.len());
         let mut size = 0;
         for transaction in transactions {
+            // private and conditional transactions are never shared with peers
+            if transaction.origin.is_private() || transaction.conditional().is_some() {
+                continue
+            }
             let encoded_len = transaction.encoded_length();
             let tx = transaction.to_recovered_transaction().into_signed();
             let pooled = if tx.is_eip4844() {
@@ -352,18 +366,19 @@ where
     }
 
     /// Returns converted [`PooledTransactionsElement`] for the given transaction hash.
+    ///
+    /// Private transactions are skipped.
     pub(crate) fn get_pooled_transaction_element(
         &self,
         tx_hash: TxHash,
     ) -> Option<PooledTransactionsElement> {
-        self.get(&tx_hash).and_then(|transaction| {
-            let tx = transaction.to_recovered_transaction().into_signed();
-            if tx.is_eip4844() {
-                self.get_blob_transaction(tx).map(PooledTransactionsElement::BlobTransaction)
-            } else {
-                PooledTransactionsElement::try_from(tx).ok()
-            }
-        })
+        let transaction = self.get(&tx_hash).filter(|tx| !tx.origin.is_private())?;
+        let tx = transaction.to_recovered_transaction().into_signed();
+        if tx.is_eip4844() {
+            self.get_blob_transaction(tx).map(PooledTransactionsElement::BlobTransaction)
+        } else {
+            PooledTransactionsElement::try_from(tx).ok()
+        }
     }
 
     /// Updates the entire pool after a new block was executed.
@@ -388,6 +403,61 @@ where
 
         // notify listeners about updates
         self.notify_on_new_state(outcome);
+
+        // remove conditional transactions that can't be included in any future block
+        let expired = self
+            .pool
+            .read()
+            .conditional_transactions()
+            .into_iter()
+            .filter(|tx| {
+                tx.conditional().map_or(false, |conditional| {
+                    conditional.is_expired(new_tip.number, new_tip.timestamp)
+                })
+            })
+            .map(|tx| *tx.hash())
+            .collect();
+        self.remove_conditional_transactions(expired);
+    }
+
+    /// Removes all conditional transactions whose known accounts include any of the given
+    /// accounts and no longer match the given state.
+    pub(crate) fn remove_unmet_conditional_transactions(
+        &self,
+        state: &dyn StateProvider,
+        accounts: &HashSet<Address>,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        if accounts.is_empty() {
+            return Vec::new()
+        }
+
+        // check outside of the pool lock, this performs state lookups
+        let conditional_transactions = self.pool.read().conditional_transactions();
+        let mut unmet = Vec::new();
+        for tx in conditional_transactions {
+            let Some(conditional) = tx.conditional() else { continue };
+            if !conditional.known_accounts.keys().any(|address| accounts.contains(address)) {
+                continue
+            }
+            match conditional.matches_state(state) {
+                Ok(true) => {}
+                Ok(false) => unmet.push(*tx.hash()),
+                Err(err) => {
+                    debug!(target: "txpool", %err, tx=?tx.hash(), "failed to check conditions");
+                }
+            }
+        }
+        self.remove_conditional_transactions(unmet)
+    }
+
+    /// Removes the given conditional transactions whose conditions are no longer met.
+    fn remove_conditional_transactions(
+        &self,
+        hashes: Vec<TxHash>,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let removed = self.remove_transactions_with_reason(hashes, RemovalReason::ConditionNotMet);
+        self.delete_discarded_blobs(removed.iter());
+        removed
     }
 
     /// Performs account updates on the pool.
@@ -397,6 +467,7 @@ where
         let changed_senders = self.changed_senders(accounts.into_iter());
         let UpdateOutcome { promoted, discarded } =
             self.pool.write().update_accounts(changed_senders);
+        self.removed_transactions.lock().record_all(&discarded, RemovalReason::Outdated);
         let mut listener = self.event_listener.write();
 
         promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
@@ -470,6 +541,8 @@ where
                 // Notify tx event listeners
                 self.notify_event_listeners(&added);
 
+                self.record_removed(&added);
+
                 if let Some(discarded) = added.discarded_transactions() {
                     self.delete_discarded_blobs(discarded.iter());
                 }
@@ -480,12 +553,23 @@ where
                 Ok(hash)
             }
             TransactionValidationOutcome::Invalid(tx, err) => {
+                self.removed_transactions.lock().record(
+                    &tx,
+                    origin,
+                    RemovalReason::Invalid(err.to_string()),
+                );
                 let mut listener = self.event_listener.write();
+                if origin.is_private() {
+                    listener.private(tx.hash());
+                }
                 listener.discarded(tx.hash());
                 Err(PoolError::new(*tx.hash(), err))
             }
             TransactionValidationOutcome::Error(tx_hash, err) => {
                 let mut listener = self.event_listener.write();
+                if origin.is_private() {
+                    listener.private(&tx_hash);
+                }
                 listener.discarded(&tx_hash);
                 Err(PoolError::other(tx_hash, err))
             }
@@ -614,7 +698,13 @@ where
             })
         }
 
-        let OnNewCanonicalStateOutcome { mined, promoted, discarded, block_hash } = outcome;
+        let OnNewCanonicalStateOutcome { mined, pruned, promoted, discarded, block_hash } = outcome;
+
+        {
+            let mut removed = self.removed_transactions.lock();
+            removed.record_all(&pruned, RemovalReason::Mined { block_hash });
+            removed.record_all(&discarded, RemovalReason::Outdated);
+        }
 
         // broadcast specific transaction events
         let mut listener = self.event_listener.write();
@@ -632,11 +722,17 @@ where
             AddedTransaction::Pending(tx) => {
                 let AddedPendingTransaction { transaction, promoted, discarded, replaced } = tx;
 
+                if transaction.origin.is_private() {
+                    listener.private(transaction.hash());
+                }
                 listener.pending(transaction.hash(), replaced.clone());
                 promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
                 discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
             }
             AddedTransaction::Parked { transaction, replaced, .. } => {
+                if transaction.origin.is_private() {
+                    listener.private(transaction.hash());
+                }
                 listener.queued(transaction.hash());
                 if let Some(replaced) = replaced {
                     listener.replaced(replaced.clone(), *transaction.hash());
@@ -645,6 +741,21 @@ where
         }
     }
 
+    /// Records the transactions that were replaced or discarded when the transaction was added.
+    fn record_removed(&self, tx: &AddedTransaction<T::Transaction>) {
+        let mut removed = self.removed_transactions.lock();
+        if let Some(replaced) = tx.replaced() {
+            removed.record(
+                &replaced.transaction,
+                replaced.origin,
+                RemovalReason::Replaced { replaced_by: *tx.hash() },
+            );
+        }
+        if let Some(discarded) = tx.discarded_transactions() {
+            removed.record_all(discarded, RemovalReason::Outdated);
+        }
+    }
+
     /// Returns an iterator that yields transactions that are ready to be included in the block.
     pub(crate) fn best_transactions(&self) -> BestTransactions<T> {
         self.get_pool_data().best_transactions()
@@ -679,16 +790,37 @@ where
         }
     }
 
+    /// Returns all transactions in the pool grouped by sub-pool
+    pub(crate) fn all_sub_pool_transactions(&self) -> AllSubPoolTransactions<T::Transaction> {
+        self.get_pool_data().all_sub_pool_transactions()
+    }
+
+    /// Returns the most recently removed transactions, oldest first.
+    pub(crate) fn removed_transactions(&self) -> Vec<RemovedTransaction> {
+        self.removed_transactions.lock().entries()
+    }
+
     /// Removes and returns all matching transactions from the pool.
     pub(crate) fn remove_transactions(
         &self,
         hashes: Vec<TxHash>,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.remove_transactions_with_reason(hashes, RemovalReason::Removed)
+    }
+
+    /// Removes and returns all matching transactions from the pool, recording the given reason.
+    fn remove_transactions_with_reason(
+        &self,
+        hashes: Vec<TxHash>,
+        reason: RemovalReason,
     ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
         if hashes.is_empty() {
             return Vec::new()
         }
         let removed = self.pool.write().remove_transactions(hashes);
 
+        self.removed_transactions.lock().record_all(&removed, reason);
+
         let mut listener = self.event_listener.write();
 
         removed.iter().for_each(|tx| listener.discarded(tx.hash()));
@@ -778,6 +910,8 @@ where
     pub(crate) fn discard_worst(&self) -> HashSet<TxHash> {
         let discarded = self.pool.write().discard_worst();
 
+        self.removed_transactions.lock().record_all(&discarded, RemovalReason::Evicted);
+
         // delete any blobs associated with discarded blob transactions
         self.delete_discarded_blobs(discarded.iter());
 
@@ -1095,6 +1229,8 @@ pub(crate) struct OnNewCanonicalStateOutcome<T: PoolTransaction> {
     pub(crate) block_hash: B256,
     /// All mined transactions.
     pub(crate) mined: Vec<TxHash>,
+    /// Mined transactions that were removed from the pool.
+    pub(crate) pruned: Vec<Arc<ValidPoolTransaction<T>>>,
     /// Transactions promoted to the pending pool.
     pub(crate) promoted: Vec<Arc<ValidPoolTransaction<T>>>,
     /// transaction that were discarded during the update
@@ -1133,11 +1269,16 @@ impl<T: PoolTransaction> OnNewCanonicalStateOutcome<T> {
 mod tests {
     use crate::{
         blobstore::{BlobStore, InMemoryBlobStore},
+        error::InvalidPoolTransactionError,
         test_utils::{MockTransaction, TestPoolBuilder},
         validate::ValidTransaction,
-        BlockInfo, PoolConfig, SubPoolLimit, TransactionOrigin, TransactionValidationOutcome, U256,
+        BlockInfo, FullTransactionEvent, GetPooledTransactionLimit, PoolConfig, PoolTransaction,
+        RemovalReason, SubPoolLimit, TransactionOrigin, TransactionValidationOutcome, TxState,
+        U256,
+    };
+    use reth_primitives::{
+        constants::MIN_PROTOCOL_BASE_FEE, kzg::Blob, transaction::generate_blob_sidecar,
     };
-    use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar};
     use std::{fs, path::PathBuf};
 
     #[test]
@@ -1219,4 +1360,117 @@ mod tests {
         // Assert that the pool's blob store matches the expected blob store.
         assert_eq!(*test_pool.blob_store(), blob_store);
     }
+
+    #[test]
+    fn test_sub_pools_and_removed_transactions() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let valid = |tx: MockTransaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(u64::MAX),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(tx),
+            propagate: true,
+        };
+
+        let tx = MockTransaction::eip1559().with_gas_limit(21_000);
+        let gapped = tx.skip(1);
+        test_pool.add_transaction(TransactionOrigin::External, valid(tx.clone())).unwrap();
+        test_pool.add_transaction(TransactionOrigin::External, valid(gapped.clone())).unwrap();
+
+        let all = test_pool.all_sub_pool_transactions();
+        assert_eq!(all.pending.len(), 1);
+        assert_eq!(all.queued.len(), 1);
+        assert!(all.pending[0].state.contains(TxState::PENDING_POOL_BITS));
+        assert!(!all.queued[0].state.contains(TxState::NO_NONCE_GAPS));
+
+        let replacement = tx.inc_price_by(MIN_PROTOCOL_BASE_FEE as u128).rng_hash();
+        test_pool.add_transaction(TransactionOrigin::External, valid(replacement.clone())).unwrap();
+
+        let invalid = MockTransaction::eip1559();
+        test_pool
+            .add_transaction(
+                TransactionOrigin::External,
+                TransactionValidationOutcome::Invalid(
+                    invalid.clone(),
+                    InvalidPoolTransactionError::Underpriced,
+                ),
+            )
+            .unwrap_err();
+
+        test_pool.remove_transactions(vec![*gapped.hash()]);
+
+        let removed = test_pool.removed_transactions();
+        assert_eq!(removed.len(), 3);
+        assert_eq!(removed[0].hash, *tx.hash());
+        assert_eq!(removed[0].reason, RemovalReason::Replaced { replaced_by: *replacement.hash() });
+        assert_eq!(removed[1].hash, *invalid.hash());
+        assert_eq!(
+            removed[1].reason,
+            RemovalReason::Invalid(InvalidPoolTransactionError::Underpriced.to_string())
+        );
+        assert_eq!(removed[2].hash, *gapped.hash());
+        assert_eq!(removed[2].reason, RemovalReason::Removed);
+    }
+
+    #[test]
+    fn test_private_transactions_not_served_to_peers() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let valid = |tx: MockTransaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(u64::MAX),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(tx),
+            propagate: false,
+        };
+
+        let private = MockTransaction::eip1559();
+        let local = MockTransaction::eip1559();
+        test_pool.add_transaction(TransactionOrigin::Private, valid(private.clone())).unwrap();
+        test_pool.add_transaction(TransactionOrigin::Local, valid(local.clone())).unwrap();
+
+        let elements = test_pool.get_pooled_transaction_elements(
+            vec![*private.hash()],
+            GetPooledTransactionLimit::None,
+        );
+        assert!(elements.is_empty());
+
+        let elements = test_pool.get_pooled_transaction_elements(
+            vec![*private.hash(), *local.hash()],
+            GetPooledTransactionLimit::None,
+        );
+        assert_eq!(elements.len(), 1);
+    }
+
+    #[test]
+    fn test_private_transactions_not_exposed() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let valid = |tx: MockTransaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(u64::MAX),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(tx),
+            propagate: false,
+        };
+        let mut all_events = test_pool.add_all_transactions_event_listener();
+
+        let private = MockTransaction::eip1559();
+        let local = MockTransaction::eip1559();
+        test_pool.add_transaction(TransactionOrigin::Private, valid(private.clone())).unwrap();
+        test_pool.add_transaction(TransactionOrigin::Local, valid(local.clone())).unwrap();
+
+        assert!(test_pool.get_pooled_transaction_element(*private.hash()).is_none());
+        assert!(test_pool.get_pooled_transaction_element(*local.hash()).is_some());
+
+        test_pool.remove_transactions(vec![*private.hash(), *local.hash()]);
+        let removed = test_pool.removed_transactions();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].hash, *local.hash());
+
+        let mut events = Vec::new();
+        while let Ok(event) = all_events.events.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], FullTransactionEvent::Pending(hash) if hash == *local.hash()));
+        assert!(
+            matches!(events[1], FullTransactionEvent::Discarded(hash) if hash == *local.hash())
+        );
+    }
 }
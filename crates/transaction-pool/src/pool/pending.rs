@@ -115,7 +115,25 @@ impl<T: TransactionOrdering> PendingPool<T> {
         }
     }
 
+    /// Same as `best` but the transactions are ordered by their priority at the given base fee
+    /// instead of the base fee the pool currently tracks.
+    fn best_with_basefee(&self, base_fee: u64) -> BestTransactions<T> {
+        let mut best = self.best();
+        for tx in best.all.values_mut() {
+            tx.priority = self.ordering.pool_priority(&tx.transaction, base_fee);
+        }
+        best.independent = best
+            .independent
+            .iter()
+            .filter_map(|tx| best.all.get(tx.transaction.id()).cloned())
+            .collect();
+        best
+    }
+
     /// Same as `best` but only returns transactions that satisfy the given basefee and blobfee.
+    ///
+    /// The transactions keep the order of the pool, those that don't satisfy the fees are skipped
+    /// while iterating.
     pub(crate) fn best_with_basefee_and_blobfee(
         &self,
         base_fee: u64,
@@ -131,6 +149,8 @@ impl<T: TransactionOrdering> PendingPool<T> {
     ///
     /// Note: this does not insert the unlocked transactions into the pool.
     ///
+    /// All transactions are ordered by their priority at the given base fee.
+    ///
     /// # Panics
     ///
     /// if the transaction is already included
@@ -139,12 +159,12 @@ impl<T: TransactionOrdering> PendingPool<T> {
         unlocked: Vec<Arc<ValidPoolTransaction<T::Transaction>>>,
         base_fee: u64,
     ) -> BestTransactions<T> {
-        let mut best = self.best();
+        let mut best = self.best_with_basefee(base_fee);
         let mut submission_id = self.submission_id;
         for tx in unlocked {
             submission_id += 1;
             debug_assert!(!best.all.contains_key(tx.id()), "transaction already included");
-            let priority = self.ordering.priority(&tx.transaction, base_fee);
+            let priority = self.ordering.pool_priority(&tx, base_fee);
             let tx_id = *tx.id();
             let transaction = PendingTransaction { submission_id, transaction: tx, priority };
             if best.ancestor(&tx_id).is_none() {
@@ -240,7 +260,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
                 }
             } else {
                 // Re-insert the transaction with new priority.
-                tx.priority = self.ordering.priority(&tx.transaction.transaction, base_fee);
+                tx.priority = self.ordering.pool_priority(&tx.transaction, base_fee);
 
                 self.size_of += tx.transaction.size();
                 self.update_independents_and_highest_nonces(&tx, &id);
@@ -302,7 +322,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         let tx_id = *tx.id();
 
         let submission_id = self.next_id();
-        let priority = self.ordering.priority(&tx.transaction, base_fee);
+        let priority = self.ordering.pool_priority(&tx, base_fee);
         let tx = PendingTransaction { submission_id, transaction: tx, priority };
 
         self.update_independents_and_highest_nonces(&tx, &tx_id);
@@ -618,6 +638,26 @@ mod tests {
         assert!(pool.is_empty());
     }
 
+    #[test]
+    fn test_best_with_basefee_and_blobfee_skips_underpriced() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = PendingPool::new(MockOrdering::default());
+        let a = f.validated_arc(MockTransaction::eip1559().with_max_fee(100).with_priority_fee(50));
+        let b = f.validated_arc(MockTransaction::eip1559().with_max_fee(60).with_priority_fee(60));
+        let c = f.validated_arc(MockTransaction::eip1559().with_max_fee(80).with_priority_fee(40));
+        pool.add_transaction(a.clone(), 0);
+        pool.add_transaction(b.clone(), 0);
+        pool.add_transaction(c.clone(), 0);
+
+        let best = pool.best().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(best, vec![*b.hash(), *a.hash(), *c.hash()]);
+
+        // `b` can't pay the base fee, the others are returned in pool order
+        let best =
+            pool.best_with_basefee_and_blobfee(70, 0).map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(best, vec![*a.hash(), *c.hash()]);
+    }
+
     #[test]
     fn test_enforce_basefee_descendant() {
         let mut f = MockTransactionFactory::default();
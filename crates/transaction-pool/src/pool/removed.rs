@@ -0,0 +1,166 @@
+//! Bounded record of transactions that were recently removed from the pool.
+
+use crate::{PoolTransaction, TransactionOrigin, ValidPoolTransaction};
+use reth_primitives::{Address, TxHash, B256};
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Why a transaction was removed from the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RemovalReason {
+    /// Replaced by another transaction of the same sender with the same nonce.
+    Replaced {
+        /// The hash of the replacement.
+        replaced_by: TxHash,
+    },
+    /// Evicted to enforce the size limits of the pool.
+    Evicted,
+    /// Included in the block belonging to the hash.
+    Mined {
+        /// The hash of the block that contains the transaction.
+        block_hash: B256,
+    },
+    /// The nonce of the transaction fell below the on-chain nonce of its sender.
+    Outdated,
+    /// The [`TransactionConditional`](crate::TransactionConditional) of the transaction no longer
+    /// holds.
+    ConditionNotMet,
+    /// Rejected by the validator.
+    ///
+    /// Holds the message of the
+    /// [`InvalidPoolTransactionError`](crate::error::InvalidPoolTransactionError).
+    Invalid(String),
+    /// Removed on request, e.g. via
+    /// [`TransactionPool::remove_transactions`](crate::TransactionPool::remove_transactions).
+    Removed,
+}
+
+/// A transaction that was removed from the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RemovedTransaction {
+    /// The hash of the transaction.
+    pub hash: TxHash,
+    /// The sender of the transaction.
+    pub sender: Address,
+    /// The nonce of the transaction.
+    pub nonce: u64,
+    /// Why the transaction was removed.
+    pub reason: RemovalReason,
+    /// Unix timestamp in seconds of the removal.
+    pub timestamp: u64,
+}
+
+impl RemovedTransaction {
+    /// Creates a new entry for the transaction, removed now.
+    pub fn new<T: PoolTransaction>(transaction: &T, reason: RemovalReason) -> Self {
+        Self {
+            hash: *transaction.hash(),
+            sender: transaction.sender(),
+            nonce: transaction.nonce(),
+            reason,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Ring buffer of the most recently removed transactions.
+#[derive(Debug)]
+pub(crate) struct RemovedTransactions {
+    entries: VecDeque<RemovedTransaction>,
+    capacity: usize,
+}
+
+impl RemovedTransactions {
+    /// Creates a new buffer that keeps at most `capacity` entries.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self { entries: VecDeque::with_capacity(capacity.min(1024)), capacity }
+    }
+
+    /// Records the removal of the transaction, dropping the oldest entry if the buffer is full.
+    ///
+    /// [`TransactionOrigin::Private`] transactions are not recorded, they must not be exposed.
+    pub(crate) fn record<T: PoolTransaction>(
+        &mut self,
+        transaction: &T,
+        origin: TransactionOrigin,
+        reason: RemovalReason,
+    ) {
+        if origin.is_private() {
+            return
+        }
+        self.push(RemovedTransaction::new(transaction, reason))
+    }
+
+    /// Records the removal of all the given pool transactions for the same reason.
+    pub(crate) fn record_all<'a, T: PoolTransaction + 'a>(
+        &mut self,
+        transactions: impl IntoIterator<Item = &'a Arc<ValidPoolTransaction<T>>>,
+        reason: RemovalReason,
+    ) {
+        for tx in transactions {
+            self.record(&tx.transaction, tx.origin, reason.clone());
+        }
+    }
+
+    fn push(&mut self, entry: RemovedTransaction) {
+        if self.capacity == 0 {
+            return
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Returns all entries, oldest first.
+    pub(crate) fn entries(&self) -> Vec<RemovedTransaction> {
+        self.entries.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockTransaction;
+
+    #[test]
+    fn drops_oldest() {
+        let mut removed = RemovedTransactions::new(2);
+        let txs =
+            (0..3).map(|nonce| MockTransaction::eip1559().with_nonce(nonce)).collect::<Vec<_>>();
+
+        removed.record(&txs[0], TransactionOrigin::External, RemovalReason::Evicted);
+        removed.record(&txs[1], TransactionOrigin::External, RemovalReason::Outdated);
+        removed.record(
+            &txs[2],
+            TransactionOrigin::Local,
+            RemovalReason::Replaced { replaced_by: TxHash::ZERO },
+        );
+
+        let entries = removed.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].hash, *txs[1].hash());
+        assert_eq!(entries[0].reason, RemovalReason::Outdated);
+        assert_eq!(entries[1].nonce, 2);
+        assert_eq!(entries[1].sender, txs[2].sender());
+    }
+
+    #[test]
+    fn skips_private() {
+        let mut removed = RemovedTransactions::new(2);
+        removed.record(
+            &MockTransaction::eip1559(),
+            TransactionOrigin::Private,
+            RemovalReason::Removed,
+        );
+        assert!(removed.entries().is_empty());
+    }
+}
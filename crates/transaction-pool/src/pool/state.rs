@@ -11,7 +11,7 @@ bitflags::bitflags! {
     ///
     /// Otherwise, it belongs in the queued sub-pool: [SubPool::Queued].
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
-    pub(crate) struct TxState: u8 {
+    pub struct TxState: u8 {
         /// Set to `1` if all ancestor transactions are pending.
         const NO_PARKED_ANCESTORS = 0b10000000;
         /// Set to `1` of the transaction is either the next transaction of the sender (on chain nonce == tx.nonce) or all prior transactions are also present in the pool.
@@ -36,12 +36,16 @@ bitflags::bitflags! {
         /// We track this as part of the state for simplicity, since blob transactions are handled differently and are mutually exclusive with normal transactions.
         const BLOB_TRANSACTION = 0b00000010;
 
+        /// All bits that need to be set for the transaction to be pending.
         const PENDING_POOL_BITS = Self::NO_PARKED_ANCESTORS.bits() | Self::NO_NONCE_GAPS.bits() | Self::ENOUGH_BALANCE.bits() | Self::NOT_TOO_MUCH_GAS.bits() |  Self::ENOUGH_FEE_CAP_BLOCK.bits() | Self::ENOUGH_BLOB_FEE_CAP_BLOCK.bits();
 
+        /// All bits that need to be set for the transaction to be in the base fee sub-pool.
         const BASE_FEE_POOL_BITS = Self::NO_PARKED_ANCESTORS.bits() | Self::NO_NONCE_GAPS.bits() | Self::ENOUGH_BALANCE.bits() | Self::NOT_TOO_MUCH_GAS.bits();
 
+        /// Bits of a transaction in the queued sub-pool.
         const QUEUED_POOL_BITS  = Self::NO_PARKED_ANCESTORS.bits();
 
+        /// Bits of a transaction in the blob sub-pool.
         const BLOB_POOL_BITS  = Self::BLOB_TRANSACTION.bits();
     }
 }
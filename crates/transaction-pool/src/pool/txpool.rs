@@ -6,7 +6,7 @@ use crate::{
     identifier::{SenderId, TransactionId},
     metrics::{AllTransactionsMetrics, TxPoolMetrics},
     pool::{
-        best::BestTransactions,
+        best::{BestTransactionFilter, BestTransactions},
         blob::BlobTransactions,
         parked::{BasefeeOrd, ParkedPool, QueuedOrd},
         pending::PendingPool,
@@ -14,8 +14,10 @@ use crate::{
         update::{Destination, PoolUpdate},
         AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome,
     },
-    traits::{BestTransactionsAttributes, BlockInfo, PoolSize},
-    PoolConfig, PoolResult, PoolTransaction, PriceBumpConfig, TransactionOrdering,
+    traits::{
+        AllSubPoolTransactions, BestTransactionsAttributes, BlockInfo, PoolSize, SubPoolTransaction,
+    },
+    PoolConfig, PoolResult, PoolTransaction, PriceBumpConfig, RateLimiter, TransactionOrdering,
     ValidPoolTransaction, U256,
 };
 use reth_primitives::{
@@ -32,6 +34,7 @@ use std::{
     fmt,
     ops::Bound::{Excluded, Unbounded},
     sync::Arc,
+    time::Instant,
 };
 use tracing::trace;
 
@@ -271,7 +274,11 @@ impl<T: TransactionOrdering> TxPool<T> {
     {
         // First we need to check if the given base fee is different than what's currently being
         // tracked
-        match best_transactions_attributes.basefee.cmp(&self.all_transactions.pending_fees.base_fee)
+        let best: Box<
+            dyn crate::traits::BestTransactions<Item = Arc<ValidPoolTransaction<T::Transaction>>>,
+        > = match best_transactions_attributes
+            .basefee
+            .cmp(&self.all_transactions.pending_fees.base_fee)
         {
             Ordering::Equal => {
                 // for EIP-4844 transactions we also need to check if the blob fee is now lower than
@@ -311,9 +318,24 @@ impl<T: TransactionOrdering> TxPool<T> {
 
                 Box::new(
                     self.pending_pool
-                        .best_with_unlocked(unlocked, self.all_transactions.pending_fees.base_fee),
+                        .best_with_unlocked(unlocked, best_transactions_attributes.basefee),
                 )
             }
+        };
+
+        // skip conditional transactions that can't be included in the block that is being built
+        match best_transactions_attributes.block_number.zip(best_transactions_attributes.timestamp)
+        {
+            Some((number, timestamp)) => Box::new(BestTransactionFilter::new(
+                best,
+                move |tx: &Arc<ValidPoolTransaction<T::Transaction>>| {
+                    tx.conditional().map_or(true, |conditional| {
+                        conditional.matches_block_number(number) &&
+                            conditional.matches_timestamp(timestamp)
+                    })
+                },
+            )),
+            None => best,
         }
     }
 
@@ -327,6 +349,34 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.basefee_pool.all().chain(self.queued_pool.all()).collect()
     }
 
+    /// Returns all transactions grouped by the sub-pool they're in, together with their state.
+    pub(crate) fn all_sub_pool_transactions(&self) -> AllSubPoolTransactions<T::Transaction> {
+        let mut all = AllSubPoolTransactions::default();
+        for tx in self.all_transactions.txs.values() {
+            let entry = SubPoolTransaction { transaction: tx.transaction.clone(), state: tx.state };
+            match tx.subpool {
+                SubPool::Queued => all.queued.push(entry),
+                SubPool::BaseFee => all.basefee.push(entry),
+                SubPool::Blob => all.blob.push(entry),
+                SubPool::Pending => all.pending.push(entry),
+            }
+        }
+        all
+    }
+
+    /// Returns all transactions that were submitted with a
+    /// [`TransactionConditional`](crate::TransactionConditional).
+    pub(crate) fn conditional_transactions(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions
+            .txs
+            .values()
+            .filter(|tx| tx.transaction.conditional().is_some())
+            .map(|tx| tx.transaction.clone())
+            .collect()
+    }
+
     /// Returns queued and pending transactions for the specified sender
     pub fn queued_and_pending_txs_by_sender(
         &self,
@@ -412,10 +462,12 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.all_transactions.set_block_info(block_info);
 
         // Remove all transaction that were included in the block
+        let mut pruned = Vec::new();
         for tx_hash in &mined_transactions {
-            if self.prune_transaction_by_hash(tx_hash).is_some() {
+            if let Some(tx) = self.prune_transaction_by_hash(tx_hash) {
                 // Update removed transactions metric
                 self.metrics.removed_transactions.increment(1);
+                pruned.push(tx);
             }
         }
 
@@ -423,7 +475,13 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         self.metrics.performed_state_updates.increment(1);
 
-        OnNewCanonicalStateOutcome { block_hash, mined: mined_transactions, promoted, discarded }
+        OnNewCanonicalStateOutcome {
+            block_hash,
+            mined: mined_transactions,
+            pruned,
+            promoted,
+            discarded,
+        }
     }
 
     /// Update sub-pools size metrics.
@@ -528,6 +586,10 @@ impl<T: TransactionOrdering> TxPool<T> {
                             PoolErrorKind::SpammerExceededCapacity(transaction.sender()),
                         ))
                     }
+                    InsertErr::ReplacementRateLimited { transaction } => Err(PoolError::new(
+                        *transaction.hash(),
+                        PoolErrorKind::ReplacementRateLimitExceeded(transaction.sender()),
+                    )),
                     InsertErr::TxGasLimitMoreThanAvailableBlockGas {
                         transaction,
                         block_gas_limit,
@@ -913,6 +975,8 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
     price_bumps: PriceBumpConfig,
     /// How to handle [`TransactionOrigin::Local`](crate::TransactionOrigin) transactions.
     local_transactions_config: LocalTransactionConfig,
+    /// Limits the replacements per sender, if configured.
+    replacement_limiter: Option<RateLimiter<SenderId>>,
     /// All Transactions metrics
     metrics: AllTransactionsMetrics,
 }
@@ -924,6 +988,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
             max_account_slots: config.max_account_slots,
             price_bumps: config.price_bumps,
             local_transactions_config: config.local_transactions_config.clone(),
+            replacement_limiter: config.replacement_rate_limit.map(RateLimiter::new),
             ..Default::default()
         }
     }
@@ -1552,6 +1617,19 @@ impl<T: PoolTransaction> AllTransactions<T> {
                         existing: *entry.get().transaction.hash(),
                     })
                 }
+
+                // Ensure the sender doesn't churn the pool with replacements
+                if let Some(limiter) = &mut self.replacement_limiter {
+                    if !self
+                        .local_transactions_config
+                        .is_local(transaction.origin, transaction.sender()) &&
+                        !limiter.check(transaction.sender_id(), Instant::now())
+                    {
+                        self.metrics.rate_limited_replacements.increment(1);
+                        return Err(InsertErr::ReplacementRateLimited { transaction })
+                    }
+                }
+
                 let new_hash = *pool_tx.transaction.hash();
                 let new_transaction = pool_tx.transaction.clone();
                 let replaced = entry.insert(pool_tx);
@@ -1693,6 +1771,7 @@ impl<T: PoolTransaction> Default for AllTransactions<T> {
             pending_fees: Default::default(),
             price_bumps: Default::default(),
             local_transactions_config: Default::default(),
+            replacement_limiter: None,
             metrics: Default::default(),
         }
     }
@@ -1746,6 +1825,8 @@ pub(crate) enum InsertErr<T: PoolTransaction> {
     },
     /// Thrown if the mutual exclusivity constraint (blob vs normal transaction) is violated.
     TxTypeConflict { transaction: Arc<ValidPoolTransaction<T>> },
+    /// The sender exceeded the configured rate of replacements.
+    ReplacementRateLimited { transaction: Arc<ValidPoolTransaction<T>> },
 }
 
 /// Transaction was successfully inserted into the pool
@@ -1831,7 +1912,7 @@ mod tests {
     use crate::{
         test_utils::{MockOrdering, MockTransaction, MockTransactionFactory, MockTransactionSet},
         traits::TransactionOrigin,
-        SubPoolLimit,
+        RateLimit, SubPoolLimit,
     };
 
     #[test]
@@ -2371,6 +2452,37 @@ mod tests {
         assert_eq!(pool.len(), 1);
     }
 
+    #[test]
+    fn insert_replace_rate_limited() {
+        let on_chain_balance = U256::ZERO;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            replacement_rate_limit: Some(RateLimit::new(1, std::time::Duration::from_secs(60))),
+            ..Default::default()
+        };
+        let mut pool = AllTransactions::new(&config);
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let first = f.validated(tx.clone());
+        let _ = pool.insert_tx(first, on_chain_balance, on_chain_nonce).unwrap();
+
+        let tx = tx.rng_hash().inc_price_by(10);
+        let replacement = f.validated(tx.clone());
+        let _ = pool.insert_tx(replacement.clone(), on_chain_balance, on_chain_nonce).unwrap();
+
+        // the second replacement exceeds the limit
+        let tx = tx.rng_hash().inc_price_by(10);
+        let err =
+            pool.insert_tx(f.validated(tx.clone()), on_chain_balance, on_chain_nonce).unwrap_err();
+        assert!(matches!(err, InsertErr::ReplacementRateLimited { .. }));
+        assert!(pool.contains(replacement.hash()));
+
+        // local replacements are exempt
+        let local =
+            f.validated_with_origin(TransactionOrigin::Local, tx.rng_hash().inc_price_by(10));
+        let _ = pool.insert_tx(local, on_chain_balance, on_chain_nonce).unwrap();
+    }
+
     #[test]
     fn insert_conflicting_type_normal_to_blob() {
         let on_chain_balance = U256::from(10_000);
@@ -0,0 +1,112 @@
+//! Rate limiting of pool operations, e.g. replacements per sender or admissions per peer.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+/// Minimum number of tracked keys before expired windows are pruned.
+const MIN_PRUNE_THRESHOLD: usize = 1024;
+
+/// Allows at most `max` events per `interval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RateLimit {
+    /// Maximum number of events per interval.
+    pub max: u32,
+    /// The length of the interval.
+    pub interval: Duration,
+}
+
+impl RateLimit {
+    /// Creates a new rate limit of `max` events per `interval`.
+    pub const fn new(max: u32, interval: Duration) -> Self {
+        Self { max, interval }
+    }
+}
+
+/// Counts events per key in fixed windows of the [`RateLimit`] interval.
+#[derive(Debug)]
+pub struct RateLimiter<K> {
+    limit: RateLimit,
+    /// Start and number of events of the current window, by key.
+    windows: HashMap<K, (Instant, u32)>,
+    /// Number of tracked keys at which expired windows are pruned.
+    prune_threshold: usize,
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+    /// Creates a new limiter with the given limit.
+    pub fn new(limit: RateLimit) -> Self {
+        Self { limit, windows: HashMap::new(), prune_threshold: MIN_PRUNE_THRESHOLD }
+    }
+
+    /// Returns the limit of the limiter.
+    pub const fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    /// Records an event for the key at the given time.
+    ///
+    /// Returns `false` if the key exceeded the limit, in which case the event is not recorded.
+    pub fn check(&mut self, key: K, now: Instant) -> bool {
+        if self.windows.len() >= self.prune_threshold {
+            self.prune(now);
+        }
+
+        let interval = self.limit.interval;
+        let (start, count) = self.windows.entry(key).or_insert((now, 0));
+        if now.saturating_duration_since(*start) >= interval {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= self.limit.max {
+            return false
+        }
+        *count += 1;
+        true
+    }
+
+    /// Returns the number of events recorded for the key in its current window.
+    pub fn count(&self, key: &K, now: Instant) -> u32 {
+        self.windows
+            .get(key)
+            .filter(|(start, _)| now.saturating_duration_since(*start) < self.limit.interval)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// Removes the windows that expired at the given time.
+    pub fn prune(&mut self, now: Instant) {
+        let interval = self.limit.interval;
+        self.windows.retain(|_, (start, _)| now.saturating_duration_since(*start) < interval);
+        self.prune_threshold = (self.windows.len() * 2).max(MIN_PRUNE_THRESHOLD);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_per_window() {
+        let mut limiter = RateLimiter::new(RateLimit::new(2, Duration::from_secs(10)));
+        let now = Instant::now();
+
+        assert!(limiter.check(1, now));
+        assert!(limiter.check(1, now));
+        assert!(!limiter.check(1, now + Duration::from_secs(5)));
+        assert_eq!(limiter.count(&1, now), 2);
+
+        // other keys are tracked separately
+        assert!(limiter.check(2, now));
+
+        // a new window starts once the interval passed
+        assert!(limiter.check(1, now + Duration::from_secs(10)));
+        assert_eq!(limiter.count(&1, now + Duration::from_secs(10)), 1);
+
+        limiter.prune(now + Duration::from_secs(15));
+        assert_eq!(limiter.count(&2, now), 0);
+        assert_eq!(limiter.count(&1, now + Duration::from_secs(15)), 1);
+    }
+}
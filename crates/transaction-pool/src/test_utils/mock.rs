@@ -635,6 +635,13 @@ impl PoolTransaction for MockTransaction {
         }
     }
 
+    fn blob_gas_used(&self) -> Option<u64> {
+        match self {
+            Self::Eip4844 { sidecar, .. } => Some(DATA_GAS_PER_BLOB * sidecar.blobs.len() as u64),
+            _ => None,
+        }
+    }
+
     /// Calculates the effective tip per gas given a base fee.
     fn effective_tip_per_gas(&self, base_fee: u64) -> Option<u128> {
         // Convert base_fee to u128 for precision in calculations
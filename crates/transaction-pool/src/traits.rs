@@ -3,9 +3,12 @@
 use crate::{
     blobstore::BlobStoreError,
     error::PoolResult,
-    pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
+    pool::{
+        state::{SubPool, TxState},
+        BestTransactionFilter, TransactionEvents,
+    },
     validate::ValidPoolTransaction,
-    AllTransactionsEvents,
+    AllTransactionsEvents, RemovedTransaction, TransactionConditional,
 };
 use futures_util::{ready, Stream};
 use reth_eth_wire_types::HandleMempoolData;
@@ -16,6 +19,7 @@ use reth_primitives::{
     SealedBlock, Transaction, TransactionSignedEcRecovered, TryFromRecoveredTransaction, TxHash,
     TxKind, B256, EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, EIP7702_TX_TYPE_ID, U256,
 };
+use reth_provider::StateProvider;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::{
@@ -220,6 +224,8 @@ pub trait TransactionPool: Send + Sync + Clone {
     ///
     /// If the transaction is a blob transaction, the sidecar will be included.
     ///
+    /// [TransactionOrigin::Private] transactions are never returned.
+    ///
     /// Consumer: P2P
     fn get_pooled_transaction_elements(
         &self,
@@ -234,6 +240,8 @@ pub trait TransactionPool: Send + Sync + Clone {
     ///
     /// If the transaction is a blob transaction, the sidecar will be included.
     ///
+    /// [TransactionOrigin::Private] transactions are never returned.
+    ///
     /// Consumer: P2P
     fn get_pooled_transaction_element(&self, tx_hash: TxHash) -> Option<PooledTransactionsElement>;
 
@@ -289,6 +297,28 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Consumer: RPC
     fn all_transactions(&self) -> AllPoolTransactions<Self::Transaction>;
 
+    /// Returns all transactions that are currently in the pool grouped by their [`SubPool`],
+    /// together with their [`TxState`].
+    ///
+    /// By default this returns no transactions, for pools that don't track sub-pools.
+    ///
+    /// Consumer: RPC
+    fn all_sub_pool_transactions(&self) -> AllSubPoolTransactions<Self::Transaction> {
+        AllSubPoolTransactions::default()
+    }
+
+    /// Returns the most recently removed transactions and why they were removed, oldest first.
+    ///
+    /// The number of tracked removals is bounded by
+    /// [`PoolConfig::max_removed_transactions`](crate::PoolConfig::max_removed_transactions).
+    ///
+    /// By default this returns no transactions, for pools that don't track removals.
+    ///
+    /// Consumer: RPC
+    fn removed_transactions(&self) -> Vec<RemovedTransaction> {
+        Vec::new()
+    }
+
     /// Removes all transactions corresponding to the given hashes.
     ///
     /// Also removes all _dependent_ transactions.
@@ -413,6 +443,19 @@ pub trait TransactionPoolExt: TransactionPool {
     /// Updates the accounts in the pool
     fn update_accounts(&self, accounts: Vec<ChangedAccount>);
 
+    /// Removes all conditional transactions that expect the storage of any of the given accounts
+    /// and no longer match the given state.
+    ///
+    /// Returns the hashes of the removed transactions. By default this removes nothing, for pools
+    /// that don't accept conditional transactions.
+    fn remove_unmet_conditional_transactions(
+        &self,
+        _state: &dyn StateProvider,
+        _accounts: &HashSet<Address>,
+    ) -> Vec<TxHash> {
+        Vec::new()
+    }
+
     /// Deletes the blob sidecar for the given transaction from the blob store
     fn delete_blob(&self, tx: B256);
 
@@ -455,6 +498,40 @@ pub struct AllPoolTransactions<T: PoolTransaction> {
     pub queued: Vec<Arc<ValidPoolTransaction<T>>>,
 }
 
+/// A transaction in the pool together with its [`TxState`].
+#[derive(Debug)]
+pub struct SubPoolTransaction<T: PoolTransaction> {
+    /// The transaction.
+    pub transaction: Arc<ValidPoolTransaction<T>>,
+    /// The state of the transaction, which determines its [`SubPool`].
+    pub state: TxState,
+}
+
+impl<T: PoolTransaction> Clone for SubPoolTransaction<T> {
+    fn clone(&self) -> Self {
+        Self { transaction: Arc::clone(&self.transaction), state: self.state }
+    }
+}
+
+/// A Helper type that bundles all transactions in the pool by [`SubPool`].
+#[derive(Debug, Clone)]
+pub struct AllSubPoolTransactions<T: PoolTransaction> {
+    /// Transactions of the [`SubPool::Pending`] sub-pool.
+    pub pending: Vec<SubPoolTransaction<T>>,
+    /// Transactions of the [`SubPool::BaseFee`] sub-pool.
+    pub basefee: Vec<SubPoolTransaction<T>>,
+    /// Transactions of the [`SubPool::Blob`] sub-pool.
+    pub blob: Vec<SubPoolTransaction<T>>,
+    /// Transactions of the [`SubPool::Queued`] sub-pool.
+    pub queued: Vec<SubPoolTransaction<T>>,
+}
+
+impl<T: PoolTransaction> Default for AllSubPoolTransactions<T> {
+    fn default() -> Self {
+        Self { pending: Vec::new(), basefee: Vec::new(), blob: Vec::new(), queued: Vec::new() }
+    }
+}
+
 // === impl AllPoolTransactions ===
 
 impl<T: PoolTransaction> AllPoolTransactions<T> {
@@ -724,6 +801,24 @@ impl<T> BestTransactions for std::iter::Empty<T> {
     fn set_skip_blobs(&mut self, _skip_blobs: bool) {}
 }
 
+impl<T: BestTransactions + ?Sized> BestTransactions for Box<T> {
+    fn mark_invalid(&mut self, transaction: &Self::Item) {
+        (**self).mark_invalid(transaction)
+    }
+
+    fn no_updates(&mut self) {
+        (**self).no_updates()
+    }
+
+    fn skip_blobs(&mut self) {
+        (**self).skip_blobs()
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        (**self).set_skip_blobs(skip_blobs)
+    }
+}
+
 /// A Helper type that bundles best transactions attributes together.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct BestTransactionsAttributes {
@@ -731,6 +826,13 @@ pub struct BestTransactionsAttributes {
     pub basefee: u64,
     /// The blob fee attribute for best transactions.
     pub blob_fee: Option<u64>,
+    /// The number of the block that is being built.
+    ///
+    /// If set together with [`Self::timestamp`], conditional transactions that can't be included
+    /// in the block are skipped.
+    pub block_number: Option<u64>,
+    /// The timestamp of the block that is being built.
+    pub timestamp: Option<u64>,
 }
 
 // === impl BestTransactionsAttributes ===
@@ -738,7 +840,7 @@ pub struct BestTransactionsAttributes {
 impl BestTransactionsAttributes {
     /// Creates a new `BestTransactionsAttributes` with the given basefee and blob fee.
     pub const fn new(basefee: u64, blob_fee: Option<u64>) -> Self {
-        Self { basefee, blob_fee }
+        Self { basefee, blob_fee, block_number: None, timestamp: None }
     }
 
     /// Creates a new `BestTransactionsAttributes` with the given basefee.
@@ -751,6 +853,13 @@ impl BestTransactionsAttributes {
         self.blob_fee = Some(blob_fee);
         self
     }
+
+    /// Sets the number and timestamp of the block that is being built.
+    pub const fn with_block(mut self, block_number: u64, timestamp: u64) -> Self {
+        self.block_number = Some(block_number);
+        self.timestamp = Some(timestamp);
+        self
+    }
 }
 
 /// Trait for transaction types used inside the pool
@@ -803,6 +912,13 @@ pub trait PoolTransaction:
     /// This will return `None` for non-EIP4844 transactions
     fn max_fee_per_blob_gas(&self) -> Option<u128>;
 
+    /// Returns the blob gas used by the transaction.
+    ///
+    /// This will return `None` for non-EIP4844 transactions
+    fn blob_gas_used(&self) -> Option<u64> {
+        None
+    }
+
     /// Returns the effective tip for this transaction.
     ///
     /// For EIP-1559 transactions: `min(max_fee_per_gas - base_fee, max_priority_fee_per_gas)`.
@@ -854,6 +970,19 @@ pub trait PoolTransaction:
 
     /// Returns `chain_id`
     fn chain_id(&self) -> Option<u64>;
+
+    /// Returns the conditions the transaction was submitted with, if any.
+    ///
+    /// See also [`TransactionConditional`].
+    fn conditional(&self) -> Option<&TransactionConditional> {
+        None
+    }
+
+    /// Attaches conditions to the transaction.
+    ///
+    /// Transaction types that don't support conditions ignore this, in which case
+    /// [`PoolTransaction::conditional`] keeps returning `None`.
+    fn set_conditional(&mut self, _conditional: TransactionConditional) {}
 }
 
 /// An extension trait that provides additional interfaces for the
@@ -897,6 +1026,9 @@ pub struct EthPooledTransaction {
 
     /// The blob side car for this transaction
     pub(crate) blob_sidecar: EthBlobTransactionSidecar,
+
+    /// The conditions this transaction was submitted with, if any.
+    pub(crate) conditional: Option<Box<TransactionConditional>>,
 }
 
 /// Represents the blob sidecar of the [`EthPooledTransaction`].
@@ -961,7 +1093,7 @@ impl EthPooledTransaction {
             ));
         }
 
-        Self { transaction, cost, encoded_length, blob_sidecar }
+        Self { transaction, cost, encoded_length, blob_sidecar, conditional: None }
     }
 
     /// Return the reference to the underlying transaction.
@@ -1062,6 +1194,10 @@ impl PoolTransaction for EthPooledTransaction {
         self.transaction.max_fee_per_blob_gas()
     }
 
+    fn blob_gas_used(&self) -> Option<u64> {
+        self.transaction.blob_gas_used()
+    }
+
     /// Returns the effective tip for this transaction.
     ///
     /// For EIP-1559 transactions: `min(max_fee_per_gas - base_fee, max_priority_fee_per_gas)`.
@@ -1105,6 +1241,14 @@ impl PoolTransaction for EthPooledTransaction {
     fn chain_id(&self) -> Option<u64> {
         self.transaction.chain_id()
     }
+
+    fn conditional(&self) -> Option<&TransactionConditional> {
+        self.conditional.as_deref()
+    }
+
+    fn set_conditional(&mut self, conditional: TransactionConditional) {
+        self.conditional = Some(Box::new(conditional));
+    }
 }
 
 impl EthPoolTransaction for EthPooledTransaction {
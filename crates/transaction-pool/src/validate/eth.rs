@@ -315,17 +315,59 @@ where
             }
         }
 
-        let account = match self
-            .client
-            .latest()
-            .and_then(|state| state.basic_account(transaction.sender()))
-        {
+        // Bound the number of storage lookups the conditions of the transaction require
+        if let Some(conditional) = transaction.conditional() {
+            if conditional.exceeds_max_cost() {
+                let cost = conditional.known_accounts_cost();
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::ConditionCostExceeded(cost),
+                )
+            }
+        }
+
+        let state = match self.client.latest() {
+            Ok(state) => state,
+            Err(err) => {
+                return TransactionValidationOutcome::Error(*transaction.hash(), Box::new(err))
+            }
+        };
+
+        let account = match state.basic_account(transaction.sender()) {
             Ok(account) => account.unwrap_or_default(),
             Err(err) => {
                 return TransactionValidationOutcome::Error(*transaction.hash(), Box::new(err))
             }
         };
 
+        // Checks the conditions of conditional transactions against the current head
+        if let Some(conditional) = transaction.conditional() {
+            let head = match self.client.latest_header() {
+                Ok(head) => head.unwrap_or_default(),
+                Err(err) => {
+                    return TransactionValidationOutcome::Error(*transaction.hash(), Box::new(err))
+                }
+            };
+            if conditional.is_expired(head.number, head.timestamp) {
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::ConditionNotMet,
+                )
+            }
+            match conditional.matches_state(&state) {
+                Ok(true) => {}
+                Ok(false) => {
+                    return TransactionValidationOutcome::Invalid(
+                        transaction,
+                        InvalidPoolTransactionError::ConditionNotMet,
+                    )
+                }
+                Err(err) => {
+                    return TransactionValidationOutcome::Error(*transaction.hash(), Box::new(err))
+                }
+            }
+        }
+
         // Signer account shouldn't have bytecode. Presence of bytecode means this is a
         // smartcontract.
         if account.has_bytecode() {
@@ -401,19 +443,24 @@ where
             }
         }
 
-        // Return the valid transaction
-        TransactionValidationOutcome::Valid {
-            balance: account.balance,
-            state_nonce: account.nonce,
-            transaction: ValidTransaction::new(transaction, maybe_blob_sidecar),
-            // by this point assume all external transactions should be propagated
-            propagate: match origin {
+        // conditional transactions are never propagated, peers would receive them without their
+        // conditions
+        let propagate = transaction.conditional().is_none() &&
+            match origin {
+                // by this point assume all external transactions should be propagated
                 TransactionOrigin::External => true,
                 TransactionOrigin::Local => {
                     self.local_transactions_config.propagate_local_transactions
                 }
                 TransactionOrigin::Private => false,
-            },
+            };
+
+        // Return the valid transaction
+        TransactionValidationOutcome::Valid {
+            balance: account.balance,
+            state_nonce: account.nonce,
+            transaction: ValidTransaction::new(transaction, maybe_blob_sidecar),
+            propagate,
         }
     }
 
@@ -795,10 +842,14 @@ mod tests {
     use super::*;
     use crate::{
         blobstore::InMemoryBlobStore, error::PoolErrorKind, CoinbaseTipOrdering,
-        EthPooledTransaction, Pool, TransactionPool,
+        EthPooledTransaction, GetPooledTransactionLimit, KnownAccount, Pool,
+        TransactionConditional, TransactionListenerKind, TransactionPool,
+        MAX_TRANSACTION_CONDITIONAL_COST,
     };
     use reth_chainspec::MAINNET;
-    use reth_primitives::{hex, FromRecoveredPooledTransaction, PooledTransactionsElement, U256};
+    use reth_primitives::{
+        hex, Address, FromRecoveredPooledTransaction, PooledTransactionsElement, B256, U256,
+    };
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
 
     fn get_transaction() -> EthPooledTransaction {
@@ -880,4 +931,102 @@ mod tests {
         let tx = pool.get(transaction.hash());
         assert!(tx.is_none());
     }
+
+    #[tokio::test]
+    async fn invalid_on_condition_not_met() {
+        let mut transaction = get_transaction();
+
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            transaction.sender(),
+            ExtendedAccount::new(transaction.nonce(), U256::MAX),
+        );
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, InMemoryBlobStore::default());
+
+        let mut conditional = TransactionConditional::default();
+        conditional.known_accounts.insert(
+            Address::with_last_byte(1),
+            KnownAccount::Slots(std::iter::once((B256::ZERO, B256::with_last_byte(1))).collect()),
+        );
+        transaction.set_conditional(conditional);
+
+        let outcome = validator.validate_one(TransactionOrigin::External, transaction.clone());
+        assert!(matches!(
+            outcome,
+            TransactionValidationOutcome::Invalid(_, InvalidPoolTransactionError::ConditionNotMet)
+        ));
+
+        transaction.set_conditional(TransactionConditional {
+            block_number_max: Some(100),
+            ..Default::default()
+        });
+        let outcome = validator.validate_one(TransactionOrigin::External, transaction);
+        assert!(outcome.is_valid());
+    }
+
+    #[tokio::test]
+    async fn invalid_on_condition_cost_exceeded() {
+        let mut transaction = get_transaction();
+
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            transaction.sender(),
+            ExtendedAccount::new(transaction.nonce(), U256::MAX),
+        );
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, InMemoryBlobStore::default());
+
+        let mut conditional = TransactionConditional::default();
+        conditional.known_accounts.extend((0..=MAX_TRANSACTION_CONDITIONAL_COST as u64).map(|i| {
+            (Address::from_word(B256::from(U256::from(i))), KnownAccount::StorageRoot(B256::ZERO))
+        }));
+        transaction.set_conditional(conditional);
+
+        let outcome = validator.validate_one(TransactionOrigin::Local, transaction);
+        assert!(matches!(
+            outcome,
+            TransactionValidationOutcome::Invalid(
+                _,
+                InvalidPoolTransactionError::ConditionCostExceeded(cost)
+            ) if cost == MAX_TRANSACTION_CONDITIONAL_COST + 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn conditional_transactions_not_propagated() {
+        let mut transaction = get_transaction();
+        transaction.set_conditional(TransactionConditional {
+            block_number_max: Some(100),
+            ..Default::default()
+        });
+
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            transaction.sender(),
+            ExtendedAccount::new(transaction.nonce(), U256::MAX),
+        );
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, blob_store.clone());
+
+        let outcome = validator.validate_one(TransactionOrigin::Local, transaction.clone());
+        assert!(matches!(outcome, TransactionValidationOutcome::Valid { propagate: false, .. }));
+
+        let pool =
+            Pool::new(validator, CoinbaseTipOrdering::default(), blob_store, Default::default());
+        let mut propagate_listener =
+            pool.pending_transactions_listener_for(TransactionListenerKind::PropagateOnly);
+        let mut all_listener = pool.pending_transactions_listener_for(TransactionListenerKind::All);
+
+        let hash = pool.add_transaction(TransactionOrigin::Local, transaction).await.unwrap();
+        assert_eq!(all_listener.try_recv().unwrap(), hash);
+
+        // the transaction is neither announced nor broadcast to new peers, nor served on request
+        assert!(propagate_listener.try_recv().is_err());
+        assert!(pool.pooled_transactions().is_empty());
+        assert!(pool
+            .get_pooled_transaction_elements(vec![hash], GetPooledTransactionLimit::None)
+            .is_empty());
+    }
 }
@@ -4,6 +4,7 @@ use crate::{
     error::InvalidPoolTransactionError,
     identifier::{SenderId, TransactionId},
     traits::{PoolTransaction, TransactionOrigin},
+    TransactionConditional,
 };
 use reth_primitives::{
     Address, BlobTransactionSidecar, IntoRecoveredTransaction, SealedBlock,
@@ -19,7 +20,7 @@ mod task;
 pub use eth::*;
 
 /// A spawnable task that performs transaction validation.
-pub use task::{TransactionValidationTaskExecutor, ValidationTask};
+pub use task::{TransactionValidationTaskExecutor, ValidationJobSender, ValidationTask};
 
 /// Validation constants.
 pub use constants::{
@@ -323,6 +324,11 @@ impl<T: PoolTransaction> ValidPoolTransaction<T> {
         self.transaction.is_eip4844()
     }
 
+    /// Returns the conditions the transaction was submitted with, if any.
+    pub fn conditional(&self) -> Option<&TransactionConditional> {
+        self.transaction.conditional()
+    }
+
     /// The heap allocated size of this transaction.
     pub(crate) fn size(&self) -> usize {
         self.transaction.size()
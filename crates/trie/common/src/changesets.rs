@@ -0,0 +1,62 @@
+use super::{BranchNodeCompact, StoredBranchNode, StoredNibblesSubKey};
+use reth_codecs::Compact;
+use serde::{Deserialize, Serialize};
+
+/// The value of a trie node before it was changed in a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+pub struct TrieChangeSetsEntry {
+    /// The nibbles of the intermediate node.
+    pub nibbles: StoredNibblesSubKey,
+    /// The node before the change, `None` if the node didn't exist.
+    pub node: Option<BranchNodeCompact>,
+}
+
+// NOTE: The subkey is encoded without compression so that the entries can be fetched with
+// `seek_by_key_subkey`, see `StorageTrieEntry`.
+impl Compact for TrieChangeSetsEntry {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        let nibbles_len = self.nibbles.to_compact(buf);
+        let node_len = self.node.map_or(0, |node| StoredBranchNode(node).to_compact(buf));
+        nibbles_len + node_len
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let (nibbles, buf) = StoredNibblesSubKey::from_compact(buf, 65);
+        if len > 65 {
+            let (node, buf) = StoredBranchNode::from_compact(buf, len - 65);
+            (Self { nibbles, node: Some(node.0) }, buf)
+        } else {
+            (Self { nibbles, node: None }, buf)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Nibbles, TrieMask};
+    use alloy_primitives::B256;
+
+    #[test]
+    fn trie_changesets_entry_roundtrip() {
+        let nibbles = StoredNibblesSubKey(Nibbles::from_nibbles_unchecked([0x1, 0x2, 0x3]));
+        for node in [
+            None,
+            Some(BranchNodeCompact::new(
+                TrieMask::new(0b11),
+                TrieMask::new(0),
+                TrieMask::new(0b1),
+                vec![B256::repeat_byte(1)],
+                None,
+            )),
+        ] {
+            let entry = TrieChangeSetsEntry { nibbles: nibbles.clone(), node };
+            let mut buf = Vec::new();
+            let len = entry.clone().to_compact(&mut buf);
+            assert_eq!(TrieChangeSetsEntry::from_compact(&buf, len).0, entry);
+        }
+    }
+}
@@ -26,6 +26,9 @@ pub use nodes::StoredBranchNode;
 mod storage;
 pub use storage::StorageTrieEntry;
 
+mod changesets;
+pub use changesets::TrieChangeSetsEntry;
+
 mod subnode;
 pub use subnode::StoredSubNode;
 
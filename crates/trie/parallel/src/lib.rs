@@ -21,6 +21,12 @@ pub mod async_root;
 #[cfg(feature = "parallel")]
 pub mod parallel_root;
 
+/// In-memory sparse trie.
+pub mod sparse;
+
+/// Implementation of sparse trie based state root computation.
+pub mod sparse_root;
+
 /// Parallel state root metrics.
 #[cfg(feature = "metrics")]
 pub mod metrics;
@@ -0,0 +1,579 @@
+use alloy_rlp::{Decodable, Encodable};
+use reth_primitives::{keccak256, B256};
+use reth_trie::{
+    nodes::{rlp_node, word_rlp, BranchNode, ExtensionNode, LeafNode, TrieNode},
+    Nibbles, TrieMask, EMPTY_ROOT_HASH,
+};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// A node of the [`SparseTrie`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SparseNode {
+    /// Empty trie, only valid at the root.
+    Empty,
+    /// A node that is not revealed, known only by its hash.
+    Hash(B256),
+    /// Leaf node with the remaining key. The value is stored separately, keyed by the full path.
+    Leaf {
+        /// The remaining key of the leaf after the node path.
+        key: Nibbles,
+    },
+    /// Extension node with the key of its child branch node.
+    Extension {
+        /// The shared key of the extension.
+        key: Nibbles,
+    },
+    /// Branch node with the bitmask of its children.
+    Branch {
+        /// The bitmask of the existing children.
+        state_mask: u16,
+    },
+}
+
+/// In-memory sparse Merkle Patricia Trie.
+///
+/// Only the parts of the trie that were revealed from proofs are stored, all other subtries are
+/// represented by their hash. Leaves can be updated and removed as long as the nodes on their
+/// path, and for removals the nodes that are merged into their parent, are revealed. The RLP of
+/// unchanged nodes is cached, so that computing the root only hashes the nodes on the paths of
+/// the keys updated since the last computation.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SparseTrie {
+    /// Revealed nodes keyed by their path.
+    nodes: HashMap<Nibbles, SparseNode>,
+    /// Leaf values keyed by their full path.
+    values: HashMap<Nibbles, Vec<u8>>,
+    /// Cached RLP of the nodes whose subtries have not changed.
+    rlp_cache: HashMap<Nibbles, Vec<u8>>,
+}
+
+impl Default for SparseTrie {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl SparseTrie {
+    /// Creates an empty trie.
+    pub fn empty() -> Self {
+        Self::with_root_node(SparseNode::Empty)
+    }
+
+    /// Creates a trie that is not revealed, only known by its root hash.
+    pub fn blind(root: B256) -> Self {
+        if root == EMPTY_ROOT_HASH {
+            Self::empty()
+        } else {
+            Self::with_root_node(SparseNode::Hash(root))
+        }
+    }
+
+    fn with_root_node(node: SparseNode) -> Self {
+        Self {
+            nodes: HashMap::from([(Nibbles::default(), node)]),
+            values: HashMap::default(),
+            rlp_cache: HashMap::default(),
+        }
+    }
+
+    /// Returns the revealed node at the given path.
+    pub fn node(&self, path: &Nibbles) -> Option<&SparseNode> {
+        self.nodes.get(path)
+    }
+
+    /// Returns the value of the leaf with the given full path, if it is revealed.
+    pub fn leaf_value(&self, path: &Nibbles) -> Option<&[u8]> {
+        self.values.get(path).map(Vec::as_slice)
+    }
+
+    /// Reveals the RLP encoded trie node at the given path.
+    ///
+    /// The node is only revealed if its path is known by its hash, i.e. it's the root of a blinded
+    /// trie or its parent is revealed. Nodes must therefore be revealed in the order of their
+    /// paths, parents first, as in proofs. Nodes that are already revealed are not changed.
+    pub fn reveal_node(&mut self, path: Nibbles, node: &[u8]) -> Result<(), SparseTrieError> {
+        if !matches!(self.nodes.get(&path), Some(SparseNode::Hash(_))) {
+            return Ok(())
+        }
+
+        let node = TrieNode::decode(&mut &node[..])?;
+        self.reveal_decoded_node(path, node)
+    }
+
+    fn reveal_decoded_node(
+        &mut self,
+        path: Nibbles,
+        node: TrieNode,
+    ) -> Result<(), SparseTrieError> {
+        match node {
+            TrieNode::Branch(branch) => {
+                let mut state_mask = 0u16;
+                let mut children = branch.stack.iter();
+                for nibble in 0..16u8 {
+                    if !branch.state_mask.is_bit_set(nibble) {
+                        continue
+                    }
+                    state_mask |= 1 << nibble;
+                    let child =
+                        children.next().ok_or(SparseTrieError::InvalidNode(path.clone()))?;
+                    self.reveal_child(join(&path, &[nibble]), child)?;
+                }
+                self.nodes.insert(path, SparseNode::Branch { state_mask });
+            }
+            TrieNode::Extension(extension) => {
+                self.reveal_child(join(&path, &extension.key), &extension.child)?;
+                self.nodes.insert(path, SparseNode::Extension { key: extension.key });
+            }
+            TrieNode::Leaf(leaf) => {
+                self.values.insert(join(&path, &leaf.key), leaf.value);
+                self.nodes.insert(path, SparseNode::Leaf { key: leaf.key });
+            }
+        }
+        Ok(())
+    }
+
+    /// Reveals the child node by its RLP reference: either its hash, or the node itself if its
+    /// encoding is shorter than 32 bytes.
+    fn reveal_child(&mut self, path: Nibbles, child: &[u8]) -> Result<(), SparseTrieError> {
+        if child.len() == B256::len_bytes() + 1 {
+            self.nodes
+                .entry(path)
+                .or_insert_with(|| SparseNode::Hash(B256::from_slice(&child[1..])));
+            Ok(())
+        } else {
+            self.reveal_decoded_node(path, TrieNode::decode(&mut &child[..])?)
+        }
+    }
+
+    /// Inserts or updates the leaf with the given full path.
+    ///
+    /// Returns [`SparseTrieError::BlindedNode`] if a node on the path is not revealed.
+    pub fn update_leaf(&mut self, path: Nibbles, value: Vec<u8>) -> Result<(), SparseTrieError> {
+        let mut current = Nibbles::default();
+        loop {
+            let node = self.nodes.get(&current).cloned().unwrap_or(SparseNode::Empty);
+            let remaining = &path[current.len()..];
+            match node {
+                SparseNode::Empty => {
+                    self.set_node(current, SparseNode::Leaf { key: to_nibbles(remaining) });
+                    break
+                }
+                SparseNode::Hash(hash) => {
+                    return Err(SparseTrieError::BlindedNode { path: current, hash })
+                }
+                SparseNode::Leaf { key } => {
+                    if key[..] == *remaining {
+                        self.rlp_cache.remove(&current);
+                        break
+                    }
+
+                    // split the leaf into a branch node with both leaves as children
+                    let common = common_prefix_length(&key, remaining);
+                    let branch_path = join(&current, &key[..common]);
+                    if common > 0 {
+                        self.set_node(
+                            current,
+                            SparseNode::Extension { key: to_nibbles(&key[..common]) },
+                        );
+                    }
+                    self.set_node(
+                        branch_path.clone(),
+                        SparseNode::Branch {
+                            state_mask: (1 << key[common]) | (1 << remaining[common]),
+                        },
+                    );
+                    self.set_node(
+                        join(&branch_path, &[key[common]]),
+                        SparseNode::Leaf { key: to_nibbles(&key[common + 1..]) },
+                    );
+                    self.set_node(
+                        join(&branch_path, &[remaining[common]]),
+                        SparseNode::Leaf { key: to_nibbles(&remaining[common + 1..]) },
+                    );
+                    break
+                }
+                SparseNode::Extension { key } => {
+                    if remaining.starts_with(&key) {
+                        self.rlp_cache.remove(&current);
+                        current = join(&current, &key);
+                        continue
+                    }
+
+                    // split the extension at the first diverging nibble
+                    let common = common_prefix_length(&key, remaining);
+                    let branch_path = join(&current, &key[..common]);
+                    if common > 0 {
+                        self.set_node(
+                            current,
+                            SparseNode::Extension { key: to_nibbles(&key[..common]) },
+                        );
+                    }
+                    self.set_node(
+                        branch_path.clone(),
+                        SparseNode::Branch {
+                            state_mask: (1 << key[common]) | (1 << remaining[common]),
+                        },
+                    );
+                    // the child of the extension keeps its path, only an extension with the rest
+                    // of the key is needed if it's not a direct child of the new branch
+                    if key.len() > common + 1 {
+                        self.set_node(
+                            join(&branch_path, &[key[common]]),
+                            SparseNode::Extension { key: to_nibbles(&key[common + 1..]) },
+                        );
+                    }
+                    self.set_node(
+                        join(&branch_path, &[remaining[common]]),
+                        SparseNode::Leaf { key: to_nibbles(&remaining[common + 1..]) },
+                    );
+                    break
+                }
+                SparseNode::Branch { state_mask } => {
+                    let nibble = remaining[0];
+                    let child_path = join(&current, &[nibble]);
+                    if state_mask & (1 << nibble) == 0 {
+                        self.set_node(
+                            current,
+                            SparseNode::Branch { state_mask: state_mask | (1 << nibble) },
+                        );
+                        self.set_node(
+                            child_path,
+                            SparseNode::Leaf { key: to_nibbles(&remaining[1..]) },
+                        );
+                        break
+                    }
+                    self.rlp_cache.remove(&current);
+                    current = child_path;
+                }
+            }
+        }
+
+        self.values.insert(path, value);
+        Ok(())
+    }
+
+    /// Removes the leaf with the given full path. Does nothing if the leaf doesn't exist.
+    ///
+    /// Returns [`SparseTrieError::BlindedNode`] if a node on the path, or the only remaining
+    /// sibling of the leaf which would be merged into its parent, is not revealed. The trie is not
+    /// modified in that case.
+    pub fn remove_leaf(&mut self, path: &Nibbles) -> Result<(), SparseTrieError> {
+        // find the leaf and its parents
+        let mut parents = Vec::new();
+        let mut current = Nibbles::default();
+        loop {
+            let remaining = &path[current.len()..];
+            match self.nodes.get(&current).unwrap_or(&SparseNode::Empty) {
+                SparseNode::Empty => return Ok(()),
+                SparseNode::Hash(hash) => {
+                    return Err(SparseTrieError::BlindedNode { path: current, hash: *hash })
+                }
+                SparseNode::Leaf { key } => {
+                    if key[..] != *remaining {
+                        return Ok(())
+                    }
+                    break
+                }
+                SparseNode::Extension { key } => {
+                    if !remaining.starts_with(key) {
+                        return Ok(())
+                    }
+                    let child_path = join(&current, key);
+                    parents.push(current);
+                    current = child_path;
+                }
+                SparseNode::Branch { state_mask } => {
+                    if state_mask & (1 << remaining[0]) == 0 {
+                        return Ok(())
+                    }
+                    let child_path = join(&current, &remaining[..1]);
+                    parents.push(current);
+                    current = child_path;
+                }
+            }
+        }
+
+        // The parent of a leaf is always a branch node. If only one child remains, it's merged
+        // into the branch, so it has to be revealed before anything is changed.
+        let Some(branch_path) = parents.pop() else {
+            // the leaf is the root node
+            self.remove_node(&current);
+            self.set_node(current, SparseNode::Empty);
+            self.values.remove(path);
+            return Ok(())
+        };
+        let Some(SparseNode::Branch { state_mask }) = self.nodes.get(&branch_path).cloned() else {
+            return Err(SparseTrieError::InvalidNode(branch_path))
+        };
+        let state_mask = state_mask & !(1 << path[branch_path.len()]);
+        let remaining_child = (state_mask.count_ones() == 1).then(|| {
+            let nibble = state_mask.trailing_zeros() as u8;
+            (nibble, join(&branch_path, &[nibble]))
+        });
+        if let Some((_, child_path)) = &remaining_child {
+            if let Some(SparseNode::Hash(hash)) = self.nodes.get(child_path) {
+                return Err(SparseTrieError::BlindedNode { path: child_path.clone(), hash: *hash })
+            }
+        }
+
+        for parent in &parents {
+            self.rlp_cache.remove(parent);
+        }
+        self.remove_node(&current);
+        self.values.remove(path);
+
+        let Some((nibble, child_path)) = remaining_child else {
+            self.set_node(branch_path, SparseNode::Branch { state_mask });
+            return Ok(())
+        };
+
+        // merge the remaining child into the branch node
+        let child = self.nodes.get(&child_path).cloned().unwrap_or(SparseNode::Empty);
+        let merged = match child {
+            SparseNode::Leaf { key } => {
+                self.remove_node(&child_path);
+                SparseNode::Leaf { key: join(&Nibbles::from_nibbles_unchecked([nibble]), &key) }
+            }
+            SparseNode::Extension { key } => {
+                self.remove_node(&child_path);
+                SparseNode::Extension {
+                    key: join(&Nibbles::from_nibbles_unchecked([nibble]), &key),
+                }
+            }
+            SparseNode::Branch { .. } => {
+                SparseNode::Extension { key: Nibbles::from_nibbles_unchecked([nibble]) }
+            }
+            SparseNode::Empty | SparseNode::Hash(_) => {
+                return Err(SparseTrieError::InvalidNode(child_path))
+            }
+        };
+
+        // an extension parent of the branch is merged with the new node as well
+        if let Some(parent_path) = parents.last() {
+            if let Some(SparseNode::Extension { key: parent_key }) = self.nodes.get(parent_path) {
+                let merged = match merged {
+                    SparseNode::Leaf { key } => SparseNode::Leaf { key: join(parent_key, &key) },
+                    SparseNode::Extension { key } => {
+                        SparseNode::Extension { key: join(parent_key, &key) }
+                    }
+                    _ => unreachable!("merged node is a leaf or an extension"),
+                };
+                let parent_path = parent_path.clone();
+                self.remove_node(&branch_path);
+                self.set_node(parent_path, merged);
+                return Ok(())
+            }
+        }
+
+        self.set_node(branch_path, merged);
+        Ok(())
+    }
+
+    /// Calculates the root hash of the trie.
+    pub fn root(&mut self) -> B256 {
+        let root_path = Nibbles::default();
+        match self.nodes.get(&root_path) {
+            None | Some(SparseNode::Empty) => EMPTY_ROOT_HASH,
+            Some(SparseNode::Hash(hash)) => *hash,
+            Some(_) => {
+                let mut buf = Vec::new();
+                self.encode_node(&root_path, &mut buf);
+                keccak256(&buf)
+            }
+        }
+    }
+
+    /// Returns the RLP reference of the node at the given path, the encoding of the node if it's
+    /// shorter than 32 bytes and the RLP of its hash otherwise.
+    fn rlp_node(&mut self, path: &Nibbles) -> Vec<u8> {
+        if let Some(rlp) = self.rlp_cache.get(path) {
+            return rlp.clone()
+        }
+
+        let rlp = if let Some(SparseNode::Hash(hash)) = self.nodes.get(path) {
+            word_rlp(hash)
+        } else {
+            let mut buf = Vec::new();
+            self.encode_node(path, &mut buf);
+            rlp_node(&buf)
+        };
+        self.rlp_cache.insert(path.clone(), rlp.clone());
+        rlp
+    }
+
+    /// RLP encodes the revealed node at the given path.
+    fn encode_node(&mut self, path: &Nibbles, buf: &mut Vec<u8>) {
+        match self.nodes.get(path).cloned() {
+            Some(SparseNode::Leaf { key }) => {
+                let value = self.values.get(&join(path, &key)).cloned().unwrap_or_default();
+                LeafNode { key, value }.encode(buf);
+            }
+            Some(SparseNode::Extension { key }) => {
+                let child = self.rlp_node(&join(path, &key));
+                ExtensionNode { key, child }.encode(buf);
+            }
+            Some(SparseNode::Branch { state_mask }) => {
+                let stack = (0..16u8)
+                    .filter(|nibble| state_mask & (1 << nibble) != 0)
+                    .map(|nibble| self.rlp_node(&join(path, &[nibble])))
+                    .collect();
+                BranchNode { stack, state_mask: TrieMask::new(state_mask) }.encode(buf);
+            }
+            Some(SparseNode::Empty | SparseNode::Hash(_)) | None => {
+                unreachable!("only revealed nodes are encoded")
+            }
+        }
+    }
+
+    /// Sets the node at the given path and invalidates its cached RLP.
+    fn set_node(&mut self, path: Nibbles, node: SparseNode) {
+        self.rlp_cache.remove(&path);
+        self.nodes.insert(path, node);
+    }
+
+    /// Removes the node at the given path and its cached RLP.
+    fn remove_node(&mut self, path: &Nibbles) {
+        self.rlp_cache.remove(path);
+        self.nodes.remove(path);
+    }
+}
+
+/// Error during sparse trie operations.
+#[derive(Error, Debug)]
+pub enum SparseTrieError {
+    /// The node at the path is not revealed.
+    #[error("sparse trie node at path {path:?} with hash {hash} is not revealed")]
+    BlindedNode {
+        /// Path of the blinded node.
+        path: Nibbles,
+        /// Hash of the blinded node.
+        hash: B256,
+    },
+    /// The trie structure at the path is invalid.
+    #[error("invalid sparse trie node at path {0:?}")]
+    InvalidNode(Nibbles),
+    /// Failed to decode a revealed node.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+}
+
+fn join(path: &Nibbles, key: &[u8]) -> Nibbles {
+    Nibbles::from_nibbles_unchecked([&path[..], key].concat())
+}
+
+fn to_nibbles(key: &[u8]) -> Nibbles {
+    Nibbles::from_nibbles_unchecked(key)
+}
+
+fn common_prefix_length(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use reth_primitives::U256;
+    use reth_trie::HashBuilder;
+    use reth_trie_common::proof::ProofRetainer;
+    use std::collections::BTreeMap;
+
+    fn hash_builder_root(leaves: &BTreeMap<B256, U256>) -> B256 {
+        let mut hash_builder = HashBuilder::default();
+        for (key, value) in leaves {
+            hash_builder
+                .add_leaf(Nibbles::unpack(key), alloy_rlp::encode_fixed_size(value).as_ref());
+        }
+        hash_builder.root()
+    }
+
+    #[test]
+    fn empty_trie() {
+        let mut trie = SparseTrie::empty();
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+        trie.remove_leaf(&Nibbles::unpack(B256::ZERO)).unwrap();
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn blinded_nodes_are_not_changed() {
+        let mut leaves = BTreeMap::from([
+            (B256::with_last_byte(1), U256::from(1)),
+            (B256::repeat_byte(0x10), U256::from(2)),
+            (B256::repeat_byte(0x20), U256::from(3)),
+        ]);
+        let root = hash_builder_root(&leaves);
+
+        // reveal only the path of the first leaf
+        let target = Nibbles::unpack(B256::with_last_byte(1));
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::from_iter([target.clone()]));
+        for (key, value) in &leaves {
+            hash_builder
+                .add_leaf(Nibbles::unpack(key), alloy_rlp::encode_fixed_size(value).as_ref());
+        }
+        hash_builder.root();
+
+        let mut trie = SparseTrie::blind(root);
+        for (path, node) in hash_builder.take_proofs() {
+            trie.reveal_node(path, &node).unwrap();
+        }
+        assert_eq!(trie.root(), root);
+
+        // the other leaves are blinded
+        assert!(matches!(
+            trie.update_leaf(Nibbles::unpack(B256::repeat_byte(0x10)), vec![0x01]),
+            Err(SparseTrieError::BlindedNode { .. })
+        ));
+
+        trie.update_leaf(target.clone(), alloy_rlp::encode_fixed_size(&U256::from(4)).to_vec())
+            .unwrap();
+        leaves.insert(B256::with_last_byte(1), U256::from(4));
+        assert_eq!(trie.root(), hash_builder_root(&leaves));
+
+        // the branch node has two remaining blinded children, so it is not merged
+        trie.remove_leaf(&target).unwrap();
+        leaves.remove(&B256::with_last_byte(1));
+        assert_eq!(trie.root(), hash_builder_root(&leaves));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            cases: 128, ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn fuzz_update_remove(
+            updates in proptest::collection::vec(
+                proptest::collection::btree_map(any::<[u8; 2]>(), any::<Option<u64>>(), 1..32),
+                1..8,
+            )
+        ) {
+            let mut trie = SparseTrie::empty();
+            let mut leaves = BTreeMap::default();
+            for update in updates {
+                for (key, value) in update {
+                    // short keys with a shared suffix make for many shared prefixes
+                    let key = B256::right_padding_from(&key);
+                    let path = Nibbles::unpack(key);
+                    match value {
+                        Some(value) => {
+                            let value = U256::from(value);
+                            trie.update_leaf(
+                                path,
+                                alloy_rlp::encode_fixed_size(&value).to_vec(),
+                            ).unwrap();
+                            leaves.insert(key, value);
+                        }
+                        None => {
+                            trie.remove_leaf(&path).unwrap();
+                            leaves.remove(&key);
+                        }
+                    }
+                }
+                assert_eq!(trie.root(), hash_builder_root(&leaves));
+            }
+        }
+    }
+}
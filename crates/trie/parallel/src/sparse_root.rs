@@ -0,0 +1,400 @@
+use crate::sparse::{SparseNode, SparseTrie, SparseTrieError};
+use alloy_rlp::{BufMut, Encodable};
+use reth_db::tables;
+use reth_db_api::{database::Database, transaction::DbTx};
+use reth_execution_errors::{StateRootError, StorageRootError};
+use reth_primitives::{keccak256, Account, Bytes, B256};
+use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
+use reth_trie::{
+    hashed_cursor::{HashedCursor, HashedCursorFactory},
+    proof::Proof,
+    HashedPostState, Nibbles, StateRoot, TrieAccount, EMPTY_ROOT_HASH,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use thiserror::Error;
+use tracing::*;
+
+/// Sparse trie based state root calculator.
+///
+/// Instead of walking the database tries after execution has finished, the calculator keeps
+/// in-memory [`SparseTrie`]s of the state trie and the storage tries of the touched accounts.
+/// They are revealed from proofs of the touched accounts and slots, and updated as soon as the
+/// state changes are available, e.g. after each transaction. Computing the root then only hashes
+/// the nodes on the paths of the keys changed since the last computation.
+///
+/// Internally, the calculator uses [`ConsistentDbView`] since
+/// it needs to rely on database state saying the same until
+/// the last transaction is open.
+/// See docs of using [`ConsistentDbView`] for caveats.
+#[derive(Debug)]
+pub struct SparseStateRoot<DB, Provider> {
+    /// Consistent view of the database.
+    view: ConsistentDbView<DB, Provider>,
+    /// The sparse state trie, `None` until the first proof is revealed.
+    account_trie: Option<SparseTrie>,
+    /// The sparse storage tries of the touched accounts.
+    storage_tries: HashMap<B256, SparseTrie>,
+    /// The latest values of the touched accounts.
+    accounts: HashMap<B256, Option<Account>>,
+    /// The touched slots whose paths are revealed in the storage tries.
+    revealed_slots: HashMap<B256, HashSet<B256>>,
+    /// The accounts whose leaves need to be updated before computing the root.
+    changed_accounts: HashSet<B256>,
+}
+
+impl<DB, Provider> SparseStateRoot<DB, Provider> {
+    /// Create new sparse state root calculator.
+    pub fn new(view: ConsistentDbView<DB, Provider>) -> Self {
+        Self {
+            view,
+            account_trie: None,
+            storage_tries: HashMap::default(),
+            accounts: HashMap::default(),
+            revealed_slots: HashMap::default(),
+            changed_accounts: HashSet::default(),
+        }
+    }
+}
+
+impl<DB, Provider> SparseStateRoot<DB, Provider>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync,
+{
+    /// Applies the state changes to the sparse tries, revealing the paths of the touched accounts
+    /// and slots that are not revealed yet.
+    ///
+    /// The state changes are applied on top of the previous ones, so they can be passed in as soon
+    /// as they are available, e.g. per transaction.
+    pub fn update(&mut self, state: HashedPostState) -> Result<(), SparseStateRootError> {
+        let provider_ro = self.view.provider_ro()?;
+        let tx = provider_ro.tx_ref();
+
+        let mut targets = HashMap::<B256, HashSet<B256>>::default();
+        for hashed_address in state.accounts.keys() {
+            if !self.accounts.contains_key(hashed_address) {
+                targets.entry(*hashed_address).or_default();
+            }
+        }
+        for (hashed_address, storage) in &state.storages {
+            let revealed_slots = self.revealed_slots.get(hashed_address);
+            let slots = storage
+                .storage
+                .keys()
+                .filter(|slot| {
+                    // the slots of wiped storage tries don't need to be revealed
+                    !storage.wiped && !revealed_slots.is_some_and(|slots| slots.contains(*slot))
+                })
+                .copied()
+                .collect::<HashSet<_>>();
+            if !slots.is_empty() || !self.accounts.contains_key(hashed_address) {
+                targets.entry(*hashed_address).or_default().extend(slots);
+            }
+        }
+        self.reveal(tx, targets)?;
+
+        for (hashed_address, account) in state.accounts {
+            self.accounts.insert(hashed_address, account);
+            self.changed_accounts.insert(hashed_address);
+        }
+        for (hashed_address, storage) in state.storages {
+            let storage_trie = self.storage_tries.entry(hashed_address).or_default();
+            if storage.wiped {
+                *storage_trie = SparseTrie::empty();
+            }
+            for (hashed_slot, value) in storage.storage {
+                let path = Nibbles::unpack(hashed_slot);
+                if value.is_zero() {
+                    remove_storage_leaf(storage_trie, tx, hashed_address, &path)?;
+                } else {
+                    storage_trie
+                        .update_leaf(path, alloy_rlp::encode_fixed_size(&value).to_vec())?;
+                }
+            }
+            self.changed_accounts.insert(hashed_address);
+        }
+        trace!(
+            target: "trie::sparse_state_root",
+            changed_accounts = self.changed_accounts.len(),
+            "applied state update"
+        );
+
+        Ok(())
+    }
+
+    /// Calculate the state root of the database state with all updates applied.
+    pub fn root(&mut self) -> Result<B256, SparseStateRootError> {
+        let provider_ro = self.view.provider_ro()?;
+        let tx = provider_ro.tx_ref();
+
+        let Some(account_trie) = self.account_trie.as_mut() else {
+            // nothing was updated
+            return Ok(StateRoot::from_tx(tx).root()?)
+        };
+
+        let mut account_rlp = Vec::with_capacity(128);
+        for hashed_address in self.changed_accounts.drain() {
+            let path = Nibbles::unpack(hashed_address);
+            match self.accounts.get(&hashed_address).copied().flatten() {
+                Some(account) => {
+                    let storage_root = self
+                        .storage_tries
+                        .get_mut(&hashed_address)
+                        .map_or(EMPTY_ROOT_HASH, SparseTrie::root);
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+                    account_trie.update_leaf(path, account_rlp.clone())?;
+                }
+                None => remove_account_leaf(account_trie, tx, &path)?,
+            }
+        }
+
+        let root = account_trie.root();
+        trace!(target: "trie::sparse_state_root", %root, "calculated state root");
+        Ok(root)
+    }
+
+    /// Reveals the paths of the target accounts and slots from a multiproof of the database state.
+    fn reveal<TX: DbTx>(
+        &mut self,
+        tx: &TX,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> Result<(), SparseStateRootError> {
+        if targets.is_empty() {
+            return Ok(())
+        }
+
+        debug!(target: "trie::sparse_state_root", accounts = targets.len(), "revealing proofs");
+        let mut multiproof = Proof::from_tx(tx).multiproof(&targets)?;
+        let account_trie = self.account_trie.get_or_insert_with(|| {
+            multiproof
+                .account_subtree
+                .get(&Nibbles::default())
+                .map_or_else(SparseTrie::empty, |root| SparseTrie::blind(keccak256(root)))
+        });
+        reveal_nodes(account_trie, multiproof.account_subtree)?;
+
+        for (hashed_address, slots) in targets {
+            if !self.accounts.contains_key(&hashed_address) {
+                let account = tx
+                    .get::<tables::HashedAccounts>(hashed_address)
+                    .map_err(ProviderError::Database)?;
+                self.accounts.insert(hashed_address, account);
+            }
+
+            let storage_multiproof = multiproof.storages.remove(&hashed_address);
+            let storage_trie = self.storage_tries.entry(hashed_address).or_insert_with(|| {
+                storage_multiproof
+                    .as_ref()
+                    .map_or_else(SparseTrie::empty, |proof| SparseTrie::blind(proof.root))
+            });
+            if let Some(storage_multiproof) = storage_multiproof {
+                reveal_nodes(storage_trie, storage_multiproof.subtree)?;
+            }
+            self.revealed_slots.entry(hashed_address).or_default().extend(slots);
+        }
+
+        Ok(())
+    }
+}
+
+/// Reveals the proof nodes, which are sorted by path, in the sparse trie.
+fn reveal_nodes(
+    trie: &mut SparseTrie,
+    nodes: BTreeMap<Nibbles, Bytes>,
+) -> Result<(), SparseTrieError> {
+    for (path, node) in nodes {
+        trie.reveal_node(path, &node)?;
+    }
+    Ok(())
+}
+
+/// Removes the account leaf from the sparse state trie, revealing blinded siblings that are merged
+/// into their parent.
+fn remove_account_leaf<TX: DbTx>(
+    trie: &mut SparseTrie,
+    tx: &TX,
+    path: &Nibbles,
+) -> Result<(), SparseStateRootError> {
+    loop {
+        let prefix = match trie.remove_leaf(path) {
+            Err(SparseTrieError::BlindedNode { path, .. }) => path,
+            result => return Ok(result?),
+        };
+
+        let mut cursor = tx.hashed_account_cursor().map_err(ProviderError::Database)?;
+        let hashed_address = blinded_node_key(&mut cursor, &prefix)?;
+        let targets = HashMap::from([(hashed_address, HashSet::default())]);
+        reveal_nodes(trie, Proof::from_tx(tx).multiproof(&targets)?.account_subtree)?;
+        ensure_revealed(trie, prefix)?;
+    }
+}
+
+/// Removes the storage leaf from the sparse storage trie, revealing blinded siblings that are
+/// merged into their parent.
+fn remove_storage_leaf<TX: DbTx>(
+    trie: &mut SparseTrie,
+    tx: &TX,
+    hashed_address: B256,
+    path: &Nibbles,
+) -> Result<(), SparseStateRootError> {
+    loop {
+        let prefix = match trie.remove_leaf(path) {
+            Err(SparseTrieError::BlindedNode { path, .. }) => path,
+            result => return Ok(result?),
+        };
+
+        let mut cursor =
+            tx.hashed_storage_cursor(hashed_address).map_err(ProviderError::Database)?;
+        let hashed_slot = blinded_node_key(&mut cursor, &prefix)?;
+        let storage_multiproof =
+            Proof::from_tx(tx).storage_multiproof(hashed_address, &HashSet::from([hashed_slot]))?;
+        reveal_nodes(trie, storage_multiproof.subtree)?;
+        ensure_revealed(trie, prefix)?;
+    }
+}
+
+/// Returns an error if the node at the path is still blinded after revealing its proof.
+fn ensure_revealed(trie: &SparseTrie, path: Nibbles) -> Result<(), SparseTrieError> {
+    match trie.node(&path) {
+        Some(SparseNode::Hash(hash)) => Err(SparseTrieError::BlindedNode { path, hash: *hash }),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the first hashed key in the database that starts with the prefix of a blinded node.
+fn blinded_node_key<C: HashedCursor>(
+    cursor: &mut C,
+    prefix: &Nibbles,
+) -> Result<B256, ProviderError> {
+    cursor.first_key_with_prefix(prefix)?.ok_or_else(|| {
+        ProviderError::Database(reth_db::DatabaseError::Other(format!(
+            "no key found for blinded trie node at {prefix:?}"
+        )))
+    })
+}
+
+/// Error during sparse state root calculation.
+#[derive(Error, Debug)]
+pub enum SparseStateRootError {
+    /// Error while revealing or updating the sparse tries.
+    #[error(transparent)]
+    SparseTrie(#[from] SparseTrieError),
+    /// Error while calculating the state root or proofs.
+    #[error(transparent)]
+    StateRoot(#[from] StateRootError),
+    /// Error while calculating storage proofs.
+    #[error(transparent)]
+    StorageRoot(#[from] StorageRootError),
+    /// Provider error.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+impl From<SparseStateRootError> for ProviderError {
+    fn from(error: SparseStateRootError) -> Self {
+        match error {
+            SparseStateRootError::Provider(error) => error,
+            SparseStateRootError::StateRoot(error) => Self::Database(error.into()),
+            SparseStateRootError::StorageRoot(StorageRootError::DB(error)) => Self::Database(error),
+            SparseStateRootError::SparseTrie(error) => {
+                Self::Database(reth_db::DatabaseError::Other(error.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use reth_db_api::transaction::DbTxMut;
+    use reth_primitives::{StorageEntry, U256};
+    use reth_provider::test_utils::create_test_provider_factory;
+    use reth_trie::HashedStorage;
+
+    /// Short keys padded with zeros, so that the keys share long prefixes and removals merge
+    /// nodes.
+    fn key(bytes: [u8; 2]) -> B256 {
+        B256::right_padding_from(&bytes)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            cases: 64, ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn fuzz_sparse_state_root(
+            init_state in proptest::collection::btree_map(
+                any::<[u8; 2]>(),
+                (any::<u64>(), proptest::collection::btree_map(any::<[u8; 2]>(), 1..u64::MAX, 0..8)),
+                0..32,
+            ),
+            updates in proptest::collection::vec(
+                (
+                    proptest::collection::btree_map(any::<[u8; 2]>(), any::<Option<u64>>(), 0..8),
+                    proptest::collection::btree_map(
+                        any::<[u8; 2]>(),
+                        proptest::collection::btree_map(any::<[u8; 2]>(), 0..3u64, 1..8),
+                        0..4,
+                    ),
+                ),
+                1..8,
+            ),
+        ) {
+            let factory = create_test_provider_factory();
+            {
+                let provider_rw = factory.provider_rw().unwrap();
+                for (address, (balance, storage)) in &init_state {
+                    let account = Account { balance: U256::from(*balance), ..Default::default() };
+                    provider_rw.tx_ref().put::<tables::HashedAccounts>(key(*address), account).unwrap();
+                    for (slot, value) in storage {
+                        provider_rw
+                            .tx_ref()
+                            .put::<tables::HashedStorages>(
+                                key(*address),
+                                StorageEntry { key: key(*slot), value: U256::from(*value) },
+                            )
+                            .unwrap();
+                    }
+                }
+                // store the branch nodes, so that proofs are revealed from the trie tables
+                let (_, updates) = StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+                updates.write_to_database(provider_rw.tx_ref()).unwrap();
+                provider_rw.commit().unwrap();
+            }
+
+            let view = ConsistentDbView::new(factory.clone(), None);
+            let mut sparse = SparseStateRoot::new(view);
+            let mut expected = HashedPostState::default();
+            for (accounts, storages) in updates {
+                let mut update = HashedPostState::default();
+                for (address, balance) in accounts {
+                    let account = balance.map(|balance| Account { balance: U256::from(balance), ..Default::default() });
+                    if account.is_none() {
+                        update.storages.insert(key(address), HashedStorage::new(true));
+                    }
+                    update.accounts.insert(key(address), account);
+                }
+                for (address, storage) in storages {
+                    // the storage of destroyed accounts is only wiped
+                    if update.accounts.get(&key(address)).is_some_and(Option::is_none) {
+                        continue
+                    }
+                    let hashed_storage = update.storages.entry(key(address)).or_insert_with(|| HashedStorage::new(false));
+                    for (slot, value) in storage {
+                        hashed_storage.storage.insert(key(slot), U256::from(value));
+                    }
+                }
+
+                sparse.update(update.clone()).unwrap();
+                expected.extend(update);
+
+                let provider_ro = factory.provider().unwrap();
+                assert_eq!(sparse.root().unwrap(), expected.state_root(provider_ro.tx_ref()).unwrap());
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::Nibbles;
 use reth_primitives::{Account, B256, U256};
 
 /// Default implementation of the hashed state cursor traits.
@@ -36,6 +37,22 @@ pub trait HashedCursor {
 
     /// Move the cursor to the next entry and return it.
     fn next(&mut self) -> Result<Option<(B256, Self::Value)>, reth_db::DatabaseError>;
+
+    /// Returns the first key that starts with the given prefix, e.g. a key in the subtrie of a
+    /// trie node.
+    fn first_key_with_prefix(
+        &mut self,
+        prefix: &Nibbles,
+    ) -> Result<Option<B256>, reth_db::DatabaseError> {
+        let mut seek_key = B256::ZERO;
+        let packed = prefix.pack();
+        seek_key[..packed.len()].copy_from_slice(&packed);
+
+        Ok(self
+            .seek(seek_key)?
+            .map(|(key, _)| key)
+            .filter(|key| Nibbles::unpack(key).starts_with(prefix)))
+    }
 }
 
 /// The cursor for iterating over hashed storage entries.
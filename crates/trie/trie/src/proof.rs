@@ -2,16 +2,39 @@ use crate::{
     hashed_cursor::{HashedCursorFactory, HashedStorageCursor},
     node_iter::{TrieElement, TrieNodeIter},
     prefix_set::TriePrefixSetsMut,
-    trie_cursor::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
+    trie_cursor::TrieCursorFactory,
     walker::TrieWalker,
     HashBuilder, Nibbles,
 };
 use alloy_rlp::{BufMut, Encodable};
-use reth_db::tables;
-use reth_db_api::transaction::DbTx;
 use reth_execution_errors::{StateRootError, StorageRootError};
-use reth_primitives::{constants::EMPTY_ROOT_HASH, keccak256, Address, B256};
+use reth_primitives::{constants::EMPTY_ROOT_HASH, keccak256, Address, Bytes, B256};
 use reth_trie_common::{proof::ProofRetainer, AccountProof, StorageProof, TrieAccount};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The merkle multiproof of the state trie and the storage tries of the target accounts.
+#[derive(Clone, Default, Debug)]
+pub struct MultiProof {
+    /// The nodes of the state trie on the paths of the target accounts, keyed by their path.
+    pub account_subtree: BTreeMap<Nibbles, Bytes>,
+    /// The storage multiproofs of the target accounts that exist.
+    pub storages: HashMap<B256, StorageMultiProof>,
+}
+
+/// The merkle multiproof of a storage trie.
+#[derive(Clone, Debug)]
+pub struct StorageMultiProof {
+    /// The storage root.
+    pub root: B256,
+    /// The nodes of the storage trie on the paths of the target slots, keyed by their path.
+    pub subtree: BTreeMap<Nibbles, Bytes>,
+}
+
+impl Default for StorageMultiProof {
+    fn default() -> Self {
+        Self { root: EMPTY_ROOT_HASH, subtree: BTreeMap::default() }
+    }
+}
 
 /// A struct for generating merkle proofs.
 ///
@@ -19,24 +42,41 @@ use reth_trie_common::{proof::ProofRetainer, AccountProof, StorageProof, TrieAcc
 /// on the hash builder and follows the same algorithm as the state root calculator.
 /// See `StateRoot::root` for more info.
 #[derive(Debug)]
-pub struct Proof<'a, TX, H> {
-    /// A reference to the database transaction.
-    tx: &'a TX,
+pub struct Proof<T, H> {
+    /// The factory for traversing trie nodes.
+    trie_cursor_factory: T,
     /// The factory for hashed cursors.
     hashed_cursor_factory: H,
     /// A set of prefix sets that have changes.
     prefix_sets: TriePrefixSetsMut,
 }
 
-impl<'a, TX, H> Proof<'a, TX, H> {
+impl<T, H> Proof<T, H> {
     /// Creates a new proof generator.
-    pub fn new(tx: &'a TX, hashed_cursor_factory: H) -> Self {
-        Self { tx, hashed_cursor_factory, prefix_sets: TriePrefixSetsMut::default() }
+    pub fn new(trie_cursor_factory: T, hashed_cursor_factory: H) -> Self {
+        Self {
+            trie_cursor_factory,
+            hashed_cursor_factory,
+            prefix_sets: TriePrefixSetsMut::default(),
+        }
+    }
+
+    /// Set the trie cursor factory.
+    pub fn with_trie_cursor_factory<TF>(self, trie_cursor_factory: TF) -> Proof<TF, H> {
+        Proof {
+            trie_cursor_factory,
+            hashed_cursor_factory: self.hashed_cursor_factory,
+            prefix_sets: self.prefix_sets,
+        }
     }
 
     /// Set the hashed cursor factory.
-    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<'a, TX, HF> {
-        Proof { tx: self.tx, hashed_cursor_factory, prefix_sets: self.prefix_sets }
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<T, HF> {
+        Proof {
+            trie_cursor_factory: self.trie_cursor_factory,
+            hashed_cursor_factory,
+            prefix_sets: self.prefix_sets,
+        }
     }
 
     /// Set the prefix sets. They have to be mutable in order to allow extension with proof target.
@@ -46,16 +86,16 @@ impl<'a, TX, H> Proof<'a, TX, H> {
     }
 }
 
-impl<'a, TX> Proof<'a, TX, &'a TX> {
+impl<'a, TX> Proof<&'a TX, &'a TX> {
     /// Create a new [Proof] instance from database transaction.
     pub fn from_tx(tx: &'a TX) -> Self {
         Self::new(tx, tx)
     }
 }
 
-impl<'a, TX, H> Proof<'a, TX, H>
+impl<T, H> Proof<T, H>
 where
-    TX: DbTx,
+    T: TrieCursorFactory,
     H: HashedCursorFactory + Clone,
 {
     /// Generate an account proof from intermediate nodes.
@@ -69,8 +109,7 @@ where
         let mut account_proof = AccountProof::new(address);
 
         let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
-        let trie_cursor =
-            DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+        let trie_cursor = self.trie_cursor_factory.account_trie_cursor()?;
 
         // Create the walker.
         let mut prefix_set = self.prefix_sets.account_prefix_set.clone();
@@ -115,6 +154,102 @@ where
         Ok(account_proof)
     }
 
+    /// Generate a multiproof of the target accounts and, for each of them, of the target slots.
+    ///
+    /// The targets are keyed by hashed address and contain the hashed slots.
+    pub fn multiproof(
+        &self,
+        targets: &HashMap<B256, HashSet<B256>>,
+    ) -> Result<MultiProof, StateRootError> {
+        let target_nibbles = targets.keys().map(Nibbles::unpack).collect::<Vec<_>>();
+
+        let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let trie_cursor = self.trie_cursor_factory.account_trie_cursor()?;
+
+        // Create the walker.
+        let mut prefix_set = self.prefix_sets.account_prefix_set.clone();
+        prefix_set.extend(target_nibbles.clone());
+        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
+
+        // Create a hash builder to rebuild the root node since it is not available in the database.
+        let retainer = ProofRetainer::from_iter(target_nibbles);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+
+        let mut storages = HashMap::with_capacity(targets.len());
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut account_node_iter = TrieNodeIter::new(walker, hashed_account_cursor);
+        while let Some(account_node) = account_node_iter.try_next()? {
+            match account_node {
+                TrieElement::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_address, account) => {
+                    let storage_root = if let Some(slots) = targets.get(&hashed_address) {
+                        let storage_multiproof = self.storage_multiproof(hashed_address, slots)?;
+                        let storage_root = storage_multiproof.root;
+                        storages.insert(hashed_address, storage_multiproof);
+                        storage_root
+                    } else {
+                        self.storage_root(hashed_address)?
+                    };
+
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        Ok(MultiProof { account_subtree: hash_builder.take_proofs(), storages })
+    }
+
+    /// Generate a multiproof of the target hashed slots in the storage trie of the account.
+    pub fn storage_multiproof(
+        &self,
+        hashed_address: B256,
+        targets: &HashSet<B256>,
+    ) -> Result<StorageMultiProof, StorageRootError> {
+        let mut hashed_storage_cursor =
+            self.hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+
+        // short circuit on empty storage
+        if hashed_storage_cursor.is_storage_empty()? {
+            return Ok(StorageMultiProof::default())
+        }
+
+        let target_nibbles = targets.iter().map(Nibbles::unpack).collect::<Vec<_>>();
+        let mut prefix_set =
+            self.prefix_sets.storage_prefix_sets.get(&hashed_address).cloned().unwrap_or_default();
+        prefix_set.extend(target_nibbles.clone());
+        let trie_cursor = self.trie_cursor_factory.storage_trie_cursor(hashed_address)?;
+        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
+
+        let retainer = ProofRetainer::from_iter(target_nibbles);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        let mut storage_node_iter = TrieNodeIter::new(walker, hashed_storage_cursor);
+        while let Some(node) = storage_node_iter.try_next()? {
+            match node {
+                TrieElement::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_slot, value) => {
+                    hash_builder.add_leaf(
+                        Nibbles::unpack(hashed_slot),
+                        alloy_rlp::encode_fixed_size(&value).as_ref(),
+                    );
+                }
+            }
+        }
+
+        let root = hash_builder.root();
+
+        Ok(StorageMultiProof { root, subtree: hash_builder.take_proofs() })
+    }
+
     /// Compute storage root.
     pub fn storage_root(&self, hashed_address: B256) -> Result<B256, StorageRootError> {
         let (storage_root, _) = self.storage_root_with_proofs(hashed_address, &[])?;
@@ -141,10 +276,7 @@ where
         let mut prefix_set =
             self.prefix_sets.storage_prefix_sets.get(&hashed_address).cloned().unwrap_or_default();
         prefix_set.extend(target_nibbles.clone());
-        let trie_cursor = DatabaseStorageTrieCursor::new(
-            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
-            hashed_address,
-        );
+        let trie_cursor = self.trie_cursor_factory.storage_trie_cursor(hashed_address)?;
         let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
         let retainer = ProofRetainer::from_iter(target_nibbles);
@@ -185,7 +317,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::StateRoot;
+    use crate::{witness::TrieWitness, HashedPostState, StateRoot};
     use once_cell::sync::Lazy;
     use reth_chainspec::{Chain, ChainSpec, HOLESKY, MAINNET};
     use reth_db_api::database::Database;
@@ -315,6 +447,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn testspec_multiproof() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let targets = [
+            "0x2031f89b3ea8014eb51a78c316e42af3e0d7695f",
+            "0x33f0fc440b8477fcfbe9d0bf8649e7dea9baedb2",
+            "0x1ed9b1dd266b607ee278726d324b855a093394a6",
+        ]
+        .map(|target| Address::from_str(target).unwrap());
+
+        let provider = factory.provider().unwrap();
+        let proof = Proof::from_tx(provider.tx_ref());
+        let multiproof = proof
+            .multiproof(
+                &targets.iter().map(|target| (keccak256(target), HashSet::default())).collect(),
+            )
+            .unwrap();
+
+        // the multiproof contains exactly the nodes of the individual proofs
+        let mut expected = targets
+            .iter()
+            .flat_map(|target| proof.account_proof(*target, &[]).unwrap().proof)
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+        expected.dedup();
+        let mut nodes = multiproof.account_subtree.into_values().collect::<Vec<_>>();
+        nodes.sort_unstable();
+        assert_eq!(nodes, expected);
+        assert_eq!(multiproof.storages.len(), targets.len());
+    }
+
+    #[test]
+    fn testspec_witness_of_removed_account() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        // the only two accounts under the branch node at `0xa77d3`
+        let removed = Address::from_str("0x33f0fc440b8477fcfbe9d0bf8649e7dea9baedb2").unwrap();
+        let sibling = Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap();
+
+        let provider = factory.provider().unwrap();
+        let tx = provider.tx_ref();
+        let sibling_leaf = Proof::from_tx(tx).account_proof(sibling, &[]).unwrap().proof.pop();
+        let sibling_leaf = sibling_leaf.unwrap();
+
+        // the witness of an update only contains the nodes on the path of the account
+        let mut target = HashedPostState::default();
+        target.accounts.insert(keccak256(removed), Some(Account::default()));
+        let witness = TrieWitness::from_tx(tx).compute(&target).unwrap();
+        let removed_proof = Proof::from_tx(tx).account_proof(removed, &[]).unwrap().proof;
+        assert_eq!(witness.len(), removed_proof.len());
+        assert!(!witness.contains_key(&keccak256(&sibling_leaf)));
+
+        // removing the account merges the sibling into the parent, which requires its node
+        target.accounts.insert(keccak256(removed), None);
+        let witness = TrieWitness::from_tx(tx).compute(&target).unwrap();
+        assert!(removed_proof.iter().all(|node| witness.contains_key(&keccak256(node))));
+        assert_eq!(witness.get(&keccak256(&sibling_leaf)), Some(&sibling_leaf));
+    }
+
     #[test]
     fn testspec_empty_storage_proof() {
         // Create test database and insert genesis accounts.
@@ -3,6 +3,7 @@ use crate::{
     prefix_set::{PrefixSetMut, TriePrefixSetsMut},
     proof::Proof,
     updates::TrieUpdates,
+    witness::TrieWitness,
     Nibbles, StateRoot,
 };
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
@@ -13,7 +14,7 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_execution_errors::StateRootError;
-use reth_primitives::{keccak256, Account, Address, BlockNumber, B256, U256};
+use reth_primitives::{keccak256, Account, Address, BlockNumber, Bytes, B256, U256};
 use reth_trie_common::AccountProof;
 use revm::db::BundleAccount;
 use std::{
@@ -149,6 +150,22 @@ impl HashedPostState {
         }
     }
 
+    /// Extend this hashed post state with contents of another, cloning only its entries.
+    pub fn extend_ref(&mut self, other: &Self) {
+        self.accounts.extend(other.accounts.iter().map(|(address, account)| (*address, *account)));
+
+        for (hashed_address, storage) in &other.storages {
+            match self.storages.entry(*hashed_address) {
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(storage.clone());
+                }
+                hash_map::Entry::Occupied(mut entry) => {
+                    entry.get_mut().extend_ref(storage);
+                }
+            }
+        }
+    }
+
     /// Converts hashed post state into [`HashedPostStateSorted`].
     pub fn into_sorted(self) -> HashedPostStateSorted {
         let mut updated_accounts = Vec::new();
@@ -269,6 +286,21 @@ impl HashedPostState {
             .with_prefix_sets_mut(prefix_sets)
             .account_proof(address, slots)
     }
+
+    /// Generates the trie witness for the target state on top of this [`HashedPostState`], see
+    /// [`TrieWitness`].
+    pub fn witness<TX: DbTx>(
+        &self,
+        tx: &TX,
+        target: &Self,
+    ) -> Result<HashMap<B256, Bytes>, StateRootError> {
+        let sorted = self.clone().into_sorted();
+        let prefix_sets = self.construct_prefix_sets();
+        TrieWitness::from_tx(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets_mut(prefix_sets)
+            .compute(target)
+    }
 }
 
 /// Representation of in-memory hashed storage.
@@ -303,6 +335,15 @@ impl HashedStorage {
         }
     }
 
+    /// Extend hashed storage with contents of other, cloning only its entries.
+    pub fn extend_ref(&mut self, other: &Self) {
+        if other.wiped {
+            self.wiped = true;
+            self.storage.clear();
+        }
+        self.storage.extend(other.storage.iter().map(|(slot, value)| (*slot, *value)));
+    }
+
     /// Converts hashed storage into [`HashedStorageSorted`].
     pub fn into_sorted(self) -> HashedStorageSorted {
         let mut non_zero_valued_slots = Vec::new();
@@ -1,14 +1,18 @@
 use crate::{
     walker::TrieWalker, BranchNodeCompact, HashBuilder, Nibbles, StorageTrieEntry,
-    StoredBranchNode, StoredNibbles, StoredNibblesSubKey,
+    StoredBranchNode, StoredNibbles, StoredNibblesSubKey, TrieChangeSetsEntry,
 };
 use reth_db::tables;
 use reth_db_api::{
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    models::BlockNumberHashedAddress,
     transaction::{DbTx, DbTxMut},
 };
-use reth_primitives::B256;
-use std::collections::{HashMap, HashSet};
+use reth_primitives::{BlockNumber, B256};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::RangeInclusive,
+};
 
 /// The aggregation of trie updates.
 #[derive(PartialEq, Eq, Clone, Default, Debug)]
@@ -73,6 +77,63 @@ impl TrieUpdates {
         }
     }
 
+    /// Creates the trie updates that revert the trie nodes to their state before the given block
+    /// range from the trie changesets, see [`tables::AccountsTrieChangeSets`] and
+    /// [`tables::StoragesTrieChangeSets`].
+    ///
+    /// Each node is reverted to its value before the first change in the range. Applied on top of
+    /// the current trie, e.g. with
+    /// [`InMemoryTrieCursorFactory`](crate::trie_cursor::InMemoryTrieCursorFactory),
+    /// the reverts give the trie as of the block before the range.
+    pub fn from_revert_range<TX: DbTx>(
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, reth_db::DatabaseError> {
+        // Iterate over account trie changesets and record value before first occurring change.
+        let mut account_nodes = HashMap::<Nibbles, Option<BranchNodeCompact>>::default();
+        let mut account_changesets_cursor = tx.cursor_read::<tables::AccountsTrieChangeSets>()?;
+        for entry in account_changesets_cursor.walk_range(range.clone())? {
+            let (_, TrieChangeSetsEntry { nibbles, node }) = entry?;
+            account_nodes.entry(nibbles.0).or_insert(node);
+        }
+
+        // Iterate over storage trie changesets and record value before first occurring change.
+        let mut storage_nodes =
+            HashMap::<B256, HashMap<Nibbles, Option<BranchNodeCompact>>>::default();
+        let mut storage_changesets_cursor = tx.cursor_read::<tables::StoragesTrieChangeSets>()?;
+        for entry in storage_changesets_cursor.walk_range(BlockNumberHashedAddress::range(range))? {
+            let (key, TrieChangeSetsEntry { nibbles, node }) = entry?;
+            storage_nodes.entry(key.hashed_address()).or_default().entry(nibbles.0).or_insert(node);
+        }
+
+        let mut updates = Self::default();
+        for (nibbles, node) in account_nodes {
+            match node {
+                Some(node) => {
+                    updates.account_nodes.insert(nibbles, node);
+                }
+                None => {
+                    updates.removed_nodes.insert(nibbles);
+                }
+            }
+        }
+        for (hashed_address, nodes) in storage_nodes {
+            let storage_updates = updates.storage_tries.entry(hashed_address).or_default();
+            for (nibbles, node) in nodes {
+                match node {
+                    Some(node) => {
+                        storage_updates.storage_nodes.insert(nibbles, node);
+                    }
+                    None => {
+                        storage_updates.removed_nodes.insert(nibbles);
+                    }
+                }
+            }
+        }
+
+        Ok(updates)
+    }
+
     /// Converts trie updates into [`TrieUpdatesSorted`].
     pub fn into_sorted(self) -> TrieUpdatesSorted {
         let mut account_nodes = Vec::from_iter(self.account_nodes);
@@ -85,6 +146,81 @@ impl TrieUpdates {
         TrieUpdatesSorted { removed_nodes: self.removed_nodes, account_nodes, storage_tries }
     }
 
+    /// Writes the current values of all trie nodes changed by these updates to the trie changesets
+    /// of the given block, see [`tables::AccountsTrieChangeSets`] and
+    /// [`tables::StoragesTrieChangeSets`].
+    ///
+    /// Must be called before the updates are written to the database. Nodes that already have an
+    /// entry in the changesets of the block are skipped, so that the entry always holds the value
+    /// before the first change.
+    ///
+    /// # Returns
+    ///
+    /// The number of changeset entries written to the database.
+    pub fn write_changesets<TX>(
+        &self,
+        tx: &TX,
+        block_number: BlockNumber,
+    ) -> Result<usize, reth_db::DatabaseError>
+    where
+        TX: DbTx + DbTxMut,
+    {
+        if self.is_empty() {
+            return Ok(0)
+        }
+
+        // Track the number of inserted entries.
+        let mut num_entries = 0;
+
+        // The root node is never stored in the database.
+        let mut account_paths = self
+            .account_nodes
+            .keys()
+            .chain(&self.removed_nodes)
+            .filter(|nibbles| !nibbles.is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+        account_paths.sort_unstable();
+        account_paths.dedup();
+
+        let mut account_trie_cursor = tx.cursor_read::<tables::AccountsTrie>()?;
+        let mut account_changesets_cursor =
+            tx.cursor_dup_write::<tables::AccountsTrieChangeSets>()?;
+        for nibbles in account_paths {
+            let nibbles = StoredNibblesSubKey(nibbles);
+            if account_changesets_cursor
+                .seek_by_key_subkey(block_number, nibbles.clone())?
+                .filter(|e| e.nibbles == nibbles)
+                .is_some()
+            {
+                continue
+            }
+
+            let node = account_trie_cursor
+                .seek_exact(StoredNibbles(nibbles.0.clone()))?
+                .map(|(_, node)| node.0);
+            account_changesets_cursor
+                .upsert(block_number, TrieChangeSetsEntry { nibbles, node })?;
+            num_entries += 1;
+        }
+
+        let mut storage_tries = Vec::from_iter(&self.storage_tries);
+        storage_tries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        let mut storage_trie_cursor = tx.cursor_dup_read::<tables::StoragesTrie>()?;
+        let mut storage_changesets_cursor =
+            tx.cursor_dup_write::<tables::StoragesTrieChangeSets>()?;
+        for (hashed_address, storage_trie_updates) in storage_tries {
+            num_entries += storage_trie_updates.write_changesets_with_cursors(
+                &mut storage_trie_cursor,
+                &mut storage_changesets_cursor,
+                block_number,
+                *hashed_address,
+            )?;
+        }
+
+        Ok(num_entries)
+    }
+
     /// Flush updates all aggregated updates to the database.
     ///
     /// # Returns
@@ -235,6 +371,74 @@ impl StorageTrieUpdates {
         self.write_with_cursor(&mut cursor, hashed_address)
     }
 
+    /// Writes the current values of the storage trie nodes changed by these updates to the trie
+    /// changesets of the given block. If the storage trie is deleted, all of its nodes are
+    /// recorded.
+    ///
+    /// # Returns
+    ///
+    /// The number of changeset entries written to the database.
+    fn write_changesets_with_cursors<C, W>(
+        &self,
+        trie_cursor: &mut C,
+        changesets_cursor: &mut W,
+        block_number: BlockNumber,
+        hashed_address: B256,
+    ) -> Result<usize, reth_db::DatabaseError>
+    where
+        C: DbCursorRO<tables::StoragesTrie> + DbDupCursorRO<tables::StoragesTrie>,
+        W: DbCursorRO<tables::StoragesTrieChangeSets>
+            + DbCursorRW<tables::StoragesTrieChangeSets>
+            + DbDupCursorRO<tables::StoragesTrieChangeSets>,
+    {
+        let mut previous_nodes = BTreeMap::<Nibbles, Option<BranchNodeCompact>>::default();
+
+        // All nodes of the deleted storage trie are changed.
+        if self.is_deleted {
+            for entry in trie_cursor.walk_dup(Some(hashed_address), None)? {
+                let (_, StorageTrieEntry { nibbles, node }) = entry?;
+                previous_nodes.insert(nibbles.0, Some(node));
+            }
+        }
+
+        // The root node is never stored in the database.
+        for nibbles in self.storage_nodes.keys().chain(&self.removed_nodes) {
+            if nibbles.is_empty() || previous_nodes.contains_key(nibbles) {
+                continue
+            }
+
+            // Nodes of the deleted storage trie were all recorded above.
+            let node = if self.is_deleted {
+                None
+            } else {
+                let subkey = StoredNibblesSubKey(nibbles.clone());
+                trie_cursor
+                    .seek_by_key_subkey(hashed_address, subkey.clone())?
+                    .filter(|e| e.nibbles == subkey)
+                    .map(|e| e.node)
+            };
+            previous_nodes.insert(nibbles.clone(), node);
+        }
+
+        let key = BlockNumberHashedAddress((block_number, hashed_address));
+        let mut num_entries = 0;
+        for (nibbles, node) in previous_nodes {
+            let nibbles = StoredNibblesSubKey(nibbles);
+            if changesets_cursor
+                .seek_by_key_subkey(key, nibbles.clone())?
+                .filter(|e| e.nibbles == nibbles)
+                .is_some()
+            {
+                continue
+            }
+
+            changesets_cursor.upsert(key, TrieChangeSetsEntry { nibbles, node })?;
+            num_entries += 1;
+        }
+
+        Ok(num_entries)
+    }
+
     /// Writes updates to database.
     ///
     /// # Returns
@@ -339,3 +543,119 @@ impl StorageTrieUpdatesSorted {
         &self.removed_nodes
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hashed_cursor::HashedPostStateCursorFactory, trie_cursor::InMemoryTrieCursorFactory,
+        HashedPostState, HashedStorage, StateRoot,
+    };
+    use reth_primitives::{keccak256, Account, StorageEntry, U256};
+    use reth_provider::test_utils::create_test_provider_factory;
+
+    fn write_hashed_state<TX: DbTx + DbTxMut>(tx: &TX, state: &HashedPostState) {
+        for (hashed_address, account) in &state.accounts {
+            match account {
+                Some(account) => tx.put::<tables::HashedAccounts>(*hashed_address, *account),
+                None => tx.delete::<tables::HashedAccounts>(*hashed_address, None).map(|_| ()),
+            }
+            .unwrap();
+        }
+
+        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>().unwrap();
+        for (hashed_address, storage) in &state.storages {
+            if storage.wiped && cursor.seek_exact(*hashed_address).unwrap().is_some() {
+                cursor.delete_current_duplicates().unwrap();
+            }
+            for (hashed_slot, value) in &storage.storage {
+                if cursor
+                    .seek_by_key_subkey(*hashed_address, *hashed_slot)
+                    .unwrap()
+                    .filter(|e| e.key == *hashed_slot)
+                    .is_some()
+                {
+                    cursor.delete_current().unwrap();
+                }
+                if !value.is_zero() {
+                    cursor
+                        .upsert(*hashed_address, StorageEntry { key: *hashed_slot, value: *value })
+                        .unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn revert_trie_from_changesets() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let key = |i: u64| keccak256(i.to_be_bytes());
+
+        // Enough accounts and slots for the tries to have stored intermediate nodes.
+        let mut initial = HashedPostState::default();
+        for i in 0..2_000 {
+            initial.accounts.insert(key(i), Some(Account { nonce: i, ..Default::default() }));
+        }
+        for i in 0..2 {
+            initial.storages.insert(
+                key(i),
+                HashedStorage::from_iter(false, (1..=2_000).map(|j| (key(j), U256::from(j)))),
+            );
+        }
+        write_hashed_state(tx, &initial);
+        let (initial_root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.write_to_database(tx).unwrap();
+
+        // Block 1 updates, creates and destroys accounts and updates and removes slots.
+        let mut change = HashedPostState::default();
+        for i in (0..2_000).step_by(7) {
+            change.accounts.insert(key(i), Some(Account { nonce: i + 1, ..Default::default() }));
+        }
+        change.accounts.insert(key(2_000), Some(Account::default()));
+        change.accounts.insert(key(1), None);
+        change.storages.insert(key(1), HashedStorage::new(true));
+        change.storages.insert(
+            key(0),
+            HashedStorage::from_iter(
+                false,
+                (1..=2_000).step_by(3).map(|j| (key(j), U256::from(j % 2 * (j + 1)))),
+            ),
+        );
+        let (changed_root, updates) = change.state_root_with_updates(tx).unwrap();
+        assert!(updates.write_changesets(tx, 1).unwrap() > 0);
+        updates.write_to_database(tx).unwrap();
+        write_hashed_state(tx, &change);
+        assert_eq!(StateRoot::from_tx(tx).root().unwrap(), changed_root);
+
+        // The values before the block of all changed accounts and slots.
+        let mut revert_state = HashedPostState::default();
+        for hashed_address in change.accounts.keys() {
+            revert_state
+                .accounts
+                .insert(*hashed_address, initial.accounts.get(hashed_address).copied().flatten());
+        }
+        revert_state.storages.insert(key(1), initial.storages[&key(1)].clone());
+        revert_state.storages.insert(
+            key(0),
+            HashedStorage::from_iter(
+                false,
+                change.storages[&key(0)].storage.keys().map(|hashed_slot| {
+                    (*hashed_slot, initial.storages[&key(0)].storage[hashed_slot])
+                }),
+            ),
+        );
+
+        // Without prefix sets, the root is computed from the reverted trie nodes.
+        let trie_reverts = TrieUpdates::from_revert_range(tx, 1..=1).unwrap().into_sorted();
+        let revert_state = revert_state.into_sorted();
+        let root = StateRoot::from_tx(tx)
+            .with_trie_cursor_factory(InMemoryTrieCursorFactory::new(tx, &trie_reverts))
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &revert_state))
+            .root()
+            .unwrap();
+        assert_eq!(root, initial_root);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    hashed_cursor::{HashedCursor, HashedCursorFactory},
+    prefix_set::TriePrefixSetsMut,
+    proof::{MultiProof, Proof},
+    trie_cursor::TrieCursorFactory,
+    HashedPostState, Nibbles,
+};
+use alloy_rlp::Decodable;
+use reth_db::DatabaseError;
+use reth_execution_errors::StateRootError;
+use reth_primitives::{keccak256, Bytes, B256};
+use reth_trie_common::nodes::TrieNode;
+use std::collections::{hash_map, BTreeMap, HashMap, HashSet};
+
+/// State transition witness for the trie.
+///
+/// The witness contains the trie nodes of the current state that are required to apply the target
+/// state on top of it and compute the new state root: the nodes on the paths of all target
+/// accounts and slots, and the nodes of subtries that are merged into their parent when the
+/// target removes all their siblings.
+#[derive(Debug)]
+pub struct TrieWitness<T, H> {
+    /// The factory for traversing trie nodes.
+    trie_cursor_factory: T,
+    /// The factory for hashed cursors.
+    hashed_cursor_factory: H,
+    /// A set of prefix sets that have changes.
+    prefix_sets: TriePrefixSetsMut,
+}
+
+impl<T, H> TrieWitness<T, H> {
+    /// Creates a new witness generator.
+    pub fn new(trie_cursor_factory: T, hashed_cursor_factory: H) -> Self {
+        Self {
+            trie_cursor_factory,
+            hashed_cursor_factory,
+            prefix_sets: TriePrefixSetsMut::default(),
+        }
+    }
+
+    /// Set the trie cursor factory.
+    pub fn with_trie_cursor_factory<TF>(self, trie_cursor_factory: TF) -> TrieWitness<TF, H> {
+        TrieWitness {
+            trie_cursor_factory,
+            hashed_cursor_factory: self.hashed_cursor_factory,
+            prefix_sets: self.prefix_sets,
+        }
+    }
+
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> TrieWitness<T, HF> {
+        TrieWitness {
+            trie_cursor_factory: self.trie_cursor_factory,
+            hashed_cursor_factory,
+            prefix_sets: self.prefix_sets,
+        }
+    }
+
+    /// Set the prefix sets. They have to be mutable in order to allow extension with proof target.
+    pub fn with_prefix_sets_mut(mut self, prefix_sets: TriePrefixSetsMut) -> Self {
+        self.prefix_sets = prefix_sets;
+        self
+    }
+}
+
+impl<'a, TX> TrieWitness<&'a TX, &'a TX> {
+    /// Create a new [`TrieWitness`] instance from database transaction.
+    pub fn from_tx(tx: &'a TX) -> Self {
+        Self::new(tx, tx)
+    }
+}
+
+impl<T, H> TrieWitness<T, H>
+where
+    T: TrieCursorFactory + Clone,
+    H: HashedCursorFactory + Clone,
+{
+    /// Compute the witness for the target state, returning the trie nodes keyed by their hash.
+    ///
+    /// The target state contains every account and slot that was accessed, with their new values.
+    /// Removed accounts and zero-valued slots are deleted from the trie.
+    pub fn compute(
+        &self,
+        target: &HashedPostState,
+    ) -> Result<HashMap<B256, Bytes>, StateRootError> {
+        let mut proof_targets = HashMap::<B256, HashSet<B256>>::default();
+        for hashed_address in target.accounts.keys() {
+            proof_targets.entry(*hashed_address).or_default();
+        }
+        for (hashed_address, storage) in &target.storages {
+            let slots = proof_targets.entry(*hashed_address).or_default();
+            // the nodes of wiped storage tries are not needed
+            if !storage.wiped {
+                slots.extend(storage.storage.keys());
+            }
+        }
+
+        let removed_accounts = target
+            .accounts
+            .iter()
+            .filter(|(_, account)| account.is_none())
+            .map(|(hashed_address, _)| *hashed_address)
+            .collect::<Vec<_>>();
+
+        // Removing keys can turn a branch node into an extension or leaf node that is merged with
+        // its only remaining child, whose node is then required as well. The proofs of the first
+        // keys in the remaining subtries are added until no more nodes are required.
+        loop {
+            let multiproof =
+                Proof::new(self.trie_cursor_factory.clone(), self.hashed_cursor_factory.clone())
+                    .with_prefix_sets_mut(self.prefix_sets.clone())
+                    .multiproof(&proof_targets)?;
+
+            let mut added = false;
+            for prefix in remaining_siblings(&multiproof.account_subtree, &removed_accounts)? {
+                let mut cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+                if let Some(hashed_address) = cursor.first_key_with_prefix(&prefix)? {
+                    if let hash_map::Entry::Vacant(entry) = proof_targets.entry(hashed_address) {
+                        entry.insert(HashSet::default());
+                        added = true;
+                    }
+                }
+            }
+
+            for (hashed_address, storage) in &target.storages {
+                let Some(storage_multiproof) = multiproof.storages.get(hashed_address) else {
+                    continue
+                };
+                if storage.wiped {
+                    continue
+                }
+
+                let removed_slots = storage
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| value.is_zero())
+                    .map(|(hashed_slot, _)| *hashed_slot)
+                    .collect::<Vec<_>>();
+                for prefix in remaining_siblings(&storage_multiproof.subtree, &removed_slots)? {
+                    let mut cursor =
+                        self.hashed_cursor_factory.hashed_storage_cursor(*hashed_address)?;
+                    if let Some(hashed_slot) = cursor.first_key_with_prefix(&prefix)? {
+                        added |=
+                            proof_targets.entry(*hashed_address).or_default().insert(hashed_slot);
+                    }
+                }
+            }
+
+            if !added {
+                return Ok(witness_nodes(multiproof))
+            }
+        }
+    }
+}
+
+/// Returns the prefixes of the subtries that may be merged into their parent branch node, because
+/// the removed keys remove all their siblings.
+fn remaining_siblings(
+    proof: &BTreeMap<Nibbles, Bytes>,
+    removed_keys: &[B256],
+) -> Result<Vec<Nibbles>, StateRootError> {
+    if removed_keys.is_empty() {
+        return Ok(Vec::new())
+    }
+
+    let removed_keys = removed_keys.iter().map(Nibbles::unpack).collect::<Vec<_>>();
+    let mut prefixes = Vec::new();
+    for (path, node) in proof {
+        let TrieNode::Branch(branch) = TrieNode::decode(&mut &node[..])
+            .map_err(|err| DatabaseError::Other(format!("invalid trie node: {err}")))?
+        else {
+            continue
+        };
+
+        // the children that contain removed keys
+        let removed_children = removed_keys
+            .iter()
+            .filter(|key| key.len() > path.len() && key.starts_with(path))
+            .map(|key| key[path.len()])
+            .collect::<HashSet<_>>();
+        if removed_children.is_empty() {
+            continue
+        }
+
+        let remaining_children = (0..16u8)
+            .filter(|nibble| {
+                branch.state_mask.is_bit_set(*nibble) && !removed_children.contains(nibble)
+            })
+            .collect::<Vec<_>>();
+        if let [nibble] = remaining_children[..] {
+            let mut prefix = path.clone();
+            prefix.push(nibble);
+            prefixes.push(prefix);
+        }
+    }
+
+    Ok(prefixes)
+}
+
+/// Collects the nodes of the multiproof keyed by their hash.
+fn witness_nodes(multiproof: MultiProof) -> HashMap<B256, Bytes> {
+    multiproof
+        .account_subtree
+        .into_values()
+        .chain(multiproof.storages.into_values().flat_map(|storage| storage.subtree.into_values()))
+        .map(|node| (keccak256(&node), node))
+        .collect()
+}
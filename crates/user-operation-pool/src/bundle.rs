@@ -0,0 +1,130 @@
+//! Hook for payload builders that include bundles of user operations.
+//!
+//! A payload builder that acts as a bundler asks the [`UserOperationBundleProvider`] for the best
+//! bundle, and includes a transaction that calls the entry point's `handleOps` with
+//! [`UserOperationBundle::calldata`]. Signing that transaction is up to the builder, since the
+//! bundler's key is not known to the pool.
+
+use crate::{contracts, UserOperationPool, ValidUserOperation};
+use alloy_sol_types::SolCall;
+use reth_primitives::{Address, Bytes, B256, U256};
+use std::{collections::HashSet, sync::Arc};
+
+/// Fixed gas cost of the `handleOps` transaction.
+const BUNDLE_TRANSACTION_GAS: u64 = 21_000;
+
+/// A bundle of user operations for an entry point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserOperationBundle {
+    /// The entry point the operations are sent to.
+    pub entry_point: Address,
+    /// The address that receives the fees paid by the operations.
+    pub beneficiary: Address,
+    /// The operations of the bundle.
+    pub operations: Vec<Arc<ValidUserOperation>>,
+}
+
+impl UserOperationBundle {
+    /// Returns the calldata of the `handleOps` call that executes the bundle.
+    pub fn calldata(&self) -> Bytes {
+        contracts::handleOpsCall {
+            ops: self.operations.iter().map(|op| (&op.operation).into()).collect(),
+            beneficiary: self.beneficiary,
+        }
+        .abi_encode()
+        .into()
+    }
+
+    /// Returns the gas limit of the `handleOps` transaction.
+    pub fn gas_limit(&self) -> u64 {
+        self.operations.iter().fold(BUNDLE_TRANSACTION_GAS, |gas, op| {
+            gas.saturating_add(op.operation.max_gas().saturating_to())
+        })
+    }
+
+    /// Returns the hashes of the operations of the bundle.
+    pub fn hashes(&self) -> Vec<B256> {
+        self.operations.iter().map(|op| op.hash).collect()
+    }
+
+    /// Returns true if the bundle has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+/// Provides bundles of user operations to payload builders.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait UserOperationBundleProvider: Send + Sync {
+    /// Returns the entry points bundles can be built for.
+    fn supported_entry_points(&self) -> Vec<Address>;
+
+    /// Returns the best bundle for the entry point that fits into the gas limit and is valid at
+    /// the given timestamp, or `None` if there are no operations to include.
+    fn best_bundle(
+        &self,
+        entry_point: Address,
+        beneficiary: Address,
+        gas_limit: u64,
+        timestamp: u64,
+    ) -> Option<UserOperationBundle>;
+}
+
+/// A [`UserOperationBundleProvider`] that never provides bundles.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct NoopUserOperationBundleProvider;
+
+impl UserOperationBundleProvider for NoopUserOperationBundleProvider {
+    fn supported_entry_points(&self) -> Vec<Address> {
+        Vec::new()
+    }
+
+    fn best_bundle(
+        &self,
+        _entry_point: Address,
+        _beneficiary: Address,
+        _gas_limit: u64,
+        _timestamp: u64,
+    ) -> Option<UserOperationBundle> {
+        None
+    }
+}
+
+impl<V> UserOperationBundleProvider for UserOperationPool<V>
+where
+    V: Send + Sync,
+{
+    fn supported_entry_points(&self) -> Vec<Address> {
+        self.config().entry_points.clone()
+    }
+
+    /// Picks the best operations by max priority fee, at most one per sender since the entry
+    /// point only allows a sender to be validated once per bundle.
+    fn best_bundle(
+        &self,
+        entry_point: Address,
+        beneficiary: Address,
+        gas_limit: u64,
+        timestamp: u64,
+    ) -> Option<UserOperationBundle> {
+        let mut bundle = UserOperationBundle { entry_point, beneficiary, operations: Vec::new() };
+        let mut senders = HashSet::new();
+        let mut gas = U256::from(BUNDLE_TRANSACTION_GAS);
+
+        for op in self.best_operations(entry_point) {
+            if !op.is_valid_at(timestamp) || senders.contains(&op.sender()) {
+                continue
+            }
+            let op_gas = op.operation.max_gas();
+            if gas + op_gas > U256::from(gas_limit) {
+                continue
+            }
+            gas += op_gas;
+            senders.insert(op.sender());
+            bundle.operations.push(op);
+        }
+
+        (!bundle.is_empty()).then_some(bundle)
+    }
+}
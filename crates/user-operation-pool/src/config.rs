@@ -0,0 +1,116 @@
+//! Configuration of the user operation pool.
+
+use reth_primitives::{address, Address, U256};
+use std::time::Duration;
+
+/// Address of the v0.6 entry point, deployed at the same address on all chains.
+pub const ENTRY_POINT_V06: Address = address!("5ff137d4b0fdcd49dca30c7cf57e578a026d2789");
+
+/// Default maximum number of operations in the pool.
+pub const DEFAULT_MAX_USER_OPERATIONS: usize = 4_096;
+
+/// Maximum number of operations of an unstaked account in the pool, see ERC-7562
+/// `SAME_SENDER_MEMPOOL_COUNT`.
+pub const DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_SENDER: usize = 4;
+
+/// Maximum number of operations in the pool that use the same unstaked factory or paymaster, see
+/// ERC-7562 `SAME_UNSTAKED_ENTITY_MEMPOOL_COUNT`.
+pub const DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_ENTITY: usize = 10;
+
+/// Default price bump (in %) required to replace an operation with the same sender and nonce.
+pub const DEFAULT_USER_OPERATION_PRICE_BUMP: u128 = 10;
+
+/// Default minimum stake of an entity to be considered staked: 1 ether.
+pub const DEFAULT_MIN_STAKE: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// Default minimum unstake delay of an entity to be considered staked: 1 day.
+pub const DEFAULT_MIN_UNSTAKE_DELAY_SEC: u64 = 86_400;
+
+/// Default maximum verification gas limit of an operation.
+pub const DEFAULT_MAX_VERIFICATION_GAS: u64 = 5_000_000;
+
+/// Default gas limit of the simulation of an operation.
+pub const DEFAULT_SIMULATION_GAS_LIMIT: u64 = 20_000_000;
+
+/// Configuration of the [`UserOperationPool`](crate::UserOperationPool).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserOperationPoolConfig {
+    /// The entry points operations are accepted for.
+    pub entry_points: Vec<Address>,
+    /// Maximum number of operations in the pool.
+    pub max_operations: usize,
+    /// Maximum number of operations of an unstaked account in the pool.
+    pub max_operations_per_unstaked_sender: usize,
+    /// Maximum number of operations that use the same unstaked factory or paymaster.
+    pub max_operations_per_unstaked_entity: usize,
+    /// Price bump (in %) required to replace an operation with the same sender and nonce.
+    pub price_bump: u128,
+    /// Minimum stake of an entity to be considered staked.
+    pub min_stake: U256,
+    /// Minimum unstake delay of an entity to be considered staked.
+    pub min_unstake_delay_sec: u64,
+    /// Maximum verification gas limit of an operation.
+    pub max_verification_gas: u64,
+    /// Gas limit of the simulation of an operation.
+    pub simulation_gas_limit: u64,
+    /// Configuration of the entity reputation.
+    pub reputation: ReputationConfig,
+}
+
+impl UserOperationPoolConfig {
+    /// Returns true if operations for the given entry point are accepted.
+    pub fn is_supported_entry_point(&self, entry_point: &Address) -> bool {
+        self.entry_points.contains(entry_point)
+    }
+}
+
+impl Default for UserOperationPoolConfig {
+    fn default() -> Self {
+        Self {
+            entry_points: vec![ENTRY_POINT_V06],
+            max_operations: DEFAULT_MAX_USER_OPERATIONS,
+            max_operations_per_unstaked_sender: DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_SENDER,
+            max_operations_per_unstaked_entity: DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_ENTITY,
+            price_bump: DEFAULT_USER_OPERATION_PRICE_BUMP,
+            min_stake: DEFAULT_MIN_STAKE,
+            min_unstake_delay_sec: DEFAULT_MIN_UNSTAKE_DELAY_SEC,
+            max_verification_gas: DEFAULT_MAX_VERIFICATION_GAS,
+            simulation_gas_limit: DEFAULT_SIMULATION_GAS_LIMIT,
+            reputation: Default::default(),
+        }
+    }
+}
+
+/// Configuration of the reputation of factories and paymasters, see ERC-7562.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReputationConfig {
+    /// An entity is expected to get at least one of this many operations it was seen in
+    /// included.
+    pub min_inclusion_rate_denominator: u64,
+    /// How many more operations than expected an entity can have seen before it's throttled.
+    pub throttling_slack: u64,
+    /// How many more operations than expected an entity can have seen before it's banned.
+    pub ban_slack: u64,
+    /// Maximum number of operations of a throttled entity in the pool.
+    pub throttled_entity_mempool_count: usize,
+    /// Interval at which the seen and included counters decay.
+    pub decay_interval: Duration,
+    /// Entities that are never throttled or banned.
+    pub allowlist: Vec<Address>,
+    /// Entities that are always banned.
+    pub blocklist: Vec<Address>,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            min_inclusion_rate_denominator: 10,
+            throttling_slack: 10,
+            ban_slack: 50,
+            throttled_entity_mempool_count: 4,
+            decay_interval: Duration::from_secs(60 * 60),
+            allowlist: Vec::new(),
+            blocklist: Vec::new(),
+        }
+    }
+}
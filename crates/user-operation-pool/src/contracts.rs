@@ -0,0 +1,122 @@
+//! ABI of the v0.6 entry point.
+
+use alloy_sol_types::sol;
+
+sol! {
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    struct UserOperation {
+        address sender;
+        uint256 nonce;
+        bytes initCode;
+        bytes callData;
+        uint256 callGasLimit;
+        uint256 verificationGasLimit;
+        uint256 preVerificationGas;
+        uint256 maxFeePerGas;
+        uint256 maxPriorityFeePerGas;
+        bytes paymasterAndData;
+        bytes signature;
+    }
+
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    struct ReturnInfo {
+        uint256 preOpGas;
+        uint256 prefund;
+        bool sigFailed;
+        uint48 validAfter;
+        uint48 validUntil;
+        bytes paymasterContext;
+    }
+
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    struct StakeInfo {
+        uint256 stake;
+        uint256 unstakeDelaySec;
+    }
+
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    struct AggregatorStakeInfo {
+        address aggregator;
+        StakeInfo stakeInfo;
+    }
+
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    error FailedOp(uint256 opIndex, string reason);
+
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    error ValidationResult(
+        ReturnInfo returnInfo,
+        StakeInfo senderInfo,
+        StakeInfo factoryInfo,
+        StakeInfo paymasterInfo
+    );
+
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    error ValidationResultWithAggregation(
+        ReturnInfo returnInfo,
+        StakeInfo senderInfo,
+        StakeInfo factoryInfo,
+        StakeInfo paymasterInfo,
+        AggregatorStakeInfo aggregatorInfo
+    );
+
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    error ExecutionResult(
+        uint256 preOpGas,
+        uint256 paid,
+        uint48 validAfter,
+        uint48 validUntil,
+        bool targetSuccess,
+        bytes targetResult
+    );
+
+    #[allow(missing_docs)]
+    function simulateValidation(UserOperation calldata userOp) external;
+
+    #[allow(missing_docs)]
+    function simulateHandleOp(
+        UserOperation calldata op,
+        address target,
+        bytes calldata targetCallData
+    ) external;
+
+    #[allow(missing_docs)]
+    function handleOps(UserOperation[] calldata ops, address beneficiary) external;
+
+    #[allow(missing_docs)]
+    event UserOperationEvent(
+        bytes32 indexed userOpHash,
+        address indexed sender,
+        address indexed paymaster,
+        uint256 nonce,
+        bool success,
+        uint256 actualGasCost,
+        uint256 actualGasUsed
+    );
+}
+
+impl From<&crate::UserOperation> for UserOperation {
+    fn from(op: &crate::UserOperation) -> Self {
+        Self {
+            sender: op.sender,
+            nonce: op.nonce,
+            initCode: op.init_code.clone(),
+            callData: op.call_data.clone(),
+            callGasLimit: op.call_gas_limit,
+            verificationGasLimit: op.verification_gas_limit,
+            preVerificationGas: op.pre_verification_gas,
+            maxFeePerGas: op.max_fee_per_gas,
+            maxPriorityFeePerGas: op.max_priority_fee_per_gas,
+            paymasterAndData: op.paymaster_and_data.clone(),
+            signature: op.signature.clone(),
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! User operation pool errors.
+
+use crate::{Entity, EntityKind};
+use reth_primitives::{Address, B256, U256};
+use reth_provider::ProviderError;
+
+/// Result alias for [`UserOperationError`].
+pub type UserOperationResult<T> = Result<T, UserOperationError>;
+
+/// Errors that can occur when a [`UserOperation`](crate::UserOperation) is validated or added to
+/// the pool.
+#[derive(Debug, thiserror::Error)]
+pub enum UserOperationError {
+    /// The entry point is not supported.
+    #[error("unsupported entry point {0}")]
+    UnsupportedEntryPoint(Address),
+    /// The operation is already in the pool.
+    #[error("user operation {0} already known")]
+    AlreadyKnown(B256),
+    /// A field of the operation is invalid.
+    #[error("invalid user operation: {0}")]
+    InvalidFields(String),
+    /// The pre-verification gas doesn't cover the calldata and bundle overhead.
+    #[error("pre-verification gas {got} is lower than the required {required}")]
+    PreVerificationGasTooLow {
+        /// The pre-verification gas of the operation.
+        got: U256,
+        /// The required pre-verification gas.
+        required: U256,
+    },
+    /// The verification gas limit exceeds the configured maximum.
+    #[error("verification gas limit {got} exceeds the maximum {max}")]
+    VerificationGasLimitTooHigh {
+        /// The verification gas limit of the operation.
+        got: U256,
+        /// The maximum verification gas limit.
+        max: u64,
+    },
+    /// The max fee per gas is lower than the base fee of the pending block.
+    #[error("max fee per gas {max_fee} is lower than the base fee {base_fee}")]
+    FeeCapTooLow {
+        /// The max fee per gas of the operation.
+        max_fee: U256,
+        /// The base fee of the pending block.
+        base_fee: u64,
+    },
+    /// The entry point rejected the operation during validation.
+    #[error("rejected by the {entity}: {reason}")]
+    Rejected {
+        /// The entity that rejected the operation, derived from the `AAxx` code of the reason.
+        entity: EntityKind,
+        /// The reason returned by the entry point.
+        reason: String,
+    },
+    /// The signature of the operation is invalid.
+    #[error("invalid signature")]
+    InvalidSignature,
+    /// The operation is not valid in the current time range.
+    #[error("operation is only valid between {valid_after} and {valid_until:?}")]
+    OutOfTimeRange {
+        /// The operation is valid from this timestamp on.
+        valid_after: u64,
+        /// The operation is valid until this timestamp.
+        valid_until: Option<u64>,
+    },
+    /// Operations that use signature aggregators are not supported.
+    #[error("signature aggregator {0} is not supported")]
+    UnsupportedAggregator(Address),
+    /// An entity used an opcode that is banned during validation.
+    #[error("{entity} used banned opcode {opcode:#04x}")]
+    BannedOpcode {
+        /// The entity whose validation used the opcode.
+        entity: Entity,
+        /// The banned opcode.
+        opcode: u8,
+    },
+    /// An entity accessed storage it is not allowed to access during validation.
+    #[error("{entity} accessed slot {slot} of {address}")]
+    StorageAccess {
+        /// The entity whose validation accessed the storage.
+        entity: Entity,
+        /// The address of the accessed storage.
+        address: Address,
+        /// The accessed slot.
+        slot: U256,
+    },
+    /// An entity accessed an address without code during validation.
+    #[error("{entity} accessed {address}, which has no code")]
+    AccessedAddressWithoutCode {
+        /// The entity whose validation accessed the address.
+        entity: Entity,
+        /// The accessed address.
+        address: Address,
+    },
+    /// An entity needs to be staked for what it does during validation.
+    #[error("{entity} is not staked")]
+    NotStaked {
+        /// The unstaked entity.
+        entity: Entity,
+    },
+    /// The entity is banned.
+    #[error("{0} is banned")]
+    Banned(Entity),
+    /// The entity is throttled and has too many operations in the pool.
+    #[error("{0} is throttled")]
+    Throttled(Entity),
+    /// The account or entity has too many operations in the pool.
+    #[error("{0} has too many operations in the pool")]
+    TooManyOperations(Entity),
+    /// The operation replaces an operation with the same sender and nonce without bumping the
+    /// fees.
+    #[error("replacement user operation underpriced")]
+    ReplacementUnderpriced,
+    /// The pool is full and the operation pays less than all operations in the pool.
+    #[error("user operation pool is full")]
+    PoolFull,
+    /// The simulation failed unexpectedly.
+    #[error("simulation failed: {0}")]
+    Simulation(String),
+    /// Failed to read the state.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Failed to communicate with the validation service.
+    #[error("validation service unreachable")]
+    ValidationServiceUnreachable,
+}
+
+impl UserOperationError {
+    /// Returns the ERC-4337 JSON-RPC error code of the error.
+    pub const fn rpc_code(&self) -> i32 {
+        match self {
+            Self::UnsupportedEntryPoint(_) |
+            Self::AlreadyKnown(_) |
+            Self::InvalidFields(_) |
+            Self::PreVerificationGasTooLow { .. } |
+            Self::VerificationGasLimitTooHigh { .. } |
+            Self::FeeCapTooLow { .. } |
+            Self::ReplacementUnderpriced |
+            Self::PoolFull |
+            Self::TooManyOperations(_) => -32602,
+            Self::Rejected { entity: EntityKind::Paymaster, .. } => -32501,
+            Self::Rejected { .. } | Self::Simulation(_) => -32500,
+            Self::BannedOpcode { .. } |
+            Self::StorageAccess { .. } |
+            Self::AccessedAddressWithoutCode { .. } => -32502,
+            Self::OutOfTimeRange { .. } => -32503,
+            Self::Banned(_) | Self::Throttled(_) => -32504,
+            Self::NotStaked { .. } => -32505,
+            Self::UnsupportedAggregator(_) => -32506,
+            Self::InvalidSignature => -32507,
+            Self::Provider(_) | Self::ValidationServiceUnreachable => -32603,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! An ERC-4337 `UserOperation` mempool.
+//!
+//! The pool accepts user operations for the configured entry points and keeps them until they are
+//! included in a block by a bundler. Before an operation is added, it is simulated with the entry
+//! point's `simulateValidation` by a [`UserOperationValidator`]. The simulation is traced to
+//! enforce the ERC-7562 opcode and storage rules, which ensure that the validation of an operation
+//! can't be invalidated by other operations in the same bundle.
+//!
+//! Factories and paymasters that are seen in many more operations than are included are
+//! throttled and eventually banned, see [`ReputationManager`].
+//!
+//! The pool is exposed via the ERC-4337 `eth` RPC methods, see [`UserOperationApiServer`], and
+//! payload builders can include bundles of the best operations via the
+//! [`UserOperationBundleProvider`].
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+// only needed to enable the optional block gas limit and base fee checks of revm
+use revm as _;
+
+mod bundle;
+pub use bundle::{
+    NoopUserOperationBundleProvider, UserOperationBundle, UserOperationBundleProvider,
+};
+
+mod config;
+pub use config::{
+    ReputationConfig, UserOperationPoolConfig, DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_ENTITY,
+    DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_SENDER, DEFAULT_MAX_USER_OPERATIONS,
+    DEFAULT_MAX_VERIFICATION_GAS, DEFAULT_MIN_STAKE, DEFAULT_MIN_UNSTAKE_DELAY_SEC,
+    DEFAULT_SIMULATION_GAS_LIMIT, DEFAULT_USER_OPERATION_PRICE_BUMP, ENTRY_POINT_V06,
+};
+
+pub mod contracts;
+
+mod error;
+pub use error::{UserOperationError, UserOperationResult};
+
+pub mod maintain;
+
+mod operation;
+pub use operation::{
+    Entity, EntityKind, MinedUserOperation, StakeInfo, UserOperation, UserOperationByHash,
+    UserOperationGasEstimate, ValidUserOperation,
+};
+
+mod pool;
+pub use pool::UserOperationPool;
+
+mod reputation;
+pub use reputation::{ReputationEntry, ReputationManager, ReputationStatus};
+
+mod rpc;
+pub use rpc::{UserOperationApiServer, UserOperationRpc};
+
+pub mod validate;
+pub use validate::{
+    EthUserOperationValidator, UserOperationValidationTaskExecutor, UserOperationValidator,
+};
@@ -0,0 +1,91 @@
+//! Support for maintaining the state of the user operation pool.
+
+use crate::{contracts, validate::UserOperationValidator, MinedUserOperation, UserOperationPool};
+use alloy_sol_types::SolEvent;
+use futures_util::{future::BoxFuture, FutureExt, Stream, StreamExt};
+use reth_primitives::Address;
+use reth_provider::{CanonStateNotification, Chain};
+use tokio::time::{interval_at, Instant};
+use tracing::debug;
+
+/// Returns a spawnable future for maintaining the state of the user operation pool.
+pub fn maintain_user_operation_pool_future<V, St>(
+    pool: UserOperationPool<V>,
+    events: St,
+) -> BoxFuture<'static, ()>
+where
+    V: UserOperationValidator + 'static,
+    St: Stream<Item = CanonStateNotification> + Send + Unpin + 'static,
+{
+    async move {
+        maintain_user_operation_pool(pool, events).await;
+    }
+    .boxed()
+}
+
+/// Maintains the state of the user operation pool by handling new blocks and decaying the
+/// reputation of entities.
+///
+/// Operations included in the committed blocks are removed from the pool. Operations of reverted
+/// blocks are not re-added, their senders have to resubmit them.
+///
+/// On each new tip, operations that expired are removed, and the remaining operations that share
+/// an entity with the included operations are validated again.
+pub async fn maintain_user_operation_pool<V, St>(pool: UserOperationPool<V>, mut events: St)
+where
+    V: UserOperationValidator + 'static,
+    St: Stream<Item = CanonStateNotification> + Send + Unpin + 'static,
+{
+    let decay_interval = pool.config().reputation.decay_interval;
+    let mut decay = interval_at(Instant::now() + decay_interval, decay_interval);
+
+    loop {
+        tokio::select! {
+            event = events.next() => {
+                let Some(event) = event else { break };
+                let chain = event.committed();
+                let tip = chain.tip();
+                let mined = mined_user_operations(&chain, &pool.config().entry_points);
+                debug!(target: "userop::pool", mined=mined.len(), tip=tip.number, "handling new chain");
+                let affected = pool.on_mined(mined);
+                pool.on_new_head_block(&tip.block);
+
+                let expired = pool.remove_expired(tip.timestamp);
+                let invalid = pool.revalidate_user_operations(affected).await;
+                debug!(target: "userop::pool", expired=expired.len(), invalid=invalid.len(), "removed user operations");
+            }
+            _ = decay.tick() => {
+                pool.decay_reputation();
+            }
+        }
+    }
+}
+
+/// Returns the user operations the given entry points executed in the blocks of the chain.
+pub fn mined_user_operations(chain: &Chain, entry_points: &[Address]) -> Vec<MinedUserOperation> {
+    let mut mined = Vec::new();
+    for (block, receipts) in chain.blocks_and_receipts() {
+        for (tx, receipt) in block.body.iter().zip(receipts) {
+            let Some(receipt) = receipt else { continue };
+            for log in &receipt.logs {
+                if !entry_points.contains(&log.address) {
+                    continue
+                }
+                let Ok(event) = contracts::UserOperationEvent::decode_log(log, true) else {
+                    continue
+                };
+                mined.push(MinedUserOperation {
+                    hash: event.userOpHash,
+                    entry_point: log.address,
+                    sender: event.sender,
+                    nonce: event.nonce,
+                    paymaster: (!event.paymaster.is_zero()).then_some(event.paymaster),
+                    block_number: block.number,
+                    block_hash: block.hash(),
+                    transaction_hash: tx.hash(),
+                });
+            }
+        }
+    }
+    mined
+}
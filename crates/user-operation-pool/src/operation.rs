@@ -0,0 +1,371 @@
+//! `UserOperation` types.
+
+use crate::contracts;
+use alloy_sol_types::SolValue;
+use reth_primitives::{keccak256, Address, BlockNumber, Bytes, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Fixed gas cost of the bundle transaction, shared by all operations of the bundle.
+const BUNDLE_FIXED_GAS: u64 = 21_000;
+/// Gas overhead of the entry point per operation.
+const PER_OPERATION_GAS: u64 = 18_300;
+/// Gas cost per word of the packed operation.
+const PER_OPERATION_WORD_GAS: u64 = 4;
+/// Calldata gas cost of a zero byte.
+const ZERO_BYTE_GAS: u64 = 4;
+/// Calldata gas cost of a non-zero byte.
+const NON_ZERO_BYTE_GAS: u64 = 16;
+/// Length of the placeholder signature used when calculating the pre-verification gas of an
+/// unsigned operation.
+const PLACEHOLDER_SIGNATURE_LEN: usize = 65;
+
+/// An ERC-4337 `UserOperation`, as defined by the v0.6 entry point.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    /// The account making the operation.
+    pub sender: Address,
+    /// Anti-replay nonce of the account.
+    pub nonce: U256,
+    /// Factory address followed by the factory calldata, if the account is not yet deployed.
+    pub init_code: Bytes,
+    /// The calldata passed to the account in the execution phase.
+    pub call_data: Bytes,
+    /// Gas allocated for the execution phase.
+    pub call_gas_limit: U256,
+    /// Gas allocated for the verification phase.
+    pub verification_gas_limit: U256,
+    /// Gas paid to the bundler to compensate for the pre-verification execution and calldata.
+    pub pre_verification_gas: U256,
+    /// Maximum fee per gas, like EIP-1559 `max_fee_per_gas`.
+    pub max_fee_per_gas: U256,
+    /// Maximum priority fee per gas, like EIP-1559 `max_priority_fee_per_gas`.
+    pub max_priority_fee_per_gas: U256,
+    /// Paymaster address followed by the paymaster data, if the operation is sponsored.
+    pub paymaster_and_data: Bytes,
+    /// Data passed to the account along with the nonce during the verification phase.
+    pub signature: Bytes,
+}
+
+impl UserOperation {
+    /// Returns the hash of the operation for the given entry point and chain.
+    ///
+    /// This is the `userOpHash` the entry point computes: the hash covers all fields except the
+    /// signature.
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> B256 {
+        let packed = (
+            self.sender,
+            self.nonce,
+            keccak256(&self.init_code),
+            keccak256(&self.call_data),
+            self.call_gas_limit,
+            self.verification_gas_limit,
+            self.pre_verification_gas,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            keccak256(&self.paymaster_and_data),
+        )
+            .abi_encode();
+        keccak256((keccak256(packed), entry_point, U256::from(chain_id)).abi_encode())
+    }
+
+    /// Returns the factory that deploys the account, if any.
+    pub fn factory(&self) -> Option<Address> {
+        address_prefix(&self.init_code)
+    }
+
+    /// Returns the paymaster that sponsors the operation, if any.
+    pub fn paymaster(&self) -> Option<Address> {
+        address_prefix(&self.paymaster_and_data)
+    }
+
+    /// Returns all entities of the operation: the account, and the factory and paymaster if any.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> {
+        [
+            Some(Entity::account(self.sender)),
+            self.factory().map(Entity::factory),
+            self.paymaster().map(Entity::paymaster),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Returns the maximum amount of gas the operation can use, including the pre-verification
+    /// gas.
+    ///
+    /// The verification gas limit applies to the account and, if present, twice to the paymaster:
+    /// once for the validation and once for the `postOp` call.
+    pub fn max_gas(&self) -> U256 {
+        let multiplier = if self.paymaster().is_some() { 3 } else { 1 };
+        self.pre_verification_gas +
+            self.verification_gas_limit * U256::from(multiplier) +
+            self.call_gas_limit
+    }
+
+    /// Calculates the gas the bundler spends on the operation outside of the entry point's
+    /// accounting: its share of the bundle transaction's fixed cost and the calldata of the
+    /// operation.
+    ///
+    /// Unsigned operations are priced with a placeholder signature.
+    pub fn calculate_pre_verification_gas(&self) -> U256 {
+        let mut op = self.clone();
+        op.pre_verification_gas = U256::from(BUNDLE_FIXED_GAS);
+        if op.signature.is_empty() {
+            op.signature = Bytes::from(vec![1u8; PLACEHOLDER_SIGNATURE_LEN]);
+        }
+        let packed = contracts::UserOperation::from(&op).abi_encode();
+
+        let calldata_gas: u64 = packed
+            .iter()
+            .map(|byte| if *byte == 0 { ZERO_BYTE_GAS } else { NON_ZERO_BYTE_GAS })
+            .sum();
+        let words = (packed.len() as u64).div_ceil(32);
+
+        U256::from(
+            calldata_gas + BUNDLE_FIXED_GAS + PER_OPERATION_GAS + PER_OPERATION_WORD_GAS * words,
+        )
+    }
+}
+
+/// Returns the address in the first 20 bytes of the data, if any.
+fn address_prefix(data: &[u8]) -> Option<Address> {
+    data.get(..20).map(Address::from_slice)
+}
+
+/// The role of an entity that takes part in a [`UserOperation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    /// The account that sends the operation.
+    Account,
+    /// The factory that deploys the account.
+    Factory,
+    /// The paymaster that pays for the operation.
+    Paymaster,
+}
+
+impl std::fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Account => f.write_str("account"),
+            Self::Factory => f.write_str("factory"),
+            Self::Paymaster => f.write_str("paymaster"),
+        }
+    }
+}
+
+/// An entity that takes part in a [`UserOperation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Entity {
+    /// The role of the entity.
+    pub kind: EntityKind,
+    /// The address of the entity.
+    pub address: Address,
+}
+
+impl Entity {
+    /// Creates a new account entity.
+    pub const fn account(address: Address) -> Self {
+        Self { kind: EntityKind::Account, address }
+    }
+
+    /// Creates a new factory entity.
+    pub const fn factory(address: Address) -> Self {
+        Self { kind: EntityKind::Factory, address }
+    }
+
+    /// Creates a new paymaster entity.
+    pub const fn paymaster(address: Address) -> Self {
+        Self { kind: EntityKind::Paymaster, address }
+    }
+}
+
+impl std::fmt::Display for Entity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind, self.address)
+    }
+}
+
+/// The stake an entity deposited in the entry point.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StakeInfo {
+    /// The staked amount.
+    pub stake: U256,
+    /// The delay after which the stake can be withdrawn.
+    pub unstake_delay_sec: u64,
+}
+
+impl StakeInfo {
+    /// Returns true if the stake and the unstake delay are at least the given minimums.
+    pub fn is_staked(&self, min_stake: U256, min_unstake_delay_sec: u64) -> bool {
+        self.stake >= min_stake && self.unstake_delay_sec >= min_unstake_delay_sec
+    }
+}
+
+/// A [`UserOperation`] that passed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidUserOperation {
+    /// The operation.
+    pub operation: UserOperation,
+    /// The entry point the operation was validated against.
+    pub entry_point: Address,
+    /// The hash of the operation.
+    pub hash: B256,
+    /// The operation is valid from this timestamp on.
+    pub valid_after: u64,
+    /// The operation is valid until this timestamp, `None` if it doesn't expire.
+    pub valid_until: Option<u64>,
+    /// The amount the account or paymaster had to prefund.
+    pub prefund: U256,
+    /// Gas used by the validation, including the pre-verification gas.
+    pub pre_operation_gas: U256,
+    /// Whether the account is staked.
+    pub account_staked: bool,
+    /// The factory of the operation and whether it's staked.
+    pub factory: Option<(Address, bool)>,
+    /// The paymaster of the operation and whether it's staked.
+    pub paymaster: Option<(Address, bool)>,
+    /// When the operation was added to the pool.
+    pub timestamp: Instant,
+}
+
+impl ValidUserOperation {
+    /// Returns the account that sends the operation.
+    pub const fn sender(&self) -> Address {
+        self.operation.sender
+    }
+
+    /// Returns the nonce of the operation.
+    pub const fn nonce(&self) -> U256 {
+        self.operation.nonce
+    }
+
+    /// Returns true if the operation is valid at the given timestamp.
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        self.valid_after <= timestamp && self.valid_until.map_or(true, |until| timestamp < until)
+    }
+}
+
+/// Gas values estimated for a [`UserOperation`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationGasEstimate {
+    /// Gas overhead of the operation outside of the entry point's accounting.
+    pub pre_verification_gas: U256,
+    /// Gas required by the verification phase.
+    pub verification_gas_limit: U256,
+    /// Gas required by the execution phase.
+    pub call_gas_limit: U256,
+}
+
+/// A [`UserOperation`] that was included in a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinedUserOperation {
+    /// The hash of the operation.
+    pub hash: B256,
+    /// The entry point that executed the operation.
+    pub entry_point: Address,
+    /// The account that sent the operation.
+    pub sender: Address,
+    /// The nonce of the operation.
+    pub nonce: U256,
+    /// The paymaster that paid for the operation, if any.
+    pub paymaster: Option<Address>,
+    /// The number of the block that included the operation.
+    pub block_number: BlockNumber,
+    /// The hash of the block that included the operation.
+    pub block_hash: B256,
+    /// The hash of the bundle transaction that included the operation.
+    pub transaction_hash: B256,
+}
+
+/// Response of `eth_getUserOperationByHash`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationByHash {
+    /// The operation.
+    pub user_operation: UserOperation,
+    /// The entry point of the operation.
+    pub entry_point: Address,
+    /// The number of the block that included the operation, `None` if it's pending.
+    pub block_number: Option<U256>,
+    /// The hash of the block that included the operation, `None` if it's pending.
+    pub block_hash: Option<B256>,
+    /// The hash of the bundle transaction that included the operation, `None` if it's pending.
+    pub transaction_hash: Option<B256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{address, hex};
+
+    fn operation() -> UserOperation {
+        UserOperation {
+            sender: address!("1f9090aae28b8a3dceadf281b0f12828e676c326"),
+            nonce: U256::from(1),
+            init_code: hex!("388c818ca8b9251b393131c08a736a67ccb1929701").into(),
+            call_data: hex!("b61d27f6").into(),
+            call_gas_limit: U256::from(100_000),
+            verification_gas_limit: U256::from(150_000),
+            pre_verification_gas: U256::from(50_000),
+            max_fee_per_gas: U256::from(10),
+            max_priority_fee_per_gas: U256::from(1),
+            paymaster_and_data: Bytes::new(),
+            signature: hex!("aa").into(),
+        }
+    }
+
+    #[test]
+    fn hash_excludes_signature() {
+        let entry_point = address!("5ff137d4b0fdcd49dca30c7cf57e578a026d2789");
+        let op = operation();
+        let hash = op.hash(entry_point, 1);
+
+        let mut signed = op.clone();
+        signed.signature = hex!("bb").into();
+        assert_eq!(signed.hash(entry_point, 1), hash);
+
+        assert_ne!(op.hash(entry_point, 5), hash);
+        assert_ne!(op.hash(Address::ZERO, 1), hash);
+
+        let mut other = op;
+        other.nonce = U256::from(2);
+        assert_ne!(other.hash(entry_point, 1), hash);
+    }
+
+    #[test]
+    fn entities() {
+        let mut op = operation();
+        assert_eq!(op.factory(), Some(address!("388c818ca8b9251b393131c08a736a67ccb19297")));
+        assert_eq!(op.paymaster(), None);
+        assert_eq!(op.entities().count(), 2);
+        assert_eq!(op.max_gas(), U256::from(300_000));
+
+        op.paymaster_and_data = hex!("5ff137d4b0fdcd49dca30c7cf57e578a026d2789").into();
+        assert_eq!(op.paymaster(), Some(address!("5ff137d4b0fdcd49dca30c7cf57e578a026d2789")));
+        assert_eq!(op.entities().count(), 3);
+        assert_eq!(op.max_gas(), U256::from(600_000));
+    }
+
+    #[test]
+    fn pre_verification_gas() {
+        let op = operation();
+        let gas = op.calculate_pre_verification_gas();
+        assert!(gas > U256::from(BUNDLE_FIXED_GAS + PER_OPERATION_GAS));
+
+        // unsigned operations are priced with a placeholder signature
+        let mut unsigned = op;
+        unsigned.signature = Bytes::new();
+        assert!(unsigned.calculate_pre_verification_gas() > gas);
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let op = operation();
+        let json = serde_json::to_value(&op).unwrap();
+        assert!(json.get("callGasLimit").is_some());
+        assert_eq!(serde_json::from_value::<UserOperation>(json).unwrap(), op);
+    }
+}
@@ -0,0 +1,751 @@
+//! The user operation pool.
+
+use crate::{
+    reputation::{ReputationEntry, ReputationManager, ReputationStatus},
+    validate::UserOperationValidator,
+    Entity, MinedUserOperation, UserOperation, UserOperationByHash, UserOperationError,
+    UserOperationGasEstimate, UserOperationPoolConfig, UserOperationResult, ValidUserOperation,
+};
+use parking_lot::{Mutex, RwLock};
+use reth_primitives::{Address, SealedBlock, B256, U256};
+use schnellru::{ByLength, LruMap};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+use tokio::sync::broadcast;
+use tracing::trace;
+
+/// Number of mined operations that are kept for `eth_getUserOperationByHash`.
+const MINED_OPERATIONS_CACHE_SIZE: u32 = 10_000;
+
+/// Capacity of the channel that broadcasts new operations.
+const NEW_OPERATION_LISTENER_BUFFER_SIZE: usize = 256;
+
+/// A pool of [`UserOperation`]s, shared between the RPC, the maintenance task and the payload
+/// builder.
+///
+/// Operations are validated with the [`UserOperationValidator`] before they are added, and are
+/// ordered by their max priority fee.
+pub struct UserOperationPool<V> {
+    inner: Arc<UserOperationPoolInner<V>>,
+}
+
+impl<V> Clone for UserOperationPool<V> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<V> fmt::Debug for UserOperationPool<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserOperationPool")
+            .field("config", &self.inner.config)
+            .finish_non_exhaustive()
+    }
+}
+
+struct UserOperationPoolInner<V> {
+    validator: V,
+    config: UserOperationPoolConfig,
+    state: RwLock<PoolState>,
+    reputation: RwLock<ReputationManager>,
+    /// Operations that were removed from the pool because they were included in a block.
+    mined: Mutex<LruMap<B256, (UserOperation, MinedUserOperation)>>,
+    new_operations: broadcast::Sender<Arc<ValidUserOperation>>,
+}
+
+impl<V> UserOperationPool<V> {
+    /// Creates a new pool with the given validator and config.
+    pub fn new(validator: V, config: UserOperationPoolConfig) -> Self {
+        let (new_operations, _) = broadcast::channel(NEW_OPERATION_LISTENER_BUFFER_SIZE);
+        Self {
+            inner: Arc::new(UserOperationPoolInner {
+                validator,
+                reputation: RwLock::new(ReputationManager::new(config.reputation.clone())),
+                config,
+                state: Default::default(),
+                mined: Mutex::new(LruMap::new(ByLength::new(MINED_OPERATIONS_CACHE_SIZE))),
+                new_operations,
+            }),
+        }
+    }
+
+    /// Returns the validator of the pool.
+    pub fn validator(&self) -> &V {
+        &self.inner.validator
+    }
+
+    /// Returns the config of the pool.
+    pub fn config(&self) -> &UserOperationPoolConfig {
+        &self.inner.config
+    }
+
+    /// Returns the number of operations in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.state.read().by_hash.len()
+    }
+
+    /// Returns true if the pool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the operation with the given hash, if it's in the pool.
+    pub fn get(&self, hash: &B256) -> Option<Arc<ValidUserOperation>> {
+        self.inner.state.read().by_hash.get(hash).map(|op| Arc::clone(&op.operation))
+    }
+
+    /// Returns the operation with the given hash, if it's in the pool or was recently included in
+    /// a block.
+    pub fn get_by_hash(&self, hash: &B256) -> Option<UserOperationByHash> {
+        if let Some(op) = self.get(hash) {
+            return Some(UserOperationByHash {
+                user_operation: op.operation.clone(),
+                entry_point: op.entry_point,
+                block_number: None,
+                block_hash: None,
+                transaction_hash: None,
+            })
+        }
+
+        let mut mined = self.inner.mined.lock();
+        let (operation, mined) = mined.get(hash)?;
+        Some(UserOperationByHash {
+            user_operation: operation.clone(),
+            entry_point: mined.entry_point,
+            block_number: Some(U256::from(mined.block_number)),
+            block_hash: Some(mined.block_hash),
+            transaction_hash: Some(mined.transaction_hash),
+        })
+    }
+
+    /// Returns all operations for the given entry point, ordered by their max priority fee, best
+    /// first.
+    pub fn best_operations(&self, entry_point: Address) -> Vec<Arc<ValidUserOperation>> {
+        let state = self.inner.state.read();
+        state
+            .best
+            .iter()
+            .rev()
+            .filter_map(|(_, _, hash)| state.by_hash.get(hash))
+            .filter(|op| op.operation.entry_point == entry_point)
+            .map(|op| Arc::clone(&op.operation))
+            .collect()
+    }
+
+    /// Removes the operations with the given hashes and returns them.
+    pub fn remove_user_operations(
+        &self,
+        hashes: impl IntoIterator<Item = B256>,
+    ) -> Vec<Arc<ValidUserOperation>> {
+        let mut state = self.inner.state.write();
+        hashes.into_iter().filter_map(|hash| state.remove(&hash)).collect()
+    }
+
+    /// Removes the operations that were included in a block and updates the reputation of their
+    /// entities.
+    ///
+    /// Nonces are two dimensional: the upper 192 bits are the key and the lower 64 bits the
+    /// sequence of the nonce. Operations of the same senders with the same key and a lower
+    /// sequence are removed as well, since they can no longer be included.
+    ///
+    /// Returns the hashes of the remaining operations that share an entity with the mined
+    /// operations, since their validation may depend on state the mined operations changed.
+    pub fn on_mined(&self, mined: impl IntoIterator<Item = MinedUserOperation>) -> Vec<B256> {
+        let mut state = self.inner.state.write();
+        let mut reputation = self.inner.reputation.write();
+        let mut cache = self.inner.mined.lock();
+        let mut touched = HashSet::new();
+
+        for mined in mined {
+            touched.insert(mined.sender);
+            touched.extend(mined.paymaster);
+            if let Some(op) = state.remove(&mined.hash) {
+                for entity in op.operation.entities() {
+                    reputation.add_included(entity.address);
+                    touched.insert(entity.address);
+                }
+                cache.insert(mined.hash, (op.operation.clone(), mined.clone()));
+            } else if let Some(paymaster) = mined.paymaster {
+                reputation.add_included(paymaster);
+            }
+
+            let first_of_key = (mined.nonce >> 64) << 64;
+            let outdated = state
+                .by_sender
+                .range(
+                    (mined.entry_point, mined.sender, first_of_key)..=
+                        (mined.entry_point, mined.sender, mined.nonce),
+                )
+                .map(|(_, hash)| *hash)
+                .collect::<Vec<_>>();
+            for hash in outdated {
+                state.remove(&hash);
+            }
+
+            trace!(target: "userop::pool", hash=?mined.hash, block=mined.block_number, "user operation mined");
+        }
+
+        state
+            .by_hash
+            .values()
+            .filter(|op| op.operation.operation.entities().any(|e| touched.contains(&e.address)))
+            .map(|op| op.operation.hash)
+            .collect()
+    }
+
+    /// Removes the operations that are no longer valid at the given timestamp and returns them.
+    pub fn remove_expired(&self, timestamp: u64) -> Vec<Arc<ValidUserOperation>> {
+        let mut state = self.inner.state.write();
+        let expired = state
+            .by_hash
+            .values()
+            .filter(|op| op.operation.valid_until.map_or(false, |until| until <= timestamp))
+            .map(|op| op.operation.hash)
+            .collect::<Vec<_>>();
+        expired.iter().filter_map(|hash| state.remove(hash)).collect()
+    }
+
+    /// Returns the reputation status of the given entity.
+    pub fn reputation_status(&self, address: &Address) -> ReputationStatus {
+        self.inner.reputation.read().status(address)
+    }
+
+    /// Returns the reputation counters of all tracked entities.
+    pub fn reputation_entries(&self) -> Vec<(Address, ReputationEntry)> {
+        self.inner.reputation.read().entries().map(|(address, entry)| (*address, *entry)).collect()
+    }
+
+    /// Decays the reputation counters, see [`ReputationManager::decay`].
+    pub fn decay_reputation(&self) {
+        self.inner.reputation.write().decay();
+    }
+
+    /// Returns a new receiver for operations that are added to the pool.
+    pub fn subscribe_user_operations(&self) -> broadcast::Receiver<Arc<ValidUserOperation>> {
+        self.inner.new_operations.subscribe()
+    }
+}
+
+impl<V> UserOperationPool<V>
+where
+    V: UserOperationValidator,
+{
+    /// Validates the operation and adds it to the pool.
+    ///
+    /// Returns the hash of the operation.
+    pub async fn add_user_operation(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> UserOperationResult<B256> {
+        // reject operations of banned entities before wasting a simulation on them
+        for entity in operation.entities() {
+            if self.reputation_status(&entity.address) == ReputationStatus::Banned {
+                return Err(UserOperationError::Banned(entity))
+            }
+        }
+
+        let operation =
+            self.inner.validator.validate_user_operation(operation, entry_point).await?;
+        let hash = operation.hash;
+
+        let operation = {
+            let mut state = self.inner.state.write();
+            let reputation = self.inner.reputation.read();
+            self.check_limits(&state, &reputation, &operation)?;
+
+            let operation = Arc::new(operation);
+            state.insert(Arc::clone(&operation));
+
+            if state.by_hash.len() > self.inner.config.max_operations {
+                if let Some(evicted) = state.worst() {
+                    state.remove(&evicted);
+                    if evicted == hash {
+                        return Err(UserOperationError::PoolFull)
+                    }
+                    trace!(target: "userop::pool", ?evicted, "evicted user operation");
+                }
+            }
+            operation
+        };
+
+        {
+            let mut reputation = self.inner.reputation.write();
+            for entity in operation.operation.entities() {
+                reputation.add_seen(entity.address);
+            }
+        }
+
+        trace!(target: "userop::pool", ?hash, sender=?operation.sender(), "added user operation");
+        let _ = self.inner.new_operations.send(operation);
+
+        Ok(hash)
+    }
+
+    /// Estimates the gas values of the operation.
+    pub async fn estimate_user_operation_gas(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> UserOperationResult<UserOperationGasEstimate> {
+        self.inner.validator.estimate_user_operation_gas(operation, entry_point).await
+    }
+
+    /// Validates the operations with the given hashes again, and removes those that are no longer
+    /// valid.
+    ///
+    /// Returns the removed operations.
+    pub async fn revalidate_user_operations(
+        &self,
+        hashes: impl IntoIterator<Item = B256>,
+    ) -> Vec<Arc<ValidUserOperation>> {
+        let mut removed = Vec::new();
+        for hash in hashes {
+            let Some(op) = self.get(&hash) else { continue };
+            let result = self
+                .inner
+                .validator
+                .validate_user_operation(op.operation.clone(), op.entry_point)
+                .await;
+
+            let mut state = self.inner.state.write();
+            match result {
+                Ok(mut valid) => {
+                    // the operation may have been removed or replaced in the meantime
+                    if let Some(pooled) = state.by_hash.get_mut(&hash) {
+                        valid.timestamp = op.timestamp;
+                        pooled.operation = Arc::new(valid);
+                    }
+                }
+                Err(err) => {
+                    if let Some(op) = state.remove(&hash) {
+                        trace!(target: "userop::pool", ?hash, %err, "removed invalid user operation");
+                        removed.push(op);
+                    }
+                }
+            }
+        }
+        removed
+    }
+
+    /// Notifies the validator about the new head block.
+    pub fn on_new_head_block(&self, block: &SealedBlock) {
+        self.inner.validator.on_new_head_block(block)
+    }
+
+    /// Checks the operation against the pool's limits and the reputation of its entities.
+    fn check_limits(
+        &self,
+        state: &PoolState,
+        reputation: &ReputationManager,
+        operation: &ValidUserOperation,
+    ) -> UserOperationResult<()> {
+        let config = &self.inner.config;
+        if state.by_hash.contains_key(&operation.hash) {
+            return Err(UserOperationError::AlreadyKnown(operation.hash))
+        }
+
+        let replaced = state.replaced_by(operation);
+        if let Some(replaced) = replaced {
+            let existing = &replaced.operation;
+            let new = &operation.operation;
+            // the fees are user supplied and can be as large as `U256::MAX`
+            let bump = U256::from(100 + config.price_bump);
+            let hundred = U256::from(100);
+            if new.max_fee_per_gas.saturating_mul(hundred) <
+                existing.max_fee_per_gas.saturating_mul(bump) ||
+                new.max_priority_fee_per_gas.saturating_mul(hundred) <
+                    existing.max_priority_fee_per_gas.saturating_mul(bump)
+            {
+                return Err(UserOperationError::ReplacementUnderpriced)
+            }
+        }
+
+        // a replacement doesn't count towards the limits of the entities it shares with the
+        // replaced operation
+        let count = |address: Address| {
+            let shared = replaced.map_or(false, |replaced| {
+                replaced.operation.entities().any(|entity| entity.address == address)
+            });
+            state.entity_count(address).saturating_sub(usize::from(shared))
+        };
+
+        let sender = Entity::account(operation.sender());
+        if !operation.account_staked &&
+            count(sender.address) >= config.max_operations_per_unstaked_sender
+        {
+            return Err(UserOperationError::TooManyOperations(sender))
+        }
+
+        let entities = [
+            operation.factory.map(|(address, staked)| (Entity::factory(address), staked)),
+            operation.paymaster.map(|(address, staked)| (Entity::paymaster(address), staked)),
+        ];
+        for (entity, staked) in entities.into_iter().flatten() {
+            let count = count(entity.address);
+            match reputation.status(&entity.address) {
+                ReputationStatus::Banned => return Err(UserOperationError::Banned(entity)),
+                ReputationStatus::Throttled
+                    if count >= config.reputation.throttled_entity_mempool_count =>
+                {
+                    return Err(UserOperationError::Throttled(entity))
+                }
+                _ => {}
+            }
+            if !staked && count >= config.max_operations_per_unstaked_entity {
+                return Err(UserOperationError::TooManyOperations(entity))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An operation in the pool.
+#[derive(Debug)]
+struct PooledUserOperation {
+    /// Insertion order of the operation, breaks ties between operations with the same fee.
+    id: u64,
+    operation: Arc<ValidUserOperation>,
+}
+
+/// The operations in the pool and their indices.
+#[derive(Debug, Default)]
+struct PoolState {
+    by_hash: HashMap<B256, PooledUserOperation>,
+    /// Operations by entry point, sender and nonce.
+    by_sender: BTreeMap<(Address, Address, U256), B256>,
+    /// Operations ordered by max priority fee and insertion order, best last.
+    best: BTreeSet<(U256, Reverse<u64>, B256)>,
+    /// Number of operations in the pool per entity.
+    entity_counts: HashMap<Address, usize>,
+    next_id: u64,
+}
+
+impl PoolState {
+    /// Returns the operation with the same entry point, sender and nonce, if any.
+    fn replaced_by(&self, operation: &ValidUserOperation) -> Option<&Arc<ValidUserOperation>> {
+        let key = (operation.entry_point, operation.sender(), operation.nonce());
+        let hash = self.by_sender.get(&key)?;
+        self.by_hash.get(hash).map(|op| &op.operation)
+    }
+
+    /// Returns the number of operations in the pool the entity takes part in.
+    fn entity_count(&self, address: Address) -> usize {
+        self.entity_counts.get(&address).copied().unwrap_or_default()
+    }
+
+    /// Returns the hash of the operation with the lowest priority.
+    fn worst(&self) -> Option<B256> {
+        self.best.first().map(|(_, _, hash)| *hash)
+    }
+
+    /// Inserts the operation, replacing the operation with the same entry point, sender and
+    /// nonce.
+    fn insert(&mut self, operation: Arc<ValidUserOperation>) {
+        let key = (operation.entry_point, operation.sender(), operation.nonce());
+        if let Some(replaced) = self.by_sender.get(&key).copied() {
+            self.remove(&replaced);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        for entity in operation.operation.entities() {
+            *self.entity_counts.entry(entity.address).or_default() += 1;
+        }
+        self.by_sender.insert(key, operation.hash);
+        self.best.insert((
+            operation.operation.max_priority_fee_per_gas,
+            Reverse(id),
+            operation.hash,
+        ));
+        self.by_hash.insert(operation.hash, PooledUserOperation { id, operation });
+    }
+
+    /// Removes the operation with the given hash.
+    fn remove(&mut self, hash: &B256) -> Option<Arc<ValidUserOperation>> {
+        let PooledUserOperation { id, operation } = self.by_hash.remove(hash)?;
+
+        for entity in operation.operation.entities() {
+            if let Some(count) = self.entity_counts.get_mut(&entity.address) {
+                *count -= 1;
+                if *count == 0 {
+                    self.entity_counts.remove(&entity.address);
+                }
+            }
+        }
+        self.by_sender.remove(&(operation.entry_point, operation.sender(), operation.nonce()));
+        self.best.remove(&(operation.operation.max_priority_fee_per_gas, Reverse(id), *hash));
+
+        Some(operation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_ENTITY, DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_SENDER,
+        ENTRY_POINT_V06,
+    };
+    use std::time::Instant;
+
+    /// Accepts all operations of senders that aren't rejected, with the given stakes and expiry.
+    #[derive(Debug, Clone, Default)]
+    struct MockValidator {
+        staked: bool,
+        valid_until: Option<u64>,
+        rejected: Arc<Mutex<HashSet<Address>>>,
+    }
+
+    impl UserOperationValidator for MockValidator {
+        async fn validate_user_operation(
+            &self,
+            operation: UserOperation,
+            entry_point: Address,
+        ) -> UserOperationResult<ValidUserOperation> {
+            if self.rejected.lock().contains(&operation.sender) {
+                return Err(UserOperationError::Banned(Entity::account(operation.sender)))
+            }
+            Ok(ValidUserOperation {
+                hash: operation.hash(entry_point, 1),
+                factory: operation.factory().map(|factory| (factory, self.staked)),
+                paymaster: operation.paymaster().map(|paymaster| (paymaster, self.staked)),
+                operation,
+                entry_point,
+                valid_after: 0,
+                valid_until: self.valid_until,
+                prefund: U256::ZERO,
+                pre_operation_gas: U256::ZERO,
+                account_staked: self.staked,
+                timestamp: Instant::now(),
+            })
+        }
+
+        async fn estimate_user_operation_gas(
+            &self,
+            _operation: UserOperation,
+            _entry_point: Address,
+        ) -> UserOperationResult<UserOperationGasEstimate> {
+            Ok(Default::default())
+        }
+    }
+
+    fn op(sender: Address, nonce: u64, fee: u64) -> UserOperation {
+        UserOperation {
+            sender,
+            nonce: U256::from(nonce),
+            max_fee_per_gas: U256::from(fee),
+            max_priority_fee_per_gas: U256::from(fee),
+            ..Default::default()
+        }
+    }
+
+    fn pool(config: UserOperationPoolConfig) -> UserOperationPool<MockValidator> {
+        UserOperationPool::new(MockValidator::default(), config)
+    }
+
+    #[tokio::test]
+    async fn add_and_order() {
+        let pool = pool(Default::default());
+        let low =
+            pool.add_user_operation(op(Address::random(), 0, 1), ENTRY_POINT_V06).await.unwrap();
+        let high =
+            pool.add_user_operation(op(Address::random(), 0, 5), ENTRY_POINT_V06).await.unwrap();
+
+        assert!(pool.add_user_operation(op(Address::ZERO, 0, 1), ENTRY_POINT_V06).await.is_ok());
+        assert!(matches!(
+            pool.add_user_operation(op(Address::ZERO, 0, 1), ENTRY_POINT_V06).await,
+            Err(UserOperationError::AlreadyKnown(_))
+        ));
+
+        let best = pool.best_operations(ENTRY_POINT_V06);
+        assert_eq!(best.len(), 3);
+        assert_eq!(best[0].hash, high);
+        assert_eq!(best[1].hash, low);
+        assert!(pool.best_operations(Address::random()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn replacement() {
+        let pool = pool(Default::default());
+        let sender = Address::random();
+        let original = pool.add_user_operation(op(sender, 0, 100), ENTRY_POINT_V06).await.unwrap();
+
+        assert!(matches!(
+            pool.add_user_operation(op(sender, 0, 105), ENTRY_POINT_V06).await,
+            Err(UserOperationError::ReplacementUnderpriced)
+        ));
+
+        let replacement =
+            pool.add_user_operation(op(sender, 0, 110), ENTRY_POINT_V06).await.unwrap();
+        assert_eq!(pool.len(), 1);
+        assert!(pool.get(&original).is_none());
+        assert!(pool.get(&replacement).is_some());
+    }
+
+    #[tokio::test]
+    async fn replacement_with_max_fees() {
+        let pool = pool(Default::default());
+        let sender = Address::random();
+        let max_fee_op = |max_fee_per_gas| UserOperation {
+            max_fee_per_gas,
+            max_priority_fee_per_gas: U256::from(1),
+            ..op(sender, 0, 1)
+        };
+        pool.add_user_operation(max_fee_op(U256::MAX), ENTRY_POINT_V06).await.unwrap();
+
+        // the bumped fees of the existing operation must not wrap around
+        assert!(matches!(
+            pool.add_user_operation(max_fee_op(U256::from(2)), ENTRY_POINT_V06).await,
+            Err(UserOperationError::ReplacementUnderpriced)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unstaked_limits() {
+        let pool = pool(Default::default());
+        let sender = Address::random();
+        for nonce in 0..DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_SENDER as u64 {
+            pool.add_user_operation(op(sender, nonce, 1), ENTRY_POINT_V06).await.unwrap();
+        }
+        assert!(matches!(
+            pool.add_user_operation(op(sender, 100, 1), ENTRY_POINT_V06).await,
+            Err(UserOperationError::TooManyOperations(_))
+        ));
+
+        let paymaster = Address::random();
+        let sponsored = |sender| UserOperation {
+            paymaster_and_data: paymaster.to_vec().into(),
+            ..op(sender, 0, 1)
+        };
+        for _ in 0..DEFAULT_MAX_OPERATIONS_PER_UNSTAKED_ENTITY {
+            pool.add_user_operation(sponsored(Address::random()), ENTRY_POINT_V06).await.unwrap();
+        }
+        assert!(matches!(
+            pool.add_user_operation(sponsored(Address::random()), ENTRY_POINT_V06).await,
+            Err(UserOperationError::TooManyOperations(entity)) if entity == Entity::paymaster(paymaster)
+        ));
+    }
+
+    #[tokio::test]
+    async fn evicts_worst_when_full() {
+        let pool = pool(UserOperationPoolConfig { max_operations: 2, ..Default::default() });
+        let worst =
+            pool.add_user_operation(op(Address::random(), 0, 1), ENTRY_POINT_V06).await.unwrap();
+        pool.add_user_operation(op(Address::random(), 0, 2), ENTRY_POINT_V06).await.unwrap();
+
+        assert!(matches!(
+            pool.add_user_operation(op(Address::random(), 0, 1), ENTRY_POINT_V06).await,
+            Err(UserOperationError::PoolFull)
+        ));
+        pool.add_user_operation(op(Address::random(), 0, 3), ENTRY_POINT_V06).await.unwrap();
+        assert_eq!(pool.len(), 2);
+        assert!(pool.get(&worst).is_none());
+    }
+
+    #[tokio::test]
+    async fn mined_operations() {
+        let pool = pool(Default::default());
+        let sender = Address::random();
+        let mined = pool.add_user_operation(op(sender, 1, 1), ENTRY_POINT_V06).await.unwrap();
+        let outdated = pool.add_user_operation(op(sender, 0, 1), ENTRY_POINT_V06).await.unwrap();
+        let pending = pool.add_user_operation(op(sender, 2, 1), ENTRY_POINT_V06).await.unwrap();
+
+        pool.on_mined([MinedUserOperation {
+            hash: mined,
+            entry_point: ENTRY_POINT_V06,
+            sender,
+            nonce: U256::from(1),
+            paymaster: None,
+            block_number: 1,
+            block_hash: B256::random(),
+            transaction_hash: B256::random(),
+        }]);
+
+        assert!(pool.get(&outdated).is_none());
+        assert!(pool.get(&pending).is_some());
+        assert_eq!(pool.get_by_hash(&mined).unwrap().block_number, Some(U256::from(1)));
+        assert_eq!(pool.get_by_hash(&pending).unwrap().block_number, None);
+        assert_eq!(
+            pool.reputation_entries(),
+            vec![(sender, ReputationEntry { ops_seen: 3, ops_included: 1 })]
+        );
+    }
+
+    #[tokio::test]
+    async fn mined_operations_with_nonce_keys() {
+        // staked, so that the sender isn't limited
+        let validator = MockValidator { staked: true, ..Default::default() };
+        let pool = UserOperationPool::new(validator, Default::default());
+        let sender = Address::random();
+        let keyed_op = |key: u64, sequence: u64| UserOperation {
+            nonce: (U256::from(key) << 64) | U256::from(sequence),
+            ..op(sender, 0, 1)
+        };
+        let mined_op = keyed_op(1, 1);
+        let mined = pool.add_user_operation(mined_op.clone(), ENTRY_POINT_V06).await.unwrap();
+        let outdated = pool.add_user_operation(keyed_op(1, 0), ENTRY_POINT_V06).await.unwrap();
+        let pending = pool.add_user_operation(keyed_op(1, 2), ENTRY_POINT_V06).await.unwrap();
+        // lower sequences of other keys are independent of the mined operation
+        let lower_key = pool.add_user_operation(keyed_op(0, 5), ENTRY_POINT_V06).await.unwrap();
+        let higher_key = pool.add_user_operation(keyed_op(2, 0), ENTRY_POINT_V06).await.unwrap();
+
+        let affected = pool.on_mined([MinedUserOperation {
+            hash: mined,
+            entry_point: ENTRY_POINT_V06,
+            sender,
+            nonce: mined_op.nonce,
+            paymaster: None,
+            block_number: 1,
+            block_hash: B256::random(),
+            transaction_hash: B256::random(),
+        }]);
+
+        assert!(pool.get(&mined).is_none());
+        assert!(pool.get(&outdated).is_none());
+        for hash in [pending, lower_key, higher_key] {
+            assert!(pool.get(&hash).is_some());
+            assert!(affected.contains(&hash));
+        }
+        assert_eq!(affected.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn remove_expired() {
+        let validator = MockValidator { valid_until: Some(10), ..Default::default() };
+        let pool = UserOperationPool::new(validator, Default::default());
+        let hash =
+            pool.add_user_operation(op(Address::random(), 0, 1), ENTRY_POINT_V06).await.unwrap();
+
+        assert!(pool.remove_expired(9).is_empty());
+        let expired = pool.remove_expired(10);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].hash, hash);
+        assert!(pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn revalidate() {
+        let validator = MockValidator::default();
+        let rejected = Arc::clone(&validator.rejected);
+        let pool = UserOperationPool::new(validator, Default::default());
+        let valid_sender = Address::random();
+        let invalid_sender = Address::random();
+        let valid = pool.add_user_operation(op(valid_sender, 0, 1), ENTRY_POINT_V06).await.unwrap();
+        let invalid =
+            pool.add_user_operation(op(invalid_sender, 0, 1), ENTRY_POINT_V06).await.unwrap();
+
+        rejected.lock().insert(invalid_sender);
+        let removed = pool.revalidate_user_operations([valid, invalid, B256::random()]).await;
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].hash, invalid);
+        assert!(pool.get(&valid).is_some());
+        assert!(pool.get(&invalid).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+//! Reputation of the entities of user operations, see ERC-7562.
+//!
+//! Every time an operation is added to the pool, the `ops_seen` counter of its entities is
+//! increased, and every time an operation is included in a block, the `ops_included` counter is
+//! increased. An entity that is seen much more often than it is included, e.g. because its
+//! validation passes in the simulation but fails on chain, is throttled and eventually banned.
+
+use crate::config::ReputationConfig;
+use reth_primitives::Address;
+use std::collections::{HashMap, HashSet};
+
+/// The reputation status of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReputationStatus {
+    /// The entity is not restricted.
+    Ok,
+    /// The number of operations of the entity in the pool is limited.
+    Throttled,
+    /// Operations of the entity are rejected.
+    Banned,
+}
+
+/// The counters of an entity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReputationEntry {
+    /// Number of operations of the entity that were added to the pool.
+    pub ops_seen: u64,
+    /// Number of operations of the entity that were included in a block.
+    pub ops_included: u64,
+}
+
+/// Tracks the reputation of entities.
+#[derive(Debug)]
+pub struct ReputationManager {
+    config: ReputationConfig,
+    entries: HashMap<Address, ReputationEntry>,
+    allowlist: HashSet<Address>,
+    blocklist: HashSet<Address>,
+}
+
+impl ReputationManager {
+    /// Creates a new manager with the given config.
+    pub fn new(config: ReputationConfig) -> Self {
+        let allowlist = config.allowlist.iter().copied().collect();
+        let blocklist = config.blocklist.iter().copied().collect();
+        Self { config, entries: HashMap::new(), allowlist, blocklist }
+    }
+
+    /// Returns the config of the manager.
+    pub const fn config(&self) -> &ReputationConfig {
+        &self.config
+    }
+
+    /// Returns the counters of the given entity.
+    pub fn entry(&self, address: &Address) -> ReputationEntry {
+        self.entries.get(address).copied().unwrap_or_default()
+    }
+
+    /// Returns the counters of all tracked entities.
+    pub fn entries(&self) -> impl Iterator<Item = (&Address, &ReputationEntry)> + '_ {
+        self.entries.iter()
+    }
+
+    /// Returns the reputation status of the given entity.
+    pub fn status(&self, address: &Address) -> ReputationStatus {
+        if self.allowlist.contains(address) {
+            return ReputationStatus::Ok
+        }
+        if self.blocklist.contains(address) {
+            return ReputationStatus::Banned
+        }
+
+        let ReputationEntry { ops_seen, ops_included } = self.entry(address);
+        let max_seen = ops_seen / self.config.min_inclusion_rate_denominator.max(1);
+        if max_seen <= ops_included + self.config.throttling_slack {
+            ReputationStatus::Ok
+        } else if max_seen <= ops_included + self.config.ban_slack {
+            ReputationStatus::Throttled
+        } else {
+            ReputationStatus::Banned
+        }
+    }
+
+    /// Records that an operation of the entity was added to the pool.
+    pub fn add_seen(&mut self, address: Address) {
+        self.entries.entry(address).or_default().ops_seen += 1;
+    }
+
+    /// Records that an operation of the entity was included in a block.
+    pub fn add_included(&mut self, address: Address) {
+        self.entries.entry(address).or_default().ops_included += 1;
+    }
+
+    /// Decays all counters by 1/24th, meant to be called hourly.
+    ///
+    /// Entities whose counters reached zero are removed.
+    pub fn decay(&mut self) {
+        self.entries.retain(|_, entry| {
+            entry.ops_seen -= entry.ops_seen / 24;
+            entry.ops_included -= entry.ops_included / 24;
+            entry.ops_seen > 0 || entry.ops_included > 0
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_and_ban() {
+        let mut manager = ReputationManager::new(ReputationConfig::default());
+        let paymaster = Address::random();
+        assert_eq!(manager.status(&paymaster), ReputationStatus::Ok);
+
+        // 10 ops seen per expected inclusion, 10 inclusions of slack
+        for _ in 0..100 {
+            manager.add_seen(paymaster);
+        }
+        assert_eq!(manager.status(&paymaster), ReputationStatus::Ok);
+        for _ in 0..10 {
+            manager.add_seen(paymaster);
+        }
+        assert_eq!(manager.status(&paymaster), ReputationStatus::Throttled);
+
+        // inclusions restore the reputation
+        manager.add_included(paymaster);
+        assert_eq!(manager.status(&paymaster), ReputationStatus::Ok);
+
+        for _ in 0..500 {
+            manager.add_seen(paymaster);
+        }
+        assert_eq!(manager.status(&paymaster), ReputationStatus::Banned);
+    }
+
+    #[test]
+    fn allow_and_block_lists() {
+        let allowed = Address::random();
+        let blocked = Address::random();
+        let mut manager = ReputationManager::new(ReputationConfig {
+            allowlist: vec![allowed],
+            blocklist: vec![blocked],
+            ..Default::default()
+        });
+        for _ in 0..1_000 {
+            manager.add_seen(allowed);
+        }
+        assert_eq!(manager.status(&allowed), ReputationStatus::Ok);
+        assert_eq!(manager.status(&blocked), ReputationStatus::Banned);
+    }
+
+    #[test]
+    fn decay() {
+        let mut manager = ReputationManager::new(ReputationConfig::default());
+        let factory = Address::random();
+        for _ in 0..48 {
+            manager.add_seen(factory);
+        }
+        manager.add_included(factory);
+
+        manager.decay();
+        assert_eq!(manager.entry(&factory), ReputationEntry { ops_seen: 46, ops_included: 1 });
+
+        let other = Address::random();
+        manager.add_included(other);
+        for _ in 0..24 {
+            manager.decay();
+        }
+        assert_eq!(manager.entry(&other).ops_included, 1);
+    }
+}
@@ -0,0 +1,97 @@
+//! ERC-4337 `eth` namespace RPC.
+
+use crate::{
+    validate::UserOperationValidator, UserOperation, UserOperationByHash, UserOperationError,
+    UserOperationGasEstimate, UserOperationPool,
+};
+use async_trait::async_trait;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::ErrorObjectOwned};
+use reth_primitives::{Address, B256};
+
+/// ERC-4337 bundler rpc interface.
+#[rpc(server, namespace = "eth")]
+pub trait UserOperationApi {
+    /// Validates the user operation and adds it to the pool.
+    ///
+    /// Returns the hash of the operation.
+    #[method(name = "sendUserOperation")]
+    async fn send_user_operation(
+        &self,
+        user_operation: UserOperation,
+        entry_point: Address,
+    ) -> RpcResult<B256>;
+
+    /// Estimates the gas values of the user operation.
+    ///
+    /// The signature of the operation doesn't need to be valid.
+    #[method(name = "estimateUserOperationGas")]
+    async fn estimate_user_operation_gas(
+        &self,
+        user_operation: UserOperation,
+        entry_point: Address,
+    ) -> RpcResult<UserOperationGasEstimate>;
+
+    /// Returns the user operation with the given hash, if it's pending or was recently included
+    /// in a block.
+    #[method(name = "getUserOperationByHash")]
+    async fn get_user_operation_by_hash(
+        &self,
+        hash: B256,
+    ) -> RpcResult<Option<UserOperationByHash>>;
+
+    /// Returns the entry points the bundler accepts operations for.
+    #[method(name = "supportedEntryPoints")]
+    async fn supported_entry_points(&self) -> RpcResult<Vec<Address>>;
+}
+
+/// `eth` API implementation for user operations, backed by the [`UserOperationPool`].
+#[derive(Debug, Clone)]
+pub struct UserOperationRpc<V> {
+    pool: UserOperationPool<V>,
+}
+
+impl<V> UserOperationRpc<V> {
+    /// Creates a new instance with the given pool.
+    pub const fn new(pool: UserOperationPool<V>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl<V> UserOperationApiServer for UserOperationRpc<V>
+where
+    V: UserOperationValidator + 'static,
+{
+    async fn send_user_operation(
+        &self,
+        user_operation: UserOperation,
+        entry_point: Address,
+    ) -> RpcResult<B256> {
+        Ok(self.pool.add_user_operation(user_operation, entry_point).await?)
+    }
+
+    async fn estimate_user_operation_gas(
+        &self,
+        user_operation: UserOperation,
+        entry_point: Address,
+    ) -> RpcResult<UserOperationGasEstimate> {
+        Ok(self.pool.estimate_user_operation_gas(user_operation, entry_point).await?)
+    }
+
+    async fn get_user_operation_by_hash(
+        &self,
+        hash: B256,
+    ) -> RpcResult<Option<UserOperationByHash>> {
+        Ok(self.pool.get_by_hash(&hash))
+    }
+
+    async fn supported_entry_points(&self) -> RpcResult<Vec<Address>> {
+        Ok(self.pool.config().entry_points.clone())
+    }
+}
+
+impl From<UserOperationError> for ErrorObjectOwned {
+    fn from(err: UserOperationError) -> Self {
+        Self::owned(err.rpc_code(), err.to_string(), None::<()>)
+    }
+}
@@ -0,0 +1,381 @@
+//! Validation of user operations against the latest state.
+
+use super::{tracer::ValidationTracer, UserOperationValidator};
+use crate::{
+    contracts, EntityKind, StakeInfo, UserOperation, UserOperationError, UserOperationGasEstimate,
+    UserOperationPoolConfig, UserOperationResult, ValidUserOperation,
+};
+use alloy_sol_types::{SolCall, SolError};
+use reth_chainspec::ChainSpec;
+use reth_evm::ConfigureEvm;
+use reth_primitives::{Address, Bytes, TxKind, U256};
+use reth_provider::{BlockReaderIdExt, EvmEnvProvider, StateProviderFactory};
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::CacheDB,
+    inspectors::NoOpInspector,
+    primitives::{
+        BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, SpecId, TxEnv,
+    },
+    Database, GetInspector,
+};
+use std::{
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Operations that expire within this many seconds are rejected, since they are unlikely to be
+/// included in time.
+const MIN_VALIDITY_WINDOW_SECS: u64 = 30;
+
+/// Gas the entry point spends on the call to the account that is not covered by the call gas
+/// limit.
+const MIN_CALL_GAS_LIMIT: u64 = 9_100;
+
+/// Percentage added to the estimated verification gas limit.
+const VERIFICATION_GAS_BUFFER_PERCENT: u64 = 10;
+
+/// Balance given to the sender when estimating gas, so that estimation works for operations of
+/// accounts that are not funded yet.
+const ESTIMATION_BALANCE: U256 = U256::from_limbs([0, 0, 1, 0]);
+
+/// A [`UserOperationValidator`] that simulates operations on the latest state.
+#[derive(Debug)]
+pub struct EthUserOperationValidator<Client, EvmConfig> {
+    inner: Arc<EthUserOperationValidatorInner<Client, EvmConfig>>,
+}
+
+impl<Client, EvmConfig> Clone for EthUserOperationValidator<Client, EvmConfig> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<Client, EvmConfig> EthUserOperationValidator<Client, EvmConfig> {
+    /// Creates a new validator.
+    pub fn new(
+        client: Client,
+        chain_spec: Arc<ChainSpec>,
+        evm_config: EvmConfig,
+        config: UserOperationPoolConfig,
+    ) -> Self {
+        Self {
+            inner: Arc::new(EthUserOperationValidatorInner {
+                client,
+                chain_spec,
+                evm_config,
+                config,
+            }),
+        }
+    }
+
+    /// Returns the chain spec of the validator.
+    pub fn chain_spec(&self) -> &Arc<ChainSpec> {
+        &self.inner.chain_spec
+    }
+
+    /// Returns the config of the validator.
+    pub fn config(&self) -> &UserOperationPoolConfig {
+        &self.inner.config
+    }
+}
+
+impl<Client, EvmConfig> UserOperationValidator for EthUserOperationValidator<Client, EvmConfig>
+where
+    Client: StateProviderFactory + BlockReaderIdExt + EvmEnvProvider + 'static,
+    EvmConfig: ConfigureEvm,
+{
+    async fn validate_user_operation(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> UserOperationResult<ValidUserOperation> {
+        self.inner.validate(operation, entry_point)
+    }
+
+    async fn estimate_user_operation_gas(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> UserOperationResult<UserOperationGasEstimate> {
+        self.inner.estimate(operation, entry_point)
+    }
+}
+
+#[derive(Debug)]
+struct EthUserOperationValidatorInner<Client, EvmConfig> {
+    client: Client,
+    chain_spec: Arc<ChainSpec>,
+    evm_config: EvmConfig,
+    config: UserOperationPoolConfig,
+}
+
+impl<Client, EvmConfig> EthUserOperationValidatorInner<Client, EvmConfig>
+where
+    Client: StateProviderFactory + BlockReaderIdExt + EvmEnvProvider,
+    EvmConfig: ConfigureEvm,
+{
+    fn validate(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> UserOperationResult<ValidUserOperation> {
+        if !self.config.is_supported_entry_point(&entry_point) {
+            return Err(UserOperationError::UnsupportedEntryPoint(entry_point))
+        }
+        self.validate_fields(&operation)?;
+
+        let call = contracts::simulateValidationCall {
+            userOp: contracts::UserOperation::from(&operation),
+        };
+        let mut tracer = ValidationTracer::new(entry_point);
+        let db = StateProviderDatabase::new(self.client.latest()?);
+        let output = self.simulate(db, entry_point, call.abi_encode().into(), &mut tracer)?;
+
+        let result = contracts::ValidationResult::abi_decode(&output, true)
+            .map_err(|_| decode_revert(&output))?;
+        let info = result.returnInfo;
+        if info.sigFailed {
+            return Err(UserOperationError::InvalidSignature)
+        }
+
+        let valid_after = info.validAfter;
+        let valid_until = (info.validUntil != 0).then_some(info.validUntil);
+        if valid_until.is_some_and(|until| until <= unix_timestamp() + MIN_VALIDITY_WINDOW_SECS) {
+            return Err(UserOperationError::OutOfTimeRange { valid_after, valid_until })
+        }
+
+        let is_staked = |info: contracts::StakeInfo| {
+            StakeInfo { stake: info.stake, unstake_delay_sec: info.unstakeDelaySec.saturating_to() }
+                .is_staked(self.config.min_stake, self.config.min_unstake_delay_sec)
+        };
+        let account_staked = is_staked(result.senderInfo);
+        let factory = operation.factory().map(|factory| (factory, is_staked(result.factoryInfo)));
+        let paymaster =
+            operation.paymaster().map(|paymaster| (paymaster, is_staked(result.paymasterInfo)));
+
+        tracer.check(&operation, account_staked, factory, paymaster)?;
+
+        Ok(ValidUserOperation {
+            hash: operation.hash(entry_point, self.chain_spec.chain().id()),
+            operation,
+            entry_point,
+            valid_after,
+            valid_until,
+            prefund: info.prefund,
+            pre_operation_gas: info.preOpGas,
+            account_staked,
+            factory,
+            paymaster,
+            timestamp: Instant::now(),
+        })
+    }
+
+    fn estimate(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> UserOperationResult<UserOperationGasEstimate> {
+        if !self.config.is_supported_entry_point(&entry_point) {
+            return Err(UserOperationError::UnsupportedEntryPoint(entry_point))
+        }
+
+        let pre_verification_gas = operation.calculate_pre_verification_gas();
+        let mut op = operation;
+        op.pre_verification_gas = pre_verification_gas;
+        op.verification_gas_limit = U256::from(self.config.max_verification_gas);
+        op.call_gas_limit = U256::from(self.config.simulation_gas_limit / 2);
+        // the fees are irrelevant for the gas usage, but must be non zero to calculate it from the
+        // paid amount
+        op.max_fee_per_gas = U256::from(1);
+        op.max_priority_fee_per_gas = U256::from(1);
+
+        let call = contracts::simulateHandleOpCall {
+            op: contracts::UserOperation::from(&op),
+            target: Address::ZERO,
+            targetCallData: Bytes::new(),
+        };
+        let mut db = CacheDB::new(StateProviderDatabase::new(self.client.latest()?));
+        db.load_account(op.sender)?.info.balance = ESTIMATION_BALANCE;
+        let output = self.simulate(db, entry_point, call.abi_encode().into(), NoOpInspector)?;
+
+        let result = contracts::ExecutionResult::abi_decode(&output, true)
+            .map_err(|_| decode_revert(&output))?;
+
+        let verification_gas = result.preOpGas.saturating_sub(pre_verification_gas);
+        let verification_gas_limit = verification_gas +
+            verification_gas * U256::from(VERIFICATION_GAS_BUFFER_PERCENT) / U256::from(100);
+        let call_gas_limit =
+            result.paid.saturating_sub(result.preOpGas).max(U256::from(MIN_CALL_GAS_LIMIT));
+
+        Ok(UserOperationGasEstimate {
+            pre_verification_gas,
+            verification_gas_limit,
+            call_gas_limit,
+        })
+    }
+
+    /// Performs the checks that don't require a simulation.
+    fn validate_fields(&self, op: &UserOperation) -> UserOperationResult<()> {
+        if !op.init_code.is_empty() && op.init_code.len() < 20 {
+            return Err(UserOperationError::InvalidFields(
+                "init code must be empty or start with the factory address".to_string(),
+            ))
+        }
+        if !op.paymaster_and_data.is_empty() && op.paymaster_and_data.len() < 20 {
+            return Err(UserOperationError::InvalidFields(
+                "paymaster and data must be empty or start with the paymaster address".to_string(),
+            ))
+        }
+        if op.max_priority_fee_per_gas > op.max_fee_per_gas {
+            return Err(UserOperationError::InvalidFields(
+                "max priority fee per gas exceeds max fee per gas".to_string(),
+            ))
+        }
+        if op.verification_gas_limit > U256::from(self.config.max_verification_gas) {
+            return Err(UserOperationError::VerificationGasLimitTooHigh {
+                got: op.verification_gas_limit,
+                max: self.config.max_verification_gas,
+            })
+        }
+        let required = op.calculate_pre_verification_gas();
+        if op.pre_verification_gas < required {
+            return Err(UserOperationError::PreVerificationGasTooLow {
+                got: op.pre_verification_gas,
+                required,
+            })
+        }
+
+        let latest = self
+            .client
+            .latest_header()?
+            .ok_or_else(|| UserOperationError::Simulation("latest header not found".to_string()))?;
+        let base_fee = latest
+            .next_block_base_fee(self.chain_spec.base_fee_params_at_timestamp(latest.timestamp))
+            .unwrap_or_default();
+        if op.max_fee_per_gas < U256::from(base_fee) {
+            return Err(UserOperationError::FeeCapTooLow { max_fee: op.max_fee_per_gas, base_fee })
+        }
+
+        Ok(())
+    }
+
+    /// Calls the entry point with the given calldata on top of the latest block and returns the
+    /// revert data.
+    ///
+    /// The simulation methods of the entry point always revert, a successful call is an error.
+    fn simulate<DB, I>(
+        &self,
+        db: DB,
+        entry_point: Address,
+        data: Bytes,
+        inspector: I,
+    ) -> UserOperationResult<Bytes>
+    where
+        DB: Database,
+        DB::Error: std::fmt::Display,
+        I: GetInspector<DB>,
+    {
+        let latest = self
+            .client
+            .latest_header()?
+            .ok_or_else(|| UserOperationError::Simulation("latest header not found".to_string()))?;
+
+        let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::LATEST);
+        let mut block_env = BlockEnv::default();
+        self.client.fill_env_with_header(
+            &mut cfg,
+            &mut block_env,
+            latest.header(),
+            self.evm_config.clone(),
+        )?;
+        cfg.disable_base_fee = true;
+        cfg.disable_block_gas_limit = true;
+
+        let tx = TxEnv {
+            caller: Address::ZERO,
+            gas_limit: self.config.simulation_gas_limit,
+            gas_price: U256::ZERO,
+            transact_to: TxKind::Call(entry_point),
+            data,
+            ..Default::default()
+        };
+        let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, tx);
+
+        let mut evm = self.evm_config.evm_with_env_and_inspector(db, env, inspector);
+        let res = evm.transact().map_err(|err| UserOperationError::Simulation(err.to_string()))?;
+        match res.result {
+            ExecutionResult::Revert { output, .. } => Ok(output),
+            ExecutionResult::Success { .. } => {
+                Err(UserOperationError::Simulation("simulation call did not revert".to_string()))
+            }
+            ExecutionResult::Halt { reason, .. } => {
+                Err(UserOperationError::Simulation(format!("simulation halted: {reason:?}")))
+            }
+        }
+    }
+}
+
+/// Converts revert data that is not the expected simulation result into an error.
+fn decode_revert(output: &[u8]) -> UserOperationError {
+    if let Ok(result) = contracts::ValidationResultWithAggregation::abi_decode(output, true) {
+        return UserOperationError::UnsupportedAggregator(result.aggregatorInfo.aggregator)
+    }
+    if let Ok(failed) = contracts::FailedOp::abi_decode(output, true) {
+        return UserOperationError::Rejected {
+            entity: failed_op_entity(&failed.reason),
+            reason: failed.reason,
+        }
+    }
+    UserOperationError::Simulation(format!(
+        "unexpected revert data {}",
+        Bytes::copy_from_slice(output)
+    ))
+}
+
+/// Returns the entity the `AAxx` code of a `FailedOp` reason refers to.
+///
+/// `AA1x` codes refer to the factory, `AA2x` codes to the account and `AA3x` codes to the
+/// paymaster.
+fn failed_op_entity(reason: &str) -> EntityKind {
+    match reason.get(..3) {
+        Some("AA1") => EntityKind::Factory,
+        Some("AA3") => EntityKind::Paymaster,
+        _ => EntityKind::Account,
+    }
+}
+
+/// Returns the current unix timestamp in seconds.
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_op_entities() {
+        assert_eq!(failed_op_entity("AA13 initCode failed or OOG"), EntityKind::Factory);
+        assert_eq!(failed_op_entity("AA23 reverted (or OOG)"), EntityKind::Account);
+        assert_eq!(failed_op_entity("AA33 reverted (or OOG)"), EntityKind::Paymaster);
+        assert_eq!(failed_op_entity(""), EntityKind::Account);
+    }
+
+    #[test]
+    fn decode_failed_op() {
+        let revert = contracts::FailedOp {
+            opIndex: U256::ZERO,
+            reason: "AA31 paymaster deposit too low".to_string(),
+        }
+        .abi_encode();
+        assert!(matches!(
+            decode_revert(&revert),
+            UserOperationError::Rejected { entity: EntityKind::Paymaster, .. }
+        ));
+        assert!(matches!(
+            decode_revert(&Bytes::from_static(&[1, 2, 3])),
+            UserOperationError::Simulation(_)
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+//! Validation of user operations.
+
+use crate::{
+    UserOperation, UserOperationError, UserOperationGasEstimate, UserOperationResult,
+    ValidUserOperation,
+};
+use reth_primitives::{Address, SealedBlock};
+use reth_tasks::TaskSpawner;
+use reth_transaction_pool::validate::{ValidationJobSender, ValidationTask};
+use std::{future::Future, sync::Arc};
+use tokio::sync::{self, oneshot};
+
+mod eth;
+pub use eth::EthUserOperationValidator;
+
+mod tracer;
+
+/// Validates [`UserOperation`]s against the state.
+pub trait UserOperationValidator: Send + Sync {
+    /// Validates the operation for the given entry point.
+    ///
+    /// Implementers must ensure that the operation passes the entry point's validation and
+    /// complies with the ERC-7562 rules, so that its validation can't be invalidated by other
+    /// operations in the same bundle.
+    fn validate_user_operation(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> impl Future<Output = UserOperationResult<ValidUserOperation>> + Send;
+
+    /// Estimates the gas values of the operation for the given entry point.
+    fn estimate_user_operation_gas(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> impl Future<Output = UserOperationResult<UserOperationGasEstimate>> + Send;
+
+    /// Invoked when the head block changes.
+    ///
+    /// This can be used to update fork specific values.
+    fn on_new_head_block(&self, _new_tip_block: &SealedBlock) {}
+}
+
+/// A [`UserOperationValidator`] that validates operations on a separate task.
+///
+/// This reuses the [`ValidationTask`] of the transaction pool: the validation futures are sent to
+/// a task that runs them, so that simulations don't block the caller.
+#[derive(Debug, Clone)]
+pub struct UserOperationValidationTaskExecutor<V> {
+    /// The validator that will validate operations on a separate task.
+    pub validator: V,
+    /// The sender half to validation tasks that perform the actual validation.
+    pub to_validation_task: Arc<sync::Mutex<ValidationJobSender>>,
+}
+
+impl<V> UserOperationValidationTaskExecutor<V> {
+    /// Creates a new executor with the given validator and spawns the validation task, plus
+    /// `num_additional_tasks` tasks on the given spawner.
+    pub fn new<T>(validator: V, tasks: T, num_additional_tasks: usize) -> Self
+    where
+        T: TaskSpawner,
+    {
+        let (tx, task) = ValidationTask::new();
+
+        for _ in 0..num_additional_tasks {
+            let task = task.clone();
+            tasks.spawn_blocking(Box::pin(async move {
+                task.run().await;
+            }));
+        }
+        tasks.spawn_critical_blocking(
+            "user-operation-validation-service",
+            Box::pin(async move {
+                task.run().await;
+            }),
+        );
+
+        Self { validator, to_validation_task: Arc::new(sync::Mutex::new(tx)) }
+    }
+
+    /// Sends the future produced by the closure to the validation task and returns its output.
+    async fn spawn_validation<F, Fut, R>(&self, f: F) -> UserOperationResult<R>
+    where
+        F: FnOnce(V) -> Fut,
+        Fut: Future<Output = UserOperationResult<R>> + Send + 'static,
+        R: Send + 'static,
+        V: Clone,
+    {
+        let (tx, rx) = oneshot::channel();
+        let job = f(self.validator.clone());
+        let res = self
+            .to_validation_task
+            .lock()
+            .await
+            .send(Box::pin(async move {
+                let _ = tx.send(job.await);
+            }))
+            .await;
+        if res.is_err() {
+            return Err(UserOperationError::ValidationServiceUnreachable)
+        }
+
+        rx.await.unwrap_or(Err(UserOperationError::ValidationServiceUnreachable))
+    }
+}
+
+impl<V> UserOperationValidator for UserOperationValidationTaskExecutor<V>
+where
+    V: UserOperationValidator + Clone + 'static,
+{
+    async fn validate_user_operation(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> UserOperationResult<ValidUserOperation> {
+        self.spawn_validation(move |validator| async move {
+            validator.validate_user_operation(operation, entry_point).await
+        })
+        .await
+    }
+
+    async fn estimate_user_operation_gas(
+        &self,
+        operation: UserOperation,
+        entry_point: Address,
+    ) -> UserOperationResult<UserOperationGasEstimate> {
+        self.spawn_validation(move |validator| async move {
+            validator.estimate_user_operation_gas(operation, entry_point).await
+        })
+        .await
+    }
+
+    fn on_new_head_block(&self, new_tip_block: &SealedBlock) {
+        self.validator.on_new_head_block(new_tip_block)
+    }
+}
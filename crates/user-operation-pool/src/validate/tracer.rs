@@ -0,0 +1,379 @@
+//! Tracer that enforces the ERC-7562 validation rules.
+//!
+//! The v0.6 entry point executes the `NUMBER` opcode as a marker between the validation phases of
+//! the entities: first the factory deploys the account, then the account validates the operation
+//! and finally the paymaster validates it. The tracer records the opcodes and storage accessed in
+//! each phase, which are then checked against the rules for the entity of the phase.
+//!
+//! Note: only the opcode, `CREATE2`, code access and storage rules are enforced.
+
+use crate::{Entity, EntityKind, UserOperation, UserOperationError};
+use reth_primitives::{Address, B256, U256};
+use reth_revm::{interpreter::Interpreter, Database, EvmContext, Inspector};
+use std::collections::{HashMap, HashSet};
+
+const KECCAK256: u8 = 0x20;
+const GAS: u8 = 0x5a;
+const NUMBER: u8 = 0x43;
+const SLOAD: u8 = 0x54;
+const SSTORE: u8 = 0x55;
+const CREATE2: u8 = 0xf5;
+const EXTCODESIZE: u8 = 0x3b;
+const EXTCODECOPY: u8 = 0x3c;
+const EXTCODEHASH: u8 = 0x3f;
+
+/// Addresses from `0x01` up to this one are the precompiles, which may be called without having
+/// code, see ERC-7562 `OP-062`.
+const MAX_PRECOMPILE_ADDRESS: u8 = 0x09;
+
+/// Opcodes that entities must not use during validation, see ERC-7562 `OP-011`.
+const BANNED_OPCODES: [u8; 15] = [
+    0x31, // BALANCE
+    0x32, // ORIGIN
+    0x3a, // GASPRICE
+    0x40, // BLOCKHASH
+    0x41, // COINBASE
+    0x42, // TIMESTAMP
+    NUMBER, 0x44, // PREVRANDAO
+    0x45, // GASLIMIT
+    0x47, // SELFBALANCE
+    0x48, // BASEFEE
+    0x49, // BLOBHASH
+    0x4a, // BLOBBASEFEE
+    0xf0, // CREATE
+    0xff, // SELFDESTRUCT
+];
+
+/// Opcodes that may follow `GAS`, see ERC-7562 `OP-012`.
+const CALL_OPCODES: [u8; 4] = [
+    0xf1, // CALL
+    0xf2, // CALLCODE
+    0xf4, // DELEGATECALL
+    0xfa, // STATICCALL
+];
+
+/// Number of slots following an associated slot that are also associated, to cover structs.
+const ASSOCIATED_SLOT_RANGE: u64 = 128;
+
+/// What happened during a validation phase.
+#[derive(Debug, Default)]
+struct PhaseTrace {
+    /// Banned opcodes and the contracts that used them.
+    banned_opcodes: Vec<(Address, u8)>,
+    /// Number of executed `CREATE2` opcodes.
+    create2_count: usize,
+    /// Accessed storage slots per contract.
+    storage: HashMap<Address, HashSet<U256>>,
+    /// Addresses without code that were accessed by `EXTCODE*` or `*CALL` opcodes.
+    accessed_without_code: Vec<Address>,
+}
+
+/// Records what the entities do during `simulateValidation`.
+#[derive(Debug)]
+pub(crate) struct ValidationTracer {
+    entry_point: Address,
+    phases: Vec<PhaseTrace>,
+    /// Results of `KECCAK256` whose input started with an address, by that address.
+    ///
+    /// Used to determine the storage slots associated with an address, like the slots of mapping
+    /// values keyed by the address.
+    keccak: HashMap<Address, Vec<U256>>,
+    /// The address the result of the currently executed `KECCAK256` belongs to.
+    pending_keccak: Option<Address>,
+    /// The contract that executed `GAS` as the previous opcode.
+    pending_gas: Option<Address>,
+}
+
+impl ValidationTracer {
+    /// Creates a new tracer for the given entry point.
+    pub(crate) fn new(entry_point: Address) -> Self {
+        Self {
+            entry_point,
+            phases: vec![PhaseTrace::default()],
+            keccak: HashMap::new(),
+            pending_keccak: None,
+            pending_gas: None,
+        }
+    }
+
+    fn phase(&mut self) -> &mut PhaseTrace {
+        self.phases.last_mut().expect("at least one phase")
+    }
+
+    /// Returns true if the slot is associated with the address: either the slot is the address
+    /// itself, or it is within [`ASSOCIATED_SLOT_RANGE`] of a hash of the address.
+    fn is_associated(&self, address: Address, slot: U256) -> bool {
+        if slot == U256::from_be_slice(address.as_slice()) {
+            return true
+        }
+        self.keccak.get(&address).map_or(false, |hashes| {
+            hashes
+                .iter()
+                .any(|hash| slot >= *hash && slot - *hash <= U256::from(ASSOCIATED_SLOT_RANGE))
+        })
+    }
+
+    /// Checks the recorded phases against the rules of the entities.
+    ///
+    /// The account, factory and paymaster flags tell whether the entity is staked.
+    pub(crate) fn check(
+        &self,
+        op: &UserOperation,
+        account_staked: bool,
+        factory: Option<(Address, bool)>,
+        paymaster: Option<(Address, bool)>,
+    ) -> Result<(), UserOperationError> {
+        let phases = [
+            factory.map(|(address, staked)| (Entity::factory(address), staked)),
+            Some((Entity::account(op.sender), account_staked)),
+            paymaster.map(|(address, staked)| (Entity::paymaster(address), staked)),
+        ];
+
+        for (phase, entity) in self.phases.iter().zip(phases) {
+            let Some((entity, staked)) = entity else { continue };
+
+            if let Some((_, opcode)) = phase.banned_opcodes.first() {
+                return Err(UserOperationError::BannedOpcode { entity, opcode: *opcode })
+            }
+
+            // only the account may be accessed before it is deployed, see `OP-041` and `OP-042`
+            if let Some(address) =
+                phase.accessed_without_code.iter().find(|address| **address != op.sender)
+            {
+                return Err(UserOperationError::AccessedAddressWithoutCode {
+                    entity,
+                    address: *address,
+                })
+            }
+
+            // only the factory may deploy the account, see `OP-031`
+            let max_create2 = if entity.kind == EntityKind::Factory { 1 } else { 0 };
+            if phase.create2_count > max_create2 {
+                return Err(UserOperationError::BannedOpcode { entity, opcode: CREATE2 })
+            }
+
+            for (address, slots) in &phase.storage {
+                // the account's own storage may always be accessed, see `STO-010`
+                if *address == op.sender {
+                    continue
+                }
+                // the entity's own storage requires a stake, see `STO-031`
+                if *address == entity.address {
+                    if !staked {
+                        return Err(UserOperationError::NotStaked { entity })
+                    }
+                    continue
+                }
+                for slot in slots {
+                    if self.is_associated(op.sender, *slot) {
+                        // an unstaked factory may not access storage associated with the account
+                        // it deploys, see `STO-022`
+                        if entity.kind == EntityKind::Factory && !staked {
+                            return Err(UserOperationError::NotStaked { entity })
+                        }
+                        continue
+                    }
+                    // any other storage requires a stake, see `STO-032` and `STO-033`
+                    if !staked {
+                        return Err(UserOperationError::StorageAccess {
+                            entity,
+                            address: *address,
+                            slot: *slot,
+                        })
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` if the address has no code, taking the changes of the simulation into account.
+fn has_no_code<DB: Database>(context: &mut EvmContext<DB>, address: Address) -> bool {
+    if !address.is_zero() && address <= Address::with_last_byte(MAX_PRECOMPILE_ADDRESS) {
+        return false
+    }
+    if let Some(account) = context.journaled_state.state.get(&address) {
+        return account.info.is_empty_code_hash()
+    }
+    // the account was not loaded during the simulation yet, so its code is unchanged
+    context.db.basic(address).ok().flatten().map_or(true, |info| info.is_empty_code_hash())
+}
+
+impl<DB: Database> Inspector<DB> for ValidationTracer {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        let opcode = interp.current_opcode();
+        let address = interp.contract.target_address;
+
+        if let Some(gas_address) = self.pending_gas.take() {
+            if !CALL_OPCODES.contains(&opcode) {
+                self.phase().banned_opcodes.push((gas_address, GAS));
+            }
+        }
+
+        if opcode == KECCAK256 {
+            if let (Ok(offset), Ok(size)) = (interp.stack.peek(0), interp.stack.peek(1)) {
+                let offset = offset.saturating_to::<usize>();
+                if size >= U256::from(32) && offset.saturating_add(32) <= interp.shared_memory.len()
+                {
+                    let word = interp.shared_memory.slice(offset, 32);
+                    if word[..12].iter().all(|byte| *byte == 0) {
+                        self.pending_keccak = Some(Address::from_slice(&word[12..]));
+                    }
+                }
+            }
+        }
+
+        if address == self.entry_point {
+            if opcode == NUMBER {
+                self.phases.push(PhaseTrace::default());
+            }
+            return
+        }
+
+        // the accessed address is the first argument of `EXTCODE*` and the second of `*CALL`
+        let accessed = match opcode {
+            EXTCODESIZE | EXTCODECOPY | EXTCODEHASH => interp.stack.peek(0).ok(),
+            opcode if CALL_OPCODES.contains(&opcode) => interp.stack.peek(1).ok(),
+            _ => None,
+        };
+        if let Some(accessed) = accessed.map(|word| Address::from_word(B256::from(word))) {
+            if has_no_code(context, accessed) {
+                self.phase().accessed_without_code.push(accessed);
+            }
+        }
+
+        match opcode {
+            GAS => self.pending_gas = Some(address),
+            CREATE2 => self.phase().create2_count += 1,
+            SLOAD | SSTORE => {
+                if let Ok(slot) = interp.stack.peek(0) {
+                    self.phase().storage.entry(address).or_default().insert(slot);
+                }
+            }
+            opcode if BANNED_OPCODES.contains(&opcode) => {
+                self.phase().banned_opcodes.push((address, opcode));
+            }
+            _ => {}
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if let Some(address) = self.pending_keccak.take() {
+            if let Ok(hash) = interp.stack.peek(0) {
+                self.keccak.entry(address).or_default().push(hash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracer_with_phases(phases: Vec<PhaseTrace>) -> ValidationTracer {
+        let mut tracer = ValidationTracer::new(Address::random());
+        tracer.phases = phases;
+        tracer
+    }
+
+    fn op() -> UserOperation {
+        UserOperation { sender: Address::random(), ..Default::default() }
+    }
+
+    #[test]
+    fn banned_opcode() {
+        let op = op();
+        let tracer = tracer_with_phases(vec![
+            PhaseTrace::default(),
+            PhaseTrace { banned_opcodes: vec![(op.sender, 0x42)], ..Default::default() },
+        ]);
+        assert!(matches!(
+            tracer.check(&op, false, None, None),
+            Err(UserOperationError::BannedOpcode { opcode: 0x42, .. })
+        ));
+    }
+
+    #[test]
+    fn create2_only_in_factory() {
+        let op = op();
+        let factory = Address::random();
+        let deploy = PhaseTrace { create2_count: 1, ..Default::default() };
+
+        let tracer = tracer_with_phases(vec![deploy]);
+        assert!(tracer.check(&op, false, Some((factory, false)), None).is_ok());
+
+        let tracer = tracer_with_phases(vec![
+            PhaseTrace::default(),
+            PhaseTrace { create2_count: 1, ..Default::default() },
+        ]);
+        assert!(tracer.check(&op, false, None, None).is_err());
+    }
+
+    #[test]
+    fn access_without_code() {
+        let op = op();
+        let phase = |address| {
+            vec![
+                PhaseTrace::default(),
+                PhaseTrace { accessed_without_code: vec![address], ..Default::default() },
+            ]
+        };
+
+        let address = Address::random();
+        assert!(matches!(
+            tracer_with_phases(phase(address)).check(&op, false, None, None),
+            Err(UserOperationError::AccessedAddressWithoutCode { address: accessed, .. }) if accessed == address
+        ));
+
+        // the account itself may be accessed before it is deployed
+        assert!(tracer_with_phases(phase(op.sender)).check(&op, false, None, None).is_ok());
+    }
+
+    #[test]
+    fn storage_rules() {
+        let op = op();
+        let paymaster = Address::random();
+        let token = Address::random();
+        let balance_slot = U256::from(12345);
+
+        let phases = || {
+            vec![
+                PhaseTrace::default(),
+                PhaseTrace::default(),
+                PhaseTrace {
+                    storage: HashMap::from([(token, HashSet::from([balance_slot]))]),
+                    ..Default::default()
+                },
+            ]
+        };
+
+        // unassociated storage of another contract requires a stake
+        let tracer = tracer_with_phases(phases());
+        assert!(matches!(
+            tracer.check(&op, false, None, Some((paymaster, false))),
+            Err(UserOperationError::StorageAccess { .. })
+        ));
+        assert!(tracer.check(&op, false, None, Some((paymaster, true))).is_ok());
+
+        // storage associated with the account is allowed
+        let mut tracer = tracer_with_phases(phases());
+        tracer.keccak.insert(op.sender, vec![balance_slot - U256::from(1)]);
+        assert!(tracer.check(&op, false, None, Some((paymaster, false))).is_ok());
+
+        // the paymaster's own storage requires a stake
+        let tracer = tracer_with_phases(vec![
+            PhaseTrace::default(),
+            PhaseTrace::default(),
+            PhaseTrace {
+                storage: HashMap::from([(paymaster, HashSet::from([U256::ZERO]))]),
+                ..Default::default()
+            },
+        ]);
+        assert!(matches!(
+            tracer.check(&op, false, None, Some((paymaster, false))),
+            Err(UserOperationError::NotStaked { .. })
+        ));
+    }
+}